@@ -19,6 +19,7 @@ pub struct JsAmplifierError {
 /// - `"provider"` → `"ProviderError"`
 /// - `"hook"` → `"HookError"`
 /// - `"context"` → `"ContextError"`
+/// - `"coordinator"` → `"CoordinatorError"`
 /// - anything else → `"AmplifierError"`
 fn error_code_for_variant(variant: &str) -> &'static str {
     match variant {
@@ -27,6 +28,7 @@ fn error_code_for_variant(variant: &str) -> &'static str {
         "provider" => "ProviderError",
         "hook" => "HookError",
         "context" => "ContextError",
+        "coordinator" => "CoordinatorError",
         _ => "AmplifierError",
     }
 }
@@ -46,13 +48,16 @@ pub fn amplifier_error_to_js(variant: String, message: String) -> JsAmplifierErr
 /// Uses [`error_code_for_variant`] for consistent code mapping.
 #[allow(dead_code)] // Used when async methods expose Result<T, AmplifierError> across FFI
 pub(crate) fn amplifier_error_to_napi(err: amplifier_core::errors::AmplifierError) -> napi::Error {
-    let (variant, msg) = match &err {
-        amplifier_core::errors::AmplifierError::Session(e) => ("session", e.to_string()),
-        amplifier_core::errors::AmplifierError::Tool(e) => ("tool", e.to_string()),
-        amplifier_core::errors::AmplifierError::Provider(e) => ("provider", e.to_string()),
-        amplifier_core::errors::AmplifierError::Hook(e) => ("hook", e.to_string()),
-        amplifier_core::errors::AmplifierError::Context(e) => ("context", e.to_string()),
+    use amplifier_core::errors::AmplifierError;
+    let variant = match err.root() {
+        AmplifierError::Session(_) => "session",
+        AmplifierError::Tool(_) => "tool",
+        AmplifierError::Provider(_) => "provider",
+        AmplifierError::Hook(_) => "hook",
+        AmplifierError::Context(_) => "context",
+        AmplifierError::Coordinator(_) => "coordinator",
+        AmplifierError::Contextual { .. } => "amplifier",
     };
     let code = error_code_for_variant(variant);
-    Error::from_reason(format!("[{code}] {msg}"))
+    Error::from_reason(format!("[{code}] {err}"))
 }
@@ -144,6 +144,17 @@ impl JsHookRegistry {
         self.inner.list_handlers(None)
     }
 
+    /// Enable or disable the handlers named `name`, on every event or only
+    /// on `event`. Returns how many registrations changed.
+    #[napi]
+    pub fn set_handler_enabled(&self, name: String, enabled: bool, event: Option<String>) -> u32 {
+        let changed = match event {
+            Some(event) => self.inner.set_handler_enabled_for(&event, &name, enabled),
+            None => self.inner.set_handler_enabled(&name, enabled),
+        };
+        changed as u32
+    }
+
     #[napi]
     pub fn set_default_fields(&self, defaults_json: String) -> Result<()> {
         let defaults: serde_json::Value =
@@ -151,4 +162,18 @@ impl JsHookRegistry {
         self.inner.set_default_fields(defaults);
         Ok(())
     }
+
+    /// Install an emit-time event filter from JSON (the shape of
+    /// `session.hooks.filter`), or remove it with `null`.
+    #[napi]
+    pub fn set_event_filter(&self, filter_json: Option<String>) -> Result<()> {
+        let Some(filter_json) = filter_json else {
+            self.inner.clear_event_filter();
+            return Ok(());
+        };
+        let config: amplifier_core::event_filter::EventFilterConfig =
+            serde_json::from_str(&filter_json).map_err(|e| Error::from_reason(e.to_string()))?;
+        self.inner.set_event_filter(config);
+        Ok(())
+    }
 }
@@ -5,7 +5,6 @@
 use std::sync::Arc;
 
 use napi::bindgen_prelude::*;
-use tokio::sync::Mutex;
 
 use crate::coordinator::JsCoordinator;
 
@@ -19,7 +18,7 @@ use crate::coordinator::JsCoordinator;
 /// shared, not copied.
 #[napi]
 pub struct JsAmplifierSession {
-    inner: Arc<Mutex<amplifier_core::Session>>,
+    inner: Arc<amplifier_core::Session>,
     cached_session_id: String,
     cached_parent_id: Option<String>,
     cached_coordinator: Option<JsCoordinator>,
@@ -43,7 +42,7 @@ impl JsAmplifierSession {
         let cached_session_id = session.session_id().to_string();
 
         Ok(Self {
-            inner: Arc::new(Mutex::new(session)),
+            inner: Arc::new(session),
             cached_session_id,
             cached_parent_id: parent_id,
             cached_coordinator: None,
@@ -62,31 +61,23 @@ impl JsAmplifierSession {
 
     #[napi(getter)]
     pub fn is_initialized(&self) -> bool {
-        match self.inner.try_lock() {
-            Ok(session) => session.is_initialized(),
-            // Safe default: lock is only held during async cleanup(), which sets
-            // initialized to false — so false is a correct conservative fallback.
-            Err(_) => false,
-        }
+        self.inner.is_initialized()
     }
 
     /// Current session lifecycle state as a lowercase string.
     ///
     /// Returns one of the `SessionState` variant strings:
-    /// - `"Running"` — session is active
-    /// - `"Completed"` — session finished successfully
-    /// - `"Failed"` — session encountered a fatal error
-    /// - `"Cancelled"` — session was cancelled via the cancellation token
+    /// - `"running"` — session is active
+    /// - `"completed"` — session finished successfully
+    /// - `"failed"` — session encountered a fatal error
+    /// - `"cancelled"` — session was cancelled via the cancellation token
     ///
-    /// Falls back to `"running"` if the session lock is held during `cleanup()`.
+    /// Never blocks: the kernel session tracks status with interior
+    /// mutability, so this is accurate even while `execute()` or `cleanup()`
+    /// is in flight.
     #[napi(getter)]
     pub fn status(&self) -> String {
-        match self.inner.try_lock() {
-            Ok(session) => session.status().to_string(),
-            // Safe default: lock is only held during async cleanup(), and sessions
-            // start as "running" — returning "running" during cleanup is tolerable.
-            Err(_) => "running".to_string(),
-        }
+        self.inner.status().to_string()
     }
 
     /// The session's coordinator — shared via `Arc`, not copied.
@@ -103,19 +94,9 @@ impl JsAmplifierSession {
                 inner: Arc::clone(&cached.inner),
             };
         }
-        // First call: extract the Arc<Coordinator> from the session.
-        // try_lock is safe here — the Mutex is only held during async execute/cleanup.
-        let coord_arc = match self.inner.try_lock() {
-            Ok(session) => session.coordinator_shared(),
-            Err(_) => {
-                log::warn!(
-                    "JsAmplifierSession::coordinator() — session lock held, \
-                     creating coordinator from default config as fallback"
-                );
-                Arc::new(amplifier_core::Coordinator::new(Default::default()))
-            }
+        let js_coord = JsCoordinator {
+            inner: self.inner.coordinator_shared(),
         };
-        let js_coord = JsCoordinator { inner: coord_arc };
         self.cached_coordinator = Some(JsCoordinator {
             inner: Arc::clone(&js_coord.inner),
         });
@@ -124,18 +105,12 @@ impl JsAmplifierSession {
 
     #[napi]
     pub fn set_initialized(&self) {
-        match self.inner.try_lock() {
-            Ok(session) => session.set_initialized(),
-            Err(_) => log::warn!(
-                "JsAmplifierSession::set_initialized() skipped — session lock held (cleanup in progress?)"
-            ),
-        }
+        self.inner.set_initialized();
     }
 
     #[napi]
     pub async fn cleanup(&self) -> Result<()> {
-        let session = self.inner.lock().await;
-        session.cleanup().await;
+        self.inner.cleanup().await;
         Ok(())
     }
 }
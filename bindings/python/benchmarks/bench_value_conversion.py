@@ -0,0 +1,80 @@
+"""Benchmark: hook payload conversion across the PyO3 boundary.
+
+Compares the old string round-trip (json.dumps(default=str) on the way in,
+json.loads on the way out to the handler) against the direct PyAny <->
+serde_json::Value conversion now used by RustHookRegistry.emit.
+
+The baseline adds the two json round-trips the bridge used to pay per emit
+(into Rust, and back out to the handler) on top of a direct emit, so it
+approximates the old cost from above. Run with a built extension:
+
+    maturin develop --release
+    python bindings/python/benchmarks/bench_value_conversion.py
+"""
+
+import asyncio
+import json
+import statistics
+import time
+
+from amplifier_core._engine import RustHookRegistry
+
+
+def make_payload(n_items: int) -> dict:
+    """A tool:post-like payload with a large, nested tool result."""
+    return {
+        "tool_name": "read_file",
+        "tool_input": {"path": "src/lib.rs"},
+        "tool_result": {
+            "success": True,
+            "output": {
+                "lines": [
+                    {"number": i, "text": f"line {i} " + "x" * 40, "tags": ["a", "b"]}
+                    for i in range(n_items)
+                ],
+                "total": n_items,
+            },
+        },
+    }
+
+
+def json_round_trip(payload: dict) -> dict:
+    """What the bridge used to do on each hop: dumps + loads."""
+    return json.loads(json.dumps(payload, default=str))
+
+
+async def time_emits(registry, payload, iterations, prepare=None) -> float:
+    samples = []
+    for _ in range(iterations):
+        start = time.perf_counter()
+        data = prepare(payload) if prepare else payload
+        await registry.emit("tool:post", data)
+        samples.append(time.perf_counter() - start)
+    return statistics.median(samples)
+
+
+async def main() -> None:
+    registry = RustHookRegistry()
+    registry.register("tool:post", lambda event, data: None, 0, name="noop")
+
+    print(f"{'items':>8} {'json (ms)':>12} {'direct (ms)':>12} {'speedup':>8}")
+    for n_items in (10, 100, 1_000, 10_000):
+        payload = make_payload(n_items)
+        iterations = max(10, 20_000 // n_items)
+        # Two json round-trips per emit in the old bridge: into Rust and back
+        # out to the Python handler.
+        baseline = await time_emits(
+            registry,
+            payload,
+            iterations,
+            prepare=lambda p: json_round_trip(json_round_trip(p)),
+        )
+        direct = await time_emits(registry, payload, iterations)
+        print(
+            f"{n_items:>8} {baseline * 1e3:>12.3f} {direct * 1e3:>12.3f} "
+            f"{baseline / direct:>7.1f}x"
+        )
+
+
+if __name__ == "__main__":
+    asyncio.run(main())
@@ -19,7 +19,7 @@ use amplifier_core::errors::{AmplifierError, HookError, SessionError};
 use amplifier_core::models::{HookAction, HookResult};
 use amplifier_core::traits::HookHandler;
 
-use crate::helpers::{is_approval_granted, json_dumps_safe, try_model_dump};
+use crate::helpers::{event_name, is_approval_granted, is_coroutine, json_to_py, to_json_value};
 
 // ---------------------------------------------------------------------------
 // PyHookHandlerBridge — wraps a Python callable as a Rust HookHandler
@@ -62,21 +62,12 @@ impl HookHandler for PyHookHandlerBridge {
             // sync result or a coroutine object, plus whether it's a coroutine.
             let (is_coro, py_result_or_coro) =
                 Python::try_attach(|py| -> PyResult<(bool, Py<PyAny>)> {
-                    let json_mod = py.import("json")?;
-                    let data_str = serde_json::to_string(&data).unwrap_or_else(|e| {
-                        log::warn!(
-                            "Failed to serialize hook data to JSON (using empty object): {e}"
-                        );
-                        "{}".to_string()
-                    });
-                    let py_data = json_mod.call_method1("loads", (&data_str,))?;
-
-                    let call_result = callable.call(py, (&event, py_data), None)?;
-                    let bound = call_result.bind(py);
+                    let py_data = json_to_py(py, &data)?;
+
+                    let call_result = callable.call(py, (event_name(py, &event), py_data), None)?;
 
                     // Check if the result is a coroutine (async handler)
-                    let inspect = py.import("inspect")?;
-                    let is_coro: bool = inspect.call_method1("iscoroutine", (bound,))?.extract()?;
+                    let is_coro = is_coroutine(call_result.bind(py))?;
 
                     Ok((is_coro, call_result))
                 })
@@ -121,17 +112,16 @@ impl HookHandler for PyHookHandlerBridge {
             // Step 3: Parse the Python result into a HookResult (reacquire GIL)
             //
             // Python hook handlers typically return Pydantic BaseModel instances
-            // (e.g. amplifier_core.models.HookResult). stdlib json.dumps() cannot
-            // serialize Pydantic models directly, so we first try model_dump() to
-            // convert to a plain dict, then json.dumps() the dict. For non-Pydantic
-            // return values (plain dicts, etc.) we fall back to json.dumps() directly.
-            let result_json: String = Python::try_attach(|py| -> PyResult<String> {
+            // (e.g. amplifier_core.models.HookResult), so we first try
+            // model_dump() to get a plain dict, then convert that directly to a
+            // serde_json::Value. For non-Pydantic return values (plain dicts,
+            // etc.) the value is converted as-is.
+            let result_value: Value = Python::try_attach(|py| -> PyResult<Value> {
                 let bound = py_result.bind(py);
                 if bound.is_none() {
-                    return Ok("{}".to_string());
+                    return Ok(serde_json::json!({}));
                 }
-                let serializable = try_model_dump(bound);
-                Ok(json_dumps_safe(py, &serializable).unwrap_or_else(|_| "{}".to_string()))
+                Ok(to_json_value(bound).unwrap_or_else(|_| serde_json::json!({})))
             })
             .ok_or_else(|| HookError::HandlerFailed {
                 message: "Failed to attach to Python runtime for result parsing".to_string(),
@@ -142,16 +132,17 @@ impl HookHandler for PyHookHandlerBridge {
                 handler_name: None,
             })?;
 
-            let hook_result: HookResult = serde_json::from_str(&result_json).unwrap_or_else(|e| {
-                log::error!(
-                    "SECURITY: Hook handler returned unparseable result — failing closed (Deny): {e} — json: {result_json}"
-                );
-                HookResult {
-                    action: HookAction::Deny,
-                    reason: Some("Hook handler returned invalid response".to_string()),
-                    ..Default::default()
-                }
-            });
+            let hook_result: HookResult = serde_json::from_value(result_value.clone())
+                .unwrap_or_else(|e| {
+                    log::error!(
+                        "SECURITY: Hook handler returned unparseable result — failing closed (Deny): {e} — json: {result_value}"
+                    );
+                    HookResult {
+                        action: HookAction::Deny,
+                        reason: Some("Hook handler returned invalid response".to_string()),
+                        ..Default::default()
+                    }
+                });
             Ok(hook_result)
         })
     }
@@ -14,7 +14,7 @@ use pyo3::types::{PyDict, PyList};
 use serde_json::Value;
 
 use crate::cancellation::PyCancellationToken;
-use crate::helpers::{json_dumps_safe, try_model_dump, wrap_future_as_coroutine};
+use crate::helpers::{to_json_value, wrap_future_as_coroutine};
 use crate::hooks::PyHookRegistry;
 
 mod capabilities;
@@ -114,9 +114,7 @@ impl PyCoordinator {
                 };
                 let cfg = sess.getattr("config")?;
                 let rc: HashMap<String, Value> = {
-                    let serializable = try_model_dump(&cfg);
-                    let json_str: String = json_dumps_safe(py, &serializable)?;
-                    serde_json::from_str(&json_str).unwrap_or_else(|e| {
+                    serde_json::from_value(to_json_value(&cfg)?).unwrap_or_else(|e| {
                         log::warn!("Failed to parse session config as JSON object (using empty config): {e}");
                         HashMap::new()
                     })
@@ -150,6 +148,7 @@ impl PyCoordinator {
         mp.set_item("orchestrator", py.None())?;
         mp.set_item("providers", PyDict::new(py))?;
         mp.set_item("tools", PyDict::new(py))?;
+        mp.set_item("agents", PyDict::new(py))?;
         mp.set_item("context", py.None())?;
         mp.set_item("hooks", &hooks_any)?;
         mp.set_item("module-source-resolver", py.None())?;
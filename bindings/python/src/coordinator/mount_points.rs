@@ -9,12 +9,26 @@ use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
+use amplifier_core::coordinator::MountPoint;
+use amplifier_core::errors::CoordinatorError;
+
 use crate::bridges::{PyApprovalProviderBridge, PyDisplayServiceBridge};
 use crate::cancellation::PyCancellationToken;
 use crate::helpers::wrap_future_as_coroutine;
 
 use super::PyCoordinator;
 
+/// Parse a mount-point name through the kernel's canonical [`MountPoint`],
+/// so unknown names fail identically in every binding.
+fn parse_mount_point(name: &str) -> PyResult<MountPoint> {
+    name.parse::<MountPoint>().map_err(coordinator_err)
+}
+
+/// Surface a [`CoordinatorError`] as a Python `ValueError`.
+fn coordinator_err(err: CoordinatorError) -> PyErr {
+    PyErr::new::<PyValueError, _>(err.to_string())
+}
+
 #[pymethods]
 impl PyCoordinator {
     // -----------------------------------------------------------------------
@@ -42,8 +56,10 @@ impl PyCoordinator {
     ///
     /// Matches Python `ModuleCoordinator.mount(mount_point, module, name=None)`.
     /// For single-slot points (orchestrator, context, module-source-resolver),
-    /// `name` is ignored. For multi-slot points (providers, tools), `name` is
-    /// required or auto-detected from `module.name`.
+    /// `name` is ignored. For multi-slot points (providers, tools, agents),
+    /// `name` is required or auto-detected from `module.name`.
+    ///
+    /// Unknown mount-point names raise `ValueError` via [`MountPoint`] parsing.
     #[pyo3(signature = (mount_point, module, name=None))]
     fn mount<'py>(
         &self,
@@ -53,44 +69,27 @@ impl PyCoordinator {
         name: Option<String>,
     ) -> PyResult<Bound<'py, PyAny>> {
         let mp = self.mount_points.bind(py);
-
-        // Validate mount point exists
-        if !mp.contains(mount_point)? {
-            return Err(PyErr::new::<PyValueError, _>(format!(
-                "Unknown mount point: {mount_point}"
-            )));
-        }
-
-        if mount_point == "hooks" {
-            return Err(PyErr::new::<PyValueError, _>(
-                "Hooks should be registered directly with the HookRegistry",
-            ));
-        }
-
-        match mount_point {
-            "orchestrator" | "context" | "module-source-resolver" => {
-                mp.set_item(mount_point, &module)?;
-            }
-            "providers" | "tools" => {
-                let resolved_name = match name {
-                    Some(n) => n,
-                    None => match module.getattr("name") {
-                        Ok(attr) => attr.extract::<String>()?,
-                        Err(_) => {
-                            return Err(PyErr::new::<PyValueError, _>(format!(
-                                "Name required for {mount_point}"
-                            )));
-                        }
-                    },
-                };
-                let sub_dict = mp.get_item(mount_point)?.ok_or_else(|| {
-                    PyErr::new::<PyRuntimeError, _>(format!(
-                        "Mount point sub-dict missing: {mount_point}"
-                    ))
-                })?;
-                sub_dict.set_item(&resolved_name, &module)?;
-            }
-            _ => {}
+        let point = parse_mount_point(mount_point)?;
+
+        if point.is_multi_slot() {
+            let resolved_name = match name {
+                Some(n) => n,
+                None => match module.getattr("name") {
+                    Ok(attr) => attr.extract::<String>()?,
+                    Err(_) => {
+                        return Err(coordinator_err(point.validate_mount(None).unwrap_err()));
+                    }
+                },
+            };
+            let sub_dict = mp.get_item(point.as_str())?.ok_or_else(|| {
+                PyErr::new::<PyRuntimeError, _>(format!(
+                    "Mount point sub-dict missing: {mount_point}"
+                ))
+            })?;
+            sub_dict.set_item(&resolved_name, &module)?;
+        } else {
+            point.validate_mount(None).map_err(coordinator_err)?;
+            mp.set_item(point.as_str(), &module)?;
         }
 
         // Return an awaitable that resolves to None (mount is async in Python)
@@ -114,36 +113,21 @@ impl PyCoordinator {
         name: Option<&str>,
     ) -> PyResult<Py<PyAny>> {
         let mp = self.mount_points.bind(py);
-
-        if !mp.contains(mount_point)? {
-            return Err(PyErr::new::<PyValueError, _>(format!(
-                "Unknown mount point: {mount_point}"
-            )));
-        }
-
-        match mount_point {
-            "orchestrator" | "context" | "hooks" | "module-source-resolver" => {
-                let item = mp.get_item(mount_point)?.ok_or_else(|| {
-                    PyErr::new::<PyRuntimeError, _>(format!("Mount point missing: {mount_point}"))
-                })?;
-                Ok(item.unbind())
-            }
-            "providers" | "tools" => {
-                let sub_dict_any = mp.get_item(mount_point)?.ok_or_else(|| {
-                    PyErr::new::<PyRuntimeError, _>(format!("Mount point missing: {mount_point}"))
-                })?;
-                match name {
-                    None => Ok(sub_dict_any.unbind()),
-                    Some(n) => {
-                        let sub = sub_dict_any.cast::<PyDict>()?;
-                        match sub.get_item(n)? {
-                            Some(item) => Ok(item.unbind()),
-                            None => Ok(py.None()),
-                        }
-                    }
+        let point = parse_mount_point(mount_point)?;
+
+        let item = mp.get_item(point.as_str())?.ok_or_else(|| {
+            PyErr::new::<PyRuntimeError, _>(format!("Mount point missing: {mount_point}"))
+        })?;
+        match (point.is_multi_slot(), name) {
+            (true, Some(n)) => {
+                let sub = item.cast::<PyDict>()?;
+                match sub.get_item(n)? {
+                    Some(module) => Ok(module.unbind()),
+                    None => Ok(py.None()),
                 }
             }
-            _ => Ok(py.None()),
+            // Single-slot, or multi-slot without a name (the whole dict)
+            _ => Ok(item.unbind()),
         }
     }
 
@@ -162,33 +146,22 @@ impl PyCoordinator {
         name: Option<&str>,
     ) -> PyResult<Bound<'py, PyAny>> {
         let mp = self.mount_points.bind(py);
-
-        if !mp.contains(mount_point)? {
-            return Err(PyErr::new::<PyValueError, _>(format!(
-                "Unknown mount point: {mount_point}"
-            )));
-        }
-
-        match mount_point {
-            "orchestrator" | "context" | "module-source-resolver" => {
-                mp.set_item(mount_point, py.None())?;
-            }
-            "providers" | "tools" => {
-                if let Some(n) = name {
-                    let sub_any = mp.get_item(mount_point)?.ok_or_else(|| {
-                        PyErr::new::<PyRuntimeError, _>(format!(
-                            "Mount point missing: {mount_point}"
-                        ))
-                    })?;
-                    let sub_dict = sub_any.cast::<PyDict>()?;
-                    sub_dict.del_item(n).ok(); // Ignore if not present
-                } else {
-                    return Err(PyErr::new::<PyValueError, _>(format!(
-                        "Name required to unmount from {mount_point}"
-                    )));
-                }
-            }
-            _ => {}
+        let point = parse_mount_point(mount_point)?;
+
+        if point.is_multi_slot() {
+            let Some(n) = name else {
+                return Err(PyErr::new::<PyValueError, _>(format!(
+                    "Name required to unmount from {mount_point}"
+                )));
+            };
+            let sub_any = mp.get_item(point.as_str())?.ok_or_else(|| {
+                PyErr::new::<PyRuntimeError, _>(format!("Mount point missing: {mount_point}"))
+            })?;
+            let sub_dict = sub_any.cast::<PyDict>()?;
+            sub_dict.del_item(n).ok(); // Ignore if not present
+        } else {
+            point.validate_mount(None).map_err(coordinator_err)?;
+            mp.set_item(point.as_str(), py.None())?;
         }
 
         wrap_future_as_coroutine(
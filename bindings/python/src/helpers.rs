@@ -3,7 +3,8 @@
 // ---------------------------------------------------------------------------
 
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{PyDict, PyString};
 
 /// Parse an approval system's decision string into a boolean.
 ///
@@ -85,3 +86,238 @@ pub(crate) fn json_dumps_safe<'py>(py: Python<'py>, obj: &Bound<'py, PyAny>) ->
         .call_method("dumps", (obj,), Some(&kwargs))?
         .extract()
 }
+
+// ---------------------------------------------------------------------------
+// Direct Python <-> serde_json::Value conversion
+// ---------------------------------------------------------------------------
+
+/// Nesting limit for [`py_to_json`]; deeper input is treated as a cycle.
+const MAX_CONVERT_DEPTH: usize = 512;
+
+/// Convert a Python object to a `serde_json::Value` without going through a
+/// JSON string.
+///
+/// Produces the same value as `json.loads(json.dumps(obj, default=str))`
+/// (see [`json_dumps_safe`]) for everything that round-trip accepts:
+///
+/// - `None`, `bool`, `int`, `float`, `str` map to their JSON counterparts
+///   (subclasses such as `IntEnum` / `StrEnum` included). Integers outside
+///   the `i64`/`u64` range become floats, as `serde_json` would parse them.
+/// - `dict` becomes an object; `str`/`int`/`float`/`bool`/`None` keys are
+///   stringified as `json.dumps` does, other keys raise `TypeError`.
+/// - `list` and `tuple` become arrays.
+/// - Anything else becomes `str(obj)` (the `default=str` fallback).
+///
+/// Non-finite floats become `null` (the string round-trip rejects them).
+/// Nesting deeper than 512 levels raises `ValueError` (json.dumps's circular
+/// reference check).
+pub(crate) fn py_to_json(obj: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    py_to_json_at(obj, 0)
+}
+
+fn py_to_json_at(obj: &Bound<'_, PyAny>, depth: usize) -> PyResult<serde_json::Value> {
+    use pyo3::exceptions::PyValueError;
+    use pyo3::types::{PyBool, PyFloat, PyInt, PyList, PyString, PyTuple};
+    use serde_json::Value;
+
+    if depth > MAX_CONVERT_DEPTH {
+        return Err(PyErr::new::<PyValueError, _>(
+            "Circular reference detected (or nesting too deep) while converting to JSON",
+        ));
+    }
+    if obj.is_none() {
+        return Ok(Value::Null);
+    }
+    // bool before int: bool is an int subclass.
+    if let Ok(b) = obj.cast::<PyBool>() {
+        return Ok(Value::Bool(b.is_true()));
+    }
+    if let Ok(i) = obj.cast::<PyInt>() {
+        if let Ok(v) = i.extract::<i64>() {
+            return Ok(Value::from(v));
+        }
+        if let Ok(v) = i.extract::<u64>() {
+            return Ok(Value::from(v));
+        }
+        return Ok(float_value(i.extract::<f64>()?));
+    }
+    if let Ok(f) = obj.cast::<PyFloat>() {
+        return Ok(float_value(f.value()));
+    }
+    if let Ok(s) = obj.cast::<PyString>() {
+        return Ok(Value::String(s.to_str()?.to_owned()));
+    }
+    if let Ok(dict) = obj.cast::<PyDict>() {
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (key, value) in dict.iter() {
+            map.insert(json_key(&key)?, py_to_json_at(&value, depth + 1)?);
+        }
+        return Ok(Value::Object(map));
+    }
+    if let Ok(list) = obj.cast::<PyList>() {
+        return list
+            .iter()
+            .map(|item| py_to_json_at(&item, depth + 1))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array);
+    }
+    if let Ok(tuple) = obj.cast::<PyTuple>() {
+        return tuple
+            .iter()
+            .map(|item| py_to_json_at(&item, depth + 1))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Value::Array);
+    }
+    // default=str fallback (Decimal, datetime, UUID, ...)
+    Ok(Value::String(obj.str()?.to_str()?.to_owned()))
+}
+
+fn float_value(f: f64) -> serde_json::Value {
+    serde_json::Number::from_f64(f)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// Stringify a dict key the way `json.dumps` does.
+fn json_key(key: &Bound<'_, PyAny>) -> PyResult<String> {
+    use pyo3::exceptions::PyTypeError;
+    use pyo3::types::{PyBool, PyFloat, PyInt, PyString};
+
+    if let Ok(s) = key.cast::<PyString>() {
+        return Ok(s.to_str()?.to_owned());
+    }
+    if key.is_none() {
+        return Ok("null".to_string());
+    }
+    if let Ok(b) = key.cast::<PyBool>() {
+        return Ok(if b.is_true() { "true" } else { "false" }.to_string());
+    }
+    if key.cast::<PyInt>().is_ok() {
+        return Ok(key.str()?.to_str()?.to_owned());
+    }
+    if let Ok(f) = key.cast::<PyFloat>() {
+        let v = f.value();
+        return Ok(match v {
+            v if v.is_nan() => "NaN".to_string(),
+            v if v == f64::INFINITY => "Infinity".to_string(),
+            v if v == f64::NEG_INFINITY => "-Infinity".to_string(),
+            _ => key.repr()?.to_str()?.to_owned(),
+        });
+    }
+    Err(PyErr::new::<PyTypeError, _>(format!(
+        "keys must be str, int, float, bool or None, not {}",
+        key.get_type().name()?
+    )))
+}
+
+/// Convert a `serde_json::Value` into the equivalent Python object
+/// (`dict`, `list`, `str`, `int`, `float`, `bool`, `None`) without going
+/// through `json.loads`.
+pub(crate) fn json_to_py<'py>(
+    py: Python<'py>,
+    value: &serde_json::Value,
+) -> PyResult<Bound<'py, PyAny>> {
+    use pyo3::types::{PyBool, PyList, PyString};
+    use serde_json::Value;
+
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => PyBool::new(py, *b).to_owned().into_any(),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_pyobject(py)?.into_any()
+            } else if let Some(u) = n.as_u64() {
+                u.into_pyobject(py)?.into_any()
+            } else {
+                n.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any()
+            }
+        }
+        Value::String(s) => PyString::new(py, s).into_any(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (k, v) in map {
+                dict.set_item(k, json_to_py(py, v)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// `model_dump` (if available) followed by [`py_to_json`]: the direct
+/// replacement for `try_model_dump` + `json_dumps_safe` + `serde_json::from_str`.
+pub(crate) fn to_json_value(obj: &Bound<'_, PyAny>) -> PyResult<serde_json::Value> {
+    py_to_json(&try_model_dump(obj))
+}
+
+// ---------------------------------------------------------------------------
+// Per-event fast paths
+// ---------------------------------------------------------------------------
+//
+// Every emit() crosses the boundary at least twice per Python handler. The
+// helpers below keep that crossing to native conversions: module and class
+// lookups are resolved once per interpreter, event names are interned, and
+// kernel results are built with `model_construct` (the kernel already
+// validated them) instead of `model_validate`.
+
+static HOOK_RESULT_CLASS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static ISCOROUTINE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// Resolve `module.attr` once and cache it in `cell`.
+fn cached_attr<'py>(
+    py: Python<'py>,
+    cell: &'static PyOnceLock<Py<PyAny>>,
+    module: &str,
+    attr: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    cell.get_or_try_init(py, || {
+        Ok::<_, PyErr>(py.import(module)?.getattr(attr)?.unbind())
+    })
+    .map(|obj| obj.bind(py).clone())
+}
+
+/// `inspect.iscoroutine(obj)`.
+pub(crate) fn is_coroutine(obj: &Bound<'_, PyAny>) -> PyResult<bool> {
+    cached_attr(obj.py(), &ISCOROUTINE, "inspect", "iscoroutine")?
+        .call1((obj,))?
+        .extract()
+}
+
+/// The event name as an interned Python string.
+///
+/// Event names come from a small fixed set, so every handler of every emit
+/// of an event shares one `str` object.
+pub(crate) fn event_name<'py>(py: Python<'py>, event: &str) -> Bound<'py, PyString> {
+    PyString::intern(py, event)
+}
+
+/// Build an `amplifier_core.models.HookResult` from a kernel result.
+///
+/// The fields are converted natively from the serde representation and
+/// passed to `HookResult.model_construct`, skipping pydantic validation:
+/// the kernel type already guarantees every field is well-formed. Fields
+/// the Python model does not declare (kernel extensions) are dropped, as
+/// `model_validate` would.
+pub(crate) fn hook_result_to_py<'py>(
+    py: Python<'py>,
+    result: &amplifier_core::HookResult,
+) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_value(result).unwrap_or_else(|e| {
+        log::warn!("Failed to serialize hook result (using empty object): {e}");
+        serde_json::json!({})
+    });
+    let fields = json_to_py(py, &value)?.cast_into::<PyDict>()?;
+    cached_attr(
+        py,
+        &HOOK_RESULT_CLASS,
+        "amplifier_core.models",
+        "HookResult",
+    )?
+    .call_method("model_construct", (), Some(&fields))
+}
@@ -5,13 +5,15 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 use serde_json::Value;
 
 use crate::bridges::PyHookHandlerBridge;
-use crate::helpers::{json_dumps_safe, try_model_dump, wrap_future_as_coroutine};
+use crate::helpers::{
+    hook_result_to_py, json_to_py, py_to_json, to_json_value, wrap_future_as_coroutine,
+};
 
 // ---------------------------------------------------------------------------
 // PyUnregisterFn — callable returned by PyHookRegistry.register()
@@ -85,7 +87,12 @@ impl PyHookRegistry {
     /// Matches Python `HookRegistry.register(event, handler, priority=0, name=None)`.
     /// The handler and name argument order matches the Python API so that
     /// module code like `registry.register(event, handler, name="my-hook")` works.
-    #[pyo3(signature = (event, handler, priority = 0, name = None))]
+    /// `phase` is one of `"pre_validation"`, `"policy"` (default), `"mutation"`
+    /// or `"observation"`. `condition` is a hook condition expression such as
+    /// `'data.tool_name == "bash"'`; the handler is only called for payloads
+    /// it holds for, without entering Python for the rest.
+    #[pyo3(signature = (event, handler, priority = 0, name = None, phase = None, condition = None))]
+    #[allow(clippy::too_many_arguments)]
     fn register(
         &self,
         py: Python<'_>,
@@ -93,13 +100,39 @@ impl PyHookRegistry {
         handler: Py<PyAny>,
         priority: i32,
         name: Option<String>,
+        phase: Option<&str>,
+        condition: Option<&str>,
     ) -> PyResult<Py<PyAny>> {
+        let phase = match phase {
+            Some(phase) => phase
+                .parse::<amplifier_core::HookPhase>()
+                .map_err(PyErr::new::<PyValueError, _>)?,
+            None => amplifier_core::HookPhase::default(),
+        };
+        let condition = condition
+            .map(str::parse::<amplifier_core::HookCondition>)
+            .transpose()
+            .map_err(|e| PyErr::new::<PyValueError, _>(e.to_string()))?;
         let handler_name =
             name.unwrap_or_else(|| format!("_auto_{event}_{}", uuid::Uuid::new_v4()));
         let bridge = Arc::new(PyHookHandlerBridge { callable: handler });
-        let unregister_fn =
-            self.inner
-                .register(event, bridge, priority, Some(handler_name.clone()));
+        let unregister_fn = match condition {
+            Some(condition) => self.inner.register_with_condition(
+                event,
+                bridge,
+                phase,
+                priority,
+                Some(handler_name.clone()),
+                condition,
+            ),
+            None => self.inner.register_in_phase(
+                event,
+                bridge,
+                phase,
+                priority,
+                Some(handler_name.clone()),
+            ),
+        };
 
         self.unregister_fns
             .lock()
@@ -118,9 +151,11 @@ impl PyHookRegistry {
         Ok(callable.into_any())
     }
 
-    /// Emit an event and return the aggregated result as a JSON string.
+    /// Emit an event and return the aggregated result as a `HookResult`.
     ///
-    /// Calls all registered handlers for the event in priority order.
+    /// Calls all registered handlers for the event in priority order. The
+    /// payload and the result are converted natively, without JSON strings,
+    /// and the result is not re-validated by pydantic.
     fn emit<'py>(
         &self,
         py: Python<'py>,
@@ -129,29 +164,14 @@ impl PyHookRegistry {
     ) -> PyResult<Bound<'py, PyAny>> {
         let inner = self.inner.clone();
         // Convert Python data to serde_json::Value
-        let serializable = try_model_dump(&data);
-        let json_str: String = json_dumps_safe(py, &serializable)?;
-        let value: Value = serde_json::from_str(&json_str)
-            .map_err(|e| PyErr::new::<PyRuntimeError, _>(format!("Invalid JSON: {e}")))?;
+        let value: Value = to_json_value(&data)?;
 
         wrap_future_as_coroutine(
             py,
             pyo3_async_runtimes::tokio::future_into_py(py, async move {
                 let result = inner.emit(&event, value).await;
-                // Convert HookResult to a JSON string, then parse it back as a
-                // Python HookResult object so callers can access .action, .data, etc.
-                let result_json = serde_json::to_string(&result).unwrap_or_else(|e| {
-                    log::warn!("Failed to serialize hook result to JSON (using empty object): {e}");
-                    "{}".to_string()
-                });
                 Python::try_attach(|py| -> PyResult<Py<PyAny>> {
-                    let json_mod = py.import("json")?;
-                    let dict = json_mod.call_method1("loads", (&result_json,))?;
-                    // Create a proper HookResult from the dict
-                    let models = py.import("amplifier_core.models")?;
-                    let hook_result_cls = models.getattr("HookResult")?;
-                    let obj = hook_result_cls.call_method1("model_validate", (&dict,))?;
-                    Ok(obj.unbind())
+                    Ok(hook_result_to_py(py, &result)?.unbind())
                 })
                 .ok_or_else(|| {
                     PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
@@ -182,19 +202,34 @@ impl PyHookRegistry {
     #[pyo3(signature = (**kwargs))]
     fn set_default_fields(&self, kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
         let value = match kwargs {
-            Some(dict) => {
-                let json_str = json_dumps_safe(dict.py(), dict.as_any())?;
-                serde_json::from_str(&json_str)
-                    .map_err(|e| PyErr::new::<PyRuntimeError, _>(format!("Invalid JSON: {e}")))?
-            }
+            Some(dict) => py_to_json(dict.as_any())?,
             None => serde_json::json!({}),
         };
         self.inner.set_default_fields(value);
         Ok(())
     }
 
+    /// Install an emit-time event filter, or remove it with `None`.
+    ///
+    /// `config` has the shape of `session.hooks.filter` (`min_level`,
+    /// `disabled`, `enabled`, `sample_rates`, `levels`).
+    #[pyo3(signature = (config = None))]
+    fn set_event_filter(&self, config: Option<&Bound<'_, PyDict>>) -> PyResult<()> {
+        let Some(dict) = config else {
+            self.inner.clear_event_filter();
+            return Ok(());
+        };
+        let config: amplifier_core::event_filter::EventFilterConfig =
+            serde_json::from_value(py_to_json(dict.as_any())?).map_err(|e| {
+                PyErr::new::<PyValueError, _>(format!("Invalid event filter config: {e}"))
+            })?;
+        self.inner.set_event_filter(config);
+        Ok(())
+    }
+
     /// Alias for `register()` -- backward compatibility with Python HookRegistry.
-    #[pyo3(signature = (event, handler, priority = 0, name = None))]
+    #[pyo3(signature = (event, handler, priority = 0, name = None, phase = None, condition = None))]
+    #[allow(clippy::too_many_arguments)]
     fn on(
         &self,
         py: Python<'_>,
@@ -202,8 +237,10 @@ impl PyHookRegistry {
         handler: Py<PyAny>,
         priority: i32,
         name: Option<String>,
+        phase: Option<&str>,
+        condition: Option<&str>,
     ) -> PyResult<Py<PyAny>> {
-        self.register(py, event, handler, priority, name)
+        self.register(py, event, handler, priority, name, phase, condition)
     }
 
     /// List registered handlers, optionally filtered by event.
@@ -215,14 +252,30 @@ impl PyHookRegistry {
         Ok(self.inner.list_handlers(event))
     }
 
+    /// Enable or disable the handlers named `name`, on every event or only
+    /// on `event`. Disabled handlers keep their registration.
+    ///
+    /// Returns how many registrations changed.
+    #[pyo3(signature = (name, enabled, event = None))]
+    fn set_handler_enabled(&self, name: &str, enabled: bool, event: Option<&str>) -> usize {
+        match event {
+            Some(event) => self.inner.set_handler_enabled_for(event, name, enabled),
+            None => self.inner.set_handler_enabled(name, enabled),
+        }
+    }
+
+    /// Disabled handler names, grouped by event.
+    fn disabled_handlers(&self) -> HashMap<String, Vec<String>> {
+        self.inner.disabled_handlers()
+    }
+
     /// Emit event and collect data from all handler responses.
     ///
     /// Unlike emit() which processes action semantics (deny short-circuits, etc.),
     /// this method simply collects result.data from all handlers for aggregation.
     ///
     /// Returns a Python `list[dict]`, where each dict is the `result.data`
-    /// from one handler response. Each `HashMap<String, Value>` is serialized
-    /// to JSON and parsed back into a Python dict via `json.loads()`.
+    /// from one handler response, converted directly from `serde_json::Value`.
     ///
     /// Matches Python `HookRegistry.emit_and_collect(event, data, timeout=1.0)`.
     #[pyo3(signature = (event, data, timeout = 1.0))]
@@ -234,10 +287,7 @@ impl PyHookRegistry {
         timeout: f64,
     ) -> PyResult<Bound<'py, PyAny>> {
         let inner = self.inner.clone();
-        let serializable = try_model_dump(&data);
-        let json_str: String = json_dumps_safe(py, &serializable)?;
-        let value: Value = serde_json::from_str(&json_str)
-            .map_err(|e| PyErr::new::<PyRuntimeError, _>(format!("Invalid JSON: {e}")))?;
+        let value: Value = to_json_value(&data)?;
         let timeout_dur = std::time::Duration::from_secs_f64(timeout);
 
         wrap_future_as_coroutine(
@@ -245,18 +295,12 @@ impl PyHookRegistry {
             pyo3_async_runtimes::tokio::future_into_py(py, async move {
                 let results = inner.emit_and_collect(&event, value, timeout_dur).await;
                 // Convert each HashMap<String, Value> to a Python dict.
-                // Serializes each result to a JSON string then json.loads() to dict.
                 // Returns Py<PyAny> (a Python list of dicts).
                 Python::try_attach(|py| -> PyResult<Py<PyAny>> {
-                    let json_mod = py.import("json")?;
                     let list = PyList::empty(py);
-                    for r in &results {
-                        let json_str = serde_json::to_string(r).unwrap_or_else(|e| {
-                            log::warn!("Failed to serialize emit_and_collect result to JSON (using empty object): {e}");
-                            "{}".to_string()
-                        });
-                        let dict = json_mod.call_method1("loads", (&json_str,))?;
-                        list.append(dict)?;
+                    for r in results {
+                        let map: serde_json::Map<String, Value> = r.into_iter().collect();
+                        list.append(json_to_py(py, &Value::Object(map))?)?;
                     }
                     Ok(list.into_any().unbind())
                 })
@@ -269,6 +313,45 @@ impl PyHookRegistry {
         )
     }
 
+    /// Emit a decision event and return the ranked, deduplicated candidates.
+    ///
+    /// Handlers return a candidate (`{"id", "score", "confidence"?,
+    /// "metadata"?}`) or `{"candidates": [...]}` in `result.data`. Returns a
+    /// Python `list[dict]`, best first, each with a `source` handler name.
+    #[pyo3(signature = (event, data, timeout = 1.0))]
+    fn emit_decision<'py>(
+        &self,
+        py: Python<'py>,
+        event: String,
+        data: Bound<'py, PyAny>,
+        timeout: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let inner = self.inner.clone();
+        let value: Value = to_json_value(&data)?;
+        let timeout_dur = std::time::Duration::from_secs_f64(timeout);
+
+        wrap_future_as_coroutine(
+            py,
+            pyo3_async_runtimes::tokio::future_into_py(py, async move {
+                let candidates = inner.emit_decision(&event, value, timeout_dur).await;
+                let candidates_value = serde_json::to_value(&candidates).unwrap_or_else(|e| {
+                    log::warn!(
+                        "Failed to serialize emit_decision candidates (using empty list): {e}"
+                    );
+                    serde_json::json!([])
+                });
+                Python::try_attach(|py| -> PyResult<Py<PyAny>> {
+                    Ok(json_to_py(py, &candidates_value)?.unbind())
+                })
+                .ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                        "Failed to attach to Python runtime",
+                    )
+                })?
+            }),
+        )
+    }
+
     // Class-level event name constants matching Python HookRegistry
     #[classattr]
     const SESSION_START: &'static str = "session:start";
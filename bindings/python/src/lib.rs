@@ -12,6 +12,9 @@
 //! | `RustHookRegistry`      | [`PyHookRegistry`]   | `amplifier_core::HookRegistry` |
 //! | `RustCancellationToken` | [`PyCancellationToken`] | `amplifier_core::CancellationToken` |
 //! | `RustCoordinator`       | [`PyCoordinator`]    | `amplifier_core::Coordinator` |
+//! | `Message`               | [`PyMessage`]        | `amplifier_core::Message`   |
+//! | `ChatRequest`           | [`PyChatRequest`]    | `amplifier_core::ChatRequest` |
+//! | `ChatResponse`          | [`PyChatResponse`]   | `amplifier_core::ChatResponse` |
 
 use prost::Message as ProstMessage;
 use pyo3::prelude::*;
@@ -26,6 +29,7 @@ mod coordinator;
 mod errors;
 mod helpers;
 mod hooks;
+mod messages;
 mod module_resolver;
 mod retry;
 mod session;
@@ -40,6 +44,7 @@ pub(crate) use cancellation::PyCancellationToken;
 pub(crate) use coordinator::PyCoordinator;
 pub(crate) use errors::PyProviderError;
 pub(crate) use hooks::{PyHookRegistry, PyUnregisterFn};
+pub(crate) use messages::{PyChatRequest, PyChatResponse, PyMessage};
 #[cfg(feature = "wasm")]
 pub(crate) use module_resolver::load_wasm_from_path;
 pub(crate) use module_resolver::resolve_module;
@@ -119,6 +124,9 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PyCoordinator>()?;
     m.add_class::<PyProviderError>()?;
     m.add_class::<PyRetryConfig>()?;
+    m.add_class::<PyMessage>()?;
+    m.add_class::<PyChatRequest>()?;
+    m.add_class::<PyChatResponse>()?;
     #[cfg(feature = "wasm")]
     {
         m.add_class::<PyWasmTool>()?;
@@ -148,10 +156,17 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("SESSION_END", amplifier_core::events::SESSION_END)?;
     m.add("SESSION_FORK", amplifier_core::events::SESSION_FORK)?;
     m.add("SESSION_RESUME", amplifier_core::events::SESSION_RESUME)?;
+    m.add("SESSION_REWIND", amplifier_core::events::SESSION_REWIND)?;
+    m.add("SESSION_REAPED", amplifier_core::events::SESSION_REAPED)?;
+    m.add(
+        "SESSION_HEARTBEAT",
+        amplifier_core::events::SESSION_HEARTBEAT,
+    )?;
 
     // Prompt lifecycle
     m.add("PROMPT_SUBMIT", amplifier_core::events::PROMPT_SUBMIT)?;
     m.add("PROMPT_COMPLETE", amplifier_core::events::PROMPT_COMPLETE)?;
+    m.add("PROMPT_DUPLICATE", amplifier_core::events::PROMPT_DUPLICATE)?;
 
     // Planning
     m.add("PLAN_START", amplifier_core::events::PLAN_START)?;
@@ -174,6 +189,8 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
         amplifier_core::events::PROVIDER_TOOL_SEQUENCE_REPAIRED,
     )?;
     m.add("PROVIDER_RESOLVE", amplifier_core::events::PROVIDER_RESOLVE)?;
+    m.add("PROVIDER_PRE", amplifier_core::events::PROVIDER_PRE)?;
+    m.add("PROVIDER_POST", amplifier_core::events::PROVIDER_POST)?;
 
     // LLM events
     m.add("LLM_REQUEST", amplifier_core::events::LLM_REQUEST)?;
@@ -192,6 +209,9 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
         "CONTENT_BLOCK_END",
         amplifier_core::events::CONTENT_BLOCK_END,
     )?;
+    m.add("CONTENT_START", amplifier_core::events::CONTENT_START)?;
+    m.add("CONTENT_DELTA", amplifier_core::events::CONTENT_DELTA)?;
+    m.add("CONTENT_STOP", amplifier_core::events::CONTENT_STOP)?;
 
     // Thinking events
     m.add("THINKING_DELTA", amplifier_core::events::THINKING_DELTA)?;
@@ -201,6 +221,14 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("TOOL_PRE", amplifier_core::events::TOOL_PRE)?;
     m.add("TOOL_POST", amplifier_core::events::TOOL_POST)?;
     m.add("TOOL_ERROR", amplifier_core::events::TOOL_ERROR)?;
+    m.add("TOOL_PROGRESS", amplifier_core::events::TOOL_PROGRESS)?;
+    m.add("TOOL_TIMEOUT", amplifier_core::events::TOOL_TIMEOUT)?;
+    m.add(
+        "TOOL_RESULT_TRANSFORM",
+        amplifier_core::events::TOOL_RESULT_TRANSFORM,
+    )?;
+    m.add("TOOLS_DISCOVER", amplifier_core::events::TOOLS_DISCOVER)?;
+    m.add("TOOLS_INVOKE", amplifier_core::events::TOOLS_INVOKE)?;
 
     // Context management
     m.add(
@@ -216,12 +244,28 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
         amplifier_core::events::CONTEXT_COMPACTION,
     )?;
     m.add("CONTEXT_INCLUDE", amplifier_core::events::CONTEXT_INCLUDE)?;
+    m.add(
+        "CONTEXT_DEDUPLICATED",
+        amplifier_core::events::CONTEXT_DEDUPLICATED,
+    )?;
+    m.add(
+        "CONTEXT_INJECTION_DROPPED",
+        amplifier_core::events::CONTEXT_INJECTION_DROPPED,
+    )?;
 
     // Orchestrator lifecycle
     m.add(
         "ORCHESTRATOR_COMPLETE",
         amplifier_core::events::ORCHESTRATOR_COMPLETE,
     )?;
+    m.add(
+        "ORCHESTRATOR_STATUS",
+        amplifier_core::events::ORCHESTRATOR_STATUS,
+    )?;
+    m.add(
+        "ORCHESTRATOR_RECOVERY",
+        amplifier_core::events::ORCHESTRATOR_RECOVERY,
+    )?;
     m.add("EXECUTION_START", amplifier_core::events::EXECUTION_START)?;
     m.add("EXECUTION_END", amplifier_core::events::EXECUTION_END)?;
 
@@ -243,9 +287,14 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
     )?;
     m.add("APPROVAL_GRANTED", amplifier_core::events::APPROVAL_GRANTED)?;
     m.add("APPROVAL_DENIED", amplifier_core::events::APPROVAL_DENIED)?;
+    m.add(
+        "APPROVAL_CANCELLED",
+        amplifier_core::events::APPROVAL_CANCELLED,
+    )?;
 
     // Cancellation lifecycle
     m.add("CANCEL_REQUESTED", amplifier_core::events::CANCEL_REQUESTED)?;
+    m.add("CANCEL_ESCALATED", amplifier_core::events::CANCEL_ESCALATED)?;
     m.add("CANCEL_COMPLETED", amplifier_core::events::CANCEL_COMPLETED)?;
 
     // Module lifecycle events
@@ -254,6 +303,22 @@ fn _engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
         amplifier_core::events::MODULE_ON_SESSION_READY_FAILED,
     )?;
 
+    // Kernel resource events
+    m.add(
+        "KERNEL_MEMORY_PRESSURE",
+        amplifier_core::events::KERNEL_MEMORY_PRESSURE,
+    )?;
+    m.add(
+        "KERNEL_MEMORY_EVICTED",
+        amplifier_core::events::KERNEL_MEMORY_EVICTED,
+    )?;
+    m.add("QUOTA_WARNING", amplifier_core::events::QUOTA_WARNING)?;
+    m.add("SAFETY_LIMIT", amplifier_core::events::SAFETY_LIMIT)?;
+    m.add(
+        "HOOK_SLOW_HANDLER",
+        amplifier_core::events::HOOK_SLOW_HANDLER,
+    )?;
+
     // Aggregate list of all events
     m.add("ALL_EVENTS", amplifier_core::events::ALL_EVENTS.to_vec())?;
 
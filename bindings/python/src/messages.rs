@@ -0,0 +1,380 @@
+// ---------------------------------------------------------------------------
+// PyMessage / PyChatRequest / PyChatResponse — typed message wrappers
+// ---------------------------------------------------------------------------
+
+use amplifier_core::messages::{ChatRequest, ChatResponse, ContentBlock, Message, MessageContent};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::helpers::{json_to_py, to_json_value};
+
+/// Serialize a Rust value straight into Python objects (no JSON string).
+fn to_py<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_value(value)
+        .map_err(|e| PyValueError::new_err(format!("Failed to convert to Python: {e}")))?;
+    json_to_py(py, &value)
+}
+
+/// Build a Rust value from a dict or Pydantic model.
+fn from_py<T: DeserializeOwned>(what: &str, data: &Bound<'_, PyAny>) -> PyResult<T> {
+    serde_json::from_value(to_json_value(data)?)
+        .map_err(|e| PyValueError::new_err(format!("Invalid {what}: {e}")))
+}
+
+fn from_json_str<T: DeserializeOwned>(what: &str, json: &str) -> PyResult<T> {
+    serde_json::from_str(json)
+        .map_err(|e| PyValueError::new_err(format!("Invalid {what} JSON: {e}")))
+}
+
+/// `"value"` or `None` for an optional string in a repr.
+fn repr_opt(value: Option<&str>) -> String {
+    value.map_or_else(|| "None".to_string(), |v| format!("{v:?}"))
+}
+
+fn to_json_str(value: &impl Serialize) -> PyResult<String> {
+    serde_json::to_string(value)
+        .map_err(|e| PyValueError::new_err(format!("Failed to serialize: {e}")))
+}
+
+/// Concatenated text of all `text` blocks.
+fn block_text(blocks: &[ContentBlock]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Message
+// ---------------------------------------------------------------------------
+
+/// A single conversation message backed by the Rust `Message` struct.
+///
+/// Fields are read directly from the Rust value; nothing is re-serialized
+/// until `to_dict()` / `to_json()` is called.
+#[pyclass(name = "Message", frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub(crate) struct PyMessage {
+    pub(crate) inner: Message,
+}
+
+#[pymethods]
+impl PyMessage {
+    /// Build from a dict or a Pydantic `Message`.
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            inner: from_py("Message", data)?,
+        })
+    }
+
+    /// Parse from a JSON string.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: from_json_str("Message", json)?,
+        })
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_str(&self.inner)
+    }
+
+    /// `"system"`, `"user"`, `"assistant"`, ...
+    #[getter]
+    fn role<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.role)
+    }
+
+    /// A `str`, or a list of content-block dicts.
+    #[getter]
+    fn content<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.content)
+    }
+
+    /// Plain text of the message: the string content, or all text blocks joined.
+    #[getter]
+    fn text(&self) -> String {
+        match &self.inner.content {
+            MessageContent::Text(text) => text.clone(),
+            MessageContent::Blocks(blocks) => block_text(blocks),
+        }
+    }
+
+    #[getter]
+    fn name(&self) -> Option<&str> {
+        self.inner.name.as_deref()
+    }
+
+    #[getter]
+    fn tool_call_id(&self) -> Option<&str> {
+        self.inner.tool_call_id.as_deref()
+    }
+
+    #[getter]
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.metadata)
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .cast::<PyMessage>()
+            .is_ok_and(|other| other.get().inner == self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        let role = serde_json::to_value(&self.inner.role)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_owned))
+            .unwrap_or_default();
+        format!("Message(role={role:?}, text={:?})", self.text())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ChatRequest
+// ---------------------------------------------------------------------------
+
+/// A provider request backed by the Rust `ChatRequest` struct.
+#[pyclass(name = "ChatRequest", frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub(crate) struct PyChatRequest {
+    pub(crate) inner: ChatRequest,
+}
+
+#[pymethods]
+impl PyChatRequest {
+    /// Build from a dict or a Pydantic `ChatRequest`.
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            inner: from_py("ChatRequest", data)?,
+        })
+    }
+
+    /// Parse from a JSON string.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: from_json_str("ChatRequest", json)?,
+        })
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_str(&self.inner)
+    }
+
+    #[getter]
+    fn messages(&self) -> Vec<PyMessage> {
+        self.inner
+            .messages
+            .iter()
+            .map(|m| PyMessage { inner: m.clone() })
+            .collect()
+    }
+
+    /// Tool specs as dicts, or None.
+    #[getter]
+    fn tools<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.tools)
+    }
+
+    #[getter]
+    fn model(&self) -> Option<&str> {
+        self.inner.model.as_deref()
+    }
+
+    #[getter]
+    fn temperature(&self) -> Option<f64> {
+        self.inner.temperature
+    }
+
+    #[getter]
+    fn top_p(&self) -> Option<f64> {
+        self.inner.top_p
+    }
+
+    #[getter]
+    fn max_output_tokens(&self) -> Option<i64> {
+        self.inner.max_output_tokens
+    }
+
+    #[getter]
+    fn stop(&self) -> Option<Vec<String>> {
+        self.inner.stop.clone()
+    }
+
+    #[getter]
+    fn stream(&self) -> Option<bool> {
+        self.inner.stream
+    }
+
+    #[getter]
+    fn timeout(&self) -> Option<f64> {
+        self.inner.timeout
+    }
+
+    #[getter]
+    fn reasoning_effort(&self) -> Option<&str> {
+        self.inner.reasoning_effort.as_deref()
+    }
+
+    #[getter]
+    fn tool_choice<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.tool_choice)
+    }
+
+    #[getter]
+    fn response_format<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.response_format)
+    }
+
+    #[getter]
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.metadata)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.messages.len()
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .cast::<PyChatRequest>()
+            .is_ok_and(|other| other.get().inner == self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ChatRequest(model={}, messages={})",
+            repr_opt(self.inner.model.as_deref()),
+            self.inner.messages.len()
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ChatResponse
+// ---------------------------------------------------------------------------
+
+/// A provider response backed by the Rust `ChatResponse` struct.
+#[pyclass(name = "ChatResponse", frozen, skip_from_py_object)]
+#[derive(Clone)]
+pub(crate) struct PyChatResponse {
+    pub(crate) inner: ChatResponse,
+}
+
+#[pymethods]
+impl PyChatResponse {
+    /// Build from a dict or a Pydantic `ChatResponse`.
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            inner: from_py("ChatResponse", data)?,
+        })
+    }
+
+    /// Parse from a JSON string.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: from_json_str("ChatResponse", json)?,
+        })
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner)
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        to_json_str(&self.inner)
+    }
+
+    /// Content blocks as dicts.
+    #[getter]
+    fn content<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.content)
+    }
+
+    /// All text blocks joined.
+    #[getter]
+    fn text(&self) -> String {
+        block_text(&self.inner.content)
+    }
+
+    /// Tool calls as dicts, or None.
+    #[getter]
+    fn tool_calls<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.tool_calls)
+    }
+
+    #[getter]
+    fn usage<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.usage)
+    }
+
+    #[getter]
+    fn degradation<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.degradation)
+    }
+
+    #[getter]
+    fn finish_reason(&self) -> Option<&str> {
+        self.inner.finish_reason.as_deref()
+    }
+
+    #[getter]
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py(py, &self.inner.metadata)
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .cast::<PyChatResponse>()
+            .is_ok_and(|other| other.get().inner == self.inner)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ChatResponse(finish_reason={}, blocks={}, tool_calls={})",
+            repr_opt(self.inner.finish_reason.as_deref()),
+            self.inner.content.len(),
+            self.inner.tool_calls.as_ref().map_or(0, Vec::len)
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Boundary helpers
+// ---------------------------------------------------------------------------
+
+/// Extract a `ChatRequest` from a `ChatRequest` wrapper (cloned, no
+/// serialization), a dict, or a Pydantic model.
+pub(crate) fn extract_chat_request(obj: &Bound<'_, PyAny>) -> PyResult<ChatRequest> {
+    match obj.cast::<PyChatRequest>() {
+        Ok(wrapper) => Ok(wrapper.get().inner.clone()),
+        Err(_) => from_py("ChatRequest", obj),
+    }
+}
+
+/// Extract a `ChatResponse` from a `ChatResponse` wrapper (cloned, no
+/// serialization), a dict, or a Pydantic model.
+pub(crate) fn extract_chat_response(obj: &Bound<'_, PyAny>) -> PyResult<ChatResponse> {
+    match obj.cast::<PyChatResponse>() {
+        Ok(wrapper) => Ok(wrapper.get().inner.clone()),
+        Err(_) => from_py("ChatResponse", obj),
+    }
+}
@@ -9,7 +9,7 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use serde_json::Value;
 
-use crate::helpers::{json_dumps_safe, wrap_future_as_coroutine};
+use crate::helpers::{py_to_json, wrap_future_as_coroutine};
 use crate::hooks::PyHookRegistry;
 
 // ---------------------------------------------------------------------------
@@ -33,7 +33,10 @@ use crate::hooks::PyHookRegistry;
 #[pyclass(name = "RustSession")]
 pub(crate) struct PySession {
     /// Rust kernel session (for session_id, parent_id, initialized flag).
-    inner: Arc<tokio::sync::Mutex<amplifier_core::Session>>,
+    ///
+    /// `Session` uses interior mutability, so it is shared without a lock —
+    /// status queries never block on an in-flight `execute()`.
+    inner: Arc<amplifier_core::Session>,
     /// The PyCoordinator instance owned by this session.
     coordinator: Py<PyAny>,
     /// Original config dict (Python dict).
@@ -109,9 +112,7 @@ impl PySession {
         }
 
         // ---- Build Rust kernel Session ----
-        let json_str: String = json_dumps_safe(py, config.as_any())?;
-        let value: Value = serde_json::from_str(&json_str)
-            .map_err(|e| PyErr::new::<PyRuntimeError, _>(format!("Invalid config JSON: {e}")))?;
+        let value: Value = py_to_json(config.as_any())?;
         let session_config = amplifier_core::SessionConfig::from_value(value)
             .map_err(|e| PyErr::new::<PyValueError, _>(format!("Invalid session config: {e}")))?;
 
@@ -174,7 +175,7 @@ impl PySession {
         //      SimpleNamespace, but coordinator.session_id is correct. ----
 
         Ok(Self {
-            inner: Arc::new(tokio::sync::Mutex::new(session)),
+            inner: Arc::new(session),
             coordinator: coord_any,
             config: config.clone().unbind(),
             is_resumed,
@@ -228,8 +229,7 @@ impl PySession {
     /// Whether the session has been initialized.
     #[getter]
     fn initialized(&self) -> PyResult<bool> {
-        let session = self.inner.blocking_lock();
-        Ok(session.is_initialized())
+        Ok(self.inner.is_initialized())
     }
 
     // -----------------------------------------------------------------------
@@ -256,8 +256,7 @@ impl PySession {
         // Step 1: Idempotency — if already initialized, return resolved future
         {
             let this = slf.borrow();
-            let session = this.inner.blocking_lock();
-            if session.is_initialized() {
+            if this.inner.is_initialized() {
                 return wrap_future_as_coroutine(
                     py,
                     pyo3_async_runtimes::tokio::future_into_py(py, async { Ok(()) }),
@@ -316,10 +315,7 @@ impl PySession {
                 })?;
 
                 // Step 5: Mark session as initialized in Rust kernel
-                {
-                    let session = inner.lock().await;
-                    session.set_initialized();
-                }
+                inner.set_initialized();
 
                 Ok(())
             }),
@@ -343,13 +339,10 @@ impl PySession {
     /// 6. Returns the result string
     fn execute<'py>(&self, py: Python<'py>, prompt: String) -> PyResult<Bound<'py, PyAny>> {
         // Step 1: Check initialized — fail fast before any async work
-        {
-            let session = self.inner.blocking_lock();
-            if !session.is_initialized() {
-                return Err(PyErr::new::<PyRuntimeError, _>(
-                    "Session not initialized. Call initialize() first.",
-                ));
-            }
+        if !self.inner.is_initialized() {
+            return Err(PyErr::new::<PyRuntimeError, _>(
+                "Session not initialized. Call initialize() first.",
+            ));
         }
 
         // Step 2: Prepare the Python orchestrator coroutine (we have the GIL here)
@@ -407,10 +400,7 @@ impl PySession {
                 // The core Session tracks the "already emitted" flag atomically;
                 // claim_lifecycle_event() returns true exactly once (the first
                 // execute() call), false on all subsequent calls.
-                let should_emit_lifecycle = {
-                    let session = inner_for_lifecycle.lock().await;
-                    session.claim_lifecycle_event()
-                };
+                let should_emit_lifecycle = inner_for_lifecycle.claim_lifecycle_event();
                 if should_emit_lifecycle {
                     // Call inner Rust emit directly — avoids the Future/coroutine
                     // mismatch that occurs when going through the Python PyO3 bridge
@@ -671,10 +661,7 @@ impl PySession {
                 // ----------------------------------------------------------
                 // Step 3: Reset the initialized flag
                 // ----------------------------------------------------------
-                {
-                    let session = inner.lock().await;
-                    session.clear_initialized();
-                }
+                inner.clear_initialized();
 
                 Ok(())
             }),
@@ -126,6 +126,17 @@ fn json_dumps_safe_signature_compiles() {
     let _: fn(Python<'_>, &Bound<'_, PyAny>) -> PyResult<String> = crate::helpers::json_dumps_safe;
 }
 
+/// Verify the direct `PyAny` <-> `serde_json::Value` converters keep their
+/// signatures (they replace the `json.dumps` / `json.loads` round-trip on the
+/// hook and config hot paths).
+#[test]
+fn direct_value_conversion_signatures_compile() {
+    let _: fn(&Bound<'_, PyAny>) -> PyResult<serde_json::Value> = crate::helpers::py_to_json;
+    let _: fn(&Bound<'_, PyAny>) -> PyResult<serde_json::Value> = crate::helpers::to_json_value;
+    let _: for<'py> fn(Python<'py>, &serde_json::Value) -> PyResult<Bound<'py, PyAny>> =
+        crate::helpers::json_to_py;
+}
+
 /// Structural guard: no raw `json.dumps()` calls outside `helpers.rs`.
 ///
 /// All `json.dumps()` at the Python/Rust FFI boundary must go through
@@ -146,12 +157,12 @@ fn no_raw_json_dumps_outside_helpers() {
                 let path = entry.path();
                 if path.is_dir() {
                     // Skip tests/ — no production FFI code there
-                    if path.file_name().map_or(false, |n| n == "tests") {
+                    if path.file_name().is_some_and(|n| n == "tests") {
                         continue;
                     }
                     check_dir(&path, violations);
-                } else if path.extension().map_or(false, |e| e == "rs")
-                    && path.file_name().map_or(false, |n| n != "helpers.rs")
+                } else if path.extension().is_some_and(|e| e == "rs")
+                    && path.file_name().is_some_and(|n| n != "helpers.rs")
                 {
                     if let Ok(content) = fs::read_to_string(&path) {
                         if content.contains(r#"call_method1("dumps""#)
@@ -175,3 +186,14 @@ fn no_raw_json_dumps_outside_helpers() {
         violations.join("\n")
     );
 }
+
+/// Verify the typed message wrappers exist and the boundary extractors keep
+/// their signatures.
+#[test]
+fn message_wrapper_types_exist() {
+    fn _assert_types_compile(_: &PyMessage, _: &PyChatRequest, _: &PyChatResponse) {}
+    let _: fn(&Bound<'_, PyAny>) -> PyResult<amplifier_core::ChatRequest> =
+        crate::messages::extract_chat_request;
+    let _: fn(&Bound<'_, PyAny>) -> PyResult<amplifier_core::ChatResponse> =
+        crate::messages::extract_chat_response;
+}
@@ -15,6 +15,7 @@ use serde_json::Value;
 
 use crate::coordinator::PyCoordinator;
 use crate::helpers::{json_dumps_safe, try_model_dump, wrap_future_as_coroutine};
+use crate::messages::{extract_chat_request, extract_chat_response};
 
 // ---------------------------------------------------------------------------
 // PyWasmTool — thin Python wrapper around a Rust Arc<dyn Tool>
@@ -185,9 +186,9 @@ impl PyWasmProvider {
 
     /// Generate a completion from a chat request.
     ///
-    /// Async method — takes a request (dict or Pydantic model), serialises it
-    /// to a Rust `ChatRequest`, calls the inner provider, and returns the
-    /// `ChatResponse` as a Python dict.
+    /// Async method — takes a request (`ChatRequest` wrapper, dict or Pydantic
+    /// model), converts it to a Rust `ChatRequest`, calls the inner provider,
+    /// and returns the `ChatResponse` as a Python dict.
     fn complete<'py>(
         &self,
         py: Python<'py>,
@@ -195,13 +196,7 @@ impl PyWasmProvider {
     ) -> PyResult<Bound<'py, PyAny>> {
         let inner = self.inner.clone();
 
-        // Convert Python request to serde_json::Value
-        let serializable = try_model_dump(&request);
-        let json_str: String = json_dumps_safe(py, &serializable)?;
-        let chat_request: amplifier_core::messages::ChatRequest = serde_json::from_str(&json_str)
-            .map_err(|e| {
-            PyErr::new::<PyValueError, _>(format!("Invalid ChatRequest JSON: {e}"))
-        })?;
+        let chat_request = extract_chat_request(&request)?;
 
         wrap_future_as_coroutine(
             py,
@@ -230,17 +225,13 @@ impl PyWasmProvider {
 
     /// Extract tool calls from a provider response.
     ///
-    /// Sync method — takes a response (dict or Pydantic model), deserialises
-    /// it as `ChatResponse`, calls `parse_tool_calls`, and returns a list of
-    /// dicts representing `ToolCall` structs.
+    /// Sync method — takes a response (`ChatResponse` wrapper, dict or
+    /// Pydantic model), converts it to a Rust `ChatResponse`, calls
+    /// `parse_tool_calls`, and returns a list of dicts representing
+    /// `ToolCall` structs.
     fn parse_tool_calls(&self, py: Python<'_>, response: Bound<'_, PyAny>) -> PyResult<Py<PyAny>> {
         let json_mod = py.import("json")?;
-        let serializable = try_model_dump(&response);
-        let json_str: String = json_dumps_safe(py, &serializable)?;
-        let chat_response: amplifier_core::messages::ChatResponse = serde_json::from_str(&json_str)
-            .map_err(|e| {
-                PyErr::new::<PyValueError, _>(format!("Invalid ChatResponse JSON: {e}"))
-            })?;
+        let chat_response = extract_chat_response(&response)?;
 
         let tool_calls = self.inner.parse_tool_calls(&chat_response);
 
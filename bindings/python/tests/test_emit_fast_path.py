@@ -0,0 +1,50 @@
+"""Tests for the native emit() result path.
+
+emit() builds its HookResult with model_construct from natively converted
+fields instead of model_validate, and hands handlers interned event names.
+The observable result must be the same HookResult the validated path built.
+"""
+
+import pytest
+
+from amplifier_core._engine import RustHookRegistry
+from amplifier_core.models import HookResult
+
+
+@pytest.mark.asyncio
+async def test_emit_returns_equivalent_hook_result():
+    registry = RustHookRegistry()
+
+    def deny(event, data):
+        return HookResult(action="deny", reason="blocked", user_message="no")
+
+    registry.register("tool:pre", deny, 0, name="deny")
+    result = await registry.emit("tool:pre", {"tool_name": "bash"})
+
+    assert type(result) is HookResult
+    expected = HookResult(action="deny", reason="blocked", user_message="no")
+    assert result.model_dump() == expected.model_dump()
+
+
+@pytest.mark.asyncio
+async def test_emit_without_handlers_returns_default_result():
+    registry = RustHookRegistry()
+    result = await registry.emit("test:event", {})
+    assert result.model_dump() == HookResult().model_dump()
+
+
+@pytest.mark.asyncio
+async def test_handlers_share_interned_event_name():
+    registry = RustHookRegistry()
+    seen = []
+
+    def capture(event, data):
+        seen.append(event)
+
+    registry.register("test:event", capture, 0, name="a")
+    registry.register("test:event", capture, 1, name="b")
+    await registry.emit("test:event", {})
+    await registry.emit("test:event", {})
+
+    assert seen == ["test:event"] * 4
+    assert all(event is seen[0] for event in seen)
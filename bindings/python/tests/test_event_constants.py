@@ -9,8 +9,12 @@ ALL_EVENT_NAMES = [
     "SESSION_END",
     "SESSION_FORK",
     "SESSION_RESUME",
+    "SESSION_REWIND",
+    "SESSION_REAPED",
+    "SESSION_HEARTBEAT",
     "PROMPT_SUBMIT",
     "PROMPT_COMPLETE",
+    "PROMPT_DUPLICATE",
     "PLAN_START",
     "PLAN_END",
     "PROVIDER_REQUEST",
@@ -20,21 +24,35 @@ ALL_EVENT_NAMES = [
     "PROVIDER_THROTTLE",
     "PROVIDER_TOOL_SEQUENCE_REPAIRED",
     "PROVIDER_RESOLVE",
+    "PROVIDER_PRE",
+    "PROVIDER_POST",
     "LLM_REQUEST",
     "LLM_RESPONSE",
     "CONTENT_BLOCK_START",
     "CONTENT_BLOCK_DELTA",
     "CONTENT_BLOCK_END",
+    "CONTENT_START",
+    "CONTENT_DELTA",
+    "CONTENT_STOP",
     "THINKING_DELTA",
     "THINKING_FINAL",
     "TOOL_PRE",
     "TOOL_POST",
     "TOOL_ERROR",
+    "TOOL_PROGRESS",
+    "TOOL_TIMEOUT",
+    "TOOL_RESULT_TRANSFORM",
+    "TOOLS_DISCOVER",
+    "TOOLS_INVOKE",
     "CONTEXT_PRE_COMPACT",
     "CONTEXT_POST_COMPACT",
     "CONTEXT_COMPACTION",
     "CONTEXT_INCLUDE",
+    "CONTEXT_DEDUPLICATED",
+    "CONTEXT_INJECTION_DROPPED",
     "ORCHESTRATOR_COMPLETE",
+    "ORCHESTRATOR_STATUS",
+    "ORCHESTRATOR_RECOVERY",
     "EXECUTION_START",
     "EXECUTION_END",
     "USER_NOTIFICATION",
@@ -44,8 +62,15 @@ ALL_EVENT_NAMES = [
     "APPROVAL_REQUIRED",
     "APPROVAL_GRANTED",
     "APPROVAL_DENIED",
+    "APPROVAL_CANCELLED",
     "CANCEL_REQUESTED",
+    "CANCEL_ESCALATED",
     "CANCEL_COMPLETED",
+    "KERNEL_MEMORY_PRESSURE",
+    "KERNEL_MEMORY_EVICTED",
+    "QUOTA_WARNING",
+    "SAFETY_LIMIT",
+    "HOOK_SLOW_HANDLER",
 ]
 
 
@@ -93,7 +118,7 @@ class TestAllEventsList:
     def test_all_events_count(self):
         from amplifier_core.events import ALL_EVENTS
 
-        assert len(ALL_EVENTS) == 42, f"Expected 42 events, got {len(ALL_EVENTS)}"
+        assert len(ALL_EVENTS) == 67, f"Expected 67 events, got {len(ALL_EVENTS)}"
 
     def test_all_events_contains_all_constants(self):
         import amplifier_core.events as events
@@ -23,7 +23,7 @@ def test_events_reexport_provider_throttle():
 def test_events_reexport_all_events():
     from amplifier_core.events import ALL_EVENTS
 
-    assert len(ALL_EVENTS) == 42
+    assert len(ALL_EVENTS) == 67
 
 
 def test_capabilities_reexport_tools():
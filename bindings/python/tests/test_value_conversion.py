@@ -0,0 +1,136 @@
+"""Tests for the direct PyAny <-> serde_json::Value conversion at the FFI boundary.
+
+emit(), emit_and_collect(), emit_decision() and hook handler dispatch convert
+payloads directly instead of via json.dumps/json.loads. The result must match
+what the string round-trip (json.dumps(..., default=str)) produced.
+"""
+
+import json
+import math
+from datetime import datetime
+from decimal import Decimal
+from enum import Enum, IntEnum
+
+import pytest
+
+from amplifier_core._engine import RustHookRegistry
+
+
+async def _round_trip(payload):
+    """Emit payload and return what a handler received."""
+    registry = RustHookRegistry()
+    received = []
+
+    def handler(event, data):
+        received.append(data)
+        return None
+
+    registry.register("test:event", handler, 0, name="capture")
+    await registry.emit("test:event", payload)
+    assert len(received) == 1
+    return received[0]
+
+
+def _via_json(payload):
+    return json.loads(json.dumps(payload, default=str))
+
+
+class Color(str, Enum):
+    RED = "red"
+
+
+class Level(IntEnum):
+    HIGH = 3
+
+
+@pytest.mark.asyncio
+async def test_nested_payload_matches_json_round_trip():
+    payload = {
+        "text": "héllo",
+        "int": 42,
+        "neg": -7,
+        "big": 2**63 + 5,
+        "float": 1.5,
+        "flag": True,
+        "none": None,
+        "list": [1, "two", [3.0, {"four": 4}]],
+        "tuple": (1, 2),
+        "nested": {"a": {"b": {"c": []}}},
+    }
+    assert await _round_trip(payload) == _via_json(payload)
+
+
+@pytest.mark.asyncio
+async def test_bool_stays_bool():
+    received = await _round_trip({"yes": True, "no": False})
+    assert received["yes"] is True
+    assert received["no"] is False
+
+
+@pytest.mark.asyncio
+async def test_non_string_keys_are_stringified_like_json_dumps():
+    payload = {1: "a", 2.5: "b", True: "c", None: "d"}
+    assert await _round_trip(payload) == _via_json(payload)
+
+
+@pytest.mark.asyncio
+async def test_unsupported_key_type_raises_type_error():
+    with pytest.raises(TypeError):
+        await _round_trip({(1, 2): "tuple key"})
+
+
+@pytest.mark.asyncio
+async def test_non_json_values_fall_back_to_str():
+    payload = {
+        "cost": Decimal("1.23"),
+        "when": datetime(2024, 1, 1, 12, 0, 0),
+        "obj": object,
+    }
+    assert await _round_trip(payload) == _via_json(payload)
+
+
+@pytest.mark.asyncio
+async def test_enum_subclasses_use_their_values():
+    payload = {"color": Color.RED, "level": Level.HIGH}
+    assert await _round_trip(payload) == {"color": "red", "level": 3}
+
+
+@pytest.mark.asyncio
+async def test_non_finite_floats_become_none():
+    received = await _round_trip({"nan": math.nan, "inf": math.inf})
+    assert received == {"nan": None, "inf": None}
+
+
+@pytest.mark.asyncio
+async def test_circular_reference_raises_value_error():
+    payload = {}
+    payload["self"] = payload
+    with pytest.raises(ValueError):
+        await _round_trip(payload)
+
+
+@pytest.mark.asyncio
+async def test_emit_and_collect_returns_plain_dicts():
+    registry = RustHookRegistry()
+
+    def handler(event, data):
+        return {"action": "continue", "data": {"echo": data["value"], "n": [1, 2]}}
+
+    registry.register("test:event", handler, 0, name="echo")
+    results = await registry.emit_and_collect("test:event", {"value": "x"})
+    assert results == [{"echo": "x", "n": [1, 2]}]
+
+
+@pytest.mark.asyncio
+async def test_handler_returning_model_is_converted():
+    from amplifier_core.models import HookResult
+
+    registry = RustHookRegistry()
+
+    def handler(event, data):
+        return HookResult(action="deny", reason="blocked")
+
+    registry.register("test:event", handler, 0, name="deny")
+    result = await registry.emit("test:event", {})
+    assert result.action == "deny"
+    assert result.reason == "blocked"
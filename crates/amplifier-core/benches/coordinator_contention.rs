@@ -0,0 +1,162 @@
+//! Coordinator read contention under concurrent tool dispatch.
+//!
+//! Each worker task repeatedly does what an orchestrator does per tool call:
+//! take a snapshot of the mounted tools, look one up by name and execute it.
+//! The same workload runs against a `Mutex<HashMap>` that clones the map on
+//! every snapshot (the coordinator's previous layout) and against the
+//! coordinator itself, with and without a concurrent writer mounting and
+//! unmounting tools.
+//!
+//! Run with `cargo bench --bench coordinator_contention`. Set
+//! `AMPLIFIER_BENCH_ITERATIONS` to change the per-worker iteration count.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use amplifier_core::coordinator::Coordinator;
+use amplifier_core::testing::EchoTool;
+use amplifier_core::traits::Tool;
+use serde_json::json;
+
+const TOOLS: usize = 64;
+const WORKERS: usize = 8;
+const DEFAULT_ITERATIONS: usize = 20_000;
+
+type ToolMap = HashMap<String, Arc<dyn Tool>>;
+
+/// Something the benchmark can dispatch tools through.
+trait ToolSource: Send + Sync + 'static {
+    fn snapshot_and_get(&self, name: &str) -> Option<Arc<dyn Tool>>;
+    fn mount(&self, name: &str, tool: Arc<dyn Tool>);
+    fn unmount(&self, name: &str);
+}
+
+/// Baseline: one mutex, whole-map clone per snapshot.
+#[derive(Default)]
+struct MutexRegistry {
+    tools: Mutex<ToolMap>,
+}
+
+impl ToolSource for MutexRegistry {
+    fn snapshot_and_get(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        let tools = self.tools.lock().unwrap().clone();
+        tools.get(name).cloned()
+    }
+
+    fn mount(&self, name: &str, tool: Arc<dyn Tool>) {
+        self.tools.lock().unwrap().insert(name.to_string(), tool);
+    }
+
+    fn unmount(&self, name: &str) {
+        self.tools.lock().unwrap().remove(name);
+    }
+}
+
+impl ToolSource for Coordinator {
+    fn snapshot_and_get(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.tools().get(name).cloned()
+    }
+
+    fn mount(&self, name: &str, tool: Arc<dyn Tool>) {
+        self.mount_tool(name, tool);
+    }
+
+    fn unmount(&self, name: &str) {
+        self.unmount_tool(name);
+    }
+}
+
+fn tool_name(i: usize) -> String {
+    format!("tool-{i:03}")
+}
+
+fn populate(source: &dyn ToolSource) {
+    for i in 0..TOOLS {
+        let name = tool_name(i);
+        source.mount(&name, Arc::new(EchoTool));
+    }
+}
+
+/// Run the dispatch workload and return its wall-clock duration.
+async fn dispatch<S: ToolSource>(source: Arc<S>, iterations: usize, with_writer: bool) -> Duration {
+    let stop = Arc::new(AtomicBool::new(false));
+    let writer = with_writer.then(|| {
+        let source = Arc::clone(&source);
+        let stop = Arc::clone(&stop);
+        tokio::spawn(async move {
+            let extra: Arc<dyn Tool> = Arc::new(EchoTool);
+            while !stop.load(Ordering::Relaxed) {
+                source.mount("extra", Arc::clone(&extra));
+                source.unmount("extra");
+                tokio::task::yield_now().await;
+            }
+        })
+    });
+
+    let started = Instant::now();
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|worker| {
+            let source = Arc::clone(&source);
+            tokio::spawn(async move {
+                for i in 0..iterations {
+                    let name = tool_name((worker + i) % TOOLS);
+                    let tool = source.snapshot_and_get(&name).expect("tool is mounted");
+                    tool.execute(json!({"i": i})).await.expect("tool succeeds");
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.await.unwrap();
+    }
+    let elapsed = started.elapsed();
+
+    stop.store(true, Ordering::Relaxed);
+    if let Some(writer) = writer {
+        writer.await.unwrap();
+    }
+    elapsed
+}
+
+fn report(label: &str, iterations: usize, elapsed: Duration) {
+    let calls = (iterations * WORKERS) as f64;
+    println!(
+        "{label:<34} {:>10.1} ms {:>12.0} calls/s",
+        elapsed.as_secs_f64() * 1000.0,
+        calls / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    let iterations = std::env::var("AMPLIFIER_BENCH_ITERATIONS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS);
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(WORKERS)
+        .build()
+        .unwrap();
+
+    println!("{WORKERS} workers x {iterations} dispatches, {TOOLS} tools mounted");
+    runtime.block_on(async {
+        for with_writer in [false, true] {
+            let suffix = if with_writer { " + writer" } else { "" };
+
+            let baseline = Arc::new(MutexRegistry::default());
+            populate(baseline.as_ref());
+            let elapsed = dispatch(baseline, iterations, with_writer).await;
+            report(&format!("mutex + clone{suffix}"), iterations, elapsed);
+
+            let coordinator = Arc::new(Coordinator::new_for_test());
+            populate(coordinator.as_ref());
+            let elapsed = dispatch(coordinator, iterations, with_writer).await;
+            report(
+                &format!("coordinator snapshot{suffix}"),
+                iterations,
+                elapsed,
+            );
+        }
+    });
+}
@@ -0,0 +1,468 @@
+//! ApprovalGate — the kernel's approval wait loop.
+//!
+//! Waits on an [`ApprovalProvider`] while watching two other ways a pending
+//! approval can end:
+//!
+//! | Ends by                       | Outcome                        | Event                 |
+//! |-------------------------------|--------------------------------|-----------------------|
+//! | Provider answers              | [`ApprovalOutcome::Responded`] | `approval:granted` / `approval:denied` |
+//! | `request.timeout` elapses     | [`ApprovalOutcome::TimedOut`]  | `approval:granted` / `approval:denied` |
+//! | A remembered decision applies | [`ApprovalOutcome::Remembered`] | `approval:granted` / `approval:denied` |
+//! | Graceful/immediate cancel     | [`ApprovalOutcome::Cancelled`] | `approval:cancelled`  |
+//!
+//! Timeouts and cancellations resolve with the gate's configured
+//! [`ApprovalDefault`], except that cancellation never approves unless
+//! [`CancelResolution::UseDefault`] is chosen explicitly. The provider future
+//! is dropped when the wait ends, which unblocks the pipeline.
+//!
+//! # Connections
+//!
+//! - Reads the [`CancellationToken`], [`HookRegistry`] and [`Clock`] from the
+//!   [`Coordinator`].
+//! - Emits `approval:required` before waiting, and shows the request on the
+//!   coordinator's [`DisplayProvider`], if any.
+//! - Answers from, and records `remember` decisions in, the coordinator's
+//!   [`ApprovalMemory`] (see [`crate::approval_memory`]). A remembered
+//!   decision skips the provider, `approval:required` and the display.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::approval_memory::ApprovalMemory;
+use crate::cancellation::{CancellationState, CancellationToken};
+use crate::clock::{self, Clock};
+use crate::coordinator::Coordinator;
+use crate::errors::AmplifierError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::models::{ApprovalDefault, ApprovalRequest, ApprovalResponse};
+use crate::orchestrator_status::{self, OrchestratorStatus};
+use crate::traits::{ApprovalProvider, DisplayProvider};
+
+/// How a cancellation during a pending approval is resolved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CancelResolution {
+    /// Always deny (the default: a cancelled session should not act).
+    #[default]
+    Deny,
+    /// Resolve with the gate's [`ApprovalDefault`], as a timeout would.
+    UseDefault,
+}
+
+/// How a pending approval ended.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApprovalOutcome {
+    /// The provider answered.
+    Responded(ApprovalResponse),
+    /// The request's timeout elapsed; resolved with the default.
+    TimedOut(ApprovalResponse),
+    /// Cancellation was requested while waiting.
+    Cancelled(ApprovalResponse),
+    /// A remembered decision applied; the provider was not asked.
+    Remembered(ApprovalResponse),
+}
+
+impl ApprovalOutcome {
+    /// The effective response, however the wait ended.
+    pub fn response(&self) -> &ApprovalResponse {
+        match self {
+            Self::Responded(r) | Self::TimedOut(r) | Self::Cancelled(r) | Self::Remembered(r) => r,
+        }
+    }
+
+    /// Whether the action may proceed.
+    pub fn approved(&self) -> bool {
+        self.response().approved
+    }
+}
+
+/// Runs approval requests with timeout and cancellation handling.
+#[derive(Clone)]
+pub struct ApprovalGate {
+    hooks: Arc<HookRegistry>,
+    cancellation: CancellationToken,
+    default: ApprovalDefault,
+    on_cancel: CancelResolution,
+    clock: Arc<dyn Clock>,
+    display: Option<Arc<dyn DisplayProvider>>,
+    memory: Option<Arc<ApprovalMemory>>,
+}
+
+impl ApprovalGate {
+    /// Create a gate using `hooks` for events and `cancellation` to abort waits.
+    pub fn new(hooks: Arc<HookRegistry>, cancellation: CancellationToken) -> Self {
+        Self {
+            hooks,
+            cancellation,
+            default: ApprovalDefault::default(),
+            on_cancel: CancelResolution::default(),
+            clock: clock::system(),
+            display: None,
+            memory: None,
+        }
+    }
+
+    /// Create a gate sharing the coordinator's hooks, cancellation token,
+    /// clock, display provider and approval memory.
+    pub fn from_coordinator(coordinator: &Coordinator) -> Self {
+        Self::new(
+            coordinator.hooks_shared(),
+            coordinator.cancellation().clone(),
+        )
+        .with_clock(coordinator.clock())
+        .with_display(coordinator.display_provider())
+        .with_memory(Some(coordinator.approval_memory()))
+    }
+
+    /// Decision used when the request times out (default: deny).
+    pub fn with_default(mut self, default: ApprovalDefault) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// How cancellation resolves a pending approval (default: deny).
+    pub fn with_cancel_resolution(mut self, on_cancel: CancelResolution) -> Self {
+        self.on_cancel = on_cancel;
+        self
+    }
+
+    /// Clock that times `request.timeout` (default: the system clock).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Show pending requests on `display`.
+    pub fn with_display(mut self, display: Option<Arc<dyn DisplayProvider>>) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Answer from and record remembered decisions in `memory`.
+    pub fn with_memory(mut self, memory: Option<Arc<ApprovalMemory>>) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    /// Ask `provider` for approval, bounded by `request.timeout` and by
+    /// cancellation of the session.
+    ///
+    /// If the session is already cancelled, or a remembered decision
+    /// applies, the provider is not consulted.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by the provider itself.
+    pub async fn request(
+        &self,
+        provider: &dyn ApprovalProvider,
+        request: ApprovalRequest,
+    ) -> Result<ApprovalOutcome, AmplifierError> {
+        let tool_name = request.tool_name.clone();
+        if self.cancellation.is_cancelled() {
+            return Ok(self.resolve_cancelled(&tool_name).await);
+        }
+        if let Some(response) = self.memory.as_ref().and_then(|m| m.recall(&request)) {
+            let outcome = ApprovalOutcome::Remembered(response);
+            self.emit_decision(&tool_name, &outcome).await;
+            return Ok(outcome);
+        }
+        let remembered = self.memory.as_ref().map(|memory| (memory, request.clone()));
+
+        self.hooks
+            .emit(
+                events::APPROVAL_REQUIRED,
+                serde_json::json!({
+                    "tool_name": tool_name,
+                    "action": request.action,
+                    "risk_level": request.risk_level,
+                    "timeout": request.timeout,
+                }),
+            )
+            .await;
+        orchestrator_status::report(OrchestratorStatus::WaitingApproval {
+            tool_name: Some(tool_name.clone()),
+        });
+        if let Some(display) = &self.display {
+            if let Err(e) = display.show_approval_prompt(&request).await {
+                log::warn!("Failed to display approval prompt for '{tool_name}': {e}");
+            }
+        }
+
+        let timeout = request
+            .timeout
+            .filter(|t| t.is_finite() && *t > 0.0)
+            .map(Duration::from_secs_f64);
+        let timer = async {
+            match timeout {
+                Some(t) => self.clock.sleep(t).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        let outcome = tokio::select! {
+            response = provider.request_approval(request) => ApprovalOutcome::Responded(response?),
+            _ = timer => ApprovalOutcome::TimedOut(self.default_response("approval timed out")),
+            _ = self.cancellation.cancelled() => return Ok(self.resolve_cancelled(&tool_name).await),
+        };
+
+        if let (ApprovalOutcome::Responded(response), Some((memory, request))) =
+            (&outcome, remembered)
+        {
+            memory.remember(&request, response).await;
+        }
+        self.emit_decision(&tool_name, &outcome).await;
+        Ok(outcome)
+    }
+
+    async fn emit_decision(&self, tool_name: &str, outcome: &ApprovalOutcome) {
+        let event = if outcome.approved() {
+            events::APPROVAL_GRANTED
+        } else {
+            events::APPROVAL_DENIED
+        };
+        self.hooks
+            .emit(
+                event,
+                serde_json::json!({
+                    "tool_name": tool_name,
+                    "reason": outcome.response().reason,
+                    "timed_out": matches!(outcome, ApprovalOutcome::TimedOut(_)),
+                    "remembered": matches!(outcome, ApprovalOutcome::Remembered(_)),
+                }),
+            )
+            .await;
+    }
+
+    fn default_response(&self, reason: &str) -> ApprovalResponse {
+        ApprovalResponse {
+            approved: self.default == ApprovalDefault::Allow,
+            reason: Some(reason.to_string()),
+            remember: false,
+        }
+    }
+
+    async fn resolve_cancelled(&self, tool_name: &str) -> ApprovalOutcome {
+        let response = match self.on_cancel {
+            CancelResolution::Deny => ApprovalResponse {
+                approved: false,
+                reason: Some("cancelled".into()),
+                remember: false,
+            },
+            CancelResolution::UseDefault => self.default_response("cancelled"),
+        };
+        let cancellation = match self.cancellation.state() {
+            CancellationState::Immediate => "immediate",
+            _ => "graceful",
+        };
+        self.hooks
+            .emit(
+                events::APPROVAL_CANCELLED,
+                serde_json::json!({
+                    "tool_name": tool_name,
+                    "approved": response.approved,
+                    "cancellation": cancellation,
+                }),
+            )
+            .await;
+        ApprovalOutcome::Cancelled(response)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    use crate::testing::{FakeApprovalProvider, FakeHookHandler, ManualClock};
+
+    /// A provider that never answers (a user who walked away).
+    struct PendingApprovalProvider;
+
+    impl ApprovalProvider for PendingApprovalProvider {
+        fn request_approval(
+            &self,
+            _request: ApprovalRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<ApprovalResponse, AmplifierError>> + Send + '_>>
+        {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    fn request(timeout: Option<f64>) -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "bash".into(),
+            action: "rm -rf build".into(),
+            details: HashMap::new(),
+            risk_level: "high".into(),
+            timeout,
+        }
+    }
+
+    fn gate_with_recorder() -> (ApprovalGate, Arc<FakeHookHandler>, CancellationToken) {
+        let hooks = Arc::new(HookRegistry::new());
+        let recorder = Arc::new(FakeHookHandler::new());
+        for event in [
+            events::APPROVAL_REQUIRED,
+            events::APPROVAL_GRANTED,
+            events::APPROVAL_DENIED,
+            events::APPROVAL_CANCELLED,
+        ] {
+            let _ = hooks.register(event, recorder.clone(), 0, None);
+        }
+        let token = CancellationToken::new();
+        (ApprovalGate::new(hooks, token.clone()), recorder, token)
+    }
+
+    fn event_names(recorder: &FakeHookHandler) -> Vec<String> {
+        recorder
+            .recorded_events()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn provider_response_is_returned() {
+        let (gate, recorder, _) = gate_with_recorder();
+        let outcome = gate
+            .request(&FakeApprovalProvider::approving(), request(None))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, ApprovalOutcome::Responded(_)));
+        assert!(outcome.approved());
+        assert_eq!(
+            event_names(&recorder),
+            vec!["approval:required", "approval:granted"]
+        );
+    }
+
+    #[tokio::test]
+    async fn remembered_decisions_skip_the_provider() {
+        let (gate, recorder, _) = gate_with_recorder();
+        let gate = gate.with_memory(Some(Arc::new(ApprovalMemory::default())));
+        let provider = FakeApprovalProvider::approving().remembering();
+
+        let first = gate.request(&provider, request(None)).await.unwrap();
+        assert!(matches!(first, ApprovalOutcome::Responded(_)));
+        let second = gate.request(&provider, request(None)).await.unwrap();
+        assert!(matches!(second, ApprovalOutcome::Remembered(ref r) if r.approved));
+        assert_eq!(provider.calls(), 1);
+        assert_eq!(
+            event_names(&recorder),
+            vec!["approval:required", "approval:granted", "approval:granted"]
+        );
+        assert_eq!(recorder.recorded_events()[2].1["remembered"], true);
+    }
+
+    #[tokio::test]
+    async fn pending_requests_are_shown_on_the_display() {
+        use crate::display::{ChannelDisplay, DisplayEvent};
+
+        let (gate, _, _) = gate_with_recorder();
+        let (display, mut shown) = ChannelDisplay::new();
+        gate.with_display(Some(Arc::new(display)))
+            .request(&FakeApprovalProvider::approving(), request(None))
+            .await
+            .unwrap();
+        assert_eq!(
+            shown.try_recv().unwrap(),
+            DisplayEvent::ApprovalPrompt(request(None))
+        );
+    }
+
+    #[tokio::test]
+    async fn timeout_resolves_with_configured_default() {
+        let (gate, recorder, _) = gate_with_recorder();
+        let outcome = gate
+            .with_default(ApprovalDefault::Allow)
+            .request(&PendingApprovalProvider, request(Some(0.02)))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, ApprovalOutcome::TimedOut(_)));
+        assert!(outcome.approved());
+        assert_eq!(recorder.recorded_events()[1].1["timed_out"], true);
+    }
+
+    #[tokio::test]
+    async fn timeout_follows_gate_clock() {
+        let (gate, recorder, _) = gate_with_recorder();
+        let clock = ManualClock::default();
+        let gate = gate.with_clock(Arc::new(clock.clone()));
+
+        let advance = async {
+            while recorder.recorded_events().is_empty() {
+                tokio::task::yield_now().await;
+            }
+            clock.advance(Duration::from_secs(59));
+            tokio::task::yield_now().await;
+            assert_eq!(recorder.recorded_events().len(), 1, "not yet timed out");
+            clock.advance(Duration::from_secs(1));
+        };
+        let (outcome, ()) = tokio::join!(
+            gate.request(&PendingApprovalProvider, request(Some(60.0))),
+            advance
+        );
+
+        assert!(matches!(outcome.unwrap(), ApprovalOutcome::TimedOut(_)));
+        assert_eq!(recorder.recorded_events()[1].1["timed_out"], true);
+    }
+
+    #[tokio::test]
+    async fn cancellation_unblocks_pending_approval() {
+        let (gate, recorder, token) = gate_with_recorder();
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            token.request_immediate();
+        });
+
+        let outcome = tokio::time::timeout(
+            Duration::from_secs(5),
+            gate.with_default(ApprovalDefault::Allow)
+                .request(&PendingApprovalProvider, request(None)),
+        )
+        .await
+        .expect("cancellation should unblock the wait")
+        .unwrap();
+        canceller.await.unwrap();
+
+        // Cancellation denies even when the timeout default is Allow.
+        assert!(matches!(outcome, ApprovalOutcome::Cancelled(_)));
+        assert!(!outcome.approved());
+        let events = recorder.recorded_events();
+        assert_eq!(events.last().unwrap().0, "approval:cancelled");
+        assert_eq!(events.last().unwrap().1["cancellation"], "immediate");
+    }
+
+    #[tokio::test]
+    async fn cancel_resolution_can_use_default() {
+        let (gate, _, token) = gate_with_recorder();
+        token.request_graceful();
+        let outcome = gate
+            .with_default(ApprovalDefault::Allow)
+            .with_cancel_resolution(CancelResolution::UseDefault)
+            .request(&PendingApprovalProvider, request(None))
+            .await
+            .unwrap();
+        assert!(matches!(outcome, ApprovalOutcome::Cancelled(_)));
+        assert!(outcome.approved());
+    }
+
+    #[tokio::test]
+    async fn already_cancelled_skips_provider() {
+        let (gate, recorder, token) = gate_with_recorder();
+        token.request_graceful();
+        let outcome = gate
+            .request(&FakeApprovalProvider::approving(), request(None))
+            .await
+            .unwrap();
+        assert!(!outcome.approved());
+        assert_eq!(event_names(&recorder), vec!["approval:cancelled"]);
+    }
+}
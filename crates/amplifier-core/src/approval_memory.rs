@@ -0,0 +1,536 @@
+//! Remembered approval decisions.
+//!
+//! An [`ApprovalResponse`] with `remember: true` asks for the decision to
+//! apply to similar requests from then on. The coordinator's
+//! [`ApprovalMemory`] stores such decisions and
+//! [`ApprovalGate`](crate::approval::ApprovalGate) consults it before asking
+//! the provider, so the user is not asked the same question twice:
+//!
+//! | Scope   | A remembered decision covers                                     |
+//! |---------|------------------------------------------------------------------|
+//! | `exact` | requests for the same tool with the same action and details      |
+//! | `tool`  | every request for the same tool                                  |
+//!
+//! Requests match by [`request_hash`] (the tool's action and details), so
+//! `exact` decisions survive reordered detail keys. When both an exact and a
+//! tool-wide decision exist, the exact one wins.
+//!
+//! Decisions last for the session. With an [`ApprovalMemoryStore`] attached
+//! (see [`ApprovalMemory::attach_store`]) they are loaded from it and every
+//! new decision is saved back, so they carry over to later sessions sharing
+//! the store. [`FileApprovalStore`] keeps them in one JSON file.
+//!
+//! Remembered decisions are charged to the coordinator's
+//! [`MemoryAccountant`] under `"approval_memory"`. A decision that would
+//! exceed the memory ceiling is not remembered (the user is asked again).
+//!
+//! Settings come from `session.approval_memory`:
+//!
+//! ```json
+//! {"session": {"approval_memory": {"scope": "tool"}}}
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::memory::MemoryAccountant;
+use crate::models::{ApprovalRequest, ApprovalResponse};
+
+// ---------------------------------------------------------------------------
+// Config
+// ---------------------------------------------------------------------------
+
+/// Which requests a remembered decision covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RememberScope {
+    /// The same tool, action and details.
+    #[default]
+    Exact,
+    /// Any request for the same tool.
+    Tool,
+}
+
+/// The `session.approval_memory` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApprovalMemoryConfig {
+    /// Honour `remember` at all. When off, nothing is stored or recalled.
+    pub enabled: bool,
+    /// What a remembered decision covers.
+    pub scope: RememberScope,
+}
+
+impl Default for ApprovalMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            scope: RememberScope::default(),
+        }
+    }
+}
+
+impl ApprovalMemoryConfig {
+    /// Read `session.approval_memory` from a mount plan, falling back to
+    /// the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("approval_memory")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.approval_memory config: {e}");
+            Self::default()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Decisions and stores
+// ---------------------------------------------------------------------------
+
+/// One remembered decision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RememberedDecision {
+    pub tool_name: String,
+    /// [`request_hash`] of the request it covers; `None` covers every
+    /// request for the tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_hash: Option<String>,
+    pub approved: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// A remembered-decision store failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ApprovalMemoryError {
+    #[error("approval store: {message}")]
+    Storage { message: String },
+}
+
+fn storage_error(message: impl Into<String>) -> ApprovalMemoryError {
+    ApprovalMemoryError::Storage {
+        message: message.into(),
+    }
+}
+
+/// Durable storage for remembered decisions.
+pub trait ApprovalMemoryStore: Send + Sync {
+    /// Every stored decision.
+    fn load(
+        &self,
+    ) -> Pin<
+        Box<dyn Future<Output = Result<Vec<RememberedDecision>, ApprovalMemoryError>> + Send + '_>,
+    >;
+
+    /// Replace the stored decisions with `decisions`.
+    fn save(
+        &self,
+        decisions: Vec<RememberedDecision>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ApprovalMemoryError>> + Send + '_>>;
+}
+
+/// Decisions kept as a JSON array in one file.
+///
+/// Saves write a temporary file and rename it over the original. A missing
+/// file loads as no decisions.
+pub struct FileApprovalStore {
+    path: PathBuf,
+    /// Serializes writers within this process.
+    write_lock: Mutex<()>,
+}
+
+impl FileApprovalStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Vec<RememberedDecision>, ApprovalMemoryError> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(storage_error(format!("read {}: {e}", self.path.display()))),
+        };
+        serde_json::from_str(&text)
+            .map_err(|e| storage_error(format!("{}: {e}", self.path.display())))
+    }
+
+    fn write(&self, decisions: &[RememberedDecision]) -> Result<(), ApprovalMemoryError> {
+        let body =
+            serde_json::to_string_pretty(decisions).map_err(|e| storage_error(e.to_string()))?;
+        let tmp = self.path.with_extension("json.tmp");
+        let _guard = self.write_lock.lock().unwrap();
+        fs::write(&tmp, body)
+            .map_err(|e| storage_error(format!("write {}: {e}", tmp.display())))?;
+        fs::rename(&tmp, &self.path)
+            .map_err(|e| storage_error(format!("rename {}: {e}", self.path.display())))
+    }
+}
+
+impl ApprovalMemoryStore for FileApprovalStore {
+    fn load(
+        &self,
+    ) -> Pin<
+        Box<dyn Future<Output = Result<Vec<RememberedDecision>, ApprovalMemoryError>> + Send + '_>,
+    > {
+        Box::pin(async move { self.read() })
+    }
+
+    fn save(
+        &self,
+        decisions: Vec<RememberedDecision>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ApprovalMemoryError>> + Send + '_>> {
+        Box::pin(async move { self.write(&decisions) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ApprovalMemory
+// ---------------------------------------------------------------------------
+
+/// Identity of an approval request: a hash of its tool, action and details.
+pub fn request_hash(request: &ApprovalRequest) -> String {
+    // JSON objects serialize with sorted keys, so detail order does not matter.
+    let canonical = serde_json::json!({
+        "tool_name": request.tool_name,
+        "action": request.action,
+        "details": request.details,
+    });
+    format!("{:x}", Sha256::digest(canonical.to_string().as_bytes()))
+}
+
+type DecisionKey = (String, Option<String>);
+
+/// Accounting category for remembered decisions.
+const MEMORY_CATEGORY: &str = "approval_memory";
+
+/// Decisions by key, with their sizes charged to a [`MemoryAccountant`].
+struct Decisions {
+    entries: HashMap<DecisionKey, (RememberedDecision, usize)>,
+    memory: Arc<MemoryAccountant>,
+}
+
+impl Decisions {
+    fn new(memory: Arc<MemoryAccountant>) -> Self {
+        Self {
+            entries: HashMap::new(),
+            memory,
+        }
+    }
+
+    /// Insert `decision`, replacing one with the same key. Returns `false`,
+    /// keeping the old one, if the memory ceiling rejects it.
+    fn insert(&mut self, decision: RememberedDecision) -> bool {
+        let size = serde_json::to_vec(&decision).map_or(0, |v| v.len());
+        if !self.memory.try_charge(MEMORY_CATEGORY, size) {
+            return false;
+        }
+        if let Some((_, old)) = self.entries.insert(key_of(&decision), (decision, size)) {
+            self.memory.release(MEMORY_CATEGORY, old);
+        }
+        true
+    }
+
+    fn get(&self, key: &DecisionKey) -> Option<&RememberedDecision> {
+        self.entries.get(key).map(|(decision, _)| decision)
+    }
+
+    fn values(&self) -> impl Iterator<Item = &RememberedDecision> {
+        self.entries.values().map(|(decision, _)| decision)
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&DecisionKey) -> bool) {
+        let memory = &self.memory;
+        self.entries.retain(|key, (_, size)| {
+            let kept = keep(key);
+            if !kept {
+                memory.release(MEMORY_CATEGORY, *size);
+            }
+            kept
+        });
+    }
+}
+
+impl Drop for Decisions {
+    fn drop(&mut self) {
+        self.retain(|_| false);
+    }
+}
+
+/// The session's remembered approval decisions; see the [module docs](self).
+pub struct ApprovalMemory {
+    config: ApprovalMemoryConfig,
+    decisions: Mutex<Decisions>,
+    store: RwLock<Option<Arc<dyn ApprovalMemoryStore>>>,
+}
+
+impl ApprovalMemory {
+    pub fn new(config: ApprovalMemoryConfig) -> Self {
+        Self {
+            config,
+            decisions: Mutex::new(Decisions::new(Arc::new(MemoryAccountant::default()))),
+            store: RwLock::new(None),
+        }
+    }
+
+    /// Charge remembered decisions to `memory` (typically
+    /// [`Coordinator::memory`](crate::coordinator::Coordinator::memory)).
+    pub fn with_memory(self, memory: Arc<MemoryAccountant>) -> Self {
+        let mut decisions = Decisions::new(memory);
+        for decision in self.decisions() {
+            decisions.insert(decision);
+        }
+        *self.decisions.lock().unwrap() = decisions;
+        self
+    }
+
+    pub fn config(&self) -> &ApprovalMemoryConfig {
+        &self.config
+    }
+
+    /// Load the decisions in `store` and save new ones to it from now on.
+    ///
+    /// Loaded decisions replace remembered ones with the same key; ones the
+    /// memory ceiling rejects are skipped. Returns how many were loaded.
+    ///
+    /// # Errors
+    ///
+    /// The store's load error; the store is not attached then.
+    pub async fn attach_store(
+        &self,
+        store: Arc<dyn ApprovalMemoryStore>,
+    ) -> Result<usize, ApprovalMemoryError> {
+        let loaded = store.load().await?;
+        let count = loaded.len();
+        {
+            let mut decisions = self.decisions.lock().unwrap();
+            for decision in loaded {
+                decisions.insert(decision);
+            }
+        }
+        *self.store.write().unwrap() = Some(store);
+        Ok(count)
+    }
+
+    /// The remembered response to `request`, if any.
+    pub fn recall(&self, request: &ApprovalRequest) -> Option<ApprovalResponse> {
+        if !self.config.enabled {
+            return None;
+        }
+        let decisions = self.decisions.lock().unwrap();
+        let tool = request.tool_name.clone();
+        let decision = decisions
+            .get(&(tool.clone(), Some(request_hash(request))))
+            .or_else(|| decisions.get(&(tool, None)))?;
+        Some(ApprovalResponse {
+            approved: decision.approved,
+            reason: decision.reason.clone(),
+            remember: true,
+        })
+    }
+
+    /// Remember `response` to `request` if it asks to be remembered.
+    ///
+    /// Saves to the attached store, if any; a failed save is logged and the
+    /// decision is still remembered for the session. Returns whether the
+    /// decision was remembered; a decision over the memory ceiling is not.
+    pub async fn remember(&self, request: &ApprovalRequest, response: &ApprovalResponse) -> bool {
+        if !self.config.enabled || !response.remember {
+            return false;
+        }
+        let decision = RememberedDecision {
+            tool_name: request.tool_name.clone(),
+            request_hash: match self.config.scope {
+                RememberScope::Exact => Some(request_hash(request)),
+                RememberScope::Tool => None,
+            },
+            approved: response.approved,
+            reason: response.reason.clone(),
+        };
+        if !self.decisions.lock().unwrap().insert(decision) {
+            log::warn!(
+                "Approval decision for {} not remembered: memory ceiling reached",
+                request.tool_name
+            );
+            return false;
+        }
+        self.persist().await;
+        true
+    }
+
+    /// Forget every decision for `tool_name`.
+    pub async fn forget(&self, tool_name: &str) {
+        self.decisions
+            .lock()
+            .unwrap()
+            .retain(|(tool, _)| tool != tool_name);
+        self.persist().await;
+    }
+
+    /// Forget every decision.
+    pub async fn clear(&self) {
+        self.decisions.lock().unwrap().retain(|_| false);
+        self.persist().await;
+    }
+
+    /// Every remembered decision, sorted by tool.
+    pub fn decisions(&self) -> Vec<RememberedDecision> {
+        let mut decisions: Vec<_> = self.decisions.lock().unwrap().values().cloned().collect();
+        decisions
+            .sort_by(|a, b| (&a.tool_name, &a.request_hash).cmp(&(&b.tool_name, &b.request_hash)));
+        decisions
+    }
+
+    async fn persist(&self) {
+        let Some(store) = self.store.read().unwrap().clone() else {
+            return;
+        };
+        if let Err(e) = store.save(self.decisions()).await {
+            log::warn!("Failed to save remembered approvals: {e}");
+        }
+    }
+}
+
+impl Default for ApprovalMemory {
+    fn default() -> Self {
+        Self::new(ApprovalMemoryConfig::default())
+    }
+}
+
+impl fmt::Debug for ApprovalMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApprovalMemory")
+            .field("config", &self.config)
+            .field("decisions", &self.decisions.lock().unwrap().entries.len())
+            .field("store", &self.store.read().unwrap().is_some())
+            .finish()
+    }
+}
+
+fn key_of(decision: &RememberedDecision) -> DecisionKey {
+    (decision.tool_name.clone(), decision.request_hash.clone())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(command: &str) -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "bash".into(),
+            action: format!("run {command}"),
+            details: HashMap::from([
+                ("command".to_string(), json!(command)),
+                ("cwd".to_string(), json!("/work")),
+            ]),
+            risk_level: "high".into(),
+            timeout: None,
+        }
+    }
+
+    fn response(approved: bool, remember: bool) -> ApprovalResponse {
+        ApprovalResponse {
+            approved,
+            reason: Some("user".into()),
+            remember,
+        }
+    }
+
+    #[tokio::test]
+    async fn exact_decisions_cover_only_the_same_request() {
+        let memory = ApprovalMemory::default();
+        assert!(
+            !memory
+                .remember(&request("ls"), &response(true, false))
+                .await
+        );
+        assert!(memory.recall(&request("ls")).is_none());
+
+        assert!(memory.remember(&request("ls"), &response(true, true)).await);
+        assert!(memory.recall(&request("ls")).unwrap().approved);
+        assert!(memory.recall(&request("rm -rf /")).is_none());
+
+        // A tool-wide decision covers the rest; the exact one still wins.
+        let tool_wide = ApprovalMemory::new(ApprovalMemoryConfig {
+            scope: RememberScope::Tool,
+            ..Default::default()
+        });
+        tool_wide
+            .remember(&request("rm -rf /"), &response(false, true))
+            .await;
+        assert!(!tool_wide.recall(&request("anything")).unwrap().approved);
+        memory.forget("bash").await;
+        assert!(memory.decisions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn decisions_are_charged_to_the_memory_accountant() {
+        use crate::memory::MemoryConfig;
+
+        let accountant = Arc::new(MemoryAccountant::new(MemoryConfig {
+            ceiling_bytes: Some(200),
+            ..Default::default()
+        }));
+        let memory = ApprovalMemory::default().with_memory(accountant.clone());
+        assert!(memory.remember(&request("ls"), &response(true, true)).await);
+        let charged = accountant.usage().by_category["approval_memory"];
+        assert!(charged > 0);
+
+        // A second decision does not fit under the ceiling.
+        assert!(
+            !memory
+                .remember(&request("pwd"), &response(true, true))
+                .await
+        );
+        assert!(memory.recall(&request("pwd")).is_none());
+        assert_eq!(accountant.usage().rejections, 1);
+
+        memory.clear().await;
+        assert_eq!(accountant.used_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn decisions_persist_through_the_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("approvals.json");
+
+        let memory = ApprovalMemory::default();
+        assert_eq!(
+            memory
+                .attach_store(Arc::new(FileApprovalStore::new(&path)))
+                .await,
+            Ok(0)
+        );
+        memory.remember(&request("ls"), &response(true, true)).await;
+
+        let next_session = ApprovalMemory::default();
+        assert_eq!(
+            next_session
+                .attach_store(Arc::new(FileApprovalStore::new(&path)))
+                .await,
+            Ok(1)
+        );
+        assert!(next_session.recall(&request("ls")).unwrap().approved);
+    }
+}
@@ -0,0 +1,740 @@
+//! AttachmentStore — content-addressed storage for large tool outputs.
+//!
+//! Big [`ToolResult`] outputs (file contents, images, long listings) bloat
+//! both the context and every hook payload that carries them. The
+//! [`AttachmentStore`] moves such outputs out of line: the bytes are stored
+//! under their SHA-256 hash and the output is replaced by a small reference
+//! object naming an `attachment://<sha256>` URI:
+//!
+//! ```json
+//! {"type": "attachment", "uri": "attachment://9f86…", "media_type": "text/plain; charset=utf-8",
+//!  "size": 48213, "preview": "first 200 characters…"}
+//! ```
+//!
+//! Providers and context managers that need the real content call
+//! [`AttachmentStore::rehydrate`] on a message (or message list) before
+//! sending it, or [`AttachmentStore::get`] for raw bytes. Identical outputs
+//! share one stored copy.
+//!
+//! # Backends
+//!
+//! | Backend                       | Storage                                    |
+//! |-------------------------------|--------------------------------------------|
+//! | [`InMemoryAttachmentBackend`] | process-local map (tests, ephemeral runs), memory-accounted via [`crate::memory`] |
+//! | [`FileAttachmentBackend`]     | one file per attachment under a directory  |
+//!
+//! Other backends (object storage, a database) implement
+//! [`AttachmentBackend`].
+//!
+//! # Connections
+//!
+//! - Set on the [`Coordinator`](crate::coordinator::Coordinator) with
+//!   `set_attachment_store`, or from `session.attachments` by
+//!   [`Session::new`](crate::session::Session::new).
+//! - [`ToolExecutor`](crate::tool_executor::ToolExecutor) offloads results
+//!   above the store's threshold automatically.
+//! - A [`HookDataLimit`](crate::hooks::spill::HookDataLimit) spills
+//!   oversized hook result data into a store.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::errors::ContextError;
+use crate::memory::MemoryAccountant;
+use crate::models::ToolResult;
+
+/// URI scheme prefix of attachment references.
+pub const ATTACHMENT_SCHEME: &str = "attachment://";
+
+/// Outputs larger than this many bytes are offloaded by default.
+pub const DEFAULT_THRESHOLD_BYTES: usize = 16 * 1024;
+
+/// Characters of the original output kept as a preview in the reference.
+const PREVIEW_CHARS: usize = 200;
+
+const TEXT_MEDIA_TYPE: &str = "text/plain; charset=utf-8";
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+fn storage_error(message: impl Into<String>) -> ContextError {
+    ContextError::Storage {
+        message: message.into(),
+    }
+}
+
+/// The attachment ID (lowercase hex SHA-256) named by `uri`, if it is a
+/// well-formed `attachment://` URI.
+pub fn parse_uri(uri: &str) -> Option<&str> {
+    let id = uri.strip_prefix(ATTACHMENT_SCHEME)?;
+    let valid = id.len() == 64
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    valid.then_some(id)
+}
+
+/// Stored attachment content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    pub media_type: String,
+    pub data: Vec<u8>,
+}
+
+/// The reference left in place of an offloaded output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "attachment")]
+pub struct AttachmentRef {
+    pub uri: String,
+    pub media_type: String,
+    /// Size of the stored content in bytes.
+    pub size: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+impl AttachmentRef {
+    /// Serialize as the JSON reference object.
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// Parse a JSON reference object; `None` for anything else.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        if value.get("type").and_then(Value::as_str) != Some("attachment") {
+            return None;
+        }
+        serde_json::from_value::<Self>(value.clone())
+            .ok()
+            .filter(|r| parse_uri(&r.uri).is_some())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AttachmentBackend
+// ---------------------------------------------------------------------------
+
+/// Storage for attachment content, keyed by attachment ID.
+///
+/// IDs are content hashes, so `put` of an existing ID may be skipped.
+pub trait AttachmentBackend: Send + Sync {
+    /// Store `attachment` under `id`.
+    fn put(
+        &self,
+        id: &str,
+        attachment: Attachment,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>>;
+
+    /// Load the attachment stored under `id`, if any.
+    fn get(
+        &self,
+        id: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Attachment>, ContextError>> + Send + '_>>;
+
+    /// Delete the attachment stored under `id`. Returns whether it existed.
+    fn remove(
+        &self,
+        id: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, ContextError>> + Send + '_>>;
+}
+
+/// Accounting category for [`InMemoryAttachmentBackend`] content.
+const MEMORY_CATEGORY: &str = "attachments";
+
+/// Bytes an attachment is charged.
+fn charged_size(attachment: &Attachment) -> usize {
+    attachment.data.len() + attachment.media_type.len()
+}
+
+/// A process-local backend, charging its content to a [`MemoryAccountant`]
+/// under `"attachments"`.
+///
+/// Attachments are referenced by URI, so none is evicted to make room: a
+/// `put` that would exceed the memory ceiling fails instead.
+#[derive(Debug, Default)]
+pub struct InMemoryAttachmentBackend {
+    attachments: Mutex<HashMap<String, Attachment>>,
+    memory: Arc<MemoryAccountant>,
+}
+
+impl InMemoryAttachmentBackend {
+    /// Create an empty, unbounded backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty backend charging its content to `memory`
+    /// (typically [`Coordinator::memory`](crate::coordinator::Coordinator::memory)).
+    pub fn with_memory(memory: Arc<MemoryAccountant>) -> Self {
+        Self {
+            attachments: Mutex::new(HashMap::new()),
+            memory,
+        }
+    }
+
+    /// Number of stored attachments.
+    pub fn len(&self) -> usize {
+        self.attachments.lock().unwrap().len()
+    }
+
+    /// Whether nothing is stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl AttachmentBackend for InMemoryAttachmentBackend {
+    fn put(
+        &self,
+        id: &str,
+        attachment: Attachment,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let mut attachments = self.attachments.lock().unwrap();
+        let result = if attachments.contains_key(id) {
+            Ok(())
+        } else if self
+            .memory
+            .try_charge(MEMORY_CATEGORY, charged_size(&attachment))
+        {
+            attachments.insert(id.to_string(), attachment);
+            Ok(())
+        } else {
+            Err(storage_error(format!(
+                "attachment of {} bytes rejected by memory ceiling",
+                attachment.data.len()
+            )))
+        };
+        Box::pin(async move { result })
+    }
+
+    fn get(
+        &self,
+        id: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Attachment>, ContextError>> + Send + '_>> {
+        let attachment = self.attachments.lock().unwrap().get(id).cloned();
+        Box::pin(async move { Ok(attachment) })
+    }
+
+    fn remove(
+        &self,
+        id: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, ContextError>> + Send + '_>> {
+        let removed = self.attachments.lock().unwrap().remove(id);
+        if let Some(attachment) = &removed {
+            self.memory
+                .release(MEMORY_CATEGORY, charged_size(attachment));
+        }
+        let removed = removed.is_some();
+        Box::pin(async move { Ok(removed) })
+    }
+}
+
+impl Drop for InMemoryAttachmentBackend {
+    fn drop(&mut self) {
+        let bytes: usize = self
+            .attachments
+            .get_mut()
+            .unwrap()
+            .values()
+            .map(charged_size)
+            .sum();
+        self.memory.release(MEMORY_CATEGORY, bytes);
+    }
+}
+
+/// A backend storing each attachment as `<id>` (content) and
+/// `<id>.type` (media type) under a directory. Uses blocking `std::fs` I/O.
+#[derive(Debug)]
+pub struct FileAttachmentBackend {
+    root: PathBuf,
+}
+
+impl FileAttachmentBackend {
+    /// Open (creating if necessary) a backend rooted at `root`.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, ContextError> {
+        let root = root.into();
+        fs::create_dir_all(&root)
+            .map_err(|e| storage_error(format!("create {}: {e}", root.display())))?;
+        Ok(Self { root })
+    }
+
+    /// The directory holding the attachment files.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn paths(&self, id: &str) -> Result<(PathBuf, PathBuf), ContextError> {
+        if parse_uri(&format!("{ATTACHMENT_SCHEME}{id}")).is_none() {
+            return Err(storage_error(format!("invalid attachment id: {id:?}")));
+        }
+        Ok((self.root.join(id), self.root.join(format!("{id}.type"))))
+    }
+
+    fn put_sync(&self, id: &str, attachment: &Attachment) -> Result<(), ContextError> {
+        let (data_path, type_path) = self.paths(id)?;
+        if data_path.exists() {
+            return Ok(());
+        }
+        fs::write(&type_path, &attachment.media_type)
+            .and_then(|()| fs::write(&data_path, &attachment.data))
+            .map_err(|e| storage_error(format!("write {}: {e}", data_path.display())))
+    }
+
+    fn get_sync(&self, id: &str) -> Result<Option<Attachment>, ContextError> {
+        let (data_path, type_path) = self.paths(id)?;
+        let data = match fs::read(&data_path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(storage_error(format!("read {}: {e}", data_path.display()))),
+        };
+        let media_type = fs::read_to_string(&type_path)
+            .unwrap_or_else(|_| "application/octet-stream".to_string());
+        Ok(Some(Attachment { media_type, data }))
+    }
+
+    fn remove_sync(&self, id: &str) -> Result<bool, ContextError> {
+        let (data_path, type_path) = self.paths(id)?;
+        let _ = fs::remove_file(&type_path);
+        match fs::remove_file(&data_path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(storage_error(format!(
+                "remove {}: {e}",
+                data_path.display()
+            ))),
+        }
+    }
+}
+
+impl AttachmentBackend for FileAttachmentBackend {
+    fn put(
+        &self,
+        id: &str,
+        attachment: Attachment,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let result = self.put_sync(id, &attachment);
+        Box::pin(async move { result })
+    }
+
+    fn get(
+        &self,
+        id: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<Attachment>, ContextError>> + Send + '_>> {
+        let result = self.get_sync(id);
+        Box::pin(async move { result })
+    }
+
+    fn remove(
+        &self,
+        id: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<bool, ContextError>> + Send + '_>> {
+        let result = self.remove_sync(id);
+        Box::pin(async move { result })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AttachmentConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.attachments` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttachmentConfig {
+    /// Outputs larger than this are offloaded.
+    #[serde(default = "default_threshold_bytes")]
+    pub threshold_bytes: usize,
+    /// Store attachments as files here instead of in memory.
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+}
+
+fn default_threshold_bytes() -> usize {
+    DEFAULT_THRESHOLD_BYTES
+}
+
+impl Default for AttachmentConfig {
+    fn default() -> Self {
+        Self {
+            threshold_bytes: default_threshold_bytes(),
+            directory: None,
+        }
+    }
+}
+
+impl AttachmentConfig {
+    /// Read `session.attachments` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("attachments"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.attachments config: {e}"))
+            .ok()
+    }
+
+    /// Build the store this config describes. An in-memory store charges
+    /// `memory`.
+    ///
+    /// # Errors
+    ///
+    /// `ContextError::Storage` if the directory cannot be created.
+    pub fn build(&self, memory: Arc<MemoryAccountant>) -> Result<AttachmentStore, ContextError> {
+        let store = match &self.directory {
+            Some(dir) => AttachmentStore::new(Box::new(FileAttachmentBackend::open(dir)?)),
+            None => AttachmentStore::new(Box::new(InMemoryAttachmentBackend::with_memory(memory))),
+        };
+        Ok(store.with_threshold(self.threshold_bytes))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AttachmentStore
+// ---------------------------------------------------------------------------
+
+/// Content-addressed attachment storage over an [`AttachmentBackend`].
+pub struct AttachmentStore {
+    backend: Box<dyn AttachmentBackend>,
+    threshold_bytes: usize,
+}
+
+impl AttachmentStore {
+    /// A store over `backend` with the default threshold.
+    pub fn new(backend: Box<dyn AttachmentBackend>) -> Self {
+        Self {
+            backend,
+            threshold_bytes: DEFAULT_THRESHOLD_BYTES,
+        }
+    }
+
+    /// A store over a fresh [`InMemoryAttachmentBackend`].
+    pub fn in_memory() -> Self {
+        Self::new(Box::new(InMemoryAttachmentBackend::new()))
+    }
+
+    /// Offload outputs larger than `bytes`.
+    pub fn with_threshold(mut self, bytes: usize) -> Self {
+        self.threshold_bytes = bytes;
+        self
+    }
+
+    /// Outputs larger than this many bytes are offloaded.
+    pub fn threshold_bytes(&self) -> usize {
+        self.threshold_bytes
+    }
+
+    /// Store `data` and return its reference (with no preview).
+    ///
+    /// # Errors
+    ///
+    /// Any backend error.
+    pub async fn put(
+        &self,
+        media_type: &str,
+        data: Vec<u8>,
+    ) -> Result<AttachmentRef, ContextError> {
+        let id = format!("{:x}", Sha256::digest(&data));
+        let reference = AttachmentRef {
+            uri: format!("{ATTACHMENT_SCHEME}{id}"),
+            media_type: media_type.to_string(),
+            size: data.len(),
+            preview: None,
+        };
+        let attachment = Attachment {
+            media_type: media_type.to_string(),
+            data,
+        };
+        self.backend.put(&id, attachment).await?;
+        Ok(reference)
+    }
+
+    /// Load the attachment named by `uri`.
+    ///
+    /// # Errors
+    ///
+    /// `ContextError::Storage` for a malformed URI, or any backend error.
+    pub async fn get(&self, uri: &str) -> Result<Option<Attachment>, ContextError> {
+        let id = parse_uri(uri)
+            .ok_or_else(|| storage_error(format!("invalid attachment URI: {uri:?}")))?;
+        self.backend.get(id).await
+    }
+
+    /// Delete the attachment named by `uri`. Returns whether it existed.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`get`](Self::get).
+    pub async fn remove(&self, uri: &str) -> Result<bool, ContextError> {
+        let id = parse_uri(uri)
+            .ok_or_else(|| storage_error(format!("invalid attachment URI: {uri:?}")))?;
+        self.backend.remove(id).await
+    }
+
+    /// Replace `result.output` with an attachment reference if it is larger
+    /// than the threshold. String outputs are stored as text; anything else
+    /// as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Any backend error.
+    pub async fn offload(&self, mut result: ToolResult) -> Result<ToolResult, ContextError> {
+        let Some(output) = result.output.as_ref().filter(|o| !o.is_null()) else {
+            return Ok(result);
+        };
+        if encoded_len(output) <= self.threshold_bytes {
+            return Ok(result);
+        }
+        let reference = self.put_value(output).await?;
+        result.output = Some(reference.to_value());
+        Ok(result)
+    }
+
+    /// Store `value` regardless of its size and return its reference with a
+    /// preview. Strings are stored as text; anything else as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Any backend error.
+    pub async fn put_value(&self, value: &Value) -> Result<AttachmentRef, ContextError> {
+        let (media_type, content) = match value {
+            Value::String(text) => (TEXT_MEDIA_TYPE, text.clone()),
+            other => (JSON_MEDIA_TYPE, other.to_string()),
+        };
+        let preview: String = content.chars().take(PREVIEW_CHARS).collect();
+        let mut reference = self.put(media_type, content.into_bytes()).await?;
+        reference.preview = Some(preview);
+        Ok(reference)
+    }
+
+    /// Replace every attachment reference inside `value` with the stored
+    /// content: text attachments become strings and JSON attachments their
+    /// parsed value. References to other media types are left in place (use
+    /// [`get`](Self::get) for raw bytes). Returns the number replaced.
+    ///
+    /// # Errors
+    ///
+    /// `ContextError::Storage` if a referenced attachment is missing, or any
+    /// backend error.
+    pub async fn rehydrate(&self, value: &mut Value) -> Result<usize, ContextError> {
+        let mut uris = HashSet::new();
+        collect_refs(value, &mut uris);
+
+        let mut contents = HashMap::new();
+        for uri in uris {
+            let attachment = self
+                .get(&uri)
+                .await?
+                .ok_or_else(|| storage_error(format!("attachment not found: {uri}")))?;
+            if let Some(content) = decode(&attachment) {
+                contents.insert(uri, content);
+            }
+        }
+        Ok(replace_refs(value, &contents))
+    }
+}
+
+/// Bytes [`AttachmentStore::put_value`] would store for `value`.
+pub(crate) fn encoded_len(value: &Value) -> usize {
+    match value {
+        Value::String(text) => text.len(),
+        other => other.to_string().len(),
+    }
+}
+
+/// Rehydrated form of `attachment`, for text and JSON media types.
+fn decode(attachment: &Attachment) -> Option<Value> {
+    let media_type = attachment.media_type.as_str();
+    if media_type.starts_with("text/") {
+        Some(Value::String(
+            String::from_utf8_lossy(&attachment.data).into_owned(),
+        ))
+    } else if media_type == JSON_MEDIA_TYPE {
+        serde_json::from_slice(&attachment.data).ok()
+    } else {
+        None
+    }
+}
+
+fn collect_refs(value: &Value, uris: &mut HashSet<String>) {
+    if let Some(reference) = AttachmentRef::from_value(value) {
+        uris.insert(reference.uri);
+        return;
+    }
+    match value {
+        Value::Array(items) => items.iter().for_each(|v| collect_refs(v, uris)),
+        Value::Object(map) => map.values().for_each(|v| collect_refs(v, uris)),
+        _ => {}
+    }
+}
+
+fn replace_refs(value: &mut Value, contents: &HashMap<String, Value>) -> usize {
+    if let Some(reference) = AttachmentRef::from_value(value) {
+        return match contents.get(&reference.uri) {
+            Some(content) => {
+                *value = content.clone();
+                1
+            }
+            None => 0,
+        };
+    }
+    match value {
+        Value::Array(items) => items.iter_mut().map(|v| replace_refs(v, contents)).sum(),
+        Value::Object(map) => map.values_mut().map(|v| replace_refs(v, contents)).sum(),
+        _ => 0,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_result(text: String) -> ToolResult {
+        ToolResult {
+            success: true,
+            output: Some(Value::String(text)),
+            error: None,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn small_outputs_are_left_inline() {
+        let store = AttachmentStore::in_memory().with_threshold(100);
+        let result = store.offload(text_result("short".into())).await.unwrap();
+        assert_eq!(result.output, Some(Value::String("short".into())));
+    }
+
+    #[tokio::test]
+    async fn large_text_round_trips_through_a_reference() {
+        let store = AttachmentStore::in_memory().with_threshold(100);
+        let text = "x".repeat(500);
+        let result = store.offload(text_result(text.clone())).await.unwrap();
+
+        let output = result.output.unwrap();
+        let reference = AttachmentRef::from_value(&output).expect("reference");
+        assert!(reference.uri.starts_with(ATTACHMENT_SCHEME));
+        assert_eq!(reference.size, 500);
+        assert_eq!(reference.preview.as_deref().map(str::len), Some(200));
+
+        let mut message = serde_json::json!({
+            "role": "tool",
+            "content": [{"type": "tool_result", "output": output}],
+        });
+        assert_eq!(store.rehydrate(&mut message).await.unwrap(), 1);
+        assert_eq!(message["content"][0]["output"], Value::String(text));
+    }
+
+    #[tokio::test]
+    async fn json_outputs_rehydrate_to_values_and_dedupe() {
+        let backend = InMemoryAttachmentBackend::new();
+        let store = AttachmentStore::new(Box::new(backend)).with_threshold(10);
+        let output = serde_json::json!({"files": ["a.rs", "b.rs", "c.rs"]});
+        let first = store
+            .offload(ToolResult {
+                success: true,
+                output: Some(output.clone()),
+                error: None,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let second = store
+            .offload(ToolResult {
+                success: true,
+                output: Some(output.clone()),
+                error: None,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(first.output, second.output);
+
+        let mut value = serde_json::json!([first.output, second.output]);
+        assert_eq!(store.rehydrate(&mut value).await.unwrap(), 2);
+        assert_eq!(value, serde_json::json!([output, output]));
+    }
+
+    #[tokio::test]
+    async fn binary_and_missing_references() {
+        let store = AttachmentStore::in_memory();
+        let image = store
+            .put("image/png", vec![0x89, b'P', b'N', b'G'])
+            .await
+            .unwrap();
+        let mut value = image.to_value();
+        assert_eq!(store.rehydrate(&mut value).await.unwrap(), 0);
+        assert_eq!(
+            store.get(&image.uri).await.unwrap().unwrap().data,
+            vec![0x89, b'P', b'N', b'G']
+        );
+
+        assert!(store.remove(&image.uri).await.unwrap());
+        assert!(store.rehydrate(&mut value).await.is_err());
+        assert!(store.get("attachment://../etc/passwd").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn in_memory_backend_is_memory_accounted() {
+        use crate::memory::MemoryConfig;
+
+        let memory = Arc::new(MemoryAccountant::new(MemoryConfig {
+            ceiling_bytes: Some(64),
+            ..Default::default()
+        }));
+        let store = AttachmentStore::new(Box::new(InMemoryAttachmentBackend::with_memory(
+            memory.clone(),
+        )));
+        let small = store.put("text/plain", vec![b'a'; 40]).await.unwrap();
+        assert_eq!(memory.usage().by_category["attachments"], 50);
+        // Storing the same content again charges nothing.
+        store.put("text/plain", vec![b'a'; 40]).await.unwrap();
+        assert_eq!(memory.used_bytes(), 50);
+
+        assert!(store.put("text/plain", vec![b'b'; 40]).await.is_err());
+        assert_eq!(memory.usage().rejections, 1);
+
+        store.remove(&small.uri).await.unwrap();
+        assert_eq!(memory.used_bytes(), 0);
+        store.put("text/plain", vec![b'b'; 40]).await.unwrap();
+        drop(store);
+        assert_eq!(memory.used_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn file_backend_persists_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let reference = {
+            let store =
+                AttachmentStore::new(Box::new(FileAttachmentBackend::open(dir.path()).unwrap()));
+            store.put("text/markdown", b"# hi".to_vec()).await.unwrap()
+        };
+
+        let reopened =
+            AttachmentStore::new(Box::new(FileAttachmentBackend::open(dir.path()).unwrap()));
+        let attachment = reopened.get(&reference.uri).await.unwrap().unwrap();
+        assert_eq!(attachment.media_type, "text/markdown");
+        assert_eq!(attachment.data, b"# hi");
+    }
+
+    #[test]
+    fn config_reads_session_attachments() {
+        let config: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "session": {"attachments": {"threshold_bytes": 1024}}
+        }))
+        .unwrap();
+        let parsed = AttachmentConfig::from_session_config(&config).unwrap();
+        assert_eq!(parsed.threshold_bytes, 1024);
+        assert!(parsed.directory.is_none());
+        assert!(AttachmentConfig::from_session_config(&HashMap::new()).is_none());
+    }
+}
@@ -0,0 +1,908 @@
+//! Tamper-evident audit log of tool executions.
+//!
+//! [`AuditLog`] observes `tool:*` and `approval:*` events and appends one
+//! [`AuditRecord`] per tool call: the tool, SHA-256 hashes of its arguments
+//! and result, the approval decision, how long it ran, and the session and
+//! turn it ran in. Arguments and results are hashed rather than stored, so
+//! the log can be kept (and shipped) without retaining tool payloads.
+//!
+//! # Hash chaining
+//!
+//! Every record carries the hash of the record before it (`prev_hash`; the
+//! first record uses [`GENESIS_HASH`]) and its own `hash`, the SHA-256 of its
+//! canonical JSON form without the `hash` field. Editing, removing or
+//! reordering a record breaks the chain, which [`AuditLog::verify`] and
+//! [`verify_jsonl`] detect.
+//!
+//! # Recording
+//!
+//! | Event                                  | Effect                              |
+//! |----------------------------------------|-------------------------------------|
+//! | `approval:granted` / `approval:denied` | Decision held for the next call of that tool |
+//! | `tool:pre`                             | Call opened (or recorded as denied) |
+//! | `tool:post`                            | Call recorded as succeeded          |
+//! | `tool:error`                           | Call recorded as failed             |
+//!
+//! A call denied at `tool:pre` — by an approval or by a hook — never
+//! reaches `tool:post`, so it is recorded immediately with no result.
+//!
+//! # Configuration
+//!
+//! Read from `session.audit`; the section being present enables the log:
+//!
+//! ```json
+//! {"session": {"audit": {"path": "/var/log/amplifier/audit.jsonl"}}}
+//! ```
+//!
+//! With a `path`, each record is also appended to that file as one JSON line
+//! as soon as it is made. An existing file is verified and its chain
+//! continued.
+//!
+//! Several logs (in this process or others) may append to one file. Each
+//! append holds an exclusive lock on the file and first reads the records
+//! other logs appended since, so every record chains from the file's latest
+//! one and the file stays a single chain.
+//!
+//! # Memory
+//!
+//! Records held in memory are charged to the session's
+//! [`MemoryAccountant`] under `"audit"` (see [`crate::memory`]). Under a
+//! ceiling, the oldest records are evicted (or new ones not held) as the
+//! eviction policy says; the file, if any, still receives every record, and
+//! [`AuditLog::verify`] checks the chain from the oldest record held.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::clock::Clock;
+use crate::errors::HookError;
+use crate::events;
+use crate::hooks::{HookPhase, HookRegistry};
+use crate::memory::{BoundedBuffer, MemoryAccountant, PushOutcome};
+use crate::models::HookResult;
+use crate::traits::HookHandler;
+
+/// `prev_hash` of the first record in a chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// Audit log failures.
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    /// The chain does not verify at `sequence`.
+    #[error("audit chain broken at record {sequence}: {reason}")]
+    ChainBroken { sequence: u64, reason: String },
+
+    /// A JSONL line is not an audit record.
+    #[error("malformed audit record on line {line}: {reason}")]
+    Malformed { line: usize, reason: String },
+
+    /// I/O error reading or writing the log.
+    #[error("audit I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+// ---------------------------------------------------------------------------
+// AuditRecord
+// ---------------------------------------------------------------------------
+
+/// The approval decision a call ran (or was refused) under.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditApproval {
+    pub approved: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The decision was the gate's default after the request timed out.
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+/// One tool execution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Position in the chain, from 0.
+    pub sequence: u64,
+    /// When the record was made.
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Turn the call ran in (see [`AuditLog::begin_turn`]); 0 before the
+    /// first turn.
+    pub turn: u64,
+    /// The turn's correlation ID (see [`crate::correlation`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    pub tool_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// SHA-256 of the canonical JSON of the tool input.
+    pub arguments_hash: String,
+    /// SHA-256 of the canonical JSON of the result (or error); `None` when
+    /// the call never ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_hash: Option<String>,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<AuditApproval>,
+    pub duration_ms: u64,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// The hash this record should carry.
+    pub fn compute_hash(&self) -> String {
+        let value = serde_json::to_value(self).expect("audit records serialize");
+        hash_without_field(value)
+    }
+}
+
+/// SHA-256 hex of `value`'s canonical JSON: object keys sorted at every
+/// level, no insignificant whitespace.
+pub fn canonical_hash(value: &Value) -> String {
+    let canonical = serde_json::to_string(&canonicalize(value)).expect("JSON values serialize");
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+            Value::Object(
+                keys.into_iter()
+                    .map(|k| (k.clone(), canonicalize(&map[k])))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+fn hash_without_field(mut record: Value) -> String {
+    if let Value::Object(map) = &mut record {
+        map.remove("hash");
+    }
+    canonical_hash(&record)
+}
+
+// ---------------------------------------------------------------------------
+// Verification
+// ---------------------------------------------------------------------------
+
+/// Tracks the expected sequence and `prev_hash` while walking a chain.
+struct ChainCursor {
+    sequence: u64,
+    head: String,
+}
+
+impl ChainCursor {
+    fn new() -> Self {
+        Self {
+            sequence: 0,
+            head: GENESIS_HASH.to_string(),
+        }
+    }
+
+    /// Check the next record, given as its JSON form.
+    fn check(&mut self, record: &Value) -> Result<(), AuditError> {
+        let broken = |reason: &str| AuditError::ChainBroken {
+            sequence: self.sequence,
+            reason: reason.to_string(),
+        };
+        if record.get("sequence").and_then(Value::as_u64) != Some(self.sequence) {
+            return Err(broken("sequence out of order"));
+        }
+        if record.get("prev_hash").and_then(Value::as_str) != Some(self.head.as_str()) {
+            return Err(broken("prev_hash does not match the previous record"));
+        }
+        let Some(hash) = record.get("hash").and_then(Value::as_str) else {
+            return Err(broken("missing hash"));
+        };
+        if hash_without_field(record.clone()) != hash {
+            return Err(broken("hash does not match contents"));
+        }
+        self.head = hash.to_string();
+        self.sequence += 1;
+        Ok(())
+    }
+}
+
+/// Verify a JSONL export (see [`AuditLog::export_jsonl`]).
+///
+/// Returns the number of records and the hash of the last one
+/// ([`GENESIS_HASH`] for an empty log). Blank lines are ignored.
+///
+/// # Errors
+///
+/// `AuditError::Malformed` for a line that is not a record,
+/// `AuditError::ChainBroken` for the first record that does not verify.
+pub fn verify_jsonl(reader: impl BufRead) -> Result<(u64, String), AuditError> {
+    let mut cursor = ChainCursor::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let malformed = |reason: String| AuditError::Malformed {
+            line: index + 1,
+            reason,
+        };
+        let value: Value = serde_json::from_str(&line).map_err(|e| malformed(e.to_string()))?;
+        serde_json::from_value::<AuditRecord>(value.clone())
+            .map_err(|e| malformed(e.to_string()))?;
+        cursor.check(&value)?;
+    }
+    Ok((cursor.sequence, cursor.head))
+}
+
+// ---------------------------------------------------------------------------
+// AuditConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.audit` section.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditConfig {
+    /// Append records to this JSONL file as they are made.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+impl AuditConfig {
+    /// Read `session.audit` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("audit"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.audit config: {e}"))
+            .ok()
+    }
+
+    /// Build the log this config describes.
+    ///
+    /// # Errors
+    ///
+    /// As for [`AuditLog::with_file`].
+    pub fn build(&self, clock: Arc<dyn Clock>) -> Result<AuditLog, AuditError> {
+        match &self.path {
+            Some(path) => AuditLog::with_file(clock, path),
+            None => Ok(AuditLog::new(clock)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AuditLog
+// ---------------------------------------------------------------------------
+
+/// A call seen at `tool:pre` and not yet recorded.
+struct OpenCall {
+    started: Instant,
+    tool_name: String,
+    tool_call_id: Option<String>,
+    arguments_hash: String,
+    approval: Option<AuditApproval>,
+}
+
+/// Accounting category for records held in memory.
+const MEMORY_CATEGORY: &str = "audit";
+
+struct AuditState {
+    records: BoundedBuffer<AuditRecord>,
+    /// Sequence of the oldest record held (of the next one when none is),
+    /// and the hash it chains from. Differ from a fresh chain's when
+    /// continuing an existing file or after evictions.
+    base_sequence: u64,
+    base_hash: String,
+    /// Sequence and hash the next record chains from.
+    next_sequence: u64,
+    tip: String,
+    /// Cleared when the memory ceiling rejects a record: later ones are not
+    /// held either, so the held records stay a contiguous chain.
+    holding: bool,
+    turn: u64,
+    open: HashMap<String, OpenCall>,
+    /// Approval decisions by tool name, waiting for that tool's `tool:pre`.
+    approvals: HashMap<String, AuditApproval>,
+}
+
+impl AuditState {
+    fn new(memory: Arc<MemoryAccountant>, next_sequence: u64, head: String) -> Self {
+        Self {
+            records: BoundedBuffer::new(MEMORY_CATEGORY, memory),
+            base_sequence: next_sequence,
+            base_hash: head.clone(),
+            next_sequence,
+            tip: head,
+            holding: true,
+            turn: 0,
+            open: HashMap::new(),
+            approvals: HashMap::new(),
+        }
+    }
+
+    /// Hold `record` in memory if the ceiling allows.
+    fn hold(&mut self, record: AuditRecord, size: usize) {
+        if !self.holding {
+            return;
+        }
+        match self.records.push(record, size) {
+            PushOutcome::Stored { evicted: 0 } => {}
+            PushOutcome::Stored { evicted } => {
+                log::warn!("Evicted {evicted} audit record(s) from memory");
+                self.base_sequence += evicted as u64;
+                if let Some(first) = self.records.iter().next() {
+                    self.base_hash = first.prev_hash.clone();
+                }
+            }
+            PushOutcome::Rejected => {
+                log::warn!(
+                    "Audit record {} not held in memory: memory ceiling reached",
+                    self.next_sequence
+                );
+                self.holding = false;
+            }
+        }
+    }
+}
+
+/// The JSONL file a log appends to.
+struct AuditFile {
+    file: File,
+    /// Length of the file as of this log's last read or write.
+    end: u64,
+}
+
+impl AuditFile {
+    /// Read the records appended by other logs since `end`, holding them so
+    /// `state` continues from the file's latest record. Call with the file
+    /// locked.
+    fn catch_up(&mut self, state: &mut AuditState) -> Result<(), AuditError> {
+        let len = self.file.metadata()?.len();
+        if len <= self.end {
+            return Ok(());
+        }
+        (&self.file).seek(SeekFrom::Start(self.end))?;
+        let mut cursor = ChainCursor {
+            sequence: state.next_sequence,
+            head: state.tip.clone(),
+        };
+        for line in BufReader::new((&self.file).take(len - self.end)).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Records are one per line, so the line number follows from the
+            // sequence.
+            let malformed = |reason: String| AuditError::Malformed {
+                line: cursor.sequence as usize + 1,
+                reason,
+            };
+            let value: Value = serde_json::from_str(&line).map_err(|e| malformed(e.to_string()))?;
+            let record: AuditRecord =
+                serde_json::from_value(value.clone()).map_err(|e| malformed(e.to_string()))?;
+            cursor.check(&value)?;
+            state.tip = record.hash.clone();
+            state.hold(record, line.len() + 1);
+            state.next_sequence += 1;
+        }
+        self.end = len;
+        Ok(())
+    }
+}
+
+/// Append-only, hash-chained record of a session's tool executions.
+pub struct AuditLog {
+    clock: Arc<dyn Clock>,
+    state: Mutex<AuditState>,
+    file: Option<Mutex<AuditFile>>,
+}
+
+impl std::fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("records", &self.state.lock().unwrap().records.len())
+            .field("file", &self.file.is_some())
+            .finish()
+    }
+}
+
+impl AuditLog {
+    /// An in-memory log.
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            state: Mutex::new(AuditState::new(
+                Arc::new(MemoryAccountant::default()),
+                0,
+                GENESIS_HASH.to_string(),
+            )),
+            file: None,
+        }
+    }
+
+    /// Charge records held in memory to `memory` (typically
+    /// [`Coordinator::memory`](crate::coordinator::Coordinator::memory)).
+    /// Call before any record is made.
+    pub fn with_memory(self, memory: Arc<MemoryAccountant>) -> Self {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.records = BoundedBuffer::new(MEMORY_CATEGORY, memory);
+        }
+        self
+    }
+
+    /// A log that also appends every record to the JSONL file at `path`,
+    /// continuing the chain already in it (and any records other logs append
+    /// to it later; see [the module docs](self)).
+    ///
+    /// # Errors
+    ///
+    /// `AuditError::Io` if the file cannot be opened, or the
+    /// [`verify_jsonl`] error if its existing contents do not verify.
+    pub fn with_file(clock: Arc<dyn Clock>, path: impl AsRef<Path>) -> Result<Self, AuditError> {
+        let path = path.as_ref();
+        let (next_sequence, head) = match File::open(path) {
+            Ok(existing) => verify_jsonl(BufReader::new(existing))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (0, GENESIS_HASH.to_string()),
+            Err(e) => return Err(e.into()),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let end = file.metadata()?.len();
+        Ok(Self {
+            clock,
+            state: Mutex::new(AuditState::new(
+                Arc::new(MemoryAccountant::default()),
+                next_sequence,
+                head,
+            )),
+            file: Some(Mutex::new(AuditFile { file, end })),
+        })
+    }
+
+    /// Register on `hooks` in [`HookPhase::Observation`] as `"audit"`, so
+    /// calls are recorded with the final arguments and results, including
+    /// calls another handler denied.
+    pub fn install(self: &Arc<Self>, hooks: &HookRegistry) {
+        for event in [
+            events::APPROVAL_GRANTED,
+            events::APPROVAL_DENIED,
+            events::TOOL_PRE,
+            events::TOOL_POST,
+            events::TOOL_ERROR,
+        ] {
+            let _ = hooks.register_in_phase(
+                event,
+                self.clone(),
+                HookPhase::Observation,
+                i32::MAX,
+                Some("audit".into()),
+            );
+        }
+    }
+
+    /// Start a new turn; later calls are recorded under it.
+    pub fn begin_turn(&self) {
+        self.state.lock().unwrap().turn += 1;
+    }
+
+    /// Records held in memory, oldest first. A log continuing a file holds
+    /// the records appended since it opened the file, by it or by other logs.
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.state.lock().unwrap().records.iter().cloned().collect()
+    }
+
+    /// Hash of the latest record ([`GENESIS_HASH`] before the first).
+    pub fn head(&self) -> String {
+        self.state.lock().unwrap().tip.clone()
+    }
+
+    /// Check that the in-memory records form an intact chain.
+    ///
+    /// # Errors
+    ///
+    /// `AuditError::ChainBroken` for the first record that does not verify.
+    pub fn verify(&self) -> Result<(), AuditError> {
+        let state = self.state.lock().unwrap();
+        let mut cursor = ChainCursor::new();
+        cursor.sequence = state.base_sequence;
+        cursor.head = state.base_hash.clone();
+        for record in state.records.iter() {
+            cursor.check(&serde_json::to_value(record).expect("audit records serialize"))?;
+        }
+        Ok(())
+    }
+
+    /// Write the records as JSON lines, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// `AuditError::Io` if writing fails.
+    pub fn export_jsonl(&self, mut writer: impl Write) -> Result<(), AuditError> {
+        for record in self.records() {
+            serde_json::to_writer(&mut writer, &record).map_err(std::io::Error::from)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn observe(&self, event: &str, data: &Value) {
+        match event {
+            events::APPROVAL_GRANTED | events::APPROVAL_DENIED => {
+                let approval = AuditApproval {
+                    approved: event == events::APPROVAL_GRANTED,
+                    reason: str_field(data, "reason"),
+                    timed_out: data
+                        .get("timed_out")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                };
+                let tool_name = str_field(data, "tool_name").unwrap_or_default();
+                self.state
+                    .lock()
+                    .unwrap()
+                    .approvals
+                    .insert(tool_name, approval);
+            }
+            events::TOOL_PRE => self.observe_pre(data),
+            events::TOOL_POST => self.close(data, true, data.get("tool_result")),
+            events::TOOL_ERROR => self.close(data, false, data.get("error")),
+            _ => {}
+        }
+    }
+
+    fn observe_pre(&self, data: &Value) {
+        let call = self.open_call(data);
+        let denied_by = data.get("denied_by");
+        let mut state = self.state.lock().unwrap();
+        let call = OpenCall {
+            approval: state.approvals.remove(&call.tool_name),
+            ..call
+        };
+        let refused = call.approval.as_ref().is_some_and(|a| !a.approved);
+        if refused || denied_by.is_some() {
+            let approval = call.approval.clone().or_else(|| {
+                Some(AuditApproval {
+                    approved: false,
+                    reason: denied_by.and_then(|d| str_field(d, "reason")),
+                    timed_out: false,
+                })
+            });
+            let call = OpenCall { approval, ..call };
+            self.append(&mut state, call, data, false, None);
+        } else {
+            state.open.insert(tool_key(data), call);
+        }
+    }
+
+    fn close(&self, data: &Value, success: bool, result: Option<&Value>) {
+        let mut state = self.state.lock().unwrap();
+        let call = match state.open.remove(&tool_key(data)) {
+            Some(call) => call,
+            // No `tool:pre` was seen (e.g. the log was installed mid-call).
+            None => self.open_call(data),
+        };
+        let result_hash = canonical_hash(result.unwrap_or(&Value::Null));
+        self.append(&mut state, call, data, success, Some(result_hash));
+    }
+
+    fn open_call(&self, data: &Value) -> OpenCall {
+        OpenCall {
+            started: self.clock.now(),
+            tool_name: str_field(data, "tool_name").unwrap_or_default(),
+            tool_call_id: str_field(data, "tool_call_id"),
+            arguments_hash: canonical_hash(data.get("tool_input").unwrap_or(&Value::Null)),
+            approval: None,
+        }
+    }
+
+    fn append(
+        &self,
+        state: &mut AuditState,
+        call: OpenCall,
+        data: &Value,
+        success: bool,
+        result_hash: Option<String>,
+    ) {
+        let mut file = self.file.as_ref().map(|file| file.lock().unwrap());
+        if let Some(file) = file.as_deref_mut() {
+            if let Err(e) = file.file.lock() {
+                log::warn!("Failed to lock audit file: {e}");
+            }
+            if let Err(e) = file.catch_up(state) {
+                log::error!("Failed to read records appended to the audit file: {e}");
+            }
+        }
+
+        let prev_hash = state.tip.clone();
+        let mut record = AuditRecord {
+            sequence: state.next_sequence,
+            timestamp: self.clock.now_utc(),
+            session_id: str_field(data, "session_id"),
+            turn: state.turn,
+            turn_id: str_field(data, "turn_id"),
+            tool_name: call.tool_name,
+            tool_call_id: call.tool_call_id,
+            arguments_hash: call.arguments_hash,
+            result_hash,
+            success,
+            approval: call.approval,
+            duration_ms: self.clock.now().duration_since(call.started).as_millis() as u64,
+            prev_hash,
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+
+        let mut line = serde_json::to_vec(&record).expect("audit records serialize");
+        line.push(b'\n');
+        if let Some(file) = file.as_deref_mut() {
+            match file.file.write_all(&line) {
+                Ok(()) => file.end += line.len() as u64,
+                Err(e) => log::error!("Failed to append audit record {}: {e}", record.sequence),
+            }
+            let _ = file.file.unlock();
+        }
+        state.tip = record.hash.clone();
+        state.hold(record, line.len());
+        state.next_sequence += 1;
+    }
+}
+
+impl HookHandler for AuditLog {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        self.observe(event, &data);
+        Box::pin(async { Ok(HookResult::default()) })
+    }
+}
+
+fn str_field(data: &Value, key: &str) -> Option<String> {
+    data.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Matches `tool:pre` to `tool:post` by call id, else by tool name.
+fn tool_key(data: &Value) -> String {
+    str_field(data, "tool_call_id")
+        .or_else(|| str_field(data, "tool_name"))
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HookAction;
+    use crate::testing::{FakeHookHandler, ManualClock};
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn audited() -> (Arc<AuditLog>, HookRegistry, ManualClock) {
+        let clock = ManualClock::default();
+        let hooks = HookRegistry::new();
+        hooks.set_default_fields(json!({"session_id": "s1"}));
+        let log = Arc::new(AuditLog::new(Arc::new(clock.clone())));
+        log.install(&hooks);
+        (log, hooks, clock)
+    }
+
+    async fn run_tool(hooks: &HookRegistry, clock: &ManualClock, id: &str, input: Value) {
+        let call = json!({"tool_name": "bash", "tool_call_id": id, "tool_input": input});
+        hooks.emit(events::TOOL_PRE, call.clone()).await;
+        clock.advance(Duration::from_millis(250));
+        let mut post = call;
+        post["tool_result"] = json!({"success": true, "output": "ok"});
+        hooks.emit(events::TOOL_POST, post).await;
+    }
+
+    #[tokio::test]
+    async fn records_tool_calls_in_a_verifiable_chain() {
+        let (log, hooks, clock) = audited();
+        log.begin_turn();
+        hooks
+            .emit(
+                events::APPROVAL_GRANTED,
+                json!({"tool_name": "bash", "reason": null, "timed_out": false}),
+            )
+            .await;
+        run_tool(&hooks, &clock, "c1", json!({"command": "ls", "cwd": "/"})).await;
+        run_tool(&hooks, &clock, "c2", json!({"cwd": "/", "command": "ls"})).await;
+
+        let records = log.records();
+        assert_eq!(records.len(), 2);
+        let first = &records[0];
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(first.session_id.as_deref(), Some("s1"));
+        assert_eq!(
+            (first.turn, first.duration_ms, first.success),
+            (1, 250, true)
+        );
+        assert!(first.approval.as_ref().unwrap().approved);
+        assert!(records[1].approval.is_none());
+        // Key order does not change the hash.
+        assert_eq!(first.arguments_hash, records[1].arguments_hash);
+        assert_eq!(records[1].prev_hash, first.hash);
+        assert_eq!(log.head(), records[1].hash);
+        log.verify().unwrap();
+
+        let mut jsonl = Vec::new();
+        log.export_jsonl(&mut jsonl).unwrap();
+        assert_eq!(
+            verify_jsonl(jsonl.as_slice()).unwrap(),
+            (2, records[1].hash.clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn denied_calls_are_recorded_without_a_result() {
+        let (log, hooks, _clock) = audited();
+        hooks
+            .emit(
+                events::APPROVAL_DENIED,
+                json!({"tool_name": "bash", "reason": "timeout", "timed_out": true}),
+            )
+            .await;
+        hooks
+            .emit(
+                events::TOOL_PRE,
+                json!({"tool_name": "bash", "tool_input": {}}),
+            )
+            .await;
+
+        let deny = Arc::new(FakeHookHandler::with_result(HookResult {
+            action: HookAction::Deny,
+            reason: Some("policy".into()),
+            ..Default::default()
+        }));
+        let _ = hooks.register(events::TOOL_PRE, deny, 0, None);
+        hooks
+            .emit(
+                events::TOOL_PRE,
+                json!({"tool_name": "write", "tool_input": {}}),
+            )
+            .await;
+
+        let records = log.records();
+        assert_eq!(records.len(), 2);
+        for record in &records {
+            assert!(!record.success);
+            assert!(record.result_hash.is_none());
+        }
+        let approval = records[0].approval.as_ref().unwrap();
+        assert!(!approval.approved && approval.timed_out);
+        assert_eq!(
+            records[1].approval.as_ref().unwrap().reason.as_deref(),
+            Some("policy")
+        );
+    }
+
+    #[tokio::test]
+    async fn tampering_breaks_the_chain() {
+        let (log, hooks, clock) = audited();
+        for id in ["c1", "c2", "c3"] {
+            run_tool(&hooks, &clock, id, json!({"id": id})).await;
+        }
+        let mut jsonl = Vec::new();
+        log.export_jsonl(&mut jsonl).unwrap();
+        let lines: Vec<&str> = std::str::from_utf8(&jsonl).unwrap().lines().collect();
+
+        let edited = lines
+            .join("\n")
+            .replace("\"success\":true", "\"success\":false");
+        let err = verify_jsonl(edited.as_bytes()).unwrap_err();
+        assert!(matches!(err, AuditError::ChainBroken { sequence: 0, .. }));
+
+        let removed = [lines[0], lines[2]].join("\n");
+        let err = verify_jsonl(removed.as_bytes()).unwrap_err();
+        assert!(matches!(err, AuditError::ChainBroken { sequence: 1, .. }));
+    }
+
+    #[tokio::test]
+    async fn held_records_are_memory_accounted() {
+        use crate::memory::MemoryConfig;
+
+        let clock = ManualClock::default();
+        let hooks = HookRegistry::new();
+        let memory = Arc::new(MemoryAccountant::new(MemoryConfig {
+            ceiling_bytes: Some(1_500),
+            ..Default::default()
+        }));
+        let log = Arc::new(AuditLog::new(Arc::new(clock.clone())).with_memory(memory.clone()));
+        log.install(&hooks);
+        for id in ["c1", "c2", "c3", "c4", "c5"] {
+            run_tool(&hooks, &clock, id, json!({"id": id})).await;
+        }
+
+        // The oldest records were evicted; what is held still verifies and
+        // the chain carries on from the latest record.
+        let records = log.records();
+        assert!(records.len() < 5);
+        assert_eq!(records.last().unwrap().sequence, 4);
+        assert_eq!(log.head(), records.last().unwrap().hash);
+        assert!(memory.usage().by_category["audit"] <= 1_500);
+        assert!(memory.usage().evictions > 0);
+        log.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn file_log_continues_an_existing_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let clock = ManualClock::default();
+        for id in ["c1", "c2"] {
+            let hooks = HookRegistry::new();
+            let config: HashMap<String, Value> =
+                HashMap::from([("session".into(), json!({"audit": {"path": path}}))]);
+            let log = Arc::new(
+                AuditConfig::from_session_config(&config)
+                    .unwrap()
+                    .build(Arc::new(clock.clone()))
+                    .unwrap(),
+            );
+            log.install(&hooks);
+            run_tool(&hooks, &clock, id, json!({})).await;
+            log.verify().unwrap();
+        }
+
+        let (count, _) = verify_jsonl(BufReader::new(File::open(&path).unwrap())).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn logs_sharing_a_file_keep_one_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let clock = ManualClock::default();
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let hooks = HookRegistry::new();
+                let log = Arc::new(AuditLog::with_file(Arc::new(clock.clone()), &path).unwrap());
+                log.install(&hooks);
+                (log, hooks)
+            })
+            .collect();
+        for round in 0..3 {
+            for (index, (_, hooks)) in writers.iter().enumerate() {
+                run_tool(hooks, &clock, &format!("c{round}-{index}"), json!({})).await;
+            }
+        }
+
+        let (count, head) = verify_jsonl(BufReader::new(File::open(&path).unwrap())).unwrap();
+        assert_eq!(count, 6);
+        // Each log holds the file's chain up to its own latest record.
+        let (first, _) = &writers[0];
+        let (second, _) = &writers[1];
+        assert_eq!(first.records().len(), 5);
+        assert_eq!(second.records().len(), 6);
+        assert_eq!(second.head(), head);
+        first.verify().unwrap();
+        second.verify().unwrap();
+    }
+}
@@ -79,6 +79,7 @@ impl GrpcContextBridge {
                     name: String::new(),
                     tool_call_id: String::new(),
                     metadata_json: String::new(),
+                    cache: 0,
                 }
             }
         }
@@ -289,6 +290,7 @@ mod tests {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = crate::generated::conversions::native_message_to_proto(native);
@@ -307,6 +309,7 @@ mod tests {
             name: String::new(),
             tool_call_id: String::new(),
             metadata_json: String::new(),
+            cache: 0,
         };
         assert_eq!(GrpcContextBridge::proto_message_to_value(&msg), Value::Null);
     }
@@ -323,6 +326,7 @@ mod tests {
             name: String::new(),
             tool_call_id: String::new(),
             metadata_json: String::new(),
+            cache: 0,
         };
         let val = GrpcContextBridge::proto_message_to_value(&msg);
         assert_ne!(val, Value::Null, "BlockContent must produce a proper Value");
@@ -345,6 +349,7 @@ mod tests {
             name: Some("alice".into()),
             tool_call_id: Some("call_123".into()),
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let val = serde_json::to_value(&native).expect("serialise Message to Value");
@@ -374,12 +379,14 @@ mod tests {
                             },
                         )),
                         visibility: 0,
+                        cache: 0,
                     }],
                 },
             )),
             name: String::new(),
             tool_call_id: String::new(),
             metadata_json: String::new(),
+            cache: 0,
         };
         let val = GrpcContextBridge::proto_message_to_value(&msg);
         assert_ne!(val, Value::Null, "BlockContent must NOT become Null");
@@ -261,6 +261,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -17,6 +17,10 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! Tool input is sent as JSON unless another [`WireFormat`] is chosen with
+//! [`GrpcToolBridge::with_wire_format`]. Output is decoded according to the
+//! `content_type` the tool labels its response with.
 
 use std::collections::HashMap;
 use std::future::Future;
@@ -28,11 +32,11 @@ use tonic::transport::Channel;
 use crate::errors::ToolError;
 use crate::generated::amplifier_module;
 use crate::generated::amplifier_module::tool_service_client::ToolServiceClient;
+use crate::generated::conversions;
 use crate::messages;
 use crate::models::ToolResult;
 use crate::traits::Tool;
-
-const CONTENT_TYPE_JSON: &str = "application/json";
+use crate::wire::WireFormat;
 
 /// A bridge that wraps a remote gRPC `ToolService` as a native [`Tool`].
 ///
@@ -44,6 +48,7 @@ pub struct GrpcToolBridge {
     name: String,
     description: String,
     spec: messages::ToolSpec,
+    wire_format: WireFormat,
 }
 
 impl GrpcToolBridge {
@@ -87,8 +92,23 @@ impl GrpcToolBridge {
             name,
             description,
             spec,
+            wire_format: WireFormat::Json,
         })
     }
+
+    /// Encode tool input in `format` instead of JSON.
+    ///
+    /// The remote tool must accept that format; responses are decoded by
+    /// their own content type regardless of this setting.
+    pub fn with_wire_format(mut self, format: WireFormat) -> Self {
+        self.wire_format = format;
+        self
+    }
+
+    /// The format tool input is encoded in.
+    pub fn wire_format(&self) -> WireFormat {
+        self.wire_format
+    }
 }
 
 impl Tool for GrpcToolBridge {
@@ -109,13 +129,16 @@ impl Tool for GrpcToolBridge {
         input: Value,
     ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
         Box::pin(async move {
-            let json_bytes = serde_json::to_vec(&input).map_err(|e| ToolError::Other {
-                message: format!("gRPC call failed: {}", e),
-            })?;
+            let input_bytes = self
+                .wire_format
+                .encode(&input)
+                .map_err(|e| ToolError::Other {
+                    message: format!("gRPC call failed: {}", e),
+                })?;
 
             let request = amplifier_module::ToolExecuteRequest {
-                input: json_bytes,
-                content_type: CONTENT_TYPE_JSON.to_string(),
+                input: input_bytes,
+                content_type: self.wire_format.content_type().to_string(),
             };
 
             let response = {
@@ -130,19 +153,22 @@ impl Tool for GrpcToolBridge {
 
             let resp = response.into_inner();
 
-            if !resp.content_type.is_empty() && resp.content_type != CONTENT_TYPE_JSON {
-                log::warn!(
-                    "Tool response has content_type '{}' but only '{}' is supported — parsing as JSON anyway",
-                    resp.content_type, CONTENT_TYPE_JSON
-                );
-            }
+            let output_format =
+                WireFormat::from_content_type(&resp.content_type).unwrap_or_else(|e| {
+                    log::warn!(
+                        "Tool '{}' response: {e} — parsing as JSON anyway",
+                        self.name
+                    );
+                    WireFormat::Json
+                });
 
             let output = if resp.output.is_empty() {
                 None
             } else {
-                serde_json::from_slice(&resp.output)
+                output_format
+                    .decode(&resp.output)
                     .map_err(|e| {
-                        log::warn!("Failed to parse tool '{}' output JSON: {e}", self.name);
+                        log::warn!("Failed to parse tool '{}' output: {e}", self.name);
                         e
                     })
                     .ok()
@@ -161,6 +187,9 @@ impl Tool for GrpcToolBridge {
                 success: resp.success,
                 output,
                 error,
+                content: conversions::proto_content_blocks_to_native(resp.content),
+                artifacts: conversions::proto_artifacts_to_native(&resp.artifacts_json),
+                display_hint: conversions::proto_display_hint_to_native(resp.display_hint),
             })
         })
     }
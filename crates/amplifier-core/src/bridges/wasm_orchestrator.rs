@@ -19,6 +19,7 @@ use wasmtime::Engine;
 
 use crate::coordinator::Coordinator;
 use crate::errors::{AmplifierError, SessionError};
+use crate::tool_executor::ToolExecutor;
 use crate::traits::{ContextManager, Orchestrator, Provider, Tool};
 
 use super::wasm_tool::{create_linker_and_store, WasmState};
@@ -94,8 +95,11 @@ fn register_kernel_service_imports(
     // ------------------------------------------------------------------
     // execute-tool: func(request: list<u8>) -> result<list<u8>, string>
     //
-    // Request JSON: {"name": "<tool-name>", "input": <json-value>}
+    // Request JSON: {"name": "<tool-name>", "input": <json-value>, "id"?: "<call-id>"}
     // Response JSON: serialized ToolResult
+    //
+    // With an "id", a repeat of the same call this turn returns the cached
+    // result (see crate::tool_executor).
     // ------------------------------------------------------------------
     {
         let coord = Arc::clone(&coordinator);
@@ -119,8 +123,9 @@ fn register_kernel_service_imports(
                     let tool = coord
                         .get_tool(name)
                         .ok_or_else(|| format!("execute-tool: tool not found: {name}"))?;
-                    let tool_result = tool
-                        .execute(input)
+                    let call_id = req.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+                    let tool_result = ToolExecutor::from_coordinator(&coord)
+                        .execute(tool.as_ref(), call_id, input)
                         .await
                         .map_err(|e| format!("execute-tool: execution failed: {e}"))?;
                     serde_json::to_vec(&tool_result)
@@ -161,10 +166,11 @@ fn register_kernel_service_imports(
                     let chat_request: crate::messages::ChatRequest =
                         serde_json::from_value(request_val)
                             .map_err(|e| format!("complete-with-provider: bad ChatRequest: {e}"))?;
-                    let response = provider
-                        .complete(chat_request)
-                        .await
-                        .map_err(|e| format!("complete-with-provider: failed: {e}"))?;
+                    let response =
+                        crate::provider_invoker::ProviderInvoker::from_coordinator(&coord)
+                            .complete(provider.as_ref(), chat_request)
+                            .await
+                            .map_err(|e| format!("complete-with-provider: failed: {e}"))?;
                     serde_json::to_vec(&response)
                         .map_err(|e| format!("complete-with-provider: serialize failed: {e}"))
                 });
@@ -543,7 +549,7 @@ mod tests {
             vec![ToolResult {
                 success: true,
                 output: Some(serde_json::json!({"prompt": "hello from test"})),
-                error: None,
+                ..Default::default()
             }],
         ));
         coordinator.mount_tool("echo-tool", echo);
@@ -5,6 +5,11 @@
 //! exports `get-info` (returns JSON-serialized `ProviderInfo` bytes), `list-models`,
 //! `complete`, and `parse-tool-calls`.
 //!
+//! Credentials declared in [`ProviderInfo::credential_env_vars`] can be
+//! resolved through a [`CredentialResolver`] with
+//! [`WasmProviderBridge::with_credentials`]; they are exposed to the guest as
+//! WASI environment variables for `list-models` and `complete`.
+//!
 //! Gated behind the `wasm` feature flag.
 
 use std::future::Future;
@@ -15,12 +20,13 @@ use std::sync::Arc;
 use wasmtime::component::Component;
 use wasmtime::Engine;
 
+use crate::credentials::{resolve_provider_credentials, CredentialResolver, SecretString};
 use crate::errors::ProviderError;
 use crate::messages::{ChatRequest, ChatResponse, ToolCall};
 use crate::models::{ModelInfo, ProviderInfo};
 use crate::traits::Provider;
 
-use super::wasm_tool::create_linker_and_store;
+use super::wasm_tool::{create_linker_and_store, create_linker_and_store_with_env};
 
 /// The WIT interface name used by `cargo component` for provider exports.
 const INTERFACE_NAME: &str = "amplifier:modules/provider@1.0.0";
@@ -55,11 +61,24 @@ fn call_get_info(engine: &Engine, component: &Component) -> WasmResult<Vec<u8>>
     Ok(info_bytes)
 }
 
+/// Expose resolved credentials as `(name, value)` WASI environment pairs.
+fn credential_env(credentials: &[(String, SecretString)]) -> Vec<(String, String)> {
+    credentials
+        .iter()
+        .map(|(name, secret)| (name.clone(), secret.expose_secret().to_string()))
+        .collect()
+}
+
 /// Helper: call `list-models` on a fresh component instance.
 ///
 /// Returns raw JSON bytes representing `Vec<ModelInfo>`.
-fn call_list_models(engine: &Engine, component: &Component) -> WasmResult<Vec<u8>> {
-    let (linker, mut store) = create_linker_and_store(engine, &super::WasmLimits::default())?;
+fn call_list_models(
+    engine: &Engine,
+    component: &Component,
+    env: &[(String, String)],
+) -> WasmResult<Vec<u8>> {
+    let (linker, mut store) =
+        create_linker_and_store_with_env(engine, &super::WasmLimits::default(), env)?;
     let instance = linker.instantiate(&mut store, component)?;
 
     let func = super::get_typed_func::<(), (Result<Vec<u8>, String>,)>(
@@ -83,8 +102,10 @@ fn call_complete(
     engine: &Engine,
     component: &Component,
     request_bytes: Vec<u8>,
+    env: &[(String, String)],
 ) -> WasmResult<Vec<u8>> {
-    let (linker, mut store) = create_linker_and_store(engine, &super::WasmLimits::default())?;
+    let (linker, mut store) =
+        create_linker_and_store_with_env(engine, &super::WasmLimits::default(), env)?;
     let instance = linker.instantiate(&mut store, component)?;
 
     let func = super::get_typed_func::<(Vec<u8>,), (Result<Vec<u8>, String>,)>(
@@ -139,6 +160,8 @@ pub struct WasmProviderBridge {
     name: String,
     /// Provider metadata, cached at load time from `get-info`.
     info: ProviderInfo,
+    /// Credentials resolved for `info.credential_env_vars`.
+    credentials: Arc<Vec<(String, SecretString)>>,
 }
 
 impl WasmProviderBridge {
@@ -165,9 +188,25 @@ impl WasmProviderBridge {
             component,
             name,
             info,
+            credentials: Arc::new(Vec::new()),
         })
     }
 
+    /// Resolve the credentials the provider declares through `resolver`.
+    ///
+    /// Credentials the resolver does not have are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the resolver itself fails.
+    pub fn with_credentials(
+        mut self,
+        resolver: &dyn CredentialResolver,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        self.credentials = Arc::new(resolve_provider_credentials(resolver, &self.info)?);
+        Ok(self)
+    }
+
     /// Convenience: load a WASM provider component from a file path.
     pub fn from_file(
         path: &Path,
@@ -194,14 +233,16 @@ impl Provider for WasmProviderBridge {
         Box::pin(async move {
             let engine = Arc::clone(&self.engine);
             let component = self.component.clone(); // Component is Arc-backed, cheap clone
+            let credentials = Arc::clone(&self.credentials);
 
-            let result_bytes =
-                tokio::task::spawn_blocking(move || call_list_models(&engine, &component))
-                    .await
-                    .map_err(|e| {
-                        wasm_provider_error(format!("WASM provider list-models task panicked: {e}"))
-                    })?
-                    .map_err(|e| wasm_provider_error(format!("WASM list-models failed: {e}")))?;
+            let result_bytes = tokio::task::spawn_blocking(move || {
+                call_list_models(&engine, &component, &credential_env(&credentials))
+            })
+            .await
+            .map_err(|e| {
+                wasm_provider_error(format!("WASM provider list-models task panicked: {e}"))
+            })?
+            .map_err(|e| wasm_provider_error(format!("WASM list-models failed: {e}")))?;
 
             let models: Vec<ModelInfo> = serde_json::from_slice(&result_bytes).map_err(|e| {
                 wasm_provider_error(format!(
@@ -227,9 +268,15 @@ impl Provider for WasmProviderBridge {
 
             let engine = Arc::clone(&self.engine);
             let component = self.component.clone();
+            let credentials = Arc::clone(&self.credentials);
 
             let result_bytes = tokio::task::spawn_blocking(move || {
-                call_complete(&engine, &component, request_bytes)
+                call_complete(
+                    &engine,
+                    &component,
+                    request_bytes,
+                    &credential_env(&credentials),
+                )
             })
             .await
             .map_err(|e| wasm_provider_error(format!("WASM provider complete task panicked: {e}")))?
@@ -385,6 +432,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -61,6 +61,18 @@ pub struct WasmToolBridge {
 pub(crate) fn create_linker_and_store(
     engine: &Engine,
     limits: &super::WasmLimits,
+) -> Result<(Linker<WasmState>, Store<WasmState>), Box<dyn std::error::Error + Send + Sync>> {
+    create_linker_and_store_with_env(engine, limits, &[])
+}
+
+/// [`create_linker_and_store`] with explicit environment variables.
+///
+/// Only `env` is visible to the guest; the host environment is still not
+/// inherited (H-01). Used to hand resolved credentials to WASM providers.
+pub(crate) fn create_linker_and_store_with_env(
+    engine: &Engine,
+    limits: &super::WasmLimits,
+    env: &[(String, String)],
 ) -> Result<(Linker<WasmState>, Store<WasmState>), Box<dyn std::error::Error + Send + Sync>> {
     let mut linker = Linker::<WasmState>::new(engine);
     wasmtime_wasi::p2::add_to_linker_sync(&mut linker)?;
@@ -70,6 +82,7 @@ pub(crate) fn create_linker_and_store(
         .stdin(wasmtime_wasi::p2::pipe::ClosedInputStream)
         .stdout(wasmtime_wasi::p2::pipe::SinkOutputStream)
         .stderr(wasmtime_wasi::p2::pipe::SinkOutputStream)
+        .envs(env)
         .build();
 
     let table = wasmtime::component::ResourceTable::new();
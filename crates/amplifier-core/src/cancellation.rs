@@ -19,8 +19,12 @@
 //!
 //! - Lives inside `Coordinator` (future `crate::coordinator`).
 //! - Orchestrators and tools check `is_cancelled` / `is_graceful` /
-//!   `is_immediate` to decide how to respond.
+//!   `is_immediate` to decide how to respond, or await
+//!   [`CancellationToken::cancelled`] to be woken.
 //! - Child tokens propagate parent cancellation to forked sessions.
+//! - [`CancellationToken::on_state_change`] observers are notified of every
+//!   transition; the `Coordinator` uses one to emit `cancel:requested` /
+//!   `cancel:escalated`.
 
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
@@ -28,6 +32,7 @@ use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 
 use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
 
 // ---------------------------------------------------------------------------
 // CancellationState
@@ -57,6 +62,12 @@ pub enum CancellationState {
 /// Stored in the token and triggered via [`CancellationToken::trigger_callbacks`].
 pub type CancelCallback = Box<dyn Fn() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
 
+/// A synchronous state-change observer: `(previous, current)`.
+///
+/// Called on the thread that made the transition, after the token's lock is
+/// released. Must not block; spawn a task for async work.
+pub type StateChangeCallback = Arc<dyn Fn(CancellationState, CancellationState) + Send + Sync>;
+
 // ---------------------------------------------------------------------------
 // Inner state (behind Mutex)
 // ---------------------------------------------------------------------------
@@ -68,6 +79,7 @@ struct Inner {
     running_tool_names: HashMap<String, String>,
     child_tokens: Vec<CancellationToken>,
     on_cancel_callbacks: Vec<CancelCallback>,
+    state_change_callbacks: Vec<StateChangeCallback>,
 }
 
 impl Inner {
@@ -78,6 +90,7 @@ impl Inner {
             running_tool_names: HashMap::new(),
             child_tokens: Vec::new(),
             on_cancel_callbacks: Vec::new(),
+            state_change_callbacks: Vec::new(),
         }
     }
 }
@@ -108,6 +121,8 @@ impl Inner {
 #[derive(Clone)]
 pub struct CancellationToken {
     inner: Arc<Mutex<Inner>>,
+    /// Wakes [`cancelled()`](Self::cancelled) waiters on every state change.
+    changed: Arc<Notify>,
 }
 
 impl CancellationToken {
@@ -115,6 +130,37 @@ impl CancellationToken {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(Inner::new())),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Wait until cancellation (graceful or immediate) is requested.
+    ///
+    /// Returns immediately if the token is already cancelled. Safe to use in
+    /// `tokio::select!` alongside the work being cancelled.
+    pub async fn cancelled(&self) {
+        self.wait_for(Self::is_cancelled).await;
+    }
+
+    /// Wait until immediate cancellation is requested.
+    ///
+    /// Graceful cancellation lets running tools finish, so tool execution
+    /// races against this rather than [`cancelled()`](Self::cancelled).
+    pub async fn cancelled_immediately(&self) {
+        self.wait_for(Self::is_immediate).await;
+    }
+
+    async fn wait_for(&self, done: fn(&Self) -> bool) {
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            // Register interest before checking state so a concurrent
+            // request between the check and the await is not missed.
+            notified.as_mut().enable();
+            if done(self) {
+                return;
+            }
+            notified.await;
         }
     }
 
@@ -148,7 +194,14 @@ impl CancellationToken {
             // Propagate to children while still holding lock on our state,
             // but we must clone children to avoid holding two locks.
             let children: Vec<CancellationToken> = inner.child_tokens.clone();
+            let observers = inner.state_change_callbacks.clone();
             drop(inner);
+            self.changed.notify_waiters();
+            notify_observers(
+                &observers,
+                CancellationState::None,
+                CancellationState::Graceful,
+            );
             for child in &children {
                 child.request_graceful();
             }
@@ -164,9 +217,13 @@ impl CancellationToken {
     pub fn request_immediate(&self) -> bool {
         let mut inner = self.inner.lock().unwrap();
         if inner.state != CancellationState::Immediate {
+            let previous = inner.state;
             inner.state = CancellationState::Immediate;
             let children: Vec<CancellationToken> = inner.child_tokens.clone();
+            let observers = inner.state_change_callbacks.clone();
             drop(inner);
+            self.changed.notify_waiters();
+            notify_observers(&observers, previous, CancellationState::Immediate);
             for child in &children {
                 child.request_immediate();
             }
@@ -182,9 +239,14 @@ impl CancellationToken {
     /// (those are session-level, matching Python behaviour).
     pub fn reset(&self) {
         let mut inner = self.inner.lock().unwrap();
-        inner.state = CancellationState::None;
+        let previous = std::mem::take(&mut inner.state);
         inner.running_tools.clear();
         inner.running_tool_names.clear();
+        let observers = inner.state_change_callbacks.clone();
+        drop(inner);
+        if previous != CancellationState::None {
+            notify_observers(&observers, previous, CancellationState::None);
+        }
     }
 
     // -- Tool tracking ---
@@ -265,6 +327,18 @@ impl CancellationToken {
             .push(callback);
     }
 
+    /// Register an observer called on every state transition, including
+    /// [`reset()`](Self::reset) back to `None`.
+    ///
+    /// Observers are session-level: `reset()` keeps them.
+    pub fn on_state_change(&self, callback: StateChangeCallback) {
+        self.inner
+            .lock()
+            .unwrap()
+            .state_change_callbacks
+            .push(callback);
+    }
+
     /// Trigger all registered cancellation callbacks.
     ///
     /// Errors (including panics) in one callback do not prevent subsequent
@@ -287,6 +361,24 @@ impl CancellationToken {
     }
 }
 
+/// Call each observer, isolating panics so one bad observer cannot poison
+/// the others or the caller's transition.
+fn notify_observers(
+    observers: &[StateChangeCallback],
+    previous: CancellationState,
+    current: CancellationState,
+) {
+    for observer in observers {
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| observer(previous, current)));
+        if result.is_err() {
+            log::error!(
+                "Cancellation state-change observer panicked ({previous:?} -> {current:?})"
+            );
+        }
+    }
+}
+
 impl Default for CancellationToken {
     fn default() -> Self {
         Self::new()
@@ -499,6 +591,50 @@ mod tests {
         assert!(called.load(Ordering::SeqCst));
     }
 
+    // ---------------------------------------------------------------
+    // State-change observers
+    // ---------------------------------------------------------------
+
+    type Transitions = Arc<Mutex<Vec<(CancellationState, CancellationState)>>>;
+
+    fn recording_token() -> (CancellationToken, Transitions) {
+        let token = CancellationToken::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        token.on_state_change(Arc::new(move |from, to| {
+            sink.lock().unwrap().push((from, to))
+        }));
+        (token, seen)
+    }
+
+    #[test]
+    fn state_change_observer_sees_each_transition() {
+        let (token, seen) = recording_token();
+        token.request_graceful();
+        token.request_graceful(); // no-op, not reported
+        token.request_immediate();
+        token.reset();
+
+        use CancellationState::*;
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(None, Graceful), (Graceful, Immediate), (Immediate, None)]
+        );
+    }
+
+    #[test]
+    fn panicking_observer_does_not_block_others() {
+        let token = CancellationToken::new();
+        token.on_state_change(Arc::new(|_, _| panic!("observer bug")));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        token.on_state_change(Arc::new(move |_, to| sink.lock().unwrap().push(to)));
+
+        assert!(token.request_immediate());
+        assert!(token.is_immediate());
+        assert_eq!(*seen.lock().unwrap(), vec![CancellationState::Immediate]);
+    }
+
     // ---------------------------------------------------------------
     // Thread safety
     // ---------------------------------------------------------------
@@ -530,4 +666,32 @@ mod tests {
         // Token should be in graceful state
         assert!(token.is_cancelled());
     }
+
+    // ---------------------------------------------------------------
+    // Awaiting cancellation
+    // ---------------------------------------------------------------
+
+    #[tokio::test]
+    async fn cancelled_returns_immediately_when_already_cancelled() {
+        let token = CancellationToken::new();
+        token.request_immediate();
+        tokio::time::timeout(std::time::Duration::from_secs(1), token.cancelled())
+            .await
+            .expect("cancelled() should not block");
+    }
+
+    #[tokio::test]
+    async fn cancelled_wakes_on_request_from_another_task() {
+        let token = CancellationToken::new();
+        let waiter = {
+            let t = token.clone();
+            tokio::spawn(async move { t.cancelled().await })
+        };
+        tokio::task::yield_now().await;
+        token.request_graceful();
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should wake")
+            .unwrap();
+    }
 }
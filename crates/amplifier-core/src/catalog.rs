@@ -0,0 +1,483 @@
+//! Module catalogs and static mount-plan validation.
+//!
+//! A [`ModuleCatalog`] lists the modules a deployment knows about — their
+//! [`ModuleDescriptor`] (type, dependencies) and the [`ConfigField`]s they
+//! accept. [`validate_mount_plan`] (or
+//! [`SessionConfig::validate_mount_plan`](crate::session::SessionConfig::validate_mount_plan))
+//! checks a mount plan against it without loading anything, so CI can reject
+//! a broken config before it reaches a session. Every problem is reported,
+//! not just the first.
+//!
+//! | Plan location          | Expected type  | Config read from         |
+//! |------------------------|----------------|--------------------------|
+//! | `session.orchestrator` | `orchestrator` | `orchestrator.config`    |
+//! | `session.context`      | `context`      | `context.config`         |
+//! | `providers[i]`         | `provider`     | `providers[i].config`    |
+//! | `tools[i]`             | `tool`         | `tools[i].config`        |
+//! | `hooks[i]`             | `hook`         | `hooks[i].config`        |
+//!
+//! A required field is satisfied by a config value, a `default`, or an
+//! `env_var` (whose value is only known at runtime). Fields with `show_when`
+//! are only checked when every condition matches the module's config.
+//! `agents` overlays are not validated: the kernel passes them through.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::manifest::ModuleDescriptor;
+use crate::models::{ConfigField, ConfigFieldType, ModuleType};
+
+// ---------------------------------------------------------------------------
+// ModuleCatalog
+// ---------------------------------------------------------------------------
+
+/// One known module.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    pub descriptor: ModuleDescriptor,
+    /// Config fields the module accepts.
+    pub config_fields: Vec<ConfigField>,
+}
+
+/// Known modules, by ID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleCatalog {
+    entries: BTreeMap<String, CatalogEntry>,
+}
+
+impl ModuleCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a module (replacing any entry with the same ID).
+    pub fn with_module(
+        mut self,
+        descriptor: ModuleDescriptor,
+        config_fields: Vec<ConfigField>,
+    ) -> Self {
+        self.insert(descriptor, config_fields);
+        self
+    }
+
+    /// Add a module (replacing any entry with the same ID).
+    pub fn insert(&mut self, descriptor: ModuleDescriptor, config_fields: Vec<ConfigField>) {
+        self.entries.insert(
+            descriptor.id().to_string(),
+            CatalogEntry {
+                descriptor,
+                config_fields,
+            },
+        );
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CatalogEntry> {
+        self.entries.get(id)
+    }
+
+    /// Every entry, by ID.
+    pub fn iter(&self) -> impl Iterator<Item = &CatalogEntry> {
+        self.entries.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl FromIterator<ModuleDescriptor> for ModuleCatalog {
+    /// A catalog of modules without config field definitions.
+    fn from_iter<I: IntoIterator<Item = ModuleDescriptor>>(iter: I) -> Self {
+        let mut catalog = Self::new();
+        for descriptor in iter {
+            catalog.insert(descriptor, Vec::new());
+        }
+        catalog
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Problems
+// ---------------------------------------------------------------------------
+
+/// One mount-plan problem. `location` is the plan path, e.g. `tools[2]`.
+#[derive(Debug, Clone, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MountPlanProblem {
+    /// The entry is not a module reference.
+    #[error("{location}: {reason}")]
+    InvalidEntry { location: String, reason: String },
+
+    /// The catalog has no module with this ID.
+    #[error("{location}: unknown module '{module}'")]
+    UnknownModule { location: String, module: String },
+
+    /// The module is listed in a section for a different module type.
+    #[error("{location}: module '{module}' is a {declared:?} module, expected {expected:?}")]
+    WrongSection {
+        location: String,
+        module: String,
+        declared: ModuleType,
+        expected: ModuleType,
+    },
+
+    /// The module appears twice in one section.
+    #[error("{location}: module '{module}' is already listed")]
+    DuplicateModule { location: String, module: String },
+
+    /// A required dependency is not in the plan.
+    #[error("{location}: module '{module}' depends on '{dependency}', which is not in the plan")]
+    MissingDependency {
+        location: String,
+        module: String,
+        dependency: String,
+    },
+
+    /// A required config field has no value, default or env var.
+    #[error("{location}: module '{module}' requires config field '{field}'")]
+    MissingConfigField {
+        location: String,
+        module: String,
+        field: String,
+    },
+
+    /// A config value does not fit its field definition.
+    #[error("{location}: config field '{field}' of module '{module}' {reason}")]
+    InvalidConfigValue {
+        location: String,
+        module: String,
+        field: String,
+        reason: String,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// A module reference found in the plan.
+struct PlanEntry<'a> {
+    location: String,
+    module: &'a str,
+    expected: ModuleType,
+    config: Option<&'a Map<String, Value>>,
+}
+
+/// Check the mount plan `config` against `catalog`. Returns every problem
+/// found, in plan order; an empty list means the plan is valid.
+pub fn validate_mount_plan(
+    config: &HashMap<String, Value>,
+    catalog: &ModuleCatalog,
+) -> Vec<MountPlanProblem> {
+    let mut problems = Vec::new();
+    let entries = plan_entries(config, &mut problems);
+    let planned: HashSet<&str> = entries.iter().map(|e| e.module).collect();
+
+    let mut seen: HashSet<(&str, &str)> = HashSet::new();
+    for entry in &entries {
+        let section = entry.location.split('[').next().unwrap_or_default();
+        if !seen.insert((section, entry.module)) {
+            problems.push(MountPlanProblem::DuplicateModule {
+                location: entry.location.clone(),
+                module: entry.module.to_string(),
+            });
+        }
+        let Some(known) = catalog.get(entry.module) else {
+            problems.push(MountPlanProblem::UnknownModule {
+                location: entry.location.clone(),
+                module: entry.module.to_string(),
+            });
+            continue;
+        };
+
+        let declared = &known.descriptor.info.module_type;
+        if *declared != entry.expected {
+            problems.push(MountPlanProblem::WrongSection {
+                location: entry.location.clone(),
+                module: entry.module.to_string(),
+                declared: declared.clone(),
+                expected: entry.expected.clone(),
+            });
+        }
+        for dependency in &known.descriptor.depends_on {
+            if !planned.contains(dependency.as_str()) {
+                problems.push(MountPlanProblem::MissingDependency {
+                    location: entry.location.clone(),
+                    module: entry.module.to_string(),
+                    dependency: dependency.clone(),
+                });
+            }
+        }
+        for field in &known.config_fields {
+            check_field(entry, field, &mut problems);
+        }
+    }
+    problems
+}
+
+/// Collect the plan's module references, reporting malformed ones.
+fn plan_entries<'a>(
+    config: &'a HashMap<String, Value>,
+    problems: &mut Vec<MountPlanProblem>,
+) -> Vec<PlanEntry<'a>> {
+    let mut entries = Vec::new();
+    let session = config.get("session");
+    for (key, expected) in [
+        ("orchestrator", ModuleType::Orchestrator),
+        ("context", ModuleType::Context),
+    ] {
+        let location = format!("session.{key}");
+        match session.and_then(|s| s.get(key)) {
+            Some(Value::String(module)) => entries.push(PlanEntry {
+                location,
+                module,
+                expected,
+                config: config
+                    .get(key)
+                    .and_then(|section| section.get("config"))
+                    .and_then(Value::as_object),
+            }),
+            Some(_) => problems.push(MountPlanProblem::InvalidEntry {
+                location,
+                reason: "must be a module ID string".into(),
+            }),
+            None => problems.push(MountPlanProblem::InvalidEntry {
+                location,
+                reason: "is required".into(),
+            }),
+        }
+    }
+
+    for (section, expected) in [
+        ("providers", ModuleType::Provider),
+        ("tools", ModuleType::Tool),
+        ("hooks", ModuleType::Hook),
+    ] {
+        let Some(list) = config.get(section) else {
+            continue;
+        };
+        let Some(items) = list.as_array() else {
+            problems.push(MountPlanProblem::InvalidEntry {
+                location: section.to_string(),
+                reason: "must be a list".into(),
+            });
+            continue;
+        };
+        for (i, item) in items.iter().enumerate() {
+            let location = format!("{section}[{i}]");
+            match item.get("module").and_then(Value::as_str) {
+                Some(module) => entries.push(PlanEntry {
+                    location,
+                    module,
+                    expected: expected.clone(),
+                    config: item.get("config").and_then(Value::as_object),
+                }),
+                None => problems.push(MountPlanProblem::InvalidEntry {
+                    location,
+                    reason: "has no 'module' ID".into(),
+                }),
+            }
+        }
+    }
+    entries
+}
+
+/// A config value compared as text (`show_when` and choices are strings).
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn check_field(entry: &PlanEntry<'_>, field: &ConfigField, problems: &mut Vec<MountPlanProblem>) {
+    let value = entry
+        .config
+        .and_then(|config| config.get(&field.id))
+        .filter(|v| !v.is_null());
+    let shown = field.show_when.as_ref().is_none_or(|conditions| {
+        conditions.iter().all(|(key, expected)| {
+            entry
+                .config
+                .and_then(|config| config.get(key))
+                .is_some_and(|actual| as_text(actual) == *expected)
+        })
+    });
+
+    let Some(value) = value else {
+        if shown && field.required && field.default_value.is_none() && field.env_var.is_none() {
+            problems.push(MountPlanProblem::MissingConfigField {
+                location: entry.location.clone(),
+                module: entry.module.to_string(),
+                field: field.id.clone(),
+            });
+        }
+        return;
+    };
+
+    let invalid = |reason: String| MountPlanProblem::InvalidConfigValue {
+        location: entry.location.clone(),
+        module: entry.module.to_string(),
+        field: field.id.clone(),
+        reason,
+    };
+    match field.field_type {
+        ConfigFieldType::Choice => {
+            let choices = field.choices.as_deref().unwrap_or_default();
+            let text = as_text(value);
+            if !choices.is_empty() && !choices.contains(&text) {
+                problems.push(invalid(format!(
+                    "is '{text}', expected one of: {}",
+                    choices.join(", ")
+                )));
+            }
+        }
+        ConfigFieldType::Boolean => {
+            let is_bool = value.is_boolean() || matches!(value.as_str(), Some("true" | "false"));
+            if !is_bool {
+                problems.push(invalid(format!("must be a boolean, got {value}")));
+            }
+        }
+        ConfigFieldType::Text | ConfigFieldType::Secret => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn descriptor(json: Value) -> ModuleDescriptor {
+        ModuleDescriptor::from_json(&json!({ "module": json }).to_string(), "test").unwrap()
+    }
+
+    fn field(id: &str, field_type: ConfigFieldType) -> ConfigField {
+        ConfigField {
+            id: id.into(),
+            display_name: id.into(),
+            field_type,
+            prompt: id.into(),
+            env_var: None,
+            choices: None,
+            required: true,
+            default_value: None,
+            show_when: None,
+            requires_model: false,
+        }
+    }
+
+    fn catalog() -> ModuleCatalog {
+        ModuleCatalog::new()
+            .with_module(
+                descriptor(json!({"id": "loop-basic", "type": "orchestrator"})),
+                Vec::new(),
+            )
+            .with_module(
+                descriptor(json!({"id": "context-simple", "type": "context"})),
+                vec![ConfigField {
+                    default_value: Some("200000".into()),
+                    ..field("max_tokens", ConfigFieldType::Text)
+                }],
+            )
+            .with_module(
+                descriptor(json!({"id": "provider-anthropic", "type": "provider"})),
+                vec![
+                    ConfigField {
+                        env_var: Some("ANTHROPIC_API_KEY".into()),
+                        ..field("api_key", ConfigFieldType::Secret)
+                    },
+                    ConfigField {
+                        choices: Some(vec!["claude-a".into(), "claude-b".into()]),
+                        ..field("model", ConfigFieldType::Choice)
+                    },
+                    ConfigField {
+                        show_when: Some(HashMap::from([("model".into(), "claude-b".into())])),
+                        ..field("thinking_budget", ConfigFieldType::Text)
+                    },
+                ],
+            )
+            .with_module(
+                descriptor(json!({
+                    "id": "tool-search",
+                    "type": "tool",
+                    "depends_on": ["provider-embeddings"]
+                })),
+                vec![field("enabled", ConfigFieldType::Boolean)],
+            )
+    }
+
+    fn plan(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn valid_plan_has_no_problems() {
+        let config = plan(json!({
+            "session": {"orchestrator": "loop-basic", "context": "context-simple"},
+            "providers": [{"module": "provider-anthropic", "config": {"model": "claude-a"}}]
+        }));
+        assert_eq!(validate_mount_plan(&config, &catalog()), Vec::new());
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let config = plan(json!({
+            "session": {"orchestrator": "context-simple"},
+            "providers": [
+                {"module": "provider-anthropic", "config": {"model": "claude-b"}},
+                {"module": "provider-missing"},
+                {"source": "git+https://example.com/x"}
+            ],
+            "tools": [{"module": "tool-search", "config": {"enabled": "yes"}}]
+        }));
+        let problems = validate_mount_plan(&config, &catalog());
+        let rendered: Vec<String> = problems.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "session.context: is required",
+                "providers[2]: has no 'module' ID",
+                "session.orchestrator: module 'context-simple' is a Context module, expected Orchestrator",
+                "providers[0]: module 'provider-anthropic' requires config field 'thinking_budget'",
+                "providers[1]: unknown module 'provider-missing'",
+                "tools[0]: module 'tool-search' depends on 'provider-embeddings', which is not in the plan",
+                "tools[0]: config field 'enabled' of module 'tool-search' must be a boolean, got \"yes\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn choices_and_duplicates_are_checked() {
+        let config = plan(json!({
+            "session": {"orchestrator": "loop-basic", "context": "context-simple"},
+            "providers": [
+                {"module": "provider-anthropic", "config": {"model": "gpt"}},
+                {"module": "provider-anthropic", "config": {"model": "claude-a"}}
+            ]
+        }));
+        let problems = validate_mount_plan(&config, &catalog());
+        assert_eq!(problems.len(), 2);
+        assert!(matches!(
+            &problems[0],
+            MountPlanProblem::InvalidConfigValue { field, .. } if field == "model"
+        ));
+        assert!(matches!(
+            &problems[1],
+            MountPlanProblem::DuplicateModule { location, .. } if location == "providers[1]"
+        ));
+        assert_eq!(
+            serde_json::to_value(&problems[1]).unwrap()["kind"],
+            "duplicate_module"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Conversation checkpoints.
+//!
+//! [`Session::checkpoint`](crate::session::Session::checkpoint) snapshots the
+//! mounted context's message history and the coordinator's turn number;
+//! [`Session::rewind`](crate::session::Session::rewind) restores both, so an
+//! agent framework can branch a conversation and explore alternatives from
+//! the same point (tree search, retries with a different prompt).
+//!
+//! Checkpoints are kept in memory for the life of the session. Rewinding does
+//! not discard checkpoints taken after the target, so a caller can move
+//! between branches freely; [`CheckpointStore::remove`] drops ones no longer
+//! needed.
+//!
+//! # Connections
+//!
+//! - Messages are read with [`ContextManager::get_messages`] and restored with
+//!   [`ContextManager::set_messages`] (through the conversation store when
+//!   the session has one).
+//! - A rewind resets per-turn coordinator state (see
+//!   [`Coordinator::rewind_turn`](crate::coordinator::Coordinator::rewind_turn))
+//!   and emits `session:rewind`.
+//!
+//! [`ContextManager::get_messages`]: crate::traits::ContextManager::get_messages
+//! [`ContextManager::set_messages`]: crate::traits::ContextManager::set_messages
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Identifies a checkpoint within its session. Rendered as `cp-<n>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CheckpointId(pub u64);
+
+impl fmt::Display for CheckpointId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cp-{}", self.0)
+    }
+}
+
+/// A saved conversation state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub id: CheckpointId,
+    /// The context's raw message history.
+    pub messages: Vec<Value>,
+    /// [`Coordinator::turn_number`](crate::coordinator::Coordinator::turn_number)
+    /// when the checkpoint was taken.
+    pub turn_number: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A checkpoint without its messages, for listings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    pub id: CheckpointId,
+    pub message_count: usize,
+    pub turn_number: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Checkpoint> for CheckpointInfo {
+    fn from(checkpoint: &Checkpoint) -> Self {
+        Self {
+            id: checkpoint.id,
+            message_count: checkpoint.messages.len(),
+            turn_number: checkpoint.turn_number,
+            created_at: checkpoint.created_at,
+        }
+    }
+}
+
+/// A session's checkpoints, in creation order.
+#[derive(Debug, Default)]
+pub struct CheckpointStore {
+    next_id: AtomicU64,
+    checkpoints: Mutex<BTreeMap<CheckpointId, Checkpoint>>,
+}
+
+impl CheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Save a checkpoint and return its ID. IDs start at 1.
+    pub fn insert(
+        &self,
+        messages: Vec<Value>,
+        turn_number: u64,
+        created_at: DateTime<Utc>,
+    ) -> CheckpointId {
+        let id = CheckpointId(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let checkpoint = Checkpoint {
+            id,
+            messages,
+            turn_number,
+            created_at,
+        };
+        self.checkpoints.lock().unwrap().insert(id, checkpoint);
+        id
+    }
+
+    pub fn get(&self, id: CheckpointId) -> Option<Checkpoint> {
+        self.checkpoints.lock().unwrap().get(&id).cloned()
+    }
+
+    /// Drop a checkpoint. Returns whether it existed.
+    pub fn remove(&self, id: CheckpointId) -> bool {
+        self.checkpoints.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Summaries of all checkpoints, oldest first.
+    pub fn list(&self) -> Vec<CheckpointInfo> {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .values()
+            .map(CheckpointInfo::from)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.checkpoints.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_sequential_and_listed_in_order() {
+        let store = CheckpointStore::new();
+        let now = Utc::now();
+        let a = store.insert(vec![serde_json::json!({"role": "user"})], 0, now);
+        let b = store.insert(Vec::new(), 1, now);
+        assert_eq!(a, CheckpointId(1));
+        assert_eq!(b.to_string(), "cp-2");
+
+        let listed = store.list();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].message_count, 1);
+        assert_eq!(listed[1].turn_number, 1);
+
+        assert!(store.remove(a));
+        assert!(!store.remove(a));
+        assert!(store.get(a).is_none());
+        assert_eq!(store.get(b).unwrap().turn_number, 1);
+    }
+}
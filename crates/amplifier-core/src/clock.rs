@@ -0,0 +1,84 @@
+//! Clock — the kernel's source of time.
+//!
+//! Everything in the kernel that reads the current time or waits for a
+//! duration goes through a [`Clock`], so tests can substitute a manual clock
+//! (see [`ManualClock`](crate::testing::ManualClock)) and get deterministic
+//! timestamps and timeouts.
+//!
+//! | Consumer                                          | Uses                      |
+//! |---------------------------------------------------|---------------------------|
+//! | [`HookRegistry::emit`](crate::hooks::HookRegistry::emit) | `now_utc` for `timestamp` |
+//! | [`ApprovalGate`](crate::approval::ApprovalGate)   | `sleep` for request timeouts |
+//! | [`TurnDeadline`](crate::deadline::TurnDeadline)   | `now` / `sleep` for expiry |
+//!
+//! The clock is owned by the [`HookRegistry`](crate::hooks::HookRegistry) and
+//! exposed through
+//! [`Coordinator::clock`](crate::coordinator::Coordinator::clock); the other
+//! consumers pick it up from there.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+/// A source of wall-clock time, monotonic time, and timers.
+pub trait Clock: Send + Sync {
+    /// Current wall-clock time (used for event timestamps).
+    fn now_utc(&self) -> DateTime<Utc>;
+
+    /// Current monotonic time (used for deadlines).
+    fn now(&self) -> Instant;
+
+    /// A future that completes once `duration` has elapsed on this clock.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+}
+
+/// The real clock: `chrono::Utc::now`, `Instant::now`, and tokio timers.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// The shared [`SystemClock`] instance.
+pub fn system() -> Arc<dyn Clock> {
+    static SYSTEM: OnceLock<Arc<dyn Clock>> = OnceLock::new();
+    SYSTEM.get_or_init(|| Arc::new(SystemClock)).clone()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn system_clock_sleep_waits() {
+        let clock = system();
+        let start = clock.now();
+        clock.sleep(Duration::from_millis(10)).await;
+        assert!(clock.now() - start >= Duration::from_millis(10));
+    }
+
+    #[test]
+    fn system_clock_tracks_utc() {
+        let before = Utc::now();
+        let now = SystemClock.now_utc();
+        assert!(now >= before);
+    }
+}
@@ -0,0 +1,347 @@
+//! Versioned mount plans and their migrations.
+//!
+//! A mount plan records its format in a top-level `config_version` (plans
+//! without one are version 1). When the format changes incompatibly, the
+//! change ships with a migration from the old version, so saved sessions
+//! and stored plans keep loading:
+//!
+//! ```json
+//! {"config_version": 1, "session": {"orchestrator": "loop-basic", "context": "context-simple"}}
+//! ```
+//!
+//! [`SessionConfig::from_value`](crate::session::SessionConfig::from_value)
+//! runs [`ConfigMigrations::builtin`] before validating, so an older plan is
+//! upgraded step by step (1 → 2 → ...) to the current version, which is then
+//! written back to `config_version`. A plan newer than the target is
+//! rejected rather than misread.
+//!
+//! Keys can also be marked deprecated. They still load, but each one found
+//! after migrating is logged as a warning and listed in the
+//! [`MigrationReport`].
+//!
+//! Hosts with plan extensions of their own, or that rewrite stored plans
+//! ahead of time, build on the kernel's set:
+//!
+//! ```rust
+//! use amplifier_core::config_migration::ConfigMigrations;
+//! use amplifier_core::session::SessionConfig;
+//! use serde_json::json;
+//!
+//! let migrations = ConfigMigrations::builtin()
+//!     .register(1, "rename session.loop to session.orchestrator", |plan| {
+//!         let session = plan.get_mut("session").and_then(|s| s.as_object_mut());
+//!         if let Some(session) = session {
+//!             if let Some(orchestrator) = session.remove("loop") {
+//!                 session.insert("orchestrator".into(), orchestrator);
+//!             }
+//!         }
+//!         Ok(())
+//!     })
+//!     .deprecate("session.verbose", "use session.hooks.filter instead");
+//!
+//! let config = SessionConfig::from_value_with_migrations(
+//!     json!({"session": {"loop": "loop-basic", "context": "context-simple"}}),
+//!     &migrations,
+//! )
+//! .unwrap();
+//! assert_eq!(config.config["config_version"], 2);
+//! assert_eq!(config.config["session"]["orchestrator"], "loop-basic");
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Top-level mount plan key holding the format version.
+pub const CONFIG_VERSION_KEY: &str = "config_version";
+
+/// The mount plan format this kernel reads natively.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// Upgrades a plan by one version, in place.
+pub type MigrationFn = Arc<dyn Fn(&mut Map<String, Value>) -> Result<(), String> + Send + Sync>;
+
+/// Why a plan could not be migrated.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize)]
+pub enum ConfigMigrationError {
+    /// `config_version` is not a positive integer.
+    #[error("config_version must be a positive integer, got {value}")]
+    InvalidVersion { value: String },
+
+    /// The plan is newer than anything this build understands.
+    #[error("config_version {version} is newer than the supported version {supported}")]
+    TooNew { version: u32, supported: u32 },
+
+    /// No migration is registered from this version.
+    #[error("no migration from config_version {from}")]
+    MissingMigration { from: u32 },
+
+    /// A migration failed.
+    #[error("migration from config_version {from} failed: {message}")]
+    Failed { from: u32, message: String },
+}
+
+/// What [`ConfigMigrations::migrate`] did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Version the plan had.
+    pub from: u32,
+    /// Version the plan has now.
+    pub to: u32,
+    /// Descriptions of the migrations applied, in order.
+    pub applied: Vec<String>,
+    /// One warning per deprecated key found.
+    pub warnings: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Whether the plan was changed.
+    pub fn migrated(&self) -> bool {
+        !self.applied.is_empty()
+    }
+}
+
+#[derive(Clone)]
+struct Migration {
+    description: String,
+    apply: MigrationFn,
+}
+
+#[derive(Debug, Clone)]
+struct DeprecatedKey {
+    path: String,
+    note: String,
+}
+
+/// Migrations between plan versions, and deprecated keys.
+#[derive(Clone)]
+pub struct ConfigMigrations {
+    /// By the version they migrate from.
+    migrations: BTreeMap<u32, Migration>,
+    deprecated: Vec<DeprecatedKey>,
+    target: u32,
+}
+
+impl ConfigMigrations {
+    /// Migrations up to `target`, with none registered.
+    pub fn new(target: u32) -> Self {
+        Self {
+            migrations: BTreeMap::new(),
+            deprecated: Vec::new(),
+            target,
+        }
+    }
+
+    /// The kernel's migrations, up to [`CURRENT_CONFIG_VERSION`].
+    pub fn builtin() -> Self {
+        Self::new(CURRENT_CONFIG_VERSION)
+    }
+
+    /// Register the migration from version `from` to `from + 1`, raising
+    /// the target to `from + 1` if needed. Replaces any migration already
+    /// registered from `from`.
+    pub fn register(
+        mut self,
+        from: u32,
+        description: impl Into<String>,
+        apply: impl Fn(&mut Map<String, Value>) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.migrations.insert(
+            from,
+            Migration {
+                description: description.into(),
+                apply: Arc::new(apply),
+            },
+        );
+        self.target = self.target.max(from + 1);
+        self
+    }
+
+    /// Warn when the dotted `path` (e.g. `"session.hooks.replay"`) is set.
+    pub fn deprecate(mut self, path: impl Into<String>, note: impl Into<String>) -> Self {
+        self.deprecated.push(DeprecatedKey {
+            path: path.into(),
+            note: note.into(),
+        });
+        self
+    }
+
+    /// The version plans are migrated to.
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    /// Upgrade `plan` to [`target`](Self::target) and check it for
+    /// deprecated keys (each logged as a warning).
+    ///
+    /// A plan that is not a JSON object is left alone. On error `plan` may
+    /// be partly migrated.
+    ///
+    /// # Errors
+    ///
+    /// Any [`ConfigMigrationError`].
+    pub fn migrate(&self, plan: &mut Value) -> Result<MigrationReport, ConfigMigrationError> {
+        let Some(plan) = plan.as_object_mut() else {
+            return Ok(MigrationReport::default());
+        };
+        let from = version_of(plan)?;
+        if from > self.target {
+            return Err(ConfigMigrationError::TooNew {
+                version: from,
+                supported: self.target,
+            });
+        }
+        let mut report = MigrationReport {
+            from,
+            to: from,
+            ..Default::default()
+        };
+        while report.to < self.target {
+            let version = report.to;
+            let migration = self
+                .migrations
+                .get(&version)
+                .ok_or(ConfigMigrationError::MissingMigration { from: version })?;
+            (migration.apply)(plan).map_err(|message| ConfigMigrationError::Failed {
+                from: version,
+                message,
+            })?;
+            log::info!(
+                "Migrated mount plan from config_version {version}: {}",
+                migration.description
+            );
+            report.applied.push(migration.description.clone());
+            report.to += 1;
+        }
+        if report.migrated() || plan.contains_key(CONFIG_VERSION_KEY) {
+            plan.insert(CONFIG_VERSION_KEY.into(), Value::from(report.to));
+        }
+
+        for key in &self.deprecated {
+            if lookup(plan, &key.path).is_some() {
+                let warning = format!("{} is deprecated: {}", key.path, key.note);
+                log::warn!("Mount plan: {warning}");
+                report.warnings.push(warning);
+            }
+        }
+        Ok(report)
+    }
+}
+
+impl Default for ConfigMigrations {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl fmt::Debug for ConfigMigrations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigMigrations")
+            .field("from", &self.migrations.keys().collect::<Vec<_>>())
+            .field("deprecated", &self.deprecated)
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+fn version_of(plan: &Map<String, Value>) -> Result<u32, ConfigMigrationError> {
+    match plan.get(CONFIG_VERSION_KEY) {
+        None => Ok(1),
+        Some(value) => value
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v > 0)
+            .ok_or_else(|| ConfigMigrationError::InvalidVersion {
+                value: value.to_string(),
+            }),
+    }
+}
+
+fn lookup<'a>(plan: &'a Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let first = plan.get(parts.next()?)?;
+    parts.try_fold(first, |value, part| value.get(part))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn migrations() -> ConfigMigrations {
+        ConfigMigrations::builtin()
+            .register(1, "move session.replay under session.hooks", |plan| {
+                let session = plan
+                    .get_mut("session")
+                    .and_then(Value::as_object_mut)
+                    .ok_or("no session section")?;
+                if let Some(replay) = session.remove("replay") {
+                    session.insert("hooks".into(), json!({"replay": replay}));
+                }
+                Ok(())
+            })
+            .register(2, "drop session.legacy", |plan| {
+                if let Some(session) = plan.get_mut("session").and_then(Value::as_object_mut) {
+                    session.remove("legacy");
+                }
+                Ok(())
+            })
+            .deprecate(
+                "session.hooks.replay",
+                "size the replay buffer per subscriber",
+            )
+    }
+
+    #[test]
+    fn older_plans_are_migrated_step_by_step() {
+        let mut plan = json!({"session": {"replay": 10, "legacy": true}});
+        let report = migrations().migrate(&mut plan).unwrap();
+        assert_eq!((report.from, report.to), (1, 3));
+        assert_eq!(report.applied.len(), 2);
+        assert_eq!(
+            plan,
+            json!({"config_version": 3, "session": {"hooks": {"replay": 10}}})
+        );
+        assert_eq!(report.warnings.len(), 1);
+
+        // Already current: nothing to do.
+        let report = migrations().migrate(&mut plan).unwrap();
+        assert!(!report.migrated());
+    }
+
+    #[test]
+    fn unusable_versions_are_rejected() {
+        let mut newer = json!({"config_version": 4, "session": {}});
+        assert_eq!(
+            migrations().migrate(&mut newer),
+            Err(ConfigMigrationError::TooNew {
+                version: 4,
+                supported: 3
+            })
+        );
+        let mut bad = json!({"config_version": "two"});
+        assert!(matches!(
+            migrations().migrate(&mut bad),
+            Err(ConfigMigrationError::InvalidVersion { .. })
+        ));
+        let mut gap = json!({"session": {}});
+        assert_eq!(
+            ConfigMigrations::new(3)
+                .register(2, "noop", |_| Ok(()))
+                .migrate(&mut gap),
+            Err(ConfigMigrationError::MissingMigration { from: 1 })
+        );
+        let mut no_session = json!({});
+        assert!(matches!(
+            migrations().migrate(&mut no_session),
+            Err(ConfigMigrationError::Failed { from: 1, .. })
+        ));
+    }
+}
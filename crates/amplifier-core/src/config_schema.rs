@@ -0,0 +1,824 @@
+//! JSON Schema for mount plans, for rendering configuration forms.
+//!
+//! [`describe_config_schema`] emits a single JSON Schema (draft 2020-12)
+//! document for a whole mount plan, so GUIs and CLIs in any binding can
+//! render and validate configuration generically:
+//!
+//! - every typed `session.*` section the kernel reads, with defaults taken
+//!   from the section structs themselves;
+//! - the `config` of every module in a [`ModuleCatalog`], built from its
+//!   [`ConfigField`]s (or its manifest's `config_schema` when it has none).
+//!   [`Coordinator::describe_config_schema`](crate::coordinator::Coordinator::describe_config_schema)
+//!   does this for the mounted providers.
+//!
+//! Module configs live under `$defs.modules.<id>` and apply to plan entries
+//! whose `module` is that ID. [`ConfigField`] details JSON Schema cannot
+//! express are kept as `x-env-var`, `x-show-when` and `x-requires-model`.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::approval_memory::ApprovalMemoryConfig;
+use crate::attachments::AttachmentConfig;
+use crate::audit::AuditConfig;
+use crate::catalog::ModuleCatalog;
+use crate::config_migration::CURRENT_CONFIG_VERSION;
+use crate::context_dedup::DedupConfig;
+use crate::event_filter::EventFilterConfig;
+use crate::heartbeat::HeartbeatConfig;
+use crate::hooks::builtin::{ContentFilterConfig, LoggingConfig, ModerationConfig};
+use crate::hooks::latency::LatencyBudgetConfig;
+use crate::injection_queue::InjectionQueueConfig;
+use crate::memory::MemoryConfig;
+use crate::model_catalog::ModelCatalogConfig;
+use crate::models::{ConfigField, ConfigFieldType, ModuleType};
+use crate::notifications::NotificationConfig;
+use crate::policy::PolicyConfig;
+use crate::prompt_history::PromptHistoryConfig;
+use crate::provenance::ProvenanceConfig;
+use crate::quota::QuotaConfig;
+use crate::recovery::TurnRecoveryPolicy;
+use crate::safety::SafetyConfig;
+use crate::streaming::StreamEventConfig;
+use crate::structured_output::StructuredOutputConfig;
+use crate::summarizer::SummarizationConfig;
+use crate::telemetry::TelemetryConfig;
+use crate::tool_cache::PureToolCacheConfig;
+use crate::tool_discovery::ToolDiscoveryConfig;
+use crate::tool_output::ToolOutputConfig;
+use crate::visibility::VisibilityConfig;
+
+/// The `$schema` of the emitted document.
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The JSON Schema of a mount plan whose modules are described by `catalog`.
+pub fn describe_config_schema(catalog: &ModuleCatalog) -> Value {
+    let mut modules = Map::new();
+    let mut ids: [(ModuleType, Vec<&str>); 5] = [
+        (ModuleType::Orchestrator, Vec::new()),
+        (ModuleType::Context, Vec::new()),
+        (ModuleType::Provider, Vec::new()),
+        (ModuleType::Tool, Vec::new()),
+        (ModuleType::Hook, Vec::new()),
+    ];
+    for entry in catalog.iter() {
+        let info = &entry.descriptor.info;
+        let schema = match entry.config_fields.is_empty() {
+            true => info
+                .config_schema
+                .clone()
+                .unwrap_or_else(|| json!({"type": "object"})),
+            false => module_config_schema(&entry.config_fields),
+        };
+        let mut schema = with_description(schema, &info.description);
+        if !info.name.is_empty() {
+            schema["title"] = json!(info.name);
+        }
+        modules.insert(info.id.clone(), schema);
+        if let Some((_, of_type)) = ids.iter_mut().find(|(t, _)| *t == info.module_type) {
+            of_type.push(&info.id);
+        }
+    }
+    let [orchestrator, context, providers, tools, hooks] = ids.map(|(_, ids)| ids);
+
+    json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "Amplifier mount plan",
+        "type": "object",
+        "required": ["session"],
+        "properties": {
+            "config_version": {
+                "type": "integer",
+                "minimum": 1,
+                "default": CURRENT_CONFIG_VERSION,
+                "description": "Mount plan format version; older plans are migrated on load.",
+            },
+            "session": session_schema(&orchestrator, &context),
+            "orchestrator": module_section(&orchestrator),
+            "context": module_section(&context),
+            "providers": module_list("Provider modules.", &providers),
+            "tools": module_list("Tool modules.", &tools),
+            "hooks": module_list("Hook modules.", &hooks),
+        },
+        "$defs": {"modules": modules},
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Modules
+// ---------------------------------------------------------------------------
+
+/// The schema of a module's `config` object.
+fn module_config_schema(fields: &[ConfigField]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        properties.insert(field.id.clone(), config_field_schema(field));
+        if field.required
+            && field.default_value.is_none()
+            && field.env_var.is_none()
+            && field.show_when.is_none()
+        {
+            required.push(field.id.clone());
+        }
+    }
+    json!({"type": "object", "properties": properties, "required": required})
+}
+
+/// The schema of one [`ConfigField`].
+fn config_field_schema(field: &ConfigField) -> Value {
+    let mut schema = match field.field_type {
+        ConfigFieldType::Text => string(),
+        ConfigFieldType::Secret => {
+            json!({"type": "string", "format": "password", "writeOnly": true})
+        }
+        ConfigFieldType::Choice => json!({
+            "type": "string",
+            "enum": field.choices.clone().unwrap_or_default(),
+        }),
+        ConfigFieldType::Boolean => boolean(),
+    };
+    schema["title"] = json!(field.display_name);
+    schema["description"] = json!(field.prompt);
+    if let Some(default) = &field.default_value {
+        schema["default"] = match field.field_type {
+            ConfigFieldType::Boolean => json!(default.eq_ignore_ascii_case("true")),
+            _ => json!(default),
+        };
+    }
+    if let Some(env_var) = &field.env_var {
+        schema["x-env-var"] = json!(env_var);
+    }
+    if let Some(show_when) = &field.show_when {
+        schema["x-show-when"] = json!(show_when);
+    }
+    if field.requires_model {
+        schema["x-requires-model"] = json!(true);
+    }
+    schema
+}
+
+fn module_ref(id: &str) -> Value {
+    json!({"$ref": format!("#/$defs/modules/{id}")})
+}
+
+/// The top-level `orchestrator` / `context` section, whose module is named
+/// by `session.<key>`: a `config` matching one of the known modules.
+fn module_section(ids: &[&str]) -> Value {
+    let config = match ids.is_empty() {
+        true => json!({"type": "object"}),
+        false => json!({"anyOf": ids.iter().map(|id| module_ref(id)).collect::<Vec<_>>()}),
+    };
+    json!({"type": "object", "properties": {"config": config}})
+}
+
+/// A `providers` / `tools` / `hooks` list, whose entries' `config` must
+/// match their `module`.
+fn module_list(description: &str, ids: &[&str]) -> Value {
+    let conditions: Vec<Value> = ids
+        .iter()
+        .map(|id| {
+            json!({
+                "if": {"properties": {"module": {"const": id}}, "required": ["module"]},
+                "then": {"properties": {"config": module_ref(id)}},
+            })
+        })
+        .collect();
+    json!({
+        "type": "array",
+        "description": description,
+        "items": {
+            "type": "object",
+            "required": ["module"],
+            "properties": {
+                "module": string(),
+                "source": string(),
+                "config": {"type": "object"},
+            },
+            "allOf": conditions,
+        },
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Session sections
+// ---------------------------------------------------------------------------
+
+fn session_schema(orchestrator: &[&str], context: &[&str]) -> Value {
+    let module_ids = |ids: &[&str]| match ids.is_empty() {
+        true => string(),
+        false => enumeration(ids),
+    };
+    let mut properties = Map::new();
+    properties.insert("orchestrator".into(), module_ids(orchestrator));
+    properties.insert("context".into(), module_ids(context));
+    properties.insert(
+        "reentrancy".into(),
+        with_default(enumeration(&["reject", "queue"]), json!("reject")),
+    );
+    properties.insert("hooks".into(), hooks_schema());
+    properties.insert("injection_budget_per_turn".into(), optional(integer()));
+    for (name, schema) in sections() {
+        properties.insert(name.into(), schema);
+    }
+    json!({
+        "type": "object",
+        "required": ["orchestrator", "context"],
+        "properties": properties,
+    })
+}
+
+/// Every typed `session.<name>` section.
+fn sections() -> Vec<(&'static str, Value)> {
+    vec![
+        (
+            "approval_memory",
+            section::<ApprovalMemoryConfig>(
+                "Remember approval decisions marked `remember`.",
+                vec![
+                    ("enabled", boolean()),
+                    ("scope", enumeration(&["exact", "tool"])),
+                ],
+            ),
+        ),
+        (
+            "attachments",
+            section::<AttachmentConfig>(
+                "Offload large binary content to an attachment store.",
+                vec![
+                    ("threshold_bytes", integer()),
+                    ("directory", optional(string())),
+                ],
+            ),
+        ),
+        (
+            "audit",
+            section::<AuditConfig>(
+                "Append-only audit log of tool calls.",
+                vec![("path", optional(string()))],
+            ),
+        ),
+        (
+            "context_dedup",
+            section::<DedupConfig>(
+                "Drop duplicate context messages.",
+                vec![
+                    ("enabled", boolean()),
+                    ("window", integer()),
+                    ("min_chars", integer()),
+                    ("tool_results", boolean()),
+                    ("injected_context", boolean()),
+                    ("ignore_whitespace", boolean()),
+                ],
+            ),
+        ),
+        (
+            "heartbeat",
+            section::<HeartbeatConfig>(
+                "Periodic session:heartbeat events during a turn.",
+                vec![("interval_secs", number())],
+            ),
+        ),
+        (
+            "injection_queue",
+            section::<InjectionQueueConfig>(
+                "Per-handler quotas for context injections.",
+                vec![
+                    ("default_quota", optional(integer())),
+                    ("quotas", map_of(integer())),
+                ],
+            ),
+        ),
+        (
+            "memory",
+            section::<MemoryConfig>(
+                "Memory ceiling for kernel buffers.",
+                vec![
+                    ("ceiling_bytes", optional(integer())),
+                    ("policy", enumeration(&["drop_oldest", "reject_new"])),
+                    ("pressure_ratio", number()),
+                ],
+            ),
+        ),
+        (
+            "model_catalog",
+            section::<ModelCatalogConfig>(
+                "Caching of provider model lists.",
+                vec![("ttl_secs", integer())],
+            ),
+        ),
+        (
+            "notifications",
+            section::<NotificationConfig>(
+                "Deduplication and rate limiting of user notifications.",
+                vec![
+                    ("dedup_window_ms", integer()),
+                    ("max_per_window", integer()),
+                    ("rate_window_ms", integer()),
+                ],
+            ),
+        ),
+        (
+            "policy",
+            section::<PolicyConfig>(
+                "Tool permission rules.",
+                vec![
+                    ("default", policy_action()),
+                    (
+                        "rules",
+                        list(closed_object(
+                            [
+                                ("tool", string()),
+                                ("arguments", map_of(string())),
+                                ("paths", list(string())),
+                                ("action", policy_action()),
+                                ("reason", string()),
+                            ],
+                            &["action"],
+                        )),
+                    ),
+                    ("path_arguments", list(string())),
+                    ("approval_timeout", number()),
+                ],
+            ),
+        ),
+        (
+            "pricing",
+            section::<crate::pricing::PricingCatalog>(
+                "Model prices for cost reporting.",
+                vec![(
+                    "models",
+                    map_of(closed_object(
+                        [
+                            ("input_per_mtok", number()),
+                            ("output_per_mtok", number()),
+                            ("cache_read_per_mtok", number()),
+                            ("cache_write_per_mtok", number()),
+                        ],
+                        &["input_per_mtok", "output_per_mtok"],
+                    )),
+                )],
+            ),
+        ),
+        (
+            "prompt_history",
+            section::<PromptHistoryConfig>(
+                "Prompt history and duplicate prompt detection.",
+                vec![
+                    ("capacity", integer()),
+                    ("duplicate_window_secs", integer()),
+                    ("replay_duplicates", boolean()),
+                ],
+            ),
+        ),
+        (
+            "provenance",
+            section::<ProvenanceConfig>(
+                "Record where each context message came from.",
+                vec![("enabled", boolean())],
+            ),
+        ),
+        (
+            "quota",
+            section::<QuotaConfig>(
+                "Per-session resource quotas.",
+                vec![
+                    ("max_tool_calls", optional(integer())),
+                    ("max_provider_calls", optional(integer())),
+                    ("max_total_tokens", optional(integer())),
+                    ("max_duration_secs", optional(number())),
+                ],
+            ),
+        ),
+        (
+            "safety",
+            section::<SafetyConfig>(
+                "Per-turn limits on turns and tool calls.",
+                vec![
+                    ("max_turns", optional(integer())),
+                    ("max_identical_tool_calls", optional(integer())),
+                    ("max_tool_calls", optional(integer())),
+                ],
+            ),
+        ),
+        (
+            "stream_events",
+            section::<StreamEventConfig>(
+                "Coalesced content streaming events.",
+                vec![
+                    ("enabled", boolean()),
+                    ("min_interval_ms", integer()),
+                    ("max_pending_chars", integer()),
+                ],
+            ),
+        ),
+        (
+            "structured_output",
+            section::<StructuredOutputConfig>(
+                "Validation of structured provider output.",
+                vec![("max_retries", integer())],
+            ),
+        ),
+        (
+            "summarization",
+            section::<SummarizationConfig>(
+                "Summarize old context messages.",
+                vec![
+                    ("enabled", boolean()),
+                    ("provider", optional(string())),
+                    ("model", optional(string())),
+                    ("trigger_messages", integer()),
+                    ("summarize_oldest", integer()),
+                    ("max_output_tokens", optional(integer())),
+                    ("instructions", optional(string())),
+                ],
+            ),
+        ),
+        (
+            "telemetry",
+            section::<TelemetryConfig>(
+                "OpenTelemetry export.",
+                vec![
+                    ("enabled", boolean()),
+                    ("spans", boolean()),
+                    ("metrics", boolean()),
+                    ("attributes", map_of(string())),
+                ],
+            ),
+        ),
+        (
+            "tool_cache",
+            section::<PureToolCacheConfig>(
+                "Session-wide caching of pure tool results.",
+                vec![
+                    ("enabled", boolean()),
+                    ("ttl_secs", integer()),
+                    ("max_entries", integer()),
+                    ("max_entry_bytes", integer()),
+                    ("invalidate_on_impure", boolean()),
+                ],
+            ),
+        ),
+        (
+            "tool_discovery",
+            section::<ToolDiscoveryConfig>(
+                "Turn-start tool discovery.",
+                vec![
+                    ("enabled", boolean()),
+                    ("max_tools", integer()),
+                    ("min_score", number()),
+                    ("timeout_ms", integer()),
+                    ("invoke_timeout_ms", integer()),
+                ],
+            ),
+        ),
+        (
+            "tool_output",
+            section::<ToolOutputConfig>(
+                "Truncation of large tool output.",
+                vec![
+                    ("max_bytes", integer()),
+                    ("head_ratio", number()),
+                    ("detect_binary", boolean()),
+                ],
+            ),
+        ),
+        (
+            "turn_recovery",
+            section::<TurnRecoveryPolicy>(
+                "Recovery from provider and tool failures within a turn.",
+                vec![
+                    ("max_consecutive_provider_failures", integer()),
+                    (
+                        "on_tool_failure",
+                        enumeration(&["abort", "report_to_model", "ask_user"]),
+                    ),
+                ],
+            ),
+        ),
+        (
+            "visibility",
+            section::<VisibilityConfig>(
+                "Restricted content each provider may receive.",
+                vec![
+                    ("default", visibility_policy()),
+                    ("providers", map_of(visibility_policy())),
+                ],
+            ),
+        ),
+        (
+            "workspace",
+            with_description(
+                closed_object(
+                    [
+                        ("root", string()),
+                        ("allowed_paths", list(string())),
+                        ("read_only", with_default(boolean(), json!(false))),
+                        ("read_only_paths", list(string())),
+                        ("write_tools", list(string())),
+                    ],
+                    &["root"],
+                ),
+                "Filesystem scope for tools.",
+            ),
+        ),
+    ]
+}
+
+/// `session.hooks`.
+fn hooks_schema() -> Value {
+    let builtin = closed_object(
+        [
+            (
+                "logging",
+                defaults_from::<LoggingConfig>(closed_object(
+                    [
+                        (
+                            "level",
+                            enumeration(&["error", "warn", "info", "debug", "trace"]),
+                        ),
+                        ("payloads", boolean()),
+                    ],
+                    &[],
+                )),
+            ),
+            (
+                "content_filter",
+                defaults_from::<ContentFilterConfig>(closed_object(
+                    [
+                        ("terms", list(string())),
+                        ("redact_emails", boolean()),
+                        ("replacement", string()),
+                    ],
+                    &[],
+                )),
+            ),
+            (
+                "moderation",
+                defaults_from::<ModerationConfig>(closed_object(
+                    [
+                        ("action", enumeration(&["deny", "flag"])),
+                        ("categories", list(string())),
+                        ("fail_closed", boolean()),
+                    ],
+                    &[],
+                )),
+            ),
+        ],
+        &[],
+    );
+    let subscription = closed_object(
+        [
+            ("event", string()),
+            ("handler", string()),
+            (
+                "priority",
+                with_default(json!({"type": "integer"}), json!(0)),
+            ),
+            (
+                "phase",
+                with_default(
+                    enumeration(&["pre_validation", "policy", "mutation", "observation"]),
+                    json!("policy"),
+                ),
+            ),
+            ("enabled", with_default(boolean(), json!(true))),
+            ("name", string()),
+            ("condition", json!({"type": "object"})),
+        ],
+        &["event", "handler"],
+    );
+    let level = enumeration(&["debug", "info", "warn"]);
+    let filter = section::<EventFilterConfig>(
+        "Emit-time event filtering.",
+        vec![
+            ("min_level", level.clone()),
+            ("disabled", list(string())),
+            ("enabled", list(string())),
+            ("sample_rates", map_of(number())),
+            ("levels", map_of(level)),
+        ],
+    );
+    let latency = section::<LatencyBudgetConfig>(
+        "Hook handler latency budgets.",
+        vec![
+            ("budget_ms", integer()),
+            ("handlers", map_of(integer())),
+            ("demote_after", integer()),
+        ],
+    );
+    json!({
+        "type": "object",
+        "properties": {
+            "replay": integer(),
+            "max_result_bytes": integer(),
+            "latency": latency,
+            "builtin": with_description(builtin, "Options for the built-in hook handlers."),
+            "subscriptions": list(subscription),
+            "filter": filter,
+        },
+    })
+}
+
+fn policy_action() -> Value {
+    enumeration(&["allow", "deny", "ask"])
+}
+
+fn visibility_policy() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "allow_internal": boolean(),
+            "allow_redacted_thinking": boolean(),
+        },
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Builders
+// ---------------------------------------------------------------------------
+
+/// A closed object section whose defaults come from `T::default()`.
+fn section<T: Default + Serialize>(description: &str, properties: Vec<(&str, Value)>) -> Value {
+    with_description(
+        defaults_from::<T>(closed_object(properties, &[])),
+        description,
+    )
+}
+
+/// `schema` with each property's `default` set from `T::default()`.
+fn defaults_from<T: Default + Serialize>(mut schema: Value) -> Value {
+    let Ok(Value::Object(defaults)) = serde_json::to_value(T::default()) else {
+        return schema;
+    };
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        for (name, default) in defaults {
+            if let Some(property) = properties.get_mut(&name) {
+                property["default"] = default;
+            }
+        }
+    }
+    schema
+}
+
+fn closed_object<'a>(
+    properties: impl IntoIterator<Item = (&'a str, Value)>,
+    required: &[&str],
+) -> Value {
+    let properties: Map<String, Value> = properties
+        .into_iter()
+        .map(|(name, schema)| (name.to_string(), schema))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn with_description(mut schema: Value, description: &str) -> Value {
+    if !description.is_empty() {
+        schema["description"] = json!(description);
+    }
+    schema
+}
+
+fn with_default(mut schema: Value, default: Value) -> Value {
+    schema["default"] = default;
+    schema
+}
+
+fn integer() -> Value {
+    json!({"type": "integer", "minimum": 0})
+}
+
+fn number() -> Value {
+    json!({"type": "number"})
+}
+
+fn boolean() -> Value {
+    json!({"type": "boolean"})
+}
+
+fn string() -> Value {
+    json!({"type": "string"})
+}
+
+fn enumeration(values: &[&str]) -> Value {
+    json!({"type": "string", "enum": values})
+}
+
+fn list(items: Value) -> Value {
+    json!({"type": "array", "items": items})
+}
+
+fn map_of(values: Value) -> Value {
+    json!({"type": "object", "additionalProperties": values})
+}
+
+/// `schema` that also accepts `null`.
+fn optional(mut schema: Value) -> Value {
+    schema["type"] = json!([schema["type"].take(), "null"]);
+    schema
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::ModuleDescriptor;
+
+    fn descriptor(json: Value) -> ModuleDescriptor {
+        ModuleDescriptor::from_json(&json!({ "module": json }).to_string(), "test").unwrap()
+    }
+
+    #[test]
+    fn session_sections_carry_struct_defaults() {
+        let schema = describe_config_schema(&ModuleCatalog::new());
+        assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+        let session = &schema["properties"]["session"]["properties"];
+        assert_eq!(
+            session["tool_output"]["properties"]["max_bytes"]["default"],
+            json!(ToolOutputConfig::default().max_bytes)
+        );
+        assert_eq!(
+            session["quota"]["properties"]["max_total_tokens"]["type"],
+            json!(["integer", "null"])
+        );
+        assert_eq!(
+            session["hooks"]["properties"]["builtin"]["properties"]["moderation"]["properties"]
+                ["action"]["default"],
+            "deny"
+        );
+
+        for (name, section) in sections() {
+            let properties = section["properties"].as_object().unwrap();
+            for (field, property) in properties {
+                assert!(property.get("type").is_some(), "session.{name}.{field}");
+            }
+        }
+    }
+
+    #[test]
+    fn module_config_fields_become_schemas() {
+        let catalog = ModuleCatalog::new()
+            .with_module(
+                descriptor(json!({"id": "loop-basic", "type": "orchestrator"})),
+                Vec::new(),
+            )
+            .with_module(
+                descriptor(json!({"id": "provider-x", "type": "provider", "name": "X"})),
+                vec![
+                    ConfigField {
+                        id: "api_key".into(),
+                        display_name: "API key".into(),
+                        field_type: ConfigFieldType::Secret,
+                        prompt: "Your API key".into(),
+                        env_var: Some("X_API_KEY".into()),
+                        choices: None,
+                        required: true,
+                        default_value: None,
+                        show_when: None,
+                        requires_model: false,
+                    },
+                    ConfigField {
+                        id: "model".into(),
+                        display_name: "Model".into(),
+                        field_type: ConfigFieldType::Choice,
+                        prompt: "Model to use".into(),
+                        env_var: None,
+                        choices: Some(vec!["small".into(), "large".into()]),
+                        required: true,
+                        default_value: None,
+                        show_when: None,
+                        requires_model: false,
+                    },
+                ],
+            );
+        let schema = describe_config_schema(&catalog);
+
+        let provider = &schema["$defs"]["modules"]["provider-x"];
+        assert_eq!(provider["title"], "X");
+        assert_eq!(provider["required"], json!(["model"]));
+        assert_eq!(provider["properties"]["api_key"]["writeOnly"], true);
+        assert_eq!(provider["properties"]["api_key"]["x-env-var"], "X_API_KEY");
+        assert_eq!(
+            provider["properties"]["model"]["enum"],
+            json!(["small", "large"])
+        );
+
+        let items = &schema["properties"]["providers"]["items"];
+        assert_eq!(
+            items["allOf"][0]["then"]["properties"]["config"]["$ref"],
+            "#/$defs/modules/provider-x"
+        );
+        assert_eq!(
+            schema["properties"]["session"]["properties"]["orchestrator"]["enum"],
+            json!(["loop-basic"])
+        );
+    }
+}
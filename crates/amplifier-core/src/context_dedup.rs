@@ -0,0 +1,476 @@
+//! Collapsing of repeated content in requests.
+//!
+//! Agents often rerun the same command and get the same output back: a lint
+//! report, a failing test run, a file that has not changed. Every copy stays
+//! in the context and is sent on every request. [`deduplicate`] keeps the
+//! first copy and replaces later identical copies within a window of
+//! messages with a short reference to it:
+//!
+//! | Content                                  | Collapsed when `…` is set |
+//! |------------------------------------------|---------------------------|
+//! | `tool` messages and `tool_result` blocks | `tool_results`            |
+//! | `system` / `developer` messages          | `injected_context`        |
+//!
+//! Keeping the first copy leaves the start of the conversation unchanged, so
+//! provider-side prompt caches still match. Tool result messages stay in
+//! place (only their content is replaced), so every tool call keeps its
+//! result. With `ignore_whitespace` (the default), copies that differ only
+//! in whitespace count as identical.
+//!
+//! # Configuration
+//!
+//! Opt-in through `session.context_dedup`:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "context_dedup": {"window": 50, "min_chars": 200}
+//!   }
+//! }
+//! ```
+//!
+//! [`DedupContext`] applies the pass to the messages the mounted context
+//! returns for each request, leaving the stored history untouched, and
+//! emits [`CONTEXT_DEDUPLICATED`](crate::events::CONTEXT_DEDUPLICATED) with
+//! the space saved.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::errors::ContextError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::traits::{ContextManager, Provider};
+
+// ---------------------------------------------------------------------------
+// DedupConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.context_dedup` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DedupConfig {
+    /// Set to `false` to keep the section but turn the pass off.
+    pub enabled: bool,
+    /// How many messages back a copy may be from the one it repeats.
+    pub window: usize,
+    /// Content shorter than this (in characters) is left alone.
+    pub min_chars: usize,
+    pub tool_results: bool,
+    pub injected_context: bool,
+    pub ignore_whitespace: bool,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: 50,
+            min_chars: 200,
+            tool_results: true,
+            injected_context: true,
+            ignore_whitespace: true,
+        }
+    }
+}
+
+impl DedupConfig {
+    /// Read `session.context_dedup` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("context_dedup"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.context_dedup config: {e}"))
+            .ok()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Deduplication pass
+// ---------------------------------------------------------------------------
+
+/// What repeated content is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    ToolResult,
+    InjectedContext,
+}
+
+impl DuplicateKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::ToolResult => "tool result",
+            Self::InjectedContext => "context",
+        }
+    }
+}
+
+/// Copies of one piece of content that were collapsed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub kind: DuplicateKind,
+    /// Index of the message holding the copy that was kept.
+    pub first_index: usize,
+    /// Number of occurrences, including the kept one.
+    pub count: usize,
+    /// Characters removed from the collapsed copies.
+    pub chars_saved: usize,
+}
+
+/// What [`deduplicate`] collapsed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupReport {
+    /// Copies replaced with a reference.
+    pub collapsed: usize,
+    /// Characters removed, net of the references added.
+    pub chars_saved: usize,
+    /// Collapsed content, in order of first occurrence.
+    pub groups: Vec<DuplicateGroup>,
+}
+
+impl DedupReport {
+    /// Whether nothing was collapsed.
+    pub fn is_empty(&self) -> bool {
+        self.collapsed == 0
+    }
+}
+
+/// Where a piece of content lives inside a message.
+enum Slot {
+    /// The message's `content`.
+    Content,
+    /// The `output` of the content block at this position.
+    Block(usize),
+}
+
+/// Replace later copies of repeated content in `messages` with references to
+/// the first copy, as configured by `config`.
+pub fn deduplicate(messages: &mut [Value], config: &DedupConfig) -> DedupReport {
+    let mut report = DedupReport::default();
+    if !config.enabled {
+        return report;
+    }
+
+    // Fingerprint → (index into `pending`, index of the kept message).
+    let mut seen: HashMap<(DuplicateKind, [u8; 32]), (usize, usize)> = HashMap::new();
+    let mut pending: Vec<DuplicateGroup> = Vec::new();
+
+    for (index, message) in messages.iter_mut().enumerate() {
+        for (kind, slot) in slots(message, config) {
+            let Some(target) = slot_value(message, &slot) else {
+                continue;
+            };
+            let text = match &*target {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let chars = text.chars().count();
+            if chars < config.min_chars {
+                continue;
+            }
+            let key = (kind, fingerprint(&text, config.ignore_whitespace));
+
+            match seen.get(&key) {
+                Some(&(group, first)) if index - first <= config.window => {
+                    let group = &mut pending[group];
+                    group.count += 1;
+                    let reference = format!(
+                        "[Duplicate {}: identical to message {first}, repeat {}; {chars} characters omitted]",
+                        kind.label(),
+                        group.count - 1,
+                    );
+                    let saved = chars.saturating_sub(reference.chars().count());
+                    group.chars_saved += saved;
+                    report.chars_saved += saved;
+                    report.collapsed += 1;
+                    *target = Value::String(reference);
+                }
+                _ => {
+                    seen.insert(key, (pending.len(), index));
+                    pending.push(DuplicateGroup {
+                        kind,
+                        first_index: index,
+                        count: 1,
+                        chars_saved: 0,
+                    });
+                }
+            }
+        }
+    }
+
+    report.groups = pending.into_iter().filter(|g| g.count > 1).collect();
+    report
+}
+
+/// The dedupable content of `message`.
+fn slots(message: &Value, config: &DedupConfig) -> Vec<(DuplicateKind, Slot)> {
+    let role = message.get("role").and_then(Value::as_str).unwrap_or("");
+    match message.get("content") {
+        Some(Value::Array(blocks)) if config.tool_results => blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.get("type").and_then(Value::as_str) == Some("tool_result"))
+            .map(|(i, _)| (DuplicateKind::ToolResult, Slot::Block(i)))
+            .collect(),
+        Some(_) if role == "tool" && config.tool_results => {
+            vec![(DuplicateKind::ToolResult, Slot::Content)]
+        }
+        Some(Value::String(_))
+            if matches!(role, "system" | "developer") && config.injected_context =>
+        {
+            vec![(DuplicateKind::InjectedContext, Slot::Content)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn slot_value<'a>(message: &'a mut Value, slot: &Slot) -> Option<&'a mut Value> {
+    let content = message.get_mut("content")?;
+    match slot {
+        Slot::Content => Some(content),
+        Slot::Block(i) => content.get_mut(*i)?.get_mut("output"),
+    }
+}
+
+fn fingerprint(text: &str, ignore_whitespace: bool) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    if ignore_whitespace {
+        for word in text.split_whitespace() {
+            hasher.update(word.as_bytes());
+            hasher.update(b" ");
+        }
+    } else {
+        hasher.update(text.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
+// ---------------------------------------------------------------------------
+// DedupContext
+// ---------------------------------------------------------------------------
+
+/// A [`ContextManager`] that deduplicates the messages its inner context
+/// prepares for each request.
+///
+/// Everything else, including [`get_messages`](ContextManager::get_messages),
+/// passes through unchanged.
+pub struct DedupContext {
+    inner: Arc<dyn ContextManager>,
+    config: DedupConfig,
+    hooks: Arc<HookRegistry>,
+}
+
+impl DedupContext {
+    /// Wrap `inner`, reporting savings on `hooks`.
+    pub fn new(
+        inner: Arc<dyn ContextManager>,
+        config: DedupConfig,
+        hooks: Arc<HookRegistry>,
+    ) -> Self {
+        Self {
+            inner,
+            config,
+            hooks,
+        }
+    }
+}
+
+impl ContextManager for DedupContext {
+    fn add_message(
+        &self,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.add_message(message)
+    }
+
+    fn get_messages_for_request(
+        &self,
+        token_budget: Option<i64>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        Box::pin(async move {
+            let mut messages = self
+                .inner
+                .get_messages_for_request(token_budget, provider)
+                .await?;
+            let report = deduplicate(&mut messages, &self.config);
+            if !report.is_empty() {
+                // At the usual four characters per token.
+                let tokens = report.chars_saved.div_ceil(4);
+                self.hooks
+                    .emit(
+                        events::CONTEXT_DEDUPLICATED,
+                        serde_json::json!({
+                            "collapsed": report.collapsed,
+                            "chars_saved": report.chars_saved,
+                            "estimated_tokens_saved": tokens,
+                            "groups": report.groups,
+                        }),
+                    )
+                    .await;
+            }
+            Ok(messages)
+        })
+    }
+
+    fn get_messages(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        self.inner.get_messages()
+    }
+
+    fn set_messages(
+        &self,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.set_messages(messages)
+    }
+
+    fn clear(&self) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.clear()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeContextManager, FakeHookHandler};
+    use serde_json::json;
+
+    fn lint_output(issues: usize) -> String {
+        (0..issues)
+            .map(|i| format!("src/lib.rs:{i}: warning: unused variable `x{i}`\n"))
+            .collect()
+    }
+
+    fn tool_message(id: &str, output: &str) -> Value {
+        json!({"role": "tool", "tool_call_id": id, "content": output})
+    }
+
+    #[test]
+    fn repeated_tool_results_collapse_to_references() {
+        let lint = lint_output(10);
+        let mut messages = vec![
+            json!({"role": "user", "content": "fix the warnings"}),
+            tool_message("c1", &lint),
+            tool_message("c2", &lint_output(9)),
+            tool_message("c3", &lint.replace('\n', "\n  ")),
+            json!({"role": "user", "content": [
+                {"type": "tool_result", "tool_call_id": "c4", "output": lint}
+            ]}),
+        ];
+        let report = deduplicate(&mut messages, &DedupConfig::default());
+
+        assert_eq!(report.collapsed, 2);
+        assert_eq!(
+            report.groups,
+            vec![DuplicateGroup {
+                kind: DuplicateKind::ToolResult,
+                first_index: 1,
+                count: 3,
+                chars_saved: report.chars_saved,
+            }]
+        );
+        assert_eq!(messages[1]["content"], json!(lint));
+        assert_eq!(messages[2]["content"], json!(lint_output(9)));
+        assert_eq!(
+            messages[3]["content"],
+            json!(format!(
+                "[Duplicate tool result: identical to message 1, repeat 1; {} characters omitted]",
+                lint.replace('\n', "\n  ").chars().count()
+            ))
+        );
+        assert_eq!(messages[3]["tool_call_id"], "c3");
+        let collapsed = messages[4]["content"][0]["output"].as_str().unwrap();
+        assert!(collapsed.contains("message 1, repeat 2"), "{collapsed}");
+    }
+
+    #[test]
+    fn window_min_chars_and_kinds_limit_collapsing() {
+        let context = "Project conventions: ".repeat(20);
+        let mut messages = vec![
+            json!({"role": "system", "content": context}),
+            tool_message("c1", "ok"),
+            tool_message("c2", "ok"),
+            json!({"role": "system", "content": context}),
+            json!({"role": "system", "content": context}),
+        ];
+        let config = DedupConfig {
+            window: 2,
+            ..DedupConfig::default()
+        };
+        let report = deduplicate(&mut messages, &config);
+
+        // Message 3 is too far from message 0 and becomes the kept copy.
+        assert_eq!(report.collapsed, 1);
+        assert_eq!(report.groups[0].kind, DuplicateKind::InjectedContext);
+        assert_eq!(report.groups[0].first_index, 3);
+        assert_eq!(messages[2]["content"], "ok");
+
+        let mut unchanged = messages.clone();
+        let off = DedupConfig {
+            injected_context: false,
+            ..DedupConfig::default()
+        };
+        assert!(deduplicate(&mut unchanged, &off).is_empty());
+    }
+
+    #[tokio::test]
+    async fn context_emits_savings_and_keeps_history() {
+        let inner = Arc::new(FakeContextManager::new());
+        let lint = lint_output(10);
+        for id in ["c1", "c2"] {
+            inner.add_message(tool_message(id, &lint)).await.unwrap();
+        }
+        let hooks = Arc::new(HookRegistry::new());
+        let observer = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(
+            events::CONTEXT_DEDUPLICATED,
+            observer.clone(),
+            0,
+            Some("observer".into()),
+        );
+        let context = DedupContext::new(inner.clone(), DedupConfig::default(), hooks);
+
+        let sent = context.get_messages_for_request(None, None).await.unwrap();
+        assert!(sent[1]["content"]
+            .as_str()
+            .unwrap()
+            .starts_with("[Duplicate"));
+        assert_eq!(
+            context.get_messages().await.unwrap()[1]["content"],
+            json!(lint)
+        );
+
+        let recorded = observer.recorded_events();
+        assert_eq!(recorded.len(), 1);
+        let payload = &recorded[0].1;
+        assert_eq!(payload["collapsed"], 1);
+        assert!(payload["estimated_tokens_saved"].as_u64().unwrap() > 0);
+        assert_eq!(payload["groups"][0]["count"], 2);
+    }
+
+    #[test]
+    fn config_is_opt_in() {
+        assert_eq!(DedupConfig::from_session_config(&HashMap::new()), None);
+        let config = HashMap::from([(
+            "session".to_string(),
+            json!({"context_dedup": {"window": 5}}),
+        )]);
+        let config = DedupConfig::from_session_config(&config).unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.window, 5);
+    }
+}
@@ -0,0 +1,1062 @@
+//! ConversationStore — durable, per-session message history.
+//!
+//! A [`ConversationStore`] is an append-only message log keyed by session ID.
+//! It is not a mounted module; instead, [`PersistentContext`] wraps any
+//! [`ContextManager`] so that every message it accepts is also written to the
+//! store, and [`Session`](crate::session::Session) reloads the stored history
+//! into the context when a resumed session first executes.
+//!
+//! # Implementations
+//!
+//! - [`InMemoryConversationStore`] — process-local, for tests and ephemeral
+//!   sessions; memory-accounted via [`crate::memory`].
+//! - [`FileConversationStore`] — one JSON Lines file per session under a
+//!   directory. Uses blocking `std::fs` I/O; appends are small and local.
+//! - `SqliteConversationStore` — one SQLite database for all sessions,
+//!   linked against the system `libsqlite3` (feature `sqlite`).
+//!
+//! # Connections
+//!
+//! - Wraps [`ContextManager`](crate::traits::ContextManager) via [`PersistentContext`].
+//! - Attached to a session with
+//!   [`Session::set_conversation_store`](crate::session::Session::set_conversation_store).
+
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use crate::errors::ContextError;
+use crate::events::KERNEL_MEMORY_EVICTED;
+use crate::hooks::HookRegistry;
+use crate::memory::{json_size, BoundedBuffer, MemoryAccountant, PushOutcome};
+use crate::traits::{ContextManager, Provider};
+
+// ---------------------------------------------------------------------------
+// ConversationStore
+// ---------------------------------------------------------------------------
+
+/// Durable storage for conversation messages, keyed by session ID.
+///
+/// Messages are opaque JSON values (the same shape [`ContextManager`]
+/// accepts). Indices are zero-based positions in the session's history.
+pub trait ConversationStore: Send + Sync {
+    /// Append one message to the end of a session's history.
+    fn append_message(
+        &self,
+        session_id: &str,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>>;
+
+    /// Load messages `start..end` of a session's history (`end = None` means
+    /// "to the end"). Out-of-range bounds are clamped; an unknown session
+    /// yields an empty list.
+    fn load_range(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: Option<usize>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>>;
+
+    /// Replace a session's entire history (used for compaction and `clear`).
+    fn replace_messages(
+        &self,
+        session_id: &str,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>>;
+
+    /// IDs of all sessions with stored history, sorted.
+    fn list_sessions(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, ContextError>> + Send + '_>>;
+}
+
+/// Clamp `start..end` to `len` and return the owned slice.
+fn slice_range(messages: &[Value], start: usize, end: Option<usize>) -> Vec<Value> {
+    let end = end.unwrap_or(messages.len()).min(messages.len());
+    let start = start.min(end);
+    messages[start..end].to_vec()
+}
+
+/// Accounting category for [`InMemoryConversationStore`] messages.
+const MEMORY_CATEGORY: &str = "conversation_store";
+
+fn storage_error(message: impl Into<String>) -> ContextError {
+    ContextError::Storage {
+        message: message.into(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// InMemoryConversationStore
+// ---------------------------------------------------------------------------
+
+/// A process-local store holding each session's history in a
+/// [`BoundedBuffer`] charged to a [`MemoryAccountant`] under the
+/// `"conversation_store"` category.
+///
+/// With a memory ceiling and [`EvictionPolicy::DropOldest`](crate::memory::EvictionPolicy),
+/// the oldest messages of the session being appended to are dropped first.
+/// Each write that drops messages emits
+/// [`KERNEL_MEMORY_EVICTED`] on the
+/// registry given to [`with_hooks`](Self::with_hooks), and logs a warning.
+pub struct InMemoryConversationStore {
+    sessions: Mutex<HashMap<String, BoundedBuffer<Value>>>,
+    memory: Arc<MemoryAccountant>,
+    hooks: Option<Arc<HookRegistry>>,
+}
+
+impl Default for InMemoryConversationStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryConversationStore {
+    /// Create an empty, unbounded store.
+    pub fn new() -> Self {
+        Self::with_memory(Arc::new(MemoryAccountant::default()))
+    }
+
+    /// Create an empty store charging its messages to `memory`
+    /// (typically [`Coordinator::memory`](crate::coordinator::Coordinator::memory)).
+    pub fn with_memory(memory: Arc<MemoryAccountant>) -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+            memory,
+            hooks: None,
+        }
+    }
+
+    /// Report evicted messages on `hooks` (typically
+    /// [`Coordinator::hooks_shared`](crate::coordinator::Coordinator::hooks_shared)).
+    pub fn with_hooks(mut self, hooks: Arc<HookRegistry>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Push `message`, returning how many older messages were evicted.
+    fn push(
+        &self,
+        buffer: &mut BoundedBuffer<Value>,
+        message: Value,
+    ) -> Result<usize, ContextError> {
+        let size = json_size(&message);
+        match buffer.push(message, size) {
+            PushOutcome::Stored { evicted } => Ok(evicted),
+            PushOutcome::Rejected => Err(storage_error(format!(
+                "message of {size} bytes rejected by memory ceiling"
+            ))),
+        }
+    }
+
+    async fn report_evicted(&self, session_id: String, evicted: usize) {
+        if evicted == 0 {
+            return;
+        }
+        log::warn!(
+            "Evicted {evicted} message(s) from session {session_id}'s history to stay under the memory ceiling"
+        );
+        if let Some(hooks) = &self.hooks {
+            hooks
+                .emit(
+                    KERNEL_MEMORY_EVICTED,
+                    serde_json::json!({
+                        "category": MEMORY_CATEGORY,
+                        "session_id": session_id,
+                        "evicted": evicted,
+                    }),
+                )
+                .await;
+        }
+    }
+}
+
+impl ConversationStore for InMemoryConversationStore {
+    fn append_message(
+        &self,
+        session_id: &str,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let buffer = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| BoundedBuffer::new(MEMORY_CATEGORY, Arc::clone(&self.memory)));
+        let result = self.push(buffer, message);
+        drop(sessions);
+        let session_id = session_id.to_string();
+        Box::pin(async move {
+            self.report_evicted(session_id, result?).await;
+            Ok(())
+        })
+    }
+
+    fn load_range(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: Option<usize>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        let messages = self
+            .sessions
+            .lock()
+            .unwrap()
+            .get(session_id)
+            .map(|buffer| {
+                let all: Vec<Value> = buffer.iter().cloned().collect();
+                slice_range(&all, start, end)
+            })
+            .unwrap_or_default();
+        Box::pin(async move { Ok(messages) })
+    }
+
+    fn replace_messages(
+        &self,
+        session_id: &str,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let buffer = sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| BoundedBuffer::new(MEMORY_CATEGORY, Arc::clone(&self.memory)));
+        buffer.drain();
+        let result = messages.into_iter().try_fold(0, |evicted, message| {
+            Ok(evicted + self.push(buffer, message)?)
+        });
+        drop(sessions);
+        let session_id = session_id.to_string();
+        Box::pin(async move {
+            self.report_evicted(session_id, result?).await;
+            Ok(())
+        })
+    }
+
+    fn list_sessions(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, ContextError>> + Send + '_>> {
+        let mut ids: Vec<String> = self.sessions.lock().unwrap().keys().cloned().collect();
+        ids.sort();
+        Box::pin(async move { Ok(ids) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// FileConversationStore
+// ---------------------------------------------------------------------------
+
+/// A durable store writing one `<session_id>.jsonl` file per session.
+///
+/// Each line is one message. Appends open the file in append mode;
+/// replacements write a temporary file and rename it over the original.
+/// Session IDs must be usable as file names (no path separators, not `.`/`..`).
+pub struct FileConversationStore {
+    root: PathBuf,
+    /// Serializes writers within this process.
+    write_lock: Mutex<()>,
+}
+
+impl FileConversationStore {
+    /// Open (creating if necessary) a store rooted at `root`.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, ContextError> {
+        let root = root.into();
+        fs::create_dir_all(&root)
+            .map_err(|e| storage_error(format!("create {}: {e}", root.display())))?;
+        Ok(Self {
+            root,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// The directory holding the session files.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn session_path(&self, session_id: &str) -> Result<PathBuf, ContextError> {
+        let valid = !session_id.is_empty()
+            && session_id != "."
+            && session_id != ".."
+            && !session_id.contains(['/', '\\', '\0']);
+        if !valid {
+            return Err(storage_error(format!(
+                "invalid session id for file store: {session_id:?}"
+            )));
+        }
+        Ok(self.root.join(format!("{session_id}.jsonl")))
+    }
+
+    fn read_all(&self, session_id: &str) -> Result<Vec<Value>, ContextError> {
+        let path = self.session_path(session_id)?;
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(storage_error(format!("open {}: {e}", path.display()))),
+        };
+        let mut messages = Vec::new();
+        for (lineno, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| storage_error(format!("read {}: {e}", path.display())))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message = serde_json::from_str(&line)
+                .map_err(|e| storage_error(format!("{}:{}: {e}", path.display(), lineno + 1)))?;
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+
+    fn append(&self, session_id: &str, message: &Value) -> Result<(), ContextError> {
+        let path = self.session_path(session_id)?;
+        let line = serde_json::to_string(message).map_err(|e| storage_error(e.to_string()))?;
+        let _guard = self.write_lock.lock().unwrap();
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| storage_error(format!("open {}: {e}", path.display())))?;
+        writeln!(file, "{line}")
+            .map_err(|e| storage_error(format!("write {}: {e}", path.display())))
+    }
+
+    fn replace(&self, session_id: &str, messages: &[Value]) -> Result<(), ContextError> {
+        let path = self.session_path(session_id)?;
+        let tmp = path.with_extension("jsonl.tmp");
+        let mut body = String::new();
+        for message in messages {
+            body.push_str(
+                &serde_json::to_string(message).map_err(|e| storage_error(e.to_string()))?,
+            );
+            body.push('\n');
+        }
+        let _guard = self.write_lock.lock().unwrap();
+        fs::write(&tmp, body)
+            .map_err(|e| storage_error(format!("write {}: {e}", tmp.display())))?;
+        fs::rename(&tmp, &path)
+            .map_err(|e| storage_error(format!("rename {}: {e}", path.display())))
+    }
+
+    fn session_ids(&self) -> Result<Vec<String>, ContextError> {
+        let entries = fs::read_dir(&self.root)
+            .map_err(|e| storage_error(format!("list {}: {e}", self.root.display())))?;
+        let mut ids: Vec<String> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "jsonl" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+impl ConversationStore for FileConversationStore {
+    fn append_message(
+        &self,
+        session_id: &str,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let result = self.append(session_id, &message);
+        Box::pin(async move { result })
+    }
+
+    fn load_range(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: Option<usize>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        let result = self
+            .read_all(session_id)
+            .map(|msgs| slice_range(&msgs, start, end));
+        Box::pin(async move { result })
+    }
+
+    fn replace_messages(
+        &self,
+        session_id: &str,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let result = self.replace(session_id, &messages);
+        Box::pin(async move { result })
+    }
+
+    fn list_sessions(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, ContextError>> + Send + '_>> {
+        let result = self.session_ids();
+        Box::pin(async move { result })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SqliteConversationStore
+// ---------------------------------------------------------------------------
+
+/// A durable store backed by a single SQLite database file (feature `sqlite`).
+///
+/// Messages live in a `messages(session_id, position, message)` table, with
+/// each message stored as JSON text; a `sessions` table keeps sessions whose
+/// history was replaced with an empty list listable. Calls run the blocking
+/// SQLite API inline, one at a time per store. Several processes may share a
+/// database file: appends compute the next position inside a single
+/// statement, replacements run in a write transaction, and a busy timeout
+/// makes writers wait for each other's locks.
+#[cfg(feature = "sqlite")]
+pub struct SqliteConversationStore {
+    path: PathBuf,
+    conn: Mutex<sqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteConversationStore {
+    /// How long a writer waits for another connection's lock before failing.
+    const BUSY_TIMEOUT_MS: i32 = 5_000;
+
+    /// Open (creating if necessary) the database at `path`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, ContextError> {
+        let path = path.into();
+        let conn = sqlite::Connection::open(&path, Self::BUSY_TIMEOUT_MS)?;
+        conn.execute("CREATE TABLE IF NOT EXISTS sessions (session_id TEXT PRIMARY KEY NOT NULL)")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS messages (
+                session_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                message TEXT NOT NULL,
+                PRIMARY KEY (session_id, position)
+            )",
+        )?;
+        Ok(Self {
+            path,
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// The database file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn append(&self, session_id: &str, message: &Value) -> Result<(), ContextError> {
+        let text = serde_json::to_string(message).map_err(|e| storage_error(e.to_string()))?;
+        let conn = self.conn.lock().unwrap();
+        conn.transaction(|conn| {
+            Self::touch_session(conn, session_id)?;
+            let mut insert = conn.prepare(
+                "INSERT INTO messages (session_id, position, message)
+                 SELECT ?1, COALESCE(MAX(position) + 1, 0), ?2
+                 FROM messages WHERE session_id = ?1",
+            )?;
+            insert.bind_text(1, session_id)?;
+            insert.bind_text(2, &text)?;
+            insert.run()
+        })
+    }
+
+    fn read_range(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: Option<usize>,
+    ) -> Result<Vec<Value>, ContextError> {
+        // Positions are contiguous from 0, so the range maps onto OFFSET and
+        // LIMIT; a negative LIMIT means "no limit" to SQLite.
+        let limit = end.map_or(-1, |end| end.saturating_sub(start) as i64);
+        let conn = self.conn.lock().unwrap();
+        let mut select = conn.prepare(
+            "SELECT message FROM messages WHERE session_id = ?1
+             ORDER BY position LIMIT ?2 OFFSET ?3",
+        )?;
+        select.bind_text(1, session_id)?;
+        select.bind_int64(2, limit)?;
+        select.bind_int64(3, i64::try_from(start).unwrap_or(i64::MAX))?;
+        let mut messages = Vec::new();
+        while select.step()? {
+            let text = select.column_text(0);
+            let message = serde_json::from_str(&text).map_err(|e| {
+                storage_error(format!(
+                    "{}: session {session_id}: {e}",
+                    self.path.display()
+                ))
+            })?;
+            messages.push(message);
+        }
+        Ok(messages)
+    }
+
+    fn replace(&self, session_id: &str, messages: &[Value]) -> Result<(), ContextError> {
+        let texts = messages
+            .iter()
+            .map(|m| serde_json::to_string(m).map_err(|e| storage_error(e.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        let conn = self.conn.lock().unwrap();
+        conn.transaction(|conn| {
+            Self::touch_session(conn, session_id)?;
+            let mut delete = conn.prepare("DELETE FROM messages WHERE session_id = ?1")?;
+            delete.bind_text(1, session_id)?;
+            delete.run()?;
+            for (position, text) in texts.iter().enumerate() {
+                let mut insert = conn.prepare(
+                    "INSERT INTO messages (session_id, position, message) VALUES (?1, ?2, ?3)",
+                )?;
+                insert.bind_text(1, session_id)?;
+                insert.bind_int64(2, position as i64)?;
+                insert.bind_text(3, text)?;
+                insert.run()?;
+            }
+            Ok(())
+        })
+    }
+
+    fn session_ids(&self) -> Result<Vec<String>, ContextError> {
+        let conn = self.conn.lock().unwrap();
+        let mut select = conn.prepare("SELECT session_id FROM sessions ORDER BY session_id")?;
+        let mut ids = Vec::new();
+        while select.step()? {
+            ids.push(select.column_text(0));
+        }
+        Ok(ids)
+    }
+
+    fn touch_session(conn: &sqlite::Connection, session_id: &str) -> Result<(), ContextError> {
+        let mut insert = conn.prepare("INSERT OR IGNORE INTO sessions (session_id) VALUES (?1)")?;
+        insert.bind_text(1, session_id)?;
+        insert.run()
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl ConversationStore for SqliteConversationStore {
+    fn append_message(
+        &self,
+        session_id: &str,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let result = self.append(session_id, &message);
+        Box::pin(async move { result })
+    }
+
+    fn load_range(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: Option<usize>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        let result = self.read_range(session_id, start, end);
+        Box::pin(async move { result })
+    }
+
+    fn replace_messages(
+        &self,
+        session_id: &str,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        let result = self.replace(session_id, &messages);
+        Box::pin(async move { result })
+    }
+
+    fn list_sessions(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, ContextError>> + Send + '_>> {
+        let result = self.session_ids();
+        Box::pin(async move { result })
+    }
+}
+
+/// Minimal safe wrapper over the system `libsqlite3` C API — just what
+/// [`SqliteConversationStore`] needs.
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use std::ffi::{c_char, c_int, c_void, CStr, CString};
+    use std::path::Path;
+    use std::ptr;
+
+    use super::storage_error;
+    use crate::errors::ContextError;
+
+    #[repr(C)]
+    struct Sqlite3 {
+        _private: [u8; 0],
+    }
+
+    #[repr(C)]
+    struct Sqlite3Stmt {
+        _private: [u8; 0],
+    }
+
+    const SQLITE_OK: c_int = 0;
+    const SQLITE_ROW: c_int = 100;
+    const SQLITE_DONE: c_int = 101;
+    const SQLITE_OPEN_READWRITE: c_int = 0x0000_0002;
+    const SQLITE_OPEN_CREATE: c_int = 0x0000_0004;
+
+    /// `SQLITE_TRANSIENT`: SQLite copies bound text before the call returns.
+    fn transient() -> *const c_void {
+        -1isize as *const c_void
+    }
+
+    #[link(name = "sqlite3")]
+    extern "C" {
+        fn sqlite3_open_v2(
+            filename: *const c_char,
+            db: *mut *mut Sqlite3,
+            flags: c_int,
+            vfs: *const c_char,
+        ) -> c_int;
+        fn sqlite3_close_v2(db: *mut Sqlite3) -> c_int;
+        fn sqlite3_errmsg(db: *mut Sqlite3) -> *const c_char;
+        fn sqlite3_busy_timeout(db: *mut Sqlite3, ms: c_int) -> c_int;
+        fn sqlite3_prepare_v2(
+            db: *mut Sqlite3,
+            sql: *const c_char,
+            len: c_int,
+            stmt: *mut *mut Sqlite3Stmt,
+            tail: *mut *const c_char,
+        ) -> c_int;
+        fn sqlite3_bind_text(
+            stmt: *mut Sqlite3Stmt,
+            index: c_int,
+            text: *const c_char,
+            len: c_int,
+            destructor: *const c_void,
+        ) -> c_int;
+        fn sqlite3_bind_int64(stmt: *mut Sqlite3Stmt, index: c_int, value: i64) -> c_int;
+        fn sqlite3_step(stmt: *mut Sqlite3Stmt) -> c_int;
+        fn sqlite3_column_text(stmt: *mut Sqlite3Stmt, column: c_int) -> *const u8;
+        fn sqlite3_column_bytes(stmt: *mut Sqlite3Stmt, column: c_int) -> c_int;
+        fn sqlite3_finalize(stmt: *mut Sqlite3Stmt) -> c_int;
+    }
+
+    /// An open database handle. Callers serialize access to it.
+    pub(super) struct Connection {
+        db: *mut Sqlite3,
+    }
+
+    // SAFETY: the handle is only used behind the store's mutex, and the
+    // system library is built thread-safe, so moving it between threads is
+    // sound.
+    unsafe impl Send for Connection {}
+
+    impl Connection {
+        pub(super) fn open(path: &Path, busy_timeout_ms: i32) -> Result<Self, ContextError> {
+            let name = path
+                .to_str()
+                .and_then(|p| CString::new(p).ok())
+                .ok_or_else(|| storage_error(format!("invalid database path: {path:?}")))?;
+            let mut db = ptr::null_mut();
+            // SAFETY: `name` is NUL-terminated and `db` is a valid out-pointer.
+            let rc = unsafe {
+                sqlite3_open_v2(
+                    name.as_ptr(),
+                    &mut db,
+                    SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE,
+                    ptr::null(),
+                )
+            };
+            // SQLite allocates a handle even when opening fails; owning it
+            // here closes it on every path.
+            let conn = Self { db };
+            if rc != SQLITE_OK {
+                return Err(conn.error(&format!("open {}", path.display())));
+            }
+            // SAFETY: `conn.db` is an open handle.
+            unsafe { sqlite3_busy_timeout(conn.db, busy_timeout_ms) };
+            Ok(conn)
+        }
+
+        pub(super) fn prepare(&self, sql: &str) -> Result<Statement<'_>, ContextError> {
+            let mut stmt = ptr::null_mut();
+            // SAFETY: `sql` is valid for `sql.len()` bytes; `stmt` is a
+            // valid out-pointer.
+            let rc = unsafe {
+                sqlite3_prepare_v2(
+                    self.db,
+                    sql.as_ptr().cast(),
+                    sql.len() as c_int,
+                    &mut stmt,
+                    ptr::null_mut(),
+                )
+            };
+            if rc != SQLITE_OK {
+                return Err(self.error("prepare"));
+            }
+            Ok(Statement { conn: self, stmt })
+        }
+
+        /// Run a statement that returns no rows.
+        pub(super) fn execute(&self, sql: &str) -> Result<(), ContextError> {
+            self.prepare(sql)?.run()
+        }
+
+        /// Run `body` inside a write transaction, rolling back if it fails.
+        pub(super) fn transaction<T>(
+            &self,
+            body: impl FnOnce(&Self) -> Result<T, ContextError>,
+        ) -> Result<T, ContextError> {
+            self.execute("BEGIN IMMEDIATE")?;
+            match body(self) {
+                Ok(value) => {
+                    self.execute("COMMIT")?;
+                    Ok(value)
+                }
+                Err(e) => {
+                    let _ = self.execute("ROLLBACK");
+                    Err(e)
+                }
+            }
+        }
+
+        fn error(&self, context: &str) -> ContextError {
+            if self.db.is_null() {
+                return storage_error(format!("sqlite {context}: out of memory"));
+            }
+            // SAFETY: `errmsg` returns a NUL-terminated string owned by the
+            // handle, valid until the next call on it.
+            let message = unsafe { CStr::from_ptr(sqlite3_errmsg(self.db)) };
+            storage_error(format!("sqlite {context}: {}", message.to_string_lossy()))
+        }
+    }
+
+    impl Drop for Connection {
+        fn drop(&mut self) {
+            // SAFETY: every statement borrows the connection, so none is
+            // still live here; closing a null handle is a no-op.
+            unsafe { sqlite3_close_v2(self.db) };
+        }
+    }
+
+    /// A prepared statement, finalized on drop.
+    pub(super) struct Statement<'c> {
+        conn: &'c Connection,
+        stmt: *mut Sqlite3Stmt,
+    }
+
+    impl Statement<'_> {
+        pub(super) fn bind_text(&mut self, index: i32, text: &str) -> Result<(), ContextError> {
+            let len = c_int::try_from(text.len())
+                .map_err(|_| storage_error("sqlite bind: text too large"))?;
+            // SAFETY: `text` is valid for `len` bytes and SQLite copies it
+            // (`SQLITE_TRANSIENT`) before returning.
+            let rc = unsafe {
+                sqlite3_bind_text(self.stmt, index, text.as_ptr().cast(), len, transient())
+            };
+            self.check(rc, "bind")
+        }
+
+        pub(super) fn bind_int64(&mut self, index: i32, value: i64) -> Result<(), ContextError> {
+            // SAFETY: `self.stmt` is a live prepared statement.
+            let rc = unsafe { sqlite3_bind_int64(self.stmt, index, value) };
+            self.check(rc, "bind")
+        }
+
+        /// Advance to the next row; `false` once the statement is done.
+        pub(super) fn step(&mut self) -> Result<bool, ContextError> {
+            // SAFETY: `self.stmt` is a live prepared statement.
+            match unsafe { sqlite3_step(self.stmt) } {
+                SQLITE_ROW => Ok(true),
+                SQLITE_DONE => Ok(false),
+                _ => Err(self.conn.error("step")),
+            }
+        }
+
+        /// Step a statement that returns no rows to completion.
+        pub(super) fn run(mut self) -> Result<(), ContextError> {
+            while self.step()? {}
+            Ok(())
+        }
+
+        /// The text of `column` in the current row (empty for NULL).
+        pub(super) fn column_text(&self, column: i32) -> String {
+            // SAFETY: called after `step` returned a row; the pointer and
+            // length describe a buffer owned by the statement that stays
+            // valid until the next step, and is copied out here.
+            unsafe {
+                let text = sqlite3_column_text(self.stmt, column);
+                if text.is_null() {
+                    return String::new();
+                }
+                let len = sqlite3_column_bytes(self.stmt, column) as usize;
+                String::from_utf8_lossy(std::slice::from_raw_parts(text, len)).into_owned()
+            }
+        }
+
+        fn check(&self, rc: c_int, context: &str) -> Result<(), ContextError> {
+            if rc == SQLITE_OK {
+                Ok(())
+            } else {
+                Err(self.conn.error(context))
+            }
+        }
+    }
+
+    impl Drop for Statement<'_> {
+        fn drop(&mut self) {
+            // SAFETY: `self.stmt` came from `sqlite3_prepare_v2` and is
+            // finalized exactly once.
+            unsafe { sqlite3_finalize(self.stmt) };
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PersistentContext
+// ---------------------------------------------------------------------------
+
+/// A [`ContextManager`] that writes every change through to a
+/// [`ConversationStore`] before delegating to an inner context manager.
+///
+/// Reads (including compaction in `get_messages_for_request`) are served by
+/// the inner context manager; the store only records what it was given.
+pub struct PersistentContext {
+    inner: Arc<dyn ContextManager>,
+    store: Arc<dyn ConversationStore>,
+    session_id: String,
+}
+
+impl PersistentContext {
+    /// Wrap `inner`, persisting its history under `session_id` in `store`.
+    pub fn new(
+        inner: Arc<dyn ContextManager>,
+        store: Arc<dyn ConversationStore>,
+        session_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner,
+            store,
+            session_id: session_id.into(),
+        }
+    }
+
+    /// Load the stored history into the inner context manager.
+    ///
+    /// Returns the number of messages restored. Does nothing (and returns 0)
+    /// if the store has no history for this session.
+    pub async fn restore(&self) -> Result<usize, ContextError> {
+        let messages = self.store.load_range(&self.session_id, 0, None).await?;
+        let count = messages.len();
+        if count > 0 {
+            self.inner.set_messages(messages).await?;
+        }
+        Ok(count)
+    }
+}
+
+impl ContextManager for PersistentContext {
+    fn add_message(
+        &self,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        Box::pin(async move {
+            self.store
+                .append_message(&self.session_id, message.clone())
+                .await?;
+            self.inner.add_message(message).await
+        })
+    }
+
+    fn get_messages_for_request(
+        &self,
+        token_budget: Option<i64>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        self.inner.get_messages_for_request(token_budget, provider)
+    }
+
+    fn get_messages(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        self.inner.get_messages()
+    }
+
+    fn set_messages(
+        &self,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        Box::pin(async move {
+            self.store
+                .replace_messages(&self.session_id, messages.clone())
+                .await?;
+            self.inner.set_messages(messages).await
+        })
+    }
+
+    fn clear(&self) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        Box::pin(async move {
+            self.store
+                .replace_messages(&self.session_id, Vec::new())
+                .await?;
+            self.inner.clear().await
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeContextManager;
+    use serde_json::json;
+
+    fn msg(n: usize) -> Value {
+        json!({"role": "user", "content": format!("m{n}")})
+    }
+
+    async fn exercise_store(store: &dyn ConversationStore) {
+        for n in 0..4 {
+            store.append_message("s1", msg(n)).await.unwrap();
+        }
+        store.append_message("s0", msg(9)).await.unwrap();
+
+        assert_eq!(store.load_range("s1", 0, None).await.unwrap().len(), 4);
+        assert_eq!(
+            store.load_range("s1", 1, Some(3)).await.unwrap(),
+            vec![msg(1), msg(2)]
+        );
+        // Bounds are clamped
+        assert_eq!(
+            store.load_range("s1", 3, Some(99)).await.unwrap(),
+            vec![msg(3)]
+        );
+        assert!(store.load_range("s1", 10, None).await.unwrap().is_empty());
+        assert!(store
+            .load_range("missing", 0, None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        store.replace_messages("s1", vec![msg(7)]).await.unwrap();
+        assert_eq!(store.load_range("s1", 0, None).await.unwrap(), vec![msg(7)]);
+
+        assert_eq!(store.list_sessions().await.unwrap(), vec!["s0", "s1"]);
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_contract() {
+        exercise_store(&InMemoryConversationStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn file_store_contract() {
+        let dir = tempfile::tempdir().unwrap();
+        exercise_store(&FileConversationStore::open(dir.path()).unwrap()).await;
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_store_contract() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteConversationStore::open(dir.path().join("conversations.db")).unwrap();
+        exercise_store(&store).await;
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn sqlite_store_survives_reopen_and_shares_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conversations.db");
+        let first = SqliteConversationStore::open(&path).unwrap();
+        let second = SqliteConversationStore::open(&path).unwrap();
+        first.append_message("s1", msg(0)).await.unwrap();
+        second.append_message("s1", msg(1)).await.unwrap();
+        first.replace_messages("empty", Vec::new()).await.unwrap();
+        drop((first, second));
+
+        let reopened = SqliteConversationStore::open(&path).unwrap();
+        assert_eq!(
+            reopened.load_range("s1", 0, None).await.unwrap(),
+            vec![msg(0), msg(1)]
+        );
+        assert_eq!(reopened.list_sessions().await.unwrap(), vec!["empty", "s1"]);
+    }
+
+    #[tokio::test]
+    async fn file_store_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let store = FileConversationStore::open(dir.path()).unwrap();
+            store.append_message("s1", msg(0)).await.unwrap();
+            store.append_message("s1", msg(1)).await.unwrap();
+        }
+        let reopened = FileConversationStore::open(dir.path()).unwrap();
+        assert_eq!(
+            reopened.load_range("s1", 0, None).await.unwrap(),
+            vec![msg(0), msg(1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn file_store_rejects_path_like_session_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileConversationStore::open(dir.path()).unwrap();
+        for bad in ["", "..", "a/b", "a\\b"] {
+            let err = store.append_message(bad, msg(0)).await.unwrap_err();
+            assert!(matches!(err, ContextError::Storage { .. }), "{bad:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn persistent_context_writes_through_and_restores() {
+        let store: Arc<dyn ConversationStore> = Arc::new(InMemoryConversationStore::new());
+        let ctx = PersistentContext::new(Arc::new(FakeContextManager::new()), store.clone(), "s1");
+        ctx.add_message(msg(0)).await.unwrap();
+        ctx.add_message(msg(1)).await.unwrap();
+        assert_eq!(store.load_range("s1", 0, None).await.unwrap().len(), 2);
+
+        // A fresh context over the same store picks the history back up.
+        let inner = Arc::new(FakeContextManager::new());
+        let resumed = PersistentContext::new(inner.clone(), store.clone(), "s1");
+        assert_eq!(resumed.restore().await.unwrap(), 2);
+        assert_eq!(inner.get_messages().await.unwrap(), vec![msg(0), msg(1)]);
+
+        resumed.clear().await.unwrap();
+        assert!(store.load_range("s1", 0, None).await.unwrap().is_empty());
+        assert!(inner.get_messages().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn in_memory_store_respects_memory_ceiling() {
+        use crate::memory::{EvictionPolicy, MemoryConfig};
+        use crate::testing::FakeHookHandler;
+
+        let size = json_size(&msg(0));
+        let memory = Arc::new(MemoryAccountant::new(MemoryConfig {
+            ceiling_bytes: Some(size * 2),
+            policy: EvictionPolicy::DropOldest,
+            ..Default::default()
+        }));
+        let hooks = Arc::new(HookRegistry::new());
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(KERNEL_MEMORY_EVICTED, handler.clone(), 0, None);
+        let store = InMemoryConversationStore::with_memory(memory.clone()).with_hooks(hooks);
+        for n in 0..3 {
+            store.append_message("s1", msg(n)).await.unwrap();
+        }
+        assert_eq!(
+            store.load_range("s1", 0, None).await.unwrap(),
+            vec![msg(1), msg(2)]
+        );
+        assert_eq!(memory.usage().by_category["conversation_store"], size * 2);
+
+        let events = handler.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["category"], "conversation_store");
+        assert_eq!(events[0].1["session_id"], "s1");
+        assert_eq!(events[0].1["evicted"], 1);
+
+        store
+            .replace_messages("s1", vec![msg(3), msg(4), msg(5)])
+            .await
+            .unwrap();
+        assert_eq!(handler.recorded_events().len(), 2);
+    }
+}
@@ -9,27 +9,104 @@
 //! The Python `ModuleCoordinator` uses dynamic typing extensively. In Rust
 //! we use typed fields for the four primary module slots (orchestrator,
 //! context, providers, tools) and typed accessor methods. Capabilities
-//! are stored as `serde_json::Value` for maximum flexibility.
+//! are stored as `serde_json::Value` for maximum flexibility; modules that
+//! need to share behavior rather than data register a typed capability
+//! object (any `Arc<T>`, including trait objects) alongside them.
+//!
+//! # Concurrency
+//!
+//! Tool and provider tables are copy-on-write snapshots behind an
+//! [`ArcSwap`], like the hook registry's handler table: [`tools()`] and
+//! [`providers()`] hand out the current `Arc<HashMap>` without locking or
+//! copying, and mounting publishes a new map. Other read-mostly state sits
+//! behind `RwLock`s; per-turn counters and cleanup lists keep `Mutex`es. No
+//! guard is held across an `.await`.
+//!
+//! [`tools()`]: Coordinator::tools
+//! [`providers()`]: Coordinator::providers
 //!
 //! # Connections
 //!
 //! - Holds a [`HookRegistry`](crate::hooks::HookRegistry) for event dispatch.
 //! - Holds a [`CancellationToken`](crate::cancellation::CancellationToken)
-//!   for cooperative cancellation.
+//!   for cooperative cancellation, and emits `cancel:requested` /
+//!   `cancel:escalated` through its hooks when the token changes state.
 //! - Stores modules as `Arc<dyn Trait>` from [`crate::traits`].
-
-use std::collections::HashMap;
+//! - Holds the session's [`MemoryAccountant`](crate::memory::MemoryAccountant)
+//!   and, optionally, its [`AttachmentStore`](crate::attachments::AttachmentStore).
+//! - Holds typed host data (one value per Rust type) for embedding
+//!   applications; see [`Coordinator::set_host_data`].
+//! - Records the capabilities each module requires
+//!   ([`Coordinator::require_capabilities`]); sessions call
+//!   [`Coordinator::check_capabilities`] before executing.
+//! - [`Coordinator::describe`] summarizes mounts and registrations as a
+//!   [`CoordinatorReport`] for host diagnostics.
+//! - Drives [`ModuleLifecycle`](crate::traits::ModuleLifecycle) for modules
+//!   that implement it: `init` on managed mounts, `shutdown` on managed
+//!   unmounts and [`Coordinator::cleanup`], and [`Coordinator::health`]
+//!   aggregates their health checks into a [`HealthReport`].
+//! - Mounts a resolved [`MountPlan`](crate::manifest::MountPlan) through a
+//!   [`ModuleFactory`](crate::manifest::ModuleFactory) in dependency order,
+//!   rolling back on failure ([`Coordinator::apply_mount_plan`]).
+//! - Throttles [`Coordinator::notify_user`] notifications before emitting
+//!   them as `user:notification` (see [`crate::notifications`]).
+//! - Caches provider model lists ([`Coordinator::models`]; see
+//!   [`crate::model_catalog`]).
+//! - Hands native orchestrators a per-turn [`TurnRecovery`] from the
+//!   session's `session.turn_recovery` policy ([`Coordinator::turn_recovery`]).
+
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::future::Future;
+use std::marker::PhantomData;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
+use arc_swap::ArcSwap;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::cancellation::CancellationToken;
+use crate::approval::ApprovalGate;
+use crate::approval_memory::{ApprovalMemory, ApprovalMemoryConfig};
+use crate::attachments::AttachmentStore;
+use crate::cancellation::{CancellationState, CancellationToken, StateChangeCallback};
+use crate::catalog::ModuleCatalog;
+use crate::clock::Clock;
+use crate::config_schema;
+use crate::credentials::{CredentialResolver, EnvCredentialResolver};
+use crate::deadline::TurnDeadline;
+use crate::display::ServiceDisplay;
+use crate::errors::{CoordinatorError, ProviderError};
+use crate::events;
 use crate::hooks::HookRegistry;
+use crate::injection_queue::InjectionQueue;
+use crate::manifest::{
+    ManifestError, ModuleDescriptor, ModuleFactory, MountPlan, MountStep, MountTarget,
+};
+use crate::memory::{MemoryAccountant, MemoryConfig};
+use crate::model_catalog::{ModelCatalog, ModelCatalogConfig};
+use crate::models::{HealthStatus, ModelInfo, ModuleHealth, ModuleInfo, ModuleType};
+use crate::module_resolver::LoadedModule;
+use crate::notifications::{
+    NotificationConfig, NotificationLevel, NotificationOutcome, NotificationThrottle,
+};
+use crate::recovery::{TurnRecovery, TurnRecoveryPolicy};
+use crate::streaming::{ResponseAccumulator, StreamEventConfig};
+use crate::token_counter::{HeuristicTokenCounter, TokenCounter};
+use crate::tool_cache::{PureToolCache, PureToolCacheConfig};
+use crate::tool_executor::ToolResultCache;
+use crate::tool_output::{ToolOutputConfig, ToolOutputProcessor};
+use crate::topology::{GraphFormat, ModuleGraph};
 use crate::traits::{
-    ApprovalProvider, ContextManager, DisplayService, Orchestrator, Provider, Tool,
+    ApprovalProvider, ContextManager, DisplayProvider, DisplayService, Moderator, ModuleLifecycle,
+    Orchestrator, Provider, Tool,
 };
+use crate::visibility::VisibilityConfig;
+use crate::workspace::Workspace;
 
 // ---------------------------------------------------------------------------
 // Type aliases for cleanup and contributor callbacks
@@ -54,10 +131,371 @@ struct ContributorEntry {
     callback: ContributorCallback,
 }
 
+/// A declared contribution channel whose contributions deserialize into `T`.
+///
+/// Obtained from [`Coordinator::declare_channel`] and passed to
+/// [`Coordinator::collect_channel`].
+pub struct Channel<T> {
+    name: String,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Channel<T> {
+    /// The channel name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> Clone for Channel<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Channel")
+            .field("name", &self.name)
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+/// Contributions collected from a typed [`Channel`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contributions<T> {
+    /// Valid contributions, in registration order.
+    pub values: Vec<T>,
+    /// Contributors whose callback failed or whose contribution did not
+    /// deserialize, in registration order.
+    pub invalid: Vec<InvalidContribution>,
+}
+
+impl<T> Default for Contributions<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            invalid: Vec::new(),
+        }
+    }
+}
+
+/// A contribution rejected by [`Coordinator::collect_channel`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidContribution {
+    /// The contributor's registered name.
+    pub contributor: String,
+    /// The callback error or deserialization error.
+    pub message: String,
+}
+
+// ---------------------------------------------------------------------------
+// MountPoint
+// ---------------------------------------------------------------------------
+
+/// Canonical mount-point names.
+///
+/// Every binding parses mount-point strings through [`MountPoint::from_str`]
+/// so that unknown names fail fast with the same
+/// [`CoordinatorError::UnknownMountPoint`] everywhere.
+///
+/// | Variant        | Name                       | Slots  |
+/// |----------------|----------------------------|--------|
+/// | `Orchestrator` | `"orchestrator"`           | single |
+/// | `Context`      | `"context"`                | single |
+/// | `Providers`    | `"providers"`              | named  |
+/// | `Tools`        | `"tools"`                  | named  |
+/// | `Agents`       | `"agents"`                 | named  |
+/// | `Resolver`     | `"module-source-resolver"` | single |
+/// | `Hooks`        | `"hooks"`                  | read-only (register on the `HookRegistry`) |
+///
+/// # Example
+///
+/// ```rust
+/// use amplifier_core::coordinator::MountPoint;
+///
+/// let mp: MountPoint = "tools".parse().unwrap();
+/// assert_eq!(mp, MountPoint::Tools);
+/// assert!(mp.is_multi_slot());
+/// assert!("tool".parse::<MountPoint>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MountPoint {
+    Orchestrator,
+    Context,
+    Providers,
+    Tools,
+    Agents,
+    Resolver,
+    Hooks,
+}
+
+impl MountPoint {
+    /// Every mount point, in canonical order.
+    pub const ALL: &'static [MountPoint] = &[
+        MountPoint::Orchestrator,
+        MountPoint::Context,
+        MountPoint::Providers,
+        MountPoint::Tools,
+        MountPoint::Agents,
+        MountPoint::Resolver,
+        MountPoint::Hooks,
+    ];
+
+    /// The canonical string name (the key used in Python's `mount_points` dict).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MountPoint::Orchestrator => "orchestrator",
+            MountPoint::Context => "context",
+            MountPoint::Providers => "providers",
+            MountPoint::Tools => "tools",
+            MountPoint::Agents => "agents",
+            MountPoint::Resolver => "module-source-resolver",
+            MountPoint::Hooks => "hooks",
+        }
+    }
+
+    /// Whether modules are stored by name (providers, tools, agents).
+    pub fn is_multi_slot(&self) -> bool {
+        matches!(
+            self,
+            MountPoint::Providers | MountPoint::Tools | MountPoint::Agents
+        )
+    }
+
+    /// Validate that a module may be mounted here.
+    ///
+    /// Returns [`CoordinatorError::NotMountable`] for `hooks` and
+    /// [`CoordinatorError::NameRequired`] for a multi-slot point without a
+    /// name.
+    pub fn validate_mount(&self, name: Option<&str>) -> Result<(), CoordinatorError> {
+        if *self == MountPoint::Hooks {
+            return Err(CoordinatorError::NotMountable {
+                mount_point: self.as_str().to_string(),
+                reason: "hooks should be registered directly with the HookRegistry".to_string(),
+            });
+        }
+        if self.is_multi_slot() && name.is_none() {
+            return Err(CoordinatorError::NameRequired {
+                mount_point: self.as_str().to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MountPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MountPoint {
+    type Err = CoordinatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MountPoint::ALL
+            .iter()
+            .copied()
+            .find(|mp| mp.as_str() == s)
+            .ok_or_else(|| CoordinatorError::UnknownMountPoint {
+                name: s.to_string(),
+                expected: MountPoint::ALL
+                    .iter()
+                    .map(|mp| mp.as_str().to_string())
+                    .collect(),
+            })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CoordinatorReport
+// ---------------------------------------------------------------------------
+
+/// One mounted module, as listed by [`Coordinator::describe`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MountedModule {
+    /// Canonical mount-point name (see [`MountPoint::as_str`]).
+    pub mount_point: String,
+    /// Mount name: the tool or provider name, or the mount point's name for
+    /// single-slot mount points.
+    pub name: String,
+    /// Module type.
+    #[serde(rename = "type")]
+    pub module_type: ModuleType,
+    /// Tool description or provider display name, when the module has one.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Metadata recorded with [`Coordinator::set_module_info`], if any.
+    #[serde(default)]
+    pub info: Option<ModuleInfo>,
+}
+
+/// Snapshot of a coordinator's mounts and registrations, returned by
+/// [`Coordinator::describe`].
+///
+/// Serializes to JSON for host diagnostics endpoints.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoordinatorReport {
+    /// Mounted modules in canonical mount-point order, then by name.
+    pub modules: Vec<MountedModule>,
+    /// Names of registered JSON capabilities.
+    pub capabilities: Vec<String>,
+    /// Names of registered typed capability objects.
+    pub capability_objects: Vec<String>,
+    /// Contributor names per contribution channel, in registration order.
+    pub channels: BTreeMap<String, Vec<String>>,
+    /// Registered hook handler count per event (events without handlers are omitted).
+    pub hooks: BTreeMap<String, usize>,
+    /// Number of pending cleanup functions.
+    pub cleanup_functions: usize,
+    /// Whether an approval provider is set.
+    pub has_approval_provider: bool,
+    /// Whether a display service is set.
+    pub has_display_service: bool,
+}
+
+// ---------------------------------------------------------------------------
+// HealthReport
+// ---------------------------------------------------------------------------
+
+/// Health of one mounted module, as listed by [`Coordinator::health`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleHealthEntry {
+    /// Canonical mount-point name (see [`MountPoint::as_str`]).
+    pub mount_point: String,
+    /// Mount name, as in [`MountedModule::name`].
+    pub name: String,
+    #[serde(flatten)]
+    pub health: ModuleHealth,
+}
+
+/// Aggregated module health, returned by [`Coordinator::health`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// The worst status among `modules` ([`HealthStatus::Healthy`] if empty).
+    pub status: HealthStatus,
+    /// Mounted modules that implement [`ModuleLifecycle`], in canonical
+    /// mount-point order, then by name.
+    pub modules: Vec<ModuleHealthEntry>,
+}
+
+/// A mounted module kept alive across lifecycle calls.
+enum LifecycleModule {
+    Orchestrator(Arc<dyn Orchestrator>),
+    Context(Arc<dyn ContextManager>),
+    Provider(Arc<dyn Provider>),
+    Tool(Arc<dyn Tool>),
+}
+
+impl LifecycleModule {
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        match self {
+            Self::Orchestrator(module) => module.lifecycle(),
+            Self::Context(module) => module.lifecycle(),
+            Self::Provider(module) => module.lifecycle(),
+            Self::Tool(module) => module.lifecycle(),
+        }
+    }
+}
+
+/// Shut down `module` if it has a lifecycle, logging failures.
+async fn shutdown_module(mount_point: MountPoint, name: &str, module: &LifecycleModule) {
+    if let Some(lifecycle) = module.lifecycle() {
+        if let Err(e) = lifecycle.shutdown().await {
+            log::warn!("{mount_point} module '{name}' failed to shut down: {e}");
+        }
+    }
+}
+
+/// Run `init` on a module about to be mounted.
+async fn init_module(
+    mount_point: MountPoint,
+    name: &str,
+    lifecycle: Option<&dyn ModuleLifecycle>,
+    config: Value,
+) -> Result<(), CoordinatorError> {
+    let Some(lifecycle) = lifecycle else {
+        return Ok(());
+    };
+    lifecycle
+        .init(config)
+        .await
+        .map_err(|e| CoordinatorError::ModuleInitFailed {
+            mount_point: mount_point.to_string(),
+            name: name.to_string(),
+            message: e.to_string(),
+        })
+}
+
+/// How to undo one step of [`Coordinator::apply_mount_plan`].
+enum MountUndo {
+    Orchestrator(Option<Arc<dyn Orchestrator>>, Arc<dyn Orchestrator>),
+    Context(Option<Arc<dyn ContextManager>>, Arc<dyn ContextManager>),
+    Approval(Option<Arc<dyn ApprovalProvider>>),
+    Provider(String),
+    Tool(String),
+    Hook(Vec<Box<dyn Fn() + Send + Sync>>),
+    Requirements(String, Option<Vec<String>>),
+}
+
 // ---------------------------------------------------------------------------
 // Coordinator
 // ---------------------------------------------------------------------------
 
+/// A mountable [`Moderator`], shared so hooks see later mounts.
+pub(crate) type ModeratorSlot = Arc<RwLock<Option<Arc<dyn Moderator>>>>;
+
+type CapabilityObjects = Arc<RwLock<HashMap<String, Arc<dyn Any + Send + Sync>>>>;
+
+/// Read-only view of a coordinator's capabilities.
+///
+/// Shares the coordinator's registries, so later registrations are visible.
+/// Tools get one on their [`ToolContext`](crate::models::ToolContext) and
+/// look capabilities up without holding the coordinator.
+#[derive(Clone, Default)]
+pub struct CapabilityLookup {
+    values: Arc<RwLock<HashMap<String, Value>>>,
+    objects: CapabilityObjects,
+}
+
+impl CapabilityLookup {
+    /// See [`Coordinator::get_capability`].
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.values.read().unwrap().get(name).cloned()
+    }
+
+    /// See [`Coordinator::get_capability_object`].
+    pub fn get_object<T>(&self, name: &str) -> Option<Arc<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        let objects = self.objects.read().unwrap();
+        let object = objects.get(name)?;
+        let typed = object.downcast_ref::<Arc<T>>();
+        if typed.is_none() {
+            log::debug!(
+                "Capability object '{name}' is not a {}",
+                std::any::type_name::<T>()
+            );
+        }
+        typed.cloned()
+    }
+}
+
+impl fmt::Debug for CapabilityLookup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CapabilityLookup")
+            .field("values", &self.values.read().unwrap().len())
+            .field("objects", &self.objects.read().unwrap().len())
+            .finish()
+    }
+}
+
 /// Central coordination hub for module mount points, capabilities, and services.
 ///
 /// Holds the four primary module slots (orchestrator, context manager,
@@ -73,18 +511,28 @@ struct ContributorEntry {
 /// ```
 pub struct Coordinator {
     // -- Module mount points (typed) --
-    orchestrator: Mutex<Option<Arc<dyn Orchestrator>>>,
-    context: Mutex<Option<Arc<dyn ContextManager>>>,
-    providers: Mutex<HashMap<String, Arc<dyn Provider>>>,
-    tools: Mutex<HashMap<String, Arc<dyn Tool>>>,
+    orchestrator: RwLock<Option<Arc<dyn Orchestrator>>>,
+    context: RwLock<Option<Arc<dyn ContextManager>>>,
+    providers: ArcSwap<HashMap<String, Arc<dyn Provider>>>,
+    tools: ArcSwap<HashMap<String, Arc<dyn Tool>>>,
+    /// Metadata for mounted modules, keyed by mount point and mount name.
+    module_info: RwLock<HashMap<(MountPoint, String), ModuleInfo>>,
+    /// Cached `list_models` results, by provider name.
+    model_catalog: ModelCatalog,
 
     // -- Subsystems --
     hooks: Arc<HookRegistry>,
     cancellation: CancellationToken,
 
     // -- Capabilities & contributions --
-    capabilities: Mutex<HashMap<String, Value>>,
+    capabilities: Arc<RwLock<HashMap<String, Value>>>,
+    /// Each value is an `Arc<T>` boxed as `Any`, so `T` may be a trait object.
+    capability_objects: CapabilityObjects,
+    /// Capabilities each module needs, by module name.
+    capability_requirements: RwLock<BTreeMap<String, Vec<String>>>,
     channels: Mutex<HashMap<String, Vec<ContributorEntry>>>,
+    /// Declared type name per typed channel.
+    channel_types: Mutex<HashMap<String, &'static str>>,
 
     // -- Cleanup --
     cleanup_functions: Mutex<Vec<CleanupFn>>,
@@ -93,30 +541,109 @@ pub struct Coordinator {
     config: HashMap<String, Value>,
 
     // -- App-layer services --
-    approval_provider: Mutex<Option<Arc<dyn ApprovalProvider>>>,
-    display_service: Mutex<Option<Arc<dyn DisplayService>>>,
+    approval_provider: RwLock<Option<Arc<dyn ApprovalProvider>>>,
+    approval_memory: Arc<ApprovalMemory>,
+    display_service: RwLock<Option<Arc<dyn DisplayService>>>,
+    display_provider: RwLock<Option<Arc<dyn DisplayProvider>>>,
+    /// Shared with the [`builtin:moderation`](crate::hooks::builtin::ModerationHook) hook.
+    moderator: ModeratorSlot,
+    notifications: NotificationThrottle,
+    stream_events: StreamEventConfig,
 
     // -- Turn tracking --
-    current_turn_injections: Mutex<usize>,
+    turn_recovery: TurnRecoveryPolicy,
+    injection_queue: Arc<InjectionQueue>,
+    turn_deadline: Mutex<Option<TurnDeadline>>,
+    turn_number: Mutex<u64>,
+    turn_id: Mutex<Option<String>>,
+    tool_results: Arc<ToolResultCache>,
+    pure_tool_results: Arc<PureToolCache>,
+
+    // -- Resource accounting --
+    memory: Arc<MemoryAccountant>,
+    token_counter: RwLock<Arc<dyn TokenCounter>>,
+    attachment_store: RwLock<Option<Arc<AttachmentStore>>>,
+    tool_output: RwLock<Arc<ToolOutputProcessor>>,
+    visibility: RwLock<Arc<VisibilityConfig>>,
+    workspace: Option<Arc<Workspace>>,
+
+    // -- Credentials --
+    credential_resolver: RwLock<Arc<dyn CredentialResolver>>,
+
+    // -- Host application data --
+    host_data: RwLock<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
 }
 
 impl Coordinator {
     /// Create a new coordinator with the given session config.
+    ///
+    /// The memory ceiling is read from `session.memory` (see [`crate::memory`])
+    /// tool output limits from `session.tool_output` (see
+    /// [`crate::tool_output`]), pure tool caching from `session.tool_cache`
+    /// (see [`crate::tool_cache`]), provider visibility policies from
+    /// `session.visibility` (see [`crate::visibility`]) and the filesystem
+    /// scope from `session.workspace` (see [`crate::workspace`]).
     pub fn new(config: HashMap<String, Value>) -> Self {
+        let memory = Arc::new(MemoryAccountant::new(MemoryConfig::from_session_config(
+            &config,
+        )));
+        let tool_output = ToolOutputProcessor::new(ToolOutputConfig::from_session_config(&config));
+        let pure_tool_results =
+            PureToolCache::new(PureToolCacheConfig::from_session_config(&config));
+        let approval_memory =
+            ApprovalMemory::new(ApprovalMemoryConfig::from_session_config(&config))
+                .with_memory(Arc::clone(&memory));
+        let visibility = VisibilityConfig::from_session_config(&config);
+        let workspace = Workspace::from_session_config(&config).map(Arc::new);
+        let notifications =
+            NotificationThrottle::new(NotificationConfig::from_session_config(&config));
+        let turn_recovery = TurnRecoveryPolicy::from_session_config(&config);
+        let stream_events = StreamEventConfig::from_session_config(&config);
+        let model_catalog = ModelCatalog::new(ModelCatalogConfig::from_session_config(&config));
+        let injection_queue = Arc::new(InjectionQueue::from_session_config(&config));
+        let hooks = Arc::new(HookRegistry::new());
+        hooks.set_injection_queue(Arc::clone(&injection_queue));
+        hooks.set_memory(Arc::clone(&memory));
+        let cancellation = CancellationToken::new();
+        cancellation.on_state_change(cancel_event_forwarder(Arc::clone(&hooks)));
         Self {
-            orchestrator: Mutex::new(None),
-            context: Mutex::new(None),
-            providers: Mutex::new(HashMap::new()),
-            tools: Mutex::new(HashMap::new()),
-            hooks: Arc::new(HookRegistry::new()),
-            cancellation: CancellationToken::new(),
-            capabilities: Mutex::new(HashMap::new()),
+            orchestrator: RwLock::new(None),
+            context: RwLock::new(None),
+            providers: ArcSwap::from_pointee(HashMap::new()),
+            tools: ArcSwap::from_pointee(HashMap::new()),
+            module_info: RwLock::new(HashMap::new()),
+            model_catalog,
+            hooks,
+            cancellation,
+            capabilities: Default::default(),
+            capability_objects: Default::default(),
+            capability_requirements: RwLock::new(BTreeMap::new()),
             channels: Mutex::new(HashMap::new()),
+            channel_types: Mutex::new(HashMap::new()),
             cleanup_functions: Mutex::new(Vec::new()),
             config,
-            approval_provider: Mutex::new(None),
-            display_service: Mutex::new(None),
-            current_turn_injections: Mutex::new(0),
+            approval_provider: RwLock::new(None),
+            approval_memory: Arc::new(approval_memory),
+            display_service: RwLock::new(None),
+            display_provider: RwLock::new(None),
+            moderator: Arc::new(RwLock::new(None)),
+            notifications,
+            stream_events,
+            turn_recovery,
+            injection_queue,
+            turn_deadline: Mutex::new(None),
+            turn_number: Mutex::new(0),
+            turn_id: Mutex::new(None),
+            tool_results: Arc::new(ToolResultCache::new()),
+            pure_tool_results: Arc::new(pure_tool_results),
+            memory,
+            token_counter: RwLock::new(Arc::new(HeuristicTokenCounter::default())),
+            attachment_store: RwLock::new(None),
+            tool_output: RwLock::new(Arc::new(tool_output)),
+            visibility: RwLock::new(Arc::new(visibility)),
+            workspace,
+            credential_resolver: RwLock::new(Arc::new(EnvCredentialResolver)),
+            host_data: RwLock::new(HashMap::new()),
         }
     }
 
@@ -129,144 +656,403 @@ impl Coordinator {
 
     /// Set the orchestrator module (single slot).
     pub fn set_orchestrator(&self, orchestrator: Arc<dyn Orchestrator>) {
-        *self.orchestrator.lock().unwrap() = Some(orchestrator);
+        *self.orchestrator.write().unwrap() = Some(orchestrator);
+        self.forget_module_info(MountPoint::Orchestrator, MountPoint::Orchestrator.as_str());
     }
 
     /// Get the orchestrator module, if mounted.
     pub fn orchestrator(&self) -> Option<Arc<dyn Orchestrator>> {
-        self.orchestrator.lock().unwrap().clone()
+        self.orchestrator.read().unwrap().clone()
     }
 
     // -- Module mount/get: ContextManager --
 
     /// Set the context manager module (single slot).
     pub fn set_context(&self, context: Arc<dyn ContextManager>) {
-        *self.context.lock().unwrap() = Some(context);
+        *self.context.write().unwrap() = Some(context);
+        self.forget_module_info(MountPoint::Context, MountPoint::Context.as_str());
     }
 
     /// Get the context manager module, if mounted.
     pub fn context(&self) -> Option<Arc<dyn ContextManager>> {
-        self.context.lock().unwrap().clone()
+        self.context.read().unwrap().clone()
     }
 
     // -- Module mount/get: Providers --
 
     /// Mount a provider by name.
     pub fn mount_provider(&self, name: &str, provider: Arc<dyn Provider>) {
-        self.providers
-            .lock()
-            .unwrap()
-            .insert(name.to_string(), provider);
+        self.providers.rcu(|providers| {
+            let mut providers = HashMap::clone(providers);
+            providers.insert(name.to_string(), Arc::clone(&provider));
+            providers
+        });
+        self.forget_module_info(MountPoint::Providers, name);
+        self.model_catalog.invalidate(name);
     }
 
     /// Get a single provider by name.
     pub fn get_provider(&self, name: &str) -> Option<Arc<dyn Provider>> {
-        self.providers.lock().unwrap().get(name).cloned()
+        self.providers.load().get(name).cloned()
     }
 
     /// Get all mounted providers as a snapshot.
-    pub fn providers(&self) -> HashMap<String, Arc<dyn Provider>> {
-        self.providers.lock().unwrap().clone()
+    ///
+    /// The snapshot is shared, not copied: later mounts publish a new map
+    /// and leave it unchanged.
+    pub fn providers(&self) -> Arc<HashMap<String, Arc<dyn Provider>>> {
+        self.providers.load_full()
     }
 
     /// Unmount a provider by name. Returns `true` if it was present.
     pub fn unmount_provider(&self, name: &str) -> bool {
-        self.providers.lock().unwrap().remove(name).is_some()
+        self.forget_module_info(MountPoint::Providers, name);
+        self.model_catalog.invalidate(name);
+        let previous = self.providers.rcu(|providers| {
+            let mut providers = HashMap::clone(providers);
+            providers.remove(name);
+            providers
+        });
+        previous.contains_key(name)
+    }
+
+    /// Initialize and mount a provider.
+    ///
+    /// If the provider implements [`ModuleLifecycle`], its `init` runs with
+    /// `config` first; on failure the provider is not mounted. A different
+    /// provider previously mounted under `name` is shut down.
+    pub async fn mount_provider_managed(
+        &self,
+        name: &str,
+        provider: Arc<dyn Provider>,
+        config: Value,
+    ) -> Result<(), CoordinatorError> {
+        init_module(MountPoint::Providers, name, provider.lifecycle(), config).await?;
+        let previous = self.get_provider(name);
+        self.mount_provider(name, Arc::clone(&provider));
+        if let Some(previous) = previous.filter(|p| !Arc::ptr_eq(p, &provider)) {
+            let previous = LifecycleModule::Provider(previous);
+            shutdown_module(MountPoint::Providers, name, &previous).await;
+        }
+        Ok(())
+    }
+
+    /// Unmount a provider and shut it down. Returns `true` if it was present.
+    ///
+    /// Shutdown errors are logged, not returned.
+    pub async fn unmount_provider_managed(&self, name: &str) -> bool {
+        let Some(provider) = self.get_provider(name) else {
+            return false;
+        };
+        self.unmount_provider(name);
+        let provider = LifecycleModule::Provider(provider);
+        shutdown_module(MountPoint::Providers, name, &provider).await;
+        true
+    }
+
+    /// The models of the provider mounted under `name`, from the model
+    /// catalog while its entry is fresh (see [`crate::model_catalog`]).
+    ///
+    /// # Errors
+    ///
+    /// The provider's `list_models` error, or `ProviderError::Other` if no
+    /// provider is mounted under `name`.
+    pub async fn models(&self, name: &str) -> Result<Arc<[ModelInfo]>, ProviderError> {
+        if let Some(models) = self.model_catalog.get(name, self.clock().now()) {
+            return Ok(models);
+        }
+        self.refresh_models(name).await
+    }
+
+    /// Fetch the models of the provider mounted under `name` now, replacing
+    /// its catalog entry. A failed fetch leaves the entry as it was.
+    ///
+    /// # Errors
+    ///
+    /// As for [`models()`](Self::models).
+    pub async fn refresh_models(&self, name: &str) -> Result<Arc<[ModelInfo]>, ProviderError> {
+        let provider = self
+            .get_provider(name)
+            .ok_or_else(|| provider_not_mounted(name))?;
+        let models = provider.list_models().await?;
+        Ok(self.model_catalog.insert(name, models, self.clock().now()))
+    }
+
+    /// [`Provider::ping`] the provider mounted under `name`, returning how
+    /// long the probe took on the session clock.
+    ///
+    /// # Errors
+    ///
+    /// The probe's error, or `ProviderError::Other` if no provider is
+    /// mounted under `name`.
+    pub async fn ping_provider(&self, name: &str) -> Result<Duration, ProviderError> {
+        let provider = self
+            .get_provider(name)
+            .ok_or_else(|| provider_not_mounted(name))?;
+        let started = self.clock().now();
+        provider.ping().await?;
+        Ok(self.clock().now().saturating_duration_since(started))
     }
 
     // -- Module mount/get: Tools --
 
     /// Mount a tool by name.
     pub fn mount_tool(&self, name: &str, tool: Arc<dyn Tool>) {
-        self.tools.lock().unwrap().insert(name.to_string(), tool);
+        self.tools.rcu(|tools| {
+            let mut tools = HashMap::clone(tools);
+            tools.insert(name.to_string(), Arc::clone(&tool));
+            tools
+        });
+        self.forget_module_info(MountPoint::Tools, name);
     }
 
     /// Get a single tool by name.
     pub fn get_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools.lock().unwrap().get(name).cloned()
+        self.tools.load().get(name).cloned()
     }
 
     /// Get all mounted tools as a snapshot.
-    pub fn tools(&self) -> HashMap<String, Arc<dyn Tool>> {
-        self.tools.lock().unwrap().clone()
+    ///
+    /// The snapshot is shared, not copied: later mounts publish a new map
+    /// and leave it unchanged.
+    pub fn tools(&self) -> Arc<HashMap<String, Arc<dyn Tool>>> {
+        self.tools.load_full()
     }
 
     /// Unmount a tool by name. Returns `true` if it was present.
     pub fn unmount_tool(&self, name: &str) -> bool {
-        self.tools.lock().unwrap().remove(name).is_some()
+        self.forget_module_info(MountPoint::Tools, name);
+        let previous = self.tools.rcu(|tools| {
+            let mut tools = HashMap::clone(tools);
+            tools.remove(name);
+            tools
+        });
+        previous.contains_key(name)
+    }
+
+    /// Initialize and mount a tool. See [`mount_provider_managed`](Self::mount_provider_managed).
+    pub async fn mount_tool_managed(
+        &self,
+        name: &str,
+        tool: Arc<dyn Tool>,
+        config: Value,
+    ) -> Result<(), CoordinatorError> {
+        init_module(MountPoint::Tools, name, tool.lifecycle(), config).await?;
+        let previous = self.get_tool(name);
+        self.mount_tool(name, Arc::clone(&tool));
+        if let Some(previous) = previous.filter(|t| !Arc::ptr_eq(t, &tool)) {
+            let previous = LifecycleModule::Tool(previous);
+            shutdown_module(MountPoint::Tools, name, &previous).await;
+        }
+        Ok(())
+    }
+
+    /// Unmount a tool and shut it down. See
+    /// [`unmount_provider_managed`](Self::unmount_provider_managed).
+    pub async fn unmount_tool_managed(&self, name: &str) -> bool {
+        let Some(tool) = self.get_tool(name) else {
+            return false;
+        };
+        self.unmount_tool(name);
+        let tool = LifecycleModule::Tool(tool);
+        shutdown_module(MountPoint::Tools, name, &tool).await;
+        true
+    }
+
+    // -- Module metadata --
+
+    /// Record the [`ModuleInfo`] of a mounted module, for [`describe()`](Self::describe).
+    ///
+    /// `name` is the mount name (a tool or provider name; the mount point's own
+    /// name for single-slot mount points). Mounting or unmounting a module
+    /// under the same name discards the recorded info, so call this after
+    /// mounting.
+    pub fn set_module_info(&self, mount_point: MountPoint, name: &str, info: ModuleInfo) {
+        self.module_info
+            .write()
+            .unwrap()
+            .insert((mount_point, name.to_string()), info);
+    }
+
+    /// The recorded [`ModuleInfo`] for a mounted module, if any.
+    pub fn module_info(&self, mount_point: MountPoint, name: &str) -> Option<ModuleInfo> {
+        self.module_info
+            .read()
+            .unwrap()
+            .get(&(mount_point, name.to_string()))
+            .cloned()
+    }
+
+    fn forget_module_info(&self, mount_point: MountPoint, name: &str) {
+        self.module_info
+            .write()
+            .unwrap()
+            .remove(&(mount_point, name.to_string()));
+    }
+
+    /// JSON Schema for a mount plan of the mounted modules (see
+    /// [`crate::config_schema`]).
+    ///
+    /// Providers are described by the `config_fields` of their
+    /// [`ProviderInfo`](crate::models::ProviderInfo); other modules by the
+    /// `config_schema` of their recorded [`ModuleInfo`].
+    pub fn describe_config_schema(&self) -> Value {
+        let descriptor = |info: ModuleInfo, mount_point: MountPoint| ModuleDescriptor {
+            info,
+            target: MountTarget::Point(mount_point),
+            depends_on: Vec::new(),
+            optional_depends_on: Vec::new(),
+            provides: Vec::new(),
+            requires: Vec::new(),
+            events: Vec::new(),
+        };
+        let mut catalog = ModuleCatalog::new();
+        for ((mount_point, _), info) in self.module_info.read().unwrap().iter() {
+            catalog.insert(descriptor(info.clone(), *mount_point), Vec::new());
+        }
+        for (name, provider) in self.providers.load().iter() {
+            let provider_info = provider.get_info();
+            let info = self
+                .module_info(MountPoint::Providers, name)
+                .unwrap_or_else(|| ModuleInfo {
+                    id: provider_info.id.clone(),
+                    name: provider_info.display_name.clone(),
+                    version: String::new(),
+                    module_type: ModuleType::Provider,
+                    mount_point: MountPoint::Providers.as_str().to_string(),
+                    description: String::new(),
+                    config_schema: None,
+                });
+            catalog.insert(
+                descriptor(info, MountPoint::Providers),
+                provider_info.config_fields,
+            );
+        }
+        config_schema::describe_config_schema(&catalog)
     }
 
     // -- Read-only accessor methods (for to_dict / introspection) --
 
     /// Names of all mounted tools.
     pub fn tool_names(&self) -> Vec<String> {
-        self.tools.lock().unwrap().keys().cloned().collect()
+        self.tools.load().keys().cloned().collect()
     }
 
     /// Names of all mounted providers.
     pub fn provider_names(&self) -> Vec<String> {
-        self.providers.lock().unwrap().keys().cloned().collect()
+        self.providers.load().keys().cloned().collect()
     }
 
     /// Whether an orchestrator is mounted.
     pub fn has_orchestrator(&self) -> bool {
-        self.orchestrator.lock().unwrap().is_some()
+        self.orchestrator.read().unwrap().is_some()
     }
 
     /// Whether a context manager is mounted.
     pub fn has_context(&self) -> bool {
-        self.context.lock().unwrap().is_some()
+        self.context.read().unwrap().is_some()
     }
 
     // -- App-layer service: ApprovalProvider --
 
     /// Set the approval provider (single slot).
     pub fn set_approval_provider(&self, provider: Arc<dyn ApprovalProvider>) {
-        *self.approval_provider.lock().unwrap() = Some(provider);
+        *self.approval_provider.write().unwrap() = Some(provider);
     }
 
     /// Clear the approval provider.
     pub fn clear_approval_provider(&self) {
-        *self.approval_provider.lock().unwrap() = None;
+        *self.approval_provider.write().unwrap() = None;
     }
 
     /// Get the approval provider, if mounted.
     pub fn approval_provider(&self) -> Option<Arc<dyn ApprovalProvider>> {
-        self.approval_provider.lock().unwrap().clone()
+        self.approval_provider.read().unwrap().clone()
     }
 
     /// Whether an approval provider is mounted.
     pub fn has_approval_provider(&self) -> bool {
-        self.approval_provider.lock().unwrap().is_some()
+        self.approval_provider.read().unwrap().is_some()
+    }
+
+    /// Approval decisions remembered for the session (see
+    /// [`crate::approval_memory`]).
+    pub fn approval_memory(&self) -> Arc<ApprovalMemory> {
+        Arc::clone(&self.approval_memory)
+    }
+
+    // -- App-layer service: Moderator --
+
+    /// Set the moderator (single slot).
+    pub fn set_moderator(&self, moderator: Arc<dyn Moderator>) {
+        *self.moderator.write().unwrap() = Some(moderator);
+    }
+
+    /// Clear the moderator.
+    pub fn clear_moderator(&self) {
+        *self.moderator.write().unwrap() = None;
+    }
+
+    /// Get the moderator, if mounted.
+    pub fn moderator(&self) -> Option<Arc<dyn Moderator>> {
+        self.moderator.read().unwrap().clone()
+    }
+
+    /// The moderator slot itself, which sees later mounts.
+    pub(crate) fn moderator_slot(&self) -> ModeratorSlot {
+        Arc::clone(&self.moderator)
     }
 
     // -- App-layer service: DisplayService --
 
     /// Set the display service (single slot).
     pub fn set_display_service(&self, service: Arc<dyn DisplayService>) {
-        *self.display_service.lock().unwrap() = Some(service);
+        *self.display_service.write().unwrap() = Some(service);
     }
 
     /// Get the display service, if mounted.
     pub fn display_service(&self) -> Option<Arc<dyn DisplayService>> {
-        self.display_service.lock().unwrap().clone()
+        self.display_service.read().unwrap().clone()
     }
 
     /// Whether a display service is mounted.
     pub fn has_display_service(&self) -> bool {
-        self.display_service.lock().unwrap().is_some()
+        self.display_service.read().unwrap().is_some()
+    }
+
+    // -- App-layer service: DisplayProvider --
+
+    /// Set the display provider (single slot; see [`crate::display`]).
+    pub fn set_display_provider(&self, provider: Arc<dyn DisplayProvider>) {
+        *self.display_provider.write().unwrap() = Some(provider);
+    }
+
+    /// The display provider, or the display service wrapped as one if only
+    /// that is mounted.
+    pub fn display_provider(&self) -> Option<Arc<dyn DisplayProvider>> {
+        if let Some(provider) = self.display_provider.read().unwrap().clone() {
+            return Some(provider);
+        }
+        self.display_service()
+            .map(|service| Arc::new(ServiceDisplay(service)) as Arc<dyn DisplayProvider>)
+    }
+
+    /// Whether a display provider itself is mounted.
+    pub fn has_display_provider(&self) -> bool {
+        self.display_provider.read().unwrap().is_some()
     }
 
     /// Names of all registered capabilities.
     pub fn capability_names(&self) -> Vec<String> {
-        self.capabilities.lock().unwrap().keys().cloned().collect()
+        self.capabilities.read().unwrap().keys().cloned().collect()
     }
 
     /// Return a JSON-compatible dict of all coordinator state for serialization/introspection.
     ///
     /// Returns a `HashMap` with keys: `tools`, `providers`, `has_orchestrator`,
     /// `has_context`, `capabilities`, `has_approval_provider`,
-    /// `has_display_service` — matching the universal Coordinator API.
+    /// `has_display_service` — matching the universal Coordinator API — plus
+    /// the session's `turn_recovery` policy.
     pub fn to_dict(&self) -> HashMap<String, serde_json::Value> {
         let mut dict = HashMap::new();
         dict.insert("tools".to_string(), serde_json::json!(self.tool_names()));
@@ -294,9 +1080,123 @@ impl Coordinator {
             "has_display_service".to_string(),
             serde_json::json!(self.has_display_service()),
         );
+        dict.insert(
+            "memory".to_string(),
+            serde_json::to_value(self.memory.usage()).unwrap_or_default(),
+        );
+        dict.insert(
+            "turn_recovery".to_string(),
+            serde_json::to_value(&self.turn_recovery).unwrap_or_default(),
+        );
+        if let Some(deadline) = self.turn_deadline() {
+            dict.insert(
+                "turn_deadline_remaining_ms".to_string(),
+                serde_json::json!(deadline.remaining().as_millis() as u64),
+            );
+        }
         dict
     }
 
+    /// A diagnostics report of everything mounted and registered on this
+    /// coordinator, for `/debug` endpoints and `inspect` commands in hosts.
+    ///
+    /// Every list is sorted so reports are stable across calls.
+    pub fn describe(&self) -> CoordinatorReport {
+        let mut modules = Vec::new();
+        let mut push = |mount_point: MountPoint,
+                        module_type: ModuleType,
+                        name: &str,
+                        description: Option<String>| {
+            modules.push(MountedModule {
+                mount_point: mount_point.as_str().to_string(),
+                name: name.to_string(),
+                module_type,
+                description,
+                info: self.module_info(mount_point, name),
+            });
+        };
+
+        if self.has_orchestrator() {
+            let name = MountPoint::Orchestrator.as_str();
+            push(
+                MountPoint::Orchestrator,
+                ModuleType::Orchestrator,
+                name,
+                None,
+            );
+        }
+        if self.has_context() {
+            let name = MountPoint::Context.as_str();
+            push(MountPoint::Context, ModuleType::Context, name, None);
+        }
+        let providers = self.providers();
+        let mut providers: Vec<_> = providers.iter().collect();
+        providers.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, provider) in providers {
+            let display_name = provider.get_info().display_name;
+            push(
+                MountPoint::Providers,
+                ModuleType::Provider,
+                name,
+                Some(display_name),
+            );
+        }
+        let tools = self.tools();
+        let mut tools: Vec<_> = tools.iter().collect();
+        tools.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, tool) in tools {
+            let description = tool.description().to_string();
+            push(MountPoint::Tools, ModuleType::Tool, name, Some(description));
+        }
+
+        let mut capabilities = self.capability_names();
+        capabilities.sort();
+        let mut capability_objects = self.capability_object_names();
+        capability_objects.sort();
+
+        let channels = self
+            .channels
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(channel, entries)| {
+                let names = entries.iter().map(|e| e.name.clone()).collect();
+                (channel.clone(), names)
+            })
+            .collect();
+        let hooks = self
+            .hooks
+            .list_handlers(None)
+            .into_iter()
+            .filter(|(_, names)| !names.is_empty())
+            .map(|(event, names)| (event, names.len()))
+            .collect();
+
+        CoordinatorReport {
+            modules,
+            capabilities,
+            capability_objects,
+            channels,
+            hooks,
+            cleanup_functions: self.cleanup_functions.lock().unwrap().len(),
+            has_approval_provider: self.has_approval_provider(),
+            has_display_service: self.has_display_service(),
+        }
+    }
+
+    /// The module topology: modules, the capabilities they require, the
+    /// events their hooks subscribe to and the channels they contribute to
+    /// (see [`crate::topology`]).
+    pub fn module_graph(&self) -> ModuleGraph {
+        ModuleGraph::from_coordinator(self)
+    }
+
+    /// [`module_graph()`](Self::module_graph) as DOT, Mermaid or JSON, for
+    /// debugging mount plans and documenting deployments.
+    pub fn export_graph(&self, format: GraphFormat) -> String {
+        self.module_graph().render(format)
+    }
+
     // -- Subsystem accessors --
 
     /// Reference to the hook registry.
@@ -347,14 +1247,118 @@ impl Coordinator {
     /// Register a capability (inter-module communication).
     pub fn register_capability(&self, name: &str, value: Value) {
         self.capabilities
-            .lock()
+            .write()
             .unwrap()
             .insert(name.to_string(), value);
     }
 
     /// Get a registered capability.
     pub fn get_capability(&self, name: &str) -> Option<Value> {
-        self.capabilities.lock().unwrap().get(name).cloned()
+        self.capabilities.read().unwrap().get(name).cloned()
+    }
+
+    /// A view of the capabilities (JSON and objects) that can be handed out
+    /// without the coordinator.
+    pub fn capability_lookup(&self) -> CapabilityLookup {
+        CapabilityLookup {
+            values: Arc::clone(&self.capabilities),
+            objects: Arc::clone(&self.capability_objects),
+        }
+    }
+
+    /// Register a typed capability object under `name`.
+    ///
+    /// `T` may be a trait object, so a module can expose callable behavior:
+    /// register an `Arc<dyn VectorStore>` and other modules call its methods
+    /// after [`get_capability_object::<dyn VectorStore>()`](Self::get_capability_object).
+    /// Independent of the JSON capabilities registered with
+    /// [`register_capability()`](Self::register_capability); a name may be
+    /// used in both. Replaces any object previously registered under `name`.
+    pub fn register_capability_object<T>(&self, name: &str, value: Arc<T>)
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.capability_objects
+            .write()
+            .unwrap()
+            .insert(name.to_string(), Arc::new(value));
+    }
+
+    /// Get the capability object registered under `name` as an `Arc<T>`.
+    ///
+    /// Returns `None` if nothing is registered under `name` or if it was
+    /// registered with a different type (`T` must match the registered type
+    /// exactly, e.g. `dyn VectorStore` rather than the concrete store).
+    pub fn get_capability_object<T>(&self, name: &str) -> Option<Arc<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.capability_lookup().get_object(name)
+    }
+
+    /// Names of all registered capability objects.
+    pub fn capability_object_names(&self) -> Vec<String> {
+        self.capability_objects
+            .read()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Declare that `module` needs `capabilities` registered (as JSON or
+    /// as an object) before the session executes. Replaces the module's
+    /// previous declaration; an empty list removes it.
+    ///
+    /// [`check_capabilities()`](Self::check_capabilities) verifies the
+    /// declarations; [`crate::manifest`] orders mounts so that providers of
+    /// a capability come before the modules requiring it.
+    pub fn require_capabilities(&self, module: &str, capabilities: &[&str]) {
+        let mut requirements = self.capability_requirements.write().unwrap();
+        if capabilities.is_empty() {
+            requirements.remove(module);
+        } else {
+            requirements.insert(
+                module.to_string(),
+                capabilities.iter().map(|c| c.to_string()).collect(),
+            );
+        }
+    }
+
+    /// Declared capability requirements, by module name.
+    pub fn capability_requirements(&self) -> BTreeMap<String, Vec<String>> {
+        self.capability_requirements.read().unwrap().clone()
+    }
+
+    /// Fail if any [declared requirement](Self::require_capabilities) is
+    /// not registered.
+    ///
+    /// # Errors
+    ///
+    /// [`CoordinatorError::MissingCapabilities`] listing every module with
+    /// unregistered capabilities.
+    pub fn check_capabilities(&self) -> Result<(), CoordinatorError> {
+        let capabilities = self.capabilities.read().unwrap();
+        let objects = self.capability_objects.read().unwrap();
+        let missing: BTreeMap<String, Vec<String>> = self
+            .capability_requirements
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|(module, required)| {
+                let absent: Vec<String> = required
+                    .iter()
+                    .filter(|c| !capabilities.contains_key(*c) && !objects.contains_key(*c))
+                    .cloned()
+                    .collect();
+                (!absent.is_empty()).then(|| (module.clone(), absent))
+            })
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(CoordinatorError::MissingCapabilities { missing })
+        }
     }
 
     // -- Contribution channels --
@@ -384,6 +1388,85 @@ impl Coordinator {
     /// Calls each registered contributor and returns non-error results.
     /// Errors in individual contributors are logged and skipped.
     pub async fn collect_contributions(&self, channel: &str) -> Vec<Value> {
+        let mut results = Vec::new();
+        for (name, result) in self.call_contributors(channel).await {
+            match result {
+                Ok(value) => results.push(value),
+                Err(e) => log::warn!("Contributor '{name}' failed: {e}"),
+            }
+        }
+        results
+    }
+
+    /// Declare `name` as a channel whose contributions deserialize into `T`.
+    ///
+    /// Declaring the same channel again with the same `T` returns another
+    /// handle; contributors register with
+    /// [`register_contributor`](Self::register_contributor) as usual.
+    ///
+    /// # Errors
+    ///
+    /// [`CoordinatorError::ChannelTypeConflict`] if the channel is already
+    /// declared with a different type.
+    pub fn declare_channel<T: DeserializeOwned + 'static>(
+        &self,
+        name: &str,
+    ) -> Result<Channel<T>, CoordinatorError> {
+        let requested = std::any::type_name::<T>();
+        let mut types = self.channel_types.lock().unwrap();
+        let declared = *types.entry(name.to_string()).or_insert(requested);
+        if declared != requested {
+            return Err(CoordinatorError::ChannelTypeConflict {
+                channel: name.to_string(),
+                declared: declared.to_string(),
+                requested: requested.to_string(),
+            });
+        }
+        Ok(Channel {
+            name: name.to_string(),
+            _type: PhantomData,
+        })
+    }
+
+    /// Collect and deserialize contributions from a typed channel.
+    ///
+    /// Failed callbacks and contributions that do not deserialize into `T`
+    /// are reported per contributor in [`Contributions::invalid`] (and
+    /// logged) rather than dropped silently.
+    pub async fn collect_channel<T: DeserializeOwned>(
+        &self,
+        channel: &Channel<T>,
+    ) -> Contributions<T> {
+        let mut contributions = Contributions::default();
+        for (name, result) in self.call_contributors(&channel.name).await {
+            let value = result.map_err(|e| e.to_string()).and_then(|value| {
+                serde_json::from_value(value).map_err(|e| format!("invalid contribution: {e}"))
+            });
+            match value {
+                Ok(value) => contributions.values.push(value),
+                Err(message) => {
+                    log::warn!(
+                        "Contributor '{name}' to channel '{}' rejected: {message}",
+                        channel.name
+                    );
+                    contributions.invalid.push(InvalidContribution {
+                        contributor: name,
+                        message,
+                    });
+                }
+            }
+        }
+        contributions
+    }
+
+    /// Call each contributor to `channel` in registration order.
+    async fn call_contributors(
+        &self,
+        channel: &str,
+    ) -> Vec<(
+        String,
+        Result<Value, Box<dyn std::error::Error + Send + Sync>>,
+    )> {
         // Snapshot callbacks to avoid holding lock during async calls
         let entries: Vec<(String, _)> = {
             let channels = self.channels.lock().unwrap();
@@ -401,15 +1484,193 @@ impl Coordinator {
 
         let mut results = Vec::new();
         for (name, fut) in entries {
-            match fut.await {
-                Ok(value) => results.push(value),
-                Err(e) => {
-                    log::warn!("Contributor '{name}' failed: {e}");
-                    continue;
+            results.push((name, fut.await));
+        }
+        results
+    }
+
+    // -- Mount plans --
+
+    /// Create each module of `plan` with `factory` and mount it, in plan
+    /// (dependency) order.
+    ///
+    /// Every module's [`ModuleLifecycle::init`] runs with its step's config
+    /// before it is mounted. Tools and providers are mounted under their own
+    /// `name()`, hook handlers are registered for the manifest's `events`,
+    /// and each module's `requires` are recorded as for [`MountPlan::apply`].
+    ///
+    /// If any step fails, the modules mounted so far are unmounted in reverse
+    /// order (shutting down those with a lifecycle) and the slots they
+    /// replaced are restored, so the coordinator is left as it was.
+    ///
+    /// # Errors
+    ///
+    /// [`ManifestError::Load`] when the factory fails,
+    /// [`ManifestError::Init`] when a module's `init` fails, and
+    /// [`ManifestError::TypeMismatch`] or [`ManifestError::NotMountable`]
+    /// as for [`MountPlan::apply`].
+    pub async fn apply_mount_plan(
+        &self,
+        plan: &MountPlan,
+        factory: &dyn ModuleFactory,
+    ) -> Result<(), ManifestError> {
+        let mut undo = Vec::new();
+        for step in &plan.steps {
+            if let Err(e) = self.mount_step(step, factory, &mut undo).await {
+                log::warn!(
+                    "Mount plan failed at '{}'; rolling back {} step(s): {e}",
+                    step.module.id(),
+                    undo.len()
+                );
+                self.roll_back(undo).await;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mount one step of a plan, recording how to undo it.
+    async fn mount_step(
+        &self,
+        step: &MountStep,
+        factory: &dyn ModuleFactory,
+        undo: &mut Vec<MountUndo>,
+    ) -> Result<(), ManifestError> {
+        let module = &step.module;
+        let id = module.id().to_string();
+        let loaded = factory
+            .create(step)
+            .await
+            .map_err(|e| ManifestError::Load {
+                module: id.clone(),
+                reason: e.to_string(),
+            })?;
+        let init_failed = |e: CoordinatorError| ManifestError::Init {
+            module: id.clone(),
+            reason: match e {
+                CoordinatorError::ModuleInitFailed { message, .. } => message,
+                other => other.to_string(),
+            },
+        };
+        let not_mountable = |reason: String| ManifestError::NotMountable {
+            module: id.clone(),
+            reason,
+        };
+
+        match (&module.info.module_type, loaded) {
+            (ModuleType::Orchestrator, LoadedModule::Orchestrator(o)) => {
+                let point = MountPoint::Orchestrator;
+                init_module(point, &id, o.lifecycle(), step.config.clone())
+                    .await
+                    .map_err(init_failed)?;
+                undo.push(MountUndo::Orchestrator(self.orchestrator(), o.clone()));
+                self.set_orchestrator(o);
+            }
+            (ModuleType::Context, LoadedModule::Context(c)) => {
+                let point = MountPoint::Context;
+                init_module(point, &id, c.lifecycle(), step.config.clone())
+                    .await
+                    .map_err(init_failed)?;
+                undo.push(MountUndo::Context(self.context(), c.clone()));
+                self.set_context(c);
+            }
+            (ModuleType::Approval, LoadedModule::Approval(a)) => {
+                undo.push(MountUndo::Approval(self.approval_provider()));
+                self.set_approval_provider(a);
+            }
+            (ModuleType::Provider, LoadedModule::Provider(p)) => {
+                let name = p.name().to_string();
+                if self.get_provider(&name).is_some() {
+                    return Err(not_mountable(format!(
+                        "provider '{name}' is already mounted"
+                    )));
+                }
+                self.mount_provider_managed(&name, p, step.config.clone())
+                    .await
+                    .map_err(init_failed)?;
+                undo.push(MountUndo::Provider(name));
+            }
+            (ModuleType::Tool, LoadedModule::Tool(t)) => {
+                let name = t.name().to_string();
+                if self.get_tool(&name).is_some() {
+                    return Err(not_mountable(format!("tool '{name}' is already mounted")));
+                }
+                self.mount_tool_managed(&name, t, step.config.clone())
+                    .await
+                    .map_err(init_failed)?;
+                undo.push(MountUndo::Tool(name));
+            }
+            (ModuleType::Hook, LoadedModule::Hook(handler)) => {
+                let unregister = module
+                    .events
+                    .iter()
+                    .map(|event| {
+                        self.hooks
+                            .register(event, handler.clone(), 0, Some(id.clone()))
+                    })
+                    .collect();
+                undo.push(MountUndo::Hook(unregister));
+            }
+            (_, loaded @ LoadedModule::PythonDelegated { .. })
+            | (_, loaded @ LoadedModule::RustDelegated { .. }) => {
+                return Err(not_mountable(format!(
+                    "{} modules must be loaded by the host",
+                    loaded.variant_name()
+                )));
+            }
+            (ModuleType::Resolver, _) => {
+                return Err(not_mountable(
+                    "the coordinator has no Rust slot for module-source resolvers".into(),
+                ));
+            }
+            (expected, loaded) => {
+                return Err(ManifestError::TypeMismatch {
+                    module: id,
+                    expected: expected.clone(),
+                    loaded: loaded.variant_name(),
+                })
+            }
+        }
+        let previous = self.capability_requirements().remove(&id);
+        let requires: Vec<&str> = module.requires.iter().map(String::as_str).collect();
+        self.require_capabilities(&id, &requires);
+        undo.push(MountUndo::Requirements(id, previous));
+        Ok(())
+    }
+
+    /// Undo mounted steps, most recent first.
+    async fn roll_back(&self, undo: Vec<MountUndo>) {
+        for step in undo.into_iter().rev() {
+            match step {
+                MountUndo::Orchestrator(previous, mounted) => {
+                    *self.orchestrator.write().unwrap() = previous;
+                    self.forget_module_info(MountPoint::Orchestrator, "orchestrator");
+                    let mounted = LifecycleModule::Orchestrator(mounted);
+                    shutdown_module(MountPoint::Orchestrator, "orchestrator", &mounted).await;
+                }
+                MountUndo::Context(previous, mounted) => {
+                    *self.context.write().unwrap() = previous;
+                    self.forget_module_info(MountPoint::Context, "context");
+                    let mounted = LifecycleModule::Context(mounted);
+                    shutdown_module(MountPoint::Context, "context", &mounted).await;
+                }
+                MountUndo::Approval(previous) => {
+                    *self.approval_provider.write().unwrap() = previous;
+                }
+                MountUndo::Provider(name) => {
+                    self.unmount_provider_managed(&name).await;
+                }
+                MountUndo::Tool(name) => {
+                    self.unmount_tool_managed(&name).await;
+                }
+                MountUndo::Hook(unregister) => unregister.iter().for_each(|f| f()),
+                MountUndo::Requirements(module, previous) => {
+                    let previous = previous.unwrap_or_default();
+                    let previous: Vec<&str> = previous.iter().map(String::as_str).collect();
+                    self.require_capabilities(&module, &previous);
                 }
             }
         }
-        results
     }
 
     // -- Cleanup --
@@ -419,10 +1680,12 @@ impl Coordinator {
         self.cleanup_functions.lock().unwrap().push(cleanup_fn);
     }
 
-    /// Run all cleanup functions in reverse registration order.
+    /// Run all cleanup functions in reverse registration order, then shut
+    /// down mounted modules that implement [`ModuleLifecycle`] (tools and
+    /// providers first, the orchestrator last).
     ///
-    /// Errors in one cleanup function do not prevent subsequent functions
-    /// from running (matching Python behaviour).
+    /// Errors in one cleanup function or shutdown do not prevent subsequent
+    /// ones from running (matching Python behaviour).
     pub async fn cleanup(&self) {
         // Take functions out to avoid holding lock during async calls
         let functions: Vec<_> = {
@@ -438,25 +1701,417 @@ impl Coordinator {
                 eprintln!("Error during cleanup: {e}");
             }
         }
+
+        for (mount_point, name, module) in self.lifecycle_modules().iter().rev() {
+            shutdown_module(*mount_point, name, module).await;
+        }
+    }
+
+    // -- Module health --
+
+    /// Run the health check of every mounted module that implements
+    /// [`ModuleLifecycle`] and aggregate the results.
+    ///
+    /// Checks run one at a time; modules without a lifecycle are omitted.
+    pub async fn health(&self) -> HealthReport {
+        let mut report = HealthReport::default();
+        for (mount_point, name, module) in self.lifecycle_modules() {
+            let Some(lifecycle) = module.lifecycle() else {
+                continue;
+            };
+            let health = lifecycle.health_check().await;
+            report.status = report.status.max(health.status);
+            report.modules.push(ModuleHealthEntry {
+                mount_point: mount_point.to_string(),
+                name,
+                health,
+            });
+        }
+        report
+    }
+
+    /// Mounted modules with a lifecycle, in canonical mount-point order,
+    /// then by name.
+    fn lifecycle_modules(&self) -> Vec<(MountPoint, String, LifecycleModule)> {
+        let mut modules = Vec::new();
+        if let Some(orchestrator) = self.orchestrator() {
+            modules.push((
+                MountPoint::Orchestrator,
+                MountPoint::Orchestrator.to_string(),
+                LifecycleModule::Orchestrator(orchestrator),
+            ));
+        }
+        if let Some(context) = self.context() {
+            modules.push((
+                MountPoint::Context,
+                MountPoint::Context.to_string(),
+                LifecycleModule::Context(context),
+            ));
+        }
+        let providers: BTreeMap<_, _> = self.providers().as_ref().clone().into_iter().collect();
+        for (name, provider) in providers {
+            modules.push((
+                MountPoint::Providers,
+                name,
+                LifecycleModule::Provider(provider),
+            ));
+        }
+        let tools: BTreeMap<_, _> = self.tools().as_ref().clone().into_iter().collect();
+        for (name, tool) in tools {
+            modules.push((MountPoint::Tools, name, LifecycleModule::Tool(tool)));
+        }
+        modules.retain(|(_, _, module)| module.lifecycle().is_some());
+        modules
     }
 
     // -- Turn management --
 
-    /// Reset per-turn tracking. Call at turn boundaries.
-    pub fn reset_turn(&self) {
-        *self.current_turn_injections.lock().unwrap() = 0;
-        // Note: cancellation is NOT reset here (persists across turns)
+    /// Reset per-turn tracking (injection count, turn deadline and memoized
+    /// tool results) and advance the turn number. Call at turn boundaries.
+    pub fn reset_turn(&self) {
+        self.injection_queue.reset();
+        *self.turn_deadline.lock().unwrap() = None;
+        *self.turn_number.lock().unwrap() += 1;
+        self.tool_results.clear();
+        // Note: cancellation is NOT reset here (persists across turns)
+    }
+
+    /// Return to `turn_number` after a conversation rewind: per-turn
+    /// tracking is reset as in [`reset_turn()`](Self::reset_turn), but the
+    /// turn number is set rather than advanced.
+    pub fn rewind_turn(&self, turn_number: u64) {
+        self.injection_queue.reset();
+        *self.turn_deadline.lock().unwrap() = None;
+        *self.turn_number.lock().unwrap() = turn_number;
+        self.tool_results.clear();
+    }
+
+    /// Estimated tokens injected this turn (see [`crate::injection_queue`]).
+    pub fn current_turn_injections(&self) -> usize {
+        self.injection_queue.used_tokens()
+    }
+
+    /// The queue hook context injections are admitted through, configured
+    /// from `session.injection_queue` and `session.injection_budget_per_turn`.
+    pub fn injection_queue(&self) -> Arc<InjectionQueue> {
+        Arc::clone(&self.injection_queue)
+    }
+
+    /// Number of [`reset_turn()`](Self::reset_turn) calls so far.
+    pub fn turn_number(&self) -> u64 {
+        *self.turn_number.lock().unwrap()
+    }
+
+    /// Correlation ID of the turn in progress, or `None` between turns (see
+    /// [`crate::correlation`]).
+    pub fn turn_id(&self) -> Option<String> {
+        self.turn_id.lock().unwrap().clone()
+    }
+
+    /// Set the turn in progress, adding its ID to the default fields of
+    /// every event (or removing it with `None`).
+    pub fn set_turn_id(&self, turn_id: Option<String>) {
+        let mut fields = match self.hooks.default_fields() {
+            Some(Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        match &turn_id {
+            Some(id) => fields.insert("turn_id".into(), Value::String(id.clone())),
+            None => fields.remove("turn_id"),
+        };
+        self.hooks.set_default_fields(Value::Object(fields));
+        *self.turn_id.lock().unwrap() = turn_id;
+    }
+
+    /// Tool results memoized this turn (see [`crate::tool_executor`]).
+    pub fn tool_results(&self) -> Arc<ToolResultCache> {
+        Arc::clone(&self.tool_results)
+    }
+
+    /// Results of pure tools cached for the session (see
+    /// [`crate::tool_cache`]).
+    pub fn pure_tool_results(&self) -> Arc<PureToolCache> {
+        Arc::clone(&self.pure_tool_results)
+    }
+
+    /// Charge `count` estimated tokens injected outside the hook registry
+    /// to this turn's injection budget.
+    pub fn increment_injections(&self, count: usize) {
+        self.injection_queue.charge(count);
+    }
+
+    /// Set the deadline for the current turn until the returned guard is
+    /// dropped, which restores the previous deadline (including when the
+    /// turn is timed out or aborted).
+    pub fn scope_turn_deadline(&self, deadline: TurnDeadline) -> TurnDeadlineScope<'_> {
+        let previous = self.turn_deadline.lock().unwrap().replace(deadline);
+        TurnDeadlineScope {
+            coordinator: self,
+            previous,
+        }
+    }
+
+    /// The current turn's deadline, if one is set.
+    pub fn turn_deadline(&self) -> Option<TurnDeadline> {
+        self.turn_deadline.lock().unwrap().clone()
+    }
+
+    /// A fresh failure tracker for one turn, applying the session's
+    /// `session.turn_recovery` policy (see [`crate::recovery`]).
+    ///
+    /// `ask_user` goes through the approval provider mounted at call time.
+    pub fn turn_recovery(&self) -> TurnRecovery {
+        let recovery = TurnRecovery::new(self.turn_recovery.clone(), self.hooks_shared());
+        match self.approval_provider() {
+            Some(provider) => {
+                recovery.with_approval(ApprovalGate::from_coordinator(self), provider)
+            }
+            None => recovery,
+        }
+    }
+
+    // -- Resource accounting --
+
+    /// Set (or clear) the store large tool outputs are offloaded to
+    /// (see [`crate::attachments`]).
+    pub fn set_attachment_store(&self, store: Option<Arc<AttachmentStore>>) {
+        *self.attachment_store.write().unwrap() = store;
+    }
+
+    /// The session's attachment store, if one is set.
+    pub fn attachment_store(&self) -> Option<Arc<AttachmentStore>> {
+        self.attachment_store.read().unwrap().clone()
+    }
+
+    /// Replace the post-processing applied to kernel-side tool results.
+    pub fn set_tool_output(&self, processor: Arc<ToolOutputProcessor>) {
+        *self.tool_output.write().unwrap() = processor;
+    }
+
+    /// The post-processing applied to kernel-side tool results
+    /// (see [`crate::tool_output`]).
+    pub fn tool_output(&self) -> Arc<ToolOutputProcessor> {
+        Arc::clone(&self.tool_output.read().unwrap())
+    }
+
+    /// Replace the per-provider content visibility policies.
+    pub fn set_visibility_config(&self, config: Arc<VisibilityConfig>) {
+        *self.visibility.write().unwrap() = config;
+    }
+
+    /// The per-provider content visibility policies applied to provider
+    /// requests (see [`crate::visibility`]).
+    pub fn visibility_config(&self) -> Arc<VisibilityConfig> {
+        Arc::clone(&self.visibility.read().unwrap())
+    }
+
+    /// The session's working directory and filesystem scope, if
+    /// `session.workspace` is set (see [`crate::workspace`]).
+    pub fn workspace(&self) -> Option<Arc<Workspace>> {
+        self.workspace.clone()
+    }
+
+    /// The memory accountant shared by this session's in-memory buffers.
+    pub fn memory(&self) -> Arc<MemoryAccountant> {
+        Arc::clone(&self.memory)
+    }
+
+    /// The session's token counter (a [`HeuristicTokenCounter`] unless replaced).
+    pub fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        Arc::clone(&self.token_counter.read().unwrap())
+    }
+
+    /// Replace the token counter used for context-window estimates.
+    pub fn set_token_counter(&self, counter: Arc<dyn TokenCounter>) {
+        *self.token_counter.write().unwrap() = counter;
+    }
+
+    // -- Credentials --
+
+    /// The resolver providers obtain credentials through (an
+    /// [`EnvCredentialResolver`] unless replaced; see [`crate::credentials`]).
+    pub fn credential_resolver(&self) -> Arc<dyn CredentialResolver> {
+        Arc::clone(&self.credential_resolver.read().unwrap())
+    }
+
+    /// Replace the credential resolver. Affects providers loaded afterwards.
+    pub fn set_credential_resolver(&self, resolver: Arc<dyn CredentialResolver>) {
+        *self.credential_resolver.write().unwrap() = resolver;
+    }
+
+    // -- Time --
+
+    /// The session's clock (the system clock unless replaced).
+    ///
+    /// Shared with the hook registry, so it also stamps event timestamps.
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.hooks.clock()
+    }
+
+    /// Replace the session's clock, e.g. with a
+    /// [`ManualClock`](crate::testing::ManualClock) in tests.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.hooks.set_clock(clock);
+    }
+
+    // -- Streaming --
+
+    /// A [`ResponseAccumulator`] that produces the canonical streaming
+    /// events under the session's `session.stream_events` coalescing, timed
+    /// on the session clock (see [`crate::streaming`]).
+    pub fn response_accumulator(&self) -> ResponseAccumulator {
+        ResponseAccumulator::with_events(self.stream_events.clone(), self.clock())
+    }
+
+    // -- User notifications --
+
+    /// Emit a `user:notification` event for display systems, unless it
+    /// repeats a recent identical notification or `source` is flooding
+    /// (see [`crate::notifications`]).
+    pub async fn notify_user(
+        &self,
+        level: NotificationLevel,
+        source: &str,
+        message: &str,
+    ) -> NotificationOutcome {
+        let outcome = self
+            .notifications
+            .check(level, source, message, self.clock().now());
+        if let NotificationOutcome::Delivered { suppressed } = outcome {
+            self.hooks
+                .emit(
+                    crate::events::USER_NOTIFICATION,
+                    serde_json::json!({
+                        "level": level,
+                        "source": source,
+                        "message": message,
+                        "suppressed": suppressed,
+                    }),
+                )
+                .await;
+        }
+        outcome
+    }
+
+    /// Emit `kernel:memory_pressure` if pressure was signalled since the last
+    /// call. Returns whether the event was emitted.
+    pub async fn emit_memory_pressure(&self) -> bool {
+        if !self.memory.take_pressure() {
+            return false;
+        }
+        let usage = serde_json::to_value(self.memory.usage()).unwrap_or_default();
+        self.hooks
+            .emit(crate::events::KERNEL_MEMORY_PRESSURE, usage)
+            .await;
+        true
+    }
+
+    // -- Host application data --
+
+    /// Attach a value of type `T` for the embedding application (tenant id,
+    /// auth principal, trace context, ...), replacing any previous `T`.
+    ///
+    /// One value is kept per type, so hosts should wrap their data in a
+    /// dedicated struct rather than storing bare `String`s. Host-authored
+    /// tools and hooks holding the coordinator read it back with
+    /// [`host_data()`](Self::host_data). Returns the previous value, if any.
+    pub fn set_host_data<T: Any + Send + Sync>(&self, value: T) -> Option<Arc<T>> {
+        self.host_data
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Arc::new(value))
+            .and_then(|previous| previous.downcast::<T>().ok())
+    }
+
+    /// The host value of type `T`, if one was attached.
+    pub fn host_data<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.host_data
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+
+    /// Detach and return the host value of type `T`.
+    pub fn remove_host_data<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.host_data
+            .write()
+            .unwrap()
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TurnDeadlineScope
+// ---------------------------------------------------------------------------
+
+/// Guard returned by [`Coordinator::scope_turn_deadline()`]; restores the
+/// previous turn deadline on drop.
+pub struct TurnDeadlineScope<'a> {
+    coordinator: &'a Coordinator,
+    previous: Option<TurnDeadline>,
+}
+
+impl Drop for TurnDeadlineScope<'_> {
+    fn drop(&mut self) {
+        *self.coordinator.turn_deadline.lock().unwrap() = self.previous.take();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Cancellation event forwarding
+// ---------------------------------------------------------------------------
+
+/// The error for a provider lookup that found nothing mounted under `name`.
+fn provider_not_mounted(name: &str) -> ProviderError {
+    ProviderError::Other {
+        message: format!("No provider mounted as '{name}'"),
+        provider: Some(name.to_string()),
+        model: None,
+        retry_after: None,
+        status_code: None,
+        retryable: false,
+        delay_multiplier: None,
     }
+}
 
-    /// Current injection count for this turn.
-    pub fn current_turn_injections(&self) -> usize {
-        *self.current_turn_injections.lock().unwrap()
-    }
+/// Build the observer that turns cancellation transitions into
+/// `cancel:requested` / `cancel:escalated` events on `hooks`.
+///
+/// Transitions are queued on a channel and emitted in order by a single
+/// forwarding task, spawned on the first transition made inside a Tokio
+/// runtime. Transitions made outside a runtime stay queued until then.
+fn cancel_event_forwarder(hooks: Arc<HookRegistry>) -> StateChangeCallback {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<(&'static str, Value)>();
+    let rx = Mutex::new(Some(rx));
+    Arc::new(move |from, to| {
+        let event = match (from, to) {
+            (
+                CancellationState::None,
+                CancellationState::Graceful | CancellationState::Immediate,
+            ) => (events::CANCEL_REQUESTED, serde_json::json!({ "state": to })),
+            (CancellationState::Graceful, CancellationState::Immediate) => (
+                events::CANCEL_ESCALATED,
+                serde_json::json!({ "from": from, "to": to }),
+            ),
+            _ => return,
+        };
+        let _ = tx.send(event);
 
-    /// Increment the injection counter.
-    pub fn increment_injections(&self, count: usize) {
-        *self.current_turn_injections.lock().unwrap() += count;
-    }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        if let Some(mut rx) = rx.lock().unwrap().take() {
+            let hooks = Arc::clone(&hooks);
+            runtime.spawn(async move {
+                while let Some((event, payload)) = rx.recv().await {
+                    hooks.emit(event, payload).await;
+                }
+            });
+        }
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -468,6 +2123,45 @@ mod tests {
     use super::*;
     use crate::testing::{FakeContextManager, FakeOrchestrator, FakeProvider, FakeTool};
 
+    // ---------------------------------------------------------------
+    // MountPoint parsing
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn mount_point_round_trips_through_str() {
+        for mp in MountPoint::ALL {
+            assert_eq!(mp.as_str().parse::<MountPoint>().unwrap(), *mp);
+            assert_eq!(mp.to_string(), mp.as_str());
+        }
+    }
+
+    #[test]
+    fn mount_point_unknown_name_is_structured_error() {
+        let err = "tool".parse::<MountPoint>().unwrap_err();
+        match &err {
+            CoordinatorError::UnknownMountPoint { name, expected } => {
+                assert_eq!(name, "tool");
+                assert!(expected.contains(&"tools".to_string()));
+            }
+            other => panic!("expected UnknownMountPoint, got {other:?}"),
+        }
+        assert!(err.to_string().starts_with("Unknown mount point: tool"));
+    }
+
+    #[test]
+    fn mount_point_validate_mount() {
+        assert!(MountPoint::Orchestrator.validate_mount(None).is_ok());
+        assert!(MountPoint::Tools.validate_mount(Some("echo")).is_ok());
+        assert!(matches!(
+            MountPoint::Agents.validate_mount(None),
+            Err(CoordinatorError::NameRequired { .. })
+        ));
+        assert!(matches!(
+            MountPoint::Hooks.validate_mount(None),
+            Err(CoordinatorError::NotMountable { .. })
+        ));
+    }
+
     // ---------------------------------------------------------------
     // Tool mount/get
     // ---------------------------------------------------------------
@@ -519,6 +2213,22 @@ mod tests {
         assert!(!coord.unmount_tool("nonexistent"));
     }
 
+    #[test]
+    fn tool_snapshots_are_unaffected_by_later_mounts() {
+        let coord = Coordinator::new_for_test();
+        coord.mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+        let before = coord.tools();
+
+        coord.mount_tool("bash", Arc::new(FakeTool::new("bash", "runs bash")));
+        assert!(coord.unmount_tool("echo"));
+
+        assert_eq!(before.len(), 1);
+        assert!(before.contains_key("echo"));
+        let after = coord.tools();
+        assert_eq!(after.len(), 1);
+        assert!(after.contains_key("bash"));
+    }
+
     #[test]
     fn tools_empty_initially() {
         let coord = Coordinator::new_for_test();
@@ -629,10 +2339,91 @@ mod tests {
         assert_eq!(coord.get_capability("nonexistent"), None);
     }
 
+    #[test]
+    fn capability_objects_expose_trait_methods() {
+        trait VectorStore: Send + Sync {
+            fn search(&self, query: &str) -> Vec<String>;
+        }
+        struct Echo;
+        impl VectorStore for Echo {
+            fn search(&self, query: &str) -> Vec<String> {
+                vec![query.to_string()]
+            }
+        }
+
+        let coord = Coordinator::new_for_test();
+        let store: Arc<dyn VectorStore> = Arc::new(Echo);
+        coord.register_capability_object("vector-store", store);
+        coord.register_capability_object("limits", Arc::new(42_u32));
+
+        let store = coord
+            .get_capability_object::<dyn VectorStore>("vector-store")
+            .unwrap();
+        assert_eq!(store.search("q"), vec!["q"]);
+        assert_eq!(*coord.get_capability_object::<u32>("limits").unwrap(), 42);
+
+        // Wrong type, missing name, and the JSON registry are all separate.
+        assert!(coord.get_capability_object::<u64>("limits").is_none());
+        assert!(coord.get_capability_object::<u32>("missing").is_none());
+        assert!(coord.get_capability("vector-store").is_none());
+        let mut names = coord.capability_object_names();
+        names.sort();
+        assert_eq!(names, vec!["limits", "vector-store"]);
+    }
+
     // ---------------------------------------------------------------
     // Contribution channels
     // ---------------------------------------------------------------
 
+    #[tokio::test]
+    async fn typed_channel_reports_invalid_contributions() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Metric {
+            name: String,
+            value: f64,
+        }
+
+        let coord = Coordinator::new_for_test();
+        let channel = coord.declare_channel::<Metric>("metrics").unwrap();
+        coord.register_contributor(
+            "metrics",
+            "good",
+            Box::new(|| Box::pin(async { Ok(serde_json::json!({"name": "a", "value": 1.0})) })),
+        );
+        coord.register_contributor(
+            "metrics",
+            "malformed",
+            Box::new(|| Box::pin(async { Ok(serde_json::json!({"name": "b"})) })),
+        );
+        coord.register_contributor(
+            "metrics",
+            "failing",
+            Box::new(|| Box::pin(async { Err("down".into()) })),
+        );
+
+        let collected = coord.collect_channel(&channel).await;
+        assert_eq!(
+            collected.values,
+            vec![Metric {
+                name: "a".into(),
+                value: 1.0
+            }]
+        );
+        let invalid: Vec<&str> = collected
+            .invalid
+            .iter()
+            .map(|i| i.contributor.as_str())
+            .collect();
+        assert_eq!(invalid, vec!["malformed", "failing"]);
+        assert!(collected.invalid[0].message.contains("value"));
+        assert_eq!(collected.invalid[1].message, "down");
+
+        // Redeclaring with the same type is fine; another type conflicts.
+        assert!(coord.declare_channel::<Metric>("metrics").is_ok());
+        let err = coord.declare_channel::<String>("metrics").unwrap_err();
+        assert_eq!(err.code(), "coordinator.channel_type_conflict");
+    }
+
     #[tokio::test]
     async fn contribution_channels() {
         let coord = Coordinator::new_for_test();
@@ -716,6 +2507,21 @@ mod tests {
         assert_eq!(coord.current_turn_injections(), 0);
     }
 
+    #[test]
+    fn turn_deadline_scope_restores_previous_deadline() {
+        use std::time::Duration;
+
+        let coord = Coordinator::new_for_test();
+        let outer = coord.scope_turn_deadline(TurnDeadline::after(Duration::from_secs(60)));
+        {
+            let _inner = coord.scope_turn_deadline(TurnDeadline::after(Duration::from_millis(1)));
+            assert!(coord.turn_deadline().unwrap().remaining() <= Duration::from_millis(1));
+        }
+        assert!(coord.turn_deadline().unwrap().remaining() > Duration::from_secs(1));
+        drop(outer);
+        assert!(coord.turn_deadline().is_none());
+    }
+
     // ---------------------------------------------------------------
     // Hooks and cancellation accessible
     // ---------------------------------------------------------------
@@ -739,6 +2545,33 @@ mod tests {
         assert!(coord.cancellation().is_graceful());
     }
 
+    #[tokio::test]
+    async fn cancellation_transitions_emit_events_in_order() {
+        let coord = Coordinator::new_for_test();
+        let recorder = Arc::new(crate::testing::FakeHookHandler::new());
+        for event in [events::CANCEL_REQUESTED, events::CANCEL_ESCALATED] {
+            let _ = coord.hooks().register(event, recorder.clone(), 0, None);
+        }
+
+        coord.cancellation().request_graceful();
+        coord.cancellation().request_immediate();
+        coord.cancellation().reset(); // back to None: no event
+        for _ in 0..100 {
+            if recorder.recorded_events().len() >= 2 {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+
+        let events = recorder.recorded_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, "cancel:requested");
+        assert_eq!(events[0].1["state"], "graceful");
+        assert_eq!(events[1].0, "cancel:escalated");
+        assert_eq!(events[1].1["from"], "graceful");
+        assert_eq!(events[1].1["to"], "immediate");
+    }
+
     #[test]
     fn to_dict_includes_all_mount_points() {
         let coord = Coordinator::new_for_test();
@@ -763,6 +2596,111 @@ mod tests {
         assert!(caps.contains(&serde_json::json!("streaming")));
     }
 
+    #[test]
+    fn describe_lists_mounts_and_registrations() {
+        let coord = Coordinator::new_for_test();
+        coord.set_orchestrator(Arc::new(FakeOrchestrator::new("done")));
+        coord.mount_tool("zeta", Arc::new(FakeTool::new("zeta", "last")));
+        coord.mount_tool("alpha", Arc::new(FakeTool::new("alpha", "first")));
+        coord.mount_provider("mock", Arc::new(FakeProvider::new("mock", "hi")));
+        let info = ModuleInfo {
+            id: "tool-alpha".into(),
+            name: "Alpha".into(),
+            version: "1.2.0".into(),
+            module_type: ModuleType::Tool,
+            mount_point: "tools".into(),
+            description: "first".into(),
+            config_schema: None,
+        };
+        coord.set_module_info(MountPoint::Tools, "alpha", info.clone());
+        coord.register_capability("streaming", serde_json::json!(true));
+        coord.register_contributor(
+            "observability.events",
+            "logger",
+            Box::new(|| Box::pin(async { Ok(serde_json::json!([])) })),
+        );
+        coord.register_cleanup(Box::new(|| Box::pin(async {})));
+        let handler = Arc::new(crate::testing::FakeHookHandler::new());
+        let _ = coord.hooks().register("tool:pre", handler.clone(), 0, None);
+        let _ = coord.hooks().register("tool:pre", handler, 1, None);
+
+        let report = coord.describe();
+        let names: Vec<_> = report
+            .modules
+            .iter()
+            .map(|m| format!("{}/{}", m.mount_point, m.name))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "orchestrator/orchestrator",
+                "providers/mock",
+                "tools/alpha",
+                "tools/zeta"
+            ]
+        );
+        assert_eq!(report.modules[2].info, Some(info));
+        assert_eq!(report.modules[3].description.as_deref(), Some("last"));
+        assert_eq!(report.capabilities, vec!["streaming"]);
+        assert_eq!(report.channels["observability.events"], vec!["logger"]);
+        assert_eq!(report.hooks["tool:pre"], 2);
+        assert_eq!(report.cleanup_functions, 1);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["modules"][0]["type"], "orchestrator");
+    }
+
+    #[test]
+    fn remounting_discards_module_info() {
+        let coord = Coordinator::new_for_test();
+        coord.mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+        let info = ModuleInfo {
+            id: "tool-echo".into(),
+            name: "Echo".into(),
+            version: "0.1.0".into(),
+            module_type: ModuleType::Tool,
+            mount_point: "tools".into(),
+            description: String::new(),
+            config_schema: None,
+        };
+        coord.set_module_info(MountPoint::Tools, "echo", info);
+        coord.mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+        assert!(coord.module_info(MountPoint::Tools, "echo").is_none());
+    }
+
+    #[test]
+    fn config_schema_describes_mounted_modules() {
+        let coord = Coordinator::new_for_test();
+        coord.mount_provider("mock", Arc::new(FakeProvider::new("mock", "hi")));
+        coord.mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+        let info = ModuleInfo {
+            id: "tool-echo".into(),
+            name: "Echo".into(),
+            version: "0.1.0".into(),
+            module_type: ModuleType::Tool,
+            mount_point: "tools".into(),
+            description: String::new(),
+            config_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {"prefix": {"type": "string"}}
+            })),
+        };
+        coord.set_module_info(MountPoint::Tools, "echo", info);
+
+        let schema = coord.describe_config_schema();
+        let modules = &schema["$defs"]["modules"];
+        assert_eq!(modules["mock"]["title"], "mock");
+        assert_eq!(
+            modules["tool-echo"]["properties"]["prefix"]["type"],
+            "string"
+        );
+        assert_eq!(
+            schema["properties"]["tools"]["items"]["allOf"][0]["if"]["properties"]["module"]
+                ["const"],
+            "tool-echo"
+        );
+    }
+
     #[tokio::test]
     async fn collect_contributions_logs_on_contributor_error() {
         let coord = Coordinator::new_for_test();
@@ -879,6 +2817,32 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn display_provider_falls_back_to_display_service() {
+        let coord = Coordinator::new_for_test();
+        assert!(coord.display_provider().is_none());
+
+        let service = Arc::new(crate::testing::FakeDisplayService::new());
+        coord.set_display_service(service.clone());
+        assert!(!coord.has_display_provider());
+        let provider = coord.display_provider().unwrap();
+        provider
+            .show_message("hello", "info", "test")
+            .await
+            .unwrap();
+        assert_eq!(service.recorded_messages().len(), 1);
+
+        coord.set_display_provider(Arc::new(crate::display::NoopDisplay));
+        assert!(coord.has_display_provider());
+        coord
+            .display_provider()
+            .unwrap()
+            .show_message("dropped", "info", "test")
+            .await
+            .unwrap();
+        assert_eq!(service.recorded_messages().len(), 1);
+    }
+
     #[test]
     fn to_dict_includes_has_display_service() {
         let coord = Coordinator::new_for_test();
@@ -890,4 +2854,453 @@ mod tests {
         let dict = coord.to_dict();
         assert_eq!(dict["has_display_service"], serde_json::json!(true));
     }
+
+    #[tokio::test]
+    async fn memory_pressure_is_emitted_once_per_episode() {
+        use crate::memory::{BoundedBuffer, PushOutcome};
+        use crate::testing::FakeHookHandler;
+
+        let mut config = HashMap::new();
+        config.insert(
+            "session".to_string(),
+            serde_json::json!({"memory": {"ceiling_bytes": 100}}),
+        );
+        let coord = Coordinator::new(config);
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = coord.hooks().register(
+            crate::events::KERNEL_MEMORY_PRESSURE,
+            handler.clone(),
+            0,
+            None,
+        );
+
+        let mut buf = BoundedBuffer::new("events", coord.memory());
+        assert!(!coord.emit_memory_pressure().await);
+        buf.push((), 60);
+        assert_eq!(buf.push((), 60), PushOutcome::Stored { evicted: 1 });
+        assert!(coord.emit_memory_pressure().await);
+        assert!(!coord.emit_memory_pressure().await);
+
+        let events = handler.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["evictions"], 1);
+        assert_eq!(events[0].1["by_category"]["events"], 60);
+        assert_eq!(coord.to_dict()["memory"]["used_bytes"], 60);
+    }
+
+    #[tokio::test]
+    async fn notify_user_emits_throttled_notifications() {
+        use crate::testing::{FakeHookHandler, ManualClock};
+
+        let mut config = HashMap::new();
+        config.insert(
+            "session".to_string(),
+            serde_json::json!({"notifications": {"dedup_window_ms": 1000}}),
+        );
+        let coord = Coordinator::new(config);
+        let clock = Arc::new(ManualClock::new(chrono::Utc::now()));
+        coord.set_clock(clock.clone());
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = coord
+            .hooks()
+            .register(crate::events::USER_NOTIFICATION, handler.clone(), 0, None);
+
+        let warn = || coord.notify_user(NotificationLevel::Warning, "provider", "retrying");
+        assert!(warn().await.is_delivered());
+        assert_eq!(warn().await, NotificationOutcome::Duplicate);
+        clock.advance(std::time::Duration::from_millis(1000));
+        assert_eq!(
+            warn().await,
+            NotificationOutcome::Delivered { suppressed: 1 }
+        );
+
+        let events = handler.recorded_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].1["level"], "warning");
+        assert_eq!(events[0].1["source"], "provider");
+        assert_eq!(events[0].1["message"], "retrying");
+        assert_eq!(events[1].1["suppressed"], 1);
+    }
+
+    #[tokio::test]
+    async fn models_are_cached_until_the_ttl_or_a_refresh() {
+        use crate::testing::ManualClock;
+
+        let coord = Coordinator::new_for_test();
+        let clock = Arc::new(ManualClock::new(chrono::Utc::now()));
+        coord.set_clock(clock.clone());
+        let model: ModelInfo = serde_json::from_value(serde_json::json!({
+            "id": "fake-model", "display_name": "Fake", "context_window": 1000, "max_output_tokens": 100
+        }))
+        .unwrap();
+        let provider = Arc::new(FakeProvider::new("fake", "hi").with_models(vec![model]));
+        coord.mount_provider("fake", provider.clone());
+
+        assert_eq!(coord.models("fake").await.unwrap()[0].id, "fake-model");
+        coord.models("fake").await.unwrap();
+        assert_eq!(provider.list_models_calls(), 1);
+
+        coord.refresh_models("fake").await.unwrap();
+        assert_eq!(provider.list_models_calls(), 2);
+        clock.advance(std::time::Duration::from_secs(300));
+        coord.models("fake").await.unwrap();
+        assert_eq!(provider.list_models_calls(), 3);
+
+        // Remounting drops the cached list.
+        coord.mount_provider("fake", provider.clone());
+        coord.models("fake").await.unwrap();
+        assert_eq!(provider.list_models_calls(), 4);
+
+        assert!(coord.ping_provider("fake").await.is_ok());
+        let err = coord.models("missing").await.unwrap_err();
+        assert!(!err.retryable());
+        assert!(coord.ping_provider("missing").await.is_err());
+    }
+
+    // ---------------------------------------------------------------
+    // Host data
+    // ---------------------------------------------------------------
+
+    #[derive(Debug, PartialEq)]
+    struct Tenant(String);
+
+    #[derive(Debug, PartialEq)]
+    struct TraceId(u64);
+
+    #[test]
+    fn host_data_is_keyed_by_type() {
+        let coord = Coordinator::new_for_test();
+        assert!(coord.host_data::<Tenant>().is_none());
+
+        assert!(coord.set_host_data(Tenant("acme".into())).is_none());
+        coord.set_host_data(TraceId(7));
+        assert_eq!(*coord.host_data::<Tenant>().unwrap(), Tenant("acme".into()));
+        assert_eq!(*coord.host_data::<TraceId>().unwrap(), TraceId(7));
+
+        let previous = coord.set_host_data(Tenant("globex".into())).unwrap();
+        assert_eq!(*previous, Tenant("acme".into()));
+        assert_eq!(coord.remove_host_data::<Tenant>().unwrap().0, "globex");
+        assert!(coord.host_data::<Tenant>().is_none());
+        assert!(coord.host_data::<TraceId>().is_some());
+    }
+
+    #[test]
+    fn token_counter_is_replaceable() {
+        use crate::token_counter::HeuristicTokenCounter;
+
+        let coord = Coordinator::new_for_test();
+        assert_eq!(coord.token_counter().count_text("", "abcdefgh"), 2);
+        coord.set_token_counter(Arc::new(HeuristicTokenCounter::new(2.0)));
+        assert_eq!(coord.token_counter().count_text("", "abcdefgh"), 4);
+    }
+
+    #[test]
+    fn credential_resolver_is_replaceable() {
+        use crate::credentials::StaticCredentialResolver;
+
+        let coord = Coordinator::new_for_test();
+        assert!(coord
+            .credential_resolver()
+            .resolve("p", "AMPLIFIER_TEST_UNSET_CREDENTIAL")
+            .is_err());
+        coord.set_credential_resolver(Arc::new(StaticCredentialResolver::new().with(
+            "p",
+            "AMPLIFIER_TEST_UNSET_CREDENTIAL",
+            "k",
+        )));
+        let secret = coord
+            .credential_resolver()
+            .resolve("p", "AMPLIFIER_TEST_UNSET_CREDENTIAL")
+            .unwrap();
+        assert_eq!(secret.expose_secret(), "k");
+    }
+
+    // ---------------------------------------------------------------
+    // Module lifecycle
+    // ---------------------------------------------------------------
+
+    /// Tool that records its lifecycle calls.
+    struct ManagedTool {
+        health: ModuleHealth,
+        fail_init: bool,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl ManagedTool {
+        fn new(health: ModuleHealth) -> Arc<Self> {
+            Arc::new(Self {
+                health,
+                fail_init: false,
+                calls: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn failing() -> Arc<Self> {
+            Arc::new(Self {
+                health: ModuleHealth::healthy(),
+                fail_init: true,
+                calls: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl Tool for ManagedTool {
+        fn name(&self) -> &str {
+            "managed"
+        }
+
+        fn description(&self) -> &str {
+            "has a lifecycle"
+        }
+
+        fn get_spec(&self) -> crate::messages::ToolSpec {
+            crate::messages::ToolSpec {
+                name: "managed".into(),
+                parameters: HashMap::new(),
+                description: None,
+                extensions: HashMap::new(),
+            }
+        }
+
+        fn execute(
+            &self,
+            _input: Value,
+        ) -> crate::traits::BoxFuture<'_, Result<crate::models::ToolResult, crate::errors::ToolError>>
+        {
+            Box::pin(async { Ok(crate::models::ToolResult::default()) })
+        }
+
+        fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+            Some(self)
+        }
+    }
+
+    impl ModuleLifecycle for ManagedTool {
+        fn init(
+            &self,
+            config: Value,
+        ) -> crate::traits::BoxFuture<'_, Result<(), crate::errors::AmplifierError>> {
+            self.calls.lock().unwrap().push(format!("init {config}"));
+            let fail = self.fail_init;
+            Box::pin(async move {
+                if fail {
+                    Err(crate::errors::ToolError::ExecutionFailed {
+                        message: "no pool".into(),
+                        stdout: None,
+                        stderr: None,
+                        exit_code: None,
+                    }
+                    .into())
+                } else {
+                    Ok(())
+                }
+            })
+        }
+
+        fn health_check(&self) -> crate::traits::BoxFuture<'_, ModuleHealth> {
+            Box::pin(async { self.health.clone() })
+        }
+
+        fn shutdown(
+            &self,
+        ) -> crate::traits::BoxFuture<'_, Result<(), crate::errors::AmplifierError>> {
+            self.calls.lock().unwrap().push("shutdown".into());
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn managed_mount_runs_init_and_skips_failed_modules() {
+        let coord = Coordinator::new_for_test();
+        let tool = ManagedTool::new(ModuleHealth::healthy());
+        coord
+            .mount_tool_managed("a", tool.clone(), serde_json::json!({"pool": 2}))
+            .await
+            .unwrap();
+        assert_eq!(tool.calls(), vec![r#"init {"pool":2}"#]);
+        assert!(coord.get_tool("a").is_some());
+
+        let err = coord
+            .mount_tool_managed("b", ManagedTool::failing(), Value::Null)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "coordinator.module_init_failed");
+        assert!(err.to_string().contains("no pool"));
+        assert!(coord.get_tool("b").is_none());
+
+        // Modules without a lifecycle mount as before.
+        coord
+            .mount_provider_managed("p", Arc::new(FakeProvider::new("p", "hi")), Value::Null)
+            .await
+            .unwrap();
+        assert!(coord.get_provider("p").is_some());
+    }
+
+    /// Builds the modules of [`mount_plan`]; `tool-broken` fails.
+    struct PlanFactory {
+        managed: Arc<ManagedTool>,
+    }
+
+    impl ModuleFactory for PlanFactory {
+        fn create<'a>(
+            &'a self,
+            step: &'a MountStep,
+        ) -> crate::traits::BoxFuture<
+            'a,
+            Result<LoadedModule, Box<dyn std::error::Error + Send + Sync>>,
+        > {
+            Box::pin(async move {
+                Ok(match step.module.id() {
+                    "loop" => LoadedModule::Orchestrator(Arc::new(FakeOrchestrator::new("done"))),
+                    "provider-mock" => {
+                        LoadedModule::Provider(Arc::new(FakeProvider::new("mock", "hi")))
+                    }
+                    "tool-managed" => LoadedModule::Tool(self.managed.clone()),
+                    _ => return Err("no such module".into()),
+                })
+            })
+        }
+    }
+
+    fn mount_plan(ids: &[(&str, &str)]) -> MountPlan {
+        let modules: Vec<_> = ids
+            .iter()
+            .map(|(id, module_type)| {
+                crate::manifest::ModuleDescriptor::from_json(
+                    &format!(
+                        r#"{{"module": {{"id": "{id}", "type": "{module_type}", "requires": ["store"]}}}}"#
+                    ),
+                    id,
+                )
+                .unwrap()
+            })
+            .collect();
+        crate::manifest::resolve_with_capabilities(&modules, &["store"]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn apply_mount_plan_initializes_modules_with_their_config() {
+        let coord = Coordinator::new_for_test();
+        let managed = ManagedTool::new(ModuleHealth::healthy());
+        let session_config = HashMap::from([(
+            "tools".to_string(),
+            serde_json::json!([{"module": "tool-managed", "config": {"pool": 2}}]),
+        )]);
+        let plan = mount_plan(&[
+            ("loop", "orchestrator"),
+            ("provider-mock", "provider"),
+            ("tool-managed", "tool"),
+        ])
+        .with_session_config(&session_config);
+        let factory = PlanFactory {
+            managed: managed.clone(),
+        };
+
+        coord.apply_mount_plan(&plan, &factory).await.unwrap();
+        assert!(coord.has_orchestrator());
+        assert_eq!(coord.provider_names(), vec!["mock"]);
+        assert_eq!(coord.tool_names(), vec!["managed"]);
+        assert_eq!(managed.calls(), vec![r#"init {"pool":2}"#]);
+        assert_eq!(coord.capability_requirements().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn apply_mount_plan_rolls_back_on_failure() {
+        let coord = Coordinator::new_for_test();
+        let managed = ManagedTool::new(ModuleHealth::healthy());
+        let plan = mount_plan(&[
+            ("loop", "orchestrator"),
+            ("provider-mock", "provider"),
+            ("tool-managed", "tool"),
+            ("tool-broken", "tool"),
+        ]);
+        let factory = PlanFactory {
+            managed: managed.clone(),
+        };
+
+        let err = coord.apply_mount_plan(&plan, &factory).await.unwrap_err();
+        assert!(
+            matches!(err, ManifestError::Load { module, reason } if module == "tool-broken" && reason == "no such module")
+        );
+        assert!(!coord.has_orchestrator());
+        assert!(coord.provider_names().is_empty());
+        assert!(coord.tool_names().is_empty());
+        assert!(coord.capability_requirements().is_empty());
+        assert_eq!(managed.calls(), vec!["init null", "shutdown"]);
+    }
+
+    #[test]
+    fn check_capabilities_reports_unregistered_requirements() {
+        let coord = Coordinator::new_for_test();
+        coord.require_capabilities("tool-search", &["vector-store", "embeddings"]);
+        coord.require_capabilities("tool-recall", &["vector-store"]);
+        coord.register_capability_object("embeddings", Arc::new(42u32));
+
+        let err = coord.check_capabilities().unwrap_err();
+        assert_eq!(err.code(), "coordinator.missing_capabilities");
+        assert_eq!(
+            err.to_string(),
+            "Missing required capabilities: tool-recall needs vector-store; \
+             tool-search needs vector-store"
+        );
+
+        coord.register_capability("vector-store", serde_json::json!({"dims": 3}));
+        assert!(coord.check_capabilities().is_ok());
+        coord.require_capabilities("tool-recall", &[]);
+        assert_eq!(coord.capability_requirements().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn health_reports_worst_status() {
+        let coord = Coordinator::new_for_test();
+        assert_eq!(coord.health().await.status, HealthStatus::Healthy);
+
+        coord.mount_tool("ok", ManagedTool::new(ModuleHealth::healthy()));
+        coord.mount_tool("slow", ManagedTool::new(ModuleHealth::degraded("fallback")));
+        coord.mount_provider("plain", Arc::new(FakeProvider::new("plain", "hi")));
+
+        let report = coord.health().await;
+        assert_eq!(report.status, HealthStatus::Degraded);
+        let names: Vec<_> = report.modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["ok", "slow"]);
+        assert_eq!(
+            serde_json::to_value(&report.modules[1]).unwrap(),
+            serde_json::json!({
+                "mount_point": "tools",
+                "name": "slow",
+                "status": "degraded",
+                "message": "fallback"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn unmount_replace_and_cleanup_shut_modules_down() {
+        let coord = Coordinator::new_for_test();
+        let first = ManagedTool::new(ModuleHealth::healthy());
+        let second = ManagedTool::new(ModuleHealth::healthy());
+        let other = ManagedTool::new(ModuleHealth::healthy());
+
+        coord
+            .mount_tool_managed("t", first.clone(), Value::Null)
+            .await
+            .unwrap();
+        coord
+            .mount_tool_managed("t", second.clone(), Value::Null)
+            .await
+            .unwrap();
+        assert_eq!(first.calls(), vec!["init null", "shutdown"]);
+
+        assert!(coord.unmount_tool_managed("t").await);
+        assert!(!coord.unmount_tool_managed("t").await);
+        assert_eq!(second.calls(), vec!["init null", "shutdown"]);
+
+        coord.mount_tool("u", other.clone());
+        coord.cleanup().await;
+        assert_eq!(other.calls(), vec!["shutdown"]);
+    }
 }
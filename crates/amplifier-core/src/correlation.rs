@@ -0,0 +1,93 @@
+//! Turn and tool-call correlation IDs on events.
+//!
+//! Every event emitted during a turn carries the IDs needed to join logs,
+//! hook payloads, audit records and traces:
+//!
+//! | Field          | Set by                                                     | On                                   |
+//! |----------------|------------------------------------------------------------|--------------------------------------|
+//! | `turn_id`      | [`Session::execute`](crate::session::Session::execute), a fresh UUID per turn | every event until the turn ends |
+//! | `tool_call_id` | [`ToolExecutor`](crate::tool_executor::ToolExecutor), per call | events emitted from the task running the call |
+//!
+//! `turn_id` is a hook-registry default field, so it reaches events emitted
+//! from any task. `tool_call_id` is task-scoped ([`scope`]), because calls
+//! run in parallel: events a tool emits while it runs (`tool:progress`,
+//! nested provider calls, `artifact:write`) carry its call ID. A field set
+//! in the event payload always wins.
+//!
+//! Code running inside a turn reads the IDs with [`current`]; tools also
+//! find them on their [`ToolContext`](crate::models::ToolContext).
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+tokio::task_local! {
+    static CURRENT: CorrelationIds;
+}
+
+/// The correlation IDs in effect.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrelationIds {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl CorrelationIds {
+    /// Whether no ID is set.
+    pub fn is_empty(&self) -> bool {
+        self.turn_id.is_none() && self.tool_call_id.is_none()
+    }
+}
+
+/// A new turn ID.
+pub fn new_turn_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The IDs of the current task's [`scope`], or empty outside one.
+pub fn current() -> CorrelationIds {
+    CURRENT.try_with(Clone::clone).unwrap_or_default()
+}
+
+/// Run `fut` with `ids` as the current IDs. Unset fields are inherited from
+/// the enclosing scope.
+pub async fn scope<F: Future>(ids: CorrelationIds, fut: F) -> F::Output {
+    let outer = current();
+    let ids = CorrelationIds {
+        turn_id: ids.turn_id.or(outer.turn_id),
+        tool_call_id: ids.tool_call_id.or(outer.tool_call_id),
+    };
+    CURRENT.scope(ids, fut).await
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scopes_nest_and_inherit() {
+        assert!(current().is_empty());
+        let turn = CorrelationIds {
+            turn_id: Some("t1".into()),
+            ..Default::default()
+        };
+        scope(turn, async {
+            let call = CorrelationIds {
+                tool_call_id: Some("c1".into()),
+                ..Default::default()
+            };
+            let inner = scope(call, async { current() }).await;
+            assert_eq!(inner.turn_id.as_deref(), Some("t1"));
+            assert_eq!(inner.tool_call_id.as_deref(), Some("c1"));
+            assert_eq!(current().tool_call_id, None);
+        })
+        .await;
+        assert!(current().is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+//! Host-pluggable credential resolution.
+//!
+//! [`ProviderInfo::credential_env_vars`] names the credentials a provider
+//! needs (`["ANTHROPIC_API_KEY"]`). Rather than each loader reading the
+//! process environment, the kernel asks the coordinator's
+//! [`CredentialResolver`] for each one, so hosts can serve secrets from a
+//! vault, a per-tenant store or a test fixture:
+//!
+//! | Resolver                       | Source                                      |
+//! |--------------------------------|---------------------------------------------|
+//! | [`EnvCredentialResolver`]      | process environment (the default)           |
+//! | [`StaticCredentialResolver`]   | an in-memory map, per provider or shared    |
+//!
+//! Resolved values are wrapped in [`SecretString`], whose `Debug` and
+//! `Display` output is redacted so credentials do not end up in logs.
+//!
+//! # Connections
+//!
+//! - Set on the [`Coordinator`](crate::coordinator::Coordinator) with
+//!   `set_credential_resolver`.
+//! - WASM providers loaded with a coordinator (see
+//!   [`module_resolver::load_module`](crate::module_resolver)) receive their
+//!   resolved credentials as WASI environment variables; nothing else in the
+//!   sandbox is inherited.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::models::ProviderInfo;
+
+/// Provider ID under which [`StaticCredentialResolver`] keeps credentials
+/// shared by every provider.
+pub const ANY_PROVIDER: &str = "*";
+
+// ---------------------------------------------------------------------------
+// SecretString
+// ---------------------------------------------------------------------------
+
+/// A credential value whose `Debug` / `Display` output is redacted.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The secret itself. Avoid formatting or logging the result.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretString([REDACTED])")
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CredentialResolver
+// ---------------------------------------------------------------------------
+
+/// Credential resolution failures.
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    /// The resolver has no value for this credential.
+    #[error("no credential {field} for provider {provider_id}")]
+    NotFound { provider_id: String, field: String },
+
+    /// The backing secret store failed.
+    #[error("credential lookup failed: {message}")]
+    Backend { message: String },
+}
+
+/// Resolves a provider's credentials.
+///
+/// `field` is one of the names listed in
+/// [`ProviderInfo::credential_env_vars`]. Resolution is synchronous because
+/// provider construction is; hosts with remote secret stores should fetch
+/// (and cache) secrets ahead of loading.
+pub trait CredentialResolver: Send + Sync {
+    /// Resolve credential `field` for `provider_id`.
+    ///
+    /// # Errors
+    ///
+    /// [`CredentialError::NotFound`] when no value exists, or
+    /// [`CredentialError::Backend`] when the lookup itself failed.
+    fn resolve(&self, provider_id: &str, field: &str) -> Result<SecretString, CredentialError>;
+}
+
+/// Resolve every credential `info` declares.
+///
+/// Missing credentials are skipped with a warning (the provider may treat
+/// some as optional); any other failure is returned.
+///
+/// # Errors
+///
+/// The first [`CredentialError::Backend`] encountered.
+pub fn resolve_provider_credentials(
+    resolver: &dyn CredentialResolver,
+    info: &ProviderInfo,
+) -> Result<Vec<(String, SecretString)>, CredentialError> {
+    let mut resolved = Vec::with_capacity(info.credential_env_vars.len());
+    for field in &info.credential_env_vars {
+        match resolver.resolve(&info.id, field) {
+            Ok(secret) => resolved.push((field.clone(), secret)),
+            Err(CredentialError::NotFound { .. }) => {
+                log::warn!("Provider '{}': credential {field} not available", info.id);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Reads credentials from the process environment: `field` is the variable
+/// name, whatever the provider.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvCredentialResolver;
+
+impl CredentialResolver for EnvCredentialResolver {
+    fn resolve(&self, provider_id: &str, field: &str) -> Result<SecretString, CredentialError> {
+        std::env::var(field)
+            .ok()
+            .filter(|value| !value.is_empty())
+            .map(SecretString::from)
+            .ok_or_else(|| CredentialError::NotFound {
+                provider_id: provider_id.to_string(),
+                field: field.to_string(),
+            })
+    }
+}
+
+/// Serves credentials from an in-memory map.
+///
+/// A value set for a specific provider wins over one set for
+/// [`ANY_PROVIDER`].
+#[derive(Debug, Clone, Default)]
+pub struct StaticCredentialResolver {
+    values: HashMap<(String, String), SecretString>,
+}
+
+impl StaticCredentialResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add credential `field` for `provider_id` (or [`ANY_PROVIDER`]).
+    pub fn with(
+        mut self,
+        provider_id: impl Into<String>,
+        field: impl Into<String>,
+        value: impl Into<SecretString>,
+    ) -> Self {
+        self.values
+            .insert((provider_id.into(), field.into()), value.into());
+        self
+    }
+}
+
+impl CredentialResolver for StaticCredentialResolver {
+    fn resolve(&self, provider_id: &str, field: &str) -> Result<SecretString, CredentialError> {
+        let lookup = |provider: &str| {
+            self.values
+                .get(&(provider.to_string(), field.to_string()))
+                .cloned()
+        };
+        lookup(provider_id)
+            .or_else(|| lookup(ANY_PROVIDER))
+            .ok_or_else(|| CredentialError::NotFound {
+                provider_id: provider_id.to_string(),
+                field: field.to_string(),
+            })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(fields: &[&str]) -> ProviderInfo {
+        ProviderInfo {
+            id: "anthropic".into(),
+            display_name: "Anthropic".into(),
+            credential_env_vars: fields.iter().map(|f| f.to_string()).collect(),
+            capabilities: Vec::new(),
+            defaults: HashMap::new(),
+            config_fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn secrets_are_redacted() {
+        let secret = SecretString::new("sk-live-123");
+        assert_eq!(format!("{secret}"), "[REDACTED]");
+        assert!(!format!("{secret:?}").contains("sk-live"));
+        assert_eq!(secret.expose_secret(), "sk-live-123");
+    }
+
+    #[test]
+    fn static_resolver_prefers_provider_specific_values() {
+        let resolver = StaticCredentialResolver::new()
+            .with(ANY_PROVIDER, "API_KEY", "shared")
+            .with("anthropic", "API_KEY", "scoped");
+        assert_eq!(
+            resolver
+                .resolve("anthropic", "API_KEY")
+                .unwrap()
+                .expose_secret(),
+            "scoped"
+        );
+        assert_eq!(
+            resolver
+                .resolve("openai", "API_KEY")
+                .unwrap()
+                .expose_secret(),
+            "shared"
+        );
+        assert!(matches!(
+            resolver.resolve("openai", "OTHER"),
+            Err(CredentialError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn env_resolver_reads_variables_by_name() {
+        assert!(EnvCredentialResolver.resolve("any", "PATH").is_ok());
+        assert!(matches!(
+            EnvCredentialResolver.resolve("any", "AMPLIFIER_TEST_UNSET_CREDENTIAL"),
+            Err(CredentialError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn provider_credentials_skip_missing_fields() {
+        let resolver = StaticCredentialResolver::new().with("anthropic", "ANTHROPIC_API_KEY", "k");
+        let resolved = resolve_provider_credentials(
+            &resolver,
+            &info(&["ANTHROPIC_API_KEY", "ANTHROPIC_BASE_URL"]),
+        )
+        .unwrap();
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].0, "ANTHROPIC_API_KEY");
+    }
+}
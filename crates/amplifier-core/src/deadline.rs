@@ -0,0 +1,284 @@
+//! TurnDeadline — wall-clock budget for a single turn.
+//!
+//! A turn deadline is set by
+//! [`Session::execute_with_deadline`](crate::session::Session::execute_with_deadline)
+//! and stored on the [`Coordinator`](crate::coordinator::Coordinator) for the
+//! duration of the turn. Kernel-side entry points read it back so that each
+//! call inside the turn gets at most the time that is left:
+//!
+//! - [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) clamps
+//!   `ChatRequest::timeout` and reports the computed value in the
+//!   `provider:pre` payload.
+//! - [`execute_tool`] bounds tool execution and fails with
+//!   [`ToolError::Timeout`] instead of overrunning the turn.
+//!
+//! Deadlines read time from a [`Clock`]; the session builds them on the
+//! coordinator's clock so a manual test clock drives expiry too.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, Clock};
+
+use crate::errors::ToolError;
+use crate::models::ToolResult;
+use crate::traits::Tool;
+
+/// The instant by which the current turn must finish.
+///
+/// Time is read from the deadline's [`Clock`] — the system clock unless built
+/// with [`after_on`](Self::after_on).
+#[derive(Clone)]
+pub struct TurnDeadline {
+    expires_at: Instant,
+    clock: Arc<dyn Clock>,
+}
+
+impl TurnDeadline {
+    /// A deadline `timeout` from now.
+    pub fn after(timeout: Duration) -> Self {
+        Self::after_on(clock::system(), timeout)
+    }
+
+    /// A deadline `timeout` from now on `clock`.
+    pub fn after_on(clock: Arc<dyn Clock>, timeout: Duration) -> Self {
+        Self {
+            expires_at: clock.now() + timeout,
+            clock,
+        }
+    }
+
+    /// A deadline at a fixed instant.
+    pub fn at(expires_at: Instant) -> Self {
+        Self {
+            expires_at,
+            clock: clock::system(),
+        }
+    }
+
+    /// The instant the deadline expires.
+    pub fn expires_at(&self) -> Instant {
+        self.expires_at
+    }
+
+    /// Time left before the deadline (zero once expired).
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(self.clock.now())
+    }
+
+    /// Whether the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// The shorter of `requested` and the remaining time.
+    pub fn clamp(&self, requested: Option<Duration>) -> Duration {
+        let remaining = self.remaining();
+        requested.map_or(remaining, |r| r.min(remaining))
+    }
+}
+
+impl fmt::Debug for TurnDeadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TurnDeadline")
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for TurnDeadline {
+    fn eq(&self, other: &Self) -> bool {
+        self.expires_at == other.expires_at
+    }
+}
+
+impl Eq for TurnDeadline {}
+
+/// Run `fut`, giving up when `deadline` (if any) is reached.
+///
+/// Returns `None` on timeout.
+pub async fn run_until<F: Future>(deadline: Option<TurnDeadline>, fut: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => {
+            let timer = deadline.clock.sleep(deadline.remaining());
+            tokio::select! {
+                biased;
+                output = fut => Some(output),
+                _ = timer => None,
+            }
+        }
+        None => Some(fut.await),
+    }
+}
+
+/// Execute `tool`, bounded by the turn deadline if one is set.
+///
+/// # Errors
+///
+/// - [`ToolError::Timeout`] if the deadline is reached before the tool returns
+///   (or has already passed, in which case the tool is not started)
+/// - Any `ToolError` from the tool itself
+pub async fn execute_tool(
+    deadline: Option<TurnDeadline>,
+    tool: &dyn Tool,
+    input: serde_json::Value,
+) -> Result<ToolResult, ToolError> {
+    run_tool(deadline, tool, tool.execute(input)).await
+}
+
+/// Drive `execution` (a future returned by one of `tool`'s execute methods),
+/// bounded by the turn deadline if one is set.
+///
+/// The future is not polled if the deadline has already passed.
+///
+/// # Errors
+///
+/// Same as [`execute_tool`].
+pub async fn run_tool<F>(
+    deadline: Option<TurnDeadline>,
+    tool: &dyn Tool,
+    execution: F,
+) -> Result<ToolResult, ToolError>
+where
+    F: Future<Output = Result<ToolResult, ToolError>>,
+{
+    let timeout_err = |budget: Duration| ToolError::Timeout {
+        name: tool.name().to_string(),
+        timeout_ms: budget.as_millis() as u64,
+        partial_output: None,
+    };
+    if let Some(deadline) = &deadline {
+        let budget = deadline.remaining();
+        if budget.is_zero() {
+            return Err(timeout_err(budget));
+        }
+        log::debug!(
+            "Tool '{}' bounded by turn deadline: {} ms",
+            tool.name(),
+            budget.as_millis()
+        );
+        return run_until(Some(deadline.clone()), execution)
+            .await
+            .unwrap_or_else(|| Err(timeout_err(budget)));
+    }
+    execution.await
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::pin::Pin;
+
+    use crate::messages::ToolSpec;
+    use crate::testing::{EchoTool, ManualClock};
+
+    /// A tool that sleeps before answering.
+    struct SlowTool(Duration);
+
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        fn description(&self) -> &str {
+            "sleeps"
+        }
+
+        fn get_spec(&self) -> ToolSpec {
+            EchoTool.get_spec()
+        }
+
+        fn execute(
+            &self,
+            _input: serde_json::Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            let delay = self.0;
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                Ok(ToolResult::default())
+            })
+        }
+    }
+
+    #[test]
+    fn clamp_takes_the_shorter_budget() {
+        let deadline = TurnDeadline::after(Duration::from_secs(10));
+        assert!(deadline.clamp(Some(Duration::from_secs(1))) <= Duration::from_secs(1));
+        let clamped = deadline.clamp(Some(Duration::from_secs(60)));
+        assert!(clamped <= Duration::from_secs(10) && clamped > Duration::from_secs(9));
+        assert!(deadline.clamp(None) <= Duration::from_secs(10));
+    }
+
+    #[test]
+    fn expired_deadline_has_no_remaining_time() {
+        let deadline = TurnDeadline::at(Instant::now() - Duration::from_millis(1));
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn execute_tool_without_deadline_runs_to_completion() {
+        let result = execute_tool(
+            None,
+            &SlowTool(Duration::from_millis(5)),
+            serde_json::json!({}),
+        )
+        .await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn execute_tool_times_out_at_deadline() {
+        let deadline = TurnDeadline::after(Duration::from_millis(20));
+        let err = execute_tool(
+            Some(deadline),
+            &SlowTool(Duration::from_secs(5)),
+            serde_json::json!({}),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::Timeout { ref name, .. } if name == "slow"));
+    }
+
+    #[tokio::test]
+    async fn deadline_follows_its_clock() {
+        let clock = ManualClock::default();
+        let deadline = TurnDeadline::after_on(Arc::new(clock.clone()), Duration::from_secs(30));
+        assert_eq!(deadline.remaining(), Duration::from_secs(30));
+
+        let advance = async {
+            clock.advance(Duration::from_secs(10));
+            tokio::task::yield_now().await;
+            clock.advance(Duration::from_secs(20));
+        };
+        let tool = SlowTool(Duration::from_secs(3600));
+        let (result, ()) = tokio::join!(
+            execute_tool(Some(deadline.clone()), &tool, serde_json::json!({}),),
+            advance
+        );
+
+        assert!(deadline.is_expired());
+        assert!(matches!(
+            result.unwrap_err(),
+            ToolError::Timeout {
+                timeout_ms: 30_000,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn execute_tool_skips_start_when_already_expired() {
+        let deadline = TurnDeadline::at(Instant::now() - Duration::from_millis(1));
+        let err = execute_tool(Some(deadline), &EchoTool, serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::Timeout { timeout_ms: 0, .. }));
+    }
+}
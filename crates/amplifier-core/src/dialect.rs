@@ -0,0 +1,1387 @@
+//! Provider wire dialects.
+//!
+//! A [`ProviderDialect`] converts the canonical [`ChatRequest`] into a
+//! provider's request body and that provider's response body back into a
+//! [`ChatResponse`]. A provider crate for an OpenAI- or Anthropic-compatible
+//! API then only implements transport:
+//!
+//! ```rust,ignore
+//! fn complete(&self, request: ChatRequest) -> ... {
+//!     Box::pin(async move {
+//!         let body = self.dialect.encode_request(&request)?;
+//!         let reply = self.http_post("/v1/messages", body).await?;
+//!         Ok(self.dialect.decode_response(&reply)?)
+//!     })
+//! }
+//! ```
+//!
+//! | Dialect               | System prompt                | Tools                         |
+//! |-----------------------|------------------------------|-------------------------------|
+//! | [`OpenAiDialect`]     | inline `system` messages     | `{"type": "function", ...}`   |
+//! | [`AnthropicDialect`]  | top-level `system` string    | `{"name", "input_schema"}`    |
+//!
+//! The building blocks — [`split_system_prompt`], [`openai_tool`],
+//! [`anthropic_tool`], [`message_text`], [`message_cache_hint`] — are
+//! public so dialects for other APIs can reuse them.
+//!
+//! Providers that build tool definitions themselves use
+//! [`ToolSpec::to_dialect`] or [`DialectTools`], which also sanitize names
+//! and truncate descriptions to the API's limits (see [`Dialect`]).
+//!
+//! Request fields a dialect cannot express (`reasoning_effort` for
+//! Anthropic, say) are dropped with a debug log. Content it cannot express
+//! (audio without a transcript) is an error, since dropping it would change
+//! the conversation. Request `extensions` are copied to the top level of the
+//! wire body, so callers can pass provider-specific parameters through.
+//!
+//! [`CacheHint`]s on messages and content blocks become Anthropic
+//! `cache_control` markers on the corresponding wire blocks (a hinted system
+//! message turns `system` into a one-block array). OpenAI caches prompts
+//! automatically, so [`OpenAiDialect`] drops hints. Either way, cache hits
+//! come back in [`Usage::cache_read_tokens`] and
+//! [`Usage::cache_write_tokens`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+
+use crate::errors::ProviderError;
+use crate::messages::{
+    CacheHint, ChatRequest, ChatResponse, ContentBlock, Message, MessageContent, ResponseFormat,
+    Role, ToolCall, ToolChoice, ToolSpec, Usage,
+};
+use crate::traits::Tool;
+
+/// `max_tokens` sent by [`AnthropicDialect`] when the request sets no
+/// `max_output_tokens` (the Messages API requires one).
+pub const DEFAULT_ANTHROPIC_MAX_TOKENS: i64 = 4096;
+
+// ---------------------------------------------------------------------------
+// ProviderDialect
+// ---------------------------------------------------------------------------
+
+/// Dialect conversion failures.
+#[derive(Debug, thiserror::Error)]
+pub enum DialectError {
+    /// The request holds content the wire format has no equivalent for.
+    #[error("{dialect} dialect cannot express {what}")]
+    Unsupported { dialect: &'static str, what: String },
+
+    /// The provider's response body did not have the expected shape.
+    #[error("malformed {dialect} response: {message}")]
+    MalformedResponse {
+        dialect: &'static str,
+        message: String,
+    },
+}
+
+impl From<DialectError> for ProviderError {
+    fn from(error: DialectError) -> Self {
+        let message = error.to_string();
+        match error {
+            DialectError::Unsupported { .. } => ProviderError::InvalidRequest {
+                message,
+                provider: None,
+                model: None,
+                retry_after: None,
+            },
+            DialectError::MalformedResponse { .. } => ProviderError::Other {
+                message,
+                provider: None,
+                model: None,
+                retry_after: None,
+                status_code: None,
+                retryable: false,
+                delay_multiplier: None,
+            },
+        }
+    }
+}
+
+/// Converts canonical chat types to and from one provider wire format.
+///
+/// Conversions are pure and synchronous; transport, authentication and
+/// error-status mapping stay with the provider.
+pub trait ProviderDialect: Send + Sync {
+    /// Short dialect name used in errors (`"openai"`).
+    fn name(&self) -> &'static str;
+
+    /// Build the wire request body for `request`.
+    ///
+    /// # Errors
+    ///
+    /// [`DialectError::Unsupported`] when the request holds content this
+    /// format cannot carry.
+    fn encode_request(&self, request: &ChatRequest) -> Result<Value, DialectError>;
+
+    /// Parse a wire response body.
+    ///
+    /// # Errors
+    ///
+    /// [`DialectError::MalformedResponse`] when `body` is not a response in
+    /// this format.
+    fn decode_response(&self, body: &Value) -> Result<ChatResponse, DialectError>;
+}
+
+// ---------------------------------------------------------------------------
+// Shared helpers
+// ---------------------------------------------------------------------------
+
+/// Where a wire format puts the system prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemPromptPlacement {
+    /// System and developer messages stay in the message list.
+    Inline,
+    /// System and developer messages are lifted out and joined (blank-line
+    /// separated) into one top-level prompt.
+    TopLevel,
+}
+
+/// Split `messages` according to `placement`.
+///
+/// With [`SystemPromptPlacement::TopLevel`], returns the joined system
+/// prompt (if any) and the remaining messages; with `Inline`, no prompt and
+/// every message.
+pub fn split_system_prompt(
+    messages: &[Message],
+    placement: SystemPromptPlacement,
+) -> (Option<String>, Vec<&Message>) {
+    if placement == SystemPromptPlacement::Inline {
+        return (None, messages.iter().collect());
+    }
+    let (system, rest): (Vec<&Message>, Vec<&Message>) = messages
+        .iter()
+        .partition(|m| matches!(m.role, Role::System | Role::Developer));
+    let prompt = system
+        .iter()
+        .map(|m| message_text(&m.content))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    ((!prompt.is_empty()).then_some(prompt), rest)
+}
+
+/// The text blocks of `content`, concatenated.
+pub fn message_text(content: &MessageContent) -> String {
+    match content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Blocks(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+/// A tool result's output as wire text: strings as-is, anything else as
+/// JSON.
+pub fn tool_output_text(output: &Value) -> String {
+    match output {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// `spec` as an OpenAI Chat Completions tool.
+pub fn openai_tool(spec: &ToolSpec) -> Value {
+    let mut function = json!({"name": spec.name, "parameters": spec.parameters});
+    if let Some(description) = &spec.description {
+        function["description"] = json!(description);
+    }
+    json!({"type": "function", "function": function})
+}
+
+/// `spec` as an Anthropic Messages tool.
+pub fn anthropic_tool(spec: &ToolSpec) -> Value {
+    let mut tool = json!({"name": spec.name, "input_schema": spec.parameters});
+    if let Some(description) = &spec.description {
+        tool["description"] = json!(description);
+    }
+    tool
+}
+
+/// The [`CacheHint`] of `message`, or else of its first hinted block.
+pub fn message_cache_hint(message: &Message) -> Option<CacheHint> {
+    message.cache.or_else(|| match &message.content {
+        MessageContent::Text(_) => None,
+        MessageContent::Blocks(blocks) => blocks.iter().find_map(ContentBlock::cache),
+    })
+}
+
+fn role_name(role: &Role) -> &'static str {
+    match role {
+        Role::System => "system",
+        Role::Developer => "developer",
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::Function => "function",
+        Role::Tool => "tool",
+    }
+}
+
+/// Copy the optional sampling fields shared by both dialects.
+fn insert_common(body: &mut Map<String, Value>, request: &ChatRequest) {
+    if let Some(model) = &request.model {
+        body.insert("model".into(), json!(model));
+    }
+    if let Some(temperature) = request.temperature {
+        body.insert("temperature".into(), json!(temperature));
+    }
+    if let Some(top_p) = request.top_p {
+        body.insert("top_p".into(), json!(top_p));
+    }
+    if let Some(stream) = request.stream {
+        body.insert("stream".into(), json!(stream));
+    }
+}
+
+/// Copy request extensions into `body` without overriding mapped fields.
+fn insert_extensions(body: &mut Map<String, Value>, request: &ChatRequest) {
+    for (key, value) in &request.extensions {
+        body.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+}
+
+fn object<'a>(
+    dialect: &'static str,
+    value: &'a Value,
+    what: &str,
+) -> Result<&'a Map<String, Value>, DialectError> {
+    value
+        .as_object()
+        .ok_or_else(|| DialectError::MalformedResponse {
+            dialect,
+            message: format!("{what} is not an object"),
+        })
+}
+
+fn int(value: &Value, key: &str) -> Option<i64> {
+    value.get(key).and_then(Value::as_i64)
+}
+
+fn arguments_map(value: &Value) -> HashMap<String, Value> {
+    match value {
+        Value::Object(map) => map.clone().into_iter().collect(),
+        _ => HashMap::new(),
+    }
+}
+
+/// Tool calls mirrored from the `ToolCall` blocks of `content`.
+pub(crate) fn tool_calls_of(content: &[ContentBlock]) -> Option<Vec<ToolCall>> {
+    let calls: Vec<ToolCall> = content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::ToolCall {
+                id, name, input, ..
+            } => Some(ToolCall {
+                id: id.clone(),
+                name: name.clone(),
+                arguments: input.clone(),
+                extensions: HashMap::new(),
+            }),
+            _ => None,
+        })
+        .collect();
+    (!calls.is_empty()).then_some(calls)
+}
+
+fn text_block(text: impl Into<String>) -> ContentBlock {
+    ContentBlock::Text {
+        text: text.into(),
+        visibility: None,
+        cache: None,
+        extensions: HashMap::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tool definitions
+// ---------------------------------------------------------------------------
+
+/// Longest tool name either API accepts.
+pub const MAX_TOOL_NAME_CHARS: usize = 64;
+
+/// Longest OpenAI function description sent; longer ones are truncated.
+pub const MAX_OPENAI_TOOL_DESCRIPTION_CHARS: usize = 1024;
+
+/// Wire format for tool definitions (see [`ToolSpec::to_dialect`]).
+///
+/// Unlike [`openai_tool`] and [`anthropic_tool`], which copy a spec as-is,
+/// conversion through a `Dialect` applies the API's rules:
+///
+/// | Dialect     | Name                                        | Description               |
+/// |-------------|---------------------------------------------|---------------------------|
+/// | `OpenAi`    | `[A-Za-z0-9_-]`, at most 64 chars           | at most 1024 chars        |
+/// | `Anthropic` | `[A-Za-z0-9_-]`, at most 64 chars           | unchanged                 |
+///
+/// Other characters in a name become `_`; truncated descriptions end in
+/// `...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    OpenAi,
+    Anthropic,
+}
+
+impl Dialect {
+    /// Longest description this dialect sends, if it has a limit.
+    pub fn max_description_chars(self) -> Option<usize> {
+        match self {
+            Self::OpenAi => Some(MAX_OPENAI_TOOL_DESCRIPTION_CHARS),
+            Self::Anthropic => None,
+        }
+    }
+
+    /// `name` with characters the API rejects replaced and the length
+    /// capped. An empty name becomes `"tool"`.
+    pub fn sanitize_tool_name(self, name: &str) -> String {
+        let sanitized: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(MAX_TOOL_NAME_CHARS)
+            .collect();
+        if sanitized.is_empty() {
+            "tool".to_string()
+        } else {
+            sanitized
+        }
+    }
+
+    /// `description` cut to [`max_description_chars`](Self::max_description_chars).
+    pub fn truncate_description(self, description: &str) -> String {
+        match self.max_description_chars() {
+            Some(max) if description.chars().count() > max => {
+                let kept: String = description.chars().take(max.saturating_sub(3)).collect();
+                format!("{}...", kept.trim_end())
+            }
+            _ => description.to_string(),
+        }
+    }
+
+    /// The wire definition of `spec` under `name`.
+    fn tool_definition(self, spec: &ToolSpec, name: String) -> Value {
+        let spec = ToolSpec {
+            name,
+            parameters: spec.parameters.clone(),
+            description: spec
+                .description
+                .as_deref()
+                .map(|d| self.truncate_description(d)),
+            extensions: HashMap::new(),
+        };
+        match self {
+            Self::OpenAi => openai_tool(&spec),
+            Self::Anthropic => anthropic_tool(&spec),
+        }
+    }
+}
+
+/// Wire definitions for a set of tools, with the mapping from sanitized
+/// wire names back to the tools' own names.
+///
+/// Names that sanitize to the same wire name get a numeric suffix
+/// (`read_file`, `read_file_2`), so every tool stays callable.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DialectTools {
+    /// Tool definitions in input order.
+    pub definitions: Vec<Value>,
+    /// Original tool name by wire name.
+    pub names: HashMap<String, String>,
+}
+
+impl DialectTools {
+    /// Convert `specs` for `dialect`.
+    pub fn new<'a>(dialect: Dialect, specs: impl IntoIterator<Item = &'a ToolSpec>) -> Self {
+        let mut tools = Self::default();
+        for spec in specs {
+            let base = dialect.sanitize_tool_name(&spec.name);
+            let mut name = base.clone();
+            let mut n = 2;
+            while tools.names.contains_key(&name) {
+                let suffix = format!("_{n}");
+                let keep = MAX_TOOL_NAME_CHARS - suffix.len();
+                name = format!("{}{suffix}", base.chars().take(keep).collect::<String>());
+                n += 1;
+            }
+            tools
+                .definitions
+                .push(dialect.tool_definition(spec, name.clone()));
+            tools.names.insert(name, spec.name.clone());
+        }
+        tools
+    }
+
+    /// Convert the specs of mounted `tools`, ordered by mount name.
+    pub fn from_tools(dialect: Dialect, tools: &HashMap<String, Arc<dyn Tool>>) -> Self {
+        let mut mounted: Vec<_> = tools.iter().collect();
+        mounted.sort_by(|a, b| a.0.cmp(b.0));
+        let specs: Vec<ToolSpec> = mounted.iter().map(|(_, tool)| tool.get_spec()).collect();
+        Self::new(dialect, &specs)
+    }
+
+    /// The tool name behind wire name `name` (`name` itself if unknown).
+    pub fn original_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.names.get(name).map_or(name, String::as_str)
+    }
+
+    /// Rewrite decoded tool calls from wire names to tool names.
+    pub fn restore_names(&self, calls: &mut [ToolCall]) {
+        for call in calls {
+            if let Some(original) = self.names.get(&call.name) {
+                call.name = original.clone();
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OpenAI
+// ---------------------------------------------------------------------------
+
+/// OpenAI Chat Completions (`/v1/chat/completions`) and compatible APIs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenAiDialect;
+
+impl OpenAiDialect {
+    const NAME: &'static str = "openai";
+
+    fn unsupported(what: impl Into<String>) -> DialectError {
+        DialectError::Unsupported {
+            dialect: Self::NAME,
+            what: what.into(),
+        }
+    }
+
+    /// A user message part, or `None` for blocks with no user-side meaning.
+    fn user_part(block: &ContentBlock) -> Result<Option<Value>, DialectError> {
+        Ok(match block {
+            ContentBlock::Text { text, .. } => Some(json!({"type": "text", "text": text})),
+            ContentBlock::Image { source, .. } => {
+                let url = match source.get("type").and_then(Value::as_str) {
+                    Some("url") => source.get("url").cloned().unwrap_or_default(),
+                    _ => json!(format!(
+                        "data:{};base64,{}",
+                        block.media_type().unwrap_or("image/png"),
+                        source.get("data").and_then(Value::as_str).unwrap_or("")
+                    )),
+                };
+                Some(json!({"type": "image_url", "image_url": {"url": url}}))
+            }
+            ContentBlock::Audio { transcript, .. } | ContentBlock::Document { transcript, .. } => {
+                let text = transcript
+                    .as_ref()
+                    .ok_or_else(|| Self::unsupported("audio or document without a transcript"))?;
+                Some(json!({"type": "text", "text": text}))
+            }
+            _ => None,
+        })
+    }
+
+    fn encode_message(message: &Message, out: &mut Vec<Value>) -> Result<(), DialectError> {
+        let role = role_name(&message.role);
+        let blocks = match &message.content {
+            MessageContent::Text(text) => {
+                let mut wire = json!({"role": role, "content": text});
+                if let Some(name) = &message.name {
+                    wire["name"] = json!(name);
+                }
+                if let Some(id) = &message.tool_call_id {
+                    wire["tool_call_id"] = json!(id);
+                }
+                out.push(wire);
+                return Ok(());
+            }
+            MessageContent::Blocks(blocks) => blocks,
+        };
+
+        // Tool results become their own `tool` messages, whatever role
+        // carried them.
+        for block in blocks {
+            if let ContentBlock::ToolResult {
+                tool_call_id,
+                output,
+                ..
+            } = block
+            {
+                out.push(json!({
+                    "role": "tool",
+                    "tool_call_id": tool_call_id,
+                    "content": tool_output_text(output),
+                }));
+            }
+        }
+
+        let mut wire = Map::new();
+        wire.insert("role".into(), json!(role));
+        if let Some(name) = &message.name {
+            wire.insert("name".into(), json!(name));
+        }
+        match message.role {
+            Role::User => {
+                let mut parts = Vec::new();
+                for block in blocks {
+                    parts.extend(Self::user_part(block)?);
+                }
+                if parts.is_empty() {
+                    return Ok(());
+                }
+                wire.insert("content".into(), Value::Array(parts));
+            }
+            Role::Assistant => {
+                let calls: Vec<Value> = blocks
+                    .iter()
+                    .filter_map(|block| match block {
+                        ContentBlock::ToolCall {
+                            id, name, input, ..
+                        } => Some(json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {
+                                "name": name,
+                                "arguments": Value::Object(input.clone().into_iter().collect())
+                                    .to_string(),
+                            },
+                        })),
+                        _ => None,
+                    })
+                    .collect();
+                let text = message_text(&message.content);
+                if text.is_empty() && calls.is_empty() {
+                    return Ok(());
+                }
+                wire.insert(
+                    "content".into(),
+                    if text.is_empty() {
+                        Value::Null
+                    } else {
+                        json!(text)
+                    },
+                );
+                if !calls.is_empty() {
+                    wire.insert("tool_calls".into(), Value::Array(calls));
+                }
+            }
+            Role::Tool => {
+                // A tool message whose results were all emitted above.
+                let text = message_text(&message.content);
+                if text.is_empty() {
+                    return Ok(());
+                }
+                let id = message
+                    .tool_call_id
+                    .as_ref()
+                    .ok_or_else(|| Self::unsupported("a tool message without tool_call_id"))?;
+                wire.insert("tool_call_id".into(), json!(id));
+                wire.insert("content".into(), json!(text));
+            }
+            _ => {
+                let text = message_text(&message.content);
+                if text.is_empty() {
+                    return Ok(());
+                }
+                wire.insert("content".into(), json!(text));
+            }
+        }
+        out.push(Value::Object(wire));
+        Ok(())
+    }
+
+    fn tool_choice(choice: &ToolChoice) -> Value {
+        match choice {
+            ToolChoice::String(mode) => json!(mode),
+            ToolChoice::Object(map) => match map.get("name") {
+                Some(name) if !map.contains_key("function") => {
+                    json!({"type": "function", "function": {"name": name}})
+                }
+                _ => json!(map),
+            },
+        }
+    }
+
+    fn response_format(format: &ResponseFormat) -> Value {
+        match format {
+            ResponseFormat::Text => json!({"type": "text"}),
+            ResponseFormat::Json => json!({"type": "json_object"}),
+            ResponseFormat::JsonSchema { schema, strict } => {
+                let mut spec = json!({"name": "response", "schema": schema});
+                if let Some(strict) = strict {
+                    spec["strict"] = json!(strict);
+                }
+                json!({"type": "json_schema", "json_schema": spec})
+            }
+        }
+    }
+
+    fn malformed(message: impl Into<String>) -> DialectError {
+        DialectError::MalformedResponse {
+            dialect: Self::NAME,
+            message: message.into(),
+        }
+    }
+}
+
+impl ProviderDialect for OpenAiDialect {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn encode_request(&self, request: &ChatRequest) -> Result<Value, DialectError> {
+        let mut messages = Vec::with_capacity(request.messages.len());
+        for message in &request.messages {
+            Self::encode_message(message, &mut messages)?;
+        }
+
+        let mut body = Map::new();
+        insert_common(&mut body, request);
+        body.insert("messages".into(), Value::Array(messages));
+        if let Some(tools) = request.tools.as_ref().filter(|t| !t.is_empty()) {
+            body.insert(
+                "tools".into(),
+                Value::Array(tools.iter().map(openai_tool).collect()),
+            );
+        }
+        if let Some(choice) = &request.tool_choice {
+            body.insert("tool_choice".into(), Self::tool_choice(choice));
+        }
+        if let Some(format) = &request.response_format {
+            body.insert("response_format".into(), Self::response_format(format));
+        }
+        if let Some(max) = request.max_output_tokens {
+            body.insert("max_completion_tokens".into(), json!(max));
+        }
+        if let Some(stop) = &request.stop {
+            body.insert("stop".into(), json!(stop));
+        }
+        if let Some(effort) = &request.reasoning_effort {
+            body.insert("reasoning_effort".into(), json!(effort));
+        }
+        if request
+            .messages
+            .iter()
+            .any(|m| message_cache_hint(m).is_some())
+        {
+            log::debug!("openai dialect: dropping cache hints (caching is automatic)");
+        }
+        insert_extensions(&mut body, request);
+        Ok(Value::Object(body))
+    }
+
+    fn decode_response(&self, body: &Value) -> Result<ChatResponse, DialectError> {
+        object(Self::NAME, body, "response")?;
+        let choice = body
+            .get("choices")
+            .and_then(|c| c.get(0))
+            .ok_or_else(|| Self::malformed("no choices"))?;
+        let message = choice
+            .get("message")
+            .ok_or_else(|| Self::malformed("choice has no message"))?;
+
+        let mut content = Vec::new();
+        if let Some(text) = message.get("content").and_then(Value::as_str) {
+            if !text.is_empty() {
+                content.push(text_block(text));
+            }
+        }
+        for call in message
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let function = call
+                .get("function")
+                .ok_or_else(|| Self::malformed("tool call has no function"))?;
+            let arguments = match function.get("arguments") {
+                Some(Value::String(raw)) if raw.trim().is_empty() => Value::Null,
+                Some(Value::String(raw)) => serde_json::from_str(raw)
+                    .map_err(|e| Self::malformed(format!("tool call arguments: {e}")))?,
+                Some(other) => other.clone(),
+                None => Value::Null,
+            };
+            content.push(ContentBlock::ToolCall {
+                id: call
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                name: function
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| Self::malformed("tool call has no name"))?
+                    .to_string(),
+                input: arguments_map(&arguments),
+                visibility: None,
+                cache: None,
+                extensions: HashMap::new(),
+            });
+        }
+
+        let usage = body.get("usage").map(|usage| Usage {
+            input_tokens: int(usage, "prompt_tokens").unwrap_or(0),
+            output_tokens: int(usage, "completion_tokens").unwrap_or(0),
+            total_tokens: int(usage, "total_tokens").unwrap_or(0),
+            reasoning_tokens: usage
+                .get("completion_tokens_details")
+                .and_then(|d| int(d, "reasoning_tokens")),
+            cache_read_tokens: usage
+                .get("prompt_tokens_details")
+                .and_then(|d| int(d, "cached_tokens")),
+            cache_write_tokens: None,
+            cost_usd: None,
+            extensions: HashMap::new(),
+        });
+
+        Ok(ChatResponse {
+            tool_calls: tool_calls_of(&content),
+            content,
+            usage,
+            degradation: None,
+            finish_reason: choice
+                .get("finish_reason")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            metadata: None,
+            extensions: HashMap::new(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Anthropic
+// ---------------------------------------------------------------------------
+
+/// Anthropic Messages (`/v1/messages`).
+#[derive(Debug, Clone, Copy)]
+pub struct AnthropicDialect {
+    default_max_tokens: i64,
+}
+
+impl Default for AnthropicDialect {
+    fn default() -> Self {
+        Self {
+            default_max_tokens: DEFAULT_ANTHROPIC_MAX_TOKENS,
+        }
+    }
+}
+
+impl AnthropicDialect {
+    const NAME: &'static str = "anthropic";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `max_tokens` used when a request has no `max_output_tokens`.
+    pub fn with_default_max_tokens(mut self, max_tokens: i64) -> Self {
+        self.default_max_tokens = max_tokens;
+        self
+    }
+
+    fn unsupported(what: impl Into<String>) -> DialectError {
+        DialectError::Unsupported {
+            dialect: Self::NAME,
+            what: what.into(),
+        }
+    }
+
+    fn malformed(message: impl Into<String>) -> DialectError {
+        DialectError::MalformedResponse {
+            dialect: Self::NAME,
+            message: message.into(),
+        }
+    }
+
+    /// A wire content block, or `None` for blocks the API has no place for.
+    fn encode_block(block: &ContentBlock) -> Result<Option<Value>, DialectError> {
+        Ok(match block {
+            ContentBlock::Text { text, .. } => Some(json!({"type": "text", "text": text})),
+            ContentBlock::Thinking {
+                thinking,
+                signature,
+                ..
+            } => {
+                let mut wire = json!({"type": "thinking", "thinking": thinking});
+                if let Some(signature) = signature {
+                    wire["signature"] = json!(signature);
+                }
+                Some(wire)
+            }
+            ContentBlock::RedactedThinking { data, .. } => {
+                Some(json!({"type": "redacted_thinking", "data": data}))
+            }
+            ContentBlock::ToolCall {
+                id, name, input, ..
+            } => Some(json!({"type": "tool_use", "id": id, "name": name, "input": input})),
+            ContentBlock::ToolResult {
+                tool_call_id,
+                output,
+                ..
+            } => Some(json!({
+                "type": "tool_result",
+                "tool_use_id": tool_call_id,
+                "content": tool_output_text(output),
+            })),
+            ContentBlock::Image { source, .. } => Some(json!({"type": "image", "source": source})),
+            ContentBlock::Document { source, title, .. } => {
+                let mut wire = json!({"type": "document", "source": source});
+                if let Some(title) = title {
+                    wire["title"] = json!(title);
+                }
+                Some(wire)
+            }
+            ContentBlock::Audio { transcript, .. } => {
+                let text = transcript
+                    .as_ref()
+                    .ok_or_else(|| Self::unsupported("audio without a transcript"))?;
+                Some(json!({"type": "text", "text": text}))
+            }
+            ContentBlock::Reasoning { .. } => None,
+        })
+    }
+
+    /// The `cache_control` marker for `hint`.
+    fn cache_control(hint: CacheHint) -> Value {
+        match hint {
+            CacheHint::Ephemeral => json!({"type": "ephemeral"}),
+        }
+    }
+
+    /// `(role, blocks)` for one canonical message. Tool messages become user
+    /// turns holding a `tool_result`. A message-level cache hint marks the
+    /// message's last wire block.
+    fn encode_message(message: &Message) -> Result<(&'static str, Vec<Value>), DialectError> {
+        let role = match message.role {
+            Role::Assistant => "assistant",
+            _ => "user",
+        };
+        let mut blocks = match (&message.role, &message.content) {
+            (Role::Tool | Role::Function, MessageContent::Text(text)) => {
+                let id = message
+                    .tool_call_id
+                    .as_ref()
+                    .ok_or_else(|| Self::unsupported("a tool message without tool_call_id"))?;
+                vec![json!({"type": "tool_result", "tool_use_id": id, "content": text})]
+            }
+            (_, MessageContent::Text(text)) => vec![json!({"type": "text", "text": text})],
+            (_, MessageContent::Blocks(blocks)) => {
+                let mut wire = Vec::with_capacity(blocks.len());
+                for block in blocks {
+                    if let Some(mut encoded) = Self::encode_block(block)? {
+                        if let Some(hint) = block.cache() {
+                            encoded["cache_control"] = Self::cache_control(hint);
+                        }
+                        wire.push(encoded);
+                    }
+                }
+                wire
+            }
+        };
+        if let (Some(hint), Some(last)) = (message.cache, blocks.last_mut()) {
+            last["cache_control"] = Self::cache_control(hint);
+        }
+        Ok((role, blocks))
+    }
+
+    fn tool_choice(choice: &ToolChoice) -> Value {
+        match choice {
+            ToolChoice::String(mode) => match mode.as_str() {
+                "required" | "any" => json!({"type": "any"}),
+                "none" => json!({"type": "none"}),
+                "auto" => json!({"type": "auto"}),
+                name => json!({"type": "tool", "name": name}),
+            },
+            ToolChoice::Object(map) => {
+                let name = map
+                    .get("name")
+                    .or_else(|| map.get("function").and_then(|f| f.get("name")));
+                match name {
+                    Some(name) => json!({"type": "tool", "name": name}),
+                    None => json!(map),
+                }
+            }
+        }
+    }
+
+    /// Canonical (OpenAI-style) finish reason for an Anthropic `stop_reason`.
+    fn finish_reason(stop_reason: &str) -> String {
+        match stop_reason {
+            "end_turn" | "stop_sequence" => "stop",
+            "tool_use" => "tool_calls",
+            "max_tokens" => "length",
+            "refusal" => "content_filter",
+            other => other,
+        }
+        .to_string()
+    }
+}
+
+impl ProviderDialect for AnthropicDialect {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn encode_request(&self, request: &ChatRequest) -> Result<Value, DialectError> {
+        let (system, rest) =
+            split_system_prompt(&request.messages, SystemPromptPlacement::TopLevel);
+
+        // Consecutive same-role turns are merged: tool results following an
+        // assistant turn arrive as separate canonical messages but must share
+        // one user turn.
+        let mut messages: Vec<Value> = Vec::with_capacity(rest.len());
+        for message in rest {
+            let (role, blocks) = Self::encode_message(message)?;
+            if blocks.is_empty() {
+                continue;
+            }
+            match messages.last_mut() {
+                Some(last) if last["role"] == role => {
+                    if let Some(content) = last["content"].as_array_mut() {
+                        content.extend(blocks);
+                    }
+                }
+                _ => messages.push(json!({"role": role, "content": blocks})),
+            }
+        }
+
+        let mut body = Map::new();
+        insert_common(&mut body, request);
+        if let Some(system) = system {
+            // The prompt is one joined string, so any hint on a system
+            // message caches all of it.
+            let hint = request
+                .messages
+                .iter()
+                .filter(|m| matches!(m.role, Role::System | Role::Developer))
+                .find_map(message_cache_hint);
+            let system = match hint {
+                Some(hint) => json!([{
+                    "type": "text",
+                    "text": system,
+                    "cache_control": Self::cache_control(hint),
+                }]),
+                None => json!(system),
+            };
+            body.insert("system".into(), system);
+        }
+        body.insert("messages".into(), Value::Array(messages));
+        body.insert(
+            "max_tokens".into(),
+            json!(request.max_output_tokens.unwrap_or(self.default_max_tokens)),
+        );
+        if let Some(tools) = request.tools.as_ref().filter(|t| !t.is_empty()) {
+            body.insert(
+                "tools".into(),
+                Value::Array(tools.iter().map(anthropic_tool).collect()),
+            );
+        }
+        if let Some(choice) = &request.tool_choice {
+            body.insert("tool_choice".into(), Self::tool_choice(choice));
+        }
+        if let Some(stop) = &request.stop {
+            body.insert("stop_sequences".into(), json!(stop));
+        }
+        if request.response_format.is_some() {
+            log::debug!("anthropic dialect: dropping response_format");
+        }
+        if request.reasoning_effort.is_some() {
+            log::debug!("anthropic dialect: dropping reasoning_effort");
+        }
+        insert_extensions(&mut body, request);
+        Ok(Value::Object(body))
+    }
+
+    fn decode_response(&self, body: &Value) -> Result<ChatResponse, DialectError> {
+        object(Self::NAME, body, "response")?;
+        let blocks = body
+            .get("content")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Self::malformed("no content array"))?;
+
+        let mut content = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let text = |key: &str| {
+                block
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => content.push(text_block(text("text"))),
+                Some("thinking") => content.push(ContentBlock::Thinking {
+                    thinking: text("thinking"),
+                    signature: block
+                        .get("signature")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                    visibility: None,
+                    content: None,
+                    extensions: HashMap::new(),
+                }),
+                Some("redacted_thinking") => content.push(ContentBlock::RedactedThinking {
+                    data: text("data"),
+                    visibility: None,
+                    extensions: HashMap::new(),
+                }),
+                Some("tool_use") => content.push(ContentBlock::ToolCall {
+                    id: text("id"),
+                    name: text("name"),
+                    input: block.get("input").map(arguments_map).unwrap_or_default(),
+                    visibility: None,
+                    cache: None,
+                    extensions: HashMap::new(),
+                }),
+                other => log::debug!("anthropic dialect: skipping content block {other:?}"),
+            }
+        }
+
+        let usage = body.get("usage").map(|usage| {
+            let input = int(usage, "input_tokens").unwrap_or(0);
+            let output = int(usage, "output_tokens").unwrap_or(0);
+            Usage {
+                input_tokens: input,
+                output_tokens: output,
+                total_tokens: input + output,
+                reasoning_tokens: None,
+                cache_read_tokens: int(usage, "cache_read_input_tokens"),
+                cache_write_tokens: int(usage, "cache_creation_input_tokens"),
+                cost_usd: None,
+                extensions: HashMap::new(),
+            }
+        });
+
+        Ok(ChatResponse {
+            tool_calls: tool_calls_of(&content),
+            content,
+            usage,
+            degradation: None,
+            finish_reason: body
+                .get("stop_reason")
+                .and_then(Value::as_str)
+                .map(Self::finish_reason),
+            metadata: None,
+            extensions: HashMap::new(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: Role, content: MessageContent) -> Message {
+        Message {
+            role,
+            content,
+            name: None,
+            tool_call_id: None,
+            metadata: None,
+            cache: None,
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// A system prompt, a user question, an assistant tool call and its
+    /// result, plus one tool.
+    fn tool_round_trip_request() -> ChatRequest {
+        let mut input = HashMap::new();
+        input.insert("city".to_string(), json!("Paris"));
+        ChatRequest {
+            messages: vec![
+                message(Role::System, MessageContent::Text("Be brief.".into())),
+                message(Role::User, MessageContent::Text("Weather?".into())),
+                message(
+                    Role::Assistant,
+                    MessageContent::Blocks(vec![ContentBlock::ToolCall {
+                        id: "call_1".into(),
+                        name: "weather".into(),
+                        input,
+                        visibility: None,
+                        cache: None,
+                        extensions: HashMap::new(),
+                    }]),
+                ),
+                Message {
+                    tool_call_id: Some("call_1".into()),
+                    ..message(Role::Tool, MessageContent::Text("sunny".into()))
+                },
+            ],
+            tools: Some(vec![ToolSpec {
+                name: "weather".into(),
+                parameters: HashMap::from([("type".to_string(), json!("object"))]),
+                description: Some("Look up weather".into()),
+                extensions: HashMap::new(),
+            }]),
+            response_format: None,
+            temperature: Some(0.2),
+            top_p: None,
+            max_output_tokens: None,
+            conversation_id: None,
+            stream: None,
+            metadata: None,
+            model: Some("m".into()),
+            tool_choice: Some(ToolChoice::String("required".into())),
+            stop: Some(vec!["END".into()]),
+            reasoning_effort: None,
+            timeout: None,
+            extensions: HashMap::from([("seed".to_string(), json!(7))]),
+        }
+    }
+
+    #[test]
+    fn system_prompt_placement() {
+        let messages = tool_round_trip_request().messages;
+        let (prompt, rest) = split_system_prompt(&messages, SystemPromptPlacement::TopLevel);
+        assert_eq!(prompt.as_deref(), Some("Be brief."));
+        assert_eq!(rest.len(), 3);
+        let (prompt, rest) = split_system_prompt(&messages, SystemPromptPlacement::Inline);
+        assert!(prompt.is_none());
+        assert_eq!(rest.len(), 4);
+    }
+
+    #[test]
+    fn openai_encodes_inline_system_and_function_tools() {
+        let body = OpenAiDialect
+            .encode_request(&tool_round_trip_request())
+            .unwrap();
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(
+            messages[0],
+            json!({"role": "system", "content": "Be brief."})
+        );
+        let call = &messages[2]["tool_calls"][0];
+        assert_eq!(call["function"]["name"], "weather");
+        assert_eq!(call["function"]["arguments"], r#"{"city":"Paris"}"#);
+        assert_eq!(
+            messages[3],
+            json!({"role": "tool", "content": "sunny", "tool_call_id": "call_1"})
+        );
+        assert_eq!(body["tools"][0]["type"], "function");
+        assert_eq!(
+            body["tools"][0]["function"]["description"],
+            "Look up weather"
+        );
+        assert_eq!(body["tool_choice"], "required");
+        assert_eq!(body["seed"], 7);
+    }
+
+    #[test]
+    fn anthropic_lifts_system_and_merges_tool_results() {
+        let body = AnthropicDialect::new()
+            .encode_request(&tool_round_trip_request())
+            .unwrap();
+        assert_eq!(body["system"], "Be brief.");
+        assert_eq!(body["max_tokens"], DEFAULT_ANTHROPIC_MAX_TOKENS);
+        assert_eq!(body["stop_sequences"], json!(["END"]));
+        assert_eq!(body["tool_choice"], json!({"type": "any"}));
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["content"][0]["type"], "tool_use");
+        assert_eq!(
+            messages[2],
+            json!({"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "call_1", "content": "sunny"}
+            ]})
+        );
+    }
+
+    #[test]
+    fn cache_hints_become_anthropic_cache_control() {
+        let mut request = tool_round_trip_request();
+        request.messages[0].cache = Some(CacheHint::Ephemeral);
+        request.messages[2].content = MessageContent::Blocks(vec![
+            text_block("Checking."),
+            ContentBlock::ToolCall {
+                id: "call_1".into(),
+                name: "weather".into(),
+                input: HashMap::new(),
+                visibility: None,
+                cache: Some(CacheHint::Ephemeral),
+                extensions: HashMap::new(),
+            },
+        ]);
+        request.messages[3].cache = Some(CacheHint::Ephemeral);
+
+        let body = AnthropicDialect::new().encode_request(&request).unwrap();
+        assert_eq!(
+            body["system"],
+            json!([{"type": "text", "text": "Be brief.", "cache_control": {"type": "ephemeral"}}])
+        );
+        let messages = body["messages"].as_array().unwrap();
+        assert!(messages[0]["content"][0].get("cache_control").is_none());
+        assert!(messages[1]["content"][0].get("cache_control").is_none());
+        assert_eq!(
+            messages[1]["content"][1]["cache_control"],
+            json!({"type": "ephemeral"})
+        );
+        assert_eq!(
+            messages[2]["content"][0]["cache_control"],
+            json!({"type": "ephemeral"})
+        );
+
+        let body = OpenAiDialect.encode_request(&request).unwrap();
+        assert!(!body.to_string().contains("cache"));
+    }
+
+    #[test]
+    fn content_without_a_wire_equivalent_is_rejected() {
+        let mut request = tool_round_trip_request();
+        request.messages.push(message(
+            Role::User,
+            MessageContent::Blocks(vec![ContentBlock::Audio {
+                source: HashMap::new(),
+                transcript: None,
+                visibility: None,
+                cache: None,
+                extensions: HashMap::new(),
+            }]),
+        ));
+        assert!(matches!(
+            AnthropicDialect::new().encode_request(&request),
+            Err(DialectError::Unsupported { .. })
+        ));
+        let error: ProviderError = OpenAiDialect.encode_request(&request).unwrap_err().into();
+        assert_eq!(error.code(), "provider.invalid_request");
+    }
+
+    #[test]
+    fn openai_decodes_text_tool_calls_and_usage() {
+        let response = OpenAiDialect
+            .decode_response(&json!({
+                "choices": [{
+                    "finish_reason": "tool_calls",
+                    "message": {
+                        "role": "assistant",
+                        "content": "Checking.",
+                        "tool_calls": [{
+                            "id": "call_1",
+                            "type": "function",
+                            "function": {"name": "weather", "arguments": "{\"city\":\"Paris\"}"}
+                        }]
+                    }
+                }],
+                "usage": {"prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15}
+            }))
+            .unwrap();
+        assert_eq!(response.content.len(), 2);
+        let calls = response.tool_calls.unwrap();
+        assert_eq!(calls[0].arguments["city"], "Paris");
+        assert_eq!(response.finish_reason.as_deref(), Some("tool_calls"));
+        assert_eq!(response.usage.unwrap().total_tokens, 15);
+
+        assert!(matches!(
+            OpenAiDialect.decode_response(&json!({"choices": []})),
+            Err(DialectError::MalformedResponse { .. })
+        ));
+    }
+
+    #[test]
+    fn anthropic_decodes_blocks_and_maps_stop_reason() {
+        let response = AnthropicDialect::new()
+            .decode_response(&json!({
+                "content": [
+                    {"type": "thinking", "thinking": "hmm", "signature": "sig"},
+                    {"type": "tool_use", "id": "tu_1", "name": "weather", "input": {"city": "Paris"}}
+                ],
+                "stop_reason": "tool_use",
+                "usage": {"input_tokens": 8, "output_tokens": 4, "cache_read_input_tokens": 2}
+            }))
+            .unwrap();
+        assert!(matches!(
+            &response.content[0],
+            ContentBlock::Thinking { signature: Some(s), .. } if s == "sig"
+        ));
+        assert_eq!(response.tool_calls.unwrap()[0].id, "tu_1");
+        assert_eq!(response.finish_reason.as_deref(), Some("tool_calls"));
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.total_tokens, 12);
+        assert_eq!(usage.cache_read_tokens, Some(2));
+    }
+
+    fn spec(name: &str, description: Option<&str>) -> ToolSpec {
+        ToolSpec {
+            name: name.into(),
+            parameters: HashMap::from([("type".to_string(), json!("object"))]),
+            description: description.map(Into::into),
+            extensions: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn to_dialect_sanitizes_names_and_truncates_descriptions() {
+        let long = "x".repeat(2000);
+        let openai = spec("fs.read file", Some(&long)).to_dialect(Dialect::OpenAi);
+        assert_eq!(openai["type"], "function");
+        assert_eq!(openai["function"]["name"], "fs_read_file");
+        let description = openai["function"]["description"].as_str().unwrap();
+        assert_eq!(
+            description.chars().count(),
+            MAX_OPENAI_TOOL_DESCRIPTION_CHARS
+        );
+        assert!(description.ends_with("..."));
+
+        let anthropic = spec(&"n".repeat(80), Some(&long)).to_dialect(Dialect::Anthropic);
+        assert_eq!(
+            anthropic["name"].as_str().unwrap().len(),
+            MAX_TOOL_NAME_CHARS
+        );
+        assert_eq!(anthropic["description"].as_str().unwrap().len(), 2000);
+        assert_eq!(anthropic["input_schema"]["type"], "object");
+    }
+
+    #[test]
+    fn dialect_tools_dedupe_names_and_restore_tool_calls() {
+        let specs = [
+            spec("read.file", None),
+            spec("read file", None),
+            spec("", None),
+        ];
+        let tools = DialectTools::new(Dialect::Anthropic, &specs);
+        let names: Vec<_> = tools
+            .definitions
+            .iter()
+            .map(|d| d["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["read_file", "read_file_2", "tool"]);
+
+        let mut calls = vec![ToolCall {
+            id: "c".into(),
+            name: "read_file_2".into(),
+            arguments: HashMap::new(),
+            extensions: HashMap::new(),
+        }];
+        tools.restore_names(&mut calls);
+        assert_eq!(calls[0].name, "read file");
+        assert_eq!(tools.original_name("unknown"), "unknown");
+    }
+
+    #[test]
+    fn dialect_tools_from_mounted_tools_are_ordered_by_name() {
+        use crate::testing::FakeTool;
+
+        let mut mounted: HashMap<String, Arc<dyn Tool>> = HashMap::new();
+        mounted.insert("zeta".into(), Arc::new(FakeTool::new("zeta", "last")));
+        mounted.insert("alpha".into(), Arc::new(FakeTool::new("alpha", "first")));
+        let tools = DialectTools::from_tools(Dialect::OpenAi, &mounted);
+        let names: Vec<_> = tools
+            .definitions
+            .iter()
+            .map(|d| d["function"]["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["alpha", "zeta"]);
+    }
+}
@@ -0,0 +1,201 @@
+//! Display providers for native hosts.
+//!
+//! Python hosts hand the session an opaque `display_system`; Rust hosts
+//! mount a [`DisplayProvider`] with
+//! [`Coordinator::set_display_provider`]. This module has the stock
+//! implementations:
+//!
+//! | Type               | Does                                                        |
+//! |--------------------|-------------------------------------------------------------|
+//! | [`NoopDisplay`]    | nothing (headless hosts, tests)                             |
+//! | [`ChannelDisplay`] | sends each call as a [`DisplayEvent`] to a receiver the host drains |
+//! | [`ServiceDisplay`] | forwards messages to a [`DisplayService`], drops the rest   |
+//!
+//! A host with its own event loop (a TUI, an IDE extension) typically uses
+//! [`ChannelDisplay`] and renders events as they arrive:
+//!
+//! ```rust,no_run
+//! # async fn example() {
+//! use std::sync::Arc;
+//! use amplifier_core::coordinator::Coordinator;
+//! use amplifier_core::display::{ChannelDisplay, DisplayEvent};
+//!
+//! let coordinator = Coordinator::new_for_test();
+//! let (display, mut events) = ChannelDisplay::new();
+//! coordinator.set_display_provider(Arc::new(display));
+//! while let Some(event) = events.recv().await {
+//!     if let DisplayEvent::ApprovalPrompt(request) = event {
+//!         println!("approve {}?", request.action);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! [`Coordinator::set_display_provider`]: crate::coordinator::Coordinator::set_display_provider
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::errors::AmplifierError;
+use crate::models::{ApprovalRequest, ToolProgress};
+use crate::traits::{DisplayProvider, DisplayService};
+
+// ---------------------------------------------------------------------------
+// NoopDisplay
+// ---------------------------------------------------------------------------
+
+/// A display provider that shows nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopDisplay;
+
+impl DisplayProvider for NoopDisplay {}
+
+// ---------------------------------------------------------------------------
+// ChannelDisplay
+// ---------------------------------------------------------------------------
+
+/// One [`DisplayProvider`] call, as sent by [`ChannelDisplay`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DisplayEvent {
+    Message {
+        message: String,
+        level: String,
+        source: String,
+    },
+    Progress {
+        source: String,
+        progress: ToolProgress,
+    },
+    ApprovalPrompt(ApprovalRequest),
+}
+
+/// A display provider that forwards every call to a channel.
+///
+/// Sending never blocks. Once the receiver is dropped, calls are discarded.
+#[derive(Debug, Clone)]
+pub struct ChannelDisplay {
+    tx: mpsc::UnboundedSender<DisplayEvent>,
+}
+
+impl ChannelDisplay {
+    /// A provider and the receiver its events arrive on.
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<DisplayEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx }, rx)
+    }
+
+    fn send(
+        &self,
+        event: DisplayEvent,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        if self.tx.send(event).is_err() {
+            log::debug!("Display channel closed; dropping display event");
+        }
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl DisplayProvider for ChannelDisplay {
+    fn show_message(
+        &self,
+        message: &str,
+        level: &str,
+        source: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        self.send(DisplayEvent::Message {
+            message: message.to_string(),
+            level: level.to_string(),
+            source: source.to_string(),
+        })
+    }
+
+    fn show_progress(
+        &self,
+        source: &str,
+        progress: &ToolProgress,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        self.send(DisplayEvent::Progress {
+            source: source.to_string(),
+            progress: progress.clone(),
+        })
+    }
+
+    fn show_approval_prompt(
+        &self,
+        request: &ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        self.send(DisplayEvent::ApprovalPrompt(request.clone()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ServiceDisplay
+// ---------------------------------------------------------------------------
+
+/// A [`DisplayService`] seen as a [`DisplayProvider`].
+///
+/// [`Coordinator::display_provider`](crate::coordinator::Coordinator::display_provider)
+/// falls back to this when only a display service (e.g. a Python
+/// `display_system`) is mounted.
+pub struct ServiceDisplay(pub Arc<dyn DisplayService>);
+
+impl DisplayProvider for ServiceDisplay {
+    fn show_message(
+        &self,
+        message: &str,
+        level: &str,
+        source: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        self.0.show_message(message, level, source)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn channel_display_forwards_every_call() {
+        let (display, mut events) = ChannelDisplay::new();
+        let request = ApprovalRequest {
+            tool_name: "bash".into(),
+            action: "rm -rf build".into(),
+            details: Default::default(),
+            risk_level: "high".into(),
+            timeout: None,
+        };
+        display.show_message("hi", "info", "kernel").await.unwrap();
+        display
+            .show_progress("scan", &ToolProgress::message("1/3"))
+            .await
+            .unwrap();
+        display.show_approval_prompt(&request).await.unwrap();
+
+        assert!(matches!(
+            events.recv().await,
+            Some(DisplayEvent::Message { ref message, .. }) if message == "hi"
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Some(DisplayEvent::Progress { ref source, .. }) if source == "scan"
+        ));
+        assert_eq!(
+            events.recv().await,
+            Some(DisplayEvent::ApprovalPrompt(request.clone()))
+        );
+
+        // A closed channel does not fail the caller.
+        drop(events);
+        display.show_approval_prompt(&request).await.unwrap();
+        NoopDisplay.show_approval_prompt(&request).await.unwrap();
+    }
+}
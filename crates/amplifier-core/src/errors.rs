@@ -7,11 +7,51 @@
 //! - [`SessionError`] — session lifecycle errors
 //! - [`HookError`] — hook dispatch errors
 //! - [`ToolError`] — tool execution errors
+//! - [`CoordinatorError`] — coordinator mount-point errors
+//! - [`ErrorReport`] — flattened, serializable snapshot of any of the above
 //!
 //! All types derive `Serialize` so errors can cross the JSON boundary
 //! to the PyO3 bridge.
-
-use serde::Serialize;
+//!
+//! # Error codes
+//!
+//! Every variant has a stable, machine-readable [`code()`](AmplifierError::code)
+//! of the form `<component>.<variant>` (`provider.rate_limit`,
+//! `tool.not_found`, ...). Codes never change once released; match on them
+//! instead of on messages.
+//!
+//! # Context and causes
+//!
+//! [`AmplifierError::context`] attaches key/value detail and
+//! [`AmplifierError::with_source`] attaches an underlying cause, exposed
+//! through [`std::error::Error::source`]. Both wrap the error in
+//! [`AmplifierError::Contextual`]; [`AmplifierError::root`] unwraps it for
+//! matching. [`AmplifierError::report`] flattens everything into an
+//! [`ErrorReport`] for bindings and logs.
+
+use std::collections::BTreeMap;
+use std::error::Error as StdError;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// An underlying cause attached with [`AmplifierError::with_source`].
+pub type ErrorCause = Arc<dyn StdError + Send + Sync>;
+
+/// Variant tag and fields of a serialized leaf error.
+fn variant_fields<T: Serialize>(error: &T) -> Value {
+    match serde_json::to_value(error) {
+        // Struct variants serialize as {"Variant": {fields}}.
+        Ok(Value::Object(map)) if map.len() == 1 => map
+            .into_iter()
+            .next()
+            .map(|(_, v)| v)
+            .unwrap_or(Value::Null),
+        // Unit variants serialize as "Variant".
+        _ => Value::Null,
+    }
+}
 
 // -- ProviderError --
 
@@ -115,6 +155,20 @@ pub enum ProviderError {
 }
 
 impl ProviderError {
+    /// Stable machine-readable code, e.g. `"provider.rate_limit"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::RateLimit { .. } => "provider.rate_limit",
+            Self::Authentication { .. } => "provider.authentication",
+            Self::ContextLength { .. } => "provider.context_length",
+            Self::ContentFilter { .. } => "provider.content_filter",
+            Self::InvalidRequest { .. } => "provider.invalid_request",
+            Self::Unavailable { .. } => "provider.unavailable",
+            Self::Timeout { .. } => "provider.timeout",
+            Self::Other { .. } => "provider.other",
+        }
+    }
+
     /// Whether the caller should consider retrying the request.
     ///
     /// Matches Python defaults: `RateLimit`, `Unavailable`, and `Timeout`
@@ -190,15 +244,101 @@ pub enum SessionError {
     #[error("missing required config: {field}")]
     ConfigMissing { field: String },
 
+    /// `${...}` references in the config could not be resolved (see
+    /// [`crate::interpolation`]).
+    #[error("unresolved config references: {}", join_unresolved(unresolved))]
+    ConfigInterpolation {
+        unresolved: Vec<crate::interpolation::UnresolvedReference>,
+    },
+
+    /// The mount plan could not be migrated to the current format (see
+    /// [`crate::config_migration`]).
+    #[error("config migration failed: {0}")]
+    ConfigMigration(#[from] crate::config_migration::ConfigMigrationError),
+
     /// Session has already completed.
     #[error("session already completed")]
     AlreadyCompleted,
 
+    /// The turn did not finish before its deadline.
+    #[error("turn deadline of {timeout_ms} ms exceeded")]
+    DeadlineExceeded { timeout_ms: u64 },
+
+    /// A session quota (see [`crate::quota`]) was exhausted.
+    #[error("session quota {resource} exceeded: {used} of {limit}")]
+    QuotaExceeded {
+        resource: String,
+        limit: u64,
+        used: u64,
+    },
+
+    /// A per-turn safeguard (see [`crate::safety`]) was hit.
+    #[error("safety limit {limit} exceeded: {observed} of {max}")]
+    SafetyLimitExceeded {
+        limit: String,
+        max: u64,
+        observed: u64,
+    },
+
+    /// No checkpoint with this ID exists in the session.
+    #[error("checkpoint not found: {checkpoint}")]
+    CheckpointNotFound { checkpoint: String },
+
+    /// A `prompt:submit` hook rejected the prompt.
+    #[error("prompt denied by hook: {reason}")]
+    PromptDenied { reason: String },
+
+    /// A `session.hooks.subscriptions` entry names a handler the host did
+    /// not provide.
+    #[error("no hook handler named {handler} (subscribed to {event})")]
+    HookHandlerNotFound { handler: String, event: String },
+
+    /// The execution was aborted before it finished (see
+    /// [`ExecutionHandle::abort`](crate::session::ExecutionHandle::abort)).
+    #[error("execution aborted")]
+    Aborted,
+
+    /// `execute()` was called while another execution was in flight and
+    /// the session rejects concurrent prompts (see
+    /// [`ReentrancyPolicy`](crate::session::ReentrancyPolicy)).
+    #[error("session is busy executing another prompt")]
+    Busy,
+
     /// Catch-all for other session errors.
     #[error("{message}")]
     Other { message: String },
 }
 
+impl SessionError {
+    /// Stable machine-readable code, e.g. `"session.not_initialized"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotInitialized => "session.not_initialized",
+            Self::ConfigMissing { .. } => "session.config_missing",
+            Self::ConfigInterpolation { .. } => "session.config_interpolation",
+            Self::ConfigMigration(_) => "session.config_migration",
+            Self::AlreadyCompleted => "session.already_completed",
+            Self::DeadlineExceeded { .. } => "session.deadline_exceeded",
+            Self::QuotaExceeded { .. } => "session.quota_exceeded",
+            Self::SafetyLimitExceeded { .. } => "session.safety_limit_exceeded",
+            Self::CheckpointNotFound { .. } => "session.checkpoint_not_found",
+            Self::PromptDenied { .. } => "session.prompt_denied",
+            Self::HookHandlerNotFound { .. } => "session.hook_handler_not_found",
+            Self::Aborted => "session.aborted",
+            Self::Busy => "session.busy",
+            Self::Other { .. } => "session.other",
+        }
+    }
+}
+
+fn join_unresolved(unresolved: &[crate::interpolation::UnresolvedReference]) -> String {
+    unresolved
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 // -- HookError --
 
 /// Hook dispatch errors.
@@ -220,6 +360,17 @@ pub enum HookError {
     Other { message: String },
 }
 
+impl HookError {
+    /// Stable machine-readable code, e.g. `"hook.handler_failed"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::HandlerFailed { .. } => "hook.handler_failed",
+            Self::Timeout => "hook.timeout",
+            Self::Other { .. } => "hook.other",
+        }
+    }
+}
+
 // -- ToolError --
 
 /// Tool execution errors.
@@ -238,11 +389,50 @@ pub enum ToolError {
     #[error("tool not found: {name}")]
     NotFound { name: String },
 
+    /// The tool was stopped at its own timeout or the turn deadline.
+    #[error("tool {name} timed out after {timeout_ms} ms")]
+    Timeout {
+        name: String,
+        timeout_ms: u64,
+        /// The last partial output the tool reported, if it reports any.
+        partial_output: Option<Value>,
+    },
+
+    /// The tool was stopped by immediate cancellation.
+    #[error("tool {name} was cancelled")]
+    Cancelled {
+        name: String,
+        /// The last partial output the tool reported, if it reports any.
+        partial_output: Option<Value>,
+    },
+
+    /// The tool's output does not match the negotiated output format.
+    #[error("tool {name} returned invalid {format} output: {message}")]
+    InvalidOutput {
+        name: String,
+        format: String,
+        message: String,
+    },
+
     /// Catch-all for other tool errors.
     #[error("{message}")]
     Other { message: String },
 }
 
+impl ToolError {
+    /// Stable machine-readable code, e.g. `"tool.execution_failed"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ExecutionFailed { .. } => "tool.execution_failed",
+            Self::NotFound { .. } => "tool.not_found",
+            Self::Timeout { .. } => "tool.timeout",
+            Self::Cancelled { .. } => "tool.cancelled",
+            Self::InvalidOutput { .. } => "tool.invalid_output",
+            Self::Other { .. } => "tool.other",
+        }
+    }
+}
+
 // -- ContextError --
 
 /// Context management errors.
@@ -252,11 +442,99 @@ pub enum ContextError {
     #[error("context compaction failed: {message}")]
     CompactionFailed { message: String },
 
+    /// Reading or writing the backing conversation store failed.
+    #[error("context storage failed: {message}")]
+    Storage { message: String },
+
+    /// A message index is past the end of the history.
+    #[error("no message at index {index} (history has {len})")]
+    MessageNotFound { index: usize, len: usize },
+
     /// Catch-all for other context errors.
     #[error("{message}")]
     Other { message: String },
 }
 
+impl ContextError {
+    /// Stable machine-readable code, e.g. `"context.compaction_failed"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::CompactionFailed { .. } => "context.compaction_failed",
+            Self::Storage { .. } => "context.storage",
+            Self::MessageNotFound { .. } => "context.message_not_found",
+            Self::Other { .. } => "context.other",
+        }
+    }
+}
+
+// -- CoordinatorError --
+
+/// Coordinator mount-point errors.
+///
+/// Shared by every binding so that a typo in a mount-point name fails with
+/// the same structured error regardless of host language.
+#[derive(Debug, thiserror::Error, Serialize)]
+pub enum CoordinatorError {
+    /// The mount-point name is not one of the canonical names.
+    #[error("Unknown mount point: {name} (expected one of: {})", .expected.join(", "))]
+    UnknownMountPoint { name: String, expected: Vec<String> },
+
+    /// A multi-slot mount point was used without a module name.
+    #[error("Name required for {mount_point}")]
+    NameRequired { mount_point: String },
+
+    /// The mount point exists but cannot be mounted into directly.
+    #[error("{mount_point} cannot be mounted directly: {reason}")]
+    NotMountable { mount_point: String, reason: String },
+
+    /// A module's [`ModuleLifecycle::init`](crate::traits::ModuleLifecycle::init)
+    /// failed, so it was not mounted.
+    #[error("{mount_point} module {name} failed to initialize: {message}")]
+    ModuleInitFailed {
+        mount_point: String,
+        name: String,
+        message: String,
+    },
+
+    /// Mounted modules declared capabilities that are not registered.
+    /// `missing` maps each module to its unregistered capabilities.
+    #[error("Missing required capabilities: {}", describe_missing(.missing))]
+    MissingCapabilities {
+        missing: BTreeMap<String, Vec<String>>,
+    },
+
+    /// A contribution channel was declared again with a different type.
+    #[error("Channel {channel} is declared as {declared}, not {requested}")]
+    ChannelTypeConflict {
+        channel: String,
+        declared: String,
+        requested: String,
+    },
+}
+
+/// `module needs a, b; other needs c`.
+fn describe_missing(missing: &BTreeMap<String, Vec<String>>) -> String {
+    missing
+        .iter()
+        .map(|(module, capabilities)| format!("{module} needs {}", capabilities.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl CoordinatorError {
+    /// Stable machine-readable code, e.g. `"coordinator.unknown_mount_point"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnknownMountPoint { .. } => "coordinator.unknown_mount_point",
+            Self::NameRequired { .. } => "coordinator.name_required",
+            Self::NotMountable { .. } => "coordinator.not_mountable",
+            Self::ModuleInitFailed { .. } => "coordinator.module_init_failed",
+            Self::MissingCapabilities { .. } => "coordinator.missing_capabilities",
+            Self::ChannelTypeConflict { .. } => "coordinator.channel_type_conflict",
+        }
+    }
+}
+
 // -- AmplifierError --
 
 /// Top-level error enum wrapping all component errors.
@@ -281,6 +559,176 @@ pub enum AmplifierError {
     /// A context management error.
     #[error(transparent)]
     Context(#[from] ContextError),
+
+    /// A coordinator mount-point error.
+    #[error(transparent)]
+    Coordinator(#[from] CoordinatorError),
+
+    /// Another error annotated with context and/or an underlying cause.
+    ///
+    /// Built by [`context()`](Self::context) and
+    /// [`with_source()`](Self::with_source); displays as the wrapped error.
+    #[error("{error}")]
+    Contextual {
+        error: Box<AmplifierError>,
+        context: BTreeMap<String, Value>,
+        #[source]
+        #[serde(skip)]
+        cause: Option<ErrorCause>,
+    },
+}
+
+impl AmplifierError {
+    /// Stable machine-readable code of the underlying error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Provider(e) => e.code(),
+            Self::Session(e) => e.code(),
+            Self::Hook(e) => e.code(),
+            Self::Tool(e) => e.code(),
+            Self::Context(e) => e.code(),
+            Self::Coordinator(e) => e.code(),
+            Self::Contextual { error, .. } => error.code(),
+        }
+    }
+
+    /// The error without any [`Contextual`](Self::Contextual) wrapping.
+    pub fn root(&self) -> &AmplifierError {
+        match self {
+            Self::Contextual { error, .. } => error.root(),
+            other => other,
+        }
+    }
+
+    /// Attach `key = value` detail. A later value for the same key wins.
+    pub fn context(self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        let (error, mut context, cause) = self.into_parts();
+        context.insert(key.into(), value.into());
+        Self::Contextual {
+            error,
+            context,
+            cause,
+        }
+    }
+
+    /// Attach the lower-level error that caused this one, replacing any
+    /// previous cause. It is returned by [`source()`](StdError::source).
+    pub fn with_source(self, cause: impl StdError + Send + Sync + 'static) -> Self {
+        let (error, context, _) = self.into_parts();
+        Self::Contextual {
+            error,
+            context,
+            cause: Some(Arc::new(cause)),
+        }
+    }
+
+    /// Context attached with [`context()`](Self::context) (empty if none).
+    pub fn context_map(&self) -> BTreeMap<String, Value> {
+        match self {
+            Self::Contextual { context, .. } => context.clone(),
+            _ => BTreeMap::new(),
+        }
+    }
+
+    /// Whether the underlying error is retryable (only provider errors can be).
+    pub fn retryable(&self) -> bool {
+        matches!(self.root(), Self::Provider(e) if e.retryable())
+    }
+
+    /// Flatten into a serializable [`ErrorReport`].
+    pub fn report(&self) -> ErrorReport {
+        let detail = match self.root() {
+            Self::Provider(e) => variant_fields(e),
+            Self::Session(e) => variant_fields(e),
+            Self::Hook(e) => variant_fields(e),
+            Self::Tool(e) => variant_fields(e),
+            Self::Context(e) => variant_fields(e),
+            Self::Coordinator(e) => variant_fields(e),
+            Self::Contextual { .. } => unreachable!("root() unwraps Contextual"),
+        };
+        let mut causes = Vec::new();
+        let mut source = self.source();
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+        ErrorReport {
+            code: self.code().to_string(),
+            message: self.to_string(),
+            retryable: self.retryable(),
+            detail,
+            context: self.context_map(),
+            causes,
+        }
+    }
+
+    fn into_parts(
+        self,
+    ) -> (
+        Box<AmplifierError>,
+        BTreeMap<String, Value>,
+        Option<ErrorCause>,
+    ) {
+        match self {
+            Self::Contextual {
+                error,
+                context,
+                cause,
+            } => (error, context, cause),
+            other => (Box::new(other), BTreeMap::new(), None),
+        }
+    }
+}
+
+// -- ErrorReport --
+
+/// Language-neutral snapshot of an [`AmplifierError`].
+///
+/// This is the shape bindings and structured logs carry across the FFI
+/// boundary: the stable `code`, the display `message`, the variant's fields
+/// in `detail`, attached `context`, and the `causes` chain outermost first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub retryable: bool,
+    /// Fields of the error variant (`null` for unit variants).
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub detail: Value,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, Value>,
+    /// Display strings of each `source()` in the chain.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+}
+
+impl From<&AmplifierError> for ErrorReport {
+    fn from(error: &AmplifierError) -> Self {
+        error.report()
+    }
+}
+
+/// Adds [`context()`](ResultExt::context) to results whose error converts
+/// into [`AmplifierError`].
+///
+/// ```rust
+/// use amplifier_core::errors::{ResultExt, ToolError};
+///
+/// let result: Result<(), ToolError> = Err(ToolError::NotFound { name: "grep".into() });
+/// let err = result.context("session_id", "s-1").unwrap_err();
+/// assert_eq!(err.code(), "tool.not_found");
+/// assert_eq!(err.context_map()["session_id"], "s-1");
+/// ```
+pub trait ResultExt<T> {
+    /// Convert the error and attach `key = value` detail.
+    fn context(self, key: impl Into<String>, value: impl Into<Value>) -> Result<T, AmplifierError>;
+}
+
+impl<T, E: Into<AmplifierError>> ResultExt<T> for Result<T, E> {
+    fn context(self, key: impl Into<String>, value: impl Into<Value>) -> Result<T, AmplifierError> {
+        self.map_err(|e| e.into().context(key, value))
+    }
 }
 
 #[cfg(test)]
@@ -481,4 +929,93 @@ mod tests {
         };
         assert_eq!(err.delay_multiplier(), Some(2.0));
     }
+
+    // -- codes, context, and reports --
+
+    #[test]
+    fn codes_are_stable_and_delegate_through_wrappers() {
+        let err = AmplifierError::from(SessionError::DeadlineExceeded { timeout_ms: 5 });
+        assert_eq!(err.code(), "session.deadline_exceeded");
+        assert_eq!(HookError::Timeout.code(), "hook.timeout");
+        let wrapped = err.context("turn", 3);
+        assert_eq!(wrapped.code(), "session.deadline_exceeded");
+        assert!(matches!(
+            wrapped.root(),
+            AmplifierError::Session(SessionError::DeadlineExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn context_accumulates_and_display_is_unchanged() {
+        let err = AmplifierError::from(ToolError::NotFound {
+            name: "grep".into(),
+        })
+        .context("session_id", "s-1")
+        .context("attempt", 1)
+        .context("attempt", 2);
+        assert_eq!(err.to_string(), "tool not found: grep");
+        let context = err.context_map();
+        assert_eq!(context.len(), 2);
+        assert_eq!(context["attempt"], 2);
+        assert!(matches!(err, AmplifierError::Contextual { ref error, .. }
+            if matches!(**error, AmplifierError::Tool(_))));
+    }
+
+    #[test]
+    fn with_source_exposes_cause_chain() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "transcript.jsonl missing");
+        let err = AmplifierError::from(ContextError::Storage {
+            message: "load failed".into(),
+        })
+        .with_source(io)
+        .context("path", "/tmp/t");
+
+        let source = err.source().expect("cause");
+        assert_eq!(source.to_string(), "transcript.jsonl missing");
+        assert_eq!(err.context_map()["path"], "/tmp/t");
+    }
+
+    #[test]
+    fn report_round_trips_through_json() {
+        let err = AmplifierError::from(ProviderError::RateLimit {
+            message: "429".into(),
+            provider: Some("openai".into()),
+            model: None,
+            retry_after: Some(2.0),
+            delay_multiplier: None,
+        })
+        .with_source(std::io::Error::other("connection reset"))
+        .context("provider_call", 4);
+
+        let report = err.report();
+        assert_eq!(report.code, "provider.rate_limit");
+        assert_eq!(report.message, "429");
+        assert!(report.retryable);
+        assert_eq!(report.detail["retry_after"], 2.0);
+        assert_eq!(report.causes, vec!["connection reset"]);
+
+        let json = serde_json::to_string(&report).unwrap();
+        let back: ErrorReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, report);
+    }
+
+    #[test]
+    fn report_of_unit_variant_omits_detail() {
+        let report = AmplifierError::from(SessionError::NotInitialized).report();
+        assert_eq!(report.code, "session.not_initialized");
+        assert!(report.detail.is_null());
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json.get("detail").is_none());
+        assert!(json.get("context").is_none());
+    }
+
+    #[test]
+    fn result_ext_converts_and_annotates() {
+        let result: Result<(), HookError> = Err(HookError::Other {
+            message: "boom".into(),
+        });
+        let err = result.context("event", "tool:pre").unwrap_err();
+        assert_eq!(err.code(), "hook.other");
+        assert_eq!(err.report().context["event"], "tool:pre");
+    }
 }
@@ -0,0 +1,313 @@
+//! Emit-time event filtering and sampling.
+//!
+//! An [`EventFilter`] installed with
+//! [`HookRegistry::set_event_filter`](crate::hooks::HookRegistry::set_event_filter)
+//! decides, per [`emit()`](crate::hooks::HookRegistry::emit), whether an event
+//! is dispatched at all. Hosts use it to switch off expensive high-frequency
+//! events in production without unregistering the handlers that consume them,
+//! and can swap or clear the filter at runtime.
+//!
+//! An event passes when, in order:
+//!
+//! 1. it matches an `enabled` pattern (which overrides the next two rules), or
+//! 2. it matches no `disabled` pattern and
+//! 3. its [`EventLevel`] is at least `min_level`;
+//! 4. and then it survives sampling: the most specific `sample_rates` pattern
+//!    keeps that fraction of matching events, deterministically (the first
+//!    event is kept, then every `1 / rate`-th).
+//!
+//! Patterns are exact event names, a namespace wildcard (`"content_block:*"`)
+//! or `"*"` for every event.
+//!
+//! Filtered events skip replay recording and every handler except those in
+//! [`HookPhase::PreValidation`](crate::hooks::HookPhase::PreValidation), so
+//! kernel enforcement such as quotas still applies. Filtering a gating event
+//! (`tool:pre`) therefore disables its policy-phase handlers; filter those with
+//! care.
+//!
+//! # Configuration
+//!
+//! Read from `session.hooks.filter`:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "hooks": {
+//!       "filter": {
+//!         "min_level": "info",
+//!         "disabled": ["thinking:*"],
+//!         "enabled": ["llm:response"],
+//!         "sample_rates": {"content_block:delta": 0.1},
+//!         "levels": {"my_module:trace": "debug"}
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::events;
+
+// ---------------------------------------------------------------------------
+// EventLevel
+// ---------------------------------------------------------------------------
+
+/// The verbosity of an event, for `min_level` filtering.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum EventLevel {
+    /// High-frequency or payload-heavy events (streaming deltas, raw LLM
+    /// traffic, tool progress).
+    #[default]
+    Debug,
+    /// Ordinary lifecycle events. Unknown events default to this level.
+    Info,
+    /// Errors, violations and resource warnings.
+    Warn,
+}
+
+impl EventLevel {
+    /// The kernel's level for `event`.
+    pub fn of(event: &str) -> Self {
+        match event {
+            events::CONTENT_BLOCK_START
+            | events::CONTENT_BLOCK_DELTA
+            | events::CONTENT_BLOCK_END
+            | events::CONTENT_START
+            | events::CONTENT_DELTA
+            | events::CONTENT_STOP
+            | events::THINKING_DELTA
+            | events::TOOL_PROGRESS
+            | events::LLM_REQUEST
+            | events::LLM_RESPONSE => EventLevel::Debug,
+            events::PROVIDER_ERROR
+            | events::TOOL_ERROR
+            | events::POLICY_VIOLATION
+            | events::CANCEL_ESCALATED
+            | events::MODULE_ON_SESSION_READY_FAILED
+            | events::KERNEL_MEMORY_PRESSURE
+            | events::KERNEL_MEMORY_EVICTED
+            | events::QUOTA_WARNING
+            | events::SAFETY_LIMIT
+            | events::HOOK_SLOW_HANDLER
+            | events::CONTEXT_INJECTION_DROPPED => EventLevel::Warn,
+            _ => EventLevel::Info,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// EventFilterConfig
+// ---------------------------------------------------------------------------
+
+/// Filter settings. The default passes every event.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFilterConfig {
+    /// Events below this level are dropped.
+    pub min_level: EventLevel,
+    /// Patterns of events to drop.
+    pub disabled: Vec<String>,
+    /// Patterns of events to keep regardless of `disabled` and `min_level`.
+    pub enabled: Vec<String>,
+    /// Fraction (0.0–1.0) of matching events to keep, by pattern.
+    pub sample_rates: BTreeMap<String, f64>,
+    /// Level overrides, by exact event name (for module-defined events).
+    pub levels: HashMap<String, EventLevel>,
+}
+
+impl EventFilterConfig {
+    /// Read `session.hooks.filter`, if present. Malformed config is logged and
+    /// ignored.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config
+            .get("session")
+            .and_then(|s| s.get("hooks"))
+            .and_then(|h| h.get("filter"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.hooks.filter config: {e}"))
+            .ok()
+    }
+
+    /// The level `event` is filtered at.
+    pub fn level(&self, event: &str) -> EventLevel {
+        self.levels
+            .get(event)
+            .copied()
+            .unwrap_or_else(|| EventLevel::of(event))
+    }
+}
+
+/// Whether `pattern` (exact, `"ns:*"` or `"*"`) matches `event`.
+fn matches(pattern: &str, event: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => event.starts_with(prefix),
+        None => pattern == event,
+    }
+}
+
+/// How specific `pattern` is: exact names beat wildcards, longer prefixes
+/// beat shorter ones.
+fn specificity(pattern: &str) -> (bool, usize) {
+    (!pattern.ends_with('*'), pattern.len())
+}
+
+// ---------------------------------------------------------------------------
+// EventFilter
+// ---------------------------------------------------------------------------
+
+/// An [`EventFilterConfig`] plus its sampling state.
+#[derive(Debug)]
+pub struct EventFilter {
+    config: EventFilterConfig,
+    /// Events seen per sampling pattern.
+    sampled: HashMap<String, AtomicU64>,
+    suppressed: AtomicU64,
+}
+
+impl EventFilter {
+    pub fn new(config: EventFilterConfig) -> Self {
+        let sampled = config
+            .sample_rates
+            .keys()
+            .map(|pattern| (pattern.clone(), AtomicU64::new(0)))
+            .collect();
+        Self {
+            config,
+            sampled,
+            suppressed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn config(&self) -> &EventFilterConfig {
+        &self.config
+    }
+
+    /// Decide whether `event` is dispatched. Advances sampling state, so call
+    /// it once per emit.
+    pub fn allows(&self, event: &str) -> bool {
+        let allowed = self.passes_rules(event) && self.sample(event);
+        if !allowed {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    /// Number of events this filter has dropped.
+    pub fn suppressed_count(&self) -> u64 {
+        self.suppressed.load(Ordering::Relaxed)
+    }
+
+    fn passes_rules(&self, event: &str) -> bool {
+        let config = &self.config;
+        if config.enabled.iter().any(|p| matches(p, event)) {
+            return true;
+        }
+        if config.disabled.iter().any(|p| matches(p, event)) {
+            return false;
+        }
+        config.level(event) >= config.min_level
+    }
+
+    fn sample(&self, event: &str) -> bool {
+        let Some((pattern, rate)) = self
+            .config
+            .sample_rates
+            .iter()
+            .filter(|(pattern, _)| matches(pattern, event))
+            .max_by_key(|(pattern, _)| specificity(pattern))
+        else {
+            return true;
+        };
+        let rate = rate.clamp(0.0, 1.0);
+        if rate >= 1.0 {
+            return true;
+        }
+        if rate <= 0.0 {
+            return false;
+        }
+        let seen = self.sampled[pattern].fetch_add(1, Ordering::Relaxed) as f64;
+        ((seen + 1.0) * rate).ceil() > (seen * rate).ceil()
+    }
+}
+
+impl From<EventFilterConfig> for EventFilter {
+    fn from(config: EventFilterConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_level_drops_debug_events_unless_enabled() {
+        let filter = EventFilter::new(EventFilterConfig {
+            min_level: EventLevel::Info,
+            enabled: vec![events::LLM_RESPONSE.into()],
+            ..Default::default()
+        });
+        assert!(!filter.allows(events::CONTENT_BLOCK_DELTA));
+        assert!(filter.allows(events::LLM_RESPONSE));
+        assert!(filter.allows(events::TOOL_PRE));
+        assert!(filter.allows("my_module:custom"));
+        assert_eq!(filter.suppressed_count(), 1);
+    }
+
+    #[test]
+    fn disabled_patterns_match_namespaces() {
+        let filter = EventFilter::new(EventFilterConfig {
+            disabled: vec!["thinking:*".into()],
+            ..Default::default()
+        });
+        assert!(!filter.allows(events::THINKING_DELTA));
+        assert!(!filter.allows(events::THINKING_FINAL));
+        assert!(filter.allows(events::TOOL_POST));
+    }
+
+    #[test]
+    fn sampling_keeps_the_configured_fraction() {
+        let filter = EventFilter::new(EventFilterConfig {
+            sample_rates: BTreeMap::from([
+                ("content_block:*".to_string(), 0.0),
+                (events::CONTENT_BLOCK_DELTA.to_string(), 0.25),
+            ]),
+            ..Default::default()
+        });
+        let kept = (0..100)
+            .filter(|_| filter.allows(events::CONTENT_BLOCK_DELTA))
+            .count();
+        assert_eq!(kept, 25);
+        assert!(!filter.allows(events::CONTENT_BLOCK_START));
+    }
+
+    #[test]
+    fn session_config_and_level_overrides() {
+        let config = HashMap::from([(
+            "session".to_string(),
+            serde_json::json!({"hooks": {"filter": {
+                "min_level": "warn",
+                "levels": {"my_module:alarm": "warn"}
+            }}}),
+        )]);
+        let parsed = EventFilterConfig::from_session_config(&config).unwrap();
+        assert_eq!(parsed.min_level, EventLevel::Warn);
+        let filter = EventFilter::new(parsed);
+        assert!(filter.allows("my_module:alarm"));
+        assert!(filter.allows(events::TOOL_ERROR));
+        assert!(!filter.allows(events::TOOL_POST));
+        assert!(EventFilterConfig::from_session_config(&HashMap::new()).is_none());
+    }
+}
@@ -0,0 +1,527 @@
+//! EventQueue — buffered, backpressure-aware dispatch for high-frequency events.
+//!
+//! Streaming orchestrators emit many small events (`content_block:delta`,
+//! `thinking:delta`, ...). Awaiting [`HookRegistry::emit`] for each one puts
+//! every hook on the streaming hot path. An [`EventQueue`] accepts events
+//! into a bounded buffer and a background task dispatches them to the hooks
+//! in order.
+//!
+//! # Overflow
+//!
+//! When the buffer is full, the [`OverflowPolicy`] decides:
+//!
+//! | Policy       | New event                                   | Push returns |
+//! |--------------|---------------------------------------------|--------------|
+//! | `DropOldest` | queued; the oldest queued event is dropped  | [`Enqueued::DroppedOldest`] |
+//! | `Coalesce`   | replaces the newest queued event of the same name, else as `DropOldest` | [`Enqueued::Coalesced`] |
+//! | `Block`      | [`push()`](EventQueue::push) waits for space | [`Enqueued::Queued`] |
+//!
+//! A coalesced payload carries `"coalesced": n`, the number of events it
+//! replaced, so hooks can tell state was skipped. `Coalesce` suits
+//! latest-value-wins events (progress, status); use `Block` where every
+//! event matters.
+//!
+//! Hook results are discarded: queued events are notifications, so hooks
+//! cannot deny or modify them. Use [`HookRegistry::emit`] directly for
+//! events whose outcome matters.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use crate::hooks::HookRegistry;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// What [`EventQueue::push`] does when the buffer is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued event to make room.
+    #[default]
+    DropOldest,
+    /// Replace the newest queued event with the same name; fall back to
+    /// dropping the oldest when there is none.
+    Coalesce,
+    /// Wait until the drain task frees a slot.
+    Block,
+}
+
+/// Buffer settings for an [`EventQueue`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventQueueConfig {
+    /// Maximum number of queued (not yet dispatched) events. At least 1.
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    #[serde(default)]
+    pub policy: OverflowPolicy,
+}
+
+fn default_capacity() -> usize {
+    1024
+}
+
+impl Default for EventQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_capacity(),
+            policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// How [`EventQueue::push`] handled an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enqueued {
+    /// Queued without displacing anything.
+    Queued,
+    /// Queued after dropping the oldest queued event.
+    DroppedOldest,
+    /// Replaced a queued event of the same name.
+    Coalesced,
+    /// The queue is closed; the event was discarded.
+    Closed,
+}
+
+/// Counters reported by [`EventQueue::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventQueueStats {
+    /// Events currently waiting for dispatch.
+    pub queued: usize,
+    /// Events handed to the hooks so far.
+    pub dispatched: u64,
+    /// Events dropped by `DropOldest` (including `Coalesce` fallbacks).
+    pub dropped: u64,
+    /// Events replaced by `Coalesce`.
+    pub coalesced: u64,
+}
+
+// ---------------------------------------------------------------------------
+// EventQueue
+// ---------------------------------------------------------------------------
+
+struct Queued {
+    event: String,
+    data: Value,
+    /// How many events this entry has replaced.
+    replaced: u64,
+}
+
+#[derive(Default)]
+struct State {
+    queue: VecDeque<Queued>,
+    /// An event has been popped but its emit has not finished.
+    in_flight: bool,
+    stats: EventQueueStats,
+}
+
+struct Shared {
+    hooks: Arc<HookRegistry>,
+    config: EventQueueConfig,
+    state: Mutex<State>,
+    closed: AtomicBool,
+    /// Wakes the drain task (single consumer, so `notify_one` permits suffice).
+    items: Notify,
+    /// Wakes producers blocked on a full buffer.
+    space: Notify,
+    /// Wakes `flush()` callers once nothing is queued or in flight.
+    idle: Notify,
+}
+
+/// A bounded event buffer drained into a [`HookRegistry`] by a background task.
+///
+/// Events are dispatched one at a time, in push order. Dropping the queue
+/// lets the drain task finish what is already queued; call
+/// [`close()`](Self::close) to wait for that.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use amplifier_core::event_queue::{EventQueue, EventQueueConfig};
+/// use amplifier_core::hooks::HookRegistry;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap().block_on(async {
+/// let queue = EventQueue::spawn(Arc::new(HookRegistry::new()), EventQueueConfig::default());
+/// queue.push("content_block:delta", serde_json::json!({"delta": "Hel"})).await;
+/// queue.push("content_block:delta", serde_json::json!({"delta": "lo"})).await;
+/// queue.flush().await;
+/// assert_eq!(queue.stats().dispatched, 2);
+/// # });
+/// ```
+pub struct EventQueue {
+    shared: Arc<Shared>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl EventQueue {
+    /// Create a queue and spawn its drain task on the current Tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime.
+    pub fn spawn(hooks: Arc<HookRegistry>, mut config: EventQueueConfig) -> Self {
+        config.capacity = config.capacity.max(1);
+        let shared = Arc::new(Shared {
+            hooks,
+            config,
+            state: Mutex::new(State::default()),
+            closed: AtomicBool::new(false),
+            items: Notify::new(),
+            space: Notify::new(),
+            idle: Notify::new(),
+        });
+        let worker = tokio::spawn(drain(Arc::clone(&shared)));
+        Self {
+            shared,
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+
+    /// The queue's settings.
+    pub fn config(&self) -> &EventQueueConfig {
+        &self.shared.config
+    }
+
+    /// Queue `event` for dispatch, applying the overflow policy when full.
+    ///
+    /// Only waits under [`OverflowPolicy::Block`].
+    pub async fn push(&self, event: &str, data: Value) -> Enqueued {
+        let shared = &self.shared;
+        let mut entry = Some(Queued {
+            event: event.to_string(),
+            data,
+            replaced: 0,
+        });
+        loop {
+            let space = shared.space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+
+            if shared.closed.load(Ordering::Acquire) {
+                return Enqueued::Closed;
+            }
+            let outcome = {
+                let mut state = shared.state.lock().unwrap();
+                if state.queue.len() < shared.config.capacity {
+                    state
+                        .queue
+                        .push_back(entry.take().expect("entry is queued once"));
+                    Some(Enqueued::Queued)
+                } else {
+                    match shared.config.policy {
+                        OverflowPolicy::Block => None,
+                        OverflowPolicy::Coalesce => {
+                            Some(coalesce_or_drop(&mut state, entry.take().expect("entry")))
+                        }
+                        OverflowPolicy::DropOldest => {
+                            Some(drop_oldest(&mut state, entry.take().expect("entry")))
+                        }
+                    }
+                }
+            };
+            match outcome {
+                Some(outcome) => {
+                    shared.items.notify_one();
+                    return outcome;
+                }
+                None => space.await,
+            }
+        }
+    }
+
+    /// Wait until every event pushed so far has been dispatched.
+    pub async fn flush(&self) {
+        loop {
+            let idle = self.shared.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+            {
+                let state = self.shared.state.lock().unwrap();
+                if state.queue.is_empty() && !state.in_flight {
+                    return;
+                }
+            }
+            if self
+                .worker
+                .lock()
+                .unwrap()
+                .as_ref()
+                .is_none_or(|w| w.is_finished())
+            {
+                return;
+            }
+            idle.await;
+        }
+    }
+
+    /// Stop accepting events, dispatch what is queued, and stop the drain task.
+    pub async fn close(&self) {
+        self.shared.closed.store(true, Ordering::Release);
+        self.shared.items.notify_one();
+        self.shared.space.notify_waiters();
+        let worker = self.worker.lock().unwrap().take();
+        if let Some(worker) = worker {
+            if let Err(e) = worker.await {
+                log::error!("EventQueue drain task failed: {e}");
+            }
+        }
+    }
+
+    /// Whether [`close()`](Self::close) has been called.
+    pub fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::Acquire)
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> EventQueueStats {
+        let state = self.shared.state.lock().unwrap();
+        EventQueueStats {
+            queued: state.queue.len(),
+            ..state.stats
+        }
+    }
+}
+
+impl Drop for EventQueue {
+    fn drop(&mut self) {
+        // The drain task dispatches what is already queued, then exits.
+        self.shared.closed.store(true, Ordering::Release);
+        self.shared.items.notify_one();
+        self.shared.space.notify_waiters();
+    }
+}
+
+fn drop_oldest(state: &mut State, entry: Queued) -> Enqueued {
+    state.queue.pop_front();
+    state.stats.dropped += 1;
+    state.queue.push_back(entry);
+    Enqueued::DroppedOldest
+}
+
+fn coalesce_or_drop(state: &mut State, mut entry: Queued) -> Enqueued {
+    let Some(existing) = state
+        .queue
+        .iter_mut()
+        .rev()
+        .find(|q| q.event == entry.event)
+    else {
+        return drop_oldest(state, entry);
+    };
+    entry.replaced = existing.replaced + 1;
+    *existing = entry;
+    state.stats.coalesced += 1;
+    Enqueued::Coalesced
+}
+
+/// The drain task: dispatch queued events in order until closed and empty.
+async fn drain(shared: Arc<Shared>) {
+    loop {
+        let next = {
+            let mut state = shared.state.lock().unwrap();
+            let next = state.queue.pop_front();
+            state.in_flight = next.is_some();
+            next
+        };
+        let Some(Queued {
+            event,
+            mut data,
+            replaced,
+        }) = next
+        else {
+            shared.idle.notify_waiters();
+            if shared.closed.load(Ordering::Acquire) {
+                return;
+            }
+            shared.items.notified().await;
+            continue;
+        };
+        shared.space.notify_waiters();
+
+        if replaced > 0 {
+            if let Value::Object(ref mut map) = data {
+                map.insert("coalesced".to_string(), Value::from(replaced));
+            }
+        }
+        shared.hooks.emit(&event, data).await;
+
+        let mut state = shared.state.lock().unwrap();
+        state.in_flight = false;
+        state.stats.dispatched += 1;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::time::Duration;
+
+    use crate::errors::HookError;
+    use crate::models::HookResult;
+    use crate::testing::FakeHookHandler;
+    use crate::traits::HookHandler;
+
+    /// Holds every dispatch until the test opens the gate.
+    struct GatedHandler {
+        gate: Arc<tokio::sync::Semaphore>,
+        seen: Mutex<Vec<Value>>,
+    }
+
+    impl HookHandler for GatedHandler {
+        fn handle(
+            &self,
+            _event: &str,
+            data: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+            Box::pin(async move {
+                let _permit = self.gate.acquire().await.unwrap();
+                self.seen.lock().unwrap().push(data);
+                Ok(HookResult::default())
+            })
+        }
+    }
+
+    fn gated(policy: OverflowPolicy, capacity: usize) -> (EventQueue, Arc<GatedHandler>) {
+        let hooks = Arc::new(HookRegistry::new());
+        let handler = Arc::new(GatedHandler {
+            gate: Arc::new(tokio::sync::Semaphore::new(0)),
+            seen: Mutex::new(Vec::new()),
+        });
+        let _ = hooks.register("delta", handler.clone(), 0, None);
+        let _ = hooks.register("status", handler.clone(), 0, None);
+        (
+            EventQueue::spawn(hooks, EventQueueConfig { capacity, policy }),
+            handler,
+        )
+    }
+
+    /// Push one event and wait until the drain task is blocked dispatching it,
+    /// so the buffer itself is empty.
+    async fn occupy_worker(queue: &EventQueue) {
+        queue.push("delta", serde_json::json!({"n": 0})).await;
+        while queue.stats().queued > 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn seen_n(handler: &GatedHandler) -> Vec<i64> {
+        handler
+            .seen
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|d| d["n"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn events_are_dispatched_in_order() {
+        let hooks = Arc::new(HookRegistry::new());
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register("delta", recorder.clone(), 0, None);
+        let queue = EventQueue::spawn(hooks, EventQueueConfig::default());
+
+        for n in 0..50 {
+            assert_eq!(
+                queue.push("delta", serde_json::json!({"n": n})).await,
+                Enqueued::Queued
+            );
+        }
+        queue.flush().await;
+
+        let order: Vec<i64> = recorder
+            .recorded_events()
+            .iter()
+            .map(|(_, d)| d["n"].as_i64().unwrap())
+            .collect();
+        assert_eq!(order, (0..50).collect::<Vec<_>>());
+        assert_eq!(queue.stats().dispatched, 50);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_the_newest_events() {
+        let (queue, handler) = gated(OverflowPolicy::DropOldest, 2);
+        occupy_worker(&queue).await;
+        for n in 1..=4 {
+            queue.push("delta", serde_json::json!({"n": n})).await;
+        }
+        assert_eq!(queue.stats().dropped, 2);
+
+        handler.gate.add_permits(100);
+        queue.flush().await;
+        assert_eq!(seen_n(&handler), vec![0, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn coalesce_replaces_latest_event_of_same_name() {
+        let (queue, handler) = gated(OverflowPolicy::Coalesce, 2);
+        occupy_worker(&queue).await;
+        queue.push("status", serde_json::json!({"n": 1})).await;
+        queue.push("delta", serde_json::json!({"n": 2})).await;
+        assert_eq!(
+            queue.push("status", serde_json::json!({"n": 3})).await,
+            Enqueued::Coalesced
+        );
+        assert_eq!(
+            queue.push("status", serde_json::json!({"n": 4})).await,
+            Enqueued::Coalesced
+        );
+
+        handler.gate.add_permits(100);
+        queue.flush().await;
+        assert_eq!(seen_n(&handler), vec![0, 4, 2]);
+        assert_eq!(handler.seen.lock().unwrap()[1]["coalesced"], 2);
+        assert_eq!(queue.stats().coalesced, 2);
+    }
+
+    #[tokio::test]
+    async fn block_waits_for_space_without_losing_events() {
+        let (queue, handler) = gated(OverflowPolicy::Block, 1);
+        let queue = Arc::new(queue);
+        occupy_worker(&queue).await;
+        queue.push("delta", serde_json::json!({"n": 1})).await;
+
+        let producer = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move { queue.push("delta", serde_json::json!({"n": 2})).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!producer.is_finished(), "push should block while full");
+
+        handler.gate.add_permits(100);
+        assert_eq!(producer.await.unwrap(), Enqueued::Queued);
+        queue.flush().await;
+        assert_eq!(seen_n(&handler), vec![0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn close_drains_and_rejects_new_events() {
+        let hooks = Arc::new(HookRegistry::new());
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register("delta", recorder.clone(), 0, None);
+        let queue = EventQueue::spawn(hooks, EventQueueConfig::default());
+        queue.push("delta", serde_json::json!({})).await;
+
+        queue.close().await;
+        assert_eq!(recorder.recorded_events().len(), 1);
+        assert!(queue.is_closed());
+        assert_eq!(
+            queue.push("delta", serde_json::json!({})).await,
+            Enqueued::Closed
+        );
+        queue.flush().await;
+    }
+}
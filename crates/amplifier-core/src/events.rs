@@ -26,6 +26,7 @@
 //! | Policy          | `policy:`         | Policy violation events                       |
 //! | Approval        | `approval:`       | Human-in-the-loop approval gates              |
 //! | Cancellation    | `cancel:`         | Graceful/immediate cancellation lifecycle     |
+//! | Kernel          | `kernel:`         | Kernel resource signals (memory pressure)     |
 
 // --- Session lifecycle ---
 
@@ -37,6 +38,16 @@ pub const SESSION_END: &str = "session:end";
 pub const SESSION_FORK: &str = "session:fork";
 /// A session has been resumed.
 pub const SESSION_RESUME: &str = "session:resume";
+/// A session was rewound to a checkpoint.
+/// Payload: {session_id, checkpoint, turn_number, message_count}
+pub const SESSION_REWIND: &str = "session:rewind";
+/// An idle session was cancelled and cleaned up by
+/// [`SessionManager::reap_idle`](crate::session_manager::SessionManager::reap_idle).
+/// Payload: {session_id, idle_ms, max_idle_ms}
+pub const SESSION_REAPED: &str = "session:reaped";
+/// Periodic liveness signal while `execute()` runs (see [`crate::heartbeat`]).
+/// Payload: {session_id, status, elapsed_ms, usage, in_flight_tools}
+pub const SESSION_HEARTBEAT: &str = "session:heartbeat";
 
 // --- Prompt lifecycle ---
 
@@ -44,6 +55,10 @@ pub const SESSION_RESUME: &str = "session:resume";
 pub const PROMPT_SUBMIT: &str = "prompt:submit";
 /// Prompt processing is complete.
 pub const PROMPT_COMPLETE: &str = "prompt:complete";
+/// A prompt identical to one submitted within the duplicate window was
+/// submitted again (see [`crate::prompt_history`]).
+/// Payload: {session_id, prompt_hash, previous_submitted_at, age_ms, replayed}
+pub const PROMPT_DUPLICATE: &str = "prompt:duplicate";
 
 // --- Planning (optional orchestration phases) ---
 
@@ -67,6 +82,10 @@ pub const PROVIDER_THROTTLE: &str = "provider:throttle";
 pub const PROVIDER_TOOL_SEQUENCE_REPAIRED: &str = "provider:tool_sequence_repaired";
 /// A provider has been resolved (selected for use).
 pub const PROVIDER_RESOLVE: &str = "provider:resolve";
+/// A provider call is about to run (pre-hook; `Modify` rewrites the request).
+pub const PROVIDER_PRE: &str = "provider:pre";
+/// A provider call has returned (post-hook; `Modify` rewrites the response).
+pub const PROVIDER_POST: &str = "provider:post";
 
 // --- LLM request/response ---
 
@@ -84,6 +103,18 @@ pub const CONTENT_BLOCK_DELTA: &str = "content_block:delta";
 /// A content block has finished streaming.
 pub const CONTENT_BLOCK_END: &str = "content_block:end";
 
+// --- Canonical streaming events (emitted from a ResponseAccumulator) ---
+
+/// A streamed content block opened.
+/// Payload: {index, block}
+pub const CONTENT_START: &str = "content:block_start";
+/// Coalesced text, thinking or tool input of a streamed block.
+/// Payload: {index, kind: "text" | "thinking" | "tool_input", delta}
+pub const CONTENT_DELTA: &str = "content:delta";
+/// A streamed content block closed.
+/// Payload: {index}
+pub const CONTENT_STOP: &str = "content:block_stop";
+
 // --- Thinking events (model reasoning) ---
 
 /// A delta chunk of model thinking/reasoning.
@@ -99,6 +130,22 @@ pub const TOOL_PRE: &str = "tool:pre";
 pub const TOOL_POST: &str = "tool:post";
 /// A tool invocation resulted in an error.
 pub const TOOL_ERROR: &str = "tool:error";
+/// A running tool reported progress.
+pub const TOOL_PROGRESS: &str = "tool:progress";
+/// A tool was stopped at its own timeout.
+/// Payload: {tool_name, tool_call_id, timeout_ms, partial_output}
+pub const TOOL_TIMEOUT: &str = "tool:timeout";
+/// A tool result is about to be returned; `Modify` may replace `tool_result`.
+/// Payload: {tool_name, tool_input, tool_result}
+pub const TOOL_RESULT_TRANSFORM: &str = "tool:result:transform";
+/// Decision event at turn start: hooks propose extra tools for the turn
+/// (see [`crate::tool_discovery`]).
+/// Payload: {session_id, prompt, mounted_tools}
+pub const TOOLS_DISCOVER: &str = "tools:discover";
+/// A discovered proxy tool was called; the proposing hook returns the
+/// result as `data.tool_result`.
+/// Payload: {tool_name, tool_input, source}
+pub const TOOLS_INVOKE: &str = "tools:invoke";
 
 // --- Context management ---
 
@@ -110,11 +157,23 @@ pub const CONTEXT_POST_COMPACT: &str = "context:post_compact";
 pub const CONTEXT_COMPACTION: &str = "context:compaction";
 /// Context has been included/added.
 pub const CONTEXT_INCLUDE: &str = "context:include";
+/// Repeated content was collapsed in messages prepared for a request.
+/// Payload: {collapsed, chars_saved, estimated_tokens_saved, groups}
+pub const CONTEXT_DEDUPLICATED: &str = "context:deduplicated";
+/// A hook's context injection was dropped by the injection queue.
+/// Payload: {event, source, priority, reason, tokens, remaining}
+pub const CONTEXT_INJECTION_DROPPED: &str = "context:injection_dropped";
 
 // --- Orchestrator lifecycle ---
 
 /// The orchestrator has completed its run.
 pub const ORCHESTRATOR_COMPLETE: &str = "orchestrator:complete";
+/// The orchestrator entered an interim state.
+/// Payload: {state, ...} (see `orchestrator_status::OrchestratorStatus`)
+pub const ORCHESTRATOR_STATUS: &str = "orchestrator:status";
+/// The orchestrator applied the turn recovery policy to a failure.
+/// Payload: {kind, target, error, action, failures} (see `recovery::RecoveryAttempt`)
+pub const ORCHESTRATOR_RECOVERY: &str = "orchestrator:recovery";
 /// Orchestrator execution begins.
 pub const EXECUTION_START: &str = "execution:start";
 /// Orchestrator execution completes.
@@ -142,11 +201,17 @@ pub const APPROVAL_REQUIRED: &str = "approval:required";
 pub const APPROVAL_GRANTED: &str = "approval:granted";
 /// An approval has been denied.
 pub const APPROVAL_DENIED: &str = "approval:denied";
+/// A pending approval was resolved by cancellation.
+/// Payload: {tool_name, approved, cancellation: "graceful" | "immediate"}
+pub const APPROVAL_CANCELLED: &str = "approval:cancelled";
 
 // --- Cancellation lifecycle ---
 
 /// Cancellation has been requested (graceful or immediate).
 pub const CANCEL_REQUESTED: &str = "cancel:requested";
+/// Graceful cancellation was escalated to immediate.
+/// Payload: {from: "graceful", to: "immediate"}
+pub const CANCEL_ESCALATED: &str = "cancel:escalated";
 /// Cancellation has been finalized, session stopping.
 pub const CANCEL_COMPLETED: &str = "cancel:completed";
 
@@ -156,6 +221,24 @@ pub const CANCEL_COMPLETED: &str = "cancel:completed";
 /// Payload: {module_id: str, error: str}
 pub const MODULE_ON_SESSION_READY_FAILED: &str = "module:on_session_ready_failed";
 
+// --- Kernel resource events ---
+
+/// Accounted kernel memory crossed its pressure threshold, or entries were evicted/rejected.
+/// Payload: {used_bytes, ceiling_bytes, by_category, evictions, rejections}
+pub const KERNEL_MEMORY_PRESSURE: &str = "kernel:memory_pressure";
+/// Entries were evicted from conversation history to stay under the memory ceiling.
+/// Payload: {category, session_id, evicted}
+pub const KERNEL_MEMORY_EVICTED: &str = "kernel:memory_evicted";
+/// A session quota reached 80% of its limit (emitted once per resource).
+/// Payload: {resource, limit, used}
+pub const QUOTA_WARNING: &str = "quota:warning";
+/// A per-turn safety limit was exceeded; the turn fails.
+/// Payload: {limit, max, observed, tool_name?}
+pub const SAFETY_LIMIT: &str = "safety:limit";
+/// A hook handler call took longer than its latency budget.
+/// Payload: {event, handler, phase, elapsed_ms, budget_ms, demoted}
+pub const HOOK_SLOW_HANDLER: &str = "hook:slow_handler";
+
 // --- Aggregate ---
 
 /// All canonical event names, for iteration and validation.
@@ -167,8 +250,12 @@ pub const ALL_EVENTS: &[&str] = &[
     SESSION_END,
     SESSION_FORK,
     SESSION_RESUME,
+    SESSION_REWIND,
+    SESSION_REAPED,
+    SESSION_HEARTBEAT,
     PROMPT_SUBMIT,
     PROMPT_COMPLETE,
+    PROMPT_DUPLICATE,
     PLAN_START,
     PLAN_END,
     PROVIDER_REQUEST,
@@ -178,21 +265,35 @@ pub const ALL_EVENTS: &[&str] = &[
     PROVIDER_THROTTLE,
     PROVIDER_TOOL_SEQUENCE_REPAIRED,
     PROVIDER_RESOLVE,
+    PROVIDER_PRE,
+    PROVIDER_POST,
     LLM_REQUEST,
     LLM_RESPONSE,
     CONTENT_BLOCK_START,
     CONTENT_BLOCK_DELTA,
     CONTENT_BLOCK_END,
+    CONTENT_START,
+    CONTENT_DELTA,
+    CONTENT_STOP,
     THINKING_DELTA,
     THINKING_FINAL,
     TOOL_PRE,
     TOOL_POST,
     TOOL_ERROR,
+    TOOL_PROGRESS,
+    TOOL_TIMEOUT,
+    TOOL_RESULT_TRANSFORM,
+    TOOLS_DISCOVER,
+    TOOLS_INVOKE,
     CONTEXT_PRE_COMPACT,
     CONTEXT_POST_COMPACT,
     CONTEXT_COMPACTION,
     CONTEXT_INCLUDE,
+    CONTEXT_DEDUPLICATED,
+    CONTEXT_INJECTION_DROPPED,
     ORCHESTRATOR_COMPLETE,
+    ORCHESTRATOR_STATUS,
+    ORCHESTRATOR_RECOVERY,
     EXECUTION_START,
     EXECUTION_END,
     USER_NOTIFICATION,
@@ -202,9 +303,16 @@ pub const ALL_EVENTS: &[&str] = &[
     APPROVAL_REQUIRED,
     APPROVAL_GRANTED,
     APPROVAL_DENIED,
+    APPROVAL_CANCELLED,
     CANCEL_REQUESTED,
+    CANCEL_ESCALATED,
     CANCEL_COMPLETED,
     MODULE_ON_SESSION_READY_FAILED,
+    KERNEL_MEMORY_PRESSURE,
+    KERNEL_MEMORY_EVICTED,
+    QUOTA_WARNING,
+    SAFETY_LIMIT,
+    HOOK_SLOW_HANDLER,
 ];
 
 #[cfg(test)]
@@ -219,12 +327,16 @@ mod tests {
         assert_eq!(SESSION_END, "session:end");
         assert_eq!(SESSION_FORK, "session:fork");
         assert_eq!(SESSION_RESUME, "session:resume");
+        assert_eq!(SESSION_REWIND, "session:rewind");
+        assert_eq!(SESSION_REAPED, "session:reaped");
+        assert_eq!(SESSION_HEARTBEAT, "session:heartbeat");
     }
 
     #[test]
     fn prompt_constants() {
         assert_eq!(PROMPT_SUBMIT, "prompt:submit");
         assert_eq!(PROMPT_COMPLETE, "prompt:complete");
+        assert_eq!(PROMPT_DUPLICATE, "prompt:duplicate");
     }
 
     #[test]
@@ -239,6 +351,8 @@ mod tests {
         assert_eq!(PROVIDER_RESPONSE, "provider:response");
         assert_eq!(PROVIDER_RETRY, "provider:retry");
         assert_eq!(PROVIDER_ERROR, "provider:error");
+        assert_eq!(PROVIDER_PRE, "provider:pre");
+        assert_eq!(PROVIDER_POST, "provider:post");
     }
 
     #[test]
@@ -252,6 +366,9 @@ mod tests {
         assert_eq!(CONTENT_BLOCK_START, "content_block:start");
         assert_eq!(CONTENT_BLOCK_DELTA, "content_block:delta");
         assert_eq!(CONTENT_BLOCK_END, "content_block:end");
+        assert_eq!(CONTENT_START, "content:block_start");
+        assert_eq!(CONTENT_DELTA, "content:delta");
+        assert_eq!(CONTENT_STOP, "content:block_stop");
     }
 
     #[test]
@@ -265,6 +382,11 @@ mod tests {
         assert_eq!(TOOL_PRE, "tool:pre");
         assert_eq!(TOOL_POST, "tool:post");
         assert_eq!(TOOL_ERROR, "tool:error");
+        assert_eq!(TOOL_PROGRESS, "tool:progress");
+        assert_eq!(TOOL_TIMEOUT, "tool:timeout");
+        assert_eq!(TOOL_RESULT_TRANSFORM, "tool:result:transform");
+        assert_eq!(TOOLS_DISCOVER, "tools:discover");
+        assert_eq!(TOOLS_INVOKE, "tools:invoke");
     }
 
     #[test]
@@ -273,11 +395,14 @@ mod tests {
         assert_eq!(CONTEXT_POST_COMPACT, "context:post_compact");
         assert_eq!(CONTEXT_COMPACTION, "context:compaction");
         assert_eq!(CONTEXT_INCLUDE, "context:include");
+        assert_eq!(CONTEXT_DEDUPLICATED, "context:deduplicated");
     }
 
     #[test]
     fn orchestrator_and_execution_constants() {
         assert_eq!(ORCHESTRATOR_COMPLETE, "orchestrator:complete");
+        assert_eq!(ORCHESTRATOR_STATUS, "orchestrator:status");
+        assert_eq!(ORCHESTRATOR_RECOVERY, "orchestrator:recovery");
         assert_eq!(EXECUTION_START, "execution:start");
         assert_eq!(EXECUTION_END, "execution:end");
     }
@@ -299,12 +424,14 @@ mod tests {
         assert_eq!(APPROVAL_REQUIRED, "approval:required");
         assert_eq!(APPROVAL_GRANTED, "approval:granted");
         assert_eq!(APPROVAL_DENIED, "approval:denied");
+        assert_eq!(APPROVAL_CANCELLED, "approval:cancelled");
     }
 
     #[test]
     fn cancellation_constants() {
         assert_eq!(CANCEL_REQUESTED, "cancel:requested");
         assert_eq!(CANCEL_COMPLETED, "cancel:completed");
+        assert_eq!(CANCEL_ESCALATED, "cancel:escalated");
     }
 
     // ---- New provider event constants (Phase 3) ----
@@ -343,11 +470,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn kernel_constants() {
+        assert_eq!(KERNEL_MEMORY_PRESSURE, "kernel:memory_pressure");
+        assert_eq!(KERNEL_MEMORY_EVICTED, "kernel:memory_evicted");
+        assert_eq!(QUOTA_WARNING, "quota:warning");
+        assert_eq!(SAFETY_LIMIT, "safety:limit");
+        assert_eq!(HOOK_SLOW_HANDLER, "hook:slow_handler");
+    }
+
     // ---- ALL_EVENTS aggregate tests ----
 
     #[test]
     fn all_events_count() {
-        assert_eq!(ALL_EVENTS.len(), 42, "expected 42 canonical events");
+        assert_eq!(ALL_EVENTS.len(), 67, "expected 67 canonical events");
     }
 
     #[test]
@@ -357,8 +493,10 @@ mod tests {
             SESSION_END,
             SESSION_FORK,
             SESSION_RESUME,
+            SESSION_HEARTBEAT,
             PROMPT_SUBMIT,
             PROMPT_COMPLETE,
+            PROMPT_DUPLICATE,
             PLAN_START,
             PLAN_END,
             PROVIDER_REQUEST,
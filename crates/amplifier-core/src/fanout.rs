@@ -0,0 +1,627 @@
+//! Concurrent multi-provider fan-out.
+//!
+//! A [`FanoutProvider`] is itself a [`Provider`]: it sends every request to
+//! several providers at once and answers with one response, chosen by its
+//! [`FanoutMode`]:
+//!
+//! | Mode       | Waits for                 | Returns                                   |
+//! |------------|---------------------------|-------------------------------------------|
+//! | `Race`     | the first success         | that response; the other calls are aborted |
+//! | `Ensemble` | every call                | the success picked by an [`EnsembleSelector`] |
+//!
+//! If every call fails, the error of the first provider is returned (the
+//! others are logged).
+//!
+//! Because it mounts like any other provider, the rest of the kernel sees
+//! one call:
+//!
+//! - **Usage.** The response's `usage` is the sum over every call that
+//!   completed, so quotas and pricing count all the tokens spent.
+//! - **Degradation.** When the first provider failed and another one
+//!   answered, `degradation` records the switch as `provider/model`
+//!   (`requested` from the first provider, `actual` from the answer) unless
+//!   the answering provider already set one.
+//! - **Report.** `metadata["fanout"]` holds a [`FanoutReport`]: the mode,
+//!   the selected provider, and every call's outcome, usage and latency.
+//!
+//! Calls run on spawned tasks in the caller's
+//! [correlation scope](crate::correlation).
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use amplifier_core::fanout::{FanoutProvider, PreferOrder};
+//! # fn example(primary: Arc<dyn amplifier_core::Provider>, backup: Arc<dyn amplifier_core::Provider>) {
+//! let ha = FanoutProvider::race("ha", vec![primary.clone(), backup.clone()]);
+//! let eval = FanoutProvider::ensemble("eval", vec![primary, backup], Arc::new(PreferOrder));
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+
+use crate::correlation;
+use crate::errors::ProviderError;
+use crate::messages::{ChatRequest, ChatResponse, Degradation, ToolCall, Usage};
+use crate::models::{ModelInfo, ProviderInfo};
+use crate::traits::{BoxFuture, Provider};
+use crate::turn::add_usage;
+
+/// `ChatResponse.metadata` key under which the [`FanoutReport`] is stored.
+pub const FANOUT_METADATA_KEY: &str = "fanout";
+
+// ---------------------------------------------------------------------------
+// Modes and selectors
+// ---------------------------------------------------------------------------
+
+/// One successful response offered to an [`EnsembleSelector`].
+#[derive(Debug, Clone)]
+pub struct FanoutCandidate {
+    pub provider: String,
+    pub response: ChatResponse,
+}
+
+/// Picks the response an ensemble returns.
+pub trait EnsembleSelector: Send + Sync {
+    /// The index into `candidates` (never empty, in provider order) of the
+    /// response to return.
+    ///
+    /// # Errors
+    ///
+    /// An error fails the whole call.
+    fn select<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        candidates: &'a [FanoutCandidate],
+    ) -> BoxFuture<'a, Result<usize, ProviderError>>;
+}
+
+/// Selects the first successful response in provider order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreferOrder;
+
+impl EnsembleSelector for PreferOrder {
+    fn select<'a>(
+        &'a self,
+        _request: &'a ChatRequest,
+        _candidates: &'a [FanoutCandidate],
+    ) -> BoxFuture<'a, Result<usize, ProviderError>> {
+        Box::pin(async { Ok(0) })
+    }
+}
+
+/// How a [`FanoutProvider`] turns several calls into one response.
+#[derive(Clone)]
+pub enum FanoutMode {
+    /// Return the first success and abort the other calls.
+    Race,
+    /// Wait for every call and let the selector choose.
+    Ensemble(Arc<dyn EnsembleSelector>),
+}
+
+impl FanoutMode {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Race => "race",
+            Self::Ensemble(_) => "ensemble",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+/// How one fanned-out call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanoutOutcome {
+    Ok,
+    Error,
+    /// Aborted because another call won the race.
+    Cancelled,
+}
+
+/// One fanned-out call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanoutCall {
+    pub provider: String,
+    pub outcome: FanoutOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// What a fan-out did; stored in `metadata["fanout"]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FanoutReport {
+    /// `"race"` or `"ensemble"`.
+    pub mode: String,
+    /// The provider whose response was returned.
+    pub selected: String,
+    /// Every call, in provider order.
+    pub calls: Vec<FanoutCall>,
+}
+
+// ---------------------------------------------------------------------------
+// FanoutProvider
+// ---------------------------------------------------------------------------
+
+/// A provider that fans each request out to several providers.
+pub struct FanoutProvider {
+    name: String,
+    providers: Vec<Arc<dyn Provider>>,
+    mode: FanoutMode,
+}
+
+type CallResult = (Result<ChatResponse, ProviderError>, Duration);
+
+impl FanoutProvider {
+    /// Fan out to `providers` in [`FanoutMode::Race`].
+    pub fn race(name: impl Into<String>, providers: Vec<Arc<dyn Provider>>) -> Self {
+        Self::new(name, providers, FanoutMode::Race)
+    }
+
+    /// Fan out to `providers` in [`FanoutMode::Ensemble`].
+    pub fn ensemble(
+        name: impl Into<String>,
+        providers: Vec<Arc<dyn Provider>>,
+        selector: Arc<dyn EnsembleSelector>,
+    ) -> Self {
+        Self::new(name, providers, FanoutMode::Ensemble(selector))
+    }
+
+    pub fn new(
+        name: impl Into<String>,
+        providers: Vec<Arc<dyn Provider>>,
+        mode: FanoutMode,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            providers,
+            mode,
+        }
+    }
+
+    pub fn providers(&self) -> &[Arc<dyn Provider>] {
+        &self.providers
+    }
+
+    pub fn mode(&self) -> &FanoutMode {
+        &self.mode
+    }
+
+    /// Run every call, stopping at the first success in race mode.
+    async fn fan_out(&self, request: &ChatRequest) -> Vec<Option<CallResult>> {
+        let ids = correlation::current();
+        let mut calls = JoinSet::new();
+        for (index, provider) in self.providers.iter().enumerate() {
+            let provider = Arc::clone(provider);
+            let request = request.clone();
+            calls.spawn(correlation::scope(ids.clone(), async move {
+                let started = Instant::now();
+                let result = provider.complete(request).await;
+                (index, result, started.elapsed())
+            }));
+        }
+
+        let mut results: Vec<Option<CallResult>> = self.providers.iter().map(|_| None).collect();
+        while let Some(joined) = calls.join_next().await {
+            let (index, result, elapsed) = match joined {
+                Ok(call) => call,
+                Err(e) => {
+                    log::error!("Fan-out provider '{}': a call panicked: {e}", self.name);
+                    continue;
+                }
+            };
+            let won = matches!(self.mode, FanoutMode::Race) && result.is_ok();
+            results[index] = Some((result, elapsed));
+            if won {
+                calls.abort_all();
+                break;
+            }
+        }
+        results
+    }
+
+    /// Pick the response to return from the successful calls (at least one).
+    async fn select(
+        &self,
+        request: &ChatRequest,
+        results: &[Option<CallResult>],
+    ) -> Result<usize, ProviderError> {
+        let successes: Vec<usize> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, call)| matches!(call, Some((Ok(_), _))))
+            .map(|(index, _)| index)
+            .collect();
+        let selector = match &self.mode {
+            FanoutMode::Race => return Ok(successes[0]),
+            FanoutMode::Ensemble(selector) => selector,
+        };
+        let candidates: Vec<FanoutCandidate> = successes
+            .iter()
+            .map(|&index| FanoutCandidate {
+                provider: self.providers[index].name().to_string(),
+                response: match &results[index] {
+                    Some((Ok(response), _)) => response.clone(),
+                    _ => unreachable!("successes only holds Ok results"),
+                },
+            })
+            .collect();
+        let choice = selector.select(request, &candidates).await?;
+        successes
+            .get(choice)
+            .copied()
+            .ok_or_else(|| ProviderError::Other {
+                message: format!(
+                    "ensemble selector chose candidate {choice} of {}",
+                    candidates.len()
+                ),
+                provider: Some(self.name.clone()),
+                model: request.model.clone(),
+                retry_after: None,
+                status_code: None,
+                retryable: false,
+                delay_multiplier: None,
+            })
+    }
+
+    /// The error returned when no call succeeded: the first provider's.
+    fn all_failed(&self, results: Vec<Option<CallResult>>) -> ProviderError {
+        let mut errors = results
+            .into_iter()
+            .zip(&self.providers)
+            .filter_map(|(call, provider)| match call {
+                Some((Err(e), _)) => Some((provider.name(), e)),
+                _ => None,
+            });
+        let Some((_, first)) = errors.next() else {
+            return ProviderError::Unavailable {
+                message: format!("fan-out provider '{}' got no response", self.name),
+                provider: Some(self.name.clone()),
+                model: None,
+                retry_after: None,
+                status_code: None,
+                delay_multiplier: None,
+            };
+        };
+        for (provider, e) in errors {
+            log::warn!("Fan-out provider '{}': '{provider}' failed: {e}", self.name);
+        }
+        first
+    }
+
+    fn report(&self, selected: usize, results: &[Option<CallResult>]) -> FanoutReport {
+        let calls = results
+            .iter()
+            .zip(&self.providers)
+            .map(|(call, provider)| {
+                let (outcome, error, usage, elapsed) = match call {
+                    Some((Ok(response), elapsed)) => (
+                        FanoutOutcome::Ok,
+                        None,
+                        response.usage.clone(),
+                        Some(*elapsed),
+                    ),
+                    Some((Err(e), elapsed)) => (
+                        FanoutOutcome::Error,
+                        Some(e.to_string()),
+                        None,
+                        Some(*elapsed),
+                    ),
+                    None => (FanoutOutcome::Cancelled, None, None, None),
+                };
+                FanoutCall {
+                    provider: provider.name().to_string(),
+                    outcome,
+                    error,
+                    usage,
+                    elapsed_ms: elapsed.map(|d| d.as_millis() as u64),
+                }
+            })
+            .collect();
+        FanoutReport {
+            mode: self.mode.name().to_string(),
+            selected: self.providers[selected].name().to_string(),
+            calls,
+        }
+    }
+}
+
+impl Provider for FanoutProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The first provider's info under this provider's name.
+    fn get_info(&self) -> ProviderInfo {
+        let mut info = self
+            .providers
+            .first()
+            .map(|p| p.get_info())
+            .unwrap_or_else(|| ProviderInfo {
+                id: String::new(),
+                display_name: String::new(),
+                credential_env_vars: Vec::new(),
+                capabilities: Vec::new(),
+                defaults: Default::default(),
+                config_fields: Vec::new(),
+            });
+        info.id = self.name.clone();
+        info.display_name = self.name.clone();
+        info
+    }
+
+    /// Models of every provider, first listing wins on duplicate IDs.
+    /// Providers that fail to list are skipped (logged).
+    fn list_models(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ModelInfo>, ProviderError>> + Send + '_>> {
+        Box::pin(async move {
+            let mut seen = HashSet::new();
+            let mut models = Vec::new();
+            let mut first_error = None;
+            for provider in &self.providers {
+                match provider.list_models().await {
+                    Ok(listed) => models.extend(
+                        listed
+                            .into_iter()
+                            .filter(|model| seen.insert(model.id.clone())),
+                    ),
+                    Err(e) => {
+                        log::warn!(
+                            "Fan-out provider '{}': '{}' failed to list models: {e}",
+                            self.name,
+                            provider.name()
+                        );
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+            match first_error {
+                Some(e) if models.is_empty() => Err(e),
+                _ => Ok(models),
+            }
+        })
+    }
+
+    fn complete(
+        &self,
+        request: ChatRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ChatResponse, ProviderError>> + Send + '_>> {
+        Box::pin(async move {
+            let mut results = self.fan_out(&request).await;
+            if !results.iter().any(|call| matches!(call, Some((Ok(_), _)))) {
+                return Err(self.all_failed(results));
+            }
+            let selected = self.select(&request, &results).await?;
+            let report = self.report(selected, &results);
+
+            let usage = results
+                .iter()
+                .filter_map(|call| match call {
+                    Some((Ok(response), _)) => response.usage.as_ref(),
+                    _ => None,
+                })
+                .fold(None, |total: Option<Usage>, usage| {
+                    Some(match total {
+                        Some(total) => add_usage(total, usage),
+                        None => usage.clone(),
+                    })
+                });
+            let primary_error = match &results[0] {
+                Some((Err(e), _)) if selected != 0 => Some(e.to_string()),
+                _ => None,
+            };
+            let Some((Ok(mut response), _)) = results[selected].take() else {
+                unreachable!("the selected call succeeded");
+            };
+
+            response.usage = usage;
+            if let (Some(reason), None) = (primary_error, &response.degradation) {
+                response.degradation = Some(Degradation {
+                    requested: qualified(self.providers[0].name(), request.model.as_deref()),
+                    actual: qualified(
+                        self.providers[selected].name(),
+                        response_model(&response).or(request.model.as_deref()),
+                    ),
+                    reason: format!("primary provider failed: {reason}"),
+                    extensions: Default::default(),
+                });
+            }
+            response
+                .metadata
+                .get_or_insert_with(Default::default)
+                .insert(
+                    FANOUT_METADATA_KEY.to_string(),
+                    serde_json::to_value(&report).unwrap_or_default(),
+                );
+            Ok(response)
+        })
+    }
+
+    /// Parsed by the provider that produced the response.
+    fn parse_tool_calls(&self, response: &ChatResponse) -> Vec<ToolCall> {
+        let selected = response
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get(FANOUT_METADATA_KEY))
+            .and_then(|report| report.get("selected"))
+            .and_then(|name| name.as_str());
+        match self.providers.iter().find(|p| Some(p.name()) == selected) {
+            Some(provider) => provider.parse_tool_calls(response),
+            None => response.tool_calls.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// `provider/model`, or the provider alone when the model is unknown.
+fn qualified(provider: &str, model: Option<&str>) -> String {
+    match model {
+        Some(model) => format!("{provider}/{model}"),
+        None => provider.to_string(),
+    }
+}
+
+fn response_model(response: &ChatResponse) -> Option<&str> {
+    response.metadata.as_ref()?.get("model")?.as_str()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::testing::{FakeProvider, FlakyProvider};
+
+    fn request() -> ChatRequest {
+        serde_json::from_value(json!({"messages": [], "model": "m1"})).unwrap()
+    }
+
+    fn report(response: &ChatResponse) -> FanoutReport {
+        serde_json::from_value(response.metadata.as_ref().unwrap()[FANOUT_METADATA_KEY].clone())
+            .unwrap()
+    }
+
+    fn text(response: &ChatResponse) -> String {
+        serde_json::to_value(&response.content[0]).unwrap()["text"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    /// Reports the given input token count on every response.
+    struct Metered(FakeProvider, i64);
+
+    impl Provider for Metered {
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+
+        fn get_info(&self) -> ProviderInfo {
+            self.0.get_info()
+        }
+
+        fn list_models(
+            &self,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<ModelInfo>, ProviderError>> + Send + '_>>
+        {
+            self.0.list_models()
+        }
+
+        fn complete(
+            &self,
+            request: ChatRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<ChatResponse, ProviderError>> + Send + '_>>
+        {
+            Box::pin(async move {
+                let mut response = self.0.complete(request).await?;
+                response.usage = Some(Usage {
+                    input_tokens: self.1,
+                    output_tokens: 0,
+                    total_tokens: self.1,
+                    reasoning_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                    cost_usd: None,
+                    extensions: Default::default(),
+                });
+                Ok(response)
+            })
+        }
+
+        fn parse_tool_calls(&self, response: &ChatResponse) -> Vec<ToolCall> {
+            self.0.parse_tool_calls(response)
+        }
+    }
+
+    struct PickLast;
+
+    impl EnsembleSelector for PickLast {
+        fn select<'a>(
+            &'a self,
+            _request: &'a ChatRequest,
+            candidates: &'a [FanoutCandidate],
+        ) -> BoxFuture<'a, Result<usize, ProviderError>> {
+            Box::pin(async move { Ok(candidates.len() - 1) })
+        }
+    }
+
+    #[tokio::test]
+    async fn race_returns_the_first_success_and_cancels_the_rest() {
+        let slow = FlakyProvider::new("slow", "late", 0).with_latency(Duration::from_secs(30));
+        let fast = FlakyProvider::new("fast", "early", 0).with_latency(Duration::from_millis(5));
+        let fanout = FanoutProvider::race("ha", vec![Arc::new(slow), Arc::new(fast)]);
+
+        let response = fanout.complete(request()).await.unwrap();
+        assert_eq!(text(&response), "early");
+        assert!(response.degradation.is_none());
+        let report = report(&response);
+        assert_eq!(
+            (report.mode.as_str(), report.selected.as_str()),
+            ("race", "fast")
+        );
+        let outcomes: Vec<_> = report.calls.iter().map(|c| c.outcome).collect();
+        assert_eq!(outcomes, [FanoutOutcome::Cancelled, FanoutOutcome::Ok]);
+    }
+
+    #[tokio::test]
+    async fn primary_failure_is_reported_as_degradation() {
+        let primary = FlakyProvider::new("primary", "a", 1);
+        let backup = FlakyProvider::new("backup", "b", 0).with_latency(Duration::from_millis(5));
+        let fanout = FanoutProvider::race("ha", vec![Arc::new(primary), Arc::new(backup)]);
+
+        let response = fanout.complete(request()).await.unwrap();
+        let degradation = response.degradation.unwrap();
+        assert_eq!(degradation.requested, "primary/m1");
+        assert_eq!(degradation.actual, "backup/m1");
+        assert!(degradation.reason.contains("injected provider failure"));
+
+        // With every provider down the primary's error surfaces.
+        let fanout = FanoutProvider::race(
+            "ha",
+            vec![
+                Arc::new(FlakyProvider::new("primary", "a", 1)),
+                Arc::new(FlakyProvider::new("backup", "b", 1)),
+            ],
+        );
+        let err = fanout.complete(request()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProviderError::Unavailable { provider: Some(ref p), .. } if p == "primary"
+        ));
+    }
+
+    #[tokio::test]
+    async fn ensemble_selects_among_all_successes_and_sums_usage() {
+        let providers: Vec<Arc<dyn Provider>> = vec![
+            Arc::new(Metered(FakeProvider::new("a", "from a"), 10)),
+            Arc::new(FlakyProvider::new("down", "-", 1)),
+            Arc::new(Metered(FakeProvider::new("b", "from b"), 32)),
+        ];
+        let fanout = FanoutProvider::ensemble("eval", providers, Arc::new(PickLast));
+
+        let response = fanout.complete(request()).await.unwrap();
+        assert_eq!(text(&response), "from b");
+        assert_eq!(response.usage.as_ref().unwrap().input_tokens, 42);
+        assert!(response.degradation.is_none());
+        let report = report(&response);
+        assert_eq!(report.selected, "b");
+        assert_eq!(report.calls[1].outcome, FanoutOutcome::Error);
+        assert_eq!(report.calls[2].usage.as_ref().unwrap().input_tokens, 32);
+    }
+}
@@ -33,6 +33,15 @@ pub struct ToolExecuteResponse {
     /// Error message if success is false.
     #[prost(string, tag = "4")]
     pub error: ::prost::alloc::string::String,
+    /// Structured content blocks, alongside or instead of output.
+    #[prost(message, repeated, tag = "5")]
+    pub content: ::prost::alloc::vec::Vec<ContentBlock>,
+    /// JSON array of attachment references (empty = none).
+    #[prost(string, tag = "6")]
+    pub artifacts_json: ::prost::alloc::string::String,
+    /// Rendering hint, e.g. "markdown" (empty = none).
+    #[prost(string, tag = "7")]
+    pub display_hint: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ModuleInfo {
@@ -222,10 +231,34 @@ pub struct ReasoningBlock {
     pub summary: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AudioBlock {
+    /// MIME type of the audio: "audio/wav", "audio/mpeg", "audio/ogg", ...
+    #[prost(string, tag = "1")]
+    pub media_type: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_json: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub transcript: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DocumentBlock {
+    /// MIME type of the document: "application/pdf", "text/plain", ...
+    #[prost(string, tag = "1")]
+    pub media_type: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_json: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub transcript: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ContentBlock {
     #[prost(enumeration = "Visibility", tag = "8")]
     pub visibility: i32,
-    #[prost(oneof = "content_block::Block", tags = "1, 2, 3, 4, 5, 6, 7")]
+    #[prost(enumeration = "CacheHint", tag = "11")]
+    pub cache: i32,
+    #[prost(oneof = "content_block::Block", tags = "1, 2, 3, 4, 5, 6, 7, 9, 10")]
     pub block: ::core::option::Option<content_block::Block>,
 }
 /// Nested message and enum types in `ContentBlock`.
@@ -246,6 +279,10 @@ pub mod content_block {
         ImageBlock(super::ImageBlock),
         #[prost(message, tag = "7")]
         ReasoningBlock(super::ReasoningBlock),
+        #[prost(message, tag = "9")]
+        AudioBlock(super::AudioBlock),
+        #[prost(message, tag = "10")]
+        DocumentBlock(super::DocumentBlock),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -263,6 +300,8 @@ pub struct Message {
     pub tool_call_id: ::prost::alloc::string::String,
     #[prost(string, tag = "6")]
     pub metadata_json: ::prost::alloc::string::String,
+    #[prost(enumeration = "CacheHint", tag = "7")]
+    pub cache: i32,
     #[prost(oneof = "message::Content", tags = "2, 3")]
     pub content: ::core::option::Option<message::Content>,
 }
@@ -403,6 +442,14 @@ pub struct ToolResult {
     pub output_json: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub error_json: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "4")]
+    pub content: ::prost::alloc::vec::Vec<ContentBlock>,
+    /// JSON array of attachment references (empty = none).
+    #[prost(string, tag = "5")]
+    pub artifacts_json: ::prost::alloc::string::String,
+    /// Rendering hint, e.g. "markdown" (empty = none).
+    #[prost(string, tag = "6")]
+    pub display_hint: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HookResult {
@@ -935,6 +982,33 @@ impl Visibility {
         }
     }
 }
+/// Prompt-caching breakpoint (see CacheHint in messages.rs).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CacheHint {
+    Unspecified = 0,
+    Ephemeral = 1,
+}
+impl CacheHint {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "CACHE_HINT_UNSPECIFIED",
+            Self::Ephemeral => "CACHE_HINT_EPHEMERAL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CACHE_HINT_UNSPECIFIED" => Some(Self::Unspecified),
+            "CACHE_HINT_EPHEMERAL" => Some(Self::Ephemeral),
+            _ => None,
+        }
+    }
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum HookAction {
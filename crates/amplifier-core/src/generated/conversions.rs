@@ -38,6 +38,13 @@ impl From<crate::models::ToolResult> for super::amplifier_module::ToolResult {
                 .error
                 .map(|e| to_json_or_warn(&e, "ToolResult error"))
                 .unwrap_or_default(),
+            content: native
+                .content
+                .into_iter()
+                .map(native_content_block_to_proto)
+                .collect(),
+            artifacts_json: native_artifacts_to_proto(&native.artifacts),
+            display_hint: native_display_hint_to_proto(native.display_hint),
         }
     }
 }
@@ -66,10 +73,58 @@ impl From<super::amplifier_module::ToolResult> for crate::models::ToolResult {
                     })
                     .ok()
             },
+            content: proto
+                .content
+                .into_iter()
+                .map(proto_content_block_to_native)
+                .collect(),
+            artifacts: proto_artifacts_to_native(&proto.artifacts_json),
+            display_hint: proto_display_hint_to_native(proto.display_hint),
         }
     }
 }
 
+/// Encode tool-result artifacts as `artifacts_json` (empty when none).
+pub fn native_artifacts_to_proto(artifacts: &[crate::attachments::AttachmentRef]) -> String {
+    if artifacts.is_empty() {
+        String::new()
+    } else {
+        to_json_or_warn(&artifacts, "ToolResult artifacts")
+    }
+}
+
+/// Decode `artifacts_json` (empty means none).
+pub fn proto_artifacts_to_native(json: &str) -> Vec<crate::attachments::AttachmentRef> {
+    if json.is_empty() {
+        Vec::new()
+    } else {
+        from_json_or_default(json, "ToolResult artifacts_json")
+    }
+}
+
+/// Encode a display hint as its snake_case name (empty when none).
+pub fn native_display_hint_to_proto(hint: Option<crate::models::DisplayHint>) -> String {
+    match hint.map(serde_json::to_value) {
+        Some(Ok(serde_json::Value::String(hint))) => hint,
+        _ => String::new(),
+    }
+}
+
+/// Decode a display hint name (empty means none).
+pub fn proto_display_hint_to_native(hint: String) -> Option<crate::models::DisplayHint> {
+    non_empty(hint).and_then(|hint| serde_json::from_value(serde_json::Value::String(hint)).ok())
+}
+
+/// Convert proto content blocks to native ones.
+pub fn proto_content_blocks_to_native(
+    blocks: Vec<super::amplifier_module::ContentBlock>,
+) -> Vec<crate::messages::ContentBlock> {
+    blocks
+        .into_iter()
+        .map(proto_content_block_to_native)
+        .collect()
+}
+
 // ---------------------------------------------------------------------------
 // ModelInfo conversions
 // ---------------------------------------------------------------------------
@@ -181,6 +236,7 @@ impl From<super::amplifier_module::Usage> for crate::messages::Usage {
             reasoning_tokens: proto.reasoning_tokens.map(i64::from),
             cache_read_tokens: proto.cache_read_tokens.map(i64::from),
             cache_write_tokens: proto.cache_creation_tokens.map(i64::from),
+            cost_usd: None,
             extensions: HashMap::new(),
         }
     }
@@ -192,6 +248,7 @@ impl From<super::amplifier_module::Usage> for crate::messages::Usage {
 
 use std::collections::HashMap;
 
+use super::amplifier_module::CacheHint as ProtoCacheHint;
 use super::amplifier_module::Role as ProtoRole;
 use super::amplifier_module::Visibility as ProtoVisibility;
 use crate::messages::Role;
@@ -253,16 +310,64 @@ fn proto_visibility_to_native(vis: i32) -> Option<crate::messages::Visibility> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// CacheHint conversion helpers (private)
+// ---------------------------------------------------------------------------
+
+fn native_cache_to_proto(cache: Option<crate::messages::CacheHint>) -> i32 {
+    match cache {
+        None => ProtoCacheHint::Unspecified as i32,
+        Some(crate::messages::CacheHint::Ephemeral) => ProtoCacheHint::Ephemeral as i32,
+    }
+}
+
+fn proto_cache_to_native(cache: i32) -> Option<crate::messages::CacheHint> {
+    match ProtoCacheHint::try_from(cache) {
+        Ok(ProtoCacheHint::Ephemeral) => Some(crate::messages::CacheHint::Ephemeral),
+        _ => None, // Unspecified or unknown
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ContentBlock conversion helpers (private)
 // ---------------------------------------------------------------------------
 
+/// `None` for proto's empty-string default.
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// Rebuild a media block's `source` map, falling back to just its media
+/// type when the sender did not include `source_json`.
+fn media_source(
+    source_json: &str,
+    media_type: &str,
+    label: &str,
+) -> HashMap<String, serde_json::Value> {
+    if !source_json.is_empty() {
+        return from_json_or_default(source_json, label);
+    }
+    let mut source = HashMap::new();
+    if !media_type.is_empty() {
+        source.insert(
+            "media_type".to_string(),
+            serde_json::Value::String(media_type.to_string()),
+        );
+    }
+    source
+}
+
 fn native_content_block_to_proto(
     block: crate::messages::ContentBlock,
 ) -> super::amplifier_module::ContentBlock {
     use super::amplifier_module::content_block::Block;
     use crate::messages::ContentBlock;
 
+    let cache = native_cache_to_proto(block.cache());
     let (proto_block, vis) = match block {
         ContentBlock::Text {
             text, visibility, ..
@@ -355,11 +460,48 @@ fn native_content_block_to_proto(
             }),
             visibility,
         ),
+        ContentBlock::Audio {
+            source,
+            transcript,
+            visibility,
+            ..
+        } => (
+            Block::AudioBlock(super::amplifier_module::AudioBlock {
+                media_type: source
+                    .get("media_type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                source_json: to_json_or_warn(&source, "Audio source"),
+                transcript: transcript.unwrap_or_default(),
+            }),
+            visibility,
+        ),
+        ContentBlock::Document {
+            source,
+            title,
+            transcript,
+            visibility,
+            ..
+        } => (
+            Block::DocumentBlock(super::amplifier_module::DocumentBlock {
+                media_type: source
+                    .get("media_type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                source_json: to_json_or_warn(&source, "Document source"),
+                title: title.unwrap_or_default(),
+                transcript: transcript.unwrap_or_default(),
+            }),
+            visibility,
+        ),
     };
 
     super::amplifier_module::ContentBlock {
         block: Some(proto_block),
         visibility: native_visibility_to_proto(&vis),
+        cache,
     }
 }
 
@@ -370,11 +512,13 @@ fn proto_content_block_to_native(
     use crate::messages::ContentBlock;
 
     let vis = proto_visibility_to_native(block.visibility);
+    let cache = proto_cache_to_native(block.cache);
 
     match block.block {
         Some(Block::TextBlock(tb)) => ContentBlock::Text {
             text: tb.text,
             visibility: vis,
+            cache,
             extensions: HashMap::new(),
         },
         Some(Block::ThinkingBlock(tb)) => ContentBlock::Thinking {
@@ -407,12 +551,14 @@ fn proto_content_block_to_native(
             name: tc.name,
             input: from_json_or_default(&tc.input_json, "ToolCallBlock input_json"),
             visibility: vis,
+            cache,
             extensions: HashMap::new(),
         },
         Some(Block::ToolResultBlock(tr)) => ContentBlock::ToolResult {
             tool_call_id: tr.tool_call_id,
             output: from_json_or_default(&tr.output_json, "ToolResultBlock output_json"),
             visibility: vis,
+            cache,
             extensions: HashMap::new(),
         },
         Some(Block::ImageBlock(ib)) => ContentBlock::Image {
@@ -422,6 +568,7 @@ fn proto_content_block_to_native(
                 from_json_or_default(&ib.source_json, "ImageBlock source_json")
             },
             visibility: vis,
+            cache,
             extensions: HashMap::new(),
         },
         Some(Block::ReasoningBlock(rb)) => ContentBlock::Reasoning {
@@ -452,11 +599,27 @@ fn proto_content_block_to_native(
             visibility: vis,
             extensions: HashMap::new(),
         },
+        Some(Block::AudioBlock(ab)) => ContentBlock::Audio {
+            source: media_source(&ab.source_json, &ab.media_type, "AudioBlock source_json"),
+            transcript: non_empty(ab.transcript),
+            visibility: vis,
+            cache,
+            extensions: HashMap::new(),
+        },
+        Some(Block::DocumentBlock(db)) => ContentBlock::Document {
+            source: media_source(&db.source_json, &db.media_type, "DocumentBlock source_json"),
+            title: non_empty(db.title),
+            transcript: non_empty(db.transcript),
+            visibility: vis,
+            cache,
+            extensions: HashMap::new(),
+        },
         None => {
             log::warn!("Proto ContentBlock has no block variant set, falling back to empty Text");
             ContentBlock::Text {
                 text: String::new(),
                 visibility: vis,
+                cache,
                 extensions: HashMap::new(),
             }
         }
@@ -495,6 +658,7 @@ pub fn native_message_to_proto(msg: crate::messages::Message) -> super::amplifie
             .metadata
             .map(|m| to_json_or_warn(&m, "Message metadata"))
             .unwrap_or_default(),
+        cache: native_cache_to_proto(msg.cache),
     }
 }
 
@@ -544,6 +708,7 @@ pub fn proto_message_to_native(
                 })
                 .ok()
         },
+        cache: proto_cache_to_native(proto.cache),
         extensions: HashMap::new(),
     })
 }
@@ -999,6 +1164,7 @@ mod tests {
             success: true,
             output: Some(serde_json::json!({"key": "value"})),
             error: None,
+            ..Default::default()
         };
         let proto: super::super::amplifier_module::ToolResult = original.clone().into();
         let restored: crate::models::ToolResult = proto.into();
@@ -1014,12 +1180,35 @@ mod tests {
                 "message".to_string(),
                 serde_json::json!("something failed"),
             )])),
+            ..Default::default()
         };
         let proto: super::super::amplifier_module::ToolResult = original.clone().into();
         let restored: crate::models::ToolResult = proto.into();
         assert_eq!(original, restored);
     }
 
+    #[test]
+    fn tool_result_rich_content_roundtrip() {
+        let original = crate::models::ToolResult::new(true, Some(serde_json::json!("ok")), None)
+            .with_content(vec![crate::messages::ContentBlock::Text {
+                text: "| a | b |".into(),
+                visibility: None,
+                cache: None,
+                extensions: HashMap::new(),
+            }])
+            .with_artifact(crate::attachments::AttachmentRef {
+                uri: "attachment://abc".into(),
+                media_type: "text/csv".into(),
+                size: 12,
+                preview: Some("a,b".into()),
+            })
+            .with_display_hint(crate::models::DisplayHint::Table);
+        let proto: super::super::amplifier_module::ToolResult = original.clone().into();
+        assert_eq!(proto.display_hint, "table");
+        let restored: crate::models::ToolResult = proto.into();
+        assert_eq!(original, restored);
+    }
+
     #[test]
     fn model_info_roundtrip() {
         let original = crate::models::ModelInfo {
@@ -1044,6 +1233,7 @@ mod tests {
             reasoning_tokens: Some(20),
             cache_read_tokens: Some(10),
             cache_write_tokens: None, // 0 in proto, None when restored
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let proto: super::super::amplifier_module::Usage = original.clone().into();
@@ -1068,6 +1258,7 @@ mod tests {
             reasoning_tokens: Some(50),
             cache_read_tokens: Some(30),
             cache_write_tokens: Some(20),
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let proto: super::super::amplifier_module::Usage = original.clone().into();
@@ -1090,6 +1281,7 @@ mod tests {
             reasoning_tokens: Some(0),
             cache_read_tokens: Some(0),
             cache_write_tokens: Some(0),
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let proto: super::super::amplifier_module::Usage = original.clone().into();
@@ -1152,6 +1344,7 @@ mod tests {
             reasoning_tokens: None,
             cache_read_tokens: None,
             cache_write_tokens: None,
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let proto: super::super::amplifier_module::Usage = original.into();
@@ -1167,6 +1360,7 @@ mod tests {
             reasoning_tokens: None,
             cache_read_tokens: None,
             cache_write_tokens: None,
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let proto: super::super::amplifier_module::Usage = original.into();
@@ -1182,6 +1376,7 @@ mod tests {
             reasoning_tokens: None,
             cache_read_tokens: None,
             cache_write_tokens: None,
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let proto: super::super::amplifier_module::Usage = original.into();
@@ -1273,6 +1468,7 @@ mod tests {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1292,11 +1488,13 @@ mod tests {
             content: MessageContent::Blocks(vec![ContentBlock::Text {
                 text: "thinking...".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }]),
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1305,6 +1503,38 @@ mod tests {
         assert_eq!(restored.content, original.content);
     }
 
+    #[test]
+    fn cache_hints_roundtrip() {
+        use crate::messages::{CacheHint, ContentBlock, Message, MessageContent};
+
+        let original = Message {
+            role: Role::User,
+            content: MessageContent::Blocks(vec![
+                ContentBlock::Text {
+                    text: "long context".into(),
+                    visibility: None,
+                    cache: Some(CacheHint::Ephemeral),
+                    extensions: HashMap::new(),
+                },
+                ContentBlock::Text {
+                    text: "question".into(),
+                    visibility: None,
+                    cache: None,
+                    extensions: HashMap::new(),
+                },
+            ]),
+            name: None,
+            tool_call_id: None,
+            metadata: None,
+            cache: Some(CacheHint::Ephemeral),
+            extensions: HashMap::new(),
+        };
+        let proto = super::native_message_to_proto(original.clone());
+        assert_eq!(proto.cache, super::ProtoCacheHint::Ephemeral as i32);
+        let restored = super::proto_message_to_native(proto).expect("should succeed");
+        assert_eq!(restored, original);
+    }
+
     #[test]
     fn message_with_tool_call_id_roundtrip() {
         use crate::messages::{Message, MessageContent};
@@ -1318,6 +1548,7 @@ mod tests {
                 "source".to_string(),
                 serde_json::json!("test"),
             )])),
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1347,6 +1578,7 @@ mod tests {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1368,6 +1600,7 @@ mod tests {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1386,11 +1619,13 @@ mod tests {
                 name: "read_file".into(),
                 input: HashMap::from([("path".to_string(), serde_json::json!("/tmp/test.txt"))]),
                 visibility: Some(Visibility::Developer),
+                cache: None,
                 extensions: HashMap::new(),
             }]),
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1408,11 +1643,13 @@ mod tests {
                 tool_call_id: "call_456".into(),
                 output: serde_json::json!({"status": "ok", "lines": 42}),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }]),
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1433,11 +1670,13 @@ mod tests {
             content: MessageContent::Blocks(vec![ContentBlock::Image {
                 source,
                 visibility: Some(Visibility::User),
+                cache: None,
                 extensions: HashMap::new(),
             }]),
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1463,6 +1702,55 @@ mod tests {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
+            extensions: HashMap::new(),
+        };
+        let proto = super::native_message_to_proto(original.clone());
+        let restored = super::proto_message_to_native(proto).expect("should succeed");
+        assert_eq!(restored.content, original.content);
+    }
+
+    #[test]
+    fn content_block_audio_and_document_roundtrip() {
+        use crate::messages::{ContentBlock, Message, MessageContent, Visibility};
+
+        let original = Message {
+            role: Role::User,
+            content: MessageContent::Blocks(vec![
+                ContentBlock::Audio {
+                    source: HashMap::from([
+                        ("type".to_string(), serde_json::json!("base64")),
+                        ("media_type".to_string(), serde_json::json!("audio/mpeg")),
+                        ("data".to_string(), serde_json::json!("SUQz")),
+                    ]),
+                    transcript: Some("hello there".into()),
+                    visibility: Some(Visibility::User),
+                    cache: None,
+                    extensions: HashMap::new(),
+                },
+                ContentBlock::Document {
+                    source: HashMap::from([
+                        ("type".to_string(), serde_json::json!("url")),
+                        (
+                            "media_type".to_string(),
+                            serde_json::json!("application/pdf"),
+                        ),
+                        (
+                            "url".to_string(),
+                            serde_json::json!("https://example.com/a.pdf"),
+                        ),
+                    ]),
+                    title: Some("Spec".into()),
+                    transcript: None,
+                    visibility: None,
+                    cache: None,
+                    extensions: HashMap::new(),
+                },
+            ]),
+            name: None,
+            tool_call_id: None,
+            metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let proto = super::native_message_to_proto(original.clone());
@@ -1470,6 +1758,31 @@ mod tests {
         assert_eq!(restored.content, original.content);
     }
 
+    #[test]
+    fn document_block_without_source_json_keeps_media_type() {
+        use super::super::amplifier_module;
+        use crate::messages::ContentBlock;
+
+        let proto = amplifier_module::ContentBlock {
+            block: Some(amplifier_module::content_block::Block::DocumentBlock(
+                amplifier_module::DocumentBlock {
+                    media_type: "text/plain".into(),
+                    source_json: String::new(),
+                    title: String::new(),
+                    transcript: "notes".into(),
+                },
+            )),
+            visibility: 0,
+            cache: 0,
+        };
+        let block = super::proto_content_block_to_native(proto);
+        assert_eq!(block.media_type(), Some("text/plain"));
+        assert!(matches!(
+            block,
+            ContentBlock::Document { title: None, transcript: Some(ref t), .. } if t == "notes"
+        ));
+    }
+
     #[test]
     fn message_none_content_returns_error() {
         use super::super::amplifier_module;
@@ -1480,6 +1793,7 @@ mod tests {
             name: String::new(),
             tool_call_id: String::new(),
             metadata_json: String::new(),
+            cache: 0,
         };
         let result = super::proto_message_to_native(proto);
         assert!(result.is_err(), "None content should return Err");
@@ -1498,6 +1812,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -1541,6 +1856,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: Some(vec![ToolSpec {
@@ -1608,6 +1924,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: Some(vec![
@@ -1667,6 +1984,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -1711,6 +2029,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -1768,6 +2087,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -1811,6 +2131,7 @@ mod tests {
             content: vec![crate::messages::ContentBlock::Text {
                 text: "Hello, world!".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tool_calls: None,
@@ -1842,6 +2163,7 @@ mod tests {
                 crate::messages::ContentBlock::Text {
                     text: "Here's the answer.".into(),
                     visibility: None,
+                    cache: None,
                     extensions: HashMap::new(),
                 },
                 crate::messages::ContentBlock::Thinking {
@@ -1868,6 +2190,7 @@ mod tests {
                 reasoning_tokens: Some(50),
                 cache_read_tokens: Some(20),
                 cache_write_tokens: None,
+                cost_usd: None,
                 extensions: HashMap::new(),
             }),
             degradation: Some(Degradation {
@@ -1944,6 +2267,7 @@ mod tests {
             content: vec![crate::messages::ContentBlock::Text {
                 text: "Let me look that up.".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tool_calls: Some(vec![
@@ -2348,6 +2672,7 @@ mod tests {
                     name: None,
                     tool_call_id: None,
                     metadata: None,
+                    cache: None,
                     extensions: HashMap::new(),
                 },
                 Message {
@@ -2355,11 +2680,13 @@ mod tests {
                     content: MessageContent::Blocks(vec![ContentBlock::Text {
                         text: "Help me!".into(),
                         visibility: None,
+                        cache: None,
                         extensions: HashMap::new(),
                     }]),
                     name: None,
                     tool_call_id: None,
                     metadata: None,
+                    cache: None,
                     extensions: HashMap::new(),
                 },
             ],
@@ -2403,6 +2730,7 @@ mod tests {
             content: vec![ContentBlock::Text {
                 text: "hello dual-write".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tool_calls: None,
@@ -2464,6 +2792,7 @@ mod tests {
                     ),
                 ),
                 visibility: 0,
+                cache: 0,
             }],
             tool_calls: vec![],
             usage: None,
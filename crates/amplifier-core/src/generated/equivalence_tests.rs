@@ -42,6 +42,7 @@ mod tests {
             success: true,
             output_json: r#"{"value": 42}"#.into(),
             error_json: String::new(),
+            ..Default::default()
         };
         assert!(result.success);
         assert_eq!(result.output_json, r#"{"value": 42}"#);
@@ -52,6 +53,7 @@ mod tests {
             success: false,
             output_json: String::new(),
             error_json: r#"{"code":"NOT_FOUND"}"#.into(),
+            ..Default::default()
         };
         assert!(!err_result.success);
         assert!(err_result.output_json.is_empty());
@@ -125,6 +127,7 @@ mod tests {
             tool_call_id: String::new(),
             metadata_json: String::new(),
             content: Some(message::Content::TextContent("Hello".into())),
+            cache: 0,
         };
         // ToolSpecProto is the proto message used inside ChatRequest.tools;
         // ToolSpec is a separate proto message used in the tool module RPC responses.
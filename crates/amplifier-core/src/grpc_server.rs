@@ -16,6 +16,8 @@ use crate::generated::conversions::{
     native_chat_response_to_proto, native_hook_result_to_proto, native_message_to_proto,
     proto_chat_request_to_native, proto_message_to_native,
 };
+use crate::provider_invoker::ProviderInvoker;
+use crate::tool_executor::ToolExecutor;
 
 /// Shared-secret authentication interceptor for KernelService.
 /// Validates the `x-amplifier-token` metadata header on every request.
@@ -133,8 +135,11 @@ impl KernelService for KernelServiceImpl {
         // Convert proto ChatRequest → native ChatRequest
         let native_request = proto_chat_request_to_native(proto_chat_request);
 
-        // Call the provider
-        match provider.complete(native_request).await {
+        // Call the provider through the provider:pre / provider:post hooks
+        match ProviderInvoker::from_coordinator(&self.coordinator)
+            .complete(provider.as_ref(), native_request)
+            .await
+        {
             Ok(native_response) => {
                 let proto_response = native_chat_response_to_proto(&native_response);
                 Ok(Response::new(proto_response))
@@ -178,11 +183,14 @@ impl KernelService for KernelServiceImpl {
         // Convert proto ChatRequest → native ChatRequest
         let native_request = proto_chat_request_to_native(proto_chat_request);
 
-        // Call the provider
-        let native_response = provider.complete(native_request).await.map_err(|e| {
-            log::error!("Provider completion failed for {provider_name}: {e}");
-            Status::internal("Provider completion failed")
-        })?;
+        // Call the provider through the provider:pre / provider:post hooks
+        let native_response = ProviderInvoker::from_coordinator(&self.coordinator)
+            .complete(provider.as_ref(), native_request)
+            .await
+            .map_err(|e| {
+                log::error!("Provider completion failed for {provider_name}: {e}");
+                Status::internal("Provider completion failed")
+            })?;
 
         let proto_response = native_chat_response_to_proto(&native_response);
 
@@ -224,32 +232,20 @@ impl KernelService for KernelServiceImpl {
             Status::invalid_argument("Invalid input JSON")
         })?;
 
-        // Execute the tool
-        match tool.execute(input).await {
-            Ok(result) => {
-                let output_json = result
-                    .output
-                    .map(|v| {
-                        serde_json::to_string(&v).unwrap_or_else(|e| {
-                            log::warn!("Failed to serialize tool result output to JSON: {e}");
-                            String::new()
-                        })
-                    })
-                    .unwrap_or_default();
-                let error_json = result
-                    .error
-                    .map(|e| {
-                        serde_json::to_string(&e).unwrap_or_else(|ser_err| {
-                            log::warn!("Failed to serialize tool result error to JSON: {ser_err}");
-                            String::new()
-                        })
-                    })
-                    .unwrap_or_default();
-                Ok(Response::new(amplifier_module::ToolResult {
-                    success: result.success,
-                    output_json,
-                    error_json,
-                }))
+        // Execute the tool, bounded by the turn deadline if one is set, with
+        // output post-processing applied
+        let executor = ToolExecutor::from_coordinator(&self.coordinator);
+        match executor.execute(tool.as_ref(), "", input).await {
+            Ok(result) => Ok(Response::new(result.into())),
+            Err(e @ crate::errors::ToolError::Timeout { .. }) => {
+                log::warn!("Tool execution hit turn deadline for {tool_name}: {e}");
+                Err(Status::deadline_exceeded(
+                    "Tool execution exceeded turn deadline",
+                ))
+            }
+            Err(e @ crate::errors::ToolError::Cancelled { .. }) => {
+                log::warn!("Tool execution cancelled for {tool_name}: {e}");
+                Err(Status::cancelled("Tool execution was cancelled"))
             }
             Err(e) => {
                 log::error!("Tool execution failed for {tool_name}: {e}");
@@ -1138,6 +1134,7 @@ mod tests {
                 name: String::new(),
                 tool_call_id: String::new(),
                 metadata_json: String::new(),
+                cache: 0,
             }),
         });
 
@@ -1165,6 +1162,7 @@ mod tests {
                 name: String::new(),
                 tool_call_id: String::new(),
                 metadata_json: String::new(),
+                cache: 0,
             }),
         });
 
@@ -1287,6 +1285,7 @@ mod tests {
                 name: String::new(),
                 tool_call_id: String::new(),
                 metadata_json: String::new(),
+                cache: 0,
             }),
         });
 
@@ -1330,6 +1329,7 @@ mod tests {
                 name: String::new(),
                 tool_call_id: String::new(),
                 metadata_json: String::new(),
+                cache: 0,
             }],
             tools: vec![],
             response_format: None,
@@ -1900,6 +1900,7 @@ mod tests {
                 name: String::new(),
                 tool_call_id: String::new(),
                 metadata_json: String::new(),
+                cache: 0,
             }),
         });
         service.add_message(add_request).await.unwrap();
@@ -0,0 +1,298 @@
+//! Periodic `session:heartbeat` events during `execute()`.
+//!
+//! Long agent turns can run for minutes without emitting anything a
+//! dashboard would notice. With `session.heartbeat` set, the session emits
+//! [`SESSION_HEARTBEAT`](crate::events::SESSION_HEARTBEAT) every
+//! `interval_secs` while the orchestrator runs:
+//!
+//! ```json
+//! {"session_id": "...", "status": "running", "elapsed_ms": 30000,
+//!  "usage": {"input_tokens": 5400, "output_tokens": 820, "total_tokens": 6220},
+//!  "in_flight_tools": ["bash"]}
+//! ```
+//!
+//! | Field             | Meaning                                                         |
+//! |-------------------|-----------------------------------------------------------------|
+//! | `status`          | `running`, or `cancelling` once cancellation was requested      |
+//! | `elapsed_ms`      | time since the orchestrator started, on the session's clock     |
+//! | `usage`           | usage summed over this execution's provider responses so far (`null` before the first) |
+//! | `in_flight_tools` | tools whose `tool:pre` has no `tool:post` / `tool:error` yet    |
+//!
+//! The figures come from a [`TurnActivity`] hook registered for the
+//! duration of the execution, which reads the same payloads as the
+//! [`TurnRecorder`](crate::turn::TurnRecorder). Heartbeats are timed on the
+//! hook registry's [`Clock`](crate::clock::Clock) and never delay the
+//! orchestrator.
+//!
+//! ```json
+//! {"session": {"heartbeat": {"interval_secs": 30}}}
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::HookError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::messages::{ChatResponse, Usage};
+use crate::models::HookResult;
+use crate::traits::HookHandler;
+use crate::turn::add_usage;
+
+// ---------------------------------------------------------------------------
+// HeartbeatConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.heartbeat` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeartbeatConfig {
+    /// Seconds between heartbeats; `0` disables them.
+    pub interval_secs: f64,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 30.0,
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// Read `session.heartbeat` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent, and the defaults when it
+    /// is malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("heartbeat"))?;
+        Some(
+            serde_json::from_value(raw.clone())
+                .map_err(|e| log::warn!("Ignoring malformed session.heartbeat config: {e}"))
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The heartbeat period, or `None` when disabled.
+    pub fn interval(&self) -> Option<Duration> {
+        Duration::try_from_secs_f64(self.interval_secs)
+            .ok()
+            .filter(|interval| !interval.is_zero())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TurnActivity
+// ---------------------------------------------------------------------------
+
+/// Events a [`TurnActivity`] must be registered on.
+pub const TRACKED_EVENTS: &[&str] = &[
+    events::PROVIDER_RESPONSE,
+    events::PROVIDER_POST,
+    events::TOOL_PRE,
+    events::TOOL_POST,
+    events::TOOL_ERROR,
+];
+
+#[derive(Default)]
+struct Activity {
+    usage: Option<Usage>,
+    /// Usage from `provider:post`, reported only when no
+    /// `provider:response` carried any.
+    post_usage: Option<Usage>,
+    in_flight: Vec<String>,
+}
+
+/// Running usage and in-flight tools of one execution, read from hook
+/// payloads.
+///
+/// Always returns [`HookResult::default()`] (continue).
+#[derive(Default)]
+pub struct TurnActivity {
+    activity: Mutex<Activity>,
+}
+
+impl TurnActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Usage summed over the provider responses seen so far.
+    pub fn usage(&self) -> Option<Usage> {
+        let activity = self.activity.lock().unwrap();
+        activity
+            .usage
+            .clone()
+            .or_else(|| activity.post_usage.clone())
+    }
+
+    /// Tools started and not yet finished, in start order.
+    pub fn in_flight_tools(&self) -> Vec<String> {
+        self.activity.lock().unwrap().in_flight.clone()
+    }
+
+    fn record(&self, event: &str, data: &Value) {
+        let tool_name = || {
+            data.get("tool_name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let mut activity = self.activity.lock().unwrap();
+        match event {
+            events::PROVIDER_RESPONSE | events::PROVIDER_POST => {
+                let Some(usage) = data
+                    .get("response")
+                    .and_then(|r| serde_json::from_value::<ChatResponse>(r.clone()).ok())
+                    .and_then(|r| r.usage)
+                else {
+                    return;
+                };
+                let total = if event == events::PROVIDER_RESPONSE {
+                    &mut activity.usage
+                } else {
+                    &mut activity.post_usage
+                };
+                *total = Some(match total.take() {
+                    Some(total) => add_usage(total, &usage),
+                    None => usage,
+                });
+            }
+            events::TOOL_PRE => activity.in_flight.push(tool_name()),
+            events::TOOL_POST | events::TOOL_ERROR => {
+                let name = tool_name();
+                if let Some(i) = activity.in_flight.iter().position(|n| *n == name) {
+                    activity.in_flight.remove(i);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl HookHandler for TurnActivity {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        self.record(event, &data);
+        Box::pin(async { Ok(HookResult::default()) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Emission
+// ---------------------------------------------------------------------------
+
+/// Drive `run` to completion, emitting `session:heartbeat` with the payload
+/// built by `beat` every `interval` on `hooks`' clock until it finishes.
+pub async fn with_heartbeat<F>(
+    hooks: &HookRegistry,
+    interval: Duration,
+    beat: impl Fn() -> Value,
+    run: F,
+) -> F::Output
+where
+    F: Future,
+{
+    let clock = hooks.clock();
+    let ticker = async {
+        loop {
+            clock.sleep(interval).await;
+            hooks.emit(events::SESSION_HEARTBEAT, beat()).await;
+        }
+    };
+    tokio::select! {
+        biased;
+        output = run => output,
+        _ = ticker => unreachable!("the heartbeat loop never ends"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeHookHandler, ManualClock};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn response(input: i64, output: i64) -> Value {
+        json!({"response": {
+            "content": [],
+            "usage": {"input_tokens": input, "output_tokens": output, "total_tokens": input + output}
+        }})
+    }
+
+    #[test]
+    fn activity_tracks_usage_and_in_flight_tools() {
+        let activity = TurnActivity::new();
+        assert_eq!(activity.usage(), None);
+
+        activity.record(events::PROVIDER_POST, &response(100, 10));
+        assert_eq!(activity.usage().unwrap().total_tokens, 110);
+        activity.record(events::PROVIDER_RESPONSE, &response(100, 10));
+        activity.record(events::PROVIDER_RESPONSE, &response(200, 20));
+        assert_eq!(activity.usage().unwrap().total_tokens, 330);
+
+        activity.record(events::TOOL_PRE, &json!({"tool_name": "bash"}));
+        activity.record(events::TOOL_PRE, &json!({"tool_name": "grep"}));
+        activity.record(events::TOOL_PRE, &json!({"tool_name": "bash"}));
+        activity.record(events::TOOL_POST, &json!({"tool_name": "bash"}));
+        activity.record(events::TOOL_ERROR, &json!({"tool_name": "grep"}));
+        assert_eq!(activity.in_flight_tools(), ["bash"]);
+    }
+
+    #[tokio::test]
+    async fn heartbeats_are_emitted_until_the_run_finishes() {
+        let clock = ManualClock::default();
+        let hooks = HookRegistry::new();
+        hooks.set_clock(Arc::new(clock.clone()));
+        let observer = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::SESSION_HEARTBEAT, observer.clone(), 0, None);
+
+        let (finish, finished) = tokio::sync::oneshot::channel::<()>();
+        let drive = async {
+            for _ in 0..2 {
+                tokio::task::yield_now().await;
+                clock.advance(Duration::from_secs(5));
+                tokio::task::yield_now().await;
+            }
+            finish.send(()).unwrap();
+        };
+        let run = with_heartbeat(
+            &hooks,
+            Duration::from_secs(5),
+            || json!({"elapsed_ms": clock.elapsed().as_millis() as u64}),
+            async { finished.await.unwrap() },
+        );
+        tokio::join!(run, drive);
+
+        let beats: Vec<_> = observer
+            .recorded_events()
+            .into_iter()
+            .map(|(_, data)| data["elapsed_ms"].clone())
+            .collect();
+        assert_eq!(beats, [json!(5000), json!(10000)]);
+    }
+
+    #[test]
+    fn zero_interval_disables_heartbeats() {
+        assert_eq!(HeartbeatConfig { interval_secs: 0.0 }.interval(), None);
+        assert_eq!(
+            HeartbeatConfig::default().interval(),
+            Some(Duration::from_secs(30))
+        );
+        assert!(HeartbeatConfig::from_session_config(&HashMap::new()).is_none());
+    }
+}
@@ -0,0 +1,270 @@
+//! Declarative hook registration.
+//!
+//! A mount plan can list hook subscriptions instead of registering handlers
+//! in code. The host provides handler implementations by name (see
+//! [`Session::provide_hook_handler`](crate::session::Session::provide_hook_handler)),
+//! and [`Session::initialize`](crate::session::Session::initialize) registers
+//! every enabled subscription on the session's hook registry.
+//!
+//! # Configuration
+//!
+//! Read from `session.hooks.subscriptions`:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "hooks": {
+//!       "subscriptions": [
+//!         {"event": "tool:pre", "handler": "approval-gate", "priority": -10},
+//!         {"event": "tool:*", "handler": "audit-log", "phase": "observation"},
+//!         {"event": "tool:pre", "handler": "rm-guard", "condition": "data.tool_name == \"bash\""},
+//!         {"event": "llm:request", "handler": "audit-log", "enabled": false}
+//!       ]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! | Field      | Default            | Meaning                                  |
+//! |------------|--------------------|------------------------------------------|
+//! | `event`    | required           | Event name passed to `register`          |
+//! | `handler`  | required           | Name the host provided the handler under |
+//! | `priority` | `0`                | Lower runs first within the phase        |
+//! | `phase`    | `"policy"`         | [`HookPhase`]                            |
+//! | `enabled`  | `true`             | Disabled entries are skipped             |
+//! | `name`     | `"<handler>"`      | Registration name (for listing/removal)  |
+//! | `condition`| none               | [`HookCondition`] the payload must meet  |
+//!
+//! # Ordering
+//!
+//! Handlers run by phase, then priority. Subscriptions with the same phase
+//! and priority run in the order they are listed, and after handlers already
+//! registered with that phase and priority. Either every enabled
+//! subscription is registered or, if one names an unknown handler, none is.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::SessionError;
+use crate::hooks::condition::HookCondition;
+use crate::hooks::{HookPhase, HookRegistry};
+use crate::traits::HookHandler;
+
+// ---------------------------------------------------------------------------
+// HookSubscription
+// ---------------------------------------------------------------------------
+
+/// One declared hook registration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HookSubscription {
+    pub event: String,
+    /// Name of the handler implementation.
+    pub handler: String,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub phase: HookPhase,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Registration name; defaults to `handler`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Only call the handler for payloads this holds for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<HookCondition>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl HookSubscription {
+    /// Read `session.hooks.subscriptions`. A missing section yields an empty
+    /// list; a malformed one is logged and ignored.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Vec<Self> {
+        let Some(raw) = config
+            .get("session")
+            .and_then(|s| s.get("hooks"))
+            .and_then(|h| h.get("subscriptions"))
+        else {
+            return Vec::new();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.hooks.subscriptions config: {e}");
+            Vec::new()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HookHandlerSet
+// ---------------------------------------------------------------------------
+
+/// Handler implementations available to subscriptions, by name.
+#[derive(Clone, Default)]
+pub struct HookHandlerSet {
+    handlers: HashMap<String, Arc<dyn HookHandler>>,
+}
+
+impl fmt::Debug for HookHandlerSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<_> = self.handlers.keys().collect();
+        names.sort();
+        f.debug_struct("HookHandlerSet")
+            .field("handlers", &names)
+            .finish()
+    }
+}
+
+impl HookHandlerSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `handler` under `name`.
+    pub fn with(mut self, name: impl Into<String>, handler: Arc<dyn HookHandler>) -> Self {
+        self.insert(name, handler);
+        self
+    }
+
+    /// Add or replace the handler under `name`.
+    pub fn insert(&mut self, name: impl Into<String>, handler: Arc<dyn HookHandler>) {
+        self.handlers.insert(name.into(), handler);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn HookHandler>> {
+        self.handlers.get(name)
+    }
+
+    /// Register every enabled subscription on `registry`, in order.
+    ///
+    /// Returns the number registered. Registrations last as long as the
+    /// registry.
+    ///
+    /// # Errors
+    ///
+    /// `SessionError::HookHandlerNotFound` if an enabled subscription names a
+    /// handler this set does not have; nothing is registered in that case.
+    pub fn apply(
+        &self,
+        subscriptions: &[HookSubscription],
+        registry: &HookRegistry,
+    ) -> Result<usize, SessionError> {
+        let resolved = subscriptions
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| match self.handlers.get(&s.handler) {
+                Some(handler) => Ok((s, Arc::clone(handler))),
+                None => Err(SessionError::HookHandlerNotFound {
+                    handler: s.handler.clone(),
+                    event: s.event.clone(),
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (subscription, handler) in &resolved {
+            let name = subscription
+                .name
+                .clone()
+                .unwrap_or_else(|| subscription.handler.clone());
+            let _unregister = match &subscription.condition {
+                Some(condition) => registry.register_with_condition(
+                    &subscription.event,
+                    Arc::clone(handler),
+                    subscription.phase,
+                    subscription.priority,
+                    Some(name),
+                    condition.clone(),
+                ),
+                None => registry.register_in_phase(
+                    &subscription.event,
+                    Arc::clone(handler),
+                    subscription.phase,
+                    subscription.priority,
+                    Some(name),
+                ),
+            };
+        }
+        Ok(resolved.len())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeHookHandler;
+    use serde_json::json;
+
+    fn subscriptions(list: Value) -> Vec<HookSubscription> {
+        let config = HashMap::from([(
+            "session".to_string(),
+            json!({"hooks": {"subscriptions": list}}),
+        )]);
+        HookSubscription::from_session_config(&config)
+    }
+
+    #[test]
+    fn parses_defaults_and_ignores_malformed_config() {
+        let parsed = subscriptions(json!([{"event": "tool:pre", "handler": "audit"}]));
+        assert_eq!(
+            parsed,
+            vec![HookSubscription {
+                event: "tool:pre".into(),
+                handler: "audit".into(),
+                priority: 0,
+                phase: HookPhase::Policy,
+                enabled: true,
+                name: None,
+                condition: None,
+            }]
+        );
+        assert!(subscriptions(json!([{"event": "tool:pre"}])).is_empty());
+        assert!(subscriptions(
+            json!([{"event": "tool:pre", "handler": "audit", "condition": "data =="}])
+        )
+        .is_empty());
+        assert!(HookSubscription::from_session_config(&HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn applies_in_phase_priority_then_declared_order() {
+        let registry = HookRegistry::new();
+        let handlers = HookHandlerSet::new()
+            .with("a", Arc::new(FakeHookHandler::new()))
+            .with("b", Arc::new(FakeHookHandler::new()));
+        let list = subscriptions(json!([
+            {"event": "tool:pre", "handler": "a", "name": "observe", "phase": "observation"},
+            {"event": "tool:pre", "handler": "b", "name": "second"},
+            {"event": "tool:pre", "handler": "a", "name": "third"},
+            {"event": "tool:pre", "handler": "a", "name": "first", "priority": -1},
+            {"event": "tool:pre", "handler": "b", "name": "off", "enabled": false}
+        ]));
+
+        assert_eq!(handlers.apply(&list, &registry).unwrap(), 4);
+        let names = &registry.list_handlers(Some("tool:pre"))["tool:pre"];
+        assert_eq!(names, &vec!["first", "second", "third", "observe"]);
+    }
+
+    #[test]
+    fn unknown_handler_registers_nothing() {
+        let registry = HookRegistry::new();
+        let handlers = HookHandlerSet::new().with("a", Arc::new(FakeHookHandler::new()));
+        let list = subscriptions(json!([
+            {"event": "tool:pre", "handler": "a"},
+            {"event": "tool:post", "handler": "missing"},
+            {"event": "tool:post", "handler": "disabled", "enabled": false}
+        ]));
+
+        let err = handlers.apply(&list, &registry).unwrap_err();
+        assert_eq!(err.code(), "session.hook_handler_not_found");
+        assert!(registry.list_handlers(None).is_empty());
+    }
+}
@@ -19,34 +19,263 @@
 //!
 //! **Action precedence:** Deny > AskUser > InjectContext > Modify > Continue
 //!
+//! # Phases
+//!
+//! Every handler belongs to a [`HookPhase`]; handlers run phase by phase, and
+//! by priority within a phase, so modules can order themselves relative to
+//! each other without agreeing on absolute priority numbers.
+//!
+//! | Phase           | Deny honored | Results used | Runs after a Deny |
+//! |-----------------|--------------|--------------|-------------------|
+//! | `PreValidation` | yes          | yes          | no                |
+//! | `Policy`        | yes          | yes          | no                |
+//! | `Mutation`      | no (ignored) | yes          | no                |
+//! | `Observation`   | no (ignored) | no           | **yes**           |
+//!
+//! [`register()`](HookRegistry::register) places handlers in `Policy`, which
+//! keeps the behaviour of handlers written before phases existed.
+//!
 //! # Connections
 //!
 //! - [`HookHandler`](crate::traits::HookHandler) trait defines the handler contract.
 //! - [`HookResult`] and [`HookAction`] from [`crate::models`] define results.
 //! - Event names come from [`crate::events`].
-
-use std::collections::HashMap;
+//!
+//! # Concurrency
+//!
+//! The handler table is an immutable snapshot behind an [`ArcSwap`]. `emit()`
+//! loads the current snapshot without locking, so concurrent emits (from
+//! several sessions sharing a registry) never contend with each other or
+//! with registration. `register()` and unregister build a new table and swap
+//! it in atomically; an emit already in flight finishes against the snapshot
+//! it loaded.
+//!
+//! # Timing
+//!
+//! [`on_handler_timing()`](HookRegistry::on_handler_timing) observers are told
+//! how long each handler call took (used for hook latency metrics). Nothing
+//! is timed while no observer is installed.
+//!
+//! # Clock
+//!
+//! Event timestamps come from the registry's [`Clock`]
+//! ([`set_clock()`](HookRegistry::set_clock)); tests install a
+//! [`ManualClock`](crate::testing::ManualClock) to make them deterministic.
+//!
+//! # Snapshots
+//!
+//! [`snapshot()`](HookRegistry::snapshot) captures the handler table and
+//! default fields; [`restore()`](HookRegistry::restore) puts them back, and
+//! [`scoped()`](HookRegistry::scoped) does both around a guard's lifetime.
+//! Tests and sub-agent setups use this to add handlers temporarily.
+//!
+//! # Replay
+//!
+//! With [`enable_replay()`](HookRegistry::enable_replay), the registry keeps
+//! the last N payloads passed to [`emit()`](HookRegistry::emit) — including
+//! events nobody was listening for. A handler mounted mid-session can then
+//! [`register_with_replay()`](HookRegistry::register_with_replay) to receive
+//! the recent events for its event name before live ones, so observability
+//! modules mounted after `session:start` still see the session begin.
+//! Replayed payloads carry `"replayed": true`; their results are ignored.
+//! The buffer is charged to the registry's
+//! [`MemoryAccountant`] ([`set_memory()`](HookRegistry::set_memory)) under
+//! `"hook_replay"`.
+//!
+//! # Filtering
+//!
+//! [`set_event_filter()`](HookRegistry::set_event_filter) installs an
+//! [`EventFilter`] that can disable, level-filter or sample events at emit
+//! time without touching registrations (see [`crate::event_filter`]).
+//!
+//! # Conditions
+//!
+//! [`register_with_condition()`](HookRegistry::register_with_condition)
+//! attaches a declarative [`HookCondition`] to a handler; the handler is
+//! skipped for events the condition does not hold for (see [`condition`]).
+//!
+//! # Disabling handlers
+//!
+//! [`set_handler_enabled()`](HookRegistry::set_handler_enabled) mutes a
+//! handler by name on every event, and
+//! [`set_handler_enabled_for()`](HookRegistry::set_handler_enabled_for) on
+//! one event, so an operator can switch off a misbehaving hook at runtime.
+//! A disabled handler keeps its registration, phase, priority and name and
+//! still appears in [`list_handlers()`](HookRegistry::list_handlers); it is
+//! just not called until re-enabled. The enabled flag is part of the handler
+//! table, so [`restore()`](HookRegistry::restore) also restores it.
+//!
+//! # Correlation
+//!
+//! Payloads without a `turn_id` or `tool_call_id` get the ones of the
+//! emitting task's [`correlation::scope`], after default fields are merged.
+//!
+//! # Injection sources
+//!
+//! The registry remembers the last few merged `inject_context` texts and the
+//! handlers that produced them, so a
+//! [`ProvenanceContext`](crate::provenance::ProvenanceContext) can attribute
+//! the message an orchestrator adds for an injection to its hook.
+//!
+//! With [`set_injection_queue()`](HookRegistry::set_injection_queue), the
+//! results are first admitted against per-handler quotas and the turn's
+//! injection budget, and merged in priority order (see
+//! [`crate::injection_queue`]).
+//!
+//! # Built-in handlers
+//!
+//! [`builtin`] has logging, token-budget and content-filter handlers that the
+//! mount plan can subscribe by name.
+//!
+//! # Latency budgets
+//!
+//! [`set_latency_budget()`](HookRegistry::set_latency_budget) times every
+//! handler call against a per-handler budget, emits `hook:slow_handler` for
+//! calls over it and can move chronically slow observers off the emitting
+//! task (see [`latency`]).
+//!
+//! # Result size limit
+//!
+//! [`set_data_limit()`](HookRegistry::set_data_limit) bounds the size of the
+//! `data` each handler returns; larger values are spilled to an attachment
+//! store and replaced by references (see [`spill`]).
+
+pub mod builtin;
+pub mod condition;
+pub mod latency;
+pub mod spill;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use arc_swap::{ArcSwap, ArcSwapOption};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::models::{HookAction, HookResult};
+use crate::clock::{self, Clock};
+use crate::correlation;
+use crate::event_filter::EventFilter;
+use crate::events;
+use crate::injection_queue::{DroppedInjection, InjectionQueue, QueuedInjection};
+use crate::memory::{json_size, BoundedBuffer, MemoryAccountant};
+use crate::models::{Candidate, HookAction, HookResult};
 use crate::traits::HookHandler;
 
+use self::condition::HookCondition;
+use self::latency::{LatencyBudget, SlowHandlerCall};
+use self::spill::HookDataLimit;
+
+// ---------------------------------------------------------------------------
+// HookPhase -- named ordering tiers
+// ---------------------------------------------------------------------------
+
+/// The tier a handler runs in. Phases run in declaration order.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum HookPhase {
+    /// Input validation; may deny.
+    PreValidation,
+    /// Permission and policy decisions; may deny. The default phase.
+    #[default]
+    Policy,
+    /// Payload rewriting; a `Deny` here is logged and ignored.
+    Mutation,
+    /// Logging and telemetry; always runs, even after a deny, and its results
+    /// are ignored.
+    Observation,
+}
+
+impl HookPhase {
+    /// All phases in execution order.
+    pub const ALL: [HookPhase; 4] = [
+        HookPhase::PreValidation,
+        HookPhase::Policy,
+        HookPhase::Mutation,
+        HookPhase::Observation,
+    ];
+
+    /// Whether a `Deny` returned in this phase stops the operation.
+    pub fn honors_deny(self) -> bool {
+        self <= HookPhase::Policy
+    }
+
+    /// The snake_case name used in configuration and bindings.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookPhase::PreValidation => "pre_validation",
+            HookPhase::Policy => "policy",
+            HookPhase::Mutation => "mutation",
+            HookPhase::Observation => "observation",
+        }
+    }
+}
+
+impl fmt::Display for HookPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HookPhase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HookPhase::ALL
+            .into_iter()
+            .find(|phase| phase.as_str() == s)
+            .ok_or_else(|| format!("unknown hook phase '{s}'"))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // HandlerEntry -- internal storage for a registered handler
 // ---------------------------------------------------------------------------
 
-/// A registered handler with its priority and name.
+/// A registered handler with its phase, priority and name.
+#[derive(Clone)]
 struct HandlerEntry {
     handler: Arc<dyn HookHandler>,
+    phase: HookPhase,
     priority: i32,
     name: String,
+    /// Only call the handler when this holds for the payload.
+    condition: Option<Arc<HookCondition>>,
+    /// Cleared by [`HookRegistry::set_handler_enabled`].
+    enabled: bool,
     /// Unique ID for unregistration.
     id: u64,
 }
 
+impl HandlerEntry {
+    fn accepts(&self, event: &str, data: &Value) -> bool {
+        self.enabled
+            && self
+                .condition
+                .as_ref()
+                .is_none_or(|condition| condition.matches(event, data))
+    }
+}
+
+/// Immutable handler table: event name → handlers sorted by (phase, priority).
+///
+/// Each event's list is shared, so a registration only rebuilds the list for
+/// the event it touches.
+type HandlerTable = HashMap<String, Arc<Vec<HandlerEntry>>>;
+
+/// Observer told how long a handler took: `(event, handler name, elapsed)`.
+pub type HandlerTimingCallback = Arc<dyn Fn(&str, &str, Duration) + Send + Sync>;
+
+/// Observer told the name of every event passed to [`HookRegistry::emit`].
+pub type EmitCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
 // ---------------------------------------------------------------------------
 // HookRegistry
 // ---------------------------------------------------------------------------
@@ -64,26 +293,65 @@ struct HandlerEntry {
 /// // register handlers, emit events ...
 /// ```
 pub struct HookRegistry {
-    /// Handlers keyed by event name, sorted by priority within each event.
+    /// Copy-on-write snapshot of the handler table.
     /// Wrapped in `Arc` so unregister closures can safely hold a reference.
-    handlers: Arc<Mutex<HashMap<String, Vec<HandlerEntry>>>>,
+    handlers: Arc<ArcSwap<HandlerTable>>,
     /// Default fields merged into every `emit()` call.
-    defaults: Mutex<Option<Value>>,
+    defaults: ArcSwapOption<Value>,
     /// Monotonically increasing ID for handler entries.
-    next_id: Mutex<u64>,
+    next_id: AtomicU64,
+    /// Handler timing observers (see [`on_handler_timing()`](Self::on_handler_timing)).
+    timing_observers: ArcSwap<Vec<HandlerTimingCallback>>,
+    /// Emit observers (see [`on_emit()`](Self::on_emit)).
+    emit_observers: ArcSwap<Vec<EmitCallback>>,
+    /// Recent events for late subscribers (see [`enable_replay()`](Self::enable_replay)).
+    replay: ArcSwapOption<ReplayBuffer>,
+    /// Accountant the replay buffer charges (see [`set_memory()`](Self::set_memory)).
+    memory: ArcSwap<MemoryAccountant>,
+    /// Source of event timestamps (see [`set_clock()`](Self::set_clock)).
+    clock: ArcSwap<Arc<dyn Clock>>,
+    /// Emit-time filter (see [`set_event_filter()`](Self::set_event_filter)).
+    filter: ArcSwapOption<EventFilter>,
+    /// Result data size limit (see [`set_data_limit()`](Self::set_data_limit)).
+    data_limit: ArcSwapOption<HookDataLimit>,
+    /// Handler latency budget (see [`set_latency_budget()`](Self::set_latency_budget)).
+    latency: ArcSwapOption<LatencyBudget>,
+    /// Recent injections, oldest first (see [`take_injection()`](Self::take_injection)).
+    injections: Mutex<VecDeque<Injection>>,
+    /// Injection admission (see [`set_injection_queue()`](Self::set_injection_queue)).
+    injection_queue: ArcSwapOption<InjectionQueue>,
+}
+
+/// How many injections [`HookRegistry`] remembers for attribution.
+const RECENT_INJECTIONS: usize = 32;
+
+/// A merged `inject_context` text and the handlers that contributed to it.
+struct Injection {
+    text: String,
+    handlers: Vec<String>,
 }
 
 impl HookRegistry {
     /// Create an empty hook registry.
     pub fn new() -> Self {
         Self {
-            handlers: Arc::new(Mutex::new(HashMap::new())),
-            defaults: Mutex::new(None),
-            next_id: Mutex::new(0),
+            handlers: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            defaults: ArcSwapOption::empty(),
+            next_id: AtomicU64::new(0),
+            timing_observers: ArcSwap::from_pointee(Vec::new()),
+            emit_observers: ArcSwap::from_pointee(Vec::new()),
+            replay: ArcSwapOption::empty(),
+            memory: ArcSwap::from_pointee(MemoryAccountant::default()),
+            clock: ArcSwap::from_pointee(clock::system()),
+            filter: ArcSwapOption::empty(),
+            data_limit: ArcSwapOption::empty(),
+            latency: ArcSwapOption::empty(),
+            injections: Mutex::new(VecDeque::new()),
+            injection_queue: ArcSwapOption::empty(),
         }
     }
 
-    /// Register a hook handler for an event.
+    /// Register a hook handler for an event in the [`HookPhase::Policy`] phase.
     ///
     /// # Arguments
     ///
@@ -102,50 +370,312 @@ impl HookRegistry {
         priority: i32,
         name: Option<String>,
     ) -> Box<dyn Fn() + Send + Sync> {
-        let id = {
-            let mut next = self.next_id.lock().unwrap();
-            let id = *next;
-            *next += 1;
-            id
-        };
+        self.register_in_phase(event, handler, HookPhase::default(), priority, name)
+    }
+
+    /// Register a hook handler for an event in a specific phase.
+    ///
+    /// `priority` orders handlers within `phase` only; every handler of an
+    /// earlier phase runs first regardless of priority.
+    pub fn register_in_phase(
+        &self,
+        event: &str,
+        handler: Arc<dyn HookHandler>,
+        phase: HookPhase,
+        priority: i32,
+        name: Option<String>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.insert(event, handler, phase, priority, name, None)
+    }
+
+    /// Register a hook handler that is only called for `event` payloads
+    /// `condition` holds for.
+    ///
+    /// Skipped events cost one condition evaluation and no handler call.
+    pub fn register_with_condition(
+        &self,
+        event: &str,
+        handler: Arc<dyn HookHandler>,
+        phase: HookPhase,
+        priority: i32,
+        name: Option<String>,
+        condition: HookCondition,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        self.insert(
+            event,
+            handler,
+            phase,
+            priority,
+            name,
+            Some(Arc::new(condition)),
+        )
+    }
+
+    fn insert(
+        &self,
+        event: &str,
+        handler: Arc<dyn HookHandler>,
+        phase: HookPhase,
+        priority: i32,
+        name: Option<String>,
+        condition: Option<Arc<HookCondition>>,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
 
         let entry_name = name.unwrap_or_else(|| format!("handler-{id}"));
 
         let entry = HandlerEntry {
             handler,
+            phase,
             priority,
             name: entry_name,
+            condition,
+            enabled: true,
             id,
         };
 
-        {
-            let mut handlers = self.handlers.lock().unwrap();
-            let event_handlers = handlers.entry(event.to_string()).or_default();
-            event_handlers.push(entry);
-            // Keep sorted by priority (lower = higher priority)
-            event_handlers.sort_by_key(|e| e.priority);
-        }
+        self.handlers.rcu(|table| {
+            let mut table = HandlerTable::clone(table);
+            let mut event_handlers = table
+                .get(event)
+                .map(|entries| entries.as_ref().clone())
+                .unwrap_or_default();
+            event_handlers.push(entry.clone());
+            // Keep sorted by phase, then priority (lower = higher priority)
+            event_handlers.sort_by_key(|e| (e.phase, e.priority));
+            table.insert(event.to_string(), Arc::new(event_handlers));
+            table
+        });
 
-        // The unregister closure holds an Arc clone of the handlers map,
+        // The unregister closure holds an Arc clone of the handler table,
         // so it can remove the entry even after the registry borrow ends.
         // This matches Python's pattern where the closure captures self._handlers.
         let event_key = event.to_string();
         let handlers_ref = self.handlers.clone();
 
         Box::new(move || {
-            let mut handlers = handlers_ref.lock().unwrap();
-            if let Some(event_handlers) = handlers.get_mut(&event_key) {
-                event_handlers.retain(|e| e.id != id);
-            }
+            handlers_ref.rcu(|table| {
+                let mut table = HandlerTable::clone(table);
+                if let Some(entries) = table.get_mut(&event_key) {
+                    if entries.iter().any(|e| e.id == id) {
+                        let remaining = entries.iter().filter(|e| e.id != id).cloned().collect();
+                        *entries = Arc::new(remaining);
+                    }
+                }
+                table
+            });
         })
     }
 
+    /// Register a handler and first deliver it up to `last` of the most
+    /// recent `event` payloads from the replay buffer, oldest first.
+    ///
+    /// Registration and the backlog are taken atomically with respect to
+    /// `emit()`, so no event is both replayed and delivered live, and none
+    /// falls between the two. Live events emitted while the backlog is being
+    /// delivered may reach the handler before the backlog finishes.
+    ///
+    /// Without [`enable_replay()`](Self::enable_replay) this is
+    /// [`register()`](Self::register).
+    pub async fn register_with_replay(
+        &self,
+        event: &str,
+        handler: Arc<dyn HookHandler>,
+        priority: i32,
+        name: Option<String>,
+        last: usize,
+    ) -> Box<dyn Fn() + Send + Sync> {
+        let Some(replay) = self.replay.load_full() else {
+            return self.register(event, handler, priority, name);
+        };
+        let (unregister, backlog) = {
+            let events = replay.events.lock().unwrap();
+            let unregister = self.register(event, Arc::clone(&handler), priority, name);
+            let mut backlog: Vec<Value> = events
+                .iter()
+                .rev()
+                .filter(|(name, _)| name == event)
+                .take(last)
+                .map(|(_, data)| data.clone())
+                .collect();
+            backlog.reverse();
+            (unregister, backlog)
+        };
+
+        for mut data in backlog {
+            if let Value::Object(ref mut map) = data {
+                map.insert("replayed".to_string(), Value::Bool(true));
+            }
+            if let Err(e) = handler.handle(event, data).await {
+                log::error!("Hook handler error replaying event '{event}': {e}");
+            }
+        }
+        unregister
+    }
+
+    /// Keep the last `capacity` events passed to [`emit()`](Self::emit) for
+    /// [`register_with_replay()`](Self::register_with_replay).
+    ///
+    /// Events are recorded as handlers receive them (default fields merged,
+    /// timestamp stamped). Calling this again resizes the buffer, keeping the
+    /// newest events; `0` disables replay and discards the buffer. Under a
+    /// memory ceiling the buffer may hold fewer than `capacity` events.
+    pub fn enable_replay(&self, capacity: usize) {
+        let old = self
+            .replay
+            .swap(None)
+            .map(|old| old.events.lock().unwrap().drain())
+            .unwrap_or_default();
+        if capacity == 0 {
+            return;
+        }
+        let mut events = BoundedBuffer::new(REPLAY_MEMORY_CATEGORY, self.memory.load_full());
+        let skip = old.len().saturating_sub(capacity);
+        for (event, data) in old.into_iter().skip(skip) {
+            let size = event.len() + json_size(&data);
+            events.push((event, data), size);
+        }
+        self.replay.store(Some(Arc::new(ReplayBuffer {
+            capacity,
+            events: Mutex::new(events),
+        })));
+    }
+
+    /// Charge the replay buffer to `memory` (typically
+    /// [`Coordinator::memory`](crate::coordinator::Coordinator::memory)).
+    ///
+    /// Applies to buffers created by later
+    /// [`enable_replay()`](Self::enable_replay) calls.
+    pub fn set_memory(&self, memory: Arc<MemoryAccountant>) {
+        self.memory.store(memory);
+    }
+
+    /// Number of events currently held for replay.
+    pub fn replay_len(&self) -> usize {
+        self.replay
+            .load()
+            .as_ref()
+            .map_or(0, |replay| replay.events.lock().unwrap().len())
+    }
+
     /// Set default fields merged into every `emit()` call.
     ///
     /// Defaults are merged with event data, with explicit event data taking
     /// precedence (matching Python's `{**defaults, **data}` pattern).
     pub fn set_default_fields(&self, defaults: Value) {
-        *self.defaults.lock().unwrap() = Some(defaults);
+        self.defaults.store(Some(Arc::new(defaults)));
+    }
+
+    /// The default fields set by [`set_default_fields()`](Self::set_default_fields), if any.
+    pub fn default_fields(&self) -> Option<Value> {
+        self.defaults.load().as_deref().cloned()
+    }
+
+    /// Replace the clock used to stamp event timestamps (default: the system
+    /// clock).
+    ///
+    /// Handler timing and handler timeouts keep using real time.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        self.clock.store(Arc::new(clock));
+    }
+
+    /// The clock used to stamp event timestamps.
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock.load())
+    }
+
+    /// Install an emit-time event filter, replacing any previous one.
+    ///
+    /// Takes effect for the next [`emit()`](Self::emit); emits already in
+    /// flight are unaffected. [`emit_and_collect()`](Self::emit_and_collect)
+    /// and [`emit_decision()`](Self::emit_decision) are never filtered.
+    pub fn set_event_filter(&self, filter: impl Into<EventFilter>) {
+        self.filter.store(Some(Arc::new(filter.into())));
+    }
+
+    /// Remove the event filter, so every event is dispatched again.
+    pub fn clear_event_filter(&self) {
+        self.filter.store(None);
+    }
+
+    /// The installed event filter, if any.
+    pub fn event_filter(&self) -> Option<Arc<EventFilter>> {
+        self.filter.load_full()
+    }
+
+    /// Bound the size of handler result data, replacing any previous limit.
+    ///
+    /// Applies to every non-observer result with `data`, before it is
+    /// chained to the next handler or returned (see [`spill`]).
+    pub fn set_data_limit(&self, limit: Arc<HookDataLimit>) {
+        self.data_limit.store(Some(limit));
+    }
+
+    /// Remove the result data size limit.
+    pub fn clear_data_limit(&self) {
+        self.data_limit.store(None);
+    }
+
+    /// The installed result data size limit, if any.
+    pub fn data_limit(&self) -> Option<Arc<HookDataLimit>> {
+        self.data_limit.load_full()
+    }
+
+    /// Time handler calls against `budget`, replacing any previous budget.
+    ///
+    /// Calls over budget are reported as `hook:slow_handler` events, and
+    /// observers it demotes run in the background (see [`latency`]).
+    /// `hook:slow_handler` handlers themselves are not timed.
+    pub fn set_latency_budget(&self, budget: Arc<LatencyBudget>) {
+        self.latency.store(Some(budget));
+    }
+
+    /// Remove the latency budget; demoted handlers run inline again.
+    pub fn clear_latency_budget(&self) {
+        self.latency.store(None);
+    }
+
+    /// The installed latency budget, if any.
+    pub fn latency_budget(&self) -> Option<Arc<LatencyBudget>> {
+        self.latency.load_full()
+    }
+
+    /// Admit `inject_context` results through `queue`, replacing any
+    /// previous queue (see [`crate::injection_queue`]).
+    pub fn set_injection_queue(&self, queue: Arc<InjectionQueue>) {
+        self.injection_queue.store(Some(queue));
+    }
+
+    /// The installed injection queue, if any.
+    pub fn injection_queue(&self) -> Option<Arc<InjectionQueue>> {
+        self.injection_queue.load_full()
+    }
+
+    /// Install an observer called after every handler invocation made by
+    /// [`emit()`](Self::emit), [`emit_and_collect()`](Self::emit_and_collect)
+    /// and [`emit_decision()`](Self::emit_decision).
+    ///
+    /// Observers run inline on the emitting task and should only record the
+    /// measurement. Failed and timed-out calls are reported too.
+    pub fn on_handler_timing(&self, callback: HandlerTimingCallback) {
+        self.timing_observers.rcu(|observers| {
+            let mut observers = Vec::clone(observers);
+            observers.push(Arc::clone(&callback));
+            observers
+        });
+    }
+
+    /// Install an observer called with the event name at the start of every
+    /// [`emit()`](Self::emit), whether or not any handler is registered or
+    /// the event filter drops the event.
+    ///
+    /// Observers run inline on the emitting task and should only record.
+    pub fn on_emit(&self, callback: EmitCallback) {
+        self.emit_observers.rcu(|observers| {
+            let mut observers = Vec::clone(observers);
+            observers.push(Arc::clone(&callback));
+            observers
+        });
     }
 
     /// Emit an event to all registered handlers.
@@ -157,59 +687,122 @@ impl HookRegistry {
     /// - First-wins on `AskUser`
     ///
     /// Action precedence: Deny > AskUser > InjectContext > Modify > Continue
+    ///
+    /// Phase rules (see the [module docs](self)) decide which handlers may
+    /// deny and which still run after a deny. An event dropped by the
+    /// [event filter](Self::set_event_filter) only reaches
+    /// [`HookPhase::PreValidation`] handlers and is not recorded for replay.
     pub async fn emit(&self, event: &str, data: Value) -> HookResult {
-        // Snapshot handlers for this event (avoids holding the lock during async calls).
-        let entries: Vec<(Arc<dyn HookHandler>, String)> = {
-            let handlers = self.handlers.lock().unwrap();
-            match handlers.get(event) {
-                Some(entries) => entries
-                    .iter()
-                    .map(|e| (e.handler.clone(), e.name.clone()))
-                    .collect(),
-                None => {
+        for observer in self.emit_observers.load().iter() {
+            observer(event);
+        }
+        let filtered = self
+            .filter
+            .load()
+            .as_ref()
+            .is_some_and(|filter| !filter.allows(event));
+        if filtered {
+            let entries: Vec<HandlerEntry> = self
+                .event_handlers(event)
+                .iter()
+                .filter(|entry| entry.phase == HookPhase::PreValidation)
+                .cloned()
+                .collect();
+            if entries.is_empty() {
+                return HookResult {
+                    action: HookAction::Continue,
+                    data: Some(value_to_map(&data)),
+                    ..Default::default()
+                };
+            }
+            return self.dispatch(event, &entries, self.stamp(data)).await;
+        }
+
+        let (entries, current_data) = match self.replay.load_full() {
+            None => {
+                let entries = self.event_handlers(event);
+                if entries.is_empty() {
                     return HookResult {
                         action: HookAction::Continue,
                         data: Some(value_to_map(&data)),
                         ..Default::default()
                     };
                 }
+                (entries, self.stamp(data))
             }
-        };
-
-        if entries.is_empty() {
-            return HookResult {
-                action: HookAction::Continue,
-                data: Some(value_to_map(&data)),
-                ..Default::default()
-            };
-        }
-
-        // Merge default fields with event data (event data takes precedence).
-        let mut current_data = {
-            let defaults = self.defaults.lock().unwrap();
-            match defaults.as_ref() {
-                Some(defaults_val) => merge_json(defaults_val, &data),
-                None => data,
+            Some(replay) => {
+                let stamped = self.stamp(data.clone());
+                // Record and load handlers under one lock so
+                // register_with_replay() sees each event exactly once.
+                let entries = {
+                    let mut events = replay.events.lock().unwrap();
+                    if events.len() == replay.capacity {
+                        events.pop_front();
+                    }
+                    let size = event.len() + json_size(&stamped);
+                    events.push((event.to_string(), stamped.clone()), size);
+                    self.event_handlers(event)
+                };
+                if entries.is_empty() {
+                    return HookResult {
+                        action: HookAction::Continue,
+                        data: Some(value_to_map(&data)),
+                        ..Default::default()
+                    };
+                }
+                (entries, stamped)
             }
         };
 
-        // Stamp infrastructure-owned timestamp (UTC ISO-8601).
-        // Together with session_id (from defaults), forms the compound identity
-        // key (session_id, timestamp) for event uniqueness and ordering.
-        // Infrastructure-owned: always present, callers cannot omit or override.
-        if let Value::Object(ref mut map) = current_data {
-            map.insert(
-                "timestamp".to_string(),
-                Value::String(chrono::Utc::now().to_rfc3339()),
-            );
-        }
+        self.dispatch(event, &entries, current_data).await
+    }
+
+    /// Run `entries` for `event` under the phase and action rules of
+    /// [`emit()`](Self::emit).
+    async fn dispatch(
+        &self,
+        event: &str,
+        entries: &[HandlerEntry],
+        mut current_data: Value,
+    ) -> HookResult {
+        let latency = self.latency_for(event);
+        let timing = latency.is_some() || !self.timing_observers.load().is_empty();
+        let data_limit = self.data_limit.load_full();
 
         // Track special actions
         let mut special_result: Option<HookResult> = None;
-        let mut inject_context_results: Vec<HookResult> = Vec::new();
+        let mut inject_context_results: Vec<QueuedInjection> = Vec::new();
+        let mut denied: Option<HookResult> = None;
+
+        for entry in entries.iter() {
+            let HandlerEntry {
+                handler,
+                phase,
+                priority,
+                name,
+                ..
+            } = entry;
+            // After a deny only observers still run.
+            if denied.is_some() && *phase != HookPhase::Observation {
+                continue;
+            }
+            if !entry.accepts(event, &current_data) {
+                continue;
+            }
+            if *phase == HookPhase::Observation
+                && latency.as_ref().is_some_and(|l| l.is_demoted(name))
+            {
+                spawn_demoted(entry, event, current_data.clone());
+                continue;
+            }
 
-        for (handler, name) in &entries {
-            let result = match handler.handle(event, current_data.clone()).await {
+            let started = timing.then(Instant::now);
+            let outcome = handler.handle(event, current_data.clone()).await;
+            if let Some(started) = started {
+                self.record_timing(latency.as_deref(), event, entry, started.elapsed())
+                    .await;
+            }
+            let mut result = match outcome {
                 Ok(r) => r,
                 Err(e) => {
                     // Error in handler -- log and continue (matches Python behaviour).
@@ -222,9 +815,35 @@ impl HookRegistry {
                 }
             };
 
-            // Deny short-circuits immediately
+            // Observers cannot influence the outcome.
+            if *phase == HookPhase::Observation {
+                continue;
+            }
+
+            if let (Some(limit), Some(data)) = (&data_limit, result.data.as_mut()) {
+                spill_oversized(limit, event, name, data).await;
+            }
+
+            // Deny short-circuits the remaining non-observation handlers
             if result.action == HookAction::Deny {
-                return result;
+                if !phase.honors_deny() {
+                    log::warn!(
+                        "Hook handler '{}' returned Deny in the {} phase for event '{}' — ignored",
+                        name,
+                        phase,
+                        event
+                    );
+                    continue;
+                }
+                // Observers see the payload annotated with who denied it.
+                if let Value::Object(ref mut map) = current_data {
+                    map.insert(
+                        "denied_by".to_string(),
+                        serde_json::json!({"handler": name, "reason": result.reason}),
+                    );
+                }
+                denied = Some(result);
+                continue;
             }
 
             // Modify chains data to next handler
@@ -242,7 +861,11 @@ impl HookRegistry {
 
             // Collect inject_context for merging at end
             if result.action == HookAction::InjectContext && result.context_injection.is_some() {
-                inject_context_results.push(result.clone());
+                inject_context_results.push(QueuedInjection {
+                    source: name.clone(),
+                    priority: *priority,
+                    result: result.clone(),
+                });
             }
 
             // Preserve ask_user (only first one -- can't merge approvals)
@@ -251,9 +874,33 @@ impl HookRegistry {
             }
         }
 
+        if let Some(result) = denied {
+            return result;
+        }
+
+        // Admit inject_context results through the queue, if any
+        if !inject_context_results.is_empty() {
+            if let Some(queue) = self.injection_queue.load_full() {
+                let admission = queue.admit(event, inject_context_results);
+                self.report_dropped(admission.dropped).await;
+                inject_context_results = admission.admitted;
+            }
+        }
+
         // Merge inject_context results if any
         if !inject_context_results.is_empty() {
-            let merged_inject = merge_inject_context_results(&inject_context_results);
+            let mut injecting_handlers: Vec<String> = Vec::new();
+            for injection in &inject_context_results {
+                if !injecting_handlers.contains(&injection.source) {
+                    injecting_handlers.push(injection.source.clone());
+                }
+            }
+            let results: Vec<HookResult> = inject_context_results
+                .into_iter()
+                .map(|injection| injection.result)
+                .collect();
+            let merged_inject = merge_inject_context_results(&results);
+            self.record_injection(&merged_inject, injecting_handlers);
             if special_result.is_none() {
                 // No ask_user captured -- inject_context wins
                 special_result = Some(merged_inject);
@@ -288,27 +935,79 @@ impl HookRegistry {
         data: Value,
         timeout: Duration,
     ) -> Vec<HashMap<String, Value>> {
-        // Snapshot handlers
-        let entries: Vec<(Arc<dyn HookHandler>, String)> = {
-            let handlers = self.handlers.lock().unwrap();
-            match handlers.get(event) {
-                Some(entries) => entries
-                    .iter()
-                    .map(|e| (e.handler.clone(), e.name.clone()))
-                    .collect(),
-                None => return Vec::new(),
+        self.collect_named(event, data, timeout)
+            .await
+            .into_iter()
+            .map(|(_, data)| data)
+            .collect()
+    }
+
+    /// Emit a decision event and return the proposed candidates, ranked.
+    ///
+    /// Each handler proposes candidates in its `result.data`, either as a
+    /// single candidate object or as `{"candidates": [...]}` (see
+    /// [`Candidate`] for the fields). The kernel:
+    ///
+    /// 1. drops malformed entries (logged),
+    /// 2. keeps one candidate per `id` -- the best-ranked one,
+    /// 3. sorts by `score` descending, then `confidence` descending, then
+    ///    handler order.
+    ///
+    /// Handlers are called as in [`emit_and_collect()`](Self::emit_and_collect),
+    /// each bounded by `timeout`.
+    pub async fn emit_decision(
+        &self,
+        event: &str,
+        data: Value,
+        timeout: Duration,
+    ) -> Vec<Candidate> {
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for (handler_name, data) in self.collect_named(event, data, timeout).await {
+            for mut candidate in parse_candidates(event, &handler_name, data) {
+                candidate.source = handler_name.clone();
+                match candidates.iter_mut().find(|c| c.id == candidate.id) {
+                    Some(existing) if candidate.outranks(existing) => *existing = candidate,
+                    Some(_) => {}
+                    None => candidates.push(candidate),
+                }
             }
-        };
+        }
+        // Stable sort keeps handler order among equals.
+        candidates.sort_by(|a, b| b.rank_cmp(a));
+        candidates
+    }
 
+    /// Call every handler for `event` with a timeout, collecting
+    /// `(handler name, result.data)` from those that returned data.
+    async fn collect_named(
+        &self,
+        event: &str,
+        data: Value,
+        timeout: Duration,
+    ) -> Vec<(String, HashMap<String, Value>)> {
+        let entries = self.event_handlers(event);
         if entries.is_empty() {
             return Vec::new();
         }
 
         let mut responses = Vec::new();
+        let latency = self.latency_for(event);
+        let timing = latency.is_some() || !self.timing_observers.load().is_empty();
+        let data_limit = self.data_limit.load_full();
 
-        for (handler, name) in &entries {
+        for entry in entries.iter() {
+            if !entry.accepts(event, &data) {
+                continue;
+            }
+            let HandlerEntry { handler, name, .. } = entry;
             let fut = handler.handle(event, data.clone());
-            let result = match tokio::time::timeout(timeout, fut).await {
+            let started = timing.then(Instant::now);
+            let outcome = tokio::time::timeout(timeout, fut).await;
+            if let Some(started) = started {
+                self.record_timing(latency.as_deref(), event, entry, started.elapsed())
+                    .await;
+            }
+            let result = match outcome {
                 Ok(Ok(r)) => r,
                 Ok(Err(e)) => {
                     // Handler error -- log and skip
@@ -330,8 +1029,11 @@ impl HookRegistry {
                 }
             };
 
-            if let Some(d) = result.data {
-                responses.push(d);
+            if let Some(mut d) = result.data {
+                if let Some(limit) = &data_limit {
+                    spill_oversized(limit, event, name, &mut d).await;
+                }
+                responses.push((name.clone(), d));
             }
         }
 
@@ -343,7 +1045,7 @@ impl HookRegistry {
     /// If `event` is `Some`, only return handlers for that event.
     /// If `None`, return all handlers grouped by event.
     pub fn list_handlers(&self, event: Option<&str>) -> HashMap<String, Vec<String>> {
-        let handlers = self.handlers.lock().unwrap();
+        let handlers = self.handlers.load();
 
         if let Some(evt) = event {
             let names = handlers
@@ -365,6 +1067,269 @@ impl HookRegistry {
                 .collect()
         }
     }
+
+    /// Enable or disable every handler named `name`, on all events.
+    ///
+    /// Returns how many registrations were changed (already in the requested
+    /// state counts as unchanged).
+    pub fn set_handler_enabled(&self, name: &str, enabled: bool) -> usize {
+        self.update_enabled(None, name, enabled)
+    }
+
+    /// Enable or disable the handlers named `name` on `event` only.
+    ///
+    /// Returns how many registrations were changed.
+    pub fn set_handler_enabled_for(&self, event: &str, name: &str, enabled: bool) -> usize {
+        self.update_enabled(Some(event), name, enabled)
+    }
+
+    fn update_enabled(&self, event: Option<&str>, name: &str, enabled: bool) -> usize {
+        let mut changed = 0;
+        self.handlers.rcu(|table| {
+            changed = 0;
+            let mut table = HandlerTable::clone(table);
+            for (evt, entries) in table.iter_mut() {
+                if event.is_some_and(|event| event != evt) {
+                    continue;
+                }
+                let matching = entries
+                    .iter()
+                    .filter(|e| e.name == name && e.enabled != enabled)
+                    .count();
+                if matching == 0 {
+                    continue;
+                }
+                changed += matching;
+                let mut updated = entries.as_ref().clone();
+                for entry in updated.iter_mut().filter(|e| e.name == name) {
+                    entry.enabled = enabled;
+                }
+                *entries = Arc::new(updated);
+            }
+            table
+        });
+        if changed > 0 {
+            log::info!(
+                "Hook handler '{name}' {} on {}",
+                if enabled { "enabled" } else { "disabled" },
+                event.unwrap_or("all events")
+            );
+        }
+        changed
+    }
+
+    /// Disabled handler names, grouped by event (events with none are
+    /// omitted).
+    pub fn disabled_handlers(&self) -> HashMap<String, Vec<String>> {
+        self.handlers
+            .load()
+            .iter()
+            .filter_map(|(event, entries)| {
+                let names: Vec<String> = entries
+                    .iter()
+                    .filter(|e| !e.enabled)
+                    .map(|e| e.name.clone())
+                    .collect();
+                (!names.is_empty()).then(|| (event.clone(), names))
+            })
+            .collect()
+    }
+
+    fn record_injection(&self, result: &HookResult, handlers: Vec<String>) {
+        let Some(text) = result
+            .context_injection
+            .as_deref()
+            .filter(|t| !t.is_empty())
+        else {
+            return;
+        };
+        let mut injections = self.injections.lock().unwrap();
+        if injections.len() == RECENT_INJECTIONS {
+            injections.pop_front();
+        }
+        injections.push_back(Injection {
+            text: text.to_string(),
+            handlers,
+        });
+    }
+
+    /// Forget and return the handlers behind the most recent injection whose
+    /// text `content` contains, or `None` if it contains none.
+    pub(crate) fn take_injection(&self, content: &str) -> Option<Vec<String>> {
+        let mut injections = self.injections.lock().unwrap();
+        let index = injections
+            .iter()
+            .rposition(|injection| content.contains(&injection.text))?;
+        injections.remove(index).map(|injection| injection.handlers)
+    }
+
+    /// Capture the registered handlers and default fields.
+    ///
+    /// Cheap: the registry is copy-on-write, so this only clones two `Arc`s.
+    /// Timing observers are not captured.
+    pub fn snapshot(&self) -> HookSnapshot {
+        HookSnapshot {
+            handlers: self.handlers.load_full(),
+            defaults: self.defaults.load_full(),
+        }
+    }
+
+    /// Replace the handlers and default fields with those in `snapshot`.
+    ///
+    /// Handlers registered since the snapshot are dropped, including any
+    /// registered concurrently by other tasks; their unregister closures
+    /// become no-ops. Unregister closures for handlers in the snapshot keep
+    /// working.
+    pub fn restore(&self, snapshot: &HookSnapshot) {
+        self.handlers.store(Arc::clone(&snapshot.handlers));
+        self.defaults.store(snapshot.defaults.clone());
+    }
+
+    /// Snapshot now and [`restore()`](Self::restore) when the returned guard
+    /// drops.
+    ///
+    /// The guard derefs to the registry, so handlers can be registered
+    /// through it:
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use amplifier_core::hooks::HookRegistry;
+    /// use amplifier_core::testing::FakeHookHandler;
+    ///
+    /// let registry = HookRegistry::new();
+    /// {
+    ///     let scope = registry.scoped();
+    ///     let _ = scope.register("tool:pre", Arc::new(FakeHookHandler::new()), 0, None);
+    ///     assert_eq!(scope.list_handlers(None).len(), 1);
+    /// }
+    /// assert!(registry.list_handlers(None).is_empty());
+    /// ```
+    pub fn scoped(&self) -> HookScope<'_> {
+        HookScope {
+            registry: self,
+            snapshot: self.snapshot(),
+        }
+    }
+
+    /// Merge default fields into `data` and stamp the timestamp.
+    fn stamp(&self, data: Value) -> Value {
+        // Event data takes precedence over defaults.
+        let mut data = match self.defaults.load().as_deref() {
+            Some(defaults_val) => merge_json(defaults_val, &data),
+            None => data,
+        };
+
+        // Stamp infrastructure-owned timestamp (UTC ISO-8601).
+        // Together with session_id (from defaults), forms the compound identity
+        // key (session_id, timestamp) for event uniqueness and ordering.
+        // Infrastructure-owned: always present, callers cannot omit or override.
+        if let Value::Object(ref mut map) = data {
+            // Task-scoped correlation IDs fill in what is not already set.
+            let ids = correlation::current();
+            for (key, id) in [("turn_id", ids.turn_id), ("tool_call_id", ids.tool_call_id)] {
+                if let Some(id) = id {
+                    map.entry(key).or_insert(Value::String(id));
+                }
+            }
+            map.insert(
+                "timestamp".to_string(),
+                Value::String(self.clock.load().now_utc().to_rfc3339()),
+            );
+        }
+        data
+    }
+
+    /// The handlers currently registered for `event`.
+    ///
+    /// Cloning the per-event `Arc` is the only work done on the hot path; the
+    /// returned list stays valid even if handlers are registered or removed
+    /// while the caller awaits them.
+    fn event_handlers(&self, event: &str) -> Arc<Vec<HandlerEntry>> {
+        self.handlers.load().get(event).cloned().unwrap_or_default()
+    }
+
+    /// The latency budget that applies to `event`'s handlers.
+    fn latency_for(&self, event: &str) -> Option<Arc<LatencyBudget>> {
+        match event {
+            events::HOOK_SLOW_HANDLER => None,
+            _ => self.latency.load_full(),
+        }
+    }
+
+    /// Tell timing observers about a call and report it if over budget.
+    async fn record_timing(
+        &self,
+        latency: Option<&LatencyBudget>,
+        event: &str,
+        entry: &HandlerEntry,
+        elapsed: Duration,
+    ) {
+        for observer in self.timing_observers.load().iter() {
+            observer(event, &entry.name, elapsed);
+        }
+        let slow = latency.and_then(|l| l.record(event, &entry.name, entry.phase, elapsed));
+        if let Some(call) = slow {
+            self.report_slow(call).await;
+        }
+    }
+
+    /// Emit `hook:slow_handler` for `call`. Boxed because it re-enters
+    /// [`emit()`](Self::emit).
+    fn report_slow(&self, call: SlowHandlerCall) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        log::warn!(
+            "Hook handler '{}' took {}ms for '{}' (budget {}ms){}",
+            call.handler,
+            call.elapsed_ms,
+            call.event,
+            call.budget_ms,
+            if call.demoted {
+                " — demoted to background"
+            } else {
+                ""
+            }
+        );
+        Box::pin(async move {
+            let data = serde_json::to_value(&call).unwrap_or_default();
+            self.emit(events::HOOK_SLOW_HANDLER, data).await;
+        })
+    }
+
+    /// Emit `context:injection_dropped` for each of `dropped`. Boxed
+    /// because it re-enters [`emit()`](Self::emit); injections returned for
+    /// that event are only logged, so a full budget cannot loop.
+    fn report_dropped(
+        &self,
+        dropped: Vec<DroppedInjection>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send + '_>> {
+        Box::pin(async move {
+            for injection in dropped {
+                log::warn!(
+                    "Dropped context injection from hook handler '{}' for '{}' ({:?}, ~{} tokens)",
+                    injection.source,
+                    injection.event,
+                    injection.reason,
+                    injection.tokens
+                );
+                if injection.event == events::CONTEXT_INJECTION_DROPPED {
+                    continue;
+                }
+                let data = serde_json::to_value(&injection).unwrap_or_default();
+                self.emit(events::CONTEXT_INJECTION_DROPPED, data).await;
+            }
+        })
+    }
+}
+
+/// Run a demoted observer on its own task; its result is ignored.
+fn spawn_demoted(entry: &HandlerEntry, event: &str, data: Value) {
+    let handler = Arc::clone(&entry.handler);
+    let name = entry.name.clone();
+    let event = event.to_string();
+    tokio::spawn(async move {
+        if let Err(e) = handler.handle(&event, data).await {
+            log::error!("Hook handler error for event '{event}' (handler '{name}'): {e}");
+        }
+    });
 }
 
 impl Default for HookRegistry {
@@ -373,10 +1338,103 @@ impl Default for HookRegistry {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// Accounting category for the replay buffer.
+const REPLAY_MEMORY_CATEGORY: &str = "hook_replay";
+
+/// Bounded history of emitted `(event, payload)` pairs.
+struct ReplayBuffer {
+    capacity: usize,
+    events: Mutex<BoundedBuffer<(String, Value)>>,
+}
+
+// ---------------------------------------------------------------------------
+// Snapshots
+// ---------------------------------------------------------------------------
+
+/// Handlers and default fields captured by [`HookRegistry::snapshot()`].
+#[derive(Clone)]
+pub struct HookSnapshot {
+    handlers: Arc<HandlerTable>,
+    defaults: Option<Arc<Value>>,
+}
+
+impl HookSnapshot {
+    /// Number of handlers captured, across all events.
+    pub fn handler_count(&self) -> usize {
+        self.handlers.values().map(|entries| entries.len()).sum()
+    }
+}
+
+impl fmt::Debug for HookSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookSnapshot")
+            .field("handlers", &self.handler_count())
+            .field("defaults", &self.defaults)
+            .finish()
+    }
+}
+
+/// Guard returned by [`HookRegistry::scoped()`]; restores the registry on drop.
+pub struct HookScope<'a> {
+    registry: &'a HookRegistry,
+    snapshot: HookSnapshot,
+}
+
+impl HookScope<'_> {
+    /// The state that will be restored.
+    pub fn snapshot(&self) -> &HookSnapshot {
+        &self.snapshot
+    }
+}
+
+impl std::ops::Deref for HookScope<'_> {
+    type Target = HookRegistry;
+
+    fn deref(&self) -> &HookRegistry {
+        self.registry
+    }
+}
+
+impl Drop for HookScope<'_> {
+    fn drop(&mut self) {
+        self.registry.restore(&self.snapshot);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Read the candidates a decision handler returned in `data`.
+fn parse_candidates(
+    event: &str,
+    handler_name: &str,
+    mut data: HashMap<String, Value>,
+) -> Vec<Candidate> {
+    let raw: Vec<Value> = match data.remove("candidates") {
+        Some(Value::Array(items)) => items,
+        Some(other) => vec![other],
+        None => vec![Value::Object(data.into_iter().collect())],
+    };
+    raw.into_iter()
+        .filter_map(|item| match serde_json::from_value::<Candidate>(item) {
+            Ok(candidate) => Some(candidate),
+            Err(e) => {
+                log::warn!(
+                    "Dropping malformed candidate from handler '{}' for event '{}': {e}",
+                    handler_name,
+                    event
+                );
+                None
+            }
+        })
+        .collect()
+}
+
 /// Merge two JSON values: `base` is overridden by `overlay`.
 /// Both should be objects; non-object values result in `overlay` winning.
 fn merge_json(base: &Value, overlay: &Value) -> Value {
@@ -400,6 +1458,21 @@ fn value_to_map(value: &Value) -> HashMap<String, Value> {
     }
 }
 
+/// Apply `limit` to the data `handler` returned for `event`.
+async fn spill_oversized(
+    limit: &HookDataLimit,
+    event: &str,
+    handler: &str,
+    data: &mut HashMap<String, Value>,
+) {
+    let spilled = limit.apply(data).await;
+    if spilled > 0 {
+        log::debug!(
+            "Spilled {spilled} value(s) of hook handler '{handler}' result for event '{event}' to attachments"
+        );
+    }
+}
+
 /// Merge multiple inject_context HookResults into a single result.
 ///
 /// Combines injections with `"\n\n"` separator, preserving settings from
@@ -810,6 +1883,70 @@ mod tests {
         assert!(injection.contains("second injection"));
     }
 
+    #[tokio::test]
+    async fn injections_are_remembered_with_their_handlers() {
+        let registry = HookRegistry::new();
+        let inject = |text: &str| {
+            Arc::new(SimpleHandler(HookResult {
+                action: HookAction::InjectContext,
+                context_injection: Some(text.into()),
+                ..Default::default()
+            }))
+        };
+        let _ = registry.register("a", inject("lint failed"), 0, Some("linter".into()));
+        let _ = registry.register("b", inject("one"), 0, Some("x".into()));
+        let _ = registry.register("b", inject("two"), 1, Some("y".into()));
+        registry.emit("a", serde_json::json!({})).await;
+        registry.emit("b", serde_json::json!({})).await;
+
+        assert_eq!(registry.take_injection("unrelated"), None);
+        assert_eq!(
+            registry.take_injection("<reminder>lint failed</reminder>"),
+            Some(vec!["linter".to_string()])
+        );
+        // Taken once.
+        assert_eq!(registry.take_injection("lint failed"), None);
+        assert_eq!(
+            registry.take_injection("one\n\ntwo"),
+            Some(vec!["x".to_string(), "y".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn queued_injections_over_budget_are_dropped_and_reported() {
+        use crate::injection_queue::{InjectionQueue, InjectionQueueConfig};
+
+        let registry = HookRegistry::new();
+        // 5 estimated tokens per turn.
+        registry.set_injection_queue(Arc::new(InjectionQueue::new(
+            InjectionQueueConfig::default(),
+            Some(5),
+        )));
+        let dropped = Arc::new(crate::testing::FakeHookHandler::new());
+        let _ = registry.register(events::CONTEXT_INJECTION_DROPPED, dropped.clone(), 0, None);
+        let inject = |text: &str| {
+            Arc::new(SimpleHandler(HookResult {
+                action: HookAction::InjectContext,
+                context_injection: Some(text.into()),
+                ..Default::default()
+            }))
+        };
+        let _ = registry.register("b", inject("later tip"), 5, Some("tips".into()));
+        let _ = registry.register("b", inject(&"x".repeat(16)), 10, Some("big".into()));
+        let _ = registry.register("b", inject("lint!"), 0, Some("linter".into()));
+
+        let result = registry.emit("b", serde_json::json!({})).await;
+        assert_eq!(
+            result.context_injection.as_deref(),
+            Some("lint!\n\nlater tip")
+        );
+        let events = dropped.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["source"], "big");
+        assert_eq!(events[0].1["reason"], "budget");
+        assert_eq!(events[0].1["remaining"], 2);
+    }
+
     // ---------------------------------------------------------------
     // Unregister
     // ---------------------------------------------------------------
@@ -971,6 +2108,87 @@ mod tests {
         assert_eq!(results[0]["fast"], serde_json::json!(true));
     }
 
+    // ---------------------------------------------------------------
+    // emit_decision
+    // ---------------------------------------------------------------
+
+    fn proposing(data: serde_json::Value) -> Arc<SimpleHandler> {
+        Arc::new(SimpleHandler(HookResult {
+            data: serde_json::from_value(data).ok(),
+            ..Default::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn emit_decision_ranks_and_deduplicates_candidates() {
+        let registry = HookRegistry::new();
+        let _ = registry.register(
+            "decision:tool_resolution",
+            proposing(serde_json::json!({"candidates": [
+                {"id": "grep", "score": 0.4},
+                {"id": "ripgrep", "score": 0.9, "confidence": 0.5},
+            ]})),
+            0,
+            Some("first".into()),
+        );
+        let _ = registry.register(
+            "decision:tool_resolution",
+            // A single-candidate response, outranking "first"'s grep.
+            proposing(
+                serde_json::json!({"id": "grep", "score": 0.7, "metadata": {"why": "exact"}}),
+            ),
+            10,
+            Some("second".into()),
+        );
+        let _ = registry.register(
+            "decision:tool_resolution",
+            proposing(serde_json::json!({"candidates": [
+                {"id": "ripgrep", "score": 0.9, "confidence": 0.8},
+                {"score": 1.0},
+            ]})),
+            20,
+            Some("third".into()),
+        );
+
+        let ranked = registry
+            .emit_decision(
+                "decision:tool_resolution",
+                serde_json::json!({}),
+                std::time::Duration::from_secs(1),
+            )
+            .await;
+
+        // The id-less entry was dropped; duplicates collapsed to the best.
+        let summary: Vec<_> = ranked
+            .iter()
+            .map(|c| (c.id.as_str(), c.source.as_str()))
+            .collect();
+        assert_eq!(summary, vec![("ripgrep", "third"), ("grep", "second")]);
+        assert_eq!(ranked[1].metadata["why"], "exact");
+    }
+
+    #[tokio::test]
+    async fn emit_decision_ties_keep_handler_order() {
+        let registry = HookRegistry::new();
+        for (name, id) in [("a", "alpha"), ("b", "beta")] {
+            let _ = registry.register(
+                "decision:agent",
+                proposing(serde_json::json!({"id": id, "score": 1.0})),
+                0,
+                Some(name.into()),
+            );
+        }
+        let ranked = registry
+            .emit_decision(
+                "decision:agent",
+                serde_json::json!({}),
+                std::time::Duration::from_secs(1),
+            )
+            .await;
+        let ids: Vec<_> = ranked.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["alpha", "beta"]);
+    }
+
     // ---------------------------------------------------------------
     // list_handlers
     // ---------------------------------------------------------------
@@ -989,15 +2207,63 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn list_handlers_filters_by_event() {
+    async fn list_handlers_filters_by_event() {
+        let registry = HookRegistry::new();
+        let h = Arc::new(SimpleHandler(HookResult::default()));
+        let _ = registry.register("tool:pre", h.clone(), 0, Some("my-hook".into()));
+        let _ = registry.register("tool:post", h, 0, Some("other-hook".into()));
+
+        let handlers = registry.list_handlers(Some("tool:pre"));
+        assert!(handlers.contains_key("tool:pre"));
+        assert!(!handlers.contains_key("tool:post"));
+    }
+
+    #[tokio::test]
+    async fn disabled_handlers_stay_registered_but_are_skipped() {
+        let registry = HookRegistry::new();
+        let noisy = Arc::new(CountingHandler::new());
+        let other = Arc::new(CountingHandler::new());
+        let _ = registry.register("tool:pre", noisy.clone(), 0, Some("noisy".into()));
+        let _ = registry.register("tool:post", noisy.clone(), 0, Some("noisy".into()));
+        let _ = registry.register("tool:pre", other.clone(), 5, Some("other".into()));
+
+        assert_eq!(registry.set_handler_enabled("noisy", false), 2);
+        assert_eq!(registry.set_handler_enabled("noisy", false), 0);
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        registry.emit("tool:post", serde_json::json!({})).await;
+        assert_eq!(noisy.call_count(), 0);
+        assert_eq!(other.call_count(), 1);
+        assert_eq!(
+            registry.list_handlers(Some("tool:pre"))["tool:pre"],
+            ["noisy", "other"]
+        );
+        assert_eq!(registry.disabled_handlers().len(), 2);
+
+        // Re-enable on one event only.
+        assert_eq!(
+            registry.set_handler_enabled_for("tool:post", "noisy", true),
+            1
+        );
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        registry.emit("tool:post", serde_json::json!({})).await;
+        assert_eq!(noisy.call_count(), 1);
+        assert_eq!(
+            registry.disabled_handlers(),
+            HashMap::from([("tool:pre".to_string(), vec!["noisy".to_string()])])
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_restores_enabled_state() {
         let registry = HookRegistry::new();
-        let h = Arc::new(SimpleHandler(HookResult::default()));
-        let _ = registry.register("tool:pre", h.clone(), 0, Some("my-hook".into()));
-        let _ = registry.register("tool:post", h, 0, Some("other-hook".into()));
+        let handler = Arc::new(CountingHandler::new());
+        let _ = registry.register("tool:pre", handler.clone(), 0, Some("h".into()));
+        let snapshot = registry.snapshot();
 
-        let handlers = registry.list_handlers(Some("tool:pre"));
-        assert!(handlers.contains_key("tool:pre"));
-        assert!(!handlers.contains_key("tool:post"));
+        registry.set_handler_enabled("h", false);
+        registry.restore(&snapshot);
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        assert_eq!(handler.call_count(), 1);
     }
 
     // ---------------------------------------------------------------
@@ -1051,6 +2317,23 @@ mod tests {
             .expect("overwritten timestamp must be valid ISO-8601");
     }
 
+    #[tokio::test]
+    async fn test_emit_timestamp_uses_registry_clock() {
+        let registry = HookRegistry::new();
+        let clock = crate::testing::ManualClock::default();
+        registry.set_clock(Arc::new(clock.clone()));
+        let handler = Arc::new(crate::testing::FakeHookHandler::new());
+        let _ = registry.register("test:event", handler.clone(), 0, None);
+
+        registry.emit("test:event", serde_json::json!({})).await;
+        clock.advance(Duration::from_millis(1500));
+        registry.emit("test:event", serde_json::json!({})).await;
+
+        let events = handler.recorded_events();
+        assert_eq!(events[0].1["timestamp"], "2025-01-01T00:00:00+00:00");
+        assert_eq!(events[1].1["timestamp"], "2025-01-01T00:00:01.500+00:00");
+    }
+
     #[tokio::test]
     async fn test_emit_and_collect_does_not_stamp_timestamp() {
         let registry = HookRegistry::new();
@@ -1092,6 +2375,86 @@ mod tests {
         assert_eq!(counter.call_count(), 1);
     }
 
+    #[tokio::test]
+    async fn event_filter_skips_all_but_pre_validation_handlers() {
+        use crate::event_filter::EventFilterConfig;
+
+        let registry = HookRegistry::new();
+        let observer = Arc::new(CountingHandler::new());
+        let validator = Arc::new(CountingHandler::new());
+        let _ = registry.register("content_block:delta", observer.clone(), 0, None);
+        let _ = registry.register_in_phase(
+            "content_block:delta",
+            validator.clone(),
+            HookPhase::PreValidation,
+            0,
+            None,
+        );
+
+        registry.set_event_filter(EventFilterConfig {
+            disabled: vec!["content_block:*".into()],
+            ..Default::default()
+        });
+        registry
+            .emit("content_block:delta", serde_json::json!({}))
+            .await;
+        assert_eq!(observer.call_count(), 0);
+        assert_eq!(validator.call_count(), 1);
+        assert_eq!(registry.event_filter().unwrap().suppressed_count(), 1);
+
+        // Toggled off at runtime without re-registering.
+        registry.clear_event_filter();
+        registry
+            .emit("content_block:delta", serde_json::json!({}))
+            .await;
+        assert_eq!(observer.call_count(), 1);
+        assert_eq!(validator.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn timing_observers_see_every_handler_call() {
+        let registry = HookRegistry::new();
+        let _ = registry.register("tool:pre", Arc::new(CountingHandler::new()), 0, None);
+        let _ = registry.register("tool:pre", Arc::new(FailingHandler), 1, Some("bad".into()));
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        registry.on_handler_timing(Arc::new(move |event, handler, _elapsed| {
+            sink.lock().unwrap().push(format!("{event}/{handler}"));
+        }));
+
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        registry
+            .emit_and_collect("tool:pre", serde_json::json!({}), Duration::from_secs(1))
+            .await;
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 4, "failed calls are timed too: {seen:?}");
+        assert!(seen[0].starts_with("tool:pre/handler-"));
+        assert_eq!(seen[1], "tool:pre/bad");
+    }
+
+    #[tokio::test]
+    async fn emit_observers_see_every_emit() {
+        use crate::event_filter::EventFilterConfig;
+
+        let registry = HookRegistry::new();
+        registry.set_event_filter(EventFilterConfig {
+            disabled: vec!["llm:*".into()],
+            ..Default::default()
+        });
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        registry.on_emit(Arc::new(move |event| {
+            sink.lock().unwrap().push(event.to_string());
+        }));
+
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        registry.emit("llm:request", serde_json::json!({})).await;
+
+        assert_eq!(*seen.lock().unwrap(), vec!["tool:pre", "llm:request"]);
+    }
+
     // ---------------------------------------------------------------
     // merge_inject_context_results -- append_to_last_tool_result
     // ---------------------------------------------------------------
@@ -1176,7 +2539,7 @@ mod tests {
     // Handler error logging via `log` crate (not eprintln!)
     // ---------------------------------------------------------------
 
-    use std::sync::OnceLock;
+    use std::sync::{Mutex, OnceLock};
 
     /// A simple test logger that captures log messages for assertion.
     struct TestLogger;
@@ -1291,4 +2654,508 @@ mod tests {
             logs
         );
     }
+
+    // ---------------------------------------------------------------
+    // Phases
+    // ---------------------------------------------------------------
+
+    fn deny(reason: &str) -> Arc<SimpleHandler> {
+        Arc::new(SimpleHandler(HookResult {
+            action: HookAction::Deny,
+            reason: Some(reason.into()),
+            ..Default::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn phases_run_in_order_regardless_of_priority() {
+        let registry = HookRegistry::new();
+        let log = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        for (label, phase, priority) in [
+            ("observe", HookPhase::Observation, -100),
+            ("mutate", HookPhase::Mutation, -50),
+            ("policy", HookPhase::Policy, 0),
+            ("validate", HookPhase::PreValidation, 100),
+        ] {
+            let handler = Arc::new(LoggingHandler {
+                label,
+                log: log.clone(),
+            });
+            let _ = registry.register_in_phase("test:event", handler, phase, priority, None);
+        }
+
+        registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(
+            *log.lock().await,
+            vec!["validate", "policy", "mutate", "observe"]
+        );
+    }
+
+    #[tokio::test]
+    async fn deny_outside_policy_phases_is_ignored() {
+        let registry = HookRegistry::new();
+        let after = Arc::new(CountingHandler::new());
+        let _ = registry.register_in_phase(
+            "test:event",
+            deny("too late"),
+            HookPhase::Mutation,
+            0,
+            None,
+        );
+        let _ =
+            registry.register_in_phase("test:event", after.clone(), HookPhase::Mutation, 10, None);
+
+        let result = registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(result.action, HookAction::Continue);
+        assert_eq!(after.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn observers_run_after_deny_and_see_who_denied() {
+        let registry = HookRegistry::new();
+        let mutator = Arc::new(CountingHandler::new());
+        let observer = Arc::new(crate::testing::FakeHookHandler::new());
+        let _ = registry.register_in_phase(
+            "test:event",
+            deny("blocked"),
+            HookPhase::PreValidation,
+            0,
+            Some("validator".into()),
+        );
+        let _ =
+            registry.register_in_phase("test:event", mutator.clone(), HookPhase::Mutation, 0, None);
+        let _ = registry.register_in_phase(
+            "test:event",
+            observer.clone(),
+            HookPhase::Observation,
+            0,
+            None,
+        );
+
+        let result = registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(result.action, HookAction::Deny);
+        assert_eq!(result.reason.as_deref(), Some("blocked"));
+        assert_eq!(mutator.call_count(), 0);
+
+        let events = observer.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["denied_by"]["handler"], "validator");
+        assert_eq!(events[0].1["denied_by"]["reason"], "blocked");
+    }
+
+    #[tokio::test]
+    async fn observer_results_are_ignored() {
+        let registry = HookRegistry::new();
+        let modifier = Arc::new(ModifyHandler {
+            key: "injected_key",
+            value: "injected_value",
+        });
+        let _ = registry.register_in_phase("test:event", modifier, HookPhase::Observation, 0, None);
+        let _ = registry.register_in_phase(
+            "test:event",
+            deny("ignored"),
+            HookPhase::Observation,
+            10,
+            None,
+        );
+
+        let result = registry
+            .emit("test:event", serde_json::json!({"original": true}))
+            .await;
+        assert_eq!(result.action, HookAction::Continue);
+        assert!(!result.data.unwrap().contains_key("injected_key"));
+    }
+
+    #[test]
+    fn hook_phase_names_round_trip() {
+        for phase in HookPhase::ALL {
+            assert_eq!(phase.as_str().parse::<HookPhase>().unwrap(), phase);
+            assert_eq!(
+                serde_json::to_value(phase).unwrap(),
+                serde_json::json!(phase.as_str())
+            );
+        }
+        assert!("late".parse::<HookPhase>().is_err());
+        assert_eq!(HookPhase::default(), HookPhase::Policy);
+    }
+
+    // ---------------------------------------------------------------
+    // Concurrency: copy-on-write handler snapshots
+    // ---------------------------------------------------------------
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_emit_and_registration_stress() {
+        const EMITTERS: usize = 8;
+        const EMITS: usize = 200;
+        const REGISTRARS: usize = 4;
+        const CYCLES: usize = 200;
+
+        let registry = Arc::new(HookRegistry::new());
+        let persistent = Arc::new(CountingHandler::new());
+        let _ = registry.register(
+            "stress:event",
+            persistent.clone(),
+            0,
+            Some("persistent".into()),
+        );
+
+        let mut tasks = Vec::new();
+        for _ in 0..EMITTERS {
+            let registry = registry.clone();
+            tasks.push(tokio::spawn(async move {
+                for _ in 0..EMITS {
+                    registry.emit("stress:event", serde_json::json!({})).await;
+                    tokio::task::yield_now().await;
+                }
+            }));
+        }
+        for r in 0..REGISTRARS {
+            let registry = registry.clone();
+            tasks.push(tokio::spawn(async move {
+                for c in 0..CYCLES {
+                    let unregister = registry.register(
+                        "stress:event",
+                        Arc::new(CountingHandler::new()),
+                        (c % 7) as i32,
+                        Some(format!("temp-{r}-{c}")),
+                    );
+                    tokio::task::yield_now().await;
+                    unregister();
+                }
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // Every emit reached the persistent handler, and every temporary
+        // registration was removed (no update lost to a racing swap).
+        assert_eq!(persistent.call_count(), EMITTERS * EMITS);
+        assert_eq!(
+            registry.list_handlers(Some("stress:event"))["stress:event"],
+            vec!["persistent".to_string()]
+        );
+    }
+
+    /// Handler that blocks until released, holding an emit in flight.
+    struct GateHandler {
+        started: Arc<tokio::sync::Notify>,
+        release: Arc<tokio::sync::Notify>,
+    }
+
+    impl HookHandler for GateHandler {
+        fn handle(
+            &self,
+            _event: &str,
+            _data: serde_json::Value,
+        ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+            Box::pin(async move {
+                self.started.notify_one();
+                self.release.notified().await;
+                Ok(HookResult::default())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn unregister_during_emit_does_not_affect_in_flight_snapshot() {
+        let started = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::new(tokio::sync::Notify::new());
+        let registry = Arc::new(HookRegistry::new());
+        let counter = Arc::new(CountingHandler::new());
+        let gate = Arc::new(GateHandler {
+            started: started.clone(),
+            release: release.clone(),
+        });
+        let _ = registry.register("test:event", gate, 0, None);
+        let unregister = registry.register("test:event", counter.clone(), 10, None);
+
+        let emitting = {
+            let registry = registry.clone();
+            tokio::spawn(async move { registry.emit("test:event", serde_json::json!({})).await })
+        };
+        started.notified().await;
+        unregister();
+        release.notify_one();
+        emitting.await.unwrap();
+
+        // The emit loaded its snapshot before the unregister swapped the table.
+        assert_eq!(counter.call_count(), 1);
+        release.notify_one();
+        registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(counter.call_count(), 1);
+    }
+
+    // ---------------------------------------------------------------
+    // Snapshot / restore
+    // ---------------------------------------------------------------
+
+    #[tokio::test]
+    async fn restore_drops_later_handlers_and_defaults() {
+        let registry = HookRegistry::new();
+        let base = Arc::new(CountingHandler::new());
+        let _ = registry.register("test:event", base.clone(), 0, None);
+        registry.set_default_fields(serde_json::json!({"session_id": "parent"}));
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.handler_count(), 1);
+
+        let added = Arc::new(CountingHandler::new());
+        let _ = registry.register("test:event", added.clone(), 0, None);
+        let _ = registry.register("other:event", added.clone(), 0, None);
+        registry.set_default_fields(serde_json::json!({"session_id": "child"}));
+
+        registry.restore(&snapshot);
+        let result = registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(base.call_count(), 1);
+        assert_eq!(added.call_count(), 0);
+        assert_eq!(result.data.unwrap()["session_id"], "parent");
+        assert!(!registry.list_handlers(None).contains_key("other:event"));
+    }
+
+    #[tokio::test]
+    async fn scoped_guard_restores_on_drop() {
+        let registry = HookRegistry::new();
+        let base = Arc::new(CountingHandler::new());
+        let unregister_base = registry.register("test:event", base.clone(), 0, None);
+
+        let scoped_handler = Arc::new(CountingHandler::new());
+        {
+            let scope = registry.scoped();
+            let _ = scope.register("test:event", scoped_handler.clone(), 0, None);
+            scope.emit("test:event", serde_json::json!({})).await;
+            assert_eq!(scoped_handler.call_count(), 1);
+        }
+        registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(scoped_handler.call_count(), 1);
+        assert_eq!(base.call_count(), 2);
+
+        // Unregister closures from before the scope still work after restore.
+        unregister_base();
+        registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(base.call_count(), 2);
+    }
+
+    #[test]
+    fn scoped_guard_restores_removed_handlers() {
+        let registry = HookRegistry::new();
+        let unregister = registry.register("test:event", Arc::new(CountingHandler::new()), 0, None);
+        {
+            let _scope = registry.scoped();
+            unregister();
+            assert!(registry.list_handlers(Some("test:event"))["test:event"].is_empty());
+        }
+        assert_eq!(
+            registry.list_handlers(Some("test:event"))["test:event"].len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn late_subscriber_receives_replayed_events() {
+        use crate::testing::FakeHookHandler;
+
+        let registry = HookRegistry::new();
+        registry.enable_replay(8);
+        registry.set_default_fields(serde_json::json!({"session_id": "s1"}));
+        registry
+            .emit("session:start", serde_json::json!({"n": 1}))
+            .await;
+        for n in 0..3 {
+            registry.emit("tool:pre", serde_json::json!({"n": n})).await;
+        }
+
+        let late = Arc::new(FakeHookHandler::new());
+        let _ = registry
+            .register_with_replay("tool:pre", late.clone(), 0, None, 2)
+            .await;
+        registry.emit("tool:pre", serde_json::json!({"n": 3})).await;
+
+        let seen = late.recorded_events();
+        let ns: Vec<_> = seen.iter().map(|(_, data)| data["n"].clone()).collect();
+        assert_eq!(ns, vec![1, 2, 3]);
+        assert_eq!(seen[0].1["replayed"], true);
+        assert_eq!(seen[0].1["session_id"], "s1");
+        assert!(seen[0].1["timestamp"].is_string());
+        assert!(seen[2].1.get("replayed").is_none());
+    }
+
+    #[tokio::test]
+    async fn replay_buffer_is_memory_accounted() {
+        use crate::memory::{MemoryConfig, MemoryUsage};
+
+        let memory = Arc::new(MemoryAccountant::new(MemoryConfig {
+            ceiling_bytes: Some(100),
+            ..Default::default()
+        }));
+        let registry = HookRegistry::new();
+        registry.set_memory(memory.clone());
+        registry.enable_replay(8);
+        for n in 0..8 {
+            registry
+                .emit("test:event", serde_json::json!({"n": n}))
+                .await;
+        }
+        // Each event is charged its name and stamped payload; the ceiling
+        // evicts the oldest long before the capacity is reached.
+        let MemoryUsage {
+            by_category,
+            evictions,
+            ..
+        } = memory.usage();
+        assert!(by_category["hook_replay"] <= 100);
+        assert!(evictions > 0);
+        assert!(registry.replay_len() < 8);
+
+        registry.enable_replay(0);
+        assert_eq!(memory.used_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn replay_buffer_is_bounded_and_optional() {
+        use crate::testing::FakeHookHandler;
+
+        let registry = HookRegistry::new();
+        registry.emit("test:event", serde_json::json!({})).await;
+        assert_eq!(registry.replay_len(), 0);
+
+        registry.enable_replay(2);
+        for n in 0..5 {
+            registry
+                .emit("test:event", serde_json::json!({"n": n}))
+                .await;
+        }
+        assert_eq!(registry.replay_len(), 2);
+        registry.enable_replay(1);
+        assert_eq!(registry.replay_len(), 1);
+
+        let late = Arc::new(FakeHookHandler::new());
+        let _ = registry
+            .register_with_replay("test:event", late.clone(), 0, None, 10)
+            .await;
+        assert_eq!(late.recorded_events()[0].1["n"], 4);
+
+        registry.enable_replay(0);
+        assert_eq!(registry.replay_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn conditional_handlers_only_see_matching_payloads() {
+        use crate::testing::FakeHookHandler;
+
+        let registry = HookRegistry::new();
+        let rewriter = Arc::new(FakeHookHandler::with_result(HookResult {
+            action: HookAction::Modify,
+            data: Some(HashMap::from([
+                ("tool_name".to_string(), serde_json::json!("bash")),
+                ("command".to_string(), serde_json::json!("rm -rf /tmp/x")),
+            ])),
+            ..Default::default()
+        }));
+        let guard = Arc::new(FakeHookHandler::new());
+        let condition: HookCondition = r#"data.tool_name == "bash" && data.command =~ "rm ""#
+            .parse()
+            .unwrap();
+        let _ = registry.register_with_condition(
+            "tool:pre",
+            rewriter,
+            HookPhase::Mutation,
+            0,
+            None,
+            r#"data.tool_name == "shell""#.parse().unwrap(),
+        );
+        let _ = registry.register_with_condition(
+            "tool:pre",
+            guard.clone(),
+            HookPhase::Observation,
+            0,
+            None,
+            condition,
+        );
+
+        registry
+            .emit(
+                "tool:pre",
+                serde_json::json!({"tool_name": "bash", "command": "ls"}),
+            )
+            .await;
+        assert!(guard.recorded_events().is_empty());
+
+        // The condition sees the payload as rewritten by earlier handlers.
+        registry
+            .emit(
+                "tool:pre",
+                serde_json::json!({"tool_name": "shell", "command": "ls"}),
+            )
+            .await;
+        let seen = guard.recorded_events();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].1["command"], "rm -rf /tmp/x");
+
+        let collected = registry
+            .emit_and_collect(
+                "tool:pre",
+                serde_json::json!({"tool_name": "read"}),
+                Duration::from_secs(1),
+            )
+            .await;
+        assert!(collected.is_empty());
+    }
+
+    #[tokio::test]
+    async fn slow_observers_are_reported_then_demoted() {
+        use crate::hooks::latency::LatencyBudgetConfig;
+        use crate::testing::FakeHookHandler;
+
+        struct Sleepy(Arc<AtomicUsize>);
+
+        impl HookHandler for Sleepy {
+            fn handle(
+                &self,
+                _event: &str,
+                _data: serde_json::Value,
+            ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>>
+            {
+                Box::pin(async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                    Ok(HookResult::default())
+                })
+            }
+        }
+
+        let registry = HookRegistry::new();
+        registry.set_latency_budget(Arc::new(LatencyBudget::new(LatencyBudgetConfig {
+            budget_ms: 1,
+            demote_after: 1,
+            ..Default::default()
+        })));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let _ = registry.register_in_phase(
+            "tool:pre",
+            Arc::new(Sleepy(calls.clone())),
+            HookPhase::Observation,
+            0,
+            Some("audit".into()),
+        );
+        let reports = Arc::new(FakeHookHandler::new());
+        let _ = registry.register(events::HOOK_SLOW_HANDLER, reports.clone(), 0, None);
+
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let recorded = reports.recorded_events();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].1["handler"], "audit");
+        assert_eq!(recorded[0].1["event"], "tool:pre");
+        assert_eq!(recorded[0].1["demoted"], true);
+        assert!(recorded[0].1["elapsed_ms"].as_u64().unwrap() >= 50);
+
+        // Demoted: the emit no longer waits for the handler.
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(reports.recorded_events().len(), 1);
+
+        registry.latency_budget().unwrap().restore("audit");
+        registry.emit("tool:pre", serde_json::json!({})).await;
+        assert!(calls.load(Ordering::SeqCst) >= 2);
+    }
 }
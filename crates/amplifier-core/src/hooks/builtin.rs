@@ -0,0 +1,766 @@
+//! Ready-made hook handlers.
+//!
+//! Pure-Rust deployments can mount these from the mount plan without writing
+//! any handler code. [`Session`](crate::session::Session) provides them to
+//! [hook subscriptions](crate::hook_subscriptions) under these names:
+//!
+//! | Name                     | Handler              | Provided when                   |
+//! |--------------------------|----------------------|---------------------------------|
+//! | `builtin:logging`        | [`LoggingHook`]      | always                          |
+//! | `builtin:token_budget`   | [`TokenBudgetGuard`] | `session.quota` sets any limit  |
+//! | `builtin:content_filter` | [`ContentFilter`]    | always                          |
+//! | `builtin:moderation`     | [`ModerationHook`]   | always                          |
+//!
+//! Options are read from `session.hooks.builtin`:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "quota": {"max_total_tokens": 200000},
+//!     "hooks": {
+//!       "builtin": {
+//!         "logging": {"level": "debug", "payloads": false},
+//!         "content_filter": {"terms": ["hunter2"], "redact_emails": true},
+//!         "moderation": {"action": "deny", "categories": ["violence"]}
+//!       },
+//!       "subscriptions": [
+//!         {"event": "tool:post", "handler": "builtin:logging", "phase": "observation"},
+//!         {"event": "provider:pre", "handler": "builtin:token_budget"},
+//!         {"event": "prompt:submit", "handler": "builtin:content_filter", "phase": "mutation"},
+//!         {"event": "prompt:submit", "handler": "builtin:moderation"},
+//!         {"event": "tool:post", "handler": "builtin:moderation"}
+//!       ]
+//!     }
+//!   }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::coordinator::{Coordinator, ModeratorSlot};
+use crate::errors::HookError;
+use crate::hook_subscriptions::HookHandlerSet;
+use crate::models::{HookAction, HookResult, ModerationResult};
+use crate::quota::{QuotaEnforcer, QuotaResource};
+use crate::token_counter::ContextBudget;
+use crate::traits::{HookHandler, Moderator};
+
+/// Handler name of [`LoggingHook`].
+pub const LOGGING: &str = "builtin:logging";
+/// Handler name of [`TokenBudgetGuard`].
+pub const TOKEN_BUDGET: &str = "builtin:token_budget";
+/// Handler name of [`ContentFilter`].
+pub const CONTENT_FILTER: &str = "builtin:content_filter";
+/// Handler name of [`ModerationHook`].
+pub const MODERATION: &str = "builtin:moderation";
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// The `session.hooks.builtin` section.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuiltinHooksConfig {
+    pub logging: LoggingConfig,
+    pub content_filter: ContentFilterConfig,
+    pub moderation: ModerationConfig,
+}
+
+impl BuiltinHooksConfig {
+    /// Read `session.hooks.builtin` from a mount plan, falling back to the
+    /// defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config
+            .get("session")
+            .and_then(|s| s.get("hooks"))
+            .and_then(|h| h.get("builtin"))
+        else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.hooks.builtin config: {e}");
+            Self::default()
+        })
+    }
+}
+
+/// Options for [`LoggingHook`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: String,
+    /// Include the event payload in each record.
+    pub payloads: bool,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".into(),
+            payloads: true,
+        }
+    }
+}
+
+/// Options for [`ContentFilter`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ContentFilterConfig {
+    /// Words and phrases to redact (ASCII case-insensitive, whole words).
+    pub terms: Vec<String>,
+    pub redact_emails: bool,
+    /// Text that replaces each match.
+    pub replacement: String,
+}
+
+impl Default for ContentFilterConfig {
+    fn default() -> Self {
+        Self {
+            terms: Vec::new(),
+            redact_emails: true,
+            replacement: "[redacted]".into(),
+        }
+    }
+}
+
+/// What [`ModerationHook`] does with flagged content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    /// Deny the event.
+    #[default]
+    Deny,
+    /// Let it through, with the classification added to the payload under
+    /// `moderation`.
+    Flag,
+}
+
+/// Options for [`ModerationHook`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModerationConfig {
+    pub action: ModerationAction,
+    /// Act only on these categories; empty acts on any flagged content.
+    pub categories: Vec<String>,
+    /// Deny when the moderator fails, instead of letting content through.
+    pub fail_closed: bool,
+}
+
+/// The built-in handlers, by name, for a session with the given options,
+/// quota enforcer and coordinator.
+pub fn handlers(
+    config: &BuiltinHooksConfig,
+    quota: Option<&Arc<QuotaEnforcer>>,
+    coordinator: &Coordinator,
+) -> HookHandlerSet {
+    let level = log::Level::from_str(&config.logging.level).unwrap_or_else(|_| {
+        log::warn!(
+            "Unknown log level '{}' for {LOGGING} — using info",
+            config.logging.level
+        );
+        log::Level::Info
+    });
+    let mut set = HookHandlerSet::new()
+        .with(
+            LOGGING,
+            Arc::new(LoggingHook::new(level).with_payloads(config.logging.payloads)),
+        )
+        .with(
+            CONTENT_FILTER,
+            Arc::new(ContentFilter::new(config.content_filter.clone())),
+        )
+        .with(
+            MODERATION,
+            Arc::new(ModerationHook::mounted(
+                coordinator,
+                config.moderation.clone(),
+            )),
+        );
+    if let Some(quota) = quota {
+        set.insert(
+            TOKEN_BUDGET,
+            Arc::new(TokenBudgetGuard::new(Arc::clone(quota))),
+        );
+    }
+    set
+}
+
+// ---------------------------------------------------------------------------
+// LoggingHook
+// ---------------------------------------------------------------------------
+
+/// Writes every event it receives to the [`log`] facade, under the
+/// `amplifier_core::events` target.
+#[derive(Debug, Clone)]
+pub struct LoggingHook {
+    level: log::Level,
+    payloads: bool,
+}
+
+impl LoggingHook {
+    /// Log at `level`, with payloads.
+    pub fn new(level: log::Level) -> Self {
+        Self {
+            level,
+            payloads: true,
+        }
+    }
+
+    /// Whether each record includes the event payload.
+    pub fn with_payloads(mut self, payloads: bool) -> Self {
+        self.payloads = payloads;
+        self
+    }
+}
+
+impl HookHandler for LoggingHook {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        if self.payloads {
+            log::log!(target: "amplifier_core::events", self.level, "{event} {data}");
+        } else {
+            log::log!(target: "amplifier_core::events", self.level, "{event}");
+        }
+        Box::pin(async { Ok(HookResult::default()) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TokenBudgetGuard
+// ---------------------------------------------------------------------------
+
+/// Denies a provider call whose request would take the session past its
+/// `max_total_tokens` quota.
+///
+/// The quota enforcer alone only learns token usage from responses, so the
+/// call that crosses the limit still runs. The guard estimates the request
+/// (its messages plus `max_output_tokens`) from a `provider:pre` payload and
+/// denies it up front. Payloads without a `request` are denied only once
+/// the budget is used up. Does nothing when no token quota is set.
+pub struct TokenBudgetGuard {
+    quota: Arc<QuotaEnforcer>,
+    budget: ContextBudget,
+}
+
+impl TokenBudgetGuard {
+    /// Guard the token quota of `quota`, estimating with [`ContextBudget::default`].
+    pub fn new(quota: Arc<QuotaEnforcer>) -> Self {
+        Self {
+            quota,
+            budget: ContextBudget::default(),
+        }
+    }
+
+    /// Estimate requests with `budget`'s token counter.
+    pub fn with_budget(mut self, budget: ContextBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Estimated tokens a request payload will use.
+    fn estimate(&self, request: &Value) -> u64 {
+        let model = request.get("model").and_then(Value::as_str).unwrap_or("");
+        let input: usize = request
+            .get("messages")
+            .and_then(Value::as_array)
+            .map(|messages| {
+                messages
+                    .iter()
+                    .map(|m| self.budget.count_value(model, m))
+                    .sum()
+            })
+            .unwrap_or(0);
+        let output = request
+            .get("max_output_tokens")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        input as u64 + output
+    }
+}
+
+impl HookHandler for TokenBudgetGuard {
+    fn handle(
+        &self,
+        _event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        let denial = self.quota.config().max_total_tokens.and_then(|limit| {
+            let used = self.quota.used(QuotaResource::TotalTokens);
+            let estimate = data.get("request").map_or(0, |r| self.estimate(r));
+            (used >= limit || used + estimate > limit).then(|| {
+                format!("token budget: {used} used + ~{estimate} requested would exceed {limit}")
+            })
+        });
+        Box::pin(async move {
+            Ok(match denial {
+                Some(reason) => HookResult {
+                    action: HookAction::Deny,
+                    reason: Some(reason),
+                    ..Default::default()
+                },
+                None => HookResult::default(),
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ContentFilter
+// ---------------------------------------------------------------------------
+
+/// Redacts configured terms and email addresses from every string in an
+/// event payload, returning the result as a `Modify`.
+///
+/// This is a baseline, not a PII detector: it matches whole words from a
+/// fixed list and `local@domain.tld` shapes only. Deployments with real
+/// requirements should mount a dedicated filter module.
+#[derive(Debug, Clone)]
+pub struct ContentFilter {
+    config: ContentFilterConfig,
+}
+
+impl ContentFilter {
+    pub fn new(config: ContentFilterConfig) -> Self {
+        Self { config }
+    }
+
+    /// `text` with every match replaced, or `None` if nothing matched.
+    pub fn redact(&self, text: &str) -> Option<String> {
+        let mut out = redact_terms(text, &self.config.terms, &self.config.replacement);
+        if self.config.redact_emails {
+            out = redact_emails(&out, &self.config.replacement);
+        }
+        (out != text).then_some(out)
+    }
+
+    fn redact_value(&self, value: &mut Value) -> bool {
+        match value {
+            Value::String(s) => match self.redact(s) {
+                Some(redacted) => {
+                    *s = redacted;
+                    true
+                }
+                None => false,
+            },
+            Value::Array(items) => {
+                let mut changed = false;
+                for item in items {
+                    changed |= self.redact_value(item);
+                }
+                changed
+            }
+            Value::Object(map) => {
+                let mut changed = false;
+                for item in map.values_mut() {
+                    changed |= self.redact_value(item);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+}
+
+impl HookHandler for ContentFilter {
+    fn handle(
+        &self,
+        _event: &str,
+        mut data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        let modified = match self.redact_value(&mut data) {
+            true => serde_json::from_value::<HashMap<String, Value>>(data).ok(),
+            false => None,
+        };
+        Box::pin(async move {
+            Ok(match modified {
+                Some(data) => HookResult {
+                    action: HookAction::Modify,
+                    data: Some(data),
+                    ..Default::default()
+                },
+                None => HookResult::default(),
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ModerationHook
+// ---------------------------------------------------------------------------
+
+/// Runs a [`Moderator`] over the
+/// `prompt` of a `prompt:submit` payload or the `tool_result` of a
+/// `tool:post` payload, and denies or flags it per [`ModerationConfig`].
+///
+/// The built-in handler uses whichever moderator the coordinator has mounted
+/// when an event arrives, and does nothing while none is, or when the
+/// payload has neither field. A moderator failure is logged and lets the content through unless
+/// `fail_closed` is set.
+pub struct ModerationHook {
+    moderator: ModeratorSlot,
+    config: ModerationConfig,
+}
+
+impl ModerationHook {
+    /// Moderate with `moderator`.
+    pub fn new(moderator: Arc<dyn Moderator>, config: ModerationConfig) -> Self {
+        Self {
+            moderator: Arc::new(RwLock::new(Some(moderator))),
+            config,
+        }
+    }
+
+    /// Moderate with the moderator `coordinator` has mounted.
+    pub(crate) fn mounted(coordinator: &Coordinator, config: ModerationConfig) -> Self {
+        Self {
+            moderator: coordinator.moderator_slot(),
+            config,
+        }
+    }
+
+    /// Whether `result` calls for the configured action.
+    fn applies(&self, result: &ModerationResult) -> bool {
+        result.flagged
+            && (self.config.categories.is_empty()
+                || result
+                    .categories
+                    .iter()
+                    .any(|c| self.config.categories.contains(c)))
+    }
+
+    fn verdict(&self, mut data: Value, result: ModerationResult) -> HookResult {
+        if !self.applies(&result) {
+            return HookResult::default();
+        }
+        let reason = match &result.reason {
+            Some(reason) => format!("moderation: {reason}"),
+            None => format!("moderation: flagged for {}", result.categories.join(", ")),
+        };
+        match self.config.action {
+            ModerationAction::Deny => HookResult {
+                action: HookAction::Deny,
+                reason: Some(reason),
+                ..Default::default()
+            },
+            ModerationAction::Flag => {
+                log::warn!("{MODERATION} flagged content — {reason}");
+                if let Value::Object(map) = &mut data {
+                    map.insert(
+                        "moderation".into(),
+                        serde_json::to_value(&result).unwrap_or_default(),
+                    );
+                }
+                HookResult {
+                    action: HookAction::Modify,
+                    data: serde_json::from_value(data).ok(),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+}
+
+/// The text a moderation hook classifies in an event payload.
+fn moderated_text(data: &Value) -> Option<String> {
+    if let Some(prompt) = data.get("prompt").and_then(Value::as_str) {
+        return Some(prompt.to_string());
+    }
+    match data.get("tool_result")? {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+impl HookHandler for ModerationHook {
+    fn handle(
+        &self,
+        _event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        let moderator = self.moderator.read().unwrap().clone();
+        let text = moderated_text(&data);
+        Box::pin(async move {
+            let (Some(moderator), Some(text)) = (moderator, text) else {
+                return Ok(HookResult::default());
+            };
+            Ok(match moderator.moderate(&text).await {
+                Ok(result) => self.verdict(data, result),
+                Err(e) if self.config.fail_closed => HookResult {
+                    action: HookAction::Deny,
+                    reason: Some(format!("moderation unavailable: {e}")),
+                    ..Default::default()
+                },
+                Err(e) => {
+                    log::warn!("{MODERATION} skipped — moderator failed: {e}");
+                    HookResult::default()
+                }
+            })
+        })
+    }
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Replace whole-word, ASCII case-insensitive occurrences of `terms`.
+fn redact_terms(text: &str, terms: &[String], replacement: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    'scan: while i < text.len() {
+        let rest = &text.as_bytes()[i..];
+        let before = text[..i].chars().next_back();
+        if !is_word_char(before) {
+            for term in terms.iter().filter(|t| !t.is_empty()) {
+                let end = i + term.len();
+                if rest.len() >= term.len()
+                    && rest[..term.len()].eq_ignore_ascii_case(term.as_bytes())
+                    && text.is_char_boundary(end)
+                    && !is_word_char(text[end..].chars().next())
+                {
+                    out.push_str(replacement);
+                    i = end;
+                    continue 'scan;
+                }
+            }
+        }
+        let c = text[i..].chars().next().unwrap_or_default();
+        out.push(c);
+        i += c.len_utf8();
+    }
+    out
+}
+
+fn is_email(word: &str) -> bool {
+    let Some((local, domain)) = word.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+}
+
+/// Replace whitespace-separated words shaped like email addresses, keeping
+/// surrounding punctuation.
+fn redact_emails(text: &str, replacement: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(word_end);
+        let core = word.trim_matches(|c: char| !c.is_alphanumeric());
+        if is_email(core) {
+            let start = word.find(core).unwrap_or(0);
+            out.push_str(&word[..start]);
+            out.push_str(replacement);
+            out.push_str(&word[start + core.len()..]);
+        } else {
+            out.push_str(word);
+        }
+        let space_end = tail
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(tail.len());
+        out.push_str(&tail[..space_end]);
+        rest = &tail[space_end..];
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events;
+    use crate::hooks::HookRegistry;
+    use crate::quota::QuotaConfig;
+    use crate::testing::{FakeModerator, ManualClock};
+    use serde_json::json;
+
+    fn guarded_hooks(max_total_tokens: u64) -> (Arc<HookRegistry>, Arc<QuotaEnforcer>) {
+        let hooks = Arc::new(HookRegistry::new());
+        let quota = Arc::new(QuotaEnforcer::new(
+            QuotaConfig {
+                max_total_tokens: Some(max_total_tokens),
+                ..Default::default()
+            },
+            Arc::new(ManualClock::default()),
+        ));
+        quota.install(&hooks);
+        let _ = hooks.register(
+            events::PROVIDER_PRE,
+            Arc::new(TokenBudgetGuard::new(Arc::clone(&quota))),
+            0,
+            Some(TOKEN_BUDGET.into()),
+        );
+        (hooks, quota)
+    }
+
+    fn pre(text: &str, max_output_tokens: u64) -> Value {
+        json!({"provider": "fake", "request": {
+            "messages": [{"role": "user", "content": text}],
+            "max_output_tokens": max_output_tokens
+        }})
+    }
+
+    #[tokio::test]
+    async fn token_budget_denies_calls_that_would_exceed_the_quota() {
+        let (hooks, _quota) = guarded_hooks(1_000);
+        let result = hooks.emit(events::PROVIDER_PRE, pre("hi", 100)).await;
+        assert_eq!(result.action, HookAction::Continue);
+
+        hooks
+            .emit(
+                events::PROVIDER_POST,
+                json!({"response": {"usage": {"input_tokens": 500, "output_tokens": 300, "total_tokens": 800}}}),
+            )
+            .await;
+        let result = hooks.emit(events::PROVIDER_PRE, pre("hi", 100)).await;
+        assert_eq!(result.action, HookAction::Continue);
+        let result = hooks.emit(events::PROVIDER_PRE, pre("hi", 400)).await;
+        assert_eq!(result.action, HookAction::Deny);
+        assert!(result.reason.unwrap().contains("800 used"));
+    }
+
+    #[test]
+    fn content_filter_redacts_whole_terms_and_emails() {
+        let filter = ContentFilter::new(ContentFilterConfig {
+            terms: vec!["darn".into(), "secret project".into()],
+            ..Default::default()
+        });
+        assert_eq!(
+            filter
+                .redact("Darn, mail (ops@example.com) about the Secret Project.")
+                .unwrap(),
+            "[redacted], mail ([redacted]) about the [redacted]."
+        );
+        assert_eq!(filter.redact("darned @handle a@b user@host"), None);
+    }
+
+    #[tokio::test]
+    async fn content_filter_modifies_payload_strings() {
+        let hooks = HookRegistry::new();
+        let _ = hooks.register(
+            events::PROMPT_SUBMIT,
+            Arc::new(ContentFilter::new(ContentFilterConfig::default())),
+            0,
+            Some(CONTENT_FILTER.into()),
+        );
+        let result = hooks
+            .emit(
+                events::PROMPT_SUBMIT,
+                json!({"prompt": "email me at me@example.org"}),
+            )
+            .await;
+        assert_eq!(result.data.unwrap()["prompt"], "email me at [redacted]");
+
+        let filter = ContentFilter::new(ContentFilterConfig::default());
+        let result = filter
+            .handle(events::PROMPT_SUBMIT, json!({"prompt": "nothing to hide"}))
+            .await
+            .unwrap();
+        assert_eq!(result.action, HookAction::Continue);
+    }
+
+    #[test]
+    fn handlers_are_named_and_token_budget_needs_a_quota() {
+        let config = BuiltinHooksConfig::from_session_config(&HashMap::from([(
+            "session".to_string(),
+            json!({"hooks": {"builtin": {"logging": {"level": "debug", "payloads": false}}}}),
+        )]));
+        assert_eq!(config.logging.level, "debug");
+
+        let coordinator = Coordinator::new_for_test();
+        let set = handlers(&config, None, &coordinator);
+        assert!(set.get(LOGGING).is_some());
+        assert!(set.get(CONTENT_FILTER).is_some());
+        assert!(set.get(MODERATION).is_some());
+        assert!(set.get(TOKEN_BUDGET).is_none());
+
+        let (_hooks, quota) = guarded_hooks(10);
+        assert!(handlers(&config, Some(&quota), &coordinator)
+            .get(TOKEN_BUDGET)
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn moderation_denies_or_flags_per_policy() {
+        let coordinator = Coordinator::new_for_test();
+        let moderator = Arc::new(FakeModerator::flagging(&["attack"]));
+        let deny = ModerationHook::mounted(&coordinator, ModerationConfig::default());
+
+        // No moderator mounted: nothing happens.
+        let result = deny
+            .handle(events::PROMPT_SUBMIT, json!({"prompt": "attack"}))
+            .await
+            .unwrap();
+        assert_eq!(result.action, HookAction::Continue);
+
+        coordinator.set_moderator(moderator.clone());
+        let result = deny
+            .handle(events::PROMPT_SUBMIT, json!({"prompt": "plan the attack"}))
+            .await
+            .unwrap();
+        assert_eq!(result.action, HookAction::Deny);
+        assert_eq!(
+            result.reason.as_deref(),
+            Some("moderation: flagged for test")
+        );
+        let result = deny
+            .handle(
+                events::TOOL_POST,
+                json!({"tool_result": {"output": "fine"}}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.action, HookAction::Continue);
+        assert_eq!(moderator.moderated()[1], r#"{"output":"fine"}"#);
+
+        let flag = ModerationHook::new(
+            moderator.clone(),
+            ModerationConfig {
+                action: ModerationAction::Flag,
+                ..Default::default()
+            },
+        );
+        let result = flag
+            .handle(events::TOOL_POST, json!({"tool_result": "attack at dawn"}))
+            .await
+            .unwrap();
+        assert_eq!(result.action, HookAction::Modify);
+        assert_eq!(
+            result.data.unwrap()["moderation"]["categories"],
+            json!(["test"])
+        );
+
+        let other_categories = ModerationHook::new(
+            moderator,
+            ModerationConfig {
+                categories: vec!["violence".into()],
+                ..Default::default()
+            },
+        );
+        let result = other_categories
+            .handle(events::PROMPT_SUBMIT, json!({"prompt": "attack"}))
+            .await
+            .unwrap();
+        assert_eq!(result.action, HookAction::Continue);
+    }
+}
@@ -0,0 +1,662 @@
+//! Declarative handler conditions.
+//!
+//! A handler registered with
+//! [`register_with_condition()`](super::HookRegistry::register_with_condition)
+//! is only called for events its [`HookCondition`] holds for. The kernel
+//! evaluates the condition before dispatch, so events a handler would ignore
+//! never cross into it — which matters most for handlers behind the Python
+//! or gRPC boundary.
+//!
+//! ```text
+//! data.tool_name == "bash" && data.tool_input.command =~ "rm "
+//! ```
+//!
+//! | Form                                   | Meaning                                             |
+//! |----------------------------------------|-----------------------------------------------------|
+//! | `data`, `data.a.b`, `data.items[0]`, `data["a b"]` | The payload or one of its fields; missing fields are `null` |
+//! | `event`                                | The event name                                      |
+//! | `"text"`, `42`, `-1.5`, `true`, `false`, `null` | Literals                                   |
+//! | `==`, `!=`                             | JSON equality; numbers compare by value (`1 == 1.0`) |
+//! | `<`, `<=`, `>`, `>=`                   | Two numbers or two strings; false otherwise         |
+//! | `=~`                                   | String contains string, or array contains value     |
+//! | `!`, `&&`, `\|\|`, `( )`               | Logic                                               |
+//!
+//! `!` binds tightest, then comparisons, then `&&`, then `||`. A value used
+//! as a condition is true unless it is `null`, `false`, `0`, `""`, `[]` or
+//! `{}`. Evaluation has no side effects and never fails: a type mismatch is
+//! simply false.
+//!
+//! Conditions are evaluated against the payload each handler would receive,
+//! so they see changes made by earlier `Modify` handlers.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Number, Value};
+
+/// Maximum nesting of parentheses and `!`, bounding parser recursion.
+const MAX_DEPTH: usize = 32;
+
+// ---------------------------------------------------------------------------
+// ConditionError
+// ---------------------------------------------------------------------------
+
+/// A condition that failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid hook condition at offset {offset}: {message}")]
+pub struct ConditionError {
+    /// Byte offset into the source where parsing failed.
+    pub offset: usize,
+    pub message: String,
+}
+
+impl ConditionError {
+    fn new(offset: usize, message: impl Into<String>) -> Self {
+        Self {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HookCondition
+// ---------------------------------------------------------------------------
+
+/// A parsed condition. Compares, displays and serializes as its source text.
+#[derive(Clone)]
+pub struct HookCondition {
+    source: String,
+    expr: Expr,
+}
+
+impl HookCondition {
+    /// Parse `source` (see the [module docs](self) for the syntax).
+    pub fn parse(source: &str) -> Result<Self, ConditionError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: source.len(),
+            depth: 0,
+        };
+        let expr = parser.or()?;
+        if let Some((offset, token)) = parser.tokens.get(parser.pos) {
+            return Err(ConditionError::new(
+                *offset,
+                format!("unexpected {}", token.describe()),
+            ));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the condition holds for `event` with payload `data`.
+    pub fn matches(&self, event: &str, data: &Value) -> bool {
+        truthy(&self.expr.eval(event, data))
+    }
+}
+
+impl fmt::Debug for HookCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HookCondition").field(&self.source).finish()
+    }
+}
+
+impl fmt::Display for HookCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl PartialEq for HookCondition {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl FromStr for HookCondition {
+    type Err = ConditionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for HookCondition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for HookCondition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::parse(&source).map_err(serde::de::Error::custom)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Value),
+    Event,
+    Path(Vec<Segment>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval<'a>(&'a self, event: &'a str, data: &'a Value) -> Cow<'a, Value> {
+        match self {
+            Expr::Literal(value) => Cow::Borrowed(value),
+            Expr::Event => Cow::Owned(Value::String(event.to_string())),
+            Expr::Path(segments) => {
+                let mut current = data;
+                for segment in segments {
+                    let next = match segment {
+                        Segment::Key(key) => current.get(key.as_str()),
+                        Segment::Index(index) => current.get(*index),
+                    };
+                    match next {
+                        Some(value) => current = value,
+                        None => return Cow::Owned(Value::Null),
+                    }
+                }
+                Cow::Borrowed(current)
+            }
+            Expr::Not(inner) => Cow::Owned(Value::Bool(!truthy(&inner.eval(event, data)))),
+            Expr::And(left, right) => Cow::Owned(Value::Bool(
+                truthy(&left.eval(event, data)) && truthy(&right.eval(event, data)),
+            )),
+            Expr::Or(left, right) => Cow::Owned(Value::Bool(
+                truthy(&left.eval(event, data)) || truthy(&right.eval(event, data)),
+            )),
+            Expr::Compare(op, left, right) => {
+                let (left, right) = (left.eval(event, data), right.eval(event, data));
+                Cow::Owned(Value::Bool(compare(*op, &left, &right)))
+            }
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => left == right,
+    }
+}
+
+fn compare(op: Op, left: &Value, right: &Value) -> bool {
+    let ordering = || match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        Op::Eq => equal(left, right),
+        Op::Ne => !equal(left, right),
+        Op::Lt => ordering() == Some(Ordering::Less),
+        Op::Le => matches!(ordering(), Some(Ordering::Less | Ordering::Equal)),
+        Op::Gt => ordering() == Some(Ordering::Greater),
+        Op::Ge => matches!(ordering(), Some(Ordering::Greater | Ordering::Equal)),
+        Op::Contains => match (left, right) {
+            (Value::String(haystack), Value::String(needle)) => haystack.contains(needle.as_str()),
+            (Value::Array(items), needle) => items.iter().any(|item| equal(item, needle)),
+            _ => false,
+        },
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(Number),
+    Dot,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Op(Op),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Ident(name) => format!("'{name}'"),
+            Token::Str(s) => format!("string {s:?}"),
+            Token::Num(n) => format!("number {n}"),
+            Token::Dot => "'.'".into(),
+            Token::LBracket => "'['".into(),
+            Token::RBracket => "']'".into(),
+            Token::LParen => "'('".into(),
+            Token::RParen => "')'".into(),
+            Token::Not => "'!'".into(),
+            Token::And => "'&&'".into(),
+            Token::Or => "'||'".into(),
+            Token::Op(_) => "operator".into(),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ConditionError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let two = source.get(start..start + 2).unwrap_or_default();
+        let two_char = match two {
+            "&&" => Some(Token::And),
+            "||" => Some(Token::Or),
+            "==" => Some(Token::Op(Op::Eq)),
+            "!=" => Some(Token::Op(Op::Ne)),
+            "<=" => Some(Token::Op(Op::Le)),
+            ">=" => Some(Token::Op(Op::Ge)),
+            "=~" => Some(Token::Op(Op::Contains)),
+            _ => None,
+        };
+        if let Some(token) = two_char {
+            chars.next();
+            chars.next();
+            tokens.push((start, token));
+            continue;
+        }
+        let token = match c {
+            '.' => Token::Dot,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '!' => Token::Not,
+            '<' => Token::Op(Op::Lt),
+            '>' => Token::Op(Op::Gt),
+            '"' => {
+                chars.next();
+                tokens.push((start, Token::Str(string_literal(start, &mut chars)?)));
+                continue;
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.' || (i == start && c == '-')) {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let text = &source[start..end];
+                let number = text
+                    .parse::<i64>()
+                    .map(Number::from)
+                    .ok()
+                    .or_else(|| text.parse::<f64>().ok().and_then(Number::from_f64))
+                    .ok_or_else(|| {
+                        ConditionError::new(start, format!("invalid number '{text}'"))
+                    })?;
+                tokens.push((start, Token::Num(number)));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push((start, Token::Ident(source[start..end].to_string())));
+                continue;
+            }
+            other => {
+                return Err(ConditionError::new(
+                    start,
+                    format!("unexpected character '{other}'"),
+                ))
+            }
+        };
+        chars.next();
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Read a string literal whose opening quote at `start` was consumed.
+fn string_literal(
+    start: usize,
+    chars: &mut std::iter::Peekable<std::str::CharIndices<'_>>,
+) -> Result<String, ConditionError> {
+    let mut text = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok(text),
+            '\\' => match chars.next() {
+                Some((_, '"')) => text.push('"'),
+                Some((_, '\\')) => text.push('\\'),
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, other)) => {
+                    return Err(ConditionError::new(
+                        i,
+                        format!("unknown escape '\\{other}'"),
+                    ))
+                }
+                None => break,
+            },
+            c => text.push(c),
+        }
+    }
+    Err(ConditionError::new(start, "unterminated string"))
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Source length, reported as the offset of "unexpected end" errors.
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |(offset, _)| *offset)
+    }
+
+    fn next(&mut self) -> Result<Token, ConditionError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|(_, token)| token.clone())
+            .ok_or_else(|| ConditionError::new(self.end, "unexpected end of condition"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ConditionError> {
+        let offset = self.offset();
+        let token = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(ConditionError::new(
+                offset,
+                format!(
+                    "expected {}, found {}",
+                    expected.describe(),
+                    token.describe()
+                ),
+            ))
+        }
+    }
+
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ConditionError>,
+    ) -> Result<T, ConditionError> {
+        if self.depth == MAX_DEPTH {
+            return Err(ConditionError::new(
+                self.offset(),
+                "condition nested too deeply",
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn or(&mut self) -> Result<Expr, ConditionError> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, ConditionError> {
+        let mut left = self.comparison()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Expr::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ConditionError> {
+        let left = self.unary()?;
+        let Some(Token::Op(op)) = self.peek() else {
+            return Ok(left);
+        };
+        let op = *op;
+        self.pos += 1;
+        let right = self.unary()?;
+        Ok(Expr::Compare(op, Box::new(left), Box::new(right)))
+    }
+
+    fn unary(&mut self) -> Result<Expr, ConditionError> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return self.nested(|p| Ok(Expr::Not(Box::new(p.unary()?))));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ConditionError> {
+        let offset = self.offset();
+        match self.next()? {
+            Token::LParen => {
+                let inner = self.nested(Self::or)?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Token::Str(s) => Ok(Expr::Literal(Value::String(s))),
+            Token::Num(n) => Ok(Expr::Literal(Value::Number(n))),
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                "event" => Ok(Expr::Event),
+                "data" => self.path(),
+                _ => Err(ConditionError::new(
+                    offset,
+                    format!("unknown name '{name}' (expected 'data' or 'event')"),
+                )),
+            },
+            token => Err(ConditionError::new(
+                offset,
+                format!("unexpected {}", token.describe()),
+            )),
+        }
+    }
+
+    /// The `.field` / `[index]` segments following `data`.
+    fn path(&mut self) -> Result<Expr, ConditionError> {
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.pos += 1;
+                    let offset = self.offset();
+                    match self.next()? {
+                        Token::Ident(key) => segments.push(Segment::Key(key)),
+                        token => {
+                            return Err(ConditionError::new(
+                                offset,
+                                format!("expected a field name, found {}", token.describe()),
+                            ))
+                        }
+                    }
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    let offset = self.offset();
+                    match self.next()? {
+                        Token::Str(key) => segments.push(Segment::Key(key)),
+                        Token::Num(n) if n.as_u64().is_some() => {
+                            segments.push(Segment::Index(n.as_u64().unwrap_or_default() as usize))
+                        }
+                        token => {
+                            return Err(ConditionError::new(
+                                offset,
+                                format!(
+                                    "expected an index or quoted key, found {}",
+                                    token.describe()
+                                ),
+                            ))
+                        }
+                    }
+                    self.expect(Token::RBracket)?;
+                }
+                _ => return Ok(Expr::Path(segments)),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn holds(condition: &str, data: Value) -> bool {
+        HookCondition::parse(condition)
+            .unwrap()
+            .matches("tool:pre", &data)
+    }
+
+    #[test]
+    fn evaluates_paths_comparisons_and_logic() {
+        let data = json!({
+            "tool_name": "bash",
+            "tool_input": {"command": "rm -rf build", "timeout": 30},
+            "tags": ["fs", "shell"],
+            "odd key": 1.0
+        });
+        let cases = [
+            (
+                r#"data.tool_name == "bash" && data.tool_input.command =~ "rm ""#,
+                true,
+            ),
+            (
+                r#"data.tool_name == "bash" && data.tool_input.command =~ "ls ""#,
+                false,
+            ),
+            (
+                r#"data.tool_name != "bash" || data.tool_input.timeout >= 30"#,
+                true,
+            ),
+            ("data.tool_input.timeout < 10", false),
+            (r#"data.tags =~ "shell" && data.tags[0] == "fs""#, true),
+            (r#"data["odd key"] == 1"#, true),
+            ("data.missing == null && !data.missing.deeper", true),
+            (r#"event == "tool:pre" && !(data.tags[5] || false)"#, true),
+            (r#"data.tool_input.timeout > "10""#, false),
+            ("data.tool_input", true),
+        ];
+        for (condition, expected) in cases {
+            assert_eq!(holds(condition, data.clone()), expected, "{condition}");
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(holds("true || false && false", json!({})));
+        assert!(!holds("(true || false) && false", json!({})));
+    }
+
+    #[test]
+    fn parse_errors_report_their_offset() {
+        let err = HookCondition::parse(r#"data.tool_name == "bash" &&"#).unwrap_err();
+        assert_eq!(err.offset, 27);
+        assert!(err.message.contains("unexpected end"));
+
+        let err = HookCondition::parse("tool_name == 1").unwrap_err();
+        assert_eq!(err.offset, 0);
+        assert!(HookCondition::parse(r#"data.x == "open"#).is_err());
+        assert!(HookCondition::parse("data.x = 1").is_err());
+        assert!(HookCondition::parse("data.x == 1 1").is_err());
+        assert!(HookCondition::parse(&"!".repeat(100)).is_err());
+        assert!(
+            HookCondition::parse(&format!("{}true{}", "(".repeat(100), ")".repeat(100))).is_err()
+        );
+    }
+
+    #[test]
+    fn serializes_as_its_source() {
+        let condition: HookCondition =
+            serde_json::from_value(json!(r#"data.tool_name == "bash""#)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&condition).unwrap(),
+            json!(r#"data.tool_name == "bash""#)
+        );
+        assert!(serde_json::from_value::<HookCondition>(json!("data ==")).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! Latency budgets for hook handlers.
+//!
+//! Handlers run inline on the emitting task, so one slow observability hook
+//! adds its latency to every tool round trip. A [`LatencyBudget`] installed
+//! with [`HookRegistry::set_latency_budget`](super::HookRegistry::set_latency_budget)
+//! times every handler call and emits `hook:slow_handler` for each call that
+//! takes longer than its budget:
+//!
+//! | Field        | Meaning                                  |
+//! |--------------|------------------------------------------|
+//! | `event`      | The event being dispatched               |
+//! | `handler`    | Handler name                             |
+//! | `phase`      | Handler phase                            |
+//! | `elapsed_ms` | How long the call took                   |
+//! | `budget_ms`  | The handler's budget                     |
+//! | `demoted`    | Whether this call demoted the handler    |
+//!
+//! With `demote_after` set, an observation handler that is over budget that
+//! many calls in a row is demoted: from then on the registry runs it on a
+//! spawned task instead of waiting for it. Observation results are ignored
+//! anyway, so only ordering changes. Handlers in other phases are reported
+//! but never demoted, since their results decide the outcome.
+//!
+//! [`Session::new`](crate::session::Session::new) installs a budget from
+//! `session.hooks.latency`:
+//!
+//! ```json
+//! {"session": {"hooks": {"latency": {
+//!   "budget_ms": 50,
+//!   "handlers": {"audit": 200},
+//!   "demote_after": 3
+//! }}}}
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::HookPhase;
+
+/// The `session.hooks.latency` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LatencyBudgetConfig {
+    /// Budget for each handler call, in milliseconds.
+    pub budget_ms: u64,
+    /// Budgets for individual handlers, by handler name.
+    pub handlers: HashMap<String, u64>,
+    /// Consecutive over-budget calls after which an observation handler is
+    /// demoted. `0` never demotes.
+    pub demote_after: u32,
+}
+
+impl Default for LatencyBudgetConfig {
+    fn default() -> Self {
+        Self {
+            budget_ms: 100,
+            handlers: HashMap::new(),
+            demote_after: 0,
+        }
+    }
+}
+
+impl LatencyBudgetConfig {
+    /// Read `session.hooks.latency` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config
+            .get("session")
+            .and_then(|s| s.get("hooks"))
+            .and_then(|h| h.get("latency"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.hooks.latency config: {e}"))
+            .ok()
+    }
+
+    /// The budget for `handler`.
+    pub fn budget_for(&self, handler: &str) -> Duration {
+        Duration::from_millis(
+            self.handlers
+                .get(handler)
+                .copied()
+                .unwrap_or(self.budget_ms),
+        )
+    }
+}
+
+/// One handler call over its budget; the `hook:slow_handler` payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlowHandlerCall {
+    pub event: String,
+    pub handler: String,
+    pub phase: HookPhase,
+    pub elapsed_ms: u64,
+    pub budget_ms: u64,
+    /// This call demoted the handler to the background lane.
+    pub demoted: bool,
+}
+
+/// Per-handler latency budgets and the handlers demoted for exceeding them.
+pub struct LatencyBudget {
+    config: LatencyBudgetConfig,
+    /// Consecutive over-budget calls, by handler name.
+    streaks: Mutex<HashMap<String, u32>>,
+    demoted: Mutex<BTreeSet<String>>,
+}
+
+impl LatencyBudget {
+    pub fn new(config: LatencyBudgetConfig) -> Self {
+        Self {
+            config,
+            streaks: Mutex::new(HashMap::new()),
+            demoted: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    pub fn config(&self) -> &LatencyBudgetConfig {
+        &self.config
+    }
+
+    /// Whether `handler` runs in the background lane.
+    pub fn is_demoted(&self, handler: &str) -> bool {
+        self.demoted.lock().unwrap().contains(handler)
+    }
+
+    /// Names of the demoted handlers, sorted.
+    pub fn demoted(&self) -> Vec<String> {
+        self.demoted.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Put a demoted handler back on the inline path.
+    pub fn restore(&self, handler: &str) {
+        self.demoted.lock().unwrap().remove(handler);
+        self.streaks.lock().unwrap().remove(handler);
+    }
+
+    /// Record one call, returning the report if it was over budget.
+    pub(super) fn record(
+        &self,
+        event: &str,
+        handler: &str,
+        phase: HookPhase,
+        elapsed: Duration,
+    ) -> Option<SlowHandlerCall> {
+        let budget = self.config.budget_for(handler);
+        let mut streaks = self.streaks.lock().unwrap();
+        if elapsed <= budget {
+            streaks.remove(handler);
+            return None;
+        }
+        let streak = streaks.entry(handler.to_string()).or_default();
+        *streak += 1;
+        let demoted = phase == HookPhase::Observation
+            && self.config.demote_after > 0
+            && *streak >= self.config.demote_after
+            && self.demoted.lock().unwrap().insert(handler.to_string());
+        Some(SlowHandlerCall {
+            event: event.to_string(),
+            handler: handler.to_string(),
+            phase,
+            elapsed_ms: elapsed.as_millis() as u64,
+            budget_ms: budget.as_millis() as u64,
+            demoted,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consecutive_slow_observer_calls_demote_it() {
+        let budget = LatencyBudget::new(LatencyBudgetConfig {
+            budget_ms: 10,
+            handlers: HashMap::from([("audit".into(), 50)]),
+            demote_after: 2,
+        });
+        let slow = Duration::from_millis(20);
+        let fast = Duration::from_millis(1);
+
+        assert!(budget
+            .record("tool:pre", "audit", HookPhase::Observation, slow)
+            .is_none());
+        let call = budget
+            .record("tool:pre", "logger", HookPhase::Observation, slow)
+            .unwrap();
+        assert_eq!(
+            (call.elapsed_ms, call.budget_ms, call.demoted),
+            (20, 10, false)
+        );
+
+        // A fast call resets the streak.
+        budget.record("tool:pre", "logger", HookPhase::Observation, fast);
+        let call = budget.record("tool:pre", "logger", HookPhase::Observation, slow);
+        assert!(!call.unwrap().demoted);
+        let call = budget.record("tool:pre", "logger", HookPhase::Observation, slow);
+        assert!(call.unwrap().demoted);
+        assert_eq!(budget.demoted(), vec!["logger"]);
+
+        // Policy handlers are only reported.
+        for _ in 0..3 {
+            let call = budget.record("tool:pre", "gate", HookPhase::Policy, slow);
+            assert!(!call.unwrap().demoted);
+        }
+        assert!(!budget.is_demoted("gate"));
+
+        budget.restore("logger");
+        assert!(budget.demoted().is_empty());
+    }
+}
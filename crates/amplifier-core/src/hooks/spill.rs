@@ -0,0 +1,194 @@
+//! Size limit for hook result data.
+//!
+//! A handler's `data` map is cloned into every later handler of the chain
+//! and into the caller's result, so one handler returning a file's contents
+//! makes every hop expensive. A [`HookDataLimit`] installed with
+//! [`HookRegistry::set_data_limit`](super::HookRegistry::set_data_limit)
+//! bounds the serialized size of each result's `data`: when a result is
+//! larger, its biggest top-level values are moved to an
+//! [`AttachmentStore`] and replaced by attachment references (see
+//! [`crate::attachments`]) until it fits. Consumers that need the content
+//! back call [`AttachmentStore::rehydrate`] on the data.
+//!
+//! [`Session::new`](crate::session::Session::new) installs a limit from
+//! `session.hooks.max_result_bytes`, spilling to the session's attachment
+//! store. When `session.attachments` is absent, it registers an in-memory
+//! store (one that never offloads tool output) as the session's store, so
+//! [`Coordinator::attachment_store`](crate::coordinator::Coordinator::attachment_store)
+//! resolves the references:
+//!
+//! ```json
+//! {"session": {"hooks": {"max_result_bytes": 65536}}}
+//! ```
+//!
+//! [`HookDataLimit::stats`] counts how often results are spilled.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::attachments::{self, AttachmentRef, AttachmentStore};
+
+/// Counters reported by [`HookDataLimit::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookSpillStats {
+    /// Results with data that were checked against the limit.
+    pub checked: u64,
+    /// Results that exceeded the limit and had values spilled.
+    pub spilled: u64,
+    /// Top-level values moved to the attachment store.
+    pub values_spilled: u64,
+    /// Bytes moved to the attachment store.
+    pub bytes_spilled: u64,
+}
+
+/// Maximum serialized size of a hook result's `data`.
+pub struct HookDataLimit {
+    max_bytes: usize,
+    store: Arc<AttachmentStore>,
+    stats: Mutex<HookSpillStats>,
+}
+
+impl HookDataLimit {
+    /// Spill result data larger than `max_bytes` to `store`.
+    pub fn new(max_bytes: usize, store: Arc<AttachmentStore>) -> Self {
+        Self {
+            max_bytes,
+            store,
+            stats: Mutex::new(HookSpillStats::default()),
+        }
+    }
+
+    /// The configured limit in bytes.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// The store spilled values are written to.
+    pub fn store(&self) -> &Arc<AttachmentStore> {
+        &self.store
+    }
+
+    /// Current counters.
+    pub fn stats(&self) -> HookSpillStats {
+        *self.stats.lock().unwrap()
+    }
+
+    /// Spill the largest values of `data` until it fits the limit. Returns
+    /// the number of values spilled.
+    ///
+    /// Values that are already attachment references are never spilled, so
+    /// data made only of references may stay above the limit. A value whose
+    /// store write fails is left in place (logged).
+    pub async fn apply(&self, data: &mut HashMap<String, Value>) -> usize {
+        let mut sizes: Vec<(String, usize)> = data
+            .iter()
+            .map(|(key, value)| (key.clone(), entry_len(key, value)))
+            .collect();
+        let mut total: usize = sizes.iter().map(|(_, size)| size).sum();
+        self.stats.lock().unwrap().checked += 1;
+        if total <= self.max_bytes {
+            return 0;
+        }
+
+        sizes.sort_by_key(|(_, size)| Reverse(*size));
+        let mut spilled = 0;
+        let mut bytes = 0;
+        for (key, size) in sizes {
+            if total <= self.max_bytes {
+                break;
+            }
+            let Some(value) = data.get_mut(&key) else {
+                continue;
+            };
+            if AttachmentRef::from_value(value).is_some() {
+                continue;
+            }
+            match self.store.put_value(value).await {
+                Ok(reference) => {
+                    bytes += reference.size;
+                    *value = reference.to_value();
+                    total = total - size + entry_len(&key, value);
+                    spilled += 1;
+                }
+                Err(e) => log::warn!("Failed to spill hook result value '{key}': {e}"),
+            }
+        }
+
+        if spilled > 0 {
+            let mut stats = self.stats.lock().unwrap();
+            stats.spilled += 1;
+            stats.values_spilled += spilled as u64;
+            stats.bytes_spilled += bytes as u64;
+        }
+        spilled
+    }
+}
+
+/// Serialized size of one `"key": value` entry, ignoring separators.
+fn entry_len(key: &str, value: &Value) -> usize {
+    key.len() + 2 + attachments::encoded_len(value)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn limited(max_bytes: usize) -> HookDataLimit {
+        HookDataLimit::new(max_bytes, Arc::new(AttachmentStore::in_memory()))
+    }
+
+    #[tokio::test]
+    async fn largest_values_spill_until_data_fits() {
+        let limit = limited(1_000);
+        let mut data = HashMap::from([
+            ("small".to_string(), json!("ok")),
+            ("medium".to_string(), json!("m".repeat(600))),
+            (
+                "large".to_string(),
+                json!({"rows": vec!["x".repeat(100); 10]}),
+            ),
+        ]);
+
+        assert_eq!(limit.apply(&mut data).await, 1);
+        assert_eq!(data["small"], "ok");
+        assert_eq!(data["medium"], "m".repeat(600));
+        let reference = AttachmentRef::from_value(&data["large"]).unwrap();
+        assert!(reference.preview.unwrap().starts_with("{\"rows\""));
+
+        let mut value = serde_json::to_value(&data).unwrap();
+        limit.store().rehydrate(&mut value).await.unwrap();
+        assert_eq!(value["large"]["rows"][9], "x".repeat(100));
+
+        let stats = limit.stats();
+        assert_eq!(
+            (stats.checked, stats.spilled, stats.values_spilled),
+            (1, 1, 1)
+        );
+        assert_eq!(stats.bytes_spilled, reference.size as u64);
+    }
+
+    #[tokio::test]
+    async fn data_within_the_limit_is_untouched() {
+        let limit = limited(64);
+        let mut data = HashMap::from([("note".to_string(), json!("short"))]);
+        assert_eq!(limit.apply(&mut data).await, 0);
+        assert_eq!(data["note"], "short");
+
+        // Already-spilled references are not spilled again.
+        let tiny = limited(1);
+        let mut data = HashMap::from([("blob".to_string(), json!("b".repeat(100)))]);
+        assert_eq!(tiny.apply(&mut data).await, 1);
+        assert_eq!(tiny.apply(&mut data).await, 0);
+        assert_eq!(tiny.stats().spilled, 1);
+        assert_eq!(limit.stats().spilled, 0);
+    }
+}
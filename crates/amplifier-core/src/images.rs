@@ -0,0 +1,1185 @@
+//! Image content: typed sources, provider size limits and re-encoding.
+//!
+//! [`ContentBlock::Image`] keeps its `source` as a JSON map so any provider
+//! shape passes through. [`ImageSource`] is the typed view of the three
+//! shapes the kernel understands:
+//!
+//! | `type`       | Fields                               | Meaning                                   |
+//! |--------------|--------------------------------------|-------------------------------------------|
+//! | `base64`     | `media_type`, `data`                 | Inline bytes                              |
+//! | `url`        | `url`                                | Fetched by the provider (`http`/`https`)  |
+//! | `attachment` | `uri`, `media_type`, `size`          | Stored in an [`AttachmentStore`](crate::attachments::AttachmentStore) |
+//!
+//! A source without `type` is read as `base64` when it has `data` and as
+//! `url` when it has `url`. Sources of any other `type` are left alone.
+//!
+//! # Limits
+//!
+//! Providers (and models, which take precedence) declare [`ImageLimits`] in
+//! their `defaults`:
+//!
+//! ```json
+//! {"max_image_bytes": 5242880, "max_image_dimension": 8000,
+//!  "image_media_types": ["image/png", "image/jpeg", "image/gif", "image/webp"]}
+//! ```
+//!
+//! [`fit_request`] checks every image in a request against them. Sizes of
+//! inline images are computed from the base64 text without decoding it; the
+//! bytes are only decoded to read dimensions or to re-encode. An image that
+//! breaks a limit is handed to the [`ImageTranscoder`], if one is given, and
+//! replaced by its output; otherwise the request is rejected with an
+//! [`ImageError`] before it is sent.
+//!
+//! [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) applies
+//! this to every call. With feature `image-codec` it transcodes with
+//! `PngTranscoder`, which downscales and re-encodes PNG images; hosts that
+//! need other formats supply a transcoder built on a fuller codec.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::attachments;
+use crate::messages::{ChatRequest, ContentBlock, MessageContent};
+use crate::models::{ModelInfo, ProviderInfo};
+use crate::request_conformance::RequestAdjustment;
+
+// ---------------------------------------------------------------------------
+// ImageError
+// ---------------------------------------------------------------------------
+
+/// Why an image cannot be sent as is.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ImageError {
+    /// The source map is not a well-formed source of its type.
+    #[error("invalid image source: {0}")]
+    InvalidSource(String),
+
+    /// The provider does not accept this media type.
+    #[error("image media type {media_type} is not accepted (expected one of: {})", .accepted.join(", "))]
+    UnsupportedMediaType {
+        media_type: String,
+        accepted: Vec<String>,
+    },
+
+    /// The image has more bytes than the provider accepts.
+    #[error("image is {size} bytes, over the {max}-byte limit")]
+    TooLarge { size: usize, max: usize },
+
+    /// A side of the image is longer than the provider accepts.
+    #[error("image is {width}x{height} pixels, over the {max}-pixel side limit")]
+    TooManyPixels { width: u32, height: u32, max: u32 },
+
+    /// An [`ImageTranscoder`] could not re-encode the image.
+    #[error("image transcoding failed: {0}")]
+    Transcode(String),
+}
+
+impl ImageError {
+    /// Whether re-encoding the image could fix this.
+    pub fn transcodable(&self) -> bool {
+        matches!(
+            self,
+            Self::UnsupportedMediaType { .. } | Self::TooLarge { .. } | Self::TooManyPixels { .. }
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ImageSource
+// ---------------------------------------------------------------------------
+
+/// Typed view of an image block's `source` map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageSource {
+    Base64 {
+        media_type: String,
+        data: String,
+    },
+    Url {
+        url: String,
+    },
+    Attachment {
+        uri: String,
+        media_type: String,
+        /// Stored size in bytes, when known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        size: Option<usize>,
+    },
+}
+
+impl ImageSource {
+    /// Inline `data`, base64-encoded.
+    pub fn from_bytes(media_type: impl Into<String>, data: &[u8]) -> Self {
+        Self::Base64 {
+            media_type: media_type.into(),
+            data: base64::engine::general_purpose::STANDARD.encode(data),
+        }
+    }
+
+    /// Read a `source` map.
+    ///
+    /// Returns `Ok(None)` for a `type` this module does not know, so
+    /// provider-specific sources pass through untouched.
+    ///
+    /// # Errors
+    ///
+    /// `ImageError::InvalidSource` for a known `type` with missing or
+    /// malformed fields.
+    pub fn from_map(source: &HashMap<String, Value>) -> Result<Option<Self>, ImageError> {
+        let kind = match source.get("type") {
+            Some(Value::String(kind)) => kind.as_str(),
+            Some(other) => return Err(ImageError::InvalidSource(format!("type {other}"))),
+            None if source.contains_key("data") => "base64",
+            None if source.contains_key("url") => "url",
+            None => return Err(ImageError::InvalidSource("no type, data or url".into())),
+        };
+        if !matches!(kind, "base64" | "url" | "attachment") {
+            return Ok(None);
+        }
+        let mut map: serde_json::Map<String, Value> =
+            source.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        map.insert("type".into(), Value::String(kind.into()));
+        let parsed: Self = serde_json::from_value(Value::Object(map))
+            .map_err(|e| ImageError::InvalidSource(format!("{kind} source: {e}")))?;
+        if let Self::Attachment { uri, .. } = &parsed {
+            if attachments::parse_uri(uri).is_none() {
+                return Err(ImageError::InvalidSource(format!("attachment URI {uri:?}")));
+            }
+        }
+        Ok(Some(parsed))
+    }
+
+    /// The `source` map for a [`ContentBlock::Image`].
+    pub fn to_map(&self) -> HashMap<String, Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(map)) => map.into_iter().collect(),
+            _ => HashMap::new(),
+        }
+    }
+
+    /// The declared media type (`None` for URLs).
+    pub fn media_type(&self) -> Option<&str> {
+        match self {
+            Self::Base64 { media_type, .. } | Self::Attachment { media_type, .. } => {
+                Some(media_type)
+            }
+            Self::Url { .. } => None,
+        }
+    }
+
+    /// Size of the image in bytes, when it can be known without fetching.
+    ///
+    /// # Errors
+    ///
+    /// `ImageError::InvalidSource` if inline data is not valid base64.
+    pub fn size(&self) -> Result<Option<usize>, ImageError> {
+        match self {
+            Self::Base64 { data, .. } => decoded_len(data).map(Some),
+            Self::Attachment { size, .. } => Ok(*size),
+            Self::Url { .. } => Ok(None),
+        }
+    }
+
+    /// Decode inline data; `None` for other sources.
+    ///
+    /// # Errors
+    ///
+    /// `ImageError::InvalidSource` if the data is not valid base64.
+    pub fn decode(&self) -> Result<Option<Vec<u8>>, ImageError> {
+        let Self::Base64 { data, .. } = self else {
+            return Ok(None);
+        };
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map(Some)
+            .map_err(|e| ImageError::InvalidSource(format!("base64 data: {e}")))
+    }
+
+    /// Check this source against `limits`.
+    ///
+    /// # Errors
+    ///
+    /// The first limit broken, or `ImageError::InvalidSource` for malformed
+    /// data or a URL that is not `http(s)`.
+    pub fn validate(&self, limits: &ImageLimits) -> Result<(), ImageError> {
+        if let Self::Url { url } = self {
+            if !(url.starts_with("https://") || url.starts_with("http://")) {
+                return Err(ImageError::InvalidSource(format!("URL {url:?}")));
+            }
+        }
+        let size = self.size()?;
+        if let (Some(media_type), Some(accepted)) = (self.media_type(), &limits.media_types) {
+            if !accepted.iter().any(|a| a.eq_ignore_ascii_case(media_type)) {
+                return Err(ImageError::UnsupportedMediaType {
+                    media_type: media_type.to_string(),
+                    accepted: accepted.clone(),
+                });
+            }
+        }
+        if let (Some(size), Some(max)) = (size, limits.max_bytes) {
+            if size > max {
+                return Err(ImageError::TooLarge { size, max });
+            }
+        }
+        if let Some(max) = limits.max_dimension {
+            let dimensions = self.decode()?.and_then(|bytes| dimensions(&bytes));
+            if let Some((width, height)) = dimensions.filter(|(w, h)| *w > max || *h > max) {
+                return Err(ImageError::TooManyPixels { width, height, max });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ImageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base64 { media_type, data } => {
+                write!(f, "{media_type} ({} base64 chars)", data.len())
+            }
+            Self::Url { url } => f.write_str(url),
+            Self::Attachment { uri, .. } => f.write_str(uri),
+        }
+    }
+}
+
+/// Bytes encoded by standard, padded base64 `data`, checked without
+/// decoding it. Whitespace is not allowed.
+fn decoded_len(data: &str) -> Result<usize, ImageError> {
+    let invalid = |what: &str| ImageError::InvalidSource(format!("base64 data: {what}"));
+    let bytes = data.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(invalid("length is not a multiple of 4"));
+    }
+    let padding = bytes.iter().rev().take_while(|b| **b == b'=').count();
+    if padding > 2 {
+        return Err(invalid("too much padding"));
+    }
+    let body = &bytes[..bytes.len() - padding];
+    if !body
+        .iter()
+        .all(|b| b.is_ascii_alphanumeric() || *b == b'+' || *b == b'/')
+    {
+        return Err(invalid("invalid character"));
+    }
+    Ok(bytes.len() / 4 * 3 - padding)
+}
+
+/// Width and height from a PNG, GIF or JPEG header; `None` for other
+/// formats or truncated data.
+pub fn dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be16 = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+    let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?));
+    let le16 = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?));
+
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some((u32::from(le16(6)?), u32::from(le16(8)?)));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        // Walk the marker segments to the first start-of-frame.
+        let mut at = 2;
+        loop {
+            if *bytes.get(at)? != 0xFF {
+                return None;
+            }
+            let marker = *bytes.get(at + 1)?;
+            let is_frame = matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame {
+                return Some((u32::from(be16(at + 7)?), u32::from(be16(at + 5)?)));
+            }
+            at += 2 + usize::from(be16(at + 2)?);
+        }
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// ImageLimits
+// ---------------------------------------------------------------------------
+
+/// What a provider accepts in an image.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageLimits {
+    /// Largest accepted image, in bytes.
+    pub max_bytes: Option<usize>,
+    /// Longest accepted side, in pixels.
+    pub max_dimension: Option<u32>,
+    /// Accepted media types; `None` accepts any.
+    pub media_types: Option<Vec<String>>,
+}
+
+impl ImageLimits {
+    /// Limits for `model` served by `provider`.
+    ///
+    /// Model values take precedence over provider values, field by field.
+    pub fn resolve(provider: &ProviderInfo, model: Option<&ModelInfo>) -> Self {
+        let limits = Self::from_defaults(&provider.defaults);
+        let Some(model) = model else {
+            return limits;
+        };
+        let overlay = Self::from_defaults(&model.defaults);
+        Self {
+            max_bytes: overlay.max_bytes.or(limits.max_bytes),
+            max_dimension: overlay.max_dimension.or(limits.max_dimension),
+            media_types: overlay.media_types.or(limits.media_types),
+        }
+    }
+
+    /// Read `max_image_bytes`, `max_image_dimension` and
+    /// `image_media_types` from a `defaults` map. Malformed entries are
+    /// ignored.
+    pub fn from_defaults(defaults: &HashMap<String, Value>) -> Self {
+        Self {
+            max_bytes: defaults
+                .get("max_image_bytes")
+                .and_then(Value::as_u64)
+                .map(|max| max as usize),
+            max_dimension: defaults
+                .get("max_image_dimension")
+                .and_then(Value::as_u64)
+                .and_then(|max| u32::try_from(max).ok()),
+            media_types: defaults
+                .get("image_media_types")
+                .and_then(Value::as_array)
+                .map(|types| {
+                    types
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                }),
+        }
+    }
+
+    /// Whether no limit is declared.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ImageTranscoder
+// ---------------------------------------------------------------------------
+
+/// Re-encodes images that break a provider's limits.
+///
+/// Implementations wrap an image codec: they scale the image down so
+/// neither side exceeds `limits.max_dimension`, encode it in one of
+/// `limits.media_types`, and compress until it fits `limits.max_bytes`.
+/// The result is validated again before it is sent.
+pub trait ImageTranscoder: Send + Sync {
+    /// Re-encode `data` (of `media_type`) to satisfy `limits`, returning the
+    /// new media type and bytes.
+    fn transcode(
+        &self,
+        media_type: &str,
+        data: &[u8],
+        limits: &ImageLimits,
+    ) -> Result<(String, Vec<u8>), ImageError>;
+}
+
+// ---------------------------------------------------------------------------
+// PngTranscoder
+// ---------------------------------------------------------------------------
+
+/// The transcoder [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker)
+/// uses unless given another: [`PngTranscoder`] when feature `image-codec`
+/// is enabled, otherwise none.
+pub fn default_transcoder() -> Option<Arc<dyn ImageTranscoder>> {
+    #[cfg(feature = "image-codec")]
+    return Some(Arc::new(PngTranscoder));
+    #[cfg(not(feature = "image-codec"))]
+    None
+}
+
+#[cfg(feature = "image-codec")]
+const PNG_MEDIA_TYPE: &str = "image/png";
+
+#[cfg(feature = "image-codec")]
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Largest image [`PngTranscoder`] decodes, in pixels (128 MiB as RGBA).
+#[cfg(feature = "image-codec")]
+const MAX_DECODED_PIXELS: u64 = 1 << 25;
+
+/// Downscales and re-encodes PNG images (feature `image-codec`).
+///
+/// Decodes any non-interlaced PNG, scales it with a box filter so neither
+/// side exceeds `max_dimension`, and re-encodes it as an RGB or RGBA PNG,
+/// shrinking it by a quarter at a time until it fits `max_bytes`. Other
+/// input formats, and providers that accept no PNG, fail with
+/// [`ImageError::Transcode`].
+#[cfg(feature = "image-codec")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PngTranscoder;
+
+#[cfg(feature = "image-codec")]
+impl ImageTranscoder for PngTranscoder {
+    fn transcode(
+        &self,
+        media_type: &str,
+        data: &[u8],
+        limits: &ImageLimits,
+    ) -> Result<(String, Vec<u8>), ImageError> {
+        if let Some(accepted) = &limits.media_types {
+            if !accepted.iter().any(|t| t == PNG_MEDIA_TYPE) {
+                return Err(ImageError::Transcode(format!(
+                    "the provider accepts no {PNG_MEDIA_TYPE} images"
+                )));
+            }
+        }
+        if !data.starts_with(PNG_SIGNATURE) {
+            return Err(ImageError::Transcode(format!(
+                "cannot decode {media_type}: only PNG input is supported"
+            )));
+        }
+        let image = png::decode(data)?;
+        let (mut width, mut height) =
+            png::fit_within(image.width, image.height, limits.max_dimension);
+        loop {
+            let encoded = if (width, height) == (image.width, image.height) {
+                png::encode(&image)?
+            } else {
+                png::encode(&image.resize(width, height))?
+            };
+            let Some(max) = limits.max_bytes.filter(|max| encoded.len() > *max) else {
+                return Ok((PNG_MEDIA_TYPE.to_string(), encoded));
+            };
+            if (width, height) == (1, 1) {
+                return Err(ImageError::Transcode(format!(
+                    "cannot fit the image in {max} bytes"
+                )));
+            }
+            width = (width * 3 / 4).max(1);
+            height = (height * 3 / 4).max(1);
+        }
+    }
+}
+
+/// PNG decoding, box-filter scaling and encoding for [`PngTranscoder`].
+#[cfg(feature = "image-codec")]
+mod png {
+    use std::io::{Read, Write};
+
+    use flate2::read::ZlibDecoder;
+    use flate2::write::ZlibEncoder;
+    use flate2::{Compression, Crc};
+
+    use super::{ImageError, MAX_DECODED_PIXELS, PNG_SIGNATURE};
+
+    /// 8-bit RGBA pixels, row-major.
+    pub(super) struct Raster {
+        pub(super) width: u32,
+        pub(super) height: u32,
+        pub(super) rgba: Vec<u8>,
+    }
+
+    fn error(message: &str) -> ImageError {
+        ImageError::Transcode(format!("PNG: {message}"))
+    }
+
+    /// `(width, height)` scaled down, keeping the aspect ratio, so neither
+    /// side exceeds `max`.
+    pub(super) fn fit_within(width: u32, height: u32, max: Option<u32>) -> (u32, u32) {
+        let longest = width.max(height);
+        match max {
+            Some(max) if longest > max => {
+                let scale =
+                    |side: u32| (u64::from(side) * u64::from(max) / u64::from(longest)) as u32;
+                (scale(width).max(1), scale(height).max(1))
+            }
+            _ => (width, height),
+        }
+    }
+
+    fn paeth(a: u8, b: u8, c: u8) -> u8 {
+        let p = i16::from(a) + i16::from(b) - i16::from(c);
+        let (pa, pb, pc) = (
+            (p - i16::from(a)).abs(),
+            (p - i16::from(b)).abs(),
+            (p - i16::from(c)).abs(),
+        );
+        if pa <= pb && pa <= pc {
+            a
+        } else if pb <= pc {
+            b
+        } else {
+            c
+        }
+    }
+
+    /// The byte `filter` predicts at `i` from the bytes before it (`line`,
+    /// already reconstructed) and above it (`prev`).
+    fn predict(filter: u8, line: &[u8], prev: &[u8], i: usize, step: usize) -> u8 {
+        let a = if i >= step { line[i - step] } else { 0 };
+        let b = prev[i];
+        let c = if i >= step { prev[i - step] } else { 0 };
+        match filter {
+            1 => a,
+            2 => b,
+            3 => ((u16::from(a) + u16::from(b)) / 2) as u8,
+            4 => paeth(a, b, c),
+            _ => 0,
+        }
+    }
+
+    pub(super) fn decode(data: &[u8]) -> Result<Raster, ImageError> {
+        if !data.starts_with(PNG_SIGNATURE) {
+            return Err(error("missing signature"));
+        }
+        let mut header = None;
+        let (mut palette, mut transparency) = (&[][..], &[][..]);
+        let mut compressed = Vec::new();
+        let mut at = PNG_SIGNATURE.len();
+        while at + 8 <= data.len() {
+            let len = u32::from_be_bytes(data[at..at + 4].try_into().unwrap()) as usize;
+            let kind = &data[at + 4..at + 8];
+            let body = at
+                .checked_add(8 + len)
+                .and_then(|end| data.get(at + 8..end))
+                .ok_or_else(|| error("truncated chunk"))?;
+            match kind {
+                b"IHDR" if body.len() == 13 => header = Some(body),
+                b"PLTE" => palette = body,
+                b"tRNS" => transparency = body,
+                b"IDAT" => compressed.extend_from_slice(body),
+                b"IEND" => break,
+                _ => {}
+            }
+            at += 12 + len;
+        }
+        let header = header.ok_or_else(|| error("missing IHDR"))?;
+        let width = u32::from_be_bytes(header[0..4].try_into().unwrap());
+        let height = u32::from_be_bytes(header[4..8].try_into().unwrap());
+        let (depth, color, interlace) = (header[8], header[9], header[12]);
+        let channels = match (color, depth) {
+            (0, 1 | 2 | 4 | 8 | 16) | (3, 1 | 2 | 4 | 8) => 1,
+            (4, 8 | 16) => 2,
+            (2, 8 | 16) => 3,
+            (6, 8 | 16) => 4,
+            _ => return Err(error("unsupported color type or bit depth")),
+        };
+        if interlace != 0 {
+            return Err(error("interlaced images are not supported"));
+        }
+        if color == 3 && palette.is_empty() {
+            return Err(error("missing PLTE"));
+        }
+        let pixels = u64::from(width) * u64::from(height);
+        if pixels == 0 || pixels > MAX_DECODED_PIXELS {
+            return Err(error("image is empty or too large to decode"));
+        }
+
+        let (w, h, depth) = (width as usize, height as usize, usize::from(depth));
+        let bits_per_pixel = channels * depth;
+        let stride = (w * bits_per_pixel).div_ceil(8);
+        let step = (bits_per_pixel / 8).max(1);
+        let mut raw = Vec::with_capacity((stride + 1) * h);
+        ZlibDecoder::new(&compressed[..])
+            .take(((stride + 1) * h) as u64)
+            .read_to_end(&mut raw)
+            .map_err(|e| error(&format!("image data: {e}")))?;
+        if raw.len() < (stride + 1) * h {
+            return Err(error("truncated image data"));
+        }
+
+        // Undo the per-row filters.
+        let mut samples = vec![0u8; stride * h];
+        let zero = vec![0u8; stride];
+        for y in 0..h {
+            let filtered = &raw[y * (stride + 1)..(y + 1) * (stride + 1)];
+            if filtered[0] > 4 {
+                return Err(error("invalid filter type"));
+            }
+            let (done, rest) = samples.split_at_mut(y * stride);
+            let prev = if y == 0 {
+                &zero[..]
+            } else {
+                &done[(y - 1) * stride..]
+            };
+            let line = &mut rest[..stride];
+            for i in 0..stride {
+                line[i] = filtered[i + 1].wrapping_add(predict(filtered[0], line, prev, i, step));
+            }
+        }
+
+        let sample = |row: &[u8], n: usize| -> u16 {
+            match depth {
+                16 => u16::from_be_bytes([row[2 * n], row[2 * n + 1]]),
+                8 => u16::from(row[n]),
+                _ => {
+                    let bit = n * depth;
+                    let shift = 8 - depth - bit % 8;
+                    u16::from((row[bit / 8] >> shift) & ((1 << depth) - 1) as u8)
+                }
+            }
+        };
+        let scale = |value: u16| -> u8 {
+            match depth {
+                16 => (value >> 8) as u8,
+                8 => value as u8,
+                _ => (u32::from(value) * 255 / ((1 << depth) - 1)) as u8,
+            }
+        };
+        let key = |n: usize| -> Option<u16> {
+            Some(u16::from_be_bytes(
+                transparency.get(2 * n..2 * n + 2)?.try_into().ok()?,
+            ))
+        };
+
+        let mut rgba = Vec::with_capacity(w * h * 4);
+        for row in samples.chunks_exact(stride) {
+            for x in 0..w {
+                let s = |k: usize| sample(row, x * channels + k);
+                let pixel = match color {
+                    0 => {
+                        let g = scale(s(0));
+                        let alpha = if key(0) == Some(s(0)) { 0 } else { 255 };
+                        [g, g, g, alpha]
+                    }
+                    2 => {
+                        let keyed = (0..3).all(|k| key(k) == Some(s(k)));
+                        [
+                            scale(s(0)),
+                            scale(s(1)),
+                            scale(s(2)),
+                            if keyed { 0 } else { 255 },
+                        ]
+                    }
+                    3 => {
+                        let index = usize::from(s(0));
+                        let rgb = palette
+                            .get(index * 3..index * 3 + 3)
+                            .ok_or_else(|| error("palette index out of range"))?;
+                        let alpha = transparency.get(index).copied().unwrap_or(255);
+                        [rgb[0], rgb[1], rgb[2], alpha]
+                    }
+                    4 => {
+                        let g = scale(s(0));
+                        [g, g, g, scale(s(1))]
+                    }
+                    _ => [scale(s(0)), scale(s(1)), scale(s(2)), scale(s(3))],
+                };
+                rgba.extend_from_slice(&pixel);
+            }
+        }
+        Ok(Raster {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    impl Raster {
+        /// Scale down to `width` x `height`, averaging each destination
+        /// pixel's source box with colors weighted by alpha.
+        pub(super) fn resize(&self, width: u32, height: u32) -> Raster {
+            let (sw, sh) = (self.width as usize, self.height as usize);
+            let (w, h) = (width as usize, height as usize);
+            let span = |i: usize, out: usize, src: usize| {
+                let start = i * src / out;
+                (start, ((i + 1) * src / out).max(start + 1))
+            };
+            let mut rgba = Vec::with_capacity(w * h * 4);
+            for y in 0..h {
+                let (y0, y1) = span(y, h, sh);
+                for x in 0..w {
+                    let (x0, x1) = span(x, w, sw);
+                    let mut sum = [0u64; 4];
+                    for sy in y0..y1 {
+                        for pixel in
+                            self.rgba[(sy * sw + x0) * 4..(sy * sw + x1) * 4].chunks_exact(4)
+                        {
+                            let alpha = u64::from(pixel[3]);
+                            for c in 0..3 {
+                                sum[c] += u64::from(pixel[c]) * alpha;
+                            }
+                            sum[3] += alpha;
+                        }
+                    }
+                    let count = ((y1 - y0) * (x1 - x0)) as u64;
+                    let color =
+                        |c: usize| (sum[c] + sum[3] / 2).checked_div(sum[3]).unwrap_or(0) as u8;
+                    rgba.extend_from_slice(&[
+                        color(0),
+                        color(1),
+                        color(2),
+                        ((sum[3] + count / 2) / count) as u8,
+                    ]);
+                }
+            }
+            Raster {
+                width,
+                height,
+                rgba,
+            }
+        }
+    }
+
+    fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+        out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        out.extend_from_slice(kind);
+        out.extend_from_slice(body);
+        let mut crc = Crc::new();
+        crc.update(kind);
+        crc.update(body);
+        out.extend_from_slice(&crc.sum().to_be_bytes());
+    }
+
+    /// Encode as 8-bit RGB, or RGBA when any pixel is translucent, choosing
+    /// each row's filter by the smallest sum of absolute residuals.
+    pub(super) fn encode(image: &Raster) -> Result<Vec<u8>, ImageError> {
+        let opaque = image.rgba.chunks_exact(4).all(|pixel| pixel[3] == 255);
+        let (color, channels) = if opaque { (2u8, 3) } else { (6, 4) };
+        let stride = image.width as usize * channels;
+        let mut filtered = Vec::with_capacity((stride + 1) * image.height as usize);
+        let mut prev = vec![0u8; stride];
+        let mut line = Vec::with_capacity(stride);
+        for row in image.rgba.chunks_exact(image.width as usize * 4) {
+            line.clear();
+            for pixel in row.chunks_exact(4) {
+                line.extend_from_slice(&pixel[..channels]);
+            }
+            let residuals = |filter: u8| -> Vec<u8> {
+                (0..stride)
+                    .map(|i| line[i].wrapping_sub(predict(filter, &line, &prev, i, channels)))
+                    .collect()
+            };
+            let cost = |bytes: &[u8]| -> u64 {
+                bytes
+                    .iter()
+                    .map(|b| u64::from((*b as i8).unsigned_abs()))
+                    .sum()
+            };
+            let (filter, bytes) = (0..5u8)
+                .map(|filter| (filter, residuals(filter)))
+                .min_by_key(|(_, bytes)| cost(bytes))
+                .expect("five filters");
+            filtered.push(filter);
+            filtered.extend_from_slice(&bytes);
+            std::mem::swap(&mut prev, &mut line);
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+        let compressed = encoder
+            .write_all(&filtered)
+            .and_then(|()| encoder.finish())
+            .map_err(|e| error(&format!("compress: {e}")))?;
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&image.width.to_be_bytes());
+        header.extend_from_slice(&image.height.to_be_bytes());
+        header.extend_from_slice(&[8, color, 0, 0, 0]);
+        let mut out = PNG_SIGNATURE.to_vec();
+        write_chunk(&mut out, b"IHDR", &header);
+        write_chunk(&mut out, b"IDAT", &compressed);
+        write_chunk(&mut out, b"IEND", &[]);
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// fit_request
+// ---------------------------------------------------------------------------
+
+/// Check every image in `request` against `limits`, re-encoding those that
+/// break one with `transcoder` when given.
+///
+/// Returns one [`RequestAdjustment`] per re-encoded image, with field
+/// `messages[i].content[j].source`.
+///
+/// # Errors
+///
+/// The first image that is malformed, or breaks a limit and cannot be
+/// re-encoded to fit.
+pub fn fit_request(
+    request: &mut ChatRequest,
+    limits: &ImageLimits,
+    transcoder: Option<&dyn ImageTranscoder>,
+) -> Result<Vec<RequestAdjustment>, ImageError> {
+    let mut adjustments = Vec::new();
+    for (i, message) in request.messages.iter_mut().enumerate() {
+        let MessageContent::Blocks(blocks) = &mut message.content else {
+            continue;
+        };
+        for (j, block) in blocks.iter_mut().enumerate() {
+            let ContentBlock::Image { source, .. } = block else {
+                continue;
+            };
+            let Some(image) = ImageSource::from_map(source)? else {
+                continue;
+            };
+            let error = match image.validate(limits) {
+                Ok(()) => continue,
+                Err(e) => e,
+            };
+            let (Some(transcoder), Some(data)) = (transcoder, image.decode()?) else {
+                return Err(error);
+            };
+            if !error.transcodable() {
+                return Err(error);
+            }
+            let from = image.media_type().unwrap_or_default();
+            let (media_type, encoded) =
+                transcoder
+                    .transcode(from, &data, limits)
+                    .map_err(|e| match e {
+                        ImageError::Transcode(reason) => {
+                            ImageError::Transcode(format!("{error}; {reason}"))
+                        }
+                        other => other,
+                    })?;
+            let fitted = ImageSource::from_bytes(&media_type, &encoded);
+            fitted.validate(limits)?;
+            adjustments.push(RequestAdjustment {
+                field: format!("messages[{i}].content[{j}].source"),
+                requested: serde_json::json!({"media_type": from, "size": data.len()}),
+                applied: serde_json::json!({"media_type": media_type, "size": encoded.len()}),
+                reason: error.to_string(),
+            });
+            *source = fitted.to_map();
+        }
+    }
+    Ok(adjustments)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A 1x1 PNG header claiming `width` x `height`.
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        bytes.extend_from_slice(&width.to_be_bytes());
+        bytes.extend_from_slice(&height.to_be_bytes());
+        bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    fn request_with(source: Value) -> ChatRequest {
+        serde_json::from_value(json!({
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "look"},
+                {"type": "image", "source": source}
+            ]}]
+        }))
+        .unwrap()
+    }
+
+    fn image_source(request: &ChatRequest) -> ImageSource {
+        let MessageContent::Blocks(blocks) = &request.messages[0].content else {
+            panic!("expected blocks");
+        };
+        let ContentBlock::Image { source, .. } = &blocks[1] else {
+            panic!("expected an image");
+        };
+        ImageSource::from_map(source).unwrap().unwrap()
+    }
+
+    /// Halves each side and re-encodes as JPEG-labelled PNG bytes.
+    struct Halve;
+
+    impl ImageTranscoder for Halve {
+        fn transcode(
+            &self,
+            _media_type: &str,
+            data: &[u8],
+            _limits: &ImageLimits,
+        ) -> Result<(String, Vec<u8>), ImageError> {
+            let (w, h) = dimensions(data).ok_or(ImageError::Transcode("not a PNG".into()))?;
+            Ok(("image/png".into(), png(w / 2, h / 2)))
+        }
+    }
+
+    #[test]
+    fn parses_typed_and_untyped_sources() {
+        let map = |v: Value| -> HashMap<String, Value> { serde_json::from_value(v).unwrap() };
+        assert_eq!(
+            ImageSource::from_map(&map(json!({"media_type": "image/png", "data": "AAAA"})))
+                .unwrap(),
+            Some(ImageSource::Base64 {
+                media_type: "image/png".into(),
+                data: "AAAA".into()
+            })
+        );
+        assert!(matches!(
+            ImageSource::from_map(&map(json!({"url": "https://x/y.png"}))).unwrap(),
+            Some(ImageSource::Url { .. })
+        ));
+        assert_eq!(
+            ImageSource::from_map(&map(json!({"type": "file", "file_id": "f1"}))).unwrap(),
+            None
+        );
+        assert!(ImageSource::from_map(&map(json!({"type": "base64", "data": "AAAA"}))).is_err());
+        assert!(ImageSource::from_map(&map(
+            json!({"type": "attachment", "uri": "attachment://nope", "media_type": "image/png"})
+        ))
+        .is_err());
+
+        let source = ImageSource::from_bytes("image/png", &png(4, 4));
+        assert_eq!(
+            ImageSource::from_map(&source.to_map()).unwrap(),
+            Some(source)
+        );
+    }
+
+    #[test]
+    fn base64_size_is_computed_without_decoding() {
+        assert_eq!(decoded_len("").unwrap(), 0);
+        assert_eq!(decoded_len("QQ==").unwrap(), 1);
+        assert_eq!(decoded_len("QUI=").unwrap(), 2);
+        assert_eq!(decoded_len("QUJD").unwrap(), 3);
+        assert!(decoded_len("QUJ").is_err());
+        assert!(decoded_len("QU J").is_err());
+        assert!(decoded_len("Q===").is_err());
+    }
+
+    #[test]
+    fn reads_png_gif_and_jpeg_dimensions() {
+        assert_eq!(dimensions(&png(640, 480)), Some((640, 480)));
+        assert_eq!(dimensions(b"GIF89a\x40\x01\xf0\x00"), Some((320, 240)));
+        let jpeg = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // APP0, 2 bytes of payload
+            0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80, // SOF0 480x640
+        ];
+        assert_eq!(dimensions(&jpeg), Some((640, 480)));
+        assert_eq!(dimensions(b"RIFF....WEBP"), None);
+    }
+
+    #[test]
+    fn validates_against_limits() {
+        let limits = ImageLimits {
+            max_bytes: Some(100),
+            max_dimension: Some(1000),
+            media_types: Some(vec!["image/png".into()]),
+        };
+        let ok = ImageSource::from_bytes("image/png", &png(800, 600));
+        assert!(ok.validate(&limits).is_ok());
+        assert!(matches!(
+            ImageSource::from_bytes("image/tiff", &png(8, 8)).validate(&limits),
+            Err(ImageError::UnsupportedMediaType { .. })
+        ));
+        assert_eq!(
+            ImageSource::from_bytes("image/png", &[0; 101]).validate(&limits),
+            Err(ImageError::TooLarge {
+                size: 101,
+                max: 100
+            })
+        );
+        assert_eq!(
+            ImageSource::from_bytes("image/png", &png(2000, 10)).validate(&limits),
+            Err(ImageError::TooManyPixels {
+                width: 2000,
+                height: 10,
+                max: 1000
+            })
+        );
+        let ftp = ImageSource::Url {
+            url: "ftp://x/y.png".into(),
+        };
+        assert!(ftp.validate(&ImageLimits::default()).is_err());
+    }
+
+    #[test]
+    fn limits_resolve_model_over_provider() {
+        let provider: ProviderInfo = serde_json::from_value(json!({
+            "id": "p", "display_name": "P", "credential_env_vars": [], "capabilities": [],
+            "defaults": {"max_image_bytes": 100, "image_media_types": ["image/png"]},
+            "config_fields": []
+        }))
+        .unwrap();
+        let model: ModelInfo = serde_json::from_value(json!({
+            "id": "m", "display_name": "M", "context_window": 1, "max_output_tokens": 1,
+            "defaults": {"max_image_bytes": 50, "max_image_dimension": 512}
+        }))
+        .unwrap();
+        assert_eq!(
+            ImageLimits::resolve(&provider, Some(&model)),
+            ImageLimits {
+                max_bytes: Some(50),
+                max_dimension: Some(512),
+                media_types: Some(vec!["image/png".into()]),
+            }
+        );
+        assert!(ImageLimits::resolve(&provider, None)
+            .max_dimension
+            .is_none());
+    }
+
+    #[test]
+    fn oversized_images_are_transcoded_or_rejected() {
+        let limits = ImageLimits {
+            max_dimension: Some(1000),
+            ..ImageLimits::default()
+        };
+        let big = ImageSource::from_bytes("image/jpeg", &png(1600, 1200));
+
+        let mut request = request_with(json!(big.to_map()));
+        let err = fit_request(&mut request, &limits, None).unwrap_err();
+        assert!(matches!(err, ImageError::TooManyPixels { .. }));
+
+        let adjustments = fit_request(&mut request, &limits, Some(&Halve)).unwrap();
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(adjustments[0].field, "messages[0].content[1].source");
+        assert_eq!(adjustments[0].applied["media_type"], "image/png");
+        let fitted = image_source(&request);
+        assert_eq!(
+            dimensions(&fitted.decode().unwrap().unwrap()),
+            Some((800, 600))
+        );
+
+        // Text-only and unknown-source requests are untouched.
+        let mut plain: ChatRequest =
+            serde_json::from_value(json!({"messages": [{"role": "user", "content": "hi"}]}))
+                .unwrap();
+        assert!(fit_request(&mut plain, &limits, None).unwrap().is_empty());
+        let mut other = request_with(json!({"type": "file", "file_id": "f1"}));
+        assert!(fit_request(&mut other, &limits, None).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "image-codec")]
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// A noisy, partly translucent `width` x `height` image encoded as PNG.
+    #[cfg(feature = "image-codec")]
+    fn noisy_png(width: u32, height: u32) -> Vec<u8> {
+        let mut state = 0x2545_f491_u32;
+        let rgba = (0..width * height * 4)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        png::encode(&png::Raster {
+            width,
+            height,
+            rgba,
+        })
+        .unwrap()
+    }
+
+    #[cfg(feature = "image-codec")]
+    #[test]
+    fn png_codec_decodes_filtered_and_palette_images() {
+        // 3x5 RGB, one row per filter type (None, Sub, Up, Average, Paeth).
+        let rgb = unhex(
+            "89504e470d0a1a0a0000000d49484452000000030000000508020000000f13c1f5000000354944\
+             4154789c636060e07213e1ead1e062948b02b2348088492e8a412e4a432e2a80d9660babd1f600a3\
+             ed292c2031110d39910000d13e096c3092ac330000000049454e44ae426082",
+        );
+        let image = png::decode(&rgb).unwrap();
+        assert_eq!((image.width, image.height), (3, 5));
+        for (n, pixel) in image.rgba.chunks_exact(4).enumerate() {
+            let (x, y) = (n % 3, n / 3);
+            let expected = [
+                ((x * 70 + y * 30) % 256) as u8,
+                ((x * 20 + y * 90) % 256) as u8,
+                ((x * y * 40 + 10) % 256) as u8,
+                255,
+            ];
+            assert_eq!(pixel, expected, "pixel ({x}, {y})");
+        }
+
+        // 5x2, 2-bit palette with a tRNS chunk.
+        let palette = unhex(
+            "89504e470d0a1a0a0000000d4948445200000005000000020203000000ed04fece0000000c504c54\
+             45ff000000ff000000ff0909095c717e860000000274524e53ff80080fb36a0000000e4944415478\
+             9c6390766078d2000003d501c0a1479cde0000000049454e44ae426082",
+        );
+        let image = png::decode(&palette).unwrap();
+        let first_row: Vec<_> = image.rgba.chunks_exact(4).take(5).collect();
+        assert_eq!(
+            first_row,
+            [
+                [255, 0, 0, 255],
+                [0, 255, 0, 128],
+                [0, 0, 255, 255],
+                [9, 9, 9, 255],
+                [0, 255, 0, 128]
+            ]
+        );
+
+        // Encoding round-trips the pixels.
+        let original = png::decode(&noisy_png(7, 3)).unwrap();
+        let again = png::decode(&png::encode(&original).unwrap()).unwrap();
+        assert_eq!(again.rgba, original.rgba);
+    }
+
+    #[cfg(feature = "image-codec")]
+    #[test]
+    fn png_transcoder_downscales_to_fit_limits() {
+        let big = noisy_png(64, 48);
+        let limits = ImageLimits {
+            max_dimension: Some(16),
+            ..ImageLimits::default()
+        };
+        let (media_type, fitted) = PngTranscoder.transcode("image/png", &big, &limits).unwrap();
+        assert_eq!(media_type, "image/png");
+        assert_eq!(dimensions(&fitted), Some((16, 12)));
+
+        // Noise barely compresses, so fitting 1 KB takes several shrinks.
+        let limits = ImageLimits {
+            max_bytes: Some(1024),
+            ..ImageLimits::default()
+        };
+        let (_, fitted) = PngTranscoder.transcode("image/png", &big, &limits).unwrap();
+        assert!(fitted.len() <= 1024);
+        let (w, h) = dimensions(&fitted).unwrap();
+        assert!(w < 64 && h < 48 && w * 3 / 4 <= h, "{w}x{h}");
+
+        let mut request = request_with(json!(ImageSource::from_bytes("image/png", &big).to_map()));
+        let adjustments = fit_request(&mut request, &limits, Some(&PngTranscoder)).unwrap();
+        assert_eq!(adjustments.len(), 1);
+        assert!(image_source(&request).size().unwrap().unwrap() <= 1024);
+    }
+
+    #[cfg(feature = "image-codec")]
+    #[test]
+    fn png_transcoder_rejects_what_it_cannot_produce() {
+        let jpeg_only = ImageLimits {
+            media_types: Some(vec!["image/jpeg".into()]),
+            ..ImageLimits::default()
+        };
+        assert!(matches!(
+            PngTranscoder.transcode("image/png", &noisy_png(4, 4), &jpeg_only),
+            Err(ImageError::Transcode(_))
+        ));
+        assert!(matches!(
+            PngTranscoder.transcode(
+                "image/gif",
+                b"GIF89a\x40\x01\xf0\x00",
+                &ImageLimits::default()
+            ),
+            Err(ImageError::Transcode(_))
+        ));
+        let tiny = ImageLimits {
+            max_bytes: Some(8),
+            ..ImageLimits::default()
+        };
+        assert!(matches!(
+            PngTranscoder.transcode("image/png", &noisy_png(4, 4), &tiny),
+            Err(ImageError::Transcode(_))
+        ));
+    }
+}
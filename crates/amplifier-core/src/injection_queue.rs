@@ -0,0 +1,380 @@
+//! Queueing of `inject_context` results against a per-turn budget.
+//!
+//! When several hooks inject context for one event, their results are
+//! merged into a single injection. With an [`InjectionQueue`] installed on
+//! the [`HookRegistry`](crate::hooks::HookRegistry) (the coordinator
+//! installs its own), the merge is admitted rather than taken whole:
+//!
+//! 1. Each source (the injecting handler) may add at most its quota of
+//!    injections per turn. Injections over quota are dropped.
+//! 2. The rest are ordered by handler priority (lower first), then by the
+//!    order the handlers ran. This is also the order they are merged in.
+//! 3. In that order, each injection is charged against what is left of
+//!    `session.injection_budget_per_turn` (estimated tokens). One that does
+//!    not fit is dropped; smaller ones after it may still fit.
+//!
+//! Every dropped injection is reported as a
+//! [`CONTEXT_INJECTION_DROPPED`](crate::events::CONTEXT_INJECTION_DROPPED)
+//! event. Usage is reset by
+//! [`Coordinator::reset_turn`](crate::coordinator::Coordinator::reset_turn).
+//!
+//! # Configuration
+//!
+//! The budget is the existing `session.injection_budget_per_turn`; quotas
+//! are set in `session.injection_queue`:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "injection_budget_per_turn": 2000,
+//!     "injection_queue": {"default_quota": 3, "quotas": {"lint-reminders": 1}}
+//!   }
+//! }
+//! ```
+//!
+//! Without either, every injection is admitted, in priority order.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::HookResult;
+
+/// Characters per estimated token, as in the Python coordinator's budget
+/// check.
+const CHARS_PER_TOKEN_ESTIMATE: usize = 4;
+
+/// Estimated tokens in injected `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count() / CHARS_PER_TOKEN_ESTIMATE
+}
+
+// ---------------------------------------------------------------------------
+// InjectionQueueConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.injection_queue` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InjectionQueueConfig {
+    /// Injections per turn for sources without an entry in `quotas`.
+    /// Unlimited when unset.
+    pub default_quota: Option<usize>,
+    /// Injections per turn, by handler name.
+    pub quotas: BTreeMap<String, usize>,
+}
+
+impl InjectionQueueConfig {
+    /// Read `session.injection_queue` from a mount plan.
+    ///
+    /// Returns the default (no quotas) when the section is absent or
+    /// malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("injection_queue")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.injection_queue config: {e}"))
+            .unwrap_or_default()
+    }
+
+    /// The quota for `source`, if it has one.
+    pub fn quota(&self, source: &str) -> Option<usize> {
+        self.quotas.get(source).copied().or(self.default_quota)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queue
+// ---------------------------------------------------------------------------
+
+/// An `inject_context` result waiting to be admitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedInjection {
+    /// Name of the handler that returned it.
+    pub source: String,
+    /// The handler's priority (lower = earlier).
+    pub priority: i32,
+    pub result: HookResult,
+}
+
+impl QueuedInjection {
+    fn tokens(&self) -> usize {
+        self.result
+            .context_injection
+            .as_deref()
+            .map_or(0, estimate_tokens)
+    }
+}
+
+/// Why an injection was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropReason {
+    /// Its source had used up its quota for the turn.
+    Quota,
+    /// It did not fit in what was left of the turn's budget.
+    Budget,
+}
+
+/// An injection that was not admitted; the payload of
+/// `context:injection_dropped`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroppedInjection {
+    /// The event the injection was returned for.
+    pub event: String,
+    pub source: String,
+    pub priority: i32,
+    pub reason: DropReason,
+    /// Estimated tokens in the injection.
+    pub tokens: usize,
+    /// Budget left when it was dropped, if there is a budget.
+    pub remaining: Option<usize>,
+}
+
+/// What [`InjectionQueue::admit`] let through.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Admission {
+    /// In merge order.
+    pub admitted: Vec<QueuedInjection>,
+    pub dropped: Vec<DroppedInjection>,
+}
+
+#[derive(Debug, Default)]
+struct TurnUsage {
+    tokens: usize,
+    by_source: HashMap<String, usize>,
+}
+
+/// Per-turn admission of context injections; see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct InjectionQueue {
+    config: InjectionQueueConfig,
+    budget: Option<usize>,
+    usage: Mutex<TurnUsage>,
+}
+
+impl InjectionQueue {
+    /// A queue with `config`'s quotas and `budget` estimated tokens per
+    /// turn (unlimited when `None`).
+    pub fn new(config: InjectionQueueConfig, budget: Option<usize>) -> Self {
+        Self {
+            config,
+            budget,
+            usage: Mutex::new(TurnUsage::default()),
+        }
+    }
+
+    /// A queue for `session.injection_queue` and
+    /// `session.injection_budget_per_turn`.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let budget = config
+            .get("session")
+            .and_then(|s| s.get("injection_budget_per_turn"))
+            .filter(|v| !v.is_null());
+        let budget = budget.and_then(|v| {
+            let parsed = v.as_u64().and_then(|b| usize::try_from(b).ok());
+            if parsed.is_none() {
+                log::warn!("Ignoring malformed session.injection_budget_per_turn: {v}");
+            }
+            parsed
+        });
+        Self::new(InjectionQueueConfig::from_session_config(config), budget)
+    }
+
+    pub fn config(&self) -> &InjectionQueueConfig {
+        &self.config
+    }
+
+    /// Estimated tokens per turn, if limited.
+    pub fn budget(&self) -> Option<usize> {
+        self.budget
+    }
+
+    /// Estimated tokens charged this turn.
+    pub fn used_tokens(&self) -> usize {
+        self.usage.lock().unwrap().tokens
+    }
+
+    /// Budget left this turn, if there is a budget.
+    pub fn remaining(&self) -> Option<usize> {
+        let used = self.used_tokens();
+        self.budget.map(|budget| budget.saturating_sub(used))
+    }
+
+    /// Charge `tokens` injected outside the queue to this turn's budget.
+    pub fn charge(&self, tokens: usize) {
+        self.usage.lock().unwrap().tokens += tokens;
+    }
+
+    /// Forget this turn's usage.
+    pub fn reset(&self) {
+        *self.usage.lock().unwrap() = TurnUsage::default();
+    }
+
+    /// Admit what fits of `injections` (in the order their handlers ran)
+    /// for `event`, charging it to the turn.
+    pub fn admit(&self, event: &str, injections: Vec<QueuedInjection>) -> Admission {
+        let mut usage = self.usage.lock().unwrap();
+        let mut admission = Admission::default();
+        let mut candidates = Vec::with_capacity(injections.len());
+        for injection in injections {
+            let used = usage.by_source.entry(injection.source.clone()).or_default();
+            match self.config.quota(&injection.source) {
+                Some(quota) if *used >= quota => {
+                    admission.dropped.push(self.dropped(
+                        event,
+                        &injection,
+                        DropReason::Quota,
+                        usage.tokens,
+                    ));
+                }
+                _ => {
+                    *used += 1;
+                    candidates.push(injection);
+                }
+            }
+        }
+
+        // Stable, so equal priorities keep the order their handlers ran in.
+        candidates.sort_by_key(|injection| injection.priority);
+        for injection in candidates {
+            let tokens = injection.tokens();
+            let fits = self
+                .budget
+                .is_none_or(|budget| usage.tokens + tokens <= budget);
+            if fits {
+                usage.tokens += tokens;
+                admission.admitted.push(injection);
+            } else {
+                // Its quota slot was never used.
+                if let Some(used) = usage.by_source.get_mut(&injection.source) {
+                    *used -= 1;
+                }
+                admission.dropped.push(self.dropped(
+                    event,
+                    &injection,
+                    DropReason::Budget,
+                    usage.tokens,
+                ));
+            }
+        }
+        admission
+    }
+
+    fn dropped(
+        &self,
+        event: &str,
+        injection: &QueuedInjection,
+        reason: DropReason,
+        used: usize,
+    ) -> DroppedInjection {
+        DroppedInjection {
+            event: event.to_string(),
+            source: injection.source.clone(),
+            priority: injection.priority,
+            reason,
+            tokens: injection.tokens(),
+            remaining: self.budget.map(|budget| budget.saturating_sub(used)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::models::HookAction;
+
+    fn injection(source: &str, priority: i32, chars: usize) -> QueuedInjection {
+        QueuedInjection {
+            source: source.into(),
+            priority,
+            result: HookResult {
+                action: HookAction::InjectContext,
+                context_injection: Some("x".repeat(chars)),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn sources(admission: &Admission) -> Vec<&str> {
+        admission
+            .admitted
+            .iter()
+            .map(|i| i.source.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn injections_are_merged_by_priority_within_the_budget() {
+        // 10 tokens per turn.
+        let queue = InjectionQueue::new(InjectionQueueConfig::default(), Some(10));
+        let admission = queue.admit(
+            "tool:post",
+            vec![
+                injection("late", 10, 8),
+                injection("big", 0, 32),
+                injection("first", 0, 16),
+                injection("tiny", 20, 4),
+            ],
+        );
+        // big (8) and first (4) would overflow: big is admitted first,
+        // first is dropped, and the smaller late (2) still fits.
+        assert_eq!(sources(&admission), ["big", "late"]);
+        assert_eq!(admission.dropped.len(), 2);
+        assert_eq!(admission.dropped[0].source, "first");
+        assert_eq!(admission.dropped[0].reason, DropReason::Budget);
+        assert_eq!(admission.dropped[0].remaining, Some(2));
+        assert_eq!(admission.dropped[1].source, "tiny");
+        assert_eq!(queue.used_tokens(), 10);
+
+        queue.reset();
+        assert_eq!(queue.remaining(), Some(10));
+    }
+
+    #[test]
+    fn sources_are_held_to_their_quotas_per_turn() {
+        let plan = HashMap::from([(
+            "session".to_string(),
+            json!({
+                "injection_budget_per_turn": null,
+                "injection_queue": {"default_quota": 2, "quotas": {"lint": 1}},
+            }),
+        )]);
+        let queue = InjectionQueue::from_session_config(&plan);
+        assert_eq!(queue.budget(), None);
+
+        let first = queue.admit(
+            "tool:post",
+            vec![injection("lint", 0, 4), injection("lint", 0, 4)],
+        );
+        assert_eq!(sources(&first), ["lint"]);
+        assert_eq!(first.dropped[0].reason, DropReason::Quota);
+
+        let second = queue.admit(
+            "prompt:submit",
+            vec![
+                injection("lint", 0, 4),
+                injection("todo", 0, 4),
+                injection("todo", 0, 4),
+                injection("todo", 0, 4),
+            ],
+        );
+        assert_eq!(sources(&second), ["todo", "todo"]);
+        assert_eq!(second.dropped.len(), 2);
+
+        queue.reset();
+        assert_eq!(
+            sources(&queue.admit("x", vec![injection("lint", 0, 4)])),
+            ["lint"]
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! `${ENV_VAR}` and `${secret:name}` references in session configs.
+//!
+//! [`SessionConfig::interpolate`](crate::session::SessionConfig::interpolate)
+//! replaces references inside every string value of a mount plan, so hosts
+//! no longer template the config themselves:
+//!
+//! ```json
+//! {"providers": [{"module": "provider-openai",
+//!                 "config": {"api_key": "${secret:openai}", "base_url": "${OPENAI_BASE_URL}"}}]}
+//! ```
+//!
+//! | Reference        | Resolved with                                              |
+//! |------------------|------------------------------------------------------------|
+//! | `${NAME}`        | `resolver.resolve(`[`ENV_SCOPE`]`, "NAME")`                |
+//! | `${secret:name}` | `resolver.resolve(`[`SECRET_SCOPE`]`, "name")`             |
+//! | `$${`            | a literal `${`                                             |
+//!
+//! Both go through a [`CredentialResolver`]: the default
+//! [`EnvCredentialResolver`](crate::credentials::EnvCredentialResolver) reads
+//! both kinds from the process environment, while a vault-backed resolver
+//! can serve secrets by name. `NAME` must be a valid environment variable
+//! name (`[A-Za-z_][A-Za-z0-9_]*`).
+//!
+//! Only values are interpolated, not keys, and the result is always a
+//! string. Resolution does not stop at the first failure: every missing
+//! variable, failed lookup and malformed reference is reported together in
+//! `SessionError::ConfigInterpolation`.
+//!
+//! Resolved secrets are stored in the config as plain strings; keep
+//! interpolated configs out of logs.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::credentials::{CredentialError, CredentialResolver};
+
+/// Provider ID passed to the resolver for `${NAME}` references.
+pub const ENV_SCOPE: &str = "env";
+
+/// Provider ID passed to the resolver for `${secret:name}` references.
+pub const SECRET_SCOPE: &str = "secret";
+
+const SECRET_PREFIX: &str = "secret:";
+
+/// A reference that could not be replaced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnresolvedReference {
+    /// Where the value sits in the config, e.g. `providers[0].config.api_key`.
+    pub path: String,
+    /// The reference as written, e.g. `${OPENAI_API_KEY}`.
+    pub reference: String,
+    /// Why it was not replaced.
+    pub reason: String,
+}
+
+impl fmt::Display for UnresolvedReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {} ({})", self.reference, self.path, self.reason)
+    }
+}
+
+/// Replace the references in every string value of `config`.
+///
+/// Values whose references all resolve are replaced even when others fail.
+///
+/// # Errors
+///
+/// Every reference that could not be resolved, in config order (top-level
+/// keys sorted).
+pub fn interpolate_config(
+    config: &mut HashMap<String, Value>,
+    resolver: &dyn CredentialResolver,
+) -> Result<(), Vec<UnresolvedReference>> {
+    let mut unresolved = Vec::new();
+    let mut keys: Vec<_> = config.keys().cloned().collect();
+    keys.sort();
+    for key in keys {
+        if let Some(value) = config.get_mut(&key) {
+            interpolate_value(value, &key, resolver, &mut unresolved);
+        }
+    }
+    if unresolved.is_empty() {
+        Ok(())
+    } else {
+        Err(unresolved)
+    }
+}
+
+fn interpolate_value(
+    value: &mut Value,
+    path: &str,
+    resolver: &dyn CredentialResolver,
+    unresolved: &mut Vec<UnresolvedReference>,
+) {
+    match value {
+        Value::String(text) if text.contains("${") => {
+            if let Some(replaced) = interpolate_str(text, path, resolver, unresolved) {
+                *text = replaced;
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                interpolate_value(item, &format!("{path}[{i}]"), resolver, unresolved);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                interpolate_value(item, &format!("{path}.{key}"), resolver, unresolved);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `text` with its references replaced, or `None` if any failed (recorded
+/// in `unresolved`).
+fn interpolate_str(
+    text: &str,
+    path: &str,
+    resolver: &dyn CredentialResolver,
+    unresolved: &mut Vec<UnresolvedReference>,
+) -> Option<String> {
+    let failures = unresolved.len();
+    let mut fail = |reference: &str, reason: String| {
+        unresolved.push(UnresolvedReference {
+            path: path.to_string(),
+            reference: reference.to_string(),
+            reason,
+        });
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            fail(&rest[start..], "unterminated reference".into());
+            return None;
+        };
+        let reference = &rest[start..start + len + 1];
+        let name = &reference[2..reference.len() - 1];
+        rest = &rest[start + len + 1..];
+
+        let (scope, field) = match name.strip_prefix(SECRET_PREFIX) {
+            Some(secret) if !secret.is_empty() => (SECRET_SCOPE, secret),
+            Some(_) => {
+                fail(reference, "empty secret name".into());
+                continue;
+            }
+            None if is_env_name(name) => (ENV_SCOPE, name),
+            None => {
+                fail(reference, "invalid variable name".into());
+                continue;
+            }
+        };
+        match resolver.resolve(scope, field) {
+            Ok(secret) => out.push_str(secret.expose_secret()),
+            Err(CredentialError::NotFound { .. }) => fail(reference, "not set".into()),
+            Err(e) => fail(reference, e.to_string()),
+        }
+    }
+    out.push_str(rest);
+    (unresolved.len() == failures).then_some(out)
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::StaticCredentialResolver;
+    use serde_json::json;
+
+    fn resolver() -> StaticCredentialResolver {
+        StaticCredentialResolver::new()
+            .with(ENV_SCOPE, "BASE_URL", "https://api.example.com")
+            .with(ENV_SCOPE, "REGION", "eu")
+            .with(SECRET_SCOPE, "openai", "sk-test")
+    }
+
+    fn config(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn replaces_env_and_secret_references() {
+        let mut plan = config(json!({
+            "providers": [{"config": {
+                "api_key": "${secret:openai}",
+                "base_url": "${BASE_URL}/v1/${REGION}",
+                "template": "literal $${BASE_URL}",
+                "retries": 3
+            }}]
+        }));
+        interpolate_config(&mut plan, &resolver()).unwrap();
+        assert_eq!(
+            plan["providers"][0]["config"],
+            json!({
+                "api_key": "sk-test",
+                "base_url": "https://api.example.com/v1/eu",
+                "template": "literal ${BASE_URL}",
+                "retries": 3
+            })
+        );
+    }
+
+    #[test]
+    fn collects_every_unresolved_reference() {
+        let mut plan = config(json!({
+            "session": {"orchestrator": "${ORCHESTRATOR}", "context": "${REGION}"},
+            "providers": [{"config": {"api_key": "${secret:anthropic}", "bad": "${not-a-var}"}}],
+            "tools": ["${TOOL"]
+        }));
+        let unresolved = interpolate_config(&mut plan, &resolver()).unwrap_err();
+        let found: Vec<_> = unresolved
+            .iter()
+            .map(|u| (u.path.as_str(), u.reference.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("providers[0].config.api_key", "${secret:anthropic}"),
+                ("providers[0].config.bad", "${not-a-var}"),
+                ("session.orchestrator", "${ORCHESTRATOR}"),
+                ("tools[0]", "${TOOL"),
+            ]
+        );
+        assert_eq!(unresolved[0].reason, "not set");
+        // Resolvable values are still replaced.
+        assert_eq!(plan["session"]["context"], "eu");
+    }
+}
@@ -12,30 +12,142 @@
 //! - `models` — Core data models (HookResult, ToolResult, ModelInfo, etc.)
 //! - `messages` — Chat protocol models (ChatRequest, ChatResponse, Message, etc.)
 //! - `traits` — Module contracts (Tool, Provider, Orchestrator, etc.)
+//! - `native` — `async fn` versions of the module contracts and the `Native` adapter
+//! - `catalog` — Module catalogs and static mount-plan validation
+//! - `config_schema` — JSON Schema of mount plans for rendering configuration forms
+//! - `config_migration` — `config_version` migrations and deprecated-key warnings for mount plans
 //! - `cancellation` — CancellationToken state machine
+//! - `clock` — Injectable time source (system clock, manual test clock)
+//! - `approval` — Approval wait loop with timeout and cancellation handling
+//! - `approval_memory` — Remembered approval decisions, optionally persisted
+//! - `display` — No-op and channel-backed display providers
 //! - `hooks` — HookRegistry event dispatch pipeline
+//! - `correlation` — Turn and tool-call correlation IDs on events
+//! - `hook_subscriptions` — Hook registrations declared in the mount plan
+//! - `event_filter` — Emit-time event filtering and sampling
+//! - `deadline` — Turn-scoped deadlines for provider and tool calls
 //! - `coordinator` — ModuleCoordinator mount points and capabilities
+//! - `credentials` — Host-pluggable provider credential resolution
+//! - `interpolation` — `${ENV_VAR}` and `${secret:name}` references in session configs
+//! - `notifications` — Deduplicated, rate-limited user notifications
+//! - `memory` — Memory accounting and bounded buffers
+//! - `model_catalog` — TTL cache of provider model lists
+//! - `dialect` — Provider wire dialects (OpenAI, Anthropic request/response mapping)
+//! - `streaming` — Reassembly of streamed provider chunks into a `ChatResponse`
+//! - `wire` — JSON, MessagePack and CBOR encodings for cross-boundary payloads
+//! - `orchestrator_status` — Typed interim orchestrator states (`orchestrator:status`)
+//! - `recovery` — Provider and tool failure recovery policy for orchestrator turns
+//! - `provider_invoker` — Hook-wrapped provider calls (`provider:pre` / `provider:post`)
+//! - `fanout` — Concurrent multi-provider calls (race and ensemble modes)
+//! - `images` — Typed image sources, provider image limits and re-encoding (PNG codec: feature `image-codec`)
+//! - `injection_queue` — Per-turn quotas, priorities and budget for hook context injections
+//! - `structured_output` — JSON-schema validation and re-asking for structured provider output
+//! - `visibility` — Per-provider stripping of internal content from requests
+//! - `tool_executor` — Idempotent, deadline-bounded tool calls
+//! - `tool_cache` — Session-wide result caching for pure tools
+//! - `tool_choice` — Kernel-side enforcement of `ChatRequest::tool_choice`
+//! - `tool_services` — Event emission, cancellation and capability lookups for running tools
+//! - `topology` — DOT, Mermaid and JSON graphs of mounted modules and their connections
+//! - `tool_output` — Tool result truncation, binary detection and transform hooks
+//! - `tool_discovery` — Per-turn proxy tools proposed by hooks (`tools:discover`)
+//! - `tools` — Reference tool implementations (`echo`, `http_fetch`, `read_file`; feature `builtin-tools`)
+//! - `conversation_store` — Durable per-session message history (SQLite backend: feature `sqlite`)
+//! - `context_dedup` — Collapsing of repeated tool results and injected context
+//! - `summarizer` — Conversation summarization for context compaction
+//! - `provenance` — Origin, turn and time recorded on each context message
+//! - `attachments` — Content-addressed storage for large tool outputs
 //! - `session` — AmplifierSession lifecycle management
+//! - `prompt_history` — Per-session prompt history and duplicate resubmission detection
+//! - `heartbeat` — Periodic `session:heartbeat` liveness events during execution
+//! - `session_manager` — Live-session table with idle reaping
+//! - `pricing` — Model pricing catalogs and per-provider, per-turn cost estimation
+//! - `quota` — Per-session tool, provider, token and duration limits
+//! - `safety` — Per-turn turn, tool-call and loop-detection safeguards
+//! - `workspace` — Per-session working directory and filesystem scope for tools
+//! - `audit` — Hash-chained audit log of tool executions
+//! - `timeline` — Ordered record of session lifecycle milestones
+//! - `checkpoint` — Conversation checkpoints for rewinding and branching
 
+pub mod approval;
+pub mod approval_memory;
+pub mod attachments;
+pub mod audit;
 pub mod bridges;
 pub mod cancellation;
 pub mod capabilities;
+pub mod catalog;
+pub mod checkpoint;
+pub mod clock;
+pub mod config_migration;
+pub mod config_schema;
+pub mod context_dedup;
+pub mod conversation_store;
 pub mod coordinator;
+pub mod correlation;
+pub mod credentials;
+pub mod deadline;
+pub mod dialect;
+pub mod display;
 pub mod errors;
+pub mod event_filter;
+pub mod event_queue;
 pub mod events;
+pub mod fanout;
 pub mod generated;
 pub mod grpc_server;
+pub mod heartbeat;
+pub mod hook_subscriptions;
 pub mod hooks;
+pub mod images;
+pub mod injection_queue;
+pub mod interpolation;
+pub mod manifest;
+pub mod memory;
 pub mod messages;
+pub mod model_catalog;
 pub mod models;
 pub mod module_resolver;
+pub mod native;
+pub mod notifications;
+pub mod orchestrator_status;
+pub mod policy;
+pub mod pricing;
+pub mod prompt_history;
+pub mod provenance;
+pub mod provider_invoker;
+pub mod quota;
+pub mod recovery;
+pub mod request_conformance;
 pub mod retry;
+pub mod safety;
 pub mod session;
+pub mod session_manager;
+pub mod streaming;
+pub mod structured_output;
+pub mod summarizer;
+pub mod telemetry;
 pub mod testing;
+pub mod timeline;
+pub mod token_counter;
+pub mod tool_cache;
+pub mod tool_choice;
+pub mod tool_discovery;
+pub mod tool_executor;
+pub mod tool_format;
+pub mod tool_output;
+pub mod tool_progress;
+pub mod tool_services;
+#[cfg(feature = "builtin-tools")]
+pub mod tools;
+pub mod topology;
 pub mod traits;
 pub mod transport;
+pub mod turn;
+pub mod visibility;
 #[cfg(feature = "wasm")]
 pub mod wasm_engine;
+pub mod wire;
+pub mod workspace;
 
 // ---------------------------------------------------------------------------
 // Re-exports — consumers write `use amplifier_core::Tool`, not
@@ -43,35 +155,187 @@ pub mod wasm_engine;
 // ---------------------------------------------------------------------------
 
 // Traits (module contracts)
-pub use traits::{ApprovalProvider, ContextManager, HookHandler, Orchestrator, Provider, Tool};
+pub use native::{
+    AsyncContextManager, AsyncHookHandler, AsyncOrchestrator, AsyncProvider, AsyncTool, Native,
+};
+pub use traits::{
+    ApprovalProvider, BoxFuture, ContextManager, DisplayProvider, HookHandler, Moderator,
+    ModuleLifecycle, Orchestrator, Provider, Tool,
+};
 
 // Error types
-pub use errors::{AmplifierError, ContextError, HookError, ProviderError, SessionError, ToolError};
+pub use errors::{
+    AmplifierError, ContextError, CoordinatorError, ErrorReport, HookError, ProviderError,
+    ResultExt, SessionError, ToolError,
+};
 
 // Core data models
 pub use models::{
-    ApprovalDefault, ApprovalRequest, ApprovalResponse, ConfigField, ConfigFieldType,
-    ContextInjectionRole, HookAction, HookResult, ModelInfo, ModuleInfo, ModuleType, ProviderInfo,
-    SessionState, SessionStatus, ToolResult, UserMessageLevel,
+    ApprovalDefault, ApprovalRequest, ApprovalResponse, Candidate, ConfigField, ConfigFieldType,
+    ContextInjectionRole, HealthStatus, HookAction, HookResult, MessagePriority, ModelInfo,
+    ModerationResult, ModuleHealth, ModuleInfo, ModuleType, ProviderInfo, SessionState,
+    SessionStatus, ToolContext, ToolOutputFormat, ToolProgress, ToolResult, UserMessageLevel,
 };
 
 // Chat protocol models
 pub use messages::{
-    ChatRequest, ChatResponse, ContentBlock, ContentBlockType, Degradation, Message,
+    CacheHint, ChatRequest, ChatResponse, ContentBlock, ContentBlockType, Degradation, Message,
     MessageContent, ResponseFormat, Role, ToolCall, ToolChoice, ToolSpec, Usage, Visibility,
 };
 
 // Cancellation
 pub use cancellation::{CancellationState, CancellationToken};
 
+// Time
+pub use clock::{Clock, SystemClock};
+
 // Hooks
-pub use hooks::HookRegistry;
+pub use correlation::CorrelationIds;
+pub use event_filter::{EventFilter, EventFilterConfig, EventLevel};
+pub use hook_subscriptions::{HookHandlerSet, HookSubscription};
+pub use hooks::builtin::{BuiltinHooksConfig, ContentFilter, LoggingHook, TokenBudgetGuard};
+pub use hooks::condition::{ConditionError, HookCondition};
+pub use hooks::spill::{HookDataLimit, HookSpillStats};
+pub use hooks::{HookPhase, HookRegistry, HookScope, HookSnapshot};
+pub use injection_queue::{DropReason, DroppedInjection, InjectionQueue, InjectionQueueConfig};
+
+// Orchestrator status
+pub use orchestrator_status::{OrchestratorStatus, StatusReporter};
+pub use recovery::{
+    FailureKind, RecoveryAction, RecoveryAttempt, ToolFailureAction, TurnRecovery,
+    TurnRecoveryPolicy,
+};
+
+// Approval
+pub use approval::{ApprovalGate, ApprovalOutcome, CancelResolution};
+pub use approval_memory::{
+    ApprovalMemory, ApprovalMemoryConfig, ApprovalMemoryStore, FileApprovalStore, RememberScope,
+};
 
 // Coordinator
-pub use coordinator::Coordinator;
+pub use coordinator::{
+    CapabilityLookup, Channel, Contributions, Coordinator, CoordinatorReport, HealthReport,
+    InvalidContribution, ModuleHealthEntry, MountPoint, MountedModule, TurnDeadlineScope,
+};
+pub use topology::{GraphFormat, ModuleGraph};
+
+// User notifications
+pub use notifications::{NotificationConfig, NotificationLevel, NotificationOutcome};
+
+// Credentials
+pub use credentials::{
+    CredentialError, CredentialResolver, EnvCredentialResolver, SecretString,
+    StaticCredentialResolver,
+};
+pub use interpolation::UnresolvedReference;
+
+// Event queue
+pub use event_queue::{EventQueue, EventQueueConfig, OverflowPolicy};
+
+// Deadlines
+pub use deadline::TurnDeadline;
+
+// Module manifests
+pub use catalog::{CatalogEntry, ModuleCatalog, MountPlanProblem};
+pub use config_migration::{ConfigMigrationError, ConfigMigrations, MigrationReport};
+pub use manifest::{ManifestError, ModuleDescriptor, ModuleFactory, MountPlan};
+
+// Memory accounting
+pub use memory::{BoundedBuffer, EvictionPolicy, MemoryAccountant, MemoryConfig, MemoryUsage};
+pub use model_catalog::{ModelCatalog, ModelCatalogConfig};
+
+// Tool permission policy
+pub use policy::{PermissionPolicy, PolicyConfig};
+pub use workspace::{PathAccess, Workspace, WorkspaceViolation};
+
+// Cost estimation
+pub use pricing::{CostBreakdown, CostReport, CostTracker, ModelPricing, PricingCatalog};
+
+// Session quotas
+pub use quota::{QuotaBreach, QuotaConfig, QuotaEnforcer, QuotaResource, QuotaUsage};
+
+// Turn safeguards
+pub use safety::{SafetyBreach, SafetyConfig, SafetyGuard, SafetyLimit};
+
+// Tool audit log
+pub use audit::{AuditApproval, AuditConfig, AuditError, AuditLog, AuditRecord};
+
+// Provider middleware
+pub use dialect::{
+    AnthropicDialect, Dialect, DialectError, DialectTools, OpenAiDialect, ProviderDialect,
+};
+pub use fanout::{EnsembleSelector, FanoutMode, FanoutProvider, FanoutReport, PreferOrder};
+#[cfg(feature = "image-codec")]
+pub use images::PngTranscoder;
+pub use images::{ImageError, ImageLimits, ImageSource, ImageTranscoder};
+pub use provider_invoker::ProviderInvoker;
+pub use request_conformance::{RequestAdjustment, RequestLimits};
+pub use streaming::{
+    ResponseAccumulator, StreamChunk, StreamError, StreamEvent, StreamEventConfig,
+};
+pub use structured_output::{SchemaViolation, StructuredOutput, StructuredOutputError};
+
+// Context hygiene
+pub use context_dedup::{DedupConfig, DedupContext, DedupReport, DuplicateGroup, DuplicateKind};
+pub use provenance::{Origin, Provenance, ProvenanceConfig, ProvenanceContext};
+pub use summarizer::{
+    CompactionReport, ProviderSummarizer, SummarizationConfig, Summarizer, SummarizingContext,
+};
+
+// Conversation storage
+#[cfg(feature = "sqlite")]
+pub use conversation_store::SqliteConversationStore;
+pub use conversation_store::{
+    ConversationStore, FileConversationStore, InMemoryConversationStore, PersistentContext,
+};
+
+// Attachments
+pub use attachments::{
+    Attachment, AttachmentBackend, AttachmentConfig, AttachmentRef, AttachmentStore,
+    FileAttachmentBackend, InMemoryAttachmentBackend,
+};
 
 // Session
-pub use session::{Session, SessionConfig};
+pub use prompt_history::{PromptHistory, PromptHistoryConfig, PromptRecord};
+pub use session::{
+    CurrentExecution, ExecutionHandle, ExecutionStatus, ReentrancyPolicy, Session, SessionConfig,
+};
+pub use session_manager::SessionManager;
+
+// Telemetry
+#[cfg(feature = "otel")]
+pub use telemetry::OtelTelemetry;
+pub use telemetry::TelemetryConfig;
+
+// Checkpoints
+pub use checkpoint::{Checkpoint, CheckpointId, CheckpointInfo, CheckpointStore};
+
+// Session timeline
+pub use timeline::{Milestone, Timeline, TimelineEntry};
+
+// Token counting
+#[cfg(feature = "tiktoken")]
+pub use token_counter::TiktokenCounter;
+pub use token_counter::{ContextBudget, HeuristicTokenCounter, TokenCounter};
+
+// Tool execution
+pub use tool_cache::{PureToolCache, PureToolCacheConfig};
+pub use tool_discovery::{DiscoveredTools, ProxyTool, ToolDiscoveryConfig};
+pub use tool_executor::{IdempotencyKey, ToolExecutor, ToolResultCache};
+pub use tool_output::{ToolOutputConfig, ToolOutputProcessor};
+
+// Tool progress
+pub use tool_progress::{ToolUpdate, ToolUpdateStream};
+pub use tool_services::ToolServices;
+
+// Turn results
+pub use turn::{ToolCallRecord, TurnResult};
+
+// Content visibility
+pub use visibility::{VisibilityConfig, VisibilityPolicy, VisibilityReport};
+
+// Wire formats
+pub use wire::{WireError, WireFormat};
 
 /// `AmplifierSession` is the universal name for the session type across all language SDKs.
 /// `Session` remains available for backward compatibility.
@@ -80,6 +344,7 @@ pub type AmplifierSession = Session;
 #[cfg(test)]
 mod tests {
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn crate_compiles() {
         assert!(true);
     }
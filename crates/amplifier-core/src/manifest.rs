@@ -0,0 +1,907 @@
+//! Module manifests — parsing, dependency resolution and mount plans.
+//!
+//! A manifest describes one module: its [`ModuleInfo`] plus the modules and
+//! capabilities it depends on. [`resolve()`] checks a set of manifests for
+//! duplicate IDs, missing dependencies and capabilities, cycles and
+//! mount-point conflicts, and orders them into a [`MountPlan`] that
+//! [`MountPlan::apply()`] mounts on a [`Coordinator`]. Hosts that construct
+//! modules asynchronously implement [`ModuleFactory`] and call
+//! [`Coordinator::apply_mount_plan`], which also runs each module's
+//! [`ModuleLifecycle::init`](crate::traits::ModuleLifecycle::init) with its
+//! config and unmounts everything it mounted if a later module fails.
+//!
+//! # Format
+//!
+//! TOML (the `[module]` section of `amplifier.toml`, so the same file can
+//! carry transport details for [`crate::module_resolver`]) or the equivalent
+//! JSON object:
+//!
+//! ```toml
+//! [module]
+//! id = "tool-search"
+//! type = "tool"
+//! version = "1.2.0"
+//! description = "Semantic search over the workspace"
+//! depends_on = ["provider-embeddings"]
+//! optional_depends_on = ["hooks-logging"]
+//! requires = ["vector-store"]
+//! ```
+//!
+//! | Field                 | Default                         |
+//! |-----------------------|---------------------------------|
+//! | `id`, `type`          | required                        |
+//! | `name`                | `id`                            |
+//! | `version`             | `"0.0.0"`                       |
+//! | `mount_point`         | derived from `type`; must match |
+//! | `description`         | `""`                            |
+//! | `config_schema`       | none                            |
+//! | `depends_on`          | `[]` — must be in the same set  |
+//! | `optional_depends_on` | `[]` — ordered before if present |
+//! | `provides`            | `[]` — capabilities it registers |
+//! | `requires`            | `[]` — capabilities it needs     |
+//! | `events`              | `[]` — required for `hook` modules |
+//!
+//! # Mount points
+//!
+//! | Type           | Mount point              | Modules per set |
+//! |----------------|--------------------------|-----------------|
+//! | `orchestrator` | `orchestrator`           | one             |
+//! | `context`      | `context`                | one             |
+//! | `provider`     | `providers`              | any             |
+//! | `tool`         | `tools`                  | any             |
+//! | `hook`         | `hooks`                  | any             |
+//! | `resolver`     | `module-source-resolver` | one             |
+//! | `approval`     | the approval provider    | one             |
+//!
+//! # Ordering
+//!
+//! Dependencies always come first, and so do the modules that `provide` a
+//! capability another module `requires`. Otherwise modules are ordered like
+//! the Python session initializer mounts them — orchestrator, context,
+//! providers, tools, agents, resolver, hooks, approval — and by input order
+//! within a mount point.
+//!
+//! # Capabilities
+//!
+//! A required capability must be provided by a module in the set or be
+//! listed as available by the host ([`resolve_with_capabilities()`]).
+//! [`MountPlan::apply()`] records each module's requirements with
+//! [`Coordinator::require_capabilities`], so a session whose modules did not
+//! actually register them fails before executing, with
+//! [`CoordinatorError::MissingCapabilities`](crate::errors::CoordinatorError::MissingCapabilities).
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::coordinator::{Coordinator, MountPoint};
+use crate::models::{ModuleInfo, ModuleType};
+use crate::module_resolver::LoadedModule;
+use crate::traits::BoxFuture;
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+/// Errors from manifest parsing, resolution and plan application.
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    /// The manifest could not be parsed.
+    #[error("invalid module manifest {source_name}: {reason}")]
+    Parse { source_name: String, reason: String },
+
+    /// I/O error reading a manifest file.
+    #[error("I/O error at {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// Two manifests share a module ID.
+    #[error("module '{id}' is declared more than once")]
+    DuplicateModule { id: String },
+
+    /// A required dependency is not in the manifest set.
+    #[error("module '{module}' depends on '{dependency}', which is not in the manifest set")]
+    MissingDependency { module: String, dependency: String },
+
+    /// No module in the set provides a required capability, and the host
+    /// did not list it as available.
+    #[error("module '{module}' requires capability '{capability}', which no module provides")]
+    MissingCapability { module: String, capability: String },
+
+    /// The dependency graph has a cycle.
+    #[error("dependency cycle between modules: {}", modules.join(", "))]
+    DependencyCycle { modules: Vec<String> },
+
+    /// More than one module targets a single-slot mount point.
+    #[error("mount point '{mount_point}' takes one module, but {} target it", modules.join(", "))]
+    MountConflict {
+        mount_point: String,
+        modules: Vec<String>,
+    },
+
+    /// The loader failed to produce a module.
+    #[error("failed to load module '{module}': {reason}")]
+    Load { module: String, reason: String },
+
+    /// The loader produced a different kind of module than the manifest declares.
+    #[error("module '{module}' is declared as {expected:?} but loaded as {loaded}")]
+    TypeMismatch {
+        module: String,
+        expected: ModuleType,
+        loaded: &'static str,
+    },
+
+    /// The loaded module cannot be mounted by the coordinator.
+    #[error("module '{module}' cannot be mounted: {reason}")]
+    NotMountable { module: String, reason: String },
+
+    /// The module's `init` failed, so it was not mounted.
+    #[error("module '{module}' failed to initialize: {reason}")]
+    Init { module: String, reason: String },
+}
+
+// ---------------------------------------------------------------------------
+// ModuleDescriptor
+// ---------------------------------------------------------------------------
+
+/// Where a module is attached once loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MountTarget {
+    /// A coordinator mount point.
+    Point(MountPoint),
+    /// The coordinator's approval provider slot.
+    ApprovalProvider,
+}
+
+impl MountTarget {
+    /// The default target for a module type.
+    pub fn for_module_type(module_type: &ModuleType) -> Self {
+        match module_type {
+            ModuleType::Orchestrator => Self::Point(MountPoint::Orchestrator),
+            ModuleType::Context => Self::Point(MountPoint::Context),
+            ModuleType::Provider => Self::Point(MountPoint::Providers),
+            ModuleType::Tool => Self::Point(MountPoint::Tools),
+            ModuleType::Hook => Self::Point(MountPoint::Hooks),
+            ModuleType::Resolver => Self::Point(MountPoint::Resolver),
+            ModuleType::Approval => Self::ApprovalProvider,
+        }
+    }
+
+    /// The name used in manifests and [`ModuleInfo::mount_point`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Point(point) => point.as_str(),
+            Self::ApprovalProvider => "approval",
+        }
+    }
+
+    /// Whether any number of modules may share this target.
+    pub fn is_multi_module(&self) -> bool {
+        match self {
+            Self::Point(point) => point.is_multi_slot() || *point == MountPoint::Hooks,
+            Self::ApprovalProvider => false,
+        }
+    }
+
+    /// Position in the default mount order.
+    fn rank(&self) -> usize {
+        match self {
+            Self::Point(point) => MountPoint::ALL
+                .iter()
+                .position(|p| p == point)
+                .unwrap_or(MountPoint::ALL.len()),
+            Self::ApprovalProvider => MountPoint::ALL.len(),
+        }
+    }
+}
+
+impl std::str::FromStr for MountTarget {
+    type Err = crate::errors::CoordinatorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "approval" {
+            return Ok(Self::ApprovalProvider);
+        }
+        s.parse().map(Self::Point)
+    }
+}
+
+/// One parsed module manifest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleDescriptor {
+    /// Module metadata; `mount_point` holds the resolved target name.
+    pub info: ModuleInfo,
+    /// Where the module is mounted.
+    pub target: MountTarget,
+    /// Modules that must be mounted first.
+    pub depends_on: Vec<String>,
+    /// Modules mounted first when present; ignored otherwise.
+    pub optional_depends_on: Vec<String>,
+    /// Capabilities the module registers on the coordinator.
+    pub provides: Vec<String>,
+    /// Capabilities the module needs; their providers are mounted first.
+    pub requires: Vec<String>,
+    /// Events a hook module's handler is registered for.
+    pub events: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawManifest {
+    module: RawModule,
+}
+
+#[derive(Deserialize)]
+struct RawModule {
+    id: String,
+    #[serde(rename = "type")]
+    module_type: ModuleType,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    mount_point: Option<String>,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    config_schema: Option<Value>,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    #[serde(default)]
+    optional_depends_on: Vec<String>,
+    #[serde(default)]
+    provides: Vec<String>,
+    #[serde(default)]
+    requires: Vec<String>,
+    #[serde(default)]
+    events: Vec<String>,
+}
+
+impl ModuleDescriptor {
+    /// Parse a TOML manifest. `source_name` is used in error messages.
+    pub fn from_toml(content: &str, source_name: &str) -> Result<Self, ManifestError> {
+        let raw: RawManifest = toml::from_str(content).map_err(|e| ManifestError::Parse {
+            source_name: source_name.to_string(),
+            reason: e.to_string(),
+        })?;
+        Self::from_raw(raw.module, source_name)
+    }
+
+    /// Parse a JSON manifest. `source_name` is used in error messages.
+    pub fn from_json(content: &str, source_name: &str) -> Result<Self, ManifestError> {
+        let raw: RawManifest = serde_json::from_str(content).map_err(|e| ManifestError::Parse {
+            source_name: source_name.to_string(),
+            reason: e.to_string(),
+        })?;
+        Self::from_raw(raw.module, source_name)
+    }
+
+    /// Read a manifest file, choosing the format by extension (`.json`,
+    /// otherwise TOML).
+    pub fn from_path(path: &Path) -> Result<Self, ManifestError> {
+        let content = std::fs::read_to_string(path).map_err(|source| ManifestError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let source_name = path.display().to_string();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&content, &source_name),
+            _ => Self::from_toml(&content, &source_name),
+        }
+    }
+
+    fn from_raw(raw: RawModule, source_name: &str) -> Result<Self, ManifestError> {
+        let invalid = |reason: String| ManifestError::Parse {
+            source_name: source_name.to_string(),
+            reason,
+        };
+        if raw.id.trim().is_empty() {
+            return Err(invalid("'id' must not be empty".into()));
+        }
+        let target = MountTarget::for_module_type(&raw.module_type);
+        if let Some(name) = &raw.mount_point {
+            let declared = name
+                .parse::<MountTarget>()
+                .map_err(|e| invalid(e.to_string()))?;
+            if declared != target {
+                return Err(invalid(format!(
+                    "{:?} modules mount at '{}', not '{name}'",
+                    raw.module_type,
+                    target.as_str()
+                )));
+            }
+        }
+        if raw.module_type == ModuleType::Hook && raw.events.is_empty() {
+            return Err(invalid(format!(
+                "hook module '{}' must list the events it handles",
+                raw.id
+            )));
+        }
+        if raw.depends_on.contains(&raw.id) {
+            return Err(invalid(format!("module '{}' depends on itself", raw.id)));
+        }
+        Ok(Self {
+            info: ModuleInfo {
+                name: raw.name.unwrap_or_else(|| raw.id.clone()),
+                id: raw.id,
+                version: raw.version.unwrap_or_else(|| "0.0.0".into()),
+                module_type: raw.module_type,
+                mount_point: target.as_str().to_string(),
+                description: raw.description,
+                config_schema: raw.config_schema,
+            },
+            target,
+            depends_on: raw.depends_on,
+            optional_depends_on: raw.optional_depends_on,
+            provides: raw.provides,
+            requires: raw.requires,
+            events: raw.events,
+        })
+    }
+
+    /// The module ID.
+    pub fn id(&self) -> &str {
+        &self.info.id
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Resolution
+// ---------------------------------------------------------------------------
+
+/// A module ready to mount, with the dependencies that precede it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountStep {
+    pub module: ModuleDescriptor,
+    /// IDs of the modules in the plan this step was ordered after.
+    pub after: Vec<String>,
+    /// The module's config, `null` unless set with
+    /// [`MountPlan::with_session_config`].
+    pub config: Value,
+}
+
+/// Manifests in mount order. See the [module docs](self) for the rules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MountPlan {
+    pub steps: Vec<MountStep>,
+}
+
+/// Validate `modules` and order them into a [`MountPlan`].
+///
+/// # Errors
+///
+/// [`ManifestError::DuplicateModule`], [`ManifestError::MissingDependency`],
+/// [`ManifestError::MissingCapability`], [`ManifestError::MountConflict`] or
+/// [`ManifestError::DependencyCycle`].
+pub fn resolve(modules: &[ModuleDescriptor]) -> Result<MountPlan, ManifestError> {
+    resolve_with_capabilities(modules, &[])
+}
+
+/// Like [`resolve()`], but capabilities in `available` (registered by the
+/// host rather than a module) satisfy `requires` without a provider.
+pub fn resolve_with_capabilities(
+    modules: &[ModuleDescriptor],
+    available: &[&str],
+) -> Result<MountPlan, ManifestError> {
+    let mut index: HashMap<&str, usize> = HashMap::with_capacity(modules.len());
+    for (i, module) in modules.iter().enumerate() {
+        if index.insert(module.id(), i).is_some() {
+            return Err(ManifestError::DuplicateModule {
+                id: module.id().to_string(),
+            });
+        }
+    }
+
+    // Single-slot conflicts, reported in mount order.
+    let mut by_target: Vec<(MountTarget, Vec<String>)> = Vec::new();
+    for module in modules.iter().filter(|m| !m.target.is_multi_module()) {
+        match by_target.iter_mut().find(|(t, _)| *t == module.target) {
+            Some((_, ids)) => ids.push(module.id().to_string()),
+            None => by_target.push((module.target, vec![module.id().to_string()])),
+        }
+    }
+    by_target.sort_by_key(|(target, _)| target.rank());
+    if let Some((target, ids)) = by_target.into_iter().find(|(_, ids)| ids.len() > 1) {
+        return Err(ManifestError::MountConflict {
+            mount_point: target.as_str().to_string(),
+            modules: ids,
+        });
+    }
+
+    let mut providers: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, module) in modules.iter().enumerate() {
+        for capability in &module.provides {
+            providers.entry(capability.as_str()).or_default().push(i);
+        }
+    }
+
+    // Edges: dependency -> dependent.
+    let mut after: Vec<Vec<usize>> = vec![Vec::new(); modules.len()];
+    for (i, module) in modules.iter().enumerate() {
+        for capability in &module.requires {
+            match providers.get(capability.as_str()) {
+                Some(by) => after[i].extend(by.iter().filter(|&&d| d != i)),
+                None if available.contains(&capability.as_str()) => {}
+                None => {
+                    return Err(ManifestError::MissingCapability {
+                        module: module.id().to_string(),
+                        capability: capability.clone(),
+                    })
+                }
+            }
+        }
+        for dep in &module.depends_on {
+            let &d = index
+                .get(dep.as_str())
+                .ok_or_else(|| ManifestError::MissingDependency {
+                    module: module.id().to_string(),
+                    dependency: dep.clone(),
+                })?;
+            after[i].push(d);
+        }
+        for dep in &module.optional_depends_on {
+            if let Some(&d) = index.get(dep.as_str()) {
+                if d != i {
+                    after[i].push(d);
+                }
+            }
+        }
+        after[i].sort_unstable();
+        after[i].dedup();
+    }
+
+    // Kahn's algorithm; the ready set is ordered by (mount rank, input order).
+    let mut pending: Vec<usize> = after.iter().map(Vec::len).collect();
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); modules.len()];
+    for (i, deps) in after.iter().enumerate() {
+        for &d in deps {
+            dependents[d].push(i);
+        }
+    }
+    let mut ready: BTreeSet<(usize, usize)> = (0..modules.len())
+        .filter(|&i| pending[i] == 0)
+        .map(|i| (modules[i].target.rank(), i))
+        .collect();
+    let mut steps = Vec::with_capacity(modules.len());
+    while let Some((rank, i)) = ready.iter().next().copied() {
+        ready.remove(&(rank, i));
+        for &next in &dependents[i] {
+            pending[next] -= 1;
+            if pending[next] == 0 {
+                ready.insert((modules[next].target.rank(), next));
+            }
+        }
+        steps.push(MountStep {
+            module: modules[i].clone(),
+            after: after[i]
+                .iter()
+                .map(|&d| modules[d].id().to_string())
+                .collect(),
+            config: Value::Null,
+        });
+    }
+
+    if steps.len() < modules.len() {
+        let mut modules: Vec<String> = (0..modules.len())
+            .filter(|&i| pending[i] > 0)
+            .map(|i| modules[i].id().to_string())
+            .collect();
+        modules.sort();
+        return Err(ManifestError::DependencyCycle { modules });
+    }
+    Ok(MountPlan { steps })
+}
+
+// ---------------------------------------------------------------------------
+// Applying a plan
+// ---------------------------------------------------------------------------
+
+impl MountPlan {
+    /// Module IDs in mount order.
+    pub fn order(&self) -> Vec<&str> {
+        self.steps.iter().map(|s| s.module.id()).collect()
+    }
+
+    /// Take each step's config from a session mount plan: `orchestrator.config`
+    /// and `context.config` for the modules named in `session`, and the
+    /// `config` of the `providers`, `tools` or `hooks` entry whose `module`
+    /// is the step's ID. Steps the plan does not mention keep theirs.
+    pub fn with_session_config(mut self, config: &HashMap<String, Value>) -> Self {
+        for step in &mut self.steps {
+            if let Some(module_config) = session_module_config(config, &step.module) {
+                step.config = module_config.clone();
+            }
+        }
+        self
+    }
+
+    /// Load each module with `load` and mount it on `coordinator`, in order.
+    ///
+    /// Tools and providers are mounted under their own `name()`; hook
+    /// handlers are registered for the manifest's `events`. Stops at the
+    /// first failure, leaving earlier modules mounted.
+    ///
+    /// # Errors
+    ///
+    /// [`ManifestError::Load`] when `load` fails,
+    /// [`ManifestError::TypeMismatch`] when it returns a different module
+    /// kind than declared, and [`ManifestError::NotMountable`] for delegated
+    /// modules (which a Rust host cannot mount), resolver modules, and tool or
+    /// provider names that are already taken.
+    pub fn apply<F, E>(&self, coordinator: &Coordinator, mut load: F) -> Result<(), ManifestError>
+    where
+        F: FnMut(&MountStep) -> Result<LoadedModule, E>,
+        E: std::fmt::Display,
+    {
+        for step in &self.steps {
+            let module = &step.module;
+            let id = module.id().to_string();
+            let loaded = load(step).map_err(|e| ManifestError::Load {
+                module: id.clone(),
+                reason: e.to_string(),
+            })?;
+            let mismatch = |loaded: &LoadedModule| ManifestError::TypeMismatch {
+                module: id.clone(),
+                expected: module.info.module_type.clone(),
+                loaded: loaded.variant_name(),
+            };
+            let not_mountable = |reason: String| ManifestError::NotMountable {
+                module: id.clone(),
+                reason,
+            };
+
+            match (&module.info.module_type, loaded) {
+                (ModuleType::Orchestrator, LoadedModule::Orchestrator(o)) => {
+                    coordinator.set_orchestrator(o)
+                }
+                (ModuleType::Context, LoadedModule::Context(c)) => coordinator.set_context(c),
+                (ModuleType::Approval, LoadedModule::Approval(a)) => {
+                    coordinator.set_approval_provider(a)
+                }
+                (ModuleType::Provider, LoadedModule::Provider(p)) => {
+                    let name = p.name().to_string();
+                    if coordinator.get_provider(&name).is_some() {
+                        return Err(not_mountable(format!(
+                            "provider '{name}' is already mounted"
+                        )));
+                    }
+                    coordinator.mount_provider(&name, p);
+                }
+                (ModuleType::Tool, LoadedModule::Tool(t)) => {
+                    let name = t.name().to_string();
+                    if coordinator.get_tool(&name).is_some() {
+                        return Err(not_mountable(format!("tool '{name}' is already mounted")));
+                    }
+                    coordinator.mount_tool(&name, t);
+                }
+                (ModuleType::Hook, LoadedModule::Hook(handler)) => {
+                    for event in &module.events {
+                        let _ = coordinator.hooks().register(
+                            event,
+                            handler.clone(),
+                            0,
+                            Some(id.clone()),
+                        );
+                    }
+                }
+                (_, loaded @ LoadedModule::PythonDelegated { .. })
+                | (_, loaded @ LoadedModule::RustDelegated { .. }) => {
+                    return Err(not_mountable(format!(
+                        "{} modules must be loaded by the host",
+                        loaded.variant_name()
+                    )));
+                }
+                (ModuleType::Resolver, _) => {
+                    return Err(not_mountable(
+                        "the coordinator has no Rust slot for module-source resolvers".into(),
+                    ));
+                }
+                (_, loaded) => return Err(mismatch(&loaded)),
+            }
+            let requires: Vec<&str> = module.requires.iter().map(String::as_str).collect();
+            coordinator.require_capabilities(&id, &requires);
+        }
+        Ok(())
+    }
+}
+
+/// The config a session mount plan gives `module`, if any.
+fn session_module_config<'a>(
+    config: &'a HashMap<String, Value>,
+    module: &ModuleDescriptor,
+) -> Option<&'a Value> {
+    let id = module.id();
+    match module.target {
+        MountTarget::Point(point @ (MountPoint::Orchestrator | MountPoint::Context)) => {
+            let key = point.as_str();
+            let named = config.get("session").and_then(|s| s.get(key))?;
+            (named.as_str() == Some(id))
+                .then(|| config.get(key).and_then(|section| section.get("config")))
+                .flatten()
+        }
+        MountTarget::Point(
+            point @ (MountPoint::Providers | MountPoint::Tools | MountPoint::Hooks),
+        ) => config
+            .get(point.as_str())?
+            .as_array()?
+            .iter()
+            .find(|entry| entry.get("module").and_then(Value::as_str) == Some(id))?
+            .get("config"),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ModuleFactory
+// ---------------------------------------------------------------------------
+
+/// Creates module instances for [`Coordinator::apply_mount_plan`].
+///
+/// `create` receives each [`MountStep`] in mount order, with its config, and
+/// may do I/O (connect a client, read credentials) before returning the
+/// module. The returned module's `init` still runs before it is mounted.
+pub trait ModuleFactory: Send + Sync {
+    /// Build the module for `step`.
+    fn create<'a>(
+        &'a self,
+        step: &'a MountStep,
+    ) -> BoxFuture<'a, Result<LoadedModule, Box<dyn std::error::Error + Send + Sync>>>;
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::testing::{FakeContextManager, FakeOrchestrator, FakeProvider, FakeTool};
+
+    fn module(id: &str, module_type: &str, deps: &[&str]) -> ModuleDescriptor {
+        let events = if module_type == "hook" {
+            ", \"events\": [\"tool:pre\"]"
+        } else {
+            ""
+        };
+        ModuleDescriptor::from_json(
+            &format!(
+                r#"{{"module": {{"id": "{id}", "type": "{module_type}", "depends_on": {}{events}}}}}"#,
+                serde_json::json!(deps)
+            ),
+            id,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn toml_manifest_fills_defaults() {
+        let m = ModuleDescriptor::from_toml(
+            r#"
+            [module]
+            id = "tool-search"
+            type = "tool"
+            transport = "python"
+            depends_on = ["provider-embeddings"]
+            "#,
+            "amplifier.toml",
+        )
+        .unwrap();
+        assert_eq!(m.info.name, "tool-search");
+        assert_eq!(m.info.version, "0.0.0");
+        assert_eq!(m.info.mount_point, "tools");
+        assert_eq!(m.target, MountTarget::Point(MountPoint::Tools));
+        assert_eq!(m.depends_on, vec!["provider-embeddings"]);
+    }
+
+    #[test]
+    fn invalid_manifests_are_rejected() {
+        let cases = [
+            r#"{"module": {"id": "x", "type": "tool", "mount_point": "tool"}}"#,
+            r#"{"module": {"id": "x", "type": "tool", "mount_point": "providers"}}"#,
+            r#"{"module": {"id": "x", "type": "hook"}}"#,
+            r#"{"module": {"id": "x", "type": "tool", "depends_on": ["x"]}}"#,
+            r#"{"module": {"id": "", "type": "tool"}}"#,
+            r#"{"module": {"id": "x", "type": "widget"}}"#,
+        ];
+        for case in cases {
+            assert!(
+                matches!(
+                    ModuleDescriptor::from_json(case, "test"),
+                    Err(ManifestError::Parse { .. })
+                ),
+                "{case}"
+            );
+        }
+    }
+
+    #[test]
+    fn plan_orders_by_dependency_then_mount_point() {
+        let plan = resolve(&[
+            module("hooks-log", "hook", &[]),
+            module("tool-search", "tool", &["provider-embed"]),
+            module("provider-embed", "provider", &[]),
+            module("loop", "orchestrator", &["hooks-log"]),
+            module("ctx", "context", &[]),
+        ])
+        .unwrap();
+        assert_eq!(
+            plan.order(),
+            vec!["ctx", "provider-embed", "tool-search", "hooks-log", "loop"]
+        );
+        assert_eq!(plan.steps[2].after, vec!["provider-embed"]);
+    }
+
+    #[test]
+    fn optional_dependencies_only_order_when_present() {
+        let mut tool = module("tool-a", "tool", &[]);
+        tool.optional_depends_on = vec!["tool-b".into(), "absent".into()];
+        let plan = resolve(&[tool, module("tool-b", "tool", &[])]).unwrap();
+        assert_eq!(plan.order(), vec!["tool-b", "tool-a"]);
+    }
+
+    #[test]
+    fn capability_providers_mount_before_requirers() {
+        let mut search = module("tool-search", "tool", &[]);
+        search.requires = vec!["vector-store".into(), "workspace-root".into()];
+        let mut store = module("tool-store", "tool", &[]);
+        store.provides = vec!["vector-store".into()];
+
+        let plan = resolve_with_capabilities(&[search.clone(), store.clone()], &["workspace-root"])
+            .unwrap();
+        assert_eq!(plan.order(), vec!["tool-store", "tool-search"]);
+        assert_eq!(plan.steps[1].after, vec!["tool-store"]);
+
+        assert!(matches!(
+            resolve(&[search.clone(), store.clone()]),
+            Err(ManifestError::MissingCapability { module, capability })
+                if module == "tool-search" && capability == "workspace-root"
+        ));
+        store.requires = vec!["search-index".into()];
+        search.provides = vec!["search-index".into()];
+        assert!(matches!(
+            resolve_with_capabilities(&[search, store], &["workspace-root"]),
+            Err(ManifestError::DependencyCycle { modules })
+                if modules == vec!["tool-search", "tool-store"]
+        ));
+    }
+
+    #[test]
+    fn resolution_errors() {
+        assert!(matches!(
+            resolve(&[module("a", "tool", &[]), module("a", "tool", &[])]),
+            Err(ManifestError::DuplicateModule { id }) if id == "a"
+        ));
+        assert!(matches!(
+            resolve(&[module("a", "tool", &["b"])]),
+            Err(ManifestError::MissingDependency { dependency, .. }) if dependency == "b"
+        ));
+        assert!(matches!(
+            resolve(&[module("loop-a", "orchestrator", &[]), module("loop-b", "orchestrator", &[])]),
+            Err(ManifestError::MountConflict { mount_point, modules })
+                if mount_point == "orchestrator" && modules == vec!["loop-a", "loop-b"]
+        ));
+        assert!(matches!(
+            resolve(&[
+                module("a", "tool", &["b"]),
+                module("b", "tool", &["c"]),
+                module("c", "tool", &["a"]),
+                module("d", "tool", &[]),
+            ]),
+            Err(ManifestError::DependencyCycle { modules }) if modules == vec!["a", "b", "c"]
+        ));
+    }
+
+    #[test]
+    fn apply_mounts_modules_on_the_coordinator() {
+        let plan = resolve(&[
+            module("loop", "orchestrator", &[]),
+            module("ctx", "context", &[]),
+            module("provider-mock", "provider", &[]),
+            module("tool-echo", "tool", &["provider-mock"]),
+        ])
+        .unwrap();
+        let coordinator = Coordinator::new_for_test();
+        plan.apply(&coordinator, |step| {
+            Ok::<_, String>(match step.module.id() {
+                "loop" => LoadedModule::Orchestrator(Arc::new(FakeOrchestrator::new("done"))),
+                "ctx" => LoadedModule::Context(Arc::new(FakeContextManager::new())),
+                "provider-mock" => {
+                    LoadedModule::Provider(Arc::new(FakeProvider::new("mock", "hi")))
+                }
+                _ => LoadedModule::Tool(Arc::new(FakeTool::new("echo", "echoes"))),
+            })
+        })
+        .unwrap();
+        assert!(coordinator.has_orchestrator());
+        assert!(coordinator.has_context());
+        assert_eq!(coordinator.provider_names(), vec!["mock"]);
+        assert_eq!(coordinator.tool_names(), vec!["echo"]);
+    }
+
+    #[test]
+    fn with_session_config_assigns_module_configs() {
+        let plan = resolve(&[
+            module("loop", "orchestrator", &[]),
+            module("provider-mock", "provider", &[]),
+            module("tool-echo", "tool", &[]),
+        ])
+        .unwrap();
+        let config: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "session": {"orchestrator": "loop", "context": "ctx"},
+            "orchestrator": {"config": {"max_steps": 3}},
+            "providers": [{"module": "provider-mock", "config": {"model": "m"}}],
+            "tools": [{"module": "tool-other", "config": {}}],
+        }))
+        .unwrap();
+        let plan = plan.with_session_config(&config);
+        let configs: Vec<&Value> = plan.steps.iter().map(|s| &s.config).collect();
+        assert_eq!(
+            configs,
+            vec![
+                &serde_json::json!({"max_steps": 3}),
+                &serde_json::json!({"model": "m"}),
+                &Value::Null,
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_records_capability_requirements() {
+        let mut tool = module("tool-search", "tool", &[]);
+        tool.requires = vec!["vector-store".into()];
+        let plan = resolve_with_capabilities(&[tool], &["vector-store"]).unwrap();
+        let coordinator = Coordinator::new_for_test();
+        plan.apply(&coordinator, |_| {
+            Ok::<_, String>(LoadedModule::Tool(Arc::new(FakeTool::new(
+                "search", "finds",
+            ))))
+        })
+        .unwrap();
+
+        assert_eq!(
+            coordinator.capability_requirements()["tool-search"],
+            vec!["vector-store"]
+        );
+        assert!(coordinator.check_capabilities().is_err());
+        coordinator.register_capability("vector-store", serde_json::json!({}));
+        assert!(coordinator.check_capabilities().is_ok());
+    }
+
+    #[test]
+    fn apply_rejects_mismatched_and_delegated_modules() {
+        let plan = resolve(&[module("tool-echo", "tool", &[])]).unwrap();
+        let coordinator = Coordinator::new_for_test();
+        let err = plan
+            .apply(&coordinator, |_| {
+                Ok::<_, String>(LoadedModule::Context(Arc::new(FakeContextManager::new())))
+            })
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ManifestError::TypeMismatch {
+                loaded: "Context",
+                ..
+            }
+        ));
+
+        let err = plan
+            .apply(&coordinator, |_| {
+                Ok::<_, String>(LoadedModule::PythonDelegated {
+                    package_name: "amplifier_module_tool_echo".into(),
+                })
+            })
+            .unwrap_err();
+        assert!(matches!(err, ManifestError::NotMountable { .. }));
+
+        let err = plan
+            .apply(&coordinator, |_| Err("no such module"))
+            .unwrap_err();
+        assert!(matches!(err, ManifestError::Load { reason, .. } if reason == "no such module"));
+    }
+}
@@ -0,0 +1,469 @@
+//! Memory accounting for kernel-held in-memory buffers.
+//!
+//! Every buffer the kernel keeps in memory charges its bytes to a shared
+//! [`MemoryAccountant`] under a category name. When a ceiling is configured,
+//! a [`BoundedBuffer`] that would exceed it applies the
+//! [`EvictionPolicy`]: drop its own oldest entries, or reject the new one.
+//! Keyed stores, which have no oldest entry to drop, charge through
+//! [`MemoryAccountant::try_charge`] and reject what does not fit.
+//!
+//! | Category             | Buffer                                         | Over the ceiling |
+//! |----------------------|------------------------------------------------|------------------|
+//! | `conversation_store` | [`InMemoryConversationStore`] history          | policy; evictions emit [`KERNEL_MEMORY_EVICTED`] |
+//! | `hook_replay`        | [`HookRegistry`] replay buffer (event history) | policy           |
+//! | `audit`              | [`AuditLog`] records (journal)                 | policy           |
+//! | `attachments`        | [`InMemoryAttachmentBackend`] (blob store)     | rejected         |
+//! | `approval_memory`    | [`ApprovalMemory`] decisions (decision log)    | rejected         |
+//!
+//! [`Session::new`](crate::session::Session::new) charges all but the
+//! conversation store, which hosts create, to the coordinator's accountant.
+//!
+//! [`InMemoryConversationStore`]: crate::conversation_store::InMemoryConversationStore
+//! [`KERNEL_MEMORY_EVICTED`]: crate::events::KERNEL_MEMORY_EVICTED
+//! [`HookRegistry`]: crate::hooks::HookRegistry
+//! [`AuditLog`]: crate::audit::AuditLog
+//! [`InMemoryAttachmentBackend`]: crate::attachments::InMemoryAttachmentBackend
+//! [`ApprovalMemory`]: crate::approval_memory::ApprovalMemory
+//!
+//! # Configuration
+//!
+//! Read from the session config by
+//! [`Coordinator::new`](crate::coordinator::Coordinator::new):
+//!
+//! ```json
+//! {"session": {"memory": {"ceiling_bytes": 8388608, "policy": "drop_oldest", "pressure_ratio": 0.9}}}
+//! ```
+//!
+//! No `memory` section means unbounded (accounting only).
+//!
+//! # Pressure
+//!
+//! Crossing `pressure_ratio × ceiling` (or any eviction/rejection) arms a
+//! one-shot pressure flag. The session drains it at the end of each turn via
+//! [`Coordinator::emit_memory_pressure`](crate::coordinator::Coordinator::emit_memory_pressure),
+//! which emits [`KERNEL_MEMORY_PRESSURE`](crate::events::KERNEL_MEMORY_PRESSURE)
+//! with a [`MemoryUsage`] snapshot.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// What a bounded buffer does when a push would exceed the ceiling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Evict the buffer's own oldest entries until the new one fits.
+    #[default]
+    DropOldest,
+    /// Keep existing entries and reject the new one.
+    RejectNew,
+}
+
+/// Memory ceiling and eviction settings (`session.memory` in the config).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryConfig {
+    /// Maximum bytes across all accounted buffers. `None` = unbounded.
+    #[serde(default)]
+    pub ceiling_bytes: Option<usize>,
+    #[serde(default)]
+    pub policy: EvictionPolicy,
+    /// Fraction of the ceiling at which pressure is signalled.
+    #[serde(default = "default_pressure_ratio")]
+    pub pressure_ratio: f64,
+}
+
+fn default_pressure_ratio() -> f64 {
+    0.9
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        Self {
+            ceiling_bytes: None,
+            policy: EvictionPolicy::default(),
+            pressure_ratio: default_pressure_ratio(),
+        }
+    }
+}
+
+impl MemoryConfig {
+    /// Extract `session.memory` from a mount plan, falling back to the default
+    /// (unbounded) when absent or malformed.
+    pub fn from_session_config(config: &std::collections::HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("memory")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.memory config: {e}");
+            Self::default()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// MemoryAccountant
+// ---------------------------------------------------------------------------
+
+/// Snapshot of accounted memory, as reported in metrics and pressure events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub used_bytes: usize,
+    pub ceiling_bytes: Option<usize>,
+    pub by_category: BTreeMap<String, usize>,
+    /// Entries evicted under [`EvictionPolicy::DropOldest`] since creation.
+    pub evictions: u64,
+    /// Entries rejected (policy or oversized) since creation.
+    pub rejections: u64,
+}
+
+/// Process- or session-wide byte accounting shared by all bounded buffers.
+#[derive(Debug)]
+pub struct MemoryAccountant {
+    config: MemoryConfig,
+    by_category: Mutex<BTreeMap<String, usize>>,
+    evictions: AtomicU64,
+    rejections: AtomicU64,
+    pressure_pending: AtomicBool,
+}
+
+impl Default for MemoryAccountant {
+    fn default() -> Self {
+        Self::new(MemoryConfig::default())
+    }
+}
+
+impl MemoryAccountant {
+    /// Create an accountant with the given ceiling and policy.
+    pub fn new(config: MemoryConfig) -> Self {
+        Self {
+            config,
+            by_category: Mutex::new(BTreeMap::new()),
+            evictions: AtomicU64::new(0),
+            rejections: AtomicU64::new(0),
+            pressure_pending: AtomicBool::new(false),
+        }
+    }
+
+    /// The configuration this accountant enforces.
+    pub fn config(&self) -> &MemoryConfig {
+        &self.config
+    }
+
+    /// Total bytes currently charged.
+    pub fn used_bytes(&self) -> usize {
+        self.by_category.lock().unwrap().values().sum()
+    }
+
+    /// A point-in-time snapshot of usage.
+    pub fn usage(&self) -> MemoryUsage {
+        let by_category = self.by_category.lock().unwrap().clone();
+        MemoryUsage {
+            used_bytes: by_category.values().sum(),
+            ceiling_bytes: self.config.ceiling_bytes,
+            by_category,
+            evictions: self.evictions.load(Ordering::Relaxed),
+            rejections: self.rejections.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Charge `bytes` to `category` if they fit under the ceiling. The check
+    /// and the charge happen under one lock, so concurrent charges cannot
+    /// together overshoot.
+    fn reserve(&self, category: &str, bytes: usize) -> bool {
+        let used = {
+            let mut map = self.by_category.lock().unwrap();
+            let used = map.values().sum::<usize>() + bytes;
+            if self
+                .config
+                .ceiling_bytes
+                .is_some_and(|ceiling| used > ceiling)
+            {
+                return false;
+            }
+            *map.entry(category.to_string()).or_default() += bytes;
+            used
+        };
+        if let Some(ceiling) = self.config.ceiling_bytes {
+            if used as f64 >= ceiling as f64 * self.config.pressure_ratio {
+                self.pressure_pending.store(true, Ordering::Release);
+            }
+        }
+        true
+    }
+
+    /// Charge `bytes` to `category` for an entry that cannot be evicted to
+    /// make room. Returns `false`, counting a rejection, if it would exceed
+    /// the ceiling; nothing is charged then.
+    pub fn try_charge(&self, category: &str, bytes: usize) -> bool {
+        if self.reserve(category, bytes) {
+            return true;
+        }
+        self.record_rejection();
+        false
+    }
+
+    /// Release `bytes` charged to `category` with
+    /// [`try_charge`](Self::try_charge).
+    pub fn release(&self, category: &str, bytes: usize) {
+        let mut map = self.by_category.lock().unwrap();
+        if let Some(used) = map.get_mut(category) {
+            *used = used.saturating_sub(bytes);
+        }
+    }
+
+    fn record_eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+        self.pressure_pending.store(true, Ordering::Release);
+    }
+
+    fn record_rejection(&self) {
+        self.rejections.fetch_add(1, Ordering::Relaxed);
+        self.pressure_pending.store(true, Ordering::Release);
+    }
+
+    /// Consume the pressure flag: returns `true` at most once per episode.
+    pub fn take_pressure(&self) -> bool {
+        self.pressure_pending.swap(false, Ordering::AcqRel)
+    }
+}
+
+/// Approximate in-memory size of a JSON value (its serialized length).
+pub fn json_size(value: &Value) -> usize {
+    serde_json::to_vec(value).map_or(0, |v| v.len())
+}
+
+// ---------------------------------------------------------------------------
+// BoundedBuffer
+// ---------------------------------------------------------------------------
+
+/// Result of [`BoundedBuffer::push`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    /// Stored; `evicted` older entries were dropped to make room.
+    Stored { evicted: usize },
+    /// Not stored (policy is `RejectNew`, or the entry alone exceeds the ceiling).
+    Rejected,
+}
+
+/// A FIFO buffer whose entries are charged to a [`MemoryAccountant`].
+///
+/// Entries are released from the accountant when evicted, drained, or when
+/// the buffer is dropped.
+#[derive(Debug)]
+pub struct BoundedBuffer<T> {
+    category: String,
+    accountant: Arc<MemoryAccountant>,
+    entries: VecDeque<(T, usize)>,
+}
+
+impl<T> BoundedBuffer<T> {
+    /// An empty buffer charging to `category`.
+    pub fn new(category: impl Into<String>, accountant: Arc<MemoryAccountant>) -> Self {
+        Self {
+            category: category.into(),
+            accountant,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Append `item`, charging `size` bytes, applying the eviction policy if
+    /// the ceiling would be exceeded.
+    pub fn push(&mut self, item: T, size: usize) -> PushOutcome {
+        let too_big = self
+            .accountant
+            .config
+            .ceiling_bytes
+            .is_some_and(|c| size > c);
+        if too_big {
+            self.accountant.record_rejection();
+            return PushOutcome::Rejected;
+        }
+
+        let mut evicted = 0;
+        while !self.accountant.reserve(&self.category, size) {
+            if self.accountant.config.policy == EvictionPolicy::RejectNew {
+                self.accountant.record_rejection();
+                return PushOutcome::Rejected;
+            }
+            match self.entries.pop_front() {
+                Some((_, bytes)) => {
+                    self.accountant.release(&self.category, bytes);
+                    self.accountant.record_eviction();
+                    evicted += 1;
+                }
+                // Other buffers hold the memory; nothing of ours left to drop.
+                None => {
+                    self.accountant.record_rejection();
+                    return PushOutcome::Rejected;
+                }
+            }
+        }
+
+        self.entries.push_back((item, size));
+        PushOutcome::Stored { evicted }
+    }
+
+    /// Number of entries held.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate entries oldest-first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.entries.iter().map(|(item, _)| item)
+    }
+
+    /// Remove and return the oldest entry, releasing its bytes.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let (item, bytes) = self.entries.pop_front()?;
+        self.accountant.release(&self.category, bytes);
+        Some(item)
+    }
+
+    /// Remove and return all entries, releasing their bytes.
+    pub fn drain(&mut self) -> Vec<T> {
+        let bytes: usize = self.entries.iter().map(|(_, b)| b).sum();
+        self.accountant.release(&self.category, bytes);
+        self.entries.drain(..).map(|(item, _)| item).collect()
+    }
+}
+
+impl<T> Drop for BoundedBuffer<T> {
+    fn drop(&mut self) {
+        let bytes: usize = self.entries.iter().map(|(_, b)| b).sum();
+        self.accountant.release(&self.category, bytes);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accountant(ceiling: usize, policy: EvictionPolicy) -> Arc<MemoryAccountant> {
+        Arc::new(MemoryAccountant::new(MemoryConfig {
+            ceiling_bytes: Some(ceiling),
+            policy,
+            pressure_ratio: 0.9,
+        }))
+    }
+
+    #[test]
+    fn unbounded_accountant_only_counts() {
+        let acct = Arc::new(MemoryAccountant::default());
+        let mut buf = BoundedBuffer::new("events", acct.clone());
+        for n in 0..100 {
+            assert_eq!(buf.push(n, 1_000), PushOutcome::Stored { evicted: 0 });
+        }
+        assert_eq!(acct.used_bytes(), 100_000);
+        assert!(!acct.take_pressure());
+    }
+
+    #[test]
+    fn drop_oldest_evicts_to_make_room() {
+        let acct = accountant(100, EvictionPolicy::DropOldest);
+        let mut buf = BoundedBuffer::new("events", acct.clone());
+        buf.push(1, 40);
+        buf.push(2, 40);
+        assert_eq!(buf.push(3, 40), PushOutcome::Stored { evicted: 1 });
+        assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(acct.used_bytes(), 80);
+        assert_eq!(acct.usage().evictions, 1);
+        assert!(acct.take_pressure());
+        assert!(!acct.take_pressure(), "pressure is one-shot");
+    }
+
+    #[test]
+    fn reject_new_keeps_existing_entries() {
+        let acct = accountant(100, EvictionPolicy::RejectNew);
+        let mut buf = BoundedBuffer::new("events", acct.clone());
+        buf.push(1, 60);
+        assert_eq!(buf.push(2, 60), PushOutcome::Rejected);
+        assert_eq!(buf.len(), 1);
+        assert_eq!(acct.usage().rejections, 1);
+    }
+
+    #[test]
+    fn oversized_entry_is_rejected() {
+        let acct = accountant(10, EvictionPolicy::DropOldest);
+        let mut buf = BoundedBuffer::new("blobs", acct.clone());
+        assert_eq!(buf.push((), 11), PushOutcome::Rejected);
+        assert_eq!(acct.used_bytes(), 0);
+    }
+
+    #[test]
+    fn categories_share_the_ceiling_and_release_on_drop() {
+        let acct = accountant(100, EvictionPolicy::DropOldest);
+        let mut a = BoundedBuffer::new("a", acct.clone());
+        a.push((), 80);
+        {
+            let mut b = BoundedBuffer::new("b", acct.clone());
+            // b has nothing of its own to evict, so it cannot take a's memory.
+            assert_eq!(b.push((), 30), PushOutcome::Rejected);
+            b.push((), 20);
+            assert_eq!(acct.usage().by_category["b"], 20);
+        }
+        assert_eq!(acct.used_bytes(), 80);
+        a.drain();
+        assert_eq!(acct.used_bytes(), 0);
+    }
+
+    #[test]
+    fn keyed_charges_are_rejected_over_the_ceiling() {
+        let acct = accountant(100, EvictionPolicy::DropOldest);
+        assert!(acct.try_charge("attachments", 70));
+        assert!(!acct.try_charge("approval_memory", 40));
+        assert_eq!(acct.usage().rejections, 1);
+        acct.release("attachments", 70);
+        assert!(acct.try_charge("approval_memory", 40));
+        assert_eq!(acct.used_bytes(), 40);
+    }
+
+    #[test]
+    fn concurrent_charges_never_overshoot_the_ceiling() {
+        let acct = accountant(1_000, EvictionPolicy::RejectNew);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        acct.try_charge("blobs", 7);
+                    }
+                });
+            }
+        });
+        assert!(acct.used_bytes() <= 1_000);
+        assert_eq!(acct.used_bytes(), 7 * (1_000 / 7));
+    }
+
+    #[test]
+    fn config_parses_from_session_section() {
+        let mut plan = std::collections::HashMap::new();
+        plan.insert(
+            "session".to_string(),
+            serde_json::json!({"memory": {"ceiling_bytes": 1024, "policy": "reject_new"}}),
+        );
+        let cfg = MemoryConfig::from_session_config(&plan);
+        assert_eq!(cfg.ceiling_bytes, Some(1024));
+        assert_eq!(cfg.policy, EvictionPolicy::RejectNew);
+        assert_eq!(cfg.pressure_ratio, 0.9);
+        assert_eq!(
+            MemoryConfig::from_session_config(&Default::default()),
+            MemoryConfig::default()
+        );
+    }
+}
@@ -13,6 +13,9 @@
 //! - All structs whose Python counterpart has `extra="allow"` carry
 //!   `#[serde(flatten)] pub extensions: HashMap<String, Value>` to
 //!   preserve unknown fields through round-trips.
+//! - Prompt caching is expressed with [`CacheHint`] on a [`Message`] or a
+//!   cacheable [`ContentBlock`]; dialects translate hints into their
+//!   provider's cache-control fields (see [`crate::dialect`]).
 
 use std::collections::HashMap;
 
@@ -45,6 +48,18 @@ pub enum Visibility {
     User,
 }
 
+/// Prompt-caching breakpoint: the request prefix up to and including the
+/// marked message or block may be cached by the provider.
+///
+/// Providers that cache automatically ignore hints; cache hits are reported
+/// in [`Usage::cache_read_tokens`] and [`Usage::cache_write_tokens`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheHint {
+    /// Cache for the provider's default (short) lifetime.
+    Ephemeral,
+}
+
 // ---- ContentBlock tagged union ----
 
 /// Content block discriminated union.
@@ -54,7 +69,15 @@ pub enum Visibility {
 ///
 /// Each variant corresponds to a Pydantic model in `message_models.py`:
 /// `TextBlock`, `ThinkingBlock`, `RedactedThinkingBlock`, `ToolCallBlock`,
-/// `ToolResultBlock`, `ImageBlock`, `ReasoningBlock`.
+/// `ToolResultBlock`, `ImageBlock`, `ReasoningBlock`, `AudioBlock`,
+/// `DocumentBlock`.
+///
+/// Media blocks (`Image`, `Audio`, `Document`) carry a `source` map in the
+/// Anthropic style: `{"type": "base64", "media_type": ..., "data": ...}` or
+/// `{"type": "url", "url": ...}`.
+///
+/// Every variant except the thinking and reasoning ones can carry a
+/// [`CacheHint`] in `cache`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ContentBlock {
@@ -63,6 +86,8 @@ pub enum ContentBlock {
         text: String,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheHint>,
         #[serde(flatten)]
         extensions: HashMap<String, Value>,
     },
@@ -93,6 +118,8 @@ pub enum ContentBlock {
         input: HashMap<String, Value>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheHint>,
         #[serde(flatten)]
         extensions: HashMap<String, Value>,
     },
@@ -102,6 +129,8 @@ pub enum ContentBlock {
         output: Value,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheHint>,
         #[serde(flatten)]
         extensions: HashMap<String, Value>,
     },
@@ -110,6 +139,8 @@ pub enum ContentBlock {
         source: HashMap<String, Value>,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheHint>,
         #[serde(flatten)]
         extensions: HashMap<String, Value>,
     },
@@ -122,6 +153,93 @@ pub enum ContentBlock {
         #[serde(flatten)]
         extensions: HashMap<String, Value>,
     },
+    #[serde(rename = "audio")]
+    Audio {
+        source: HashMap<String, Value>,
+        /// Text of the recording, when known (for providers without audio
+        /// input, and for display).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        transcript: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheHint>,
+        #[serde(flatten)]
+        extensions: HashMap<String, Value>,
+    },
+    #[serde(rename = "document")]
+    Document {
+        source: HashMap<String, Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Text extracted from the document, when known.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        transcript: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        visibility: Option<Visibility>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache: Option<CacheHint>,
+        #[serde(flatten)]
+        extensions: HashMap<String, Value>,
+    },
+}
+
+impl ContentBlock {
+    /// The block's visibility, if set.
+    pub fn visibility(&self) -> Option<&Visibility> {
+        match self {
+            ContentBlock::Text { visibility, .. }
+            | ContentBlock::Thinking { visibility, .. }
+            | ContentBlock::RedactedThinking { visibility, .. }
+            | ContentBlock::ToolCall { visibility, .. }
+            | ContentBlock::ToolResult { visibility, .. }
+            | ContentBlock::Image { visibility, .. }
+            | ContentBlock::Reasoning { visibility, .. }
+            | ContentBlock::Audio { visibility, .. }
+            | ContentBlock::Document { visibility, .. } => visibility.as_ref(),
+        }
+    }
+
+    /// The block's cache hint, if set. Always `None` for thinking and
+    /// reasoning blocks.
+    pub fn cache(&self) -> Option<CacheHint> {
+        match self {
+            ContentBlock::Text { cache, .. }
+            | ContentBlock::ToolCall { cache, .. }
+            | ContentBlock::ToolResult { cache, .. }
+            | ContentBlock::Image { cache, .. }
+            | ContentBlock::Audio { cache, .. }
+            | ContentBlock::Document { cache, .. } => *cache,
+            ContentBlock::Thinking { .. }
+            | ContentBlock::RedactedThinking { .. }
+            | ContentBlock::Reasoning { .. } => None,
+        }
+    }
+
+    /// `source.media_type` of an image, audio or document block.
+    pub fn media_type(&self) -> Option<&str> {
+        match self {
+            ContentBlock::Image { source, .. }
+            | ContentBlock::Audio { source, .. }
+            | ContentBlock::Document { source, .. } => {
+                source.get("media_type").and_then(Value::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    /// Typed `source` of an image block.
+    ///
+    /// `None` for other blocks and for image sources of a type
+    /// [`ImageSource`](crate::images::ImageSource) does not model.
+    pub fn image_source(
+        &self,
+    ) -> Option<Result<crate::images::ImageSource, crate::images::ImageError>> {
+        let ContentBlock::Image { source, .. } = self else {
+            return None;
+        };
+        crate::images::ImageSource::from_map(source).transpose()
+    }
 }
 
 // ---- Message types ----
@@ -159,6 +277,9 @@ pub struct Message {
     pub tool_call_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, Value>>,
+    /// Cache breakpoint after this message's content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheHint>,
     #[serde(flatten)]
     pub extensions: HashMap<String, Value>,
 }
@@ -174,6 +295,39 @@ pub struct ToolSpec {
     pub extensions: HashMap<String, Value>,
 }
 
+impl ToolSpec {
+    /// This spec as a tool definition for `dialect`, with the name sanitized
+    /// and the description truncated to the API's limits.
+    ///
+    /// Sanitizing can change the name; use
+    /// [`DialectTools`](crate::dialect::DialectTools) to convert several specs
+    /// and map tool calls back.
+    pub fn to_dialect(&self, dialect: crate::dialect::Dialect) -> Value {
+        crate::dialect::DialectTools::new(dialect, [self])
+            .definitions
+            .remove(0)
+    }
+
+    /// Execution timeout from the `timeout` extension, in seconds (the unit
+    /// of `ChatRequest::timeout`). Non-positive values mean no timeout.
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.extensions
+            .get("timeout")
+            .and_then(Value::as_f64)
+            .filter(|secs| *secs > 0.0 && secs.is_finite())
+            .map(std::time::Duration::from_secs_f64)
+    }
+
+    /// Whether the `pure` extension declares that the tool's result depends
+    /// only on its arguments, so it may be cached (see [`crate::tool_cache`]).
+    pub fn is_pure(&self) -> bool {
+        self.extensions
+            .get("pure")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+}
+
 // ---- Response format ----
 
 /// Response format specification.
@@ -275,6 +429,10 @@ pub struct Usage {
     pub cache_read_tokens: Option<i64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cache_write_tokens: Option<i64>,
+    /// Cost in USD as a decimal string (`"0.047"`), matching Python's
+    /// `Decimal` serializer. `None` means rate data is unavailable, not zero.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<String>,
     #[serde(flatten)]
     pub extensions: HashMap<String, Value>,
 }
@@ -381,6 +539,7 @@ mod tests {
         let block = ContentBlock::Text {
             text: "hello".into(),
             visibility: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&block).unwrap();
@@ -404,6 +563,7 @@ mod tests {
             ContentBlock::Text {
                 text: "hello".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }
         );
@@ -414,6 +574,7 @@ mod tests {
         let block = ContentBlock::Text {
             text: "hi".into(),
             visibility: Some(Visibility::User),
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&block).unwrap();
@@ -461,6 +622,7 @@ mod tests {
             name: "read_file".into(),
             input,
             visibility: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&block).unwrap();
@@ -478,6 +640,7 @@ mod tests {
             tool_call_id: "call_123".into(),
             output: json!("file contents"),
             visibility: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&block).unwrap();
@@ -495,6 +658,7 @@ mod tests {
         let block = ContentBlock::Image {
             source,
             visibility: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&block).unwrap();
@@ -518,6 +682,49 @@ mod tests {
         assert_eq!(deserialized, block);
     }
 
+    #[test]
+    fn content_block_audio_round_trip() {
+        let json = json!({
+            "type": "audio",
+            "source": {"type": "base64", "media_type": "audio/wav", "data": "UklGRg=="},
+            "transcript": "turn left at the lights",
+            "visibility": "user"
+        });
+        let block: ContentBlock = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(block.media_type(), Some("audio/wav"));
+        assert_eq!(block.visibility(), Some(&Visibility::User));
+        if let ContentBlock::Audio { transcript, .. } = &block {
+            assert_eq!(transcript.as_deref(), Some("turn left at the lights"));
+        } else {
+            panic!("Expected Audio variant");
+        }
+        assert_eq!(serde_json::to_value(&block).unwrap(), json);
+    }
+
+    #[test]
+    fn content_block_document_round_trip() {
+        let block = ContentBlock::Document {
+            source: HashMap::from([
+                ("type".to_string(), json!("url")),
+                ("media_type".to_string(), json!("application/pdf")),
+                ("url".to_string(), json!("https://example.com/report.pdf")),
+            ]),
+            title: Some("Q3 report".into()),
+            transcript: None,
+            visibility: None,
+            cache: None,
+            extensions: HashMap::from([("citations".to_string(), json!({"enabled": true}))]),
+        };
+        let json = serde_json::to_value(&block).unwrap();
+        assert_eq!(json["type"], "document");
+        assert_eq!(json["citations"]["enabled"], true);
+        assert!(json.get("transcript").is_none());
+        let deserialized: ContentBlock = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, block);
+        assert_eq!(deserialized.media_type(), Some("application/pdf"));
+        assert_eq!(deserialized.visibility(), None);
+    }
+
     #[test]
     fn content_block_extensions_preserved() {
         let json = json!({
@@ -557,6 +764,7 @@ mod tests {
         let content = MessageContent::Blocks(vec![ContentBlock::Text {
             text: "hello".into(),
             visibility: None,
+            cache: None,
             extensions: HashMap::new(),
         }]);
         let json = serde_json::to_value(&content).unwrap();
@@ -622,6 +830,7 @@ mod tests {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&msg).unwrap();
@@ -638,11 +847,13 @@ mod tests {
             content: MessageContent::Blocks(vec![ContentBlock::Text {
                 text: "thinking...".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }]),
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&msg).unwrap();
@@ -651,6 +862,27 @@ mod tests {
         assert_eq!(json["content"][0]["type"], "text");
     }
 
+    #[test]
+    fn cache_hints_round_trip() {
+        let json = json!({
+            "role": "user",
+            "content": [
+                {"type": "text", "text": "long context", "cache": "ephemeral"},
+                {"type": "text", "text": "question"}
+            ],
+            "cache": "ephemeral"
+        });
+        let msg: Message = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(msg.cache, Some(CacheHint::Ephemeral));
+        assert!(msg.extensions.is_empty());
+        let MessageContent::Blocks(blocks) = &msg.content else {
+            panic!("Expected Blocks variant");
+        };
+        assert_eq!(blocks[0].cache(), Some(CacheHint::Ephemeral));
+        assert_eq!(blocks[1].cache(), None);
+        assert_eq!(serde_json::to_value(&msg).unwrap(), json);
+    }
+
     #[test]
     fn message_round_trip() {
         let json = json!({
@@ -806,6 +1038,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: None,
@@ -841,6 +1074,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tools: Some(vec![ToolSpec {
@@ -936,6 +1170,7 @@ mod tests {
             reasoning_tokens: Some(20),
             cache_read_tokens: None,
             cache_write_tokens: None,
+            cost_usd: None,
             extensions: HashMap::new(),
         };
         let json = serde_json::to_value(&usage).unwrap();
@@ -993,6 +1228,7 @@ mod tests {
             content: vec![ContentBlock::Text {
                 text: "Hello!".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tool_calls: None,
@@ -1003,6 +1239,7 @@ mod tests {
                 reasoning_tokens: None,
                 cache_read_tokens: None,
                 cache_write_tokens: None,
+                cost_usd: None,
                 extensions: HashMap::new(),
             }),
             degradation: None,
@@ -1026,6 +1263,7 @@ mod tests {
             content: vec![ContentBlock::Text {
                 text: "Let me search.".into(),
                 visibility: None,
+                cache: None,
                 extensions: HashMap::new(),
             }],
             tool_calls: Some(vec![ToolCall {
@@ -0,0 +1,176 @@
+//! Cached provider model lists.
+//!
+//! [`Provider::list_models`](crate::traits::Provider::list_models) may query
+//! a remote API, and hosts call it repeatedly to fill model pickers.
+//! [`Coordinator::models`](crate::coordinator::Coordinator::models) answers
+//! from a per-provider [`ModelCatalog`] instead, asking the provider again
+//! only once its entry is older than `ttl_secs`;
+//! [`Coordinator::refresh_models`](crate::coordinator::Coordinator::refresh_models)
+//! refetches immediately.
+//!
+//! Failed listings are not cached. Mounting or unmounting a provider drops
+//! its entry. Entries age on the coordinator's [`Clock`](crate::clock::Clock),
+//! so tests can expire them with a [`ManualClock`](crate::testing::ManualClock).
+//!
+//! Settings come from `session.model_catalog`:
+//!
+//! ```json
+//! {"session": {"model_catalog": {"ttl_secs": 300}}}
+//! ```
+//!
+//! `ttl_secs: 0` disables caching.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::ModelInfo;
+
+// ---------------------------------------------------------------------------
+// ModelCatalogConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.model_catalog` section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelCatalogConfig {
+    /// How long a provider's model list is reused; `0` disables caching.
+    pub ttl_secs: u64,
+}
+
+impl Default for ModelCatalogConfig {
+    fn default() -> Self {
+        Self { ttl_secs: 300 }
+    }
+}
+
+impl ModelCatalogConfig {
+    /// Read `session.model_catalog` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("model_catalog")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.model_catalog config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ModelCatalog
+// ---------------------------------------------------------------------------
+
+struct CachedModels {
+    models: Arc<[ModelInfo]>,
+    fetched_at: Instant,
+}
+
+/// Model lists by provider name, each valid for the configured TTL.
+///
+/// The catalog only stores lists; fetching them is the coordinator's job.
+/// Concurrent misses for the same provider may each fetch, and the last
+/// one stored wins.
+pub struct ModelCatalog {
+    config: ModelCatalogConfig,
+    entries: Mutex<HashMap<String, CachedModels>>,
+}
+
+impl ModelCatalog {
+    pub fn new(config: ModelCatalogConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ModelCatalogConfig {
+        &self.config
+    }
+
+    /// `provider`'s cached list, if one was stored less than the TTL before
+    /// `now`.
+    pub fn get(&self, provider: &str, now: Instant) -> Option<Arc<[ModelInfo]>> {
+        let ttl = Duration::from_secs(self.config.ttl_secs);
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(provider)
+            .filter(|entry| now.saturating_duration_since(entry.fetched_at) < ttl)
+            .map(|entry| Arc::clone(&entry.models))
+    }
+
+    /// Store `provider`'s list as fetched at `now`, returning it shared.
+    pub fn insert(&self, provider: &str, models: Vec<ModelInfo>, now: Instant) -> Arc<[ModelInfo]> {
+        let models: Arc<[ModelInfo]> = models.into();
+        if self.config.ttl_secs > 0 {
+            self.entries.lock().unwrap().insert(
+                provider.to_string(),
+                CachedModels {
+                    models: Arc::clone(&models),
+                    fetched_at: now,
+                },
+            );
+        }
+        models
+    }
+
+    /// Drop `provider`'s entry. Returns `true` if there was one.
+    pub fn invalidate(&self, provider: &str) -> bool {
+        self.entries.lock().unwrap().remove(provider).is_some()
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(id: &str) -> ModelInfo {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "display_name": id,
+            "context_window": 1000,
+            "max_output_tokens": 100
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn entries_expire_after_the_ttl() {
+        let catalog = ModelCatalog::new(ModelCatalogConfig { ttl_secs: 60 });
+        let start = Instant::now();
+        assert!(catalog.get("openai", start).is_none());
+
+        catalog.insert("openai", vec![model("gpt")], start);
+        let cached = catalog
+            .get("openai", start + Duration::from_secs(59))
+            .unwrap();
+        assert_eq!(cached[0].id, "gpt");
+        assert!(catalog
+            .get("openai", start + Duration::from_secs(60))
+            .is_none());
+
+        assert!(catalog.invalidate("openai"));
+        assert!(!catalog.invalidate("openai"));
+    }
+
+    #[test]
+    fn zero_ttl_disables_caching() {
+        let catalog = ModelCatalog::new(ModelCatalogConfig { ttl_secs: 0 });
+        let now = Instant::now();
+        assert_eq!(catalog.insert("openai", vec![model("gpt")], now).len(), 1);
+        assert!(catalog.get("openai", now).is_none());
+    }
+}
@@ -9,6 +9,9 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::attachments::AttachmentRef;
+use crate::messages::ContentBlock;
+
 // ---------------------------------------------------------------------------
 // Enums
 // ---------------------------------------------------------------------------
@@ -46,6 +49,59 @@ pub enum ContextInjectionRole {
     Assistant,
 }
 
+/// How reluctant context compaction is to drop a message.
+///
+/// Stored in a message's `metadata.priority`. Messages are evicted lowest
+/// priority first; `Pinned` messages are never evicted. System and developer
+/// messages are always `Pinned`, whatever their metadata says.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MessagePriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Pinned,
+}
+
+impl MessagePriority {
+    /// `metadata` key holding a message's priority.
+    pub const METADATA_KEY: &'static str = "priority";
+
+    /// The priority of a JSON message as stored by a context manager.
+    pub fn of(message: &Value) -> Self {
+        if matches!(
+            message.get("role").and_then(Value::as_str),
+            Some("system" | "developer")
+        ) {
+            return Self::Pinned;
+        }
+        message
+            .get("metadata")
+            .and_then(|m| m.get(Self::METADATA_KEY))
+            .and_then(|p| serde_json::from_value(p.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Record this priority in `message.metadata`.
+    ///
+    /// Does nothing if `message` is not a JSON object.
+    pub fn apply(self, message: &mut Value) {
+        let Some(object) = message.as_object_mut() else {
+            return;
+        };
+        let metadata = object
+            .entry("metadata")
+            .or_insert_with(|| Value::Object(Default::default()));
+        if !metadata.is_object() {
+            *metadata = Value::Object(Default::default());
+        }
+        metadata[Self::METADATA_KEY] = serde_json::to_value(self).expect("enum serializes");
+    }
+}
+
 /// Default decision on approval timeout or error.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -102,6 +158,33 @@ pub enum SessionState {
     Cancelled,
 }
 
+/// Output format a tool can produce.
+///
+/// Tools advertise the formats they support in their spec (see
+/// [`crate::tool_format`]); the caller's preference travels in
+/// [`ToolContext::output_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolOutputFormat {
+    /// Plain text (`output` is a string).
+    Text,
+    /// Structured JSON (`output` is any non-string JSON value).
+    Json,
+    /// Markdown text (`output` is a string).
+    Markdown,
+}
+
+impl ToolOutputFormat {
+    /// The wire name (`"text"`, `"json"`, `"markdown"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ToolOutputFormat::Text => "text",
+            ToolOutputFormat::Json => "json",
+            ToolOutputFormat::Markdown => "markdown",
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Structs
 // ---------------------------------------------------------------------------
@@ -220,7 +303,73 @@ impl Default for HookResult {
     }
 }
 
+/// A ranked proposal returned by a decision hook.
+///
+/// Returned by [`HookRegistry::emit_decision`](crate::hooks::HookRegistry::emit_decision)
+/// for events where several hooks propose options (tool resolution, agent
+/// selection).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candidate {
+    /// Identity used for deduplication (e.g. a tool or agent name).
+    pub id: String,
+
+    /// Ranking score; higher is better.
+    pub score: f64,
+
+    /// How sure the proposing hook is, in `0.0..=1.0`. Breaks score ties.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+
+    /// Free-form data for the caller.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, Value>,
+
+    /// Name of the handler that proposed this candidate (set by the kernel).
+    #[serde(default)]
+    pub source: String,
+}
+
+impl Candidate {
+    /// Compare by score, then confidence (missing confidence ranks lowest).
+    pub(crate) fn rank_cmp(&self, other: &Candidate) -> std::cmp::Ordering {
+        let confidence = |c: &Candidate| c.confidence.unwrap_or(f64::NEG_INFINITY);
+        self.score
+            .total_cmp(&other.score)
+            .then(confidence(self).total_cmp(&confidence(other)))
+    }
+
+    /// Whether `self` ranks strictly above `other`.
+    pub(crate) fn outranks(&self, other: &Candidate) -> bool {
+        self.rank_cmp(other).is_gt()
+    }
+}
+
+/// How a host should render a tool result.
+///
+/// Hints unknown to this version deserialize as [`DisplayHint::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayHint {
+    Text,
+    Markdown,
+    Json,
+    Code,
+    Diff,
+    Table,
+    Image,
+    /// Do not show the result to the user.
+    Hidden,
+    #[serde(untagged)]
+    Other(String),
+}
+
 /// Result from tool execution.
+///
+/// `output` is what the model reads. Tools with non-text output can also
+/// return `content` blocks (images, documents, formatted text) and
+/// `artifacts` stored in the [`AttachmentStore`](crate::attachments::AttachmentStore);
+/// all three rich fields are omitted from the JSON when empty, so results
+/// from older tools round-trip unchanged.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolResult {
     /// Whether execution succeeded.
@@ -234,6 +383,18 @@ pub struct ToolResult {
     /// Error details if failed.
     #[serde(default)]
     pub error: Option<HashMap<String, Value>>,
+
+    /// Structured content blocks, alongside or instead of `output`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<ContentBlock>,
+
+    /// Files the tool produced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<AttachmentRef>,
+
+    /// How a host should render the result.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_hint: Option<DisplayHint>,
 }
 
 fn default_true() -> bool {
@@ -246,6 +407,9 @@ impl Default for ToolResult {
             success: true,
             output: None,
             error: None,
+            content: Vec::new(),
+            artifacts: Vec::new(),
+            display_hint: None,
         }
     }
 }
@@ -263,11 +427,36 @@ impl ToolResult {
             success,
             output,
             error,
+            ..Self::default()
         };
         result.auto_populate_output();
         result
     }
 
+    /// Attach structured content blocks.
+    pub fn with_content(mut self, content: Vec<ContentBlock>) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Attach a produced file.
+    pub fn with_artifact(mut self, artifact: AttachmentRef) -> Self {
+        self.artifacts.push(artifact);
+        self
+    }
+
+    /// Set how a host should render the result.
+    pub fn with_display_hint(mut self, hint: DisplayHint) -> Self {
+        self.display_hint = Some(hint);
+        self
+    }
+
+    /// Whether the result carries content blocks or artifacts beyond
+    /// `output`.
+    pub fn has_rich_content(&self) -> bool {
+        !self.content.is_empty() || !self.artifacts.is_empty()
+    }
+
     /// Auto-populate output from error message when tools forget to set it.
     fn auto_populate_output(&mut self) {
         if !self.success && self.output.is_none() {
@@ -280,6 +469,128 @@ impl ToolResult {
     }
 }
 
+/// Per-call context handed to [`Tool::execute_with_context`](crate::traits::Tool::execute_with_context).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolContext {
+    /// ID of the tool call being executed, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+
+    /// Output format the tool should produce. Set by negotiation; `None`
+    /// means the tool advertises no formats and may return anything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<ToolOutputFormat>,
+
+    /// The session's working directory and filesystem scope. Resolve
+    /// relative paths with [`Workspace::resolve`](crate::workspace::Workspace::resolve).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<crate::workspace::Workspace>,
+
+    /// ID of the turn the call belongs to (see [`crate::correlation`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+
+    /// Hooks, cancellation and capabilities for the call (see
+    /// [`crate::tool_services`]). Not serialized.
+    #[serde(skip)]
+    pub services: crate::tool_services::ToolServices,
+}
+
+impl ToolContext {
+    /// Emit `tool:<name>:<event>` with `data` on the session's hooks.
+    pub async fn emit(&self, event: &str, data: Value) -> HookResult {
+        self.services.emit(event, data).await
+    }
+
+    /// The session's cancellation token, if attached.
+    pub fn cancellation(&self) -> Option<&crate::cancellation::CancellationToken> {
+        self.services.cancellation()
+    }
+
+    /// Whether the session has requested cancellation (graceful or immediate).
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation()
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    /// The JSON capability registered under `name`.
+    pub fn capability(&self, name: &str) -> Option<Value> {
+        self.services.capabilities().get(name)
+    }
+
+    /// The capability object registered under `name` as an `Arc<T>`.
+    pub fn capability_object<T>(&self, name: &str) -> Option<std::sync::Arc<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.services.capabilities().get_object(name)
+    }
+}
+
+/// A progress report from a running tool.
+///
+/// Yielded by [`Tool::execute_streaming`](crate::traits::Tool::execute_streaming)
+/// and emitted as `tool:progress`. All fields are optional: a tool may report
+/// only a status line, only a count, or both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolProgress {
+    /// Human-readable status (e.g. `"compiling 12/40 crates"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Units of work done so far.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<f64>,
+
+    /// Total units of work, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+
+    /// Tool-specific detail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+
+    /// Output produced so far. The latest one is kept on the error if the
+    /// call times out or is cancelled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partial_output: Option<Value>,
+}
+
+impl ToolProgress {
+    /// A status-only report.
+    pub fn message(message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A report carrying the output produced so far.
+    pub fn partial(output: impl Into<Value>) -> Self {
+        Self {
+            partial_output: Some(output.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A `completed` of `total` report.
+    pub fn step(completed: f64, total: f64) -> Self {
+        Self {
+            completed: Some(completed),
+            total: Some(total),
+            ..Default::default()
+        }
+    }
+
+    /// Progress as a value in `0.0..=1.0`, when both counts are known.
+    pub fn fraction(&self) -> Option<f64> {
+        match (self.completed, self.total) {
+            (Some(done), Some(total)) if total > 0.0 => Some((done / total).clamp(0.0, 1.0)),
+            _ => None,
+        }
+    }
+}
+
 /// Model metadata for provider models.
 ///
 /// Describes capabilities and defaults for a specific model available from a provider.
@@ -380,6 +691,52 @@ pub struct ProviderInfo {
     pub config_fields: Vec<ConfigField>,
 }
 
+/// Health of a module, as reported by
+/// [`ModuleLifecycle::health_check`](crate::traits::ModuleLifecycle::health_check).
+///
+/// Ordered from best to worst, so the worst of several statuses is their
+/// maximum.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    #[default]
+    Healthy,
+    /// Working, but impaired (e.g. a fallback endpoint is in use).
+    Degraded,
+    /// Not able to serve requests.
+    Unhealthy,
+}
+
+/// A module's health status with an optional explanation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleHealth {
+    pub status: HealthStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ModuleHealth {
+    pub fn healthy() -> Self {
+        Self::default()
+    }
+
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Degraded,
+            message: Some(message.into()),
+        }
+    }
+
+    pub fn unhealthy(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Unhealthy,
+            message: Some(message.into()),
+        }
+    }
+}
+
 /// Module metadata.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ModuleInfo {
@@ -454,14 +811,10 @@ pub struct SessionStatus {
     /// Accumulated session cost in USD stored as a high-precision decimal string
     /// (e.g., "0.047832"). None means rate data was unavailable — not zero cost.
     ///
-    /// Stored as String (not rust_decimal::Decimal) deliberately:
-    ///   - The kernel does not perform arithmetic on cost — it only stores and passes it.
-    ///   - Type enforcement (Decimal, float rejection) is the responsibility of the
-    ///     Python layer where cost enters and exits the system.
-    ///   - Avoids a rust_decimal dependency in the kernel for a transport-only field.
-    ///
-    /// If cost arithmetic is ever needed inside the kernel, change this type to
-    /// rust_decimal::Decimal and add the rust_decimal crate to Cargo.toml.
+    /// Stored as String (not rust_decimal::Decimal) so the value crosses the
+    /// Python boundary unchanged. The kernel computes it, when a pricing
+    /// catalog is configured, in integer nano-dollars (see
+    /// [`CostReport::apply_to`](crate::pricing::CostReport::apply_to)).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cost_usd: Option<String>,
 
@@ -524,6 +877,25 @@ pub struct ApprovalResponse {
     pub remember: bool,
 }
 
+// ---------------------------------------------------------------------------
+// Moderation types
+// ---------------------------------------------------------------------------
+
+/// Classification returned by a [`Moderator`](crate::traits::Moderator).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModerationResult {
+    /// Whether the content violates any category.
+    pub flagged: bool,
+
+    /// Categories the content was flagged for (e.g. `"violence"`).
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    /// Classifier-specific explanation.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -533,6 +905,19 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn message_priority_reads_metadata_and_pins_system() {
+        let mut message = json!({"role": "user", "content": "a", "metadata": "junk"});
+        assert_eq!(MessagePriority::of(&message), MessagePriority::Normal);
+        MessagePriority::High.apply(&mut message);
+        assert_eq!(message["metadata"], json!({"priority": "high"}));
+        assert_eq!(MessagePriority::of(&message), MessagePriority::High);
+
+        let mut system = json!({"role": "system", "content": "s"});
+        MessagePriority::Low.apply(&mut system);
+        assert_eq!(MessagePriority::of(&system), MessagePriority::Pinned);
+    }
+
     // --- HookResult tests (from PLAN) ---
 
     #[test]
@@ -663,6 +1048,7 @@ mod tests {
             success: true,
             output: Some(json!({"key": "value"})),
             error: None,
+            ..Default::default()
         };
         let json_str = serde_json::to_string(&result).unwrap();
         let deserialized: ToolResult = serde_json::from_str(&json_str).unwrap();
@@ -678,6 +1064,7 @@ mod tests {
                 "message".to_string(),
                 json!("command failed"),
             )])),
+            ..Default::default()
         };
         assert!(!result.success);
         assert_eq!(
@@ -686,6 +1073,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tool_result_rich_fields_default_and_round_trip() {
+        let legacy: ToolResult = serde_json::from_value(json!({"output": "ok"})).unwrap();
+        assert!(!legacy.has_rich_content());
+        assert_eq!(
+            serde_json::to_value(&legacy).unwrap(),
+            json!({"success": true, "output": "ok", "error": null})
+        );
+
+        let artifact = AttachmentRef {
+            uri: "attachment://abc".into(),
+            media_type: "image/png".into(),
+            size: 42,
+            preview: None,
+        };
+        let rich = ToolResult::new(true, Some(json!("chart rendered")), None)
+            .with_content(vec![ContentBlock::Text {
+                text: "**done**".into(),
+                visibility: None,
+                cache: None,
+                extensions: HashMap::new(),
+            }])
+            .with_artifact(artifact)
+            .with_display_hint(DisplayHint::Markdown);
+        let value = serde_json::to_value(&rich).unwrap();
+        assert_eq!(value["display_hint"], "markdown");
+        assert_eq!(value["artifacts"][0]["type"], "attachment");
+        assert_eq!(serde_json::from_value::<ToolResult>(value).unwrap(), rich);
+
+        let future: ToolResult = serde_json::from_value(json!({"display_hint": "chart"})).unwrap();
+        assert_eq!(
+            future.display_hint,
+            Some(DisplayHint::Other("chart".into()))
+        );
+    }
+
     // --- ToolResult auto-populate tests ---
 
     #[test]
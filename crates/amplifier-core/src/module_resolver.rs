@@ -575,6 +575,9 @@ fn verify_wasm_integrity(
 /// [`crate::transport::load_grpc_orchestrator`].
 ///
 /// `coordinator` is required only for `ModuleType::Orchestrator` WASM modules.
+/// Provider credentials are resolved through the coordinator's
+/// [`CredentialResolver`](crate::credentials::CredentialResolver), or from the
+/// process environment when no coordinator is given.
 #[cfg(feature = "wasm")]
 pub fn load_module(
     manifest: &ModuleManifest,
@@ -665,7 +668,15 @@ pub fn load_module(
                     Ok(LoadedModule::Approval(approval))
                 }
                 ModuleType::Provider => {
-                    let provider = crate::transport::load_wasm_provider(bytes, engine)?;
+                    let resolver = coordinator
+                        .as_ref()
+                        .map(|coord| coord.credential_resolver())
+                        .unwrap_or_else(|| Arc::new(crate::credentials::EnvCredentialResolver));
+                    let provider = crate::transport::load_wasm_provider_with_credentials(
+                        bytes,
+                        engine,
+                        resolver.as_ref(),
+                    )?;
                     Ok(LoadedModule::Provider(provider))
                 }
                 ModuleType::Orchestrator => {
@@ -0,0 +1,415 @@
+//! `async fn` authoring traits for module implementors.
+//!
+//! The contracts in [`crate::traits`] return `Pin<Box<dyn Future>>` so the
+//! kernel can store modules as `Arc<dyn Trait>`. That keeps dispatch dynamic
+//! but makes every implementation wrap its body in `Box::pin(async move { .. })`.
+//!
+//! The traits here are the same contracts written with return-position
+//! `impl Future`, so implementors write plain `async fn`. They are not
+//! object-safe; wrap an implementation in [`Native`] to get the boxed,
+//! object-safe contract the kernel mounts:
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//! use std::sync::Arc;
+//!
+//! use amplifier_core::native::{AsyncTool, Native};
+//! use amplifier_core::{Tool, ToolError, ToolResult, ToolSpec};
+//! use serde_json::Value;
+//!
+//! struct EchoTool;
+//!
+//! impl AsyncTool for EchoTool {
+//!     fn name(&self) -> &str { "echo" }
+//!     fn description(&self) -> &str { "Echoes input back" }
+//!     fn get_spec(&self) -> ToolSpec {
+//!         ToolSpec {
+//!             name: "echo".into(),
+//!             parameters: HashMap::new(),
+//!             description: None,
+//!             extensions: HashMap::new(),
+//!         }
+//!     }
+//!     async fn execute(&self, input: Value) -> Result<ToolResult, ToolError> {
+//!         Ok(ToolResult::new(true, Some(input), None))
+//!     }
+//! }
+//!
+//! let tool: Arc<dyn Tool> = Arc::new(Native(EchoTool));
+//! ```
+//!
+//! Existing boxed-future implementations are unaffected: the object-safe
+//! traits keep their signatures, and both styles can be mounted side by side.
+//! [`BoxFuture`](crate::traits::BoxFuture) shortens the boxed signatures for
+//! code that keeps implementing them directly.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::errors::{AmplifierError, ContextError, HookError, ProviderError, ToolError};
+use crate::messages::{ChatRequest, ChatResponse, ToolCall, ToolSpec};
+use crate::models::{HookResult, ModelInfo, ProviderInfo, ToolContext, ToolResult};
+use crate::traits::{
+    BoxFuture, ContextManager, HookHandler, ModuleLifecycle, Orchestrator, Provider, Tool,
+};
+
+// ---------------------------------------------------------------------------
+// Native adapter
+// ---------------------------------------------------------------------------
+
+/// Adapts an `async fn` implementation to the matching object-safe trait.
+///
+/// `Native<T>` implements [`Tool`] when `T: AsyncTool`, [`Provider`] when
+/// `T: AsyncProvider`, and so on.
+#[derive(Debug, Clone, Default)]
+pub struct Native<T>(pub T);
+
+impl<T> Native<T> {
+    pub fn new(inner: T) -> Self {
+        Self(inner)
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AsyncTool
+// ---------------------------------------------------------------------------
+
+/// [`Tool`] with `async fn` methods.
+pub trait AsyncTool: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    fn get_spec(&self) -> ToolSpec;
+
+    /// See [`Tool::execute`].
+    fn execute(&self, input: Value) -> impl Future<Output = Result<ToolResult, ToolError>> + Send;
+
+    /// See [`Tool::execute_with_context`]. The default ignores `context`.
+    fn execute_with_context(
+        &self,
+        input: Value,
+        context: ToolContext,
+    ) -> impl Future<Output = Result<ToolResult, ToolError>> + Send {
+        let _ = context;
+        self.execute(input)
+    }
+
+    /// See [`Tool::lifecycle`].
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
+}
+
+impl<T: AsyncTool> Tool for Native<T> {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn description(&self) -> &str {
+        self.0.description()
+    }
+
+    fn get_spec(&self) -> ToolSpec {
+        self.0.get_spec()
+    }
+
+    fn execute(&self, input: Value) -> BoxFuture<'_, Result<ToolResult, ToolError>> {
+        Box::pin(self.0.execute(input))
+    }
+
+    fn execute_with_context(
+        &self,
+        input: Value,
+        context: ToolContext,
+    ) -> BoxFuture<'_, Result<ToolResult, ToolError>> {
+        Box::pin(self.0.execute_with_context(input, context))
+    }
+
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        self.0.lifecycle()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AsyncProvider
+// ---------------------------------------------------------------------------
+
+/// [`Provider`] with `async fn` methods.
+pub trait AsyncProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn get_info(&self) -> ProviderInfo;
+
+    /// See [`Provider::list_models`].
+    fn list_models(&self) -> impl Future<Output = Result<Vec<ModelInfo>, ProviderError>> + Send;
+
+    /// See [`Provider::complete`].
+    fn complete(
+        &self,
+        request: ChatRequest,
+    ) -> impl Future<Output = Result<ChatResponse, ProviderError>> + Send;
+
+    fn parse_tool_calls(&self, response: &ChatResponse) -> Vec<ToolCall>;
+
+    /// See [`Provider::ping`].
+    fn ping(&self) -> impl Future<Output = Result<(), ProviderError>> + Send {
+        async { self.list_models().await.map(|_| ()) }
+    }
+
+    /// See [`Tool::lifecycle`].
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
+}
+
+impl<T: AsyncProvider> Provider for Native<T> {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn get_info(&self) -> ProviderInfo {
+        self.0.get_info()
+    }
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<ModelInfo>, ProviderError>> {
+        Box::pin(self.0.list_models())
+    }
+
+    fn complete(&self, request: ChatRequest) -> BoxFuture<'_, Result<ChatResponse, ProviderError>> {
+        Box::pin(self.0.complete(request))
+    }
+
+    fn parse_tool_calls(&self, response: &ChatResponse) -> Vec<ToolCall> {
+        self.0.parse_tool_calls(response)
+    }
+
+    fn ping(&self) -> BoxFuture<'_, Result<(), ProviderError>> {
+        Box::pin(self.0.ping())
+    }
+
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        self.0.lifecycle()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AsyncOrchestrator
+// ---------------------------------------------------------------------------
+
+/// [`Orchestrator`] with an `async fn` agent loop.
+pub trait AsyncOrchestrator: Send + Sync {
+    /// See [`Orchestrator::execute`].
+    fn execute(
+        &self,
+        prompt: String,
+        context: Arc<dyn ContextManager>,
+        providers: HashMap<String, Arc<dyn Provider>>,
+        tools: HashMap<String, Arc<dyn Tool>>,
+        hooks: Value,
+        coordinator: Value,
+    ) -> impl Future<Output = Result<String, AmplifierError>> + Send;
+
+    /// See [`Tool::lifecycle`].
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
+}
+
+impl<T: AsyncOrchestrator> Orchestrator for Native<T> {
+    fn execute(
+        &self,
+        prompt: String,
+        context: Arc<dyn ContextManager>,
+        providers: HashMap<String, Arc<dyn Provider>>,
+        tools: HashMap<String, Arc<dyn Tool>>,
+        hooks: Value,
+        coordinator: Value,
+    ) -> BoxFuture<'_, Result<String, AmplifierError>> {
+        Box::pin(
+            self.0
+                .execute(prompt, context, providers, tools, hooks, coordinator),
+        )
+    }
+
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        self.0.lifecycle()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AsyncContextManager
+// ---------------------------------------------------------------------------
+
+/// [`ContextManager`] with `async fn` methods.
+pub trait AsyncContextManager: Send + Sync {
+    fn add_message(&self, message: Value) -> impl Future<Output = Result<(), ContextError>> + Send;
+
+    /// See [`ContextManager::get_messages_for_request`].
+    fn get_messages_for_request(
+        &self,
+        token_budget: Option<i64>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> impl Future<Output = Result<Vec<Value>, ContextError>> + Send;
+
+    fn get_messages(&self) -> impl Future<Output = Result<Vec<Value>, ContextError>> + Send;
+
+    fn set_messages(
+        &self,
+        messages: Vec<Value>,
+    ) -> impl Future<Output = Result<(), ContextError>> + Send;
+
+    fn clear(&self) -> impl Future<Output = Result<(), ContextError>> + Send;
+
+    /// See [`Tool::lifecycle`].
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
+}
+
+impl<T: AsyncContextManager> ContextManager for Native<T> {
+    fn add_message(&self, message: Value) -> BoxFuture<'_, Result<(), ContextError>> {
+        Box::pin(self.0.add_message(message))
+    }
+
+    fn get_messages_for_request(
+        &self,
+        token_budget: Option<i64>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> BoxFuture<'_, Result<Vec<Value>, ContextError>> {
+        Box::pin(self.0.get_messages_for_request(token_budget, provider))
+    }
+
+    fn get_messages(&self) -> BoxFuture<'_, Result<Vec<Value>, ContextError>> {
+        Box::pin(self.0.get_messages())
+    }
+
+    fn set_messages(&self, messages: Vec<Value>) -> BoxFuture<'_, Result<(), ContextError>> {
+        Box::pin(self.0.set_messages(messages))
+    }
+
+    fn clear(&self) -> BoxFuture<'_, Result<(), ContextError>> {
+        Box::pin(self.0.clear())
+    }
+
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        self.0.lifecycle()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AsyncHookHandler
+// ---------------------------------------------------------------------------
+
+/// [`HookHandler`] with an `async fn` handler.
+pub trait AsyncHookHandler: Send + Sync {
+    /// See [`HookHandler::handle`].
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> impl Future<Output = Result<HookResult, HookError>> + Send;
+
+    /// See [`HookHandler::get_subscriptions`].
+    fn get_subscriptions(&self, _config: &Value) -> Vec<(String, i32, String)> {
+        vec![("*".to_string(), 0, "hook".to_string())]
+    }
+}
+
+impl<T: AsyncHookHandler> HookHandler for Native<T> {
+    fn handle(&self, event: &str, data: Value) -> BoxFuture<'_, Result<HookResult, HookError>> {
+        // The boxed contract only borrows `self`, so the event name is owned
+        // for the lifetime of the future.
+        let event = event.to_string();
+        Box::pin(async move { self.0.handle(&event, data).await })
+    }
+
+    fn get_subscriptions(&self, config: &Value) -> Vec<(String, i32, String)> {
+        self.0.get_subscriptions(config)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HookAction;
+    use std::sync::Mutex;
+
+    struct Upper;
+
+    impl AsyncTool for Upper {
+        fn name(&self) -> &str {
+            "upper"
+        }
+        fn description(&self) -> &str {
+            "Uppercases text"
+        }
+        fn get_spec(&self) -> ToolSpec {
+            ToolSpec {
+                name: "upper".into(),
+                parameters: HashMap::new(),
+                description: None,
+                extensions: HashMap::new(),
+            }
+        }
+        async fn execute(&self, input: Value) -> Result<ToolResult, ToolError> {
+            let text = input.as_str().unwrap_or_default().to_uppercase();
+            Ok(ToolResult {
+                success: true,
+                output: Some(Value::String(text)),
+                error: None,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Mutex<Vec<String>>,
+    }
+
+    impl AsyncHookHandler for Recorder {
+        async fn handle(&self, event: &str, _data: Value) -> Result<HookResult, HookError> {
+            self.seen.lock().unwrap().push(event.to_string());
+            Ok(HookResult::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn native_tool_is_object_safe_tool() {
+        let tool: Arc<dyn Tool> = Arc::new(Native(Upper));
+        assert_eq!(tool.name(), "upper");
+        let result = tool
+            .execute_with_context(Value::from("hi"), ToolContext::default())
+            .await
+            .unwrap();
+        assert_eq!(result.output, Some(Value::from("HI")));
+    }
+
+    #[tokio::test]
+    async fn native_hook_runs_in_registry() {
+        let registry = crate::hooks::HookRegistry::new();
+        let recorder = Arc::new(Native(Recorder::default()));
+        let _unregister = registry.register("tool:pre", recorder.clone(), 0, None);
+        let result = registry
+            .emit("tool:pre", serde_json::json!({"tool_name": "x"}))
+            .await;
+        assert_eq!(result.action, HookAction::Continue);
+        assert_eq!(*recorder.inner().seen.lock().unwrap(), vec!["tool:pre"]);
+    }
+}
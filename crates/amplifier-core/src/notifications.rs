@@ -0,0 +1,320 @@
+//! Throttled user notifications.
+//!
+//! Modules that want to tell the user something ("rate limited, retrying",
+//! "index rebuilt") call
+//! [`Coordinator::notify_user`](crate::coordinator::Coordinator::notify_user),
+//! which emits [`USER_NOTIFICATION`](crate::events::USER_NOTIFICATION) with a
+//! structured payload for display systems to render:
+//!
+//! ```json
+//! {"level": "warning", "source": "provider-openai", "message": "Rate limited, retrying",
+//!  "suppressed": 3}
+//! ```
+//!
+//! Two filters keep a misbehaving module from flooding the display:
+//!
+//! - **Dedup** — a notification identical (same level, source and message)
+//!   to one delivered less than `dedup_window_ms` ago is dropped.
+//! - **Rate limit** — each source may deliver at most `max_per_window`
+//!   notifications per `rate_window_ms`. Errors are exempt, so a flood of
+//!   warnings cannot hide a failure.
+//!
+//! `suppressed` counts what was dropped for that source since its last
+//! delivered notification, so displays can say "(3 similar messages
+//! hidden)". Both filters are tuned through `session.notifications`:
+//!
+//! ```json
+//! {"session": {"notifications": {"dedup_window_ms": 10000, "max_per_window": 5}}}
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ---------------------------------------------------------------------------
+// NotificationLevel
+// ---------------------------------------------------------------------------
+
+/// Severity of a user notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    /// The level as it appears in event payloads.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for NotificationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// NotificationConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.notifications` section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationConfig {
+    /// Identical notifications within this window are delivered once.
+    pub dedup_window_ms: u64,
+    /// Notifications a source may deliver per `rate_window_ms`.
+    pub max_per_window: usize,
+    pub rate_window_ms: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            dedup_window_ms: 10_000,
+            max_per_window: 10,
+            rate_window_ms: 60_000,
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Read `session.notifications` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("notifications")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.notifications config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// NotificationThrottle
+// ---------------------------------------------------------------------------
+
+/// What happened to a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationOutcome {
+    /// Emitted as `user:notification`.
+    Delivered {
+        /// Notifications from the same source dropped since its last
+        /// delivery.
+        suppressed: usize,
+    },
+    /// Dropped: identical to a recent notification.
+    Duplicate,
+    /// Dropped: the source exceeded its rate limit.
+    RateLimited,
+}
+
+impl NotificationOutcome {
+    pub fn is_delivered(self) -> bool {
+        matches!(self, Self::Delivered { .. })
+    }
+}
+
+#[derive(Default)]
+struct SourceState {
+    /// Delivery times within the rate window, oldest first.
+    delivered: VecDeque<Instant>,
+    suppressed: usize,
+}
+
+#[derive(Default)]
+struct ThrottleState {
+    /// Last delivery of each distinct notification.
+    recent: HashMap<(NotificationLevel, String, String), Instant>,
+    sources: HashMap<String, SourceState>,
+}
+
+/// The dedup and rate-limit filters behind
+/// [`Coordinator::notify_user`](crate::coordinator::Coordinator::notify_user).
+pub struct NotificationThrottle {
+    config: NotificationConfig,
+    state: Mutex<ThrottleState>,
+}
+
+impl NotificationThrottle {
+    pub fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(ThrottleState::default()),
+        }
+    }
+
+    pub fn config(&self) -> &NotificationConfig {
+        &self.config
+    }
+
+    /// Decide whether a notification sent at `now` is delivered, recording
+    /// it either way.
+    pub fn check(
+        &self,
+        level: NotificationLevel,
+        source: &str,
+        message: &str,
+        now: Instant,
+    ) -> NotificationOutcome {
+        let dedup_window = Duration::from_millis(self.config.dedup_window_ms);
+        let rate_window = Duration::from_millis(self.config.rate_window_ms);
+        let mut state = self.state.lock().unwrap();
+        state
+            .recent
+            .retain(|_, at| now.saturating_duration_since(*at) < dedup_window);
+
+        let key = (level, source.to_string(), message.to_string());
+        let duplicate = state.recent.contains_key(&key);
+        let source_state = state.sources.entry(source.to_string()).or_default();
+        while source_state
+            .delivered
+            .front()
+            .is_some_and(|at| now.saturating_duration_since(*at) >= rate_window)
+        {
+            source_state.delivered.pop_front();
+        }
+
+        if duplicate {
+            source_state.suppressed += 1;
+            return NotificationOutcome::Duplicate;
+        }
+        if level != NotificationLevel::Error
+            && source_state.delivered.len() >= self.config.max_per_window
+        {
+            source_state.suppressed += 1;
+            return NotificationOutcome::RateLimited;
+        }
+
+        source_state.delivered.push_back(now);
+        let suppressed = std::mem::take(&mut source_state.suppressed);
+        state.recent.insert(key, now);
+        NotificationOutcome::Delivered { suppressed }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle(dedup_window_ms: u64, max_per_window: usize) -> NotificationThrottle {
+        NotificationThrottle::new(NotificationConfig {
+            dedup_window_ms,
+            max_per_window,
+            rate_window_ms: 1_000,
+        })
+    }
+
+    #[test]
+    fn identical_notifications_are_delivered_once_per_window() {
+        let throttle = throttle(500, 10);
+        let start = Instant::now();
+        let warn = |at: u64, message: &str| {
+            throttle.check(
+                NotificationLevel::Warning,
+                "provider",
+                message,
+                start + Duration::from_millis(at),
+            )
+        };
+
+        assert_eq!(
+            warn(0, "retrying"),
+            NotificationOutcome::Delivered { suppressed: 0 }
+        );
+        assert_eq!(warn(100, "retrying"), NotificationOutcome::Duplicate);
+        assert_eq!(warn(200, "retrying"), NotificationOutcome::Duplicate);
+        assert_eq!(
+            warn(300, "gave up"),
+            NotificationOutcome::Delivered { suppressed: 2 }
+        );
+        assert_eq!(
+            warn(600, "retrying"),
+            NotificationOutcome::Delivered { suppressed: 0 }
+        );
+        // A different level is a different notification.
+        assert!(throttle
+            .check(
+                NotificationLevel::Info,
+                "provider",
+                "retrying",
+                start + Duration::from_millis(700)
+            )
+            .is_delivered());
+    }
+
+    #[test]
+    fn floods_are_rate_limited_per_source_except_errors() {
+        let throttle = throttle(0, 2);
+        let start = Instant::now();
+        let info = |source: &str, at: u64, n: usize| {
+            throttle.check(
+                NotificationLevel::Info,
+                source,
+                &format!("step {n}"),
+                start + Duration::from_millis(at),
+            )
+        };
+
+        assert!(info("indexer", 0, 1).is_delivered());
+        assert!(info("indexer", 10, 2).is_delivered());
+        assert_eq!(info("indexer", 20, 3), NotificationOutcome::RateLimited);
+        assert!(info("other", 30, 1).is_delivered());
+        assert_eq!(
+            throttle.check(
+                NotificationLevel::Error,
+                "indexer",
+                "crashed",
+                start + Duration::from_millis(40)
+            ),
+            NotificationOutcome::Delivered { suppressed: 1 }
+        );
+        // Once the window has passed, the source may deliver again.
+        assert!(info("indexer", 1_045, 4).is_delivered());
+        assert!(info("indexer", 1_055, 5).is_delivered());
+        assert_eq!(info("indexer", 1_065, 6), NotificationOutcome::RateLimited);
+    }
+
+    #[test]
+    fn config_defaults_when_section_is_missing_or_malformed() {
+        assert_eq!(
+            NotificationConfig::from_session_config(&HashMap::new()),
+            NotificationConfig::default()
+        );
+        let config = HashMap::from([(
+            "session".to_string(),
+            serde_json::json!({"notifications": {"max_per_window": 3}}),
+        )]);
+        assert_eq!(
+            NotificationConfig::from_session_config(&config).max_per_window,
+            3
+        );
+        let malformed = HashMap::from([(
+            "session".to_string(),
+            serde_json::json!({"notifications": {"max": 3}}),
+        )]);
+        assert_eq!(
+            NotificationConfig::from_session_config(&malformed),
+            NotificationConfig::default()
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Typed interim states of a running orchestrator.
+//!
+//! While a turn runs, an orchestrator moves through states a UI wants to
+//! show: waiting for the model, running a tool, waiting for approval,
+//! backing off before a provider retry. Orchestrators report them with
+//! [`report`]:
+//!
+//! ```rust
+//! use amplifier_core::orchestrator_status::{self, OrchestratorStatus};
+//!
+//! orchestrator_status::report(OrchestratorStatus::Thinking);
+//! orchestrator_status::report(OrchestratorStatus::CallingTool {
+//!     name: "grep".into(),
+//!     tool_call_id: Some("call_1".into()),
+//! });
+//! ```
+//!
+//! [`Session::execute`](crate::session::Session::execute) runs the
+//! orchestrator under [`forward`], which emits every reported state as an
+//! [`ORCHESTRATOR_STATUS`](crate::events::ORCHESTRATOR_STATUS) event whose
+//! payload is the serialized [`OrchestratorStatus`] (`{"state": "calling_tool",
+//! "name": "grep", ...}`). A state equal to the previous one is not emitted
+//! again.
+//!
+//! Reporting is a no-op outside [`forward`], so orchestrators need no setup
+//! to run in tests. The reporter is task-local: work spawned onto another
+//! task should carry a [`StatusReporter::current`] clone with it. The
+//! kernel's [`ApprovalGate`](crate::approval::ApprovalGate) and
+//! [`ToolExecutor`](crate::tool_executor::ToolExecutor) report
+//! `waiting_approval` and `calling_tool` themselves.
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::events;
+use crate::hooks::HookRegistry;
+
+// ---------------------------------------------------------------------------
+// OrchestratorStatus
+// ---------------------------------------------------------------------------
+
+/// An interim state of the orchestrator's turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum OrchestratorStatus {
+    /// Waiting for a provider response.
+    Thinking,
+    /// Running a tool.
+    CallingTool {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_call_id: Option<String>,
+    },
+    /// Waiting for the user to approve a tool call.
+    WaitingApproval {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tool_name: Option<String>,
+    },
+    /// Backing off before retrying a failed provider call.
+    RetryingProvider {
+        provider: String,
+        /// The attempt about to be made (the first retry is 1).
+        attempt: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        delay_ms: Option<u64>,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Reporting
+// ---------------------------------------------------------------------------
+
+tokio::task_local! {
+    static CURRENT: StatusReporter;
+}
+
+/// Sends states to the [`forward`] call the current task runs under. Cheap
+/// to clone.
+#[derive(Debug, Clone)]
+pub struct StatusReporter {
+    tx: mpsc::UnboundedSender<OrchestratorStatus>,
+}
+
+impl StatusReporter {
+    /// The reporter of the current task, if it runs under [`forward`].
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// Report `status`. Never blocks; reports after the turn has ended are
+    /// discarded.
+    pub fn report(&self, status: OrchestratorStatus) {
+        let _ = self.tx.send(status);
+    }
+
+    /// Run `fut` with this reporter as the current one.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+}
+
+/// Report `status` on the current task's reporter, if any.
+pub fn report(status: OrchestratorStatus) {
+    let _ = CURRENT.try_with(|reporter| reporter.report(status));
+}
+
+/// Run `fut`, emitting the states reported from it on `hooks`.
+///
+/// States still queued when `fut` completes are emitted before returning.
+pub async fn forward<F: Future>(hooks: &HookRegistry, fut: F) -> F::Output {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let fut = StatusReporter { tx }.scope(fut);
+    tokio::pin!(fut);
+
+    let mut last: Option<OrchestratorStatus> = None;
+    loop {
+        tokio::select! {
+            biased;
+            Some(status) = rx.recv() => emit(hooks, &mut last, status).await,
+            output = &mut fut => {
+                while let Ok(status) = rx.try_recv() {
+                    emit(hooks, &mut last, status).await;
+                }
+                return output;
+            }
+        }
+    }
+}
+
+async fn emit(
+    hooks: &HookRegistry,
+    last: &mut Option<OrchestratorStatus>,
+    status: OrchestratorStatus,
+) {
+    if last.as_ref() == Some(&status) {
+        return;
+    }
+    let payload = serde_json::to_value(&status).unwrap_or_default();
+    *last = Some(status);
+    hooks.emit(events::ORCHESTRATOR_STATUS, payload).await;
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeHookHandler;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn recorder(hooks: &HookRegistry) -> Arc<FakeHookHandler> {
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::ORCHESTRATOR_STATUS, recorder.clone(), 0, None);
+        recorder
+    }
+
+    #[tokio::test]
+    async fn reported_states_become_typed_events() {
+        let hooks = HookRegistry::new();
+        let recorder = recorder(&hooks);
+
+        let output = forward(&hooks, async {
+            report(OrchestratorStatus::Thinking);
+            report(OrchestratorStatus::Thinking);
+            tokio::task::yield_now().await;
+            report(OrchestratorStatus::CallingTool {
+                name: "grep".into(),
+                tool_call_id: Some("c1".into()),
+            });
+            report(OrchestratorStatus::RetryingProvider {
+                provider: "openai".into(),
+                attempt: 1,
+                delay_ms: Some(500),
+            });
+            report(OrchestratorStatus::Thinking);
+            "done"
+        })
+        .await;
+
+        assert_eq!(output, "done");
+        let states: Vec<_> = recorder
+            .recorded_events()
+            .into_iter()
+            .map(|(_, data)| data)
+            .collect();
+        assert_eq!(states.len(), 4);
+        assert_eq!(states[0]["state"], "thinking");
+        assert_eq!(states[1]["state"], "calling_tool");
+        assert_eq!(states[1]["name"], "grep");
+        assert_eq!(states[2]["attempt"], 1);
+        assert_eq!(states[3]["state"], "thinking");
+        let parsed: OrchestratorStatus = serde_json::from_value(json!({
+            "state": "waiting_approval", "tool_name": "bash"
+        }))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            OrchestratorStatus::WaitingApproval {
+                tool_name: Some("bash".into())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn reporting_outside_forward_is_a_no_op_and_clones_cross_tasks() {
+        report(OrchestratorStatus::Thinking);
+        assert!(StatusReporter::current().is_none());
+
+        let hooks = HookRegistry::new();
+        let recorder = recorder(&hooks);
+        forward(&hooks, async {
+            let reporter = StatusReporter::current().unwrap();
+            tokio::spawn(async move {
+                report(OrchestratorStatus::Thinking);
+                reporter.report(OrchestratorStatus::WaitingApproval { tool_name: None });
+            })
+            .await
+            .unwrap();
+        })
+        .await;
+
+        let events = recorder.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["state"], "waiting_approval");
+    }
+}
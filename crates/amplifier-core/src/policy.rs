@@ -0,0 +1,634 @@
+//! Declarative tool permission policy.
+//!
+//! `session.policy` lists allow/deny/ask rules that are checked on every
+//! `tool:pre` event, so hosts do not have to hand-write a permission hook:
+//!
+//! ```json
+//! {"session": {"policy": {
+//!     "default": "allow",
+//!     "rules": [
+//!         {"tool": "bash", "arguments": {"command": "rm -rf *"}, "action": "deny",
+//!          "reason": "recursive delete"},
+//!         {"tool": "write_*", "paths": ["/etc", "/usr"], "action": "ask"},
+//!         {"tool": "web_fetch", "action": "deny"}
+//!     ]
+//! }}}
+//! ```
+//!
+//! # Matching
+//!
+//! Rules are checked in order and the **first** match decides; `default`
+//! (`allow` unless set) applies when none match. Within a rule every
+//! condition given must hold:
+//!
+//! | Condition   | Matches when                                                   |
+//! |-------------|----------------------------------------------------------------|
+//! | `tool`      | the tool name matches the glob (`*`, `?`; default `*`)         |
+//! | `arguments` | each named argument exists and its value matches the glob (non-strings are matched as JSON text) |
+//! | `paths`     | some path argument (see `path_arguments`) lies under one of the prefixes |
+//!
+//! Paths are normalized lexically (`.` and `..` resolved) before comparison,
+//! and prefixes match whole components: `/etc` covers `/etc/hosts` but not
+//! `/etcetera`.
+//!
+//! # Outcomes
+//!
+//! | Action  | `tool:pre` result                                               |
+//! |---------|-----------------------------------------------------------------|
+//! | `allow` | `continue` — later hooks still run                              |
+//! | `deny`  | `deny` with the rule's reason                                   |
+//! | `ask`   | `ask_user` with a generated approval prompt, defaulting to deny |
+//!
+//! The policy hook runs in [`HookPhase::PreValidation`], ahead of every
+//! custom hook registered with [`HookRegistry::register`]. A malformed
+//! `session.policy` fails closed: every tool call is denied.
+//!
+//! # Workspace
+//!
+//! A policy built [`with_workspace`](PermissionPolicy::with_workspace)
+//! (which [`Session::new`](crate::session::Session::new) does when
+//! `session.workspace` is set) first denies path arguments that lie outside
+//! the [`Workspace`] or write to its read-only paths, before any rule is
+//! checked. Relative paths in arguments and `paths` prefixes are then taken
+//! relative to the workspace root.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::HookError;
+use crate::events;
+use crate::hooks::{HookPhase, HookRegistry};
+use crate::models::{ApprovalDefault, HookAction, HookResult};
+use crate::traits::HookHandler;
+use crate::workspace::Workspace;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// What a matching rule (or the default) does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    #[default]
+    Allow,
+    Deny,
+    /// Ask the user for approval.
+    Ask,
+}
+
+/// One allow/deny/ask rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyRule {
+    /// Tool-name glob.
+    #[serde(default = "match_all")]
+    pub tool: String,
+    /// Argument name → value glob.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arguments: BTreeMap<String, String>,
+    /// Path prefixes; matches when a path argument lies under one of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    pub action: PolicyAction,
+    /// Shown to the agent on deny and to the user on ask.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+fn match_all() -> String {
+    "*".to_string()
+}
+
+/// The `session.policy` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyConfig {
+    /// Action when no rule matches.
+    #[serde(default)]
+    pub default: PolicyAction,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+    /// Tool arguments treated as paths by `paths` conditions.
+    #[serde(default = "default_path_arguments")]
+    pub path_arguments: Vec<String>,
+    /// Seconds to wait for the user on `ask` (default 300).
+    #[serde(default = "default_approval_timeout")]
+    pub approval_timeout: f64,
+}
+
+fn default_path_arguments() -> Vec<String> {
+    ["path", "file_path", "filepath", "directory", "dir", "cwd"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+fn default_approval_timeout() -> f64 {
+    300.0
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            default: PolicyAction::default(),
+            rules: Vec::new(),
+            path_arguments: default_path_arguments(),
+            approval_timeout: default_approval_timeout(),
+        }
+    }
+}
+
+impl PolicyConfig {
+    /// Read `session.policy` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent. A malformed section is
+    /// logged and replaced by a deny-everything policy.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("policy"))?;
+        Some(serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::error!("Malformed session.policy config ({e}); denying all tool calls");
+            Self {
+                default: PolicyAction::Deny,
+                ..Self::default()
+            }
+        }))
+    }
+
+    /// Whether this policy can ever affect a tool call.
+    pub fn is_active(&self) -> bool {
+        self.default != PolicyAction::Allow
+            || self.rules.iter().any(|r| r.action != PolicyAction::Allow)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+/// The outcome of evaluating a tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyDecision {
+    Allow,
+    Deny { reason: String },
+    Ask { prompt: String },
+}
+
+impl PolicyDecision {
+    /// The `tool:pre` hook result for this decision.
+    pub fn into_hook_result(self, approval_timeout: f64) -> HookResult {
+        match self {
+            Self::Allow => HookResult::default(),
+            Self::Deny { reason } => HookResult {
+                action: HookAction::Deny,
+                reason: Some(reason),
+                ..Default::default()
+            },
+            Self::Ask { prompt } => HookResult {
+                action: HookAction::AskUser,
+                approval_prompt: Some(prompt),
+                approval_timeout,
+                approval_default: ApprovalDefault::Deny,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// A compiled [`PolicyConfig`], usable directly or as a `tool:pre` hook.
+#[derive(Debug, Clone)]
+pub struct PermissionPolicy {
+    config: PolicyConfig,
+    workspace: Option<Workspace>,
+}
+
+impl PermissionPolicy {
+    pub fn new(config: PolicyConfig) -> Self {
+        Self {
+            config,
+            workspace: None,
+        }
+    }
+
+    /// Confine path arguments to `workspace`.
+    pub fn with_workspace(mut self, workspace: Workspace) -> Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    pub fn config(&self) -> &PolicyConfig {
+        &self.config
+    }
+
+    pub fn workspace(&self) -> Option<&Workspace> {
+        self.workspace.as_ref()
+    }
+
+    /// Register on `tool:pre` in [`HookPhase::PreValidation`] as `"policy"`.
+    pub fn install(self: &Arc<Self>, hooks: &HookRegistry) {
+        let _ = hooks.register_in_phase(
+            events::TOOL_PRE,
+            self.clone(),
+            HookPhase::PreValidation,
+            0,
+            Some("policy".into()),
+        );
+    }
+
+    /// Decide a call to `tool_name` with `input`.
+    pub fn evaluate(&self, tool_name: &str, input: &Value) -> PolicyDecision {
+        if let Some(workspace) = &self.workspace {
+            let access = workspace.access_for(tool_name);
+            for path in self.path_arguments(input) {
+                if let Err(violation) = workspace.check(path, access) {
+                    return PolicyDecision::Deny {
+                        reason: format!("Tool '{tool_name}' denied by policy: {violation}"),
+                    };
+                }
+            }
+        }
+        let matched = self
+            .config
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| self.rule_matches(rule, tool_name, input));
+        let (action, reason) = match matched {
+            Some((i, rule)) => (
+                rule.action,
+                rule.reason
+                    .clone()
+                    .unwrap_or_else(|| format!("policy rule {} matched", i + 1)),
+            ),
+            None => (self.config.default, "no policy rule matched".to_string()),
+        };
+        match action {
+            PolicyAction::Allow => PolicyDecision::Allow,
+            PolicyAction::Deny => PolicyDecision::Deny {
+                reason: format!("Tool '{tool_name}' denied by policy: {reason}"),
+            },
+            PolicyAction::Ask => PolicyDecision::Ask {
+                prompt: approval_prompt(tool_name, input, &reason),
+            },
+        }
+    }
+
+    fn rule_matches(&self, rule: &PolicyRule, tool_name: &str, input: &Value) -> bool {
+        if !glob_match(&rule.tool, tool_name) {
+            return false;
+        }
+        let arguments_match = rule
+            .arguments
+            .iter()
+            .all(|(key, pattern)| match input.get(key) {
+                Some(Value::String(s)) => glob_match(pattern, s),
+                Some(other) => glob_match(pattern, &other.to_string()),
+                None => false,
+            });
+        if !arguments_match {
+            return false;
+        }
+        if rule.paths.is_empty() {
+            return true;
+        }
+        self.path_arguments(input).any(|path| {
+            let path = self.resolve(path);
+            rule.paths
+                .iter()
+                .any(|prefix| path.starts_with(self.resolve(prefix)))
+        })
+    }
+
+    fn path_arguments<'a>(&'a self, input: &'a Value) -> impl Iterator<Item = &'a str> {
+        self.config
+            .path_arguments
+            .iter()
+            .filter_map(|key| input.get(key).and_then(Value::as_str))
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        match &self.workspace {
+            Some(workspace) => workspace.resolve(path),
+            None => normalize(Path::new(path)),
+        }
+    }
+}
+
+impl HookHandler for PermissionPolicy {
+    fn handle(
+        &self,
+        _event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        let tool_name = data
+            .get("tool_name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let input = data.get("tool_input").unwrap_or(&Value::Null);
+        let result = self
+            .evaluate(tool_name, input)
+            .into_hook_result(self.config.approval_timeout);
+        Box::pin(async move { Ok(result) })
+    }
+}
+
+/// Longest argument value rendered into an approval prompt.
+const PROMPT_VALUE_LIMIT: usize = 200;
+
+fn approval_prompt(tool_name: &str, input: &Value, reason: &str) -> String {
+    let mut prompt = format!("Allow tool '{tool_name}'");
+    if let Some(args) = input.as_object().filter(|a| !a.is_empty()) {
+        let rendered: Vec<String> = args
+            .iter()
+            .map(|(key, value)| {
+                let mut text = match value {
+                    Value::String(s) => format!("{s:?}"),
+                    other => other.to_string(),
+                };
+                if text.len() > PROMPT_VALUE_LIMIT {
+                    let cut = (0..=PROMPT_VALUE_LIMIT)
+                        .rev()
+                        .find(|&i| text.is_char_boundary(i))
+                        .unwrap_or(0);
+                    text.truncate(cut);
+                    text.push('…');
+                }
+                format!("{key}={text}")
+            })
+            .collect();
+        prompt.push_str(&format!(" with {}", rendered.join(", ")));
+    }
+    prompt.push_str(&format!("? ({reason})"));
+    prompt
+}
+
+/// Resolve `.` and `..` without touching the filesystem.
+///
+/// `..` at the root stays at the root (`/../etc` is `/etc`); leading `..`
+/// of a relative path are kept (`../../x` stays as is).
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match out.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    out.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => out.push(".."),
+            },
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Glob match supporting `*` (any run) and `?` (any one character).
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(config: Value) -> PermissionPolicy {
+        PermissionPolicy::new(serde_json::from_value(config).unwrap())
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("write_*", "write_file"));
+        assert!(!glob_match("write_*", "read_file"));
+        assert!(glob_match("rm -rf *", "rm -rf build"));
+        assert!(glob_match("b?sh", "bash"));
+        assert!(glob_match("*.env", "config/.env"));
+        assert!(!glob_match("bash", "bash2"));
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let p = policy(json!({
+            "rules": [
+                {"tool": "bash", "arguments": {"command": "ls*"}, "action": "allow"},
+                {"tool": "bash", "action": "deny", "reason": "no shell"},
+            ]
+        }));
+        assert_eq!(
+            p.evaluate("bash", &json!({"command": "ls -la"})),
+            PolicyDecision::Allow
+        );
+        assert_eq!(
+            p.evaluate("bash", &json!({"command": "curl x"})),
+            PolicyDecision::Deny {
+                reason: "Tool 'bash' denied by policy: no shell".into()
+            }
+        );
+        assert_eq!(p.evaluate("read_file", &json!({})), PolicyDecision::Allow);
+    }
+
+    #[test]
+    fn default_applies_when_nothing_matches() {
+        let p = policy(json!({
+            "default": "deny",
+            "rules": [{"tool": "read_*", "action": "allow"}]
+        }));
+        assert_eq!(p.evaluate("read_file", &json!({})), PolicyDecision::Allow);
+        assert!(matches!(
+            p.evaluate("bash", &json!({})),
+            PolicyDecision::Deny { reason } if reason.contains("no policy rule matched")
+        ));
+    }
+
+    #[test]
+    fn path_prefixes_match_whole_components_after_normalizing() {
+        let p = policy(json!({
+            "rules": [{"tool": "write_*", "paths": ["/etc"], "action": "deny"}]
+        }));
+        let denied = |path: &str| {
+            matches!(
+                p.evaluate("write_file", &json!({"file_path": path})),
+                PolicyDecision::Deny { .. }
+            )
+        };
+        assert!(denied("/etc/hosts"));
+        assert!(denied("/etc"));
+        assert!(denied("/tmp/../etc/passwd"));
+        assert!(denied("/etc/./ssh/config"));
+        assert!(denied("/../etc/passwd"));
+        assert!(denied("/tmp/../../../etc/passwd"));
+        assert!(!denied("/etcetera/x"));
+        assert!(!denied("/home/me/etc"));
+        // No path argument at all: the paths condition cannot hold.
+        assert_eq!(
+            p.evaluate("write_file", &json!({"content": "x"})),
+            PolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn normalizing_never_climbs_above_the_root() {
+        assert_eq!(
+            normalize(Path::new("/../etc/passwd")),
+            Path::new("/etc/passwd")
+        );
+        assert_eq!(normalize(Path::new("/a/../../b")), Path::new("/b"));
+        assert_eq!(normalize(Path::new("../../x")), Path::new("../../x"));
+        assert_eq!(normalize(Path::new("a/../../x")), Path::new("../x"));
+        assert_eq!(normalize(Path::new("./a/./b/..")), Path::new("a"));
+    }
+
+    #[test]
+    fn workspace_confines_path_arguments() {
+        let p = policy(json!({
+            "rules": [{"tool": "*", "paths": ["secrets"], "action": "deny", "reason": "secrets"}]
+        }))
+        .with_workspace(Workspace::new("/work").with_read_only_path("vendor"));
+        let decide = |tool: &str, path: &str| p.evaluate(tool, &json!({"path": path}));
+
+        assert_eq!(decide("read_file", "src/lib.rs"), PolicyDecision::Allow);
+        assert_eq!(decide("read_file", "vendor/x.rs"), PolicyDecision::Allow);
+        assert_eq!(
+            decide("write_file", "vendor/x.rs"),
+            PolicyDecision::Deny {
+                reason: "Tool 'write_file' denied by policy: /work/vendor/x.rs is read-only".into()
+            }
+        );
+        assert!(matches!(
+            decide("read_file", "../etc/passwd"),
+            PolicyDecision::Deny { reason } if reason.ends_with("/etc/passwd is outside the workspace")
+        ));
+        // Rule prefixes resolve against the root too.
+        assert!(matches!(
+            decide("read_file", "/work/secrets/key"),
+            PolicyDecision::Deny { reason } if reason.ends_with("secrets")
+        ));
+    }
+
+    #[test]
+    fn non_string_arguments_match_as_json_text() {
+        let p = policy(json!({
+            "rules": [{"tool": "*", "arguments": {"force": "true"}, "action": "deny"}]
+        }));
+        assert!(matches!(
+            p.evaluate("git_push", &json!({"force": true})),
+            PolicyDecision::Deny { .. }
+        ));
+        assert_eq!(
+            p.evaluate("git_push", &json!({"force": false})),
+            PolicyDecision::Allow
+        );
+    }
+
+    #[test]
+    fn ask_generates_prompt_and_denies_by_default() {
+        let p = policy(json!({
+            "approval_timeout": 30.0,
+            "rules": [{"tool": "bash", "action": "ask", "reason": "shell access"}]
+        }));
+        let decision = p.evaluate("bash", &json!({"command": "make build"}));
+        let PolicyDecision::Ask { prompt } = &decision else {
+            panic!("expected ask, got {decision:?}");
+        };
+        assert_eq!(
+            prompt,
+            r#"Allow tool 'bash' with command="make build"? (shell access)"#
+        );
+
+        let result = decision.into_hook_result(p.config().approval_timeout);
+        assert_eq!(result.action, HookAction::AskUser);
+        assert_eq!(result.approval_default, ApprovalDefault::Deny);
+        assert_eq!(result.approval_timeout, 30.0);
+    }
+
+    #[test]
+    fn long_argument_values_are_truncated_in_prompts() {
+        let prompt = approval_prompt("write", &json!({"content": "é".repeat(500)}), "r");
+        assert!(prompt.len() < 300, "{prompt}");
+        assert!(prompt.contains('…'));
+    }
+
+    #[test]
+    fn config_section_is_optional_and_fails_closed() {
+        assert!(PolicyConfig::from_session_config(&Default::default()).is_none());
+
+        let mut plan = HashMap::new();
+        plan.insert(
+            "session".to_string(),
+            json!({"policy": {"rules": [{"tool": "bash", "action": "maybe"}]}}),
+        );
+        let config = PolicyConfig::from_session_config(&plan).unwrap();
+        assert_eq!(config.default, PolicyAction::Deny);
+        assert!(config.is_active());
+
+        assert!(!PolicyConfig::default().is_active());
+    }
+
+    #[tokio::test]
+    async fn installed_policy_runs_before_custom_hooks() {
+        use crate::testing::FakeHookHandler;
+
+        let hooks = HookRegistry::new();
+        let custom = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::TOOL_PRE, custom.clone(), -100, None);
+        Arc::new(policy(
+            json!({"rules": [{"tool": "bash", "action": "deny"}]}),
+        ))
+        .install(&hooks);
+
+        let result = hooks
+            .emit(
+                events::TOOL_PRE,
+                json!({"tool_name": "bash", "tool_input": {"command": "ls"}}),
+            )
+            .await;
+        assert_eq!(result.action, HookAction::Deny);
+        assert!(custom.recorded_events().is_empty());
+
+        let result = hooks
+            .emit(
+                events::TOOL_PRE,
+                json!({"tool_name": "read", "tool_input": {}}),
+            )
+            .await;
+        assert_eq!(result.action, HookAction::Continue);
+        assert_eq!(custom.recorded_events().len(), 1);
+    }
+}
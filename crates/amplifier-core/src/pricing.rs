@@ -0,0 +1,570 @@
+//! Usage-based cost estimation.
+//!
+//! A [`PricingCatalog`] holds per-model token rates in USD per million
+//! tokens, keyed by model ID. A [`CostTracker`] installed on a session's
+//! hooks prices every provider response and keeps totals per provider and
+//! per turn; [`CostReport::apply_to`] copies them into a [`SessionStatus`].
+//!
+//! ```json
+//! {"session": {"pricing": {"models": {
+//!     "anthropic/claude-sonnet-4-5*": {
+//!         "input_per_mtok": 3.0,
+//!         "output_per_mtok": 15.0,
+//!         "cache_read_per_mtok": 0.3,
+//!         "cache_write_per_mtok": 3.75
+//!     },
+//!     "gpt-4o": {"input_per_mtok": 2.5, "output_per_mtok": 10.0}
+//! }}}}
+//! ```
+//!
+//! # Lookup
+//!
+//! Keys are a model ID, optionally prefixed with `provider/`, and may end in
+//! `*` to match dated releases (`claude-sonnet-4-5-20250929`). The most
+//! specific key wins: provider-qualified over bare, exact over wildcard,
+//! longer over shorter.
+//!
+//! # Pricing a response
+//!
+//! | Source                                           | Used for               |
+//! |--------------------------------------------------|------------------------|
+//! | `usage.cost_usd` reported by the provider         | the cost, when present |
+//! | `degradation.actual`, payload `model`, `metadata.model` | the model, in that order |
+//! | payload `provider`                               | the provider           |
+//!
+//! Cache tokens are charged on top of `input_tokens` at their own rate
+//! (defaulting to the input rate), matching providers that report them
+//! separately. Amounts are summed as integer nano-dollars and reported as
+//! six-decimal strings (`"0.047832"`). A total is `None` when any call in it
+//! could not be priced, since a partial sum would read as the full cost.
+//!
+//! Responses are counted like [`crate::quota`] counts tokens: on
+//! `provider:response`, or on `provider:post` when the orchestrator never
+//! emits `provider:request`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::HookError;
+use crate::events;
+use crate::hooks::{HookPhase, HookRegistry};
+use crate::messages::Usage;
+use crate::models::{HookResult, SessionStatus};
+use crate::traits::HookHandler;
+
+/// Nano-dollars per dollar.
+const NANOS_PER_USD: u64 = 1_000_000_000;
+
+// ---------------------------------------------------------------------------
+// Amounts
+// ---------------------------------------------------------------------------
+
+/// Format nano-dollars as a six-decimal USD string (`"0.047832"`).
+pub fn format_usd(nanos: u64) -> String {
+    let micros = (nanos + 500) / 1_000;
+    format!("{}.{:06}", micros / 1_000_000, micros % 1_000_000)
+}
+
+/// Parse a USD decimal string into nano-dollars. Returns `None` for
+/// negative or malformed amounts.
+pub fn parse_usd(amount: &str) -> Option<u64> {
+    let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if !digits(whole) || !digits(fraction) {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let mut nanos: String = fraction.chars().take(9).collect();
+    while nanos.len() < 9 {
+        nanos.push('0');
+    }
+    whole
+        .checked_mul(NANOS_PER_USD)?
+        .checked_add(nanos.parse().ok()?)
+}
+
+// ---------------------------------------------------------------------------
+// PricingCatalog
+// ---------------------------------------------------------------------------
+
+/// Pricing load failures.
+#[derive(Debug, thiserror::Error)]
+pub enum PricingError {
+    /// The catalog could not be parsed.
+    #[error("invalid pricing catalog {source_name}: {reason}")]
+    Parse { source_name: String, reason: String },
+
+    /// I/O error reading a catalog file.
+    #[error("I/O error at {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Token rates for one model, in USD per million tokens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    /// Rate for cache reads; the input rate when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_per_mtok: Option<f64>,
+    /// Rate for cache writes; the input rate when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_per_mtok: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn new(input_per_mtok: f64, output_per_mtok: f64) -> Self {
+        Self {
+            input_per_mtok,
+            output_per_mtok,
+            cache_read_per_mtok: None,
+            cache_write_per_mtok: None,
+        }
+    }
+
+    /// Set the cache read and write rates.
+    pub fn with_cache_rates(mut self, read_per_mtok: f64, write_per_mtok: f64) -> Self {
+        self.cache_read_per_mtok = Some(read_per_mtok);
+        self.cache_write_per_mtok = Some(write_per_mtok);
+        self
+    }
+
+    /// The cost of `usage`, in nano-dollars.
+    pub fn cost_nanos(&self, usage: &Usage) -> u64 {
+        // Tokens times USD per million tokens gives micro-dollars.
+        let charge = |tokens: Option<i64>, rate: f64| tokens.unwrap_or(0).max(0) as f64 * rate;
+        let total = charge(Some(usage.input_tokens), self.input_per_mtok)
+            + charge(Some(usage.output_tokens), self.output_per_mtok)
+            + charge(
+                usage.cache_read_tokens,
+                self.cache_read_per_mtok.unwrap_or(self.input_per_mtok),
+            )
+            + charge(
+                usage.cache_write_tokens,
+                self.cache_write_per_mtok.unwrap_or(self.input_per_mtok),
+            );
+        (total * 1_000.0).round().max(0.0) as u64
+    }
+}
+
+/// Model rates by key. See the [module docs](self) for key matching.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PricingCatalog {
+    #[serde(default)]
+    pub models: BTreeMap<String, ModelPricing>,
+}
+
+impl PricingCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add rates under `key` (`"gpt-4o"`, `"anthropic/claude-*"`).
+    pub fn with_model(mut self, key: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.models.insert(key.into(), pricing);
+        self
+    }
+
+    /// Parse a JSON catalog. `source_name` is used in error messages.
+    pub fn from_json(content: &str, source_name: &str) -> Result<Self, PricingError> {
+        serde_json::from_str(content).map_err(|e| PricingError::Parse {
+            source_name: source_name.to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Read a JSON catalog file.
+    pub fn from_path(path: &Path) -> Result<Self, PricingError> {
+        let content = std::fs::read_to_string(path).map_err(|source| PricingError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_json(&content, &path.display().to_string())
+    }
+
+    /// Read `session.pricing`, if present. Malformed config is logged and
+    /// ignored.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("pricing"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.pricing config: {e}"))
+            .ok()
+    }
+
+    /// Rates for `model` served by `provider`, if any key matches.
+    pub fn get(&self, provider: &str, model: &str) -> Option<&ModelPricing> {
+        self.models
+            .iter()
+            .filter_map(|(key, pricing)| {
+                let (qualified, pattern) = match key.split_once('/') {
+                    Some((p, pattern)) if p == provider => (true, pattern),
+                    Some(_) => return None,
+                    None => (false, key.as_str()),
+                };
+                let exact = match pattern.strip_suffix('*') {
+                    Some(prefix) if model.starts_with(prefix) => false,
+                    None if pattern == model => true,
+                    _ => return None,
+                };
+                Some(((qualified, exact, pattern.len()), pricing))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, pricing)| pricing)
+    }
+
+    /// The cost of `usage` in nano-dollars, if the model is priced.
+    pub fn cost_nanos(&self, provider: &str, model: &str, usage: &Usage) -> Option<u64> {
+        self.get(provider, model).map(|p| p.cost_nanos(usage))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reports
+// ---------------------------------------------------------------------------
+
+/// Usage and cost for a set of provider calls.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostBreakdown {
+    pub calls: u64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Estimated cost; `None` when any call could not be priced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<String>,
+    /// Calls whose model had no rates.
+    pub unpriced_calls: u64,
+}
+
+/// A session's costs so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostReport {
+    pub total: CostBreakdown,
+    pub by_provider: BTreeMap<String, CostBreakdown>,
+    /// One entry per [`CostTracker::begin_turn`], oldest first.
+    pub turns: Vec<CostBreakdown>,
+}
+
+impl CostReport {
+    /// Copy the totals into `status`.
+    pub fn apply_to(&self, status: &mut SessionStatus) {
+        status.total_input_tokens = self.total.input_tokens;
+        status.total_output_tokens = self.total.output_tokens;
+        status.cost_usd = self.total.cost_usd.clone();
+    }
+}
+
+/// Running sums behind a [`CostBreakdown`].
+#[derive(Debug, Clone, Default)]
+struct Tally {
+    calls: u64,
+    input_tokens: i64,
+    output_tokens: i64,
+    cache_read_tokens: i64,
+    cache_write_tokens: i64,
+    nanos: u64,
+    unpriced_calls: u64,
+}
+
+impl Tally {
+    fn add(&mut self, usage: &Usage, cost: Option<u64>) {
+        self.calls += 1;
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cache_read_tokens += usage.cache_read_tokens.unwrap_or(0);
+        self.cache_write_tokens += usage.cache_write_tokens.unwrap_or(0);
+        match cost {
+            Some(nanos) => self.nanos += nanos,
+            None => self.unpriced_calls += 1,
+        }
+    }
+
+    fn breakdown(&self) -> CostBreakdown {
+        CostBreakdown {
+            calls: self.calls,
+            input_tokens: self.input_tokens,
+            output_tokens: self.output_tokens,
+            cache_read_tokens: self.cache_read_tokens,
+            cache_write_tokens: self.cache_write_tokens,
+            cost_usd: (self.unpriced_calls == 0).then(|| format_usd(self.nanos)),
+            unpriced_calls: self.unpriced_calls,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Tallies {
+    total: Tally,
+    by_provider: BTreeMap<String, Tally>,
+    turns: Vec<Tally>,
+}
+
+// ---------------------------------------------------------------------------
+// CostTracker
+// ---------------------------------------------------------------------------
+
+/// Prices a session's provider responses against a [`PricingCatalog`].
+#[derive(Debug)]
+pub struct CostTracker {
+    catalog: RwLock<Arc<PricingCatalog>>,
+    tallies: Mutex<Tallies>,
+    /// Set once `provider:request` is seen; disables the invoker fallback.
+    orchestrator_events: AtomicBool,
+}
+
+impl CostTracker {
+    pub fn new(catalog: PricingCatalog) -> Self {
+        Self {
+            catalog: RwLock::new(Arc::new(catalog)),
+            tallies: Mutex::new(Tallies::default()),
+            orchestrator_events: AtomicBool::new(false),
+        }
+    }
+
+    pub fn catalog(&self) -> Arc<PricingCatalog> {
+        Arc::clone(&self.catalog.read().unwrap())
+    }
+
+    /// Replace the catalog. Calls already priced keep their cost.
+    pub fn set_catalog(&self, catalog: PricingCatalog) {
+        *self.catalog.write().unwrap() = Arc::new(catalog);
+    }
+
+    /// Register on `hooks` in [`HookPhase::Observation`] as `"pricing"`, so
+    /// responses are priced after other handlers have modified them.
+    pub fn install(self: &Arc<Self>, hooks: &HookRegistry) {
+        for event in [
+            events::PROVIDER_REQUEST,
+            events::PROVIDER_RESPONSE,
+            events::PROVIDER_POST,
+        ] {
+            let _ = hooks.register_in_phase(
+                event,
+                self.clone(),
+                HookPhase::Observation,
+                i32::MAX,
+                Some("pricing".into()),
+            );
+        }
+    }
+
+    /// Start a new turn; later calls are attributed to it.
+    pub fn begin_turn(&self) {
+        self.tallies.lock().unwrap().turns.push(Tally::default());
+    }
+
+    /// Record one call's usage. The provider's own `usage.cost_usd` is used
+    /// when present, otherwise the catalog's rates for `model`.
+    pub fn record(&self, provider: &str, model: Option<&str>, usage: &Usage) {
+        let cost =
+            usage.cost_usd.as_deref().and_then(parse_usd).or_else(|| {
+                model.and_then(|model| self.catalog().cost_nanos(provider, model, usage))
+            });
+        let mut tallies = self.tallies.lock().unwrap();
+        tallies.total.add(usage, cost);
+        tallies
+            .by_provider
+            .entry(provider.to_string())
+            .or_default()
+            .add(usage, cost);
+        if let Some(turn) = tallies.turns.last_mut() {
+            turn.add(usage, cost);
+        }
+    }
+
+    /// Costs so far.
+    pub fn report(&self) -> CostReport {
+        let tallies = self.tallies.lock().unwrap();
+        CostReport {
+            total: tallies.total.breakdown(),
+            by_provider: tallies
+                .by_provider
+                .iter()
+                .map(|(provider, tally)| (provider.clone(), tally.breakdown()))
+                .collect(),
+            turns: tallies.turns.iter().map(Tally::breakdown).collect(),
+        }
+    }
+
+    /// The current turn's costs, if a turn has begun.
+    pub fn current_turn(&self) -> Option<CostBreakdown> {
+        self.tallies
+            .lock()
+            .unwrap()
+            .turns
+            .last()
+            .map(Tally::breakdown)
+    }
+
+    fn observe(&self, event: &str, data: &Value) {
+        let fallback = !self.orchestrator_events.load(Ordering::SeqCst);
+        match event {
+            events::PROVIDER_REQUEST => self.orchestrator_events.store(true, Ordering::SeqCst),
+            events::PROVIDER_RESPONSE => self.observe_response(data),
+            events::PROVIDER_POST if fallback => self.observe_response(data),
+            _ => {}
+        }
+    }
+
+    fn observe_response(&self, data: &Value) {
+        let Some(response) = data.get("response") else {
+            return;
+        };
+        let Some(usage) = response
+            .get("usage")
+            .and_then(|u| serde_json::from_value::<Usage>(u.clone()).ok())
+        else {
+            return;
+        };
+        let model = response
+            .get("degradation")
+            .and_then(|d| d.get("actual"))
+            .or_else(|| data.get("model"))
+            .or_else(|| response.get("metadata").and_then(|m| m.get("model")))
+            .and_then(Value::as_str);
+        let provider = data
+            .get("provider")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        self.record(provider, model, &usage);
+    }
+}
+
+impl HookHandler for CostTracker {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        self.observe(event, &data);
+        Box::pin(async { Ok(HookResult::default()) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn usage(input: i64, output: i64) -> Usage {
+        serde_json::from_value(json!({
+            "input_tokens": input,
+            "output_tokens": output,
+            "total_tokens": input + output,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn amounts_round_trip_through_strings() {
+        assert_eq!(format_usd(47_832_000), "0.047832");
+        assert_eq!(format_usd(1_500_000_000), "1.500000");
+        assert_eq!(parse_usd("0.047832"), Some(47_832_000));
+        assert_eq!(parse_usd("2"), Some(2 * NANOS_PER_USD));
+        assert_eq!(parse_usd("-1"), None);
+        assert_eq!(parse_usd("abc"), None);
+    }
+
+    #[test]
+    fn most_specific_key_wins() {
+        let catalog = PricingCatalog::from_json(
+            r#"{"models": {
+                "claude-*": {"input_per_mtok": 1.0, "output_per_mtok": 1.0},
+                "claude-sonnet-*": {"input_per_mtok": 2.0, "output_per_mtok": 2.0},
+                "anthropic/claude-*": {"input_per_mtok": 3.0, "output_per_mtok": 3.0},
+                "claude-sonnet-4-5": {"input_per_mtok": 4.0, "output_per_mtok": 4.0}
+            }}"#,
+            "test",
+        )
+        .unwrap();
+        let rate = |provider, model| catalog.get(provider, model).map(|p| p.input_per_mtok);
+        assert_eq!(rate("other", "claude-haiku"), Some(1.0));
+        assert_eq!(rate("other", "claude-sonnet-4-5-20250929"), Some(2.0));
+        assert_eq!(rate("anthropic", "claude-sonnet-4-5-20250929"), Some(3.0));
+        assert_eq!(rate("other", "claude-sonnet-4-5"), Some(4.0));
+        assert_eq!(rate("other", "gpt-4o"), None);
+    }
+
+    #[test]
+    fn cache_tokens_default_to_the_input_rate() {
+        let mut usage = usage(1_000_000, 100_000);
+        usage.cache_read_tokens = Some(1_000_000);
+        let plain = ModelPricing::new(3.0, 15.0);
+        assert_eq!(format_usd(plain.cost_nanos(&usage)), "7.500000");
+        let cached = plain.with_cache_rates(0.3, 3.75);
+        assert_eq!(format_usd(cached.cost_nanos(&usage)), "4.800000");
+    }
+
+    #[tokio::test]
+    async fn tracker_breaks_costs_down_by_provider_and_turn() {
+        let tracker = Arc::new(CostTracker::new(
+            PricingCatalog::new().with_model("gpt-4o", ModelPricing::new(2.5, 10.0)),
+        ));
+        let hooks = HookRegistry::new();
+        tracker.install(&hooks);
+
+        tracker.begin_turn();
+        hooks
+            .emit(
+                events::PROVIDER_POST,
+                json!({"provider": "openai", "model": "gpt-4o", "response": {"usage": usage(1000, 100)}}),
+            )
+            .await;
+        tracker.begin_turn();
+        hooks
+            .emit(
+                events::PROVIDER_POST,
+                json!({"provider": "local", "model": "llama", "response": {"usage": usage(50, 5)}}),
+            )
+            .await;
+
+        let report = tracker.report();
+        assert_eq!(report.total.calls, 2);
+        assert_eq!(report.total.cost_usd, None);
+        assert_eq!(report.total.unpriced_calls, 1);
+        assert_eq!(
+            report.by_provider["openai"].cost_usd.as_deref(),
+            Some("0.003500")
+        );
+        assert_eq!(report.turns.len(), 2);
+        assert_eq!(report.turns[0].cost_usd.as_deref(), Some("0.003500"));
+        assert_eq!(report.turns[1].input_tokens, 50);
+
+        let mut status: SessionStatus =
+            serde_json::from_value(json!({"session_id": "s", "started_at": "now"})).unwrap();
+        report.apply_to(&mut status);
+        assert_eq!(status.total_input_tokens, 1050);
+        assert!(status.cost_usd.is_none());
+    }
+
+    #[test]
+    fn provider_reported_cost_wins_over_the_catalog() {
+        let tracker = CostTracker::new(PricingCatalog::new());
+        let mut reported = usage(10, 10);
+        reported.cost_usd = Some("0.25".into());
+        tracker.record("anthropic", Some("claude"), &reported);
+        assert_eq!(tracker.report().total.cost_usd.as_deref(), Some("0.250000"));
+    }
+}
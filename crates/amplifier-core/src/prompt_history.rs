@@ -0,0 +1,294 @@
+//! Per-session prompt history and duplicate resubmission detection.
+//!
+//! Every prompt passed to [`Session::execute`](crate::session::Session::execute)
+//! or [`Session::run_turn`](crate::session::Session::run_turn) is recorded
+//! with its SHA-256 hash, submission time and, once the turn succeeds, its
+//! output. Chat frontends with retry buttons tend to resubmit the same
+//! prompt seconds after the first: when a prompt's hash matches one
+//! submitted less than `duplicate_window_secs` earlier, the session emits
+//! [`PROMPT_DUPLICATE`](crate::events::PROMPT_DUPLICATE):
+//!
+//! ```json
+//! {"session_id": "...", "prompt_hash": "9f86d0...", "previous_submitted_at": "2026-01-01T00:00:00Z",
+//!  "age_ms": 1200, "replayed": false}
+//! ```
+//!
+//! With `replay_duplicates` set, a duplicate of a prompt that completed is
+//! not executed again: `execute()` returns the earlier output and
+//! `run_turn()` the earlier [`TurnResult`], and `replayed` is `true`.
+//! Duplicates of prompts that failed or are still running execute normally.
+//!
+//! Matching uses the prompt as submitted, before `prompt:submit` hooks
+//! rewrite it. Rewinding the session clears the history, since earlier
+//! results no longer follow from the conversation.
+//!
+//! Settings come from `session.prompt_history`:
+//!
+//! ```json
+//! {"session": {"prompt_history": {"capacity": 100, "duplicate_window_secs": 10,
+//!                                 "replay_duplicates": false}}}
+//! ```
+//!
+//! `duplicate_window_secs: 0` disables detection; `capacity: 0` disables the
+//! history altogether.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::turn::TurnResult;
+
+// ---------------------------------------------------------------------------
+// PromptHistoryConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.prompt_history` section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromptHistoryConfig {
+    /// Most prompts kept; the oldest are dropped first.
+    pub capacity: usize,
+    /// How long a prompt counts as a duplicate of an identical earlier one;
+    /// `0` disables detection.
+    pub duplicate_window_secs: u64,
+    /// Answer duplicates of completed prompts from the history instead of
+    /// executing them again.
+    pub replay_duplicates: bool,
+}
+
+impl Default for PromptHistoryConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 100,
+            duplicate_window_secs: 10,
+            replay_duplicates: false,
+        }
+    }
+}
+
+impl PromptHistoryConfig {
+    /// Read `session.prompt_history` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("prompt_history")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.prompt_history config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PromptRecord
+// ---------------------------------------------------------------------------
+
+/// One submitted prompt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptRecord {
+    /// Position in the session's submission order, starting at 1.
+    pub id: u64,
+    /// Hex SHA-256 of `prompt`.
+    pub prompt_hash: String,
+    pub prompt: String,
+    pub submitted_at: DateTime<Utc>,
+    /// The orchestrator's output, once the turn succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// The structured result, when the turn ran through `run_turn()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn: Option<TurnResult>,
+}
+
+/// Hex SHA-256 of `prompt`.
+pub fn prompt_hash(prompt: &str) -> String {
+    format!("{:x}", Sha256::digest(prompt.as_bytes()))
+}
+
+// ---------------------------------------------------------------------------
+// PromptHistory
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct Records {
+    entries: VecDeque<PromptRecord>,
+    next_id: u64,
+}
+
+/// A session's submitted prompts, oldest first.
+pub struct PromptHistory {
+    config: PromptHistoryConfig,
+    records: Mutex<Records>,
+}
+
+impl PromptHistory {
+    pub fn new(config: PromptHistoryConfig) -> Self {
+        Self {
+            config,
+            records: Mutex::new(Records::default()),
+        }
+    }
+
+    pub fn config(&self) -> &PromptHistoryConfig {
+        &self.config
+    }
+
+    /// The latest record of `prompt` submitted less than the duplicate
+    /// window before `now`.
+    pub fn duplicate_of(&self, prompt: &str, now: DateTime<Utc>) -> Option<PromptRecord> {
+        let window = chrono::Duration::seconds(
+            i64::try_from(self.config.duplicate_window_secs).unwrap_or(i64::MAX),
+        );
+        if window.is_zero() {
+            return None;
+        }
+        let hash = prompt_hash(prompt);
+        let records = self.records.lock().unwrap();
+        records
+            .entries
+            .iter()
+            .rev()
+            .take_while(|r| now.signed_duration_since(r.submitted_at) < window)
+            .find(|r| r.prompt_hash == hash)
+            .cloned()
+    }
+
+    /// Whether `previous` should be answered from the history.
+    pub fn replays(&self, previous: &PromptRecord) -> bool {
+        self.config.replay_duplicates && previous.output.is_some()
+    }
+
+    /// Record `prompt` as submitted at `now`, returning its id.
+    pub fn record(&self, prompt: &str, now: DateTime<Utc>) -> u64 {
+        let mut records = self.records.lock().unwrap();
+        records.next_id += 1;
+        let id = records.next_id;
+        if self.config.capacity == 0 {
+            return id;
+        }
+        if records.entries.len() == self.config.capacity {
+            records.entries.pop_front();
+        }
+        records.entries.push_back(PromptRecord {
+            id,
+            prompt_hash: prompt_hash(prompt),
+            prompt: prompt.to_string(),
+            submitted_at: now,
+            output: None,
+            turn: None,
+        });
+        id
+    }
+
+    /// Store the output of prompt `id`, if it is still in the history.
+    pub fn complete(&self, id: u64, output: &str) {
+        self.update(id, |record| record.output = Some(output.to_string()));
+    }
+
+    /// Store the structured result of prompt `id`, if it is still in the
+    /// history.
+    pub fn attach_turn(&self, id: u64, turn: &TurnResult) {
+        self.update(id, |record| record.turn = Some(turn.clone()));
+    }
+
+    fn update(&self, id: u64, apply: impl FnOnce(&mut PromptRecord)) {
+        let mut records = self.records.lock().unwrap();
+        if let Some(record) = records.entries.iter_mut().rev().find(|r| r.id == id) {
+            apply(record);
+        }
+    }
+
+    /// Every recorded prompt, oldest first.
+    pub fn entries(&self) -> Vec<PromptRecord> {
+        self.records
+            .lock()
+            .unwrap()
+            .entries
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Drop every record. Ids keep increasing.
+    pub fn clear(&self) {
+        self.records.lock().unwrap().entries.clear();
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn duplicates_are_matched_within_the_window() {
+        let history = PromptHistory::new(PromptHistoryConfig {
+            duplicate_window_secs: 10,
+            ..PromptHistoryConfig::default()
+        });
+        let first = history.record("hello", at(0));
+        history.record("other", at(5));
+
+        let duplicate = history.duplicate_of("hello", at(9)).unwrap();
+        assert_eq!(duplicate.id, first);
+        assert_eq!(duplicate.prompt_hash, prompt_hash("hello"));
+        assert!(history.duplicate_of("hello", at(10)).is_none());
+        assert!(history.duplicate_of("hello!", at(1)).is_none());
+
+        assert!(!history.replays(&duplicate));
+    }
+
+    #[test]
+    fn completed_prompts_replay_when_enabled() {
+        let history = PromptHistory::new(PromptHistoryConfig {
+            replay_duplicates: true,
+            ..PromptHistoryConfig::default()
+        });
+        let id = history.record("hello", at(0));
+        assert!(!history.replays(&history.duplicate_of("hello", at(1)).unwrap()));
+
+        history.complete(id, "hi there");
+        let duplicate = history.duplicate_of("hello", at(1)).unwrap();
+        assert!(history.replays(&duplicate));
+        assert_eq!(duplicate.output.as_deref(), Some("hi there"));
+    }
+
+    #[test]
+    fn capacity_bounds_the_history() {
+        let history = PromptHistory::new(PromptHistoryConfig {
+            capacity: 2,
+            ..PromptHistoryConfig::default()
+        });
+        for (i, prompt) in ["a", "b", "c"].into_iter().enumerate() {
+            history.record(prompt, at(i as i64));
+        }
+        let prompts: Vec<_> = history.entries().into_iter().map(|r| r.prompt).collect();
+        assert_eq!(prompts, ["b", "c"]);
+
+        history.clear();
+        assert!(history.entries().is_empty());
+        assert_eq!(history.record("d", at(3)), 4);
+    }
+
+    #[test]
+    fn zero_window_disables_detection() {
+        let history = PromptHistory::new(PromptHistoryConfig {
+            duplicate_window_secs: 0,
+            ..PromptHistoryConfig::default()
+        });
+        history.record("hello", at(0));
+        assert!(history.duplicate_of("hello", at(0)).is_none());
+    }
+}
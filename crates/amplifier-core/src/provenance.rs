@@ -0,0 +1,459 @@
+//! Where context messages came from.
+//!
+//! With `session.provenance` configured, the session wraps its context
+//! manager in a [`ProvenanceContext`], which records in each message it adds
+//! who produced it, in which turn and when:
+//!
+//! ```json
+//! {"role": "tool", "tool_call_id": "c1", "content": "...",
+//!  "metadata": {"provenance": {"origin": "tool:bash", "turn": 3,
+//!                              "timestamp": "2026-01-01T12:00:00Z"}}}
+//! ```
+//!
+//! | Origin        | Assigned to                                                       |
+//! |---------------|-------------------------------------------------------------------|
+//! | `tool:<name>` | tool results (role `tool`, or `tool_result` blocks)                |
+//! | `hook:<name>` | messages holding context injected by that hook handler            |
+//! | `injection`   | other `system` / `developer` messages, and context injected by several handlers at once |
+//! | `provider`    | other `assistant` messages                                         |
+//! | `user`        | other `user` messages                                              |
+//!
+//! Tool names come from the message's `name`, or else from the tool call
+//! with the same ID in an earlier assistant message. Hook injections are
+//! recognized by their text (see
+//! [`HookRegistry`](crate::hooks::HookRegistry#injection-sources)), so an
+//! orchestrator may wrap it in markup. The turn is the coordinator's
+//! [`turn_number()`](crate::coordinator::Coordinator::turn_number) and the
+//! timestamp comes from its clock.
+//!
+//! A provenance already present on a message is kept, so orchestrators that
+//! know better can set their own. Messages restored with `set_messages` are
+//! stored as given. Read the records back with
+//! [`ContextManager::get_provenance`] or [`Provenance::of`].
+//!
+//! ```json
+//! {"session": {"provenance": {}}}
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::coordinator::Coordinator;
+use crate::errors::ContextError;
+use crate::traits::{ContextManager, Provider};
+
+// ---------------------------------------------------------------------------
+// ProvenanceConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.provenance` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProvenanceConfig {
+    /// Set to `false` to keep the section but stop recording.
+    pub enabled: bool,
+}
+
+impl Default for ProvenanceConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl ProvenanceConfig {
+    /// Read `session.provenance` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("provenance"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.provenance config: {e}"))
+            .ok()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Origin / Provenance
+// ---------------------------------------------------------------------------
+
+/// Who produced a message. Serialized as `user`, `provider`, `tool:<name>`,
+/// `hook:<name>` or `injection`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub enum Origin {
+    User,
+    Provider,
+    Tool(String),
+    Hook(String),
+    Injection,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User => f.write_str("user"),
+            Self::Provider => f.write_str("provider"),
+            Self::Tool(name) => write!(f, "tool:{name}"),
+            Self::Hook(name) => write!(f, "hook:{name}"),
+            Self::Injection => f.write_str("injection"),
+        }
+    }
+}
+
+impl FromStr for Origin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("tool", name)) => Ok(Self::Tool(name.to_string())),
+            Some(("hook", name)) => Ok(Self::Hook(name.to_string())),
+            _ => match s {
+                "user" => Ok(Self::User),
+                "provider" => Ok(Self::Provider),
+                "injection" => Ok(Self::Injection),
+                _ => Err(format!("unknown message origin '{s}'")),
+            },
+        }
+    }
+}
+
+impl From<Origin> for String {
+    fn from(origin: Origin) -> Self {
+        origin.to_string()
+    }
+}
+
+impl TryFrom<String> for Origin {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Where a context message came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub origin: Origin,
+    /// The coordinator's turn number when the message was added.
+    pub turn: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Provenance {
+    /// `metadata` key holding a message's provenance.
+    pub const METADATA_KEY: &'static str = "provenance";
+
+    /// The provenance recorded on a JSON message, if any.
+    pub fn of(message: &Value) -> Option<Self> {
+        message
+            .get("metadata")
+            .and_then(|m| m.get(Self::METADATA_KEY))
+            .and_then(|p| serde_json::from_value(p.clone()).ok())
+    }
+
+    /// Record this provenance in `message.metadata`.
+    ///
+    /// Does nothing if `message` is not a JSON object.
+    pub fn apply(&self, message: &mut Value) {
+        let Some(object) = message.as_object_mut() else {
+            return;
+        };
+        let metadata = object
+            .entry("metadata")
+            .or_insert_with(|| Value::Object(Default::default()));
+        if !metadata.is_object() {
+            *metadata = Value::Object(Default::default());
+        }
+        metadata[Self::METADATA_KEY] = serde_json::to_value(self).expect("provenance serializes");
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ProvenanceContext
+// ---------------------------------------------------------------------------
+
+/// A [`ContextManager`] that records a [`Provenance`] on every message added
+/// through it.
+///
+/// Everything else passes through unchanged.
+pub struct ProvenanceContext {
+    inner: Arc<dyn ContextManager>,
+    coordinator: Arc<Coordinator>,
+    /// Tool call ID → tool name, from the assistant messages seen so far.
+    tool_calls: Mutex<HashMap<String, String>>,
+}
+
+impl ProvenanceContext {
+    /// Wrap `inner`, taking turns, clock and injections from `coordinator`.
+    pub fn new(inner: Arc<dyn ContextManager>, coordinator: Arc<Coordinator>) -> Self {
+        Self {
+            inner,
+            coordinator,
+            tool_calls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Stamp `message` unless it already carries a provenance.
+    fn stamp(&self, message: &mut Value) {
+        self.remember_tool_calls(message);
+        if Provenance::of(message).is_some() {
+            return;
+        }
+        Provenance {
+            origin: self.origin(message),
+            turn: self.coordinator.turn_number(),
+            timestamp: self.coordinator.clock().now_utc(),
+        }
+        .apply(message);
+    }
+
+    fn origin(&self, message: &Value) -> Origin {
+        let role = message.get("role").and_then(Value::as_str).unwrap_or("");
+        if let Some(call_id) = tool_result_id(message, role) {
+            let name = message
+                .get("name")
+                .and_then(Value::as_str)
+                .map(String::from)
+                .or_else(|| self.tool_calls.lock().unwrap().get(call_id).cloned())
+                .unwrap_or_else(|| "unknown".to_string());
+            return Origin::Tool(name);
+        }
+        let text = text_of(message);
+        if !text.is_empty() {
+            if let Some(mut handlers) = self.coordinator.hooks().take_injection(&text) {
+                return match handlers.len() {
+                    1 => Origin::Hook(handlers.remove(0)),
+                    _ => Origin::Injection,
+                };
+            }
+        }
+        match role {
+            "assistant" => Origin::Provider,
+            "system" | "developer" => Origin::Injection,
+            _ => Origin::User,
+        }
+    }
+
+    fn remember_tool_calls(&self, message: &Value) {
+        let calls = message
+            .get("tool_calls")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|call| {
+                let name = call
+                    .get("name")
+                    .or_else(|| call.get("tool"))
+                    .or_else(|| call.get("function").and_then(|f| f.get("name")));
+                Some((call.get("id")?.as_str()?, name?.as_str()?))
+            });
+        let blocks = content_blocks(message)
+            .filter(|b| b.get("type").and_then(Value::as_str) == Some("tool_call"))
+            .filter_map(|b| Some((b.get("id")?.as_str()?, b.get("name")?.as_str()?)));
+        let mut tool_calls = self.tool_calls.lock().unwrap();
+        for (id, name) in calls.chain(blocks) {
+            tool_calls.insert(id.to_string(), name.to_string());
+        }
+    }
+}
+
+/// The tool call ID `message` answers, if it is a tool result (`""` when
+/// the ID is missing).
+fn tool_result_id<'a>(message: &'a Value, role: &str) -> Option<&'a str> {
+    if role == "tool" {
+        return Some(
+            message
+                .get("tool_call_id")
+                .and_then(Value::as_str)
+                .unwrap_or_default(),
+        );
+    }
+    content_blocks(message)
+        .find(|b| b.get("type").and_then(Value::as_str) == Some("tool_result"))
+        .map(|b| {
+            b.get("tool_call_id")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        })
+}
+
+fn content_blocks(message: &Value) -> impl Iterator<Item = &Value> {
+    message
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+/// The text content of `message`: its string content, or its text blocks.
+fn text_of(message: &Value) -> String {
+    match message.get("content") {
+        Some(Value::String(text)) => text.clone(),
+        _ => content_blocks(message)
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+impl ContextManager for ProvenanceContext {
+    fn add_message(
+        &self,
+        mut message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.stamp(&mut message);
+        self.inner.add_message(message)
+    }
+
+    fn get_messages_for_request(
+        &self,
+        token_budget: Option<i64>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        self.inner.get_messages_for_request(token_budget, provider)
+    }
+
+    fn get_messages(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        self.inner.get_messages()
+    }
+
+    fn set_messages(
+        &self,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.set_messages(messages)
+    }
+
+    fn clear(&self) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.clear()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HookAction, HookResult};
+    use crate::testing::{FakeContextManager, FakeHookHandler};
+    use serde_json::json;
+
+    fn context() -> (ProvenanceContext, Arc<Coordinator>) {
+        let coordinator = Arc::new(Coordinator::new_for_test());
+        let context = ProvenanceContext::new(
+            Arc::new(FakeContextManager::new()),
+            Arc::clone(&coordinator),
+        );
+        (context, coordinator)
+    }
+
+    async fn origins(context: &ProvenanceContext) -> Vec<String> {
+        context
+            .get_provenance()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.map(|p| p.origin.to_string()).unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn origin_round_trips_through_its_string_form() {
+        for text in [
+            "user",
+            "provider",
+            "tool:bash",
+            "hook:lint:strict",
+            "injection",
+        ] {
+            let origin: Origin = text.parse().unwrap();
+            assert_eq!(origin.to_string(), text);
+        }
+        assert!("robot".parse::<Origin>().is_err());
+        assert_eq!(
+            serde_json::to_value(Origin::Tool("grep".into())).unwrap(),
+            json!("tool:grep")
+        );
+    }
+
+    #[tokio::test]
+    async fn messages_are_attributed_by_role_and_tool_call() {
+        let (context, coordinator) = context();
+        coordinator.reset_turn();
+
+        for message in [
+            json!({"role": "system", "content": "You are helpful."}),
+            json!({"role": "user", "content": "list files"}),
+            json!({"role": "assistant", "content": "", "tool_calls": [{"id": "c1", "tool": "bash", "arguments": {}}]}),
+            json!({"role": "tool", "tool_call_id": "c1", "content": "a.txt"}),
+            json!({"role": "assistant", "content": [{"type": "tool_call", "id": "c2", "name": "grep", "input": {}}]}),
+            json!({"role": "user", "content": [{"type": "tool_result", "tool_call_id": "c2", "output": "x"}]}),
+            json!({"role": "assistant", "content": "done"}),
+        ] {
+            context.add_message(message).await.unwrap();
+        }
+
+        assert_eq!(
+            origins(&context).await,
+            [
+                "injection",
+                "user",
+                "provider",
+                "tool:bash",
+                "provider",
+                "tool:grep",
+                "provider"
+            ]
+        );
+        let provenance = context.get_provenance().await.unwrap();
+        assert_eq!(provenance[0].as_ref().unwrap().turn, 1);
+    }
+
+    #[tokio::test]
+    async fn hook_injections_and_explicit_provenance() {
+        let (context, coordinator) = context();
+        let linter = Arc::new(FakeHookHandler::with_result(HookResult {
+            action: HookAction::InjectContext,
+            context_injection: Some("2 lint errors".into()),
+            ..Default::default()
+        }));
+        let _ = coordinator
+            .hooks()
+            .register("tool:post", linter, 0, Some("linter".into()));
+        coordinator.hooks().emit("tool:post", json!({})).await;
+
+        let mut explicit = json!({"role": "user", "content": "from the host"});
+        Provenance {
+            origin: Origin::Hook("host".into()),
+            turn: 7,
+            timestamp: Utc::now(),
+        }
+        .apply(&mut explicit);
+
+        for message in [
+            json!({"role": "user", "content": "<system-reminder>2 lint errors</system-reminder>"}),
+            json!({"role": "user", "content": "2 lint errors"}),
+            explicit,
+        ] {
+            context.add_message(message).await.unwrap();
+        }
+
+        assert_eq!(
+            origins(&context).await,
+            ["hook:linter", "user", "hook:host"]
+        );
+    }
+}
@@ -0,0 +1,1068 @@
+//! ProviderInvoker — hook-wrapped provider calls.
+//!
+//! Orchestrators call [`ProviderInvoker::complete`] instead of
+//! [`Provider::complete`] directly. The invoker emits
+//! [`PROVIDER_PRE`](crate::events::PROVIDER_PRE) before the call and
+//! [`PROVIDER_POST`](crate::events::PROVIDER_POST) after it, so hooks can
+//! act as request/response middleware.
+//!
+//! # Middleware Semantics
+//!
+//! | Event           | Payload                                  | `Modify` rewrites | `Deny` returns                    |
+//! |-----------------|------------------------------------------|-------------------|-----------------------------------|
+//! | `provider:pre`  | `{"provider": name, "request": {...}}`   | `request`         | `ProviderError::InvalidRequest`   |
+//! | `provider:post` | `{"provider": name, "response": {...}}`  | `response`        | `ProviderError::ContentFilter`    |
+//!
+//! A modified payload that no longer deserializes into a [`ChatRequest`] /
+//! [`ChatResponse`] is logged and ignored — the original value is used.
+//! Provider failures emit [`PROVIDER_ERROR`](crate::events::PROVIDER_ERROR)
+//! and are returned unchanged.
+//!
+//! # Turn Deadlines
+//!
+//! When a [`TurnDeadline`] is set, `request.timeout` is clamped to the time
+//! remaining (before and after `provider:pre`), the clamped value is reported
+//! as `timeout_ms` in the `provider:pre` payload, and the call itself is
+//! abandoned with `ProviderError::Timeout` at the deadline.
+//!
+//! # Request Conformance
+//!
+//! Before and after `provider:pre` the request is brought within the limits
+//! of the provider and model (see [`crate::request_conformance`]). Limits
+//! come from [`Provider::get_info`] and, when a model catalog is supplied
+//! with [`ProviderInvoker::with_models`], from the [`ModelInfo`] whose `id`
+//! matches `request.model`. Any adjustments are listed in the response's
+//! `metadata["request_adjustments"]` before `provider:post` runs.
+//!
+//! # Image Limits
+//!
+//! Alongside conformance, every image in the request is checked against the
+//! provider's and model's [`ImageLimits`] (see [`crate::images`]). Images
+//! that break a limit are re-encoded by the transcoder set with
+//! [`ProviderInvoker::with_image_transcoder`] (by default
+//! [`images::default_transcoder`]) and listed with the other
+//! adjustments; without one, or if re-encoding fails, the call returns
+//! `ProviderError::InvalidRequest` without reaching the provider.
+//!
+//! # Content Visibility
+//!
+//! Before and after `provider:pre`, internal content the provider is not
+//! allowed to see is stripped from the request (see [`crate::visibility`]),
+//! so neither `provider:pre` hooks nor the provider receive it unless the
+//! provider's [`VisibilityPolicy`](crate::visibility::VisibilityPolicy)
+//! allows it. What was removed is recorded in the response's
+//! `metadata["withheld_content"]`.
+//!
+//! # Tool Choice
+//!
+//! A `tool_choice` naming a tool is checked before and after `provider:pre`:
+//! the tool must be mounted (when the invoker knows the mounted tools, see
+//! [`ProviderInvoker::with_mounted_tools`]) and offered, and `tools` is cut
+//! down to it, otherwise the call returns `ProviderError::InvalidRequest`.
+//! A response whose tool calls break the choice is treated as a provider
+//! failure: `provider:error` is emitted and a retryable
+//! `ProviderError::Other` is returned (see [`crate::tool_choice`]).
+//!
+//! # Structured Output
+//!
+//! [`ProviderInvoker::complete_structured`] validates the final text of a
+//! request with a `json_schema` response format and re-asks on failure
+//! (see [`crate::structured_output`]).
+//!
+//! # Connections
+//!
+//! - Dispatches through [`HookRegistry::emit`](crate::hooks::HookRegistry::emit).
+//! - Used by the kernel-side provider entry points
+//!   ([`crate::grpc_server`], the WASM orchestrator host imports).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::coordinator::Coordinator;
+use crate::deadline::{self, TurnDeadline};
+use crate::errors::ProviderError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::images::{self, ImageLimits, ImageTranscoder};
+use crate::messages::{ChatRequest, ChatResponse, Message, MessageContent, ResponseFormat, Role};
+use crate::models::{HookAction, HookResult, ModelInfo};
+use crate::request_conformance::{self, RequestAdjustment, RequestLimits};
+use crate::structured_output::{
+    self, StructuredOutput, StructuredOutputConfig, StructuredOutputError,
+};
+use crate::token_counter::{HeuristicTokenCounter, TokenCounter};
+use crate::tool_choice::{self, ToolChoiceError};
+use crate::traits::Provider;
+use crate::visibility::{self, VisibilityConfig, VisibilityReport};
+
+/// Runs provider calls through the `provider:pre` / `provider:post` hooks.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example(
+/// #     provider: std::sync::Arc<dyn amplifier_core::Provider>,
+/// #     request: amplifier_core::ChatRequest,
+/// # ) {
+/// use amplifier_core::coordinator::Coordinator;
+/// use amplifier_core::provider_invoker::ProviderInvoker;
+///
+/// let coord = Coordinator::new_for_test();
+/// let invoker = ProviderInvoker::from_coordinator(&coord);
+/// let response = invoker.complete(provider.as_ref(), request).await;
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ProviderInvoker {
+    hooks: Arc<HookRegistry>,
+    deadline: Option<TurnDeadline>,
+    models: Option<Arc<[ModelInfo]>>,
+    token_counter: Arc<dyn TokenCounter>,
+    visibility: Arc<VisibilityConfig>,
+    image_transcoder: Option<Arc<dyn ImageTranscoder>>,
+    structured_retries: u32,
+    mounted_tools: Option<Arc<[String]>>,
+}
+
+impl ProviderInvoker {
+    /// Create an invoker that dispatches through `hooks`, with no deadline.
+    pub fn new(hooks: Arc<HookRegistry>) -> Self {
+        Self {
+            hooks,
+            deadline: None,
+            models: None,
+            token_counter: Arc::new(HeuristicTokenCounter::default()),
+            visibility: Arc::new(VisibilityConfig::default()),
+            image_transcoder: images::default_transcoder(),
+            structured_retries: StructuredOutputConfig::default().max_retries,
+            mounted_tools: None,
+        }
+    }
+
+    /// Create an invoker sharing the coordinator's hook registry, token
+    /// counter, visibility policies, mounted tools and current turn
+    /// deadline, re-asking
+    /// for structured output as set in `session.structured_output`.
+    pub fn from_coordinator(coordinator: &Coordinator) -> Self {
+        let structured = StructuredOutputConfig::from_session_config(coordinator.config());
+        Self::new(coordinator.hooks_shared())
+            .with_deadline(coordinator.turn_deadline())
+            .with_token_counter(coordinator.token_counter())
+            .with_visibility(coordinator.visibility_config())
+            .with_structured_retries(structured.max_retries)
+            .with_mounted_tools(coordinator.tool_names())
+    }
+
+    /// Bound every call made through this invoker by `deadline`.
+    pub fn with_deadline(mut self, deadline: Option<TurnDeadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Enforce per-model limits using `models` (typically the provider's
+    /// [`Provider::list_models`] result).
+    pub fn with_models(mut self, models: impl Into<Arc<[ModelInfo]>>) -> Self {
+        self.models = Some(models.into());
+        self
+    }
+
+    /// Estimate input size with `counter` when checking the context window.
+    pub fn with_token_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.token_counter = counter;
+        self
+    }
+
+    /// Strip internal content according to `config` (by default, nothing
+    /// internal is sent to any provider).
+    pub fn with_visibility(mut self, config: Arc<VisibilityConfig>) -> Self {
+        self.visibility = config;
+        self
+    }
+
+    /// Re-encode images that break the provider's limits with `transcoder`
+    /// instead of rejecting the request.
+    pub fn with_image_transcoder(mut self, transcoder: Arc<dyn ImageTranscoder>) -> Self {
+        self.image_transcoder = Some(transcoder);
+        self
+    }
+
+    /// Re-ask up to `retries` times when structured output does not match
+    /// its schema (default 2).
+    pub fn with_structured_retries(mut self, retries: u32) -> Self {
+        self.structured_retries = retries;
+        self
+    }
+
+    /// Require a tool named by `tool_choice` to be one of `names`.
+    pub fn with_mounted_tools(mut self, names: impl Into<Arc<[String]>>) -> Self {
+        self.mounted_tools = Some(names.into());
+        self
+    }
+
+    /// Call `provider.complete(request)` wrapped in `provider:pre` / `provider:post`.
+    ///
+    /// # Errors
+    ///
+    /// - `ProviderError::InvalidRequest` if a `provider:pre` hook denies the call,
+    ///   an image cannot be brought within the provider's limits, or
+    ///   `tool_choice` names a tool that is not mounted or offered
+    /// - `ProviderError::Other` (retryable) if the response's tool calls
+    ///   break `tool_choice`
+    /// - `ProviderError::ContentFilter` if a `provider:post` hook denies the response
+    /// - `ProviderError::Timeout` if the turn deadline is reached
+    /// - Any `ProviderError` from the provider itself
+    pub async fn complete(
+        &self,
+        provider: &dyn Provider,
+        mut request: ChatRequest,
+    ) -> Result<ChatResponse, ProviderError> {
+        let provider_name = provider.name().to_string();
+        let mut withheld = self.enforce_visibility(&provider_name, &mut request);
+        let mut adjustments = self.conform(provider, &mut request);
+        merge_adjustments(&mut adjustments, self.fit_images(provider, &mut request)?);
+        merge_adjustments(
+            &mut adjustments,
+            self.restrict_tools(provider, &mut request)?,
+        );
+        self.clamp_timeout(&mut request);
+        if self.deadline.as_ref().is_some_and(|d| d.is_expired()) {
+            return Err(deadline_error(&provider_name, request.model));
+        }
+
+        // -- provider:pre --
+        let mut pre_payload = serde_json::json!({
+            "provider": provider_name,
+            "request": request,
+        });
+        if let Some(timeout) = request.timeout {
+            pre_payload["timeout_ms"] = serde_json::json!((timeout * 1000.0) as u64);
+        }
+        let pre = self.hooks.emit(events::PROVIDER_PRE, pre_payload).await;
+        if pre.action == HookAction::Deny {
+            return Err(ProviderError::InvalidRequest {
+                message: denial_message("request", &pre),
+                provider: Some(provider_name),
+                model: request.model,
+                retry_after: None,
+            });
+        }
+        let mut request: ChatRequest = take_payload(&pre, "request").unwrap_or(request);
+        withheld.merge(self.enforce_visibility(&provider_name, &mut request));
+        merge_adjustments(&mut adjustments, self.conform(provider, &mut request));
+        merge_adjustments(&mut adjustments, self.fit_images(provider, &mut request)?);
+        merge_adjustments(
+            &mut adjustments,
+            self.restrict_tools(provider, &mut request)?,
+        );
+        self.clamp_timeout(&mut request);
+        let model = request.model.clone();
+        let choice = request.tool_choice.clone();
+
+        // -- the provider call itself --
+        let outcome = deadline::run_until(self.deadline.clone(), provider.complete(request))
+            .await
+            .unwrap_or_else(|| Err(deadline_error(&provider_name, model.clone())))
+            .and_then(|response| {
+                let calls = provider.parse_tool_calls(&response);
+                match tool_choice::check_calls(choice.as_ref(), &calls) {
+                    Ok(()) => Ok(response),
+                    Err(e) => Err(tool_choice_violation(e, &provider_name, model.clone())),
+                }
+            });
+        let mut response = match outcome {
+            Ok(response) => response,
+            Err(e) => {
+                self.hooks
+                    .emit(
+                        events::PROVIDER_ERROR,
+                        serde_json::json!({
+                            "provider": provider_name,
+                            "error": e.to_string(),
+                            "retryable": e.retryable(),
+                        }),
+                    )
+                    .await;
+                return Err(e);
+            }
+        };
+
+        if !adjustments.is_empty() {
+            response
+                .metadata
+                .get_or_insert_with(Default::default)
+                .insert(
+                    request_conformance::ADJUSTMENTS_METADATA_KEY.to_string(),
+                    serde_json::to_value(&adjustments).unwrap_or(Value::Null),
+                );
+        }
+        if !withheld.is_empty() {
+            response
+                .metadata
+                .get_or_insert_with(Default::default)
+                .insert(
+                    visibility::WITHHELD_METADATA_KEY.to_string(),
+                    serde_json::to_value(withheld).unwrap_or(Value::Null),
+                );
+        }
+
+        // -- provider:post --
+        let post = self
+            .hooks
+            .emit(
+                events::PROVIDER_POST,
+                serde_json::json!({
+                    "provider": provider_name,
+                    "model": model,
+                    "response": response,
+                }),
+            )
+            .await;
+        if post.action == HookAction::Deny {
+            return Err(ProviderError::ContentFilter {
+                message: denial_message("response", &post),
+                provider: Some(provider_name),
+                model,
+                retry_after: None,
+            });
+        }
+        Ok(take_payload(&post, "response").unwrap_or(response))
+    }
+
+    /// [`complete()`](Self::complete) a request with a `json_schema` response
+    /// format, returning its output validated and deserialized into `T`.
+    ///
+    /// Invalid output is answered with a correction request, up to the
+    /// configured number of retries (see [`crate::structured_output`]).
+    ///
+    /// # Errors
+    ///
+    /// - `StructuredOutputError::NoSchema` if the request has no
+    ///   `json_schema` response format
+    /// - `StructuredOutputError::Invalid` if the last attempt still did not
+    ///   match
+    /// - `StructuredOutputError::Provider` for any error from
+    ///   [`complete()`](Self::complete)
+    pub async fn complete_structured<T: DeserializeOwned>(
+        &self,
+        provider: &dyn Provider,
+        mut request: ChatRequest,
+    ) -> Result<StructuredOutput<T>, StructuredOutputError> {
+        let Some(ResponseFormat::JsonSchema { schema, .. }) = &request.response_format else {
+            return Err(StructuredOutputError::NoSchema);
+        };
+        let schema = Value::Object(schema.clone().into_iter().collect());
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let response = self.complete(provider, request.clone()).await?;
+            let text = structured_output::response_text(&response);
+            let violations = match structured_output::parse(&schema, &text) {
+                Ok((value, json)) => {
+                    return Ok(StructuredOutput {
+                        value,
+                        json,
+                        response,
+                        attempts,
+                    })
+                }
+                Err(violations) => violations,
+            };
+            if attempts > self.structured_retries {
+                return Err(StructuredOutputError::Invalid {
+                    attempts,
+                    violations,
+                    text,
+                });
+            }
+            log::debug!(
+                "Structured output from '{}' did not match its schema (attempt {attempts}); re-asking",
+                provider.name()
+            );
+            request.messages.push(Message {
+                role: Role::Assistant,
+                content: MessageContent::Text(text),
+                name: None,
+                tool_call_id: None,
+                metadata: None,
+                cache: None,
+                extensions: Default::default(),
+            });
+            request
+                .messages
+                .push(structured_output::reask_message(&violations));
+        }
+    }
+
+    /// Apply provider and model limits to `request`.
+    fn conform(
+        &self,
+        provider: &dyn Provider,
+        request: &mut ChatRequest,
+    ) -> Vec<RequestAdjustment> {
+        let limits = RequestLimits::resolve(&provider.get_info(), self.model_for(request));
+        request_conformance::conform(request, &limits, self.token_counter.as_ref())
+    }
+
+    /// Check the images in `request` against provider and model limits,
+    /// re-encoding those that break one when a transcoder is set.
+    fn fit_images(
+        &self,
+        provider: &dyn Provider,
+        request: &mut ChatRequest,
+    ) -> Result<Vec<RequestAdjustment>, ProviderError> {
+        let limits = ImageLimits::resolve(&provider.get_info(), self.model_for(request));
+        images::fit_request(request, &limits, self.image_transcoder.as_deref()).map_err(|e| {
+            ProviderError::InvalidRequest {
+                message: e.to_string(),
+                provider: Some(provider.name().to_string()),
+                model: request.model.clone(),
+                retry_after: None,
+            }
+        })
+    }
+
+    /// Enforce a `tool_choice` that names a tool on `request`.
+    fn restrict_tools(
+        &self,
+        provider: &dyn Provider,
+        request: &mut ChatRequest,
+    ) -> Result<Vec<RequestAdjustment>, ProviderError> {
+        match tool_choice::restrict(request, self.mounted_tools.as_deref()) {
+            Ok(adjustment) => Ok(adjustment.into_iter().collect()),
+            Err(e) => Err(ProviderError::InvalidRequest {
+                message: e.to_string(),
+                provider: Some(provider.name().to_string()),
+                model: request.model.clone(),
+                retry_after: None,
+            }),
+        }
+    }
+
+    /// The catalog entry for `request.model`, if a catalog was supplied.
+    fn model_for(&self, request: &ChatRequest) -> Option<&ModelInfo> {
+        let id = request.model.as_deref()?;
+        self.models.as_deref()?.iter().find(|m| m.id == id)
+    }
+
+    /// Strip the content `provider` may not see from `request`.
+    fn enforce_visibility(&self, provider: &str, request: &mut ChatRequest) -> VisibilityReport {
+        visibility::enforce(request, self.visibility.policy_for(provider))
+    }
+
+    /// Clamp `request.timeout` (seconds) to the time left in the turn.
+    fn clamp_timeout(&self, request: &mut ChatRequest) {
+        if let Some(deadline) = &self.deadline {
+            let requested = request
+                .timeout
+                .filter(|t| t.is_finite() && *t >= 0.0)
+                .map(Duration::from_secs_f64);
+            request.timeout = Some(deadline.clamp(requested).as_secs_f64());
+        }
+    }
+}
+
+fn deadline_error(provider: &str, model: Option<String>) -> ProviderError {
+    ProviderError::Timeout {
+        message: "turn deadline reached before the provider responded".into(),
+        provider: Some(provider.to_string()),
+        model,
+        retry_after: None,
+        delay_multiplier: None,
+    }
+}
+
+fn tool_choice_violation(
+    error: ToolChoiceError,
+    provider: &str,
+    model: Option<String>,
+) -> ProviderError {
+    ProviderError::Other {
+        message: format!("provider ignored tool_choice: {error}"),
+        provider: Some(provider.to_string()),
+        model,
+        retry_after: None,
+        status_code: None,
+        retryable: true,
+        delay_multiplier: None,
+    }
+}
+
+/// Add `later` to `adjustments`, folding a second change to the same field
+/// into the first so `requested` stays the caller's original value.
+fn merge_adjustments(adjustments: &mut Vec<RequestAdjustment>, later: Vec<RequestAdjustment>) {
+    for adjustment in later {
+        match adjustments.iter_mut().find(|a| a.field == adjustment.field) {
+            Some(existing) => {
+                existing.applied = adjustment.applied;
+                existing.reason = adjustment.reason;
+            }
+            None => adjustments.push(adjustment),
+        }
+    }
+}
+
+/// Extract and deserialize `result.data[key]`, if present and well-formed.
+///
+/// `emit()` returns the (possibly modified) event data on `Continue`; other
+/// actions carry no payload, in which case the caller keeps its original.
+fn take_payload<T: DeserializeOwned>(result: &HookResult, key: &str) -> Option<T> {
+    let value: Value = result.data.as_ref()?.get(key)?.clone();
+    match serde_json::from_value(value) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            log::warn!("Ignoring malformed '{key}' from provider hook: {e}");
+            None
+        }
+    }
+}
+
+fn denial_message(what: &str, result: &HookResult) -> String {
+    match result.reason.as_deref() {
+        Some(reason) => format!("provider {what} denied by hook: {reason}"),
+        None => format!("provider {what} denied by hook"),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    use crate::errors::HookError;
+    use crate::messages::{ContentBlock, Message, MessageContent, Role};
+    use crate::testing::{FakeHookHandler, FakeProvider};
+    use crate::traits::HookHandler;
+
+    fn request() -> ChatRequest {
+        ChatRequest {
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text("hi".into()),
+                name: None,
+                tool_call_id: None,
+                metadata: None,
+                cache: None,
+                extensions: HashMap::new(),
+            }],
+            tools: None,
+            response_format: None,
+            temperature: None,
+            top_p: None,
+            max_output_tokens: None,
+            conversation_id: None,
+            stream: None,
+            metadata: None,
+            model: Some("original-model".into()),
+            tool_choice: None,
+            stop: None,
+            reasoning_effort: None,
+            timeout: None,
+            extensions: HashMap::new(),
+        }
+    }
+
+    /// Handler that rewrites one field inside a nested payload object.
+    struct RewriteHandler {
+        payload_key: &'static str,
+        rewrite: fn(&mut Value),
+    }
+
+    impl HookHandler for RewriteHandler {
+        fn handle(
+            &self,
+            _event: &str,
+            data: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+            let mut map: HashMap<String, Value> = serde_json::from_value(data).unwrap();
+            (self.rewrite)(map.get_mut(self.payload_key).unwrap());
+            Box::pin(async move {
+                Ok(HookResult {
+                    action: HookAction::Modify,
+                    data: Some(map),
+                    ..Default::default()
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn emits_pre_and_post_around_call() {
+        let hooks = Arc::new(HookRegistry::new());
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::PROVIDER_PRE, recorder.clone(), 0, None);
+        let _ = hooks.register(events::PROVIDER_POST, recorder.clone(), 0, None);
+
+        let provider = FakeProvider::new("fake", "hello");
+        let invoker = ProviderInvoker::new(hooks);
+        let response = invoker.complete(&provider, request()).await.unwrap();
+
+        assert_eq!(response.content.len(), 1);
+        let events = recorder.recorded_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, "provider:pre");
+        assert_eq!(events[0].1["provider"], "fake");
+        assert_eq!(events[0].1["request"]["model"], "original-model");
+        assert_eq!(events[1].0, "provider:post");
+        assert_eq!(events[1].1["response"]["finish_reason"], "stop");
+    }
+
+    #[tokio::test]
+    async fn pre_modify_rewrites_request() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_PRE,
+            Arc::new(RewriteHandler {
+                payload_key: "request",
+                rewrite: |req| req["model"] = serde_json::json!("rewritten-model"),
+            }),
+            0,
+            None,
+        );
+
+        let provider = FakeProvider::new("fake", "hello");
+        ProviderInvoker::new(hooks)
+            .complete(&provider, request())
+            .await
+            .unwrap();
+
+        let calls = provider.recorded_calls();
+        assert_eq!(calls[0].model.as_deref(), Some("rewritten-model"));
+    }
+
+    #[tokio::test]
+    async fn post_modify_rewrites_response() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_POST,
+            Arc::new(RewriteHandler {
+                payload_key: "response",
+                rewrite: |resp| resp["content"][0]["text"] = serde_json::json!("[scrubbed]"),
+            }),
+            0,
+            None,
+        );
+
+        let provider = FakeProvider::new("fake", "secret");
+        let response = ProviderInvoker::new(hooks)
+            .complete(&provider, request())
+            .await
+            .unwrap();
+
+        match &response.content[0] {
+            ContentBlock::Text { text, .. } => assert_eq!(text, "[scrubbed]"),
+            other => panic!("expected text block, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn pre_deny_skips_provider() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_PRE,
+            Arc::new(FakeHookHandler::with_result(HookResult {
+                action: HookAction::Deny,
+                reason: Some("over budget".into()),
+                ..Default::default()
+            })),
+            0,
+            None,
+        );
+
+        let provider = FakeProvider::new("fake", "hello");
+        let err = ProviderInvoker::new(hooks)
+            .complete(&provider, request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::InvalidRequest { .. }));
+        assert!(err.to_string().contains("over budget"));
+        assert!(provider.recorded_calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn post_deny_returns_content_filter() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_POST,
+            Arc::new(FakeHookHandler::with_result(HookResult {
+                action: HookAction::Deny,
+                ..Default::default()
+            })),
+            0,
+            None,
+        );
+
+        let provider = FakeProvider::new("fake", "hello");
+        let err = ProviderInvoker::new(hooks)
+            .complete(&provider, request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::ContentFilter { .. }));
+        assert_eq!(provider.recorded_calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn malformed_modification_keeps_original_request() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_PRE,
+            Arc::new(RewriteHandler {
+                payload_key: "request",
+                rewrite: |req| *req = serde_json::json!("not a request"),
+            }),
+            0,
+            None,
+        );
+
+        let provider = FakeProvider::new("fake", "hello");
+        ProviderInvoker::new(hooks)
+            .complete(&provider, request())
+            .await
+            .unwrap();
+
+        let calls = provider.recorded_calls();
+        assert_eq!(calls[0].model.as_deref(), Some("original-model"));
+    }
+
+    /// Provider that never answers within a test's lifetime.
+    struct StalledProvider;
+
+    impl Provider for StalledProvider {
+        fn name(&self) -> &str {
+            "stalled"
+        }
+
+        fn get_info(&self) -> crate::models::ProviderInfo {
+            FakeProvider::new("stalled", "").get_info()
+        }
+
+        fn list_models(
+            &self,
+        ) -> Pin<
+            Box<
+                dyn Future<Output = Result<Vec<crate::models::ModelInfo>, ProviderError>>
+                    + Send
+                    + '_,
+            >,
+        > {
+            Box::pin(async { Ok(vec![]) })
+        }
+
+        fn complete(
+            &self,
+            _request: ChatRequest,
+        ) -> Pin<Box<dyn Future<Output = Result<ChatResponse, ProviderError>> + Send + '_>>
+        {
+            Box::pin(async {
+                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                unreachable!("deadline should have fired")
+            })
+        }
+
+        fn parse_tool_calls(&self, _response: &ChatResponse) -> Vec<crate::messages::ToolCall> {
+            vec![]
+        }
+    }
+
+    #[tokio::test]
+    async fn deadline_clamps_request_timeout() {
+        let hooks = Arc::new(HookRegistry::new());
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::PROVIDER_PRE, recorder.clone(), 0, None);
+
+        let mut req = request();
+        req.timeout = Some(600.0);
+        let provider = FakeProvider::new("fake", "hello");
+        ProviderInvoker::new(hooks)
+            .with_deadline(Some(TurnDeadline::after(Duration::from_secs(5))))
+            .complete(&provider, req)
+            .await
+            .unwrap();
+
+        let sent = provider.recorded_calls()[0].timeout.unwrap();
+        assert!(sent <= 5.0 && sent > 4.0, "timeout not clamped: {sent}");
+        let timeout_ms = recorder.recorded_events()[0].1["timeout_ms"]
+            .as_u64()
+            .unwrap();
+        assert!(timeout_ms <= 5000);
+    }
+
+    #[tokio::test]
+    async fn deadline_abandons_slow_provider() {
+        let hooks = Arc::new(HookRegistry::new());
+        let errors = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::PROVIDER_ERROR, errors.clone(), 0, None);
+
+        let err = ProviderInvoker::new(hooks)
+            .with_deadline(Some(TurnDeadline::after(Duration::from_millis(20))))
+            .complete(&StalledProvider, request())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::Timeout { .. }));
+        assert_eq!(errors.recorded_events().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn conforms_request_to_model_limits() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_PRE,
+            Arc::new(RewriteHandler {
+                payload_key: "request",
+                rewrite: |req| req["max_output_tokens"] = serde_json::json!(50_000),
+            }),
+            0,
+            None,
+        );
+        let model = ModelInfo {
+            id: "original-model".into(),
+            display_name: "Original".into(),
+            context_window: 100_000,
+            max_output_tokens: 4096,
+            capabilities: Vec::new(),
+            defaults: HashMap::from([("max_stop_sequences".to_string(), serde_json::json!(1))]),
+        };
+        let provider = FakeProvider::new("fake", "hello");
+        let mut req = request();
+        req.max_output_tokens = Some(8192);
+        req.stop = Some(vec!["A".into(), "B".into()]);
+
+        let response = ProviderInvoker::new(hooks)
+            .with_models(vec![model])
+            .complete(&provider, req)
+            .await
+            .unwrap();
+
+        let sent = &provider.recorded_calls()[0];
+        assert_eq!(sent.max_output_tokens, Some(4096));
+        assert_eq!(sent.stop, Some(vec!["A".to_string()]));
+        let adjustments = &response.metadata.unwrap()["request_adjustments"];
+        assert_eq!(adjustments.as_array().unwrap().len(), 2);
+        assert_eq!(adjustments[0]["field"], "max_output_tokens");
+        assert_eq!(adjustments[0]["requested"], 8192);
+        assert_eq!(adjustments[0]["applied"], 4096);
+        assert_eq!(adjustments[1]["field"], "stop");
+    }
+
+    #[tokio::test]
+    async fn conforming_request_adds_no_metadata() {
+        let provider = FakeProvider::new("fake", "hello");
+        let response = ProviderInvoker::new(Arc::new(HookRegistry::new()))
+            .complete(&provider, request())
+            .await
+            .unwrap();
+        assert!(response
+            .metadata
+            .is_none_or(|m| !m.contains_key("request_adjustments")));
+    }
+
+    #[tokio::test]
+    async fn oversized_image_is_rejected_before_the_provider() {
+        let model = ModelInfo {
+            id: "original-model".into(),
+            display_name: "Original".into(),
+            context_window: 100_000,
+            max_output_tokens: 4096,
+            capabilities: Vec::new(),
+            defaults: HashMap::from([("max_image_bytes".to_string(), serde_json::json!(4))]),
+        };
+        let provider = FakeProvider::new("fake", "hello");
+        let mut req = request();
+        req.messages[0].content = MessageContent::Blocks(vec![ContentBlock::Image {
+            source: crate::images::ImageSource::from_bytes("image/png", b"12345").to_map(),
+            visibility: None,
+            cache: None,
+            extensions: HashMap::new(),
+        }]);
+
+        let err = ProviderInvoker::new(Arc::new(HookRegistry::new()))
+            .with_models(vec![model])
+            .complete(&provider, req)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProviderError::InvalidRequest { .. }));
+        assert!(err.to_string().contains("over the 4-byte limit"));
+        assert!(provider.recorded_calls().is_empty());
+    }
+
+    fn with_internal_reasoning(mut req: ChatRequest) -> ChatRequest {
+        req.messages.push(
+            serde_json::from_value(serde_json::json!({
+                "role": "assistant",
+                "content": [
+                    {"type": "thinking", "thinking": "secret", "visibility": "internal"},
+                    {"type": "text", "text": "visible"}
+                ]
+            }))
+            .unwrap(),
+        );
+        req
+    }
+
+    #[tokio::test]
+    async fn internal_content_is_withheld_even_when_a_hook_adds_it() {
+        let hooks = Arc::new(HookRegistry::new());
+        let _ = hooks.register(
+            events::PROVIDER_PRE,
+            Arc::new(RewriteHandler {
+                payload_key: "request",
+                rewrite: |request| {
+                    request["messages"][0]["content"] = serde_json::json!([
+                        {"type": "text", "text": "injected", "visibility": "internal"},
+                        {"type": "text", "text": "hi"}
+                    ]);
+                },
+            }),
+            0,
+            None,
+        );
+        let provider = FakeProvider::new("third-party", "hello");
+        let response = ProviderInvoker::new(hooks)
+            .complete(&provider, with_internal_reasoning(request()))
+            .await
+            .unwrap();
+
+        let sent = serde_json::to_string(&provider.recorded_calls()[0]).unwrap();
+        assert!(!sent.contains("secret") && !sent.contains("injected"));
+        assert!(sent.contains("visible"));
+        let withheld = &response.metadata.unwrap()["withheld_content"];
+        assert_eq!(withheld["internal_blocks"], 2);
+    }
+
+    #[tokio::test]
+    async fn allowed_provider_receives_internal_content() {
+        let config = VisibilityConfig::default().with_provider(
+            "first-party",
+            crate::visibility::VisibilityPolicy {
+                allow_internal: true,
+                allow_redacted_thinking: false,
+            },
+        );
+        let provider = FakeProvider::new("first-party", "hello");
+        let response = ProviderInvoker::new(Arc::new(HookRegistry::new()))
+            .with_visibility(Arc::new(config))
+            .complete(&provider, with_internal_reasoning(request()))
+            .await
+            .unwrap();
+
+        let sent = serde_json::to_string(&provider.recorded_calls()[0]).unwrap();
+        assert!(sent.contains("secret"));
+        assert!(response
+            .metadata
+            .is_none_or(|m| !m.contains_key("withheld_content")));
+    }
+
+    fn structured_request() -> ChatRequest {
+        ChatRequest {
+            response_format: Some(ResponseFormat::JsonSchema {
+                schema: serde_json::from_value(serde_json::json!({
+                    "type": "object",
+                    "properties": {"answer": {"type": "integer"}},
+                    "required": ["answer"]
+                }))
+                .unwrap(),
+                strict: None,
+            }),
+            ..request()
+        }
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Answer {
+        answer: i64,
+    }
+
+    #[tokio::test]
+    async fn invalid_structured_output_is_re_asked() {
+        let invoker = ProviderInvoker::new(Arc::new(HookRegistry::new()));
+        let provider =
+            FakeProvider::new("p", r#"{"answer": 42}"#).with_responses([r#"{"answer": "42"}"#]);
+
+        let output = invoker
+            .complete_structured::<Answer>(&provider, structured_request())
+            .await
+            .unwrap();
+        assert_eq!(output.value.answer, 42);
+        assert_eq!(output.attempts, 2);
+
+        let retry = &provider.recorded_calls()[1];
+        assert_eq!(retry.messages.len(), 3);
+        assert_eq!(retry.messages[1].role, Role::Assistant);
+        let MessageContent::Text(correction) = &retry.messages[2].content else {
+            panic!("expected a text correction");
+        };
+        assert!(correction.contains("$.answer: expected integer, got string"));
+    }
+
+    #[tokio::test]
+    async fn structured_output_gives_up_after_the_retries() {
+        let invoker =
+            ProviderInvoker::new(Arc::new(HookRegistry::new())).with_structured_retries(1);
+        let provider = FakeProvider::new("p", "no idea");
+
+        let err = invoker
+            .complete_structured::<Answer>(&provider, structured_request())
+            .await
+            .unwrap_err();
+        let StructuredOutputError::Invalid { attempts, text, .. } = err else {
+            panic!("expected Invalid, got {err:?}");
+        };
+        assert_eq!((attempts, text.as_str()), (2, "no idea"));
+
+        assert!(matches!(
+            invoker
+                .complete_structured::<Answer>(&provider, request())
+                .await,
+            Err(StructuredOutputError::NoSchema)
+        ));
+    }
+
+    #[tokio::test]
+    async fn tool_choice_is_enforced_around_the_call() {
+        use crate::messages::{ToolChoice, ToolSpec};
+
+        let hooks = Arc::new(HookRegistry::new());
+        let errors = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::PROVIDER_ERROR, errors.clone(), 0, None);
+        let invoker = ProviderInvoker::new(hooks);
+        let provider = FakeProvider::new("fake", "no tools today");
+        let spec = |name: &str| ToolSpec {
+            name: name.into(),
+            parameters: HashMap::new(),
+            description: None,
+            extensions: HashMap::new(),
+        };
+        let mut req = request();
+        req.tools = Some(vec![spec("search"), spec("bash")]);
+        req.tool_choice = Some(ToolChoice::String("search".into()));
+
+        // Only the named tool is offered, and a reply without the call fails.
+        let err = invoker.complete(&provider, req.clone()).await.unwrap_err();
+        assert!(err.retryable());
+        assert!(err.to_string().contains("tool_choice"), "{err}");
+        let sent = &provider.recorded_calls()[0];
+        assert_eq!(sent.tools.as_ref().unwrap().len(), 1);
+        assert_eq!(errors.recorded_events().len(), 1);
+
+        // A tool that is not mounted never reaches the provider.
+        let err = invoker
+            .with_mounted_tools(vec!["bash".to_string()])
+            .complete(&provider, req)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "provider.invalid_request");
+        assert_eq!(provider.recorded_calls().len(), 1);
+    }
+}
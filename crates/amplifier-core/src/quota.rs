@@ -0,0 +1,589 @@
+//! Per-session resource quotas.
+//!
+//! Hosts that multiplex many users over one process cap each session with
+//! `session.quota`:
+//!
+//! ```json
+//! {"session": {"quota": {
+//!     "max_tool_calls": 200,
+//!     "max_provider_calls": 50,
+//!     "max_total_tokens": 500000,
+//!     "max_duration_secs": 3600
+//! }}}
+//! ```
+//!
+//! Every limit is optional. A [`QuotaEnforcer`] counts usage from the
+//! session's hook events:
+//!
+//! | Resource         | Counted on                                                    |
+//! |------------------|---------------------------------------------------------------|
+//! | `tool_calls`     | each `tool:pre`                                               |
+//! | `provider_calls` | each `provider:request`, or `provider:pre` when the orchestrator never emits `provider:request` |
+//! | `total_tokens`   | `usage.total_tokens` of each `provider:response`, or `provider:post` under the same fallback |
+//! | `duration`       | wall-clock time since the session was created                 |
+//!
+//! The `provider:pre` / `provider:post` fallback keeps an orchestrator that
+//! both emits `provider:request` and routes through the kernel's
+//! [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) from being
+//! counted twice.
+//!
+//! # Enforcement
+//!
+//! - When a resource first reaches 80% of its limit, `quota:warning` is
+//!   emitted once with `{resource, limit, used}`.
+//! - A `tool:pre`, `provider:request` or `provider:pre` that would go over
+//!   its limit is denied, and once any quota is breached every later one is
+//!   denied too. Tokens are only known after a response, so a token breach
+//!   takes effect from the next call.
+//! - [`Session::execute`](crate::session::Session::execute) runs the
+//!   orchestrator through [`QuotaEnforcer::run`]: a turn is refused once a
+//!   quota is breached, is abandoned when the duration limit is reached, and
+//!   fails with [`SessionError::QuotaExceeded`] if a quota was breached while
+//!   it ran.
+//!
+//! The enforcer's hooks run in [`HookPhase::PreValidation`], so a tool call
+//! counts against the quota even if a later hook denies it.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clock::Clock;
+use crate::deadline::{self, TurnDeadline};
+use crate::errors::{AmplifierError, HookError, SessionError};
+use crate::events;
+use crate::hooks::{HookPhase, HookRegistry};
+use crate::models::{HookAction, HookResult};
+use crate::traits::HookHandler;
+
+/// Fraction of a limit at which `quota:warning` is emitted.
+pub const WARNING_RATIO: f64 = 0.8;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// The `session.quota` section.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaConfig {
+    #[serde(default)]
+    pub max_tool_calls: Option<u64>,
+    #[serde(default)]
+    pub max_provider_calls: Option<u64>,
+    #[serde(default)]
+    pub max_total_tokens: Option<u64>,
+    #[serde(default)]
+    pub max_duration_secs: Option<f64>,
+}
+
+impl QuotaConfig {
+    /// Read `session.quota` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("quota"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.quota config: {e}"))
+            .ok()
+    }
+
+    /// Whether any limit is set.
+    pub fn is_active(&self) -> bool {
+        QuotaResource::ALL.iter().any(|r| self.limit(*r).is_some())
+    }
+
+    /// The limit for `resource` (milliseconds for [`QuotaResource::Duration`]).
+    pub fn limit(&self, resource: QuotaResource) -> Option<u64> {
+        match resource {
+            QuotaResource::ToolCalls => self.max_tool_calls,
+            QuotaResource::ProviderCalls => self.max_provider_calls,
+            QuotaResource::TotalTokens => self.max_total_tokens,
+            QuotaResource::Duration => self
+                .max_duration_secs
+                .map(|secs| (secs.max(0.0) * 1000.0) as u64),
+        }
+    }
+}
+
+/// A resource a quota limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaResource {
+    ToolCalls,
+    ProviderCalls,
+    TotalTokens,
+    /// Wall-clock milliseconds since the session was created.
+    Duration,
+}
+
+impl QuotaResource {
+    pub const ALL: [QuotaResource; 4] = [
+        Self::ToolCalls,
+        Self::ProviderCalls,
+        Self::TotalTokens,
+        Self::Duration,
+    ];
+
+    /// The name used in events and errors, e.g. `"tool_calls"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ToolCalls => "tool_calls",
+            Self::ProviderCalls => "provider_calls",
+            Self::TotalTokens => "total_tokens",
+            Self::Duration => "duration",
+        }
+    }
+}
+
+/// Usage counted so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub tool_calls: u64,
+    pub provider_calls: u64,
+    pub total_tokens: u64,
+    pub elapsed_ms: u64,
+}
+
+/// The first quota a session went over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaBreach {
+    pub resource: QuotaResource,
+    pub limit: u64,
+    pub used: u64,
+}
+
+impl QuotaBreach {
+    pub fn to_error(self) -> SessionError {
+        SessionError::QuotaExceeded {
+            resource: self.resource.as_str().to_string(),
+            limit: self.limit,
+            used: self.used,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// QuotaEnforcer
+// ---------------------------------------------------------------------------
+
+/// Counts a session's usage against its [`QuotaConfig`].
+pub struct QuotaEnforcer {
+    config: QuotaConfig,
+    clock: Arc<dyn Clock>,
+    started: Instant,
+    tool_calls: AtomicU64,
+    provider_calls: AtomicU64,
+    total_tokens: AtomicU64,
+    /// Set once `provider:request` is seen; disables the invoker fallback.
+    orchestrator_events: AtomicBool,
+    warned: Mutex<HashSet<QuotaResource>>,
+    breach: Mutex<Option<QuotaBreach>>,
+}
+
+impl QuotaEnforcer {
+    /// Start counting now, measuring duration on `clock`.
+    pub fn new(config: QuotaConfig, clock: Arc<dyn Clock>) -> Self {
+        let started = clock.now();
+        Self {
+            config,
+            clock,
+            started,
+            tool_calls: AtomicU64::new(0),
+            provider_calls: AtomicU64::new(0),
+            total_tokens: AtomicU64::new(0),
+            orchestrator_events: AtomicBool::new(false),
+            warned: Mutex::new(HashSet::new()),
+            breach: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &QuotaConfig {
+        &self.config
+    }
+
+    /// Register the counting hooks on `hooks` in [`HookPhase::PreValidation`]
+    /// as `"quota"`.
+    pub fn install(self: &Arc<Self>, hooks: &Arc<HookRegistry>) {
+        let handler = Arc::new(QuotaHook {
+            enforcer: Arc::clone(self),
+            hooks: Arc::downgrade(hooks),
+        });
+        for event in [
+            events::TOOL_PRE,
+            events::PROVIDER_REQUEST,
+            events::PROVIDER_PRE,
+            events::PROVIDER_RESPONSE,
+            events::PROVIDER_POST,
+        ] {
+            let _ = hooks.register_in_phase(
+                event,
+                handler.clone(),
+                HookPhase::PreValidation,
+                0,
+                Some("quota".into()),
+            );
+        }
+    }
+
+    /// Usage counted so far.
+    pub fn usage(&self) -> QuotaUsage {
+        QuotaUsage {
+            tool_calls: self.used(QuotaResource::ToolCalls),
+            provider_calls: self.used(QuotaResource::ProviderCalls),
+            total_tokens: self.used(QuotaResource::TotalTokens),
+            elapsed_ms: self.used(QuotaResource::Duration),
+        }
+    }
+
+    /// Usage of `resource` (milliseconds for [`QuotaResource::Duration`]).
+    pub fn used(&self, resource: QuotaResource) -> u64 {
+        match resource {
+            QuotaResource::ToolCalls => self.tool_calls.load(Ordering::SeqCst),
+            QuotaResource::ProviderCalls => self.provider_calls.load(Ordering::SeqCst),
+            QuotaResource::TotalTokens => self.total_tokens.load(Ordering::SeqCst),
+            QuotaResource::Duration => self.elapsed().as_millis() as u64,
+        }
+    }
+
+    /// The first quota breached, if any.
+    pub fn breach(&self) -> Option<QuotaBreach> {
+        *self.breach.lock().unwrap()
+    }
+
+    /// Fail if a quota has been breached (checking the duration limit now).
+    ///
+    /// # Errors
+    ///
+    /// `SessionError::QuotaExceeded` for the first quota breached.
+    pub fn check(&self) -> Result<(), SessionError> {
+        self.check_duration();
+        match self.breach() {
+            Some(breach) => Err(breach.to_error()),
+            None => Ok(()),
+        }
+    }
+
+    /// Run one turn under the quota.
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::QuotaExceeded` if a quota was already breached (`fut`
+    ///   is not polled), the duration limit is reached while `fut` runs
+    ///   (`fut` is dropped), or a quota was breached during the turn
+    /// - Any error from `fut`
+    pub async fn run<T>(
+        &self,
+        fut: impl Future<Output = Result<T, AmplifierError>>,
+    ) -> Result<T, AmplifierError> {
+        self.check()?;
+        let remaining = self
+            .config
+            .limit(QuotaResource::Duration)
+            .map(|limit| Duration::from_millis(limit).saturating_sub(self.elapsed()));
+        let deadline = remaining.map(|r| TurnDeadline::after_on(Arc::clone(&self.clock), r));
+        let outcome = deadline::run_until(deadline, fut).await;
+        if outcome.is_none() {
+            self.record_breach(QuotaResource::Duration, self.used(QuotaResource::Duration));
+        }
+        self.check()?;
+        outcome.unwrap_or_else(|| unreachable!("a duration breach was just recorded"))
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.clock.now().saturating_duration_since(self.started)
+    }
+
+    fn check_duration(&self) {
+        let used = self.used(QuotaResource::Duration);
+        if self
+            .config
+            .limit(QuotaResource::Duration)
+            .is_some_and(|limit| used >= limit)
+        {
+            self.record_breach(QuotaResource::Duration, used);
+        }
+    }
+
+    fn record_breach(&self, resource: QuotaResource, used: u64) {
+        let Some(limit) = self.config.limit(resource) else {
+            return;
+        };
+        self.breach.lock().unwrap().get_or_insert(QuotaBreach {
+            resource,
+            limit,
+            used,
+        });
+    }
+
+    /// Add `amount` to a counter. Returns the new usage and whether it is
+    /// over the limit.
+    fn charge(&self, resource: QuotaResource, amount: u64) -> (u64, bool) {
+        let counter = match resource {
+            QuotaResource::ToolCalls => &self.tool_calls,
+            QuotaResource::ProviderCalls => &self.provider_calls,
+            QuotaResource::TotalTokens => &self.total_tokens,
+            QuotaResource::Duration => return (self.used(resource), false),
+        };
+        let used = counter.fetch_add(amount, Ordering::SeqCst) + amount;
+        let over = self.config.limit(resource).is_some_and(|l| used > l);
+        if over {
+            self.record_breach(resource, used);
+        }
+        (used, over)
+    }
+
+    /// Resources newly at or past [`WARNING_RATIO`] of their limit, with
+    /// their limit and usage. Each resource is returned at most once.
+    fn take_warnings(&self) -> Vec<(QuotaResource, u64, u64)> {
+        let mut warned = self.warned.lock().unwrap();
+        QuotaResource::ALL
+            .into_iter()
+            .filter_map(|resource| {
+                let limit = self.config.limit(resource)?;
+                let used = self.used(resource);
+                let due = used as f64 >= limit as f64 * WARNING_RATIO;
+                (due && warned.insert(resource)).then_some((resource, limit, used))
+            })
+            .collect()
+    }
+
+    /// Count one event. Returns a denial reason for calls that must not run.
+    fn observe(&self, event: &str, data: &Value) -> Option<String> {
+        let fallback = !self.orchestrator_events.load(Ordering::SeqCst);
+        let (resource, over) = match event {
+            events::TOOL_PRE => self.charge_call(QuotaResource::ToolCalls),
+            events::PROVIDER_REQUEST => {
+                self.orchestrator_events.store(true, Ordering::SeqCst);
+                self.charge_call(QuotaResource::ProviderCalls)
+            }
+            events::PROVIDER_PRE if fallback => self.charge_call(QuotaResource::ProviderCalls),
+            events::PROVIDER_PRE => (Some(QuotaResource::ProviderCalls), false),
+            events::PROVIDER_RESPONSE => {
+                self.charge(QuotaResource::TotalTokens, response_tokens(data));
+                (None, false)
+            }
+            events::PROVIDER_POST if fallback => {
+                self.charge(QuotaResource::TotalTokens, response_tokens(data));
+                (None, false)
+            }
+            _ => (None, false),
+        };
+        self.check_duration();
+        let resource = resource?;
+        if over {
+            let limit = self.config.limit(resource).unwrap_or_default();
+            return Some(format!(
+                "Session quota exceeded: {} limit is {limit}",
+                resource.as_str()
+            ));
+        }
+        self.breach().map(|breach| {
+            format!(
+                "Session quota exceeded: {} limit is {}",
+                breach.resource.as_str(),
+                breach.limit
+            )
+        })
+    }
+
+    /// Charge one call, unless a quota is already breached.
+    fn charge_call(&self, resource: QuotaResource) -> (Option<QuotaResource>, bool) {
+        if self.breach().is_some() {
+            return (Some(resource), false);
+        }
+        let (_, over) = self.charge(resource, 1);
+        (Some(resource), over)
+    }
+}
+
+/// `response.usage.total_tokens` of a provider response payload.
+fn response_tokens(data: &Value) -> u64 {
+    data.get("response")
+        .and_then(|r| r.get("usage"))
+        .and_then(|u| u.get("total_tokens"))
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// The hook handler registered by [`QuotaEnforcer::install`].
+///
+/// Holds the registry weakly, since the registry owns the handler.
+struct QuotaHook {
+    enforcer: Arc<QuotaEnforcer>,
+    hooks: Weak<HookRegistry>,
+}
+
+impl HookHandler for QuotaHook {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        let denial = self.enforcer.observe(event, &data);
+        let warnings = self.enforcer.take_warnings();
+        Box::pin(async move {
+            if let Some(hooks) = self.hooks.upgrade() {
+                for (resource, limit, used) in warnings {
+                    hooks
+                        .emit(
+                            events::QUOTA_WARNING,
+                            serde_json::json!({
+                                "resource": resource.as_str(),
+                                "limit": limit,
+                                "used": used,
+                            }),
+                        )
+                        .await;
+                }
+            }
+            Ok(match denial {
+                Some(reason) => HookResult {
+                    action: HookAction::Deny,
+                    reason: Some(reason),
+                    ..Default::default()
+                },
+                None => HookResult::default(),
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeHookHandler, ManualClock};
+
+    fn enforcer(config: Value, clock: Arc<dyn Clock>) -> (Arc<QuotaEnforcer>, Arc<HookRegistry>) {
+        let config: QuotaConfig = serde_json::from_value(config).unwrap();
+        let enforcer = Arc::new(QuotaEnforcer::new(config, clock));
+        let hooks = Arc::new(HookRegistry::new());
+        enforcer.install(&hooks);
+        (enforcer, hooks)
+    }
+
+    #[test]
+    fn config_section_is_optional() {
+        let config: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "session": {"quota": {"max_tool_calls": 3, "max_duration_secs": 1.5}}
+        }))
+        .unwrap();
+        let quota = QuotaConfig::from_session_config(&config).unwrap();
+        assert!(quota.is_active());
+        assert_eq!(quota.limit(QuotaResource::ToolCalls), Some(3));
+        assert_eq!(quota.limit(QuotaResource::Duration), Some(1500));
+        assert_eq!(quota.limit(QuotaResource::TotalTokens), None);
+        assert!(QuotaConfig::from_session_config(&HashMap::new()).is_none());
+        assert!(!QuotaConfig::default().is_active());
+    }
+
+    #[tokio::test]
+    async fn tool_calls_warn_at_80_percent_then_deny() {
+        let (enforcer, hooks) = enforcer(
+            serde_json::json!({"max_tool_calls": 5}),
+            crate::clock::system(),
+        );
+        let warnings = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::QUOTA_WARNING, warnings.clone(), 0, None);
+
+        for _ in 0..5 {
+            let result = hooks.emit(events::TOOL_PRE, serde_json::json!({})).await;
+            assert_eq!(result.action, HookAction::Continue);
+        }
+        let seen = warnings.recorded_events();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].1["resource"], "tool_calls");
+        assert_eq!(seen[0].1["used"], 4);
+
+        let result = hooks.emit(events::TOOL_PRE, serde_json::json!({})).await;
+        assert_eq!(result.action, HookAction::Deny);
+        assert!(result.reason.unwrap().contains("tool_calls"));
+        assert_eq!(
+            enforcer.breach(),
+            Some(QuotaBreach {
+                resource: QuotaResource::ToolCalls,
+                limit: 5,
+                used: 6
+            })
+        );
+        assert!(matches!(
+            enforcer.check(),
+            Err(SessionError::QuotaExceeded { ref resource, .. }) if resource == "tool_calls"
+        ));
+    }
+
+    #[tokio::test]
+    async fn tokens_are_counted_once_and_block_later_calls() {
+        let (enforcer, hooks) = enforcer(
+            serde_json::json!({"max_total_tokens": 100}),
+            crate::clock::system(),
+        );
+        let response = serde_json::json!({"response": {"usage": {"total_tokens": 60}}});
+
+        hooks
+            .emit(events::PROVIDER_REQUEST, serde_json::json!({}))
+            .await;
+        hooks
+            .emit(events::PROVIDER_PRE, serde_json::json!({}))
+            .await;
+        hooks.emit(events::PROVIDER_POST, response.clone()).await;
+        hooks
+            .emit(events::PROVIDER_RESPONSE, response.clone())
+            .await;
+        assert_eq!(enforcer.usage().provider_calls, 1);
+        assert_eq!(enforcer.usage().total_tokens, 60);
+        assert!(enforcer.breach().is_none());
+
+        hooks.emit(events::PROVIDER_RESPONSE, response).await;
+        assert_eq!(
+            enforcer.breach().unwrap().resource,
+            QuotaResource::TotalTokens
+        );
+        let result = hooks
+            .emit(events::PROVIDER_REQUEST, serde_json::json!({}))
+            .await;
+        assert_eq!(result.action, HookAction::Deny);
+        assert_eq!(enforcer.usage().provider_calls, 1);
+    }
+
+    #[tokio::test]
+    async fn run_abandons_turn_at_duration_limit() {
+        let clock = ManualClock::default();
+        let (enforcer, _hooks) = enforcer(
+            serde_json::json!({"max_duration_secs": 10}),
+            Arc::new(clock.clone()),
+        );
+
+        let ok = enforcer.run(async { Ok::<_, AmplifierError>(1) }).await;
+        assert_eq!(ok.unwrap(), 1);
+
+        let sleeper = clock.clone();
+        let run = enforcer.run(async move {
+            sleeper.sleep(Duration::from_secs(60)).await;
+            Ok::<_, AmplifierError>(2)
+        });
+        let advance = async {
+            tokio::task::yield_now().await;
+            clock.advance(Duration::from_secs(11));
+        };
+        let (outcome, ()) = tokio::join!(run, advance);
+        assert!(matches!(
+            outcome,
+            Err(AmplifierError::Session(SessionError::QuotaExceeded { ref resource, limit: 10_000, .. }))
+                if resource == "duration"
+        ));
+
+        let refused = enforcer.run(async { Ok::<_, AmplifierError>(3) }).await;
+        assert!(refused.is_err());
+    }
+}
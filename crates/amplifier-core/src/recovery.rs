@@ -0,0 +1,397 @@
+//! Error recovery rules for orchestrator turns.
+//!
+//! Orchestrators decide what to do when a provider call or a tool fails.
+//! [`TurnRecoveryPolicy`] writes those rules down once per session, and
+//! [`TurnRecovery`] applies them inside a turn:
+//!
+//! | Failure  | Decision                                                        |
+//! |----------|-----------------------------------------------------------------|
+//! | Provider | retry while the error is retryable and fewer than `max_consecutive_provider_failures` calls in a row have failed; abort otherwise |
+//! | Tool     | `on_tool_failure`: abort the turn, report the error to the model, or ask the user which of the two |
+//!
+//! Every decision is emitted as
+//! [`ORCHESTRATOR_RECOVERY`](crate::events::ORCHESTRATOR_RECOVERY), so a
+//! [`TurnRecorder`](crate::turn::TurnRecorder) lists it in
+//! [`TurnResult::recovery_attempts`](crate::turn::TurnResult::recovery_attempts).
+//! Provider retries are also reported as
+//! [`OrchestratorStatus::RetryingProvider`].
+//!
+//! Native orchestrators get a fresh tracker per turn from
+//! [`Coordinator::turn_recovery`](crate::coordinator::Coordinator::turn_recovery);
+//! orchestrators in other languages read the policy from the coordinator
+//! dict's `turn_recovery` key.
+//!
+//! # Configuration
+//!
+//! ```json
+//! {"session": {"turn_recovery": {"max_consecutive_provider_failures": 3, "on_tool_failure": "ask_user"}}}
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::approval::ApprovalGate;
+use crate::errors::ProviderError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::models::ApprovalRequest;
+use crate::orchestrator_status::{self, OrchestratorStatus};
+use crate::traits::ApprovalProvider;
+
+// ---------------------------------------------------------------------------
+// TurnRecoveryPolicy
+// ---------------------------------------------------------------------------
+
+/// What to do when a tool call fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolFailureAction {
+    /// End the turn with the tool's error.
+    Abort,
+    /// Return the error to the model as the tool's result and continue.
+    #[default]
+    ReportToModel,
+    /// Ask the user through the approval provider: approval continues as
+    /// [`ReportToModel`](Self::ReportToModel), denial aborts.
+    AskUser,
+}
+
+/// The `session.turn_recovery` section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TurnRecoveryPolicy {
+    /// Provider calls that may fail in a row before the turn is aborted.
+    pub max_consecutive_provider_failures: u32,
+    pub on_tool_failure: ToolFailureAction,
+}
+
+impl Default for TurnRecoveryPolicy {
+    fn default() -> Self {
+        Self {
+            max_consecutive_provider_failures: 3,
+            on_tool_failure: ToolFailureAction::ReportToModel,
+        }
+    }
+}
+
+impl TurnRecoveryPolicy {
+    /// Read `session.turn_recovery` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("turn_recovery")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.turn_recovery config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Decisions
+// ---------------------------------------------------------------------------
+
+/// What the orchestrator should do about a failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// Call the provider again.
+    RetryProvider,
+    /// Give the error to the model as the tool result and keep going.
+    ReportToModel,
+    /// End the turn with the error.
+    AbortTurn,
+}
+
+/// What failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    Provider,
+    Tool,
+}
+
+/// One recovery decision, as emitted on `orchestrator:recovery` and listed
+/// in a [`TurnResult`](crate::turn::TurnResult).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryAttempt {
+    pub kind: FailureKind,
+    /// Provider or tool name.
+    pub target: String,
+    pub error: String,
+    pub action: RecoveryAction,
+    /// Consecutive provider failures so far, or 1 for a tool failure.
+    pub failures: u32,
+}
+
+// ---------------------------------------------------------------------------
+// TurnRecovery
+// ---------------------------------------------------------------------------
+
+/// Applies a [`TurnRecoveryPolicy`] to the failures of one turn.
+pub struct TurnRecovery {
+    policy: TurnRecoveryPolicy,
+    hooks: Arc<HookRegistry>,
+    approval: Option<(ApprovalGate, Arc<dyn ApprovalProvider>)>,
+    consecutive_provider_failures: Mutex<u32>,
+}
+
+impl TurnRecovery {
+    /// A tracker reporting on `hooks`. Without
+    /// [`with_approval`](Self::with_approval), `ask_user` falls back to
+    /// reporting the error to the model.
+    pub fn new(policy: TurnRecoveryPolicy, hooks: Arc<HookRegistry>) -> Self {
+        Self {
+            policy,
+            hooks,
+            approval: None,
+            consecutive_provider_failures: Mutex::new(0),
+        }
+    }
+
+    /// Ask the user through `provider`, waiting with `gate`.
+    pub fn with_approval(
+        mut self,
+        gate: ApprovalGate,
+        provider: Arc<dyn ApprovalProvider>,
+    ) -> Self {
+        self.approval = Some((gate, provider));
+        self
+    }
+
+    pub fn policy(&self) -> &TurnRecoveryPolicy {
+        &self.policy
+    }
+
+    /// Decide what to do after `provider` failed with `error`.
+    ///
+    /// Returns [`RecoveryAction::RetryProvider`] or
+    /// [`RecoveryAction::AbortTurn`].
+    pub async fn provider_failed(&self, provider: &str, error: &ProviderError) -> RecoveryAction {
+        let failures = {
+            let mut count = self.consecutive_provider_failures.lock().unwrap();
+            *count += 1;
+            *count
+        };
+        let action =
+            if error.retryable() && failures < self.policy.max_consecutive_provider_failures {
+                orchestrator_status::report(OrchestratorStatus::RetryingProvider {
+                    provider: provider.to_string(),
+                    attempt: failures,
+                    delay_ms: error.retry_after().map(|s| (s * 1000.0) as u64),
+                });
+                RecoveryAction::RetryProvider
+            } else {
+                RecoveryAction::AbortTurn
+            };
+        self.record(
+            FailureKind::Provider,
+            provider,
+            &error.to_string(),
+            action,
+            failures,
+        )
+        .await;
+        action
+    }
+
+    /// Record a successful provider call, resetting the failure streak.
+    pub fn provider_succeeded(&self) {
+        *self.consecutive_provider_failures.lock().unwrap() = 0;
+    }
+
+    /// Decide what to do after `tool` failed with `error`.
+    ///
+    /// Returns [`RecoveryAction::ReportToModel`] or
+    /// [`RecoveryAction::AbortTurn`].
+    pub async fn tool_failed(&self, tool: &str, error: &str) -> RecoveryAction {
+        let action = match self.policy.on_tool_failure {
+            ToolFailureAction::Abort => RecoveryAction::AbortTurn,
+            ToolFailureAction::ReportToModel => RecoveryAction::ReportToModel,
+            ToolFailureAction::AskUser => self.ask_user(tool, error).await,
+        };
+        self.record(FailureKind::Tool, tool, error, action, 1).await;
+        action
+    }
+
+    async fn ask_user(&self, tool: &str, error: &str) -> RecoveryAction {
+        let Some((gate, provider)) = &self.approval else {
+            log::warn!(
+                "No approval provider to ask about failed tool '{tool}'; reporting to model"
+            );
+            return RecoveryAction::ReportToModel;
+        };
+        let request = ApprovalRequest {
+            tool_name: tool.to_string(),
+            action: format!("Tool '{tool}' failed: {error}. Continue the turn?"),
+            details: HashMap::from([("error".to_string(), Value::String(error.to_string()))]),
+            risk_level: "low".to_string(),
+            timeout: None,
+        };
+        match gate.request(provider.as_ref(), request).await {
+            Ok(outcome) if outcome.approved() => RecoveryAction::ReportToModel,
+            Ok(_) => RecoveryAction::AbortTurn,
+            Err(e) => {
+                log::warn!("Approval request for failed tool '{tool}' failed: {e}");
+                RecoveryAction::AbortTurn
+            }
+        }
+    }
+
+    async fn record(
+        &self,
+        kind: FailureKind,
+        target: &str,
+        error: &str,
+        action: RecoveryAction,
+        failures: u32,
+    ) {
+        let attempt = RecoveryAttempt {
+            kind,
+            target: target.to_string(),
+            error: error.to_string(),
+            action,
+            failures,
+        };
+        self.hooks
+            .emit(
+                events::ORCHESTRATOR_RECOVERY,
+                serde_json::to_value(attempt).unwrap_or_default(),
+            )
+            .await;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinator::Coordinator;
+    use crate::testing::{FakeApprovalProvider, FakeHookHandler};
+
+    fn rate_limited() -> ProviderError {
+        ProviderError::RateLimit {
+            message: "slow down".into(),
+            provider: Some("openai".into()),
+            model: None,
+            retry_after: Some(1.5),
+            delay_multiplier: None,
+        }
+    }
+
+    fn recovery(policy: TurnRecoveryPolicy) -> (TurnRecovery, Arc<FakeHookHandler>) {
+        let hooks = Arc::new(HookRegistry::new());
+        let observer = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::ORCHESTRATOR_RECOVERY, observer.clone(), 0, None);
+        (TurnRecovery::new(policy, hooks), observer)
+    }
+
+    #[tokio::test]
+    async fn provider_failures_retry_until_the_streak_limit() {
+        let (recovery, observer) = recovery(TurnRecoveryPolicy {
+            max_consecutive_provider_failures: 2,
+            ..TurnRecoveryPolicy::default()
+        });
+
+        assert_eq!(
+            recovery.provider_failed("openai", &rate_limited()).await,
+            RecoveryAction::RetryProvider
+        );
+        recovery.provider_succeeded();
+        assert_eq!(
+            recovery.provider_failed("openai", &rate_limited()).await,
+            RecoveryAction::RetryProvider
+        );
+        assert_eq!(
+            recovery.provider_failed("openai", &rate_limited()).await,
+            RecoveryAction::AbortTurn
+        );
+
+        let events = observer.recorded_events();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[2].1["kind"], "provider");
+        assert_eq!(events[2].1["action"], "abort_turn");
+        assert_eq!(events[2].1["failures"], 2);
+    }
+
+    #[tokio::test]
+    async fn non_retryable_provider_errors_abort_immediately() {
+        let (recovery, _) = recovery(TurnRecoveryPolicy::default());
+        let error = ProviderError::Authentication {
+            message: "bad key".into(),
+            provider: None,
+            model: None,
+            retry_after: None,
+        };
+        assert_eq!(
+            recovery.provider_failed("openai", &error).await,
+            RecoveryAction::AbortTurn
+        );
+    }
+
+    #[tokio::test]
+    async fn tool_failures_follow_the_configured_action() {
+        for (on_tool_failure, expected) in [
+            (ToolFailureAction::Abort, RecoveryAction::AbortTurn),
+            (
+                ToolFailureAction::ReportToModel,
+                RecoveryAction::ReportToModel,
+            ),
+            // No approval provider: reported to the model.
+            (ToolFailureAction::AskUser, RecoveryAction::ReportToModel),
+        ] {
+            let (recovery, observer) = recovery(TurnRecoveryPolicy {
+                on_tool_failure,
+                ..TurnRecoveryPolicy::default()
+            });
+            assert_eq!(recovery.tool_failed("bash", "exit 1").await, expected);
+            assert_eq!(observer.recorded_events()[0].1["target"], "bash");
+        }
+    }
+
+    #[tokio::test]
+    async fn ask_user_denial_aborts_the_turn() {
+        let coordinator = Coordinator::new_for_test();
+        let required = Arc::new(FakeHookHandler::new());
+        let _ = coordinator
+            .hooks()
+            .register(events::APPROVAL_REQUIRED, required.clone(), 0, None);
+        let recovery = TurnRecovery::new(
+            TurnRecoveryPolicy {
+                on_tool_failure: ToolFailureAction::AskUser,
+                ..TurnRecoveryPolicy::default()
+            },
+            coordinator.hooks_shared(),
+        )
+        .with_approval(
+            ApprovalGate::from_coordinator(&coordinator),
+            Arc::new(FakeApprovalProvider::denying()),
+        );
+
+        assert_eq!(
+            recovery.tool_failed("bash", "exit 1").await,
+            RecoveryAction::AbortTurn
+        );
+        let (_, payload) = &required.recorded_events()[0];
+        assert_eq!(payload["tool_name"], "bash");
+        assert!(payload["action"].as_str().unwrap().contains("exit 1"));
+    }
+
+    #[test]
+    fn policy_from_session_config() {
+        let config = HashMap::from([(
+            "session".to_string(),
+            serde_json::json!({"turn_recovery": {"on_tool_failure": "ask_user"}}),
+        )]);
+        let policy = TurnRecoveryPolicy::from_session_config(&config);
+        assert_eq!(policy.on_tool_failure, ToolFailureAction::AskUser);
+        assert_eq!(policy.max_consecutive_provider_failures, 3);
+    }
+}
@@ -0,0 +1,399 @@
+//! Provider request conformance.
+//!
+//! Providers reject out-of-range parameters with an opaque 400. Before a
+//! request reaches the provider, [`conform`] brings it inside the limits the
+//! provider and model advertise, and reports every change it made:
+//!
+//! | Field               | Limit source                                        | Adjustment                            |
+//! |---------------------|-----------------------------------------------------|---------------------------------------|
+//! | `max_output_tokens` | [`ModelInfo::max_output_tokens`], `defaults.max_output_tokens` | clamped down; `<= 0` removed |
+//! | `stop`              | `defaults.max_stop_sequences`                       | empty and duplicate entries removed, then truncated |
+//! | `temperature`       | `defaults.temperature_range` (`[min, max]`)         | clamped; non-finite removed           |
+//! | `max_output_tokens` | [`ModelInfo::context_window`]                        | clamped so input + output fit the window |
+//!
+//! `defaults` is [`ProviderInfo::defaults`] overlaid with
+//! [`ModelInfo::defaults`], so a provider can declare its stop-sequence
+//! limit once and a model can narrow it. Limits that are not declared are
+//! not enforced. Input size is estimated with a [`TokenCounter`].
+//!
+//! [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) applies
+//! this to every call and records the adjustments in the response's
+//! `metadata["request_adjustments"]`.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::messages::ChatRequest;
+use crate::models::{ModelInfo, ProviderInfo};
+use crate::token_counter::TokenCounter;
+
+/// `ChatResponse.metadata` key under which adjustments are recorded.
+pub const ADJUSTMENTS_METADATA_KEY: &str = "request_adjustments";
+
+/// Parameter limits a request must satisfy.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestLimits {
+    /// Largest accepted `max_output_tokens`.
+    pub max_output_tokens: Option<i64>,
+    /// Largest accepted number of stop sequences.
+    pub max_stop_sequences: Option<usize>,
+    /// Accepted `temperature` range, inclusive.
+    pub temperature_range: Option<(f64, f64)>,
+    /// Context window shared by input and output.
+    pub context_window: Option<i64>,
+}
+
+impl RequestLimits {
+    /// Limits for `model` served by `provider`.
+    ///
+    /// Model values take precedence over provider values.
+    pub fn resolve(provider: &ProviderInfo, model: Option<&ModelInfo>) -> Self {
+        let mut limits = Self::from_defaults(&provider.defaults);
+        if let Some(model) = model {
+            let overlay = Self::from_defaults(&model.defaults);
+            limits.max_stop_sequences = overlay.max_stop_sequences.or(limits.max_stop_sequences);
+            limits.temperature_range = overlay.temperature_range.or(limits.temperature_range);
+            limits.max_output_tokens = Some(model.max_output_tokens)
+                .filter(|max| *max > 0)
+                .or(overlay.max_output_tokens)
+                .or(limits.max_output_tokens);
+            limits.context_window = Some(model.context_window).filter(|w| *w > 0);
+        }
+        limits
+    }
+
+    /// Read limits from a `defaults` map. Malformed entries are ignored.
+    pub fn from_defaults(defaults: &HashMap<String, Value>) -> Self {
+        let temperature_range = defaults
+            .get("temperature_range")
+            .and_then(Value::as_array)
+            .and_then(|range| match range.as_slice() {
+                [min, max] => Some((min.as_f64()?, max.as_f64()?)),
+                _ => None,
+            })
+            .filter(|(min, max)| min <= max);
+        Self {
+            max_output_tokens: defaults
+                .get("max_output_tokens")
+                .and_then(Value::as_i64)
+                .filter(|max| *max > 0),
+            max_stop_sequences: defaults
+                .get("max_stop_sequences")
+                .and_then(Value::as_u64)
+                .map(|max| max as usize),
+            temperature_range,
+            context_window: None,
+        }
+    }
+}
+
+/// One change [`conform`] made to a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestAdjustment {
+    /// Request field that was changed.
+    pub field: String,
+    /// Value the caller asked for.
+    pub requested: Value,
+    /// Value sent to the provider (`null` if the field was removed).
+    pub applied: Value,
+    /// Why the change was needed.
+    pub reason: String,
+}
+
+impl RequestAdjustment {
+    pub(crate) fn new(
+        field: &str,
+        requested: impl Serialize,
+        applied: impl Serialize,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.to_string(),
+            requested: serde_json::to_value(requested).unwrap_or(Value::Null),
+            applied: serde_json::to_value(applied).unwrap_or(Value::Null),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Bring `request` within `limits`, returning the changes made.
+///
+/// `counter` estimates the request's input tokens for the context-window
+/// check.
+pub fn conform(
+    request: &mut ChatRequest,
+    limits: &RequestLimits,
+    counter: &dyn TokenCounter,
+) -> Vec<RequestAdjustment> {
+    let mut adjustments = Vec::new();
+
+    if let Some(requested) = request.max_output_tokens {
+        if requested <= 0 {
+            request.max_output_tokens = None;
+            adjustments.push(RequestAdjustment::new(
+                "max_output_tokens",
+                requested,
+                Value::Null,
+                "must be positive",
+            ));
+        } else if let Some(max) = limits.max_output_tokens.filter(|max| requested > *max) {
+            request.max_output_tokens = Some(max);
+            adjustments.push(RequestAdjustment::new(
+                "max_output_tokens",
+                requested,
+                max,
+                format!("model allows at most {max} output tokens"),
+            ));
+        }
+    }
+
+    // Only clamp when the input itself fits; otherwise the context manager
+    // has to compact and no output limit can help.
+    if let (Some(requested), Some(window)) = (request.max_output_tokens, limits.context_window) {
+        let input = counter.count_request(request) as i64;
+        let room = window - input;
+        if room > 0 && requested > room {
+            request.max_output_tokens = Some(room);
+            adjustments.push(RequestAdjustment::new(
+                "max_output_tokens",
+                requested,
+                room,
+                format!("~{input} input tokens leave {room} of the {window}-token context window"),
+            ));
+        }
+    }
+
+    if let Some(stop) = request.stop.take() {
+        let mut seen = HashSet::new();
+        let mut applied: Vec<String> = stop
+            .iter()
+            .filter(|s| !s.is_empty() && seen.insert(s.as_str()))
+            .cloned()
+            .collect();
+        let mut reasons = Vec::new();
+        if applied.len() < stop.len() {
+            reasons.push("empty or duplicate stop sequences removed".to_string());
+        }
+        if let Some(max) = limits.max_stop_sequences.filter(|max| applied.len() > *max) {
+            applied.truncate(max);
+            reasons.push(format!("provider allows at most {max} stop sequences"));
+        }
+        if !reasons.is_empty() {
+            adjustments.push(RequestAdjustment::new(
+                "stop",
+                &stop,
+                &applied,
+                reasons.join("; "),
+            ));
+        }
+        request.stop = Some(applied).filter(|s| !s.is_empty());
+    }
+
+    if let Some(requested) = request.temperature {
+        if !requested.is_finite() {
+            request.temperature = None;
+            adjustments.push(RequestAdjustment::new(
+                "temperature",
+                requested.to_string(),
+                Value::Null,
+                "must be a finite number",
+            ));
+        } else if let Some((min, max)) = limits.temperature_range {
+            let clamped = requested.clamp(min, max);
+            if clamped != requested {
+                request.temperature = Some(clamped);
+                adjustments.push(RequestAdjustment::new(
+                    "temperature",
+                    requested,
+                    clamped,
+                    format!("model accepts temperature in [{min}, {max}]"),
+                ));
+            }
+        }
+    }
+
+    adjustments
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_counter::HeuristicTokenCounter;
+    use serde_json::json;
+
+    fn conform(request: &mut ChatRequest, limits: &RequestLimits) -> Vec<RequestAdjustment> {
+        super::conform(request, limits, &HeuristicTokenCounter::default())
+    }
+
+    fn request() -> ChatRequest {
+        serde_json::from_value(json!({"messages": []})).unwrap()
+    }
+
+    fn provider(defaults: Value) -> ProviderInfo {
+        ProviderInfo {
+            id: "p".into(),
+            display_name: "P".into(),
+            credential_env_vars: Vec::new(),
+            capabilities: Vec::new(),
+            defaults: serde_json::from_value(defaults).unwrap(),
+            config_fields: Vec::new(),
+        }
+    }
+
+    fn model(max_output_tokens: i64, defaults: Value) -> ModelInfo {
+        ModelInfo {
+            id: "m".into(),
+            display_name: "M".into(),
+            context_window: 200_000,
+            max_output_tokens,
+            capabilities: Vec::new(),
+            defaults: serde_json::from_value(defaults).unwrap(),
+        }
+    }
+
+    #[test]
+    fn resolve_overlays_model_on_provider() {
+        let provider = provider(json!({
+            "max_stop_sequences": 4,
+            "temperature_range": [0.0, 2.0],
+            "max_output_tokens": 4096,
+        }));
+        let limits = RequestLimits::resolve(
+            &provider,
+            Some(&model(8192, json!({"temperature_range": [0.0, 1.0]}))),
+        );
+        assert_eq!(
+            limits,
+            RequestLimits {
+                max_output_tokens: Some(8192),
+                max_stop_sequences: Some(4),
+                temperature_range: Some((0.0, 1.0)),
+                context_window: Some(200_000),
+            }
+        );
+
+        let provider_only = RequestLimits::resolve(&provider, None);
+        assert_eq!(provider_only.max_output_tokens, Some(4096));
+    }
+
+    #[test]
+    fn malformed_defaults_are_ignored() {
+        let limits = RequestLimits::from_defaults(
+            &serde_json::from_value(json!({
+                "max_stop_sequences": -1,
+                "temperature_range": [1.0, 0.0],
+                "max_output_tokens": "lots",
+            }))
+            .unwrap(),
+        );
+        assert_eq!(limits, RequestLimits::default());
+    }
+
+    #[test]
+    fn clamps_max_output_tokens() {
+        let limits = RequestLimits {
+            max_output_tokens: Some(1000),
+            ..Default::default()
+        };
+        let mut req = request();
+        req.max_output_tokens = Some(5000);
+        let adjustments = conform(&mut req, &limits);
+        assert_eq!(req.max_output_tokens, Some(1000));
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(adjustments[0].field, "max_output_tokens");
+        assert_eq!(adjustments[0].requested, json!(5000));
+        assert_eq!(adjustments[0].applied, json!(1000));
+
+        req.max_output_tokens = Some(0);
+        conform(&mut req, &limits);
+        assert_eq!(req.max_output_tokens, None);
+    }
+
+    #[test]
+    fn normalizes_stop_sequences() {
+        let limits = RequestLimits {
+            max_stop_sequences: Some(2),
+            ..Default::default()
+        };
+        let mut req = request();
+        req.stop = Some(vec![
+            "a".into(),
+            "".into(),
+            "a".into(),
+            "b".into(),
+            "c".into(),
+        ]);
+        let adjustments = conform(&mut req, &limits);
+        assert_eq!(req.stop, Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(adjustments.len(), 1);
+        assert_eq!(adjustments[0].applied, json!(["a", "b"]));
+        assert!(adjustments[0].reason.contains("at most 2"));
+
+        req.stop = Some(vec!["".into()]);
+        conform(&mut req, &limits);
+        assert_eq!(req.stop, None);
+    }
+
+    #[test]
+    fn clamps_temperature() {
+        let limits = RequestLimits {
+            temperature_range: Some((0.0, 1.0)),
+            ..Default::default()
+        };
+        let mut req = request();
+        req.temperature = Some(1.7);
+        let adjustments = conform(&mut req, &limits);
+        assert_eq!(req.temperature, Some(1.0));
+        assert_eq!(adjustments[0].applied, json!(1.0));
+
+        req.temperature = Some(f64::NAN);
+        let adjustments = conform(&mut req, &RequestLimits::default());
+        assert_eq!(req.temperature, None);
+        assert_eq!(adjustments[0].requested, json!("NaN"));
+    }
+
+    #[test]
+    fn conforming_request_is_untouched() {
+        let limits = RequestLimits {
+            max_output_tokens: Some(1000),
+            max_stop_sequences: Some(4),
+            temperature_range: Some((0.0, 1.0)),
+            context_window: Some(100_000),
+        };
+        let mut req = request();
+        req.max_output_tokens = Some(1000);
+        req.stop = Some(vec!["END".into()]);
+        req.temperature = Some(0.5);
+        let before = req.clone();
+        assert!(conform(&mut req, &limits).is_empty());
+        assert_eq!(req, before);
+    }
+
+    #[test]
+    fn clamps_output_to_remaining_context_window() {
+        let limits = RequestLimits {
+            context_window: Some(1_000),
+            ..Default::default()
+        };
+        let mut req: ChatRequest = serde_json::from_value(json!({
+            "messages": [{"role": "user", "content": "x".repeat(2_000)}],
+            "max_output_tokens": 800,
+        }))
+        .unwrap();
+        let input = HeuristicTokenCounter::default().count_request(&req) as i64;
+        let adjustments = conform(&mut req, &limits);
+        assert_eq!(req.max_output_tokens, Some(1_000 - input));
+        assert_eq!(adjustments[0].requested, json!(800));
+
+        // Input alone overflows: nothing to clamp to.
+        req.messages[0].content = crate::messages::MessageContent::Text("x".repeat(8_000));
+        req.max_output_tokens = Some(800);
+        assert!(conform(&mut req, &limits).is_empty());
+    }
+}
@@ -0,0 +1,446 @@
+//! Per-turn safeguards against runaway agent loops.
+//!
+//! `session.safety` caps what a single `execute()` may do:
+//!
+//! ```json
+//! {"session": {"safety": {
+//!     "max_turns": 25,
+//!     "max_identical_tool_calls": 3,
+//!     "max_tool_calls": 100
+//! }}}
+//! ```
+//!
+//! Every limit is optional, and every counter starts again with each
+//! `execute()`. A [`SafetyGuard`] counts from the session's hook events:
+//!
+//! | Limit                  | Counted on                                                  |
+//! |------------------------|-------------------------------------------------------------|
+//! | `turns`                | each `provider:request`, or `provider:pre` when the orchestrator never emits `provider:request` |
+//! | `tool_calls`           | each `tool:pre`                                             |
+//! | `identical_tool_calls` | consecutive `tool:pre`s with the same `tool_name` and `tool_input` |
+//!
+//! # Enforcement
+//!
+//! - The call that would go over a limit is denied and `safety:limit` is
+//!   emitted once with `{limit, max, observed}` (plus `tool_name` for
+//!   `identical_tool_calls`). Every later tool and provider call in the turn
+//!   is denied too, so the orchestrator winds down instead of looping.
+//! - [`Session::execute`](crate::session::Session::execute) runs the
+//!   orchestrator through [`SafetyGuard::run`], which fails the turn with
+//!   [`SessionError::SafetyLimitExceeded`] if a limit was hit.
+//!
+//! Unlike [`crate::quota`], which budgets a whole session, these limits
+//! describe one well-behaved turn. The guard's hooks run in
+//! [`HookPhase::PreValidation`], so a call counts even if a later hook
+//! denies it.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::audit::canonical_hash;
+use crate::errors::{AmplifierError, HookError, SessionError};
+use crate::events;
+use crate::hooks::{HookPhase, HookRegistry};
+use crate::models::{HookAction, HookResult};
+use crate::traits::HookHandler;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// The `session.safety` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SafetyConfig {
+    #[serde(default)]
+    pub max_turns: Option<u64>,
+    #[serde(default)]
+    pub max_identical_tool_calls: Option<u64>,
+    #[serde(default)]
+    pub max_tool_calls: Option<u64>,
+}
+
+impl SafetyConfig {
+    /// Read `session.safety` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("safety"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.safety config: {e}"))
+            .ok()
+    }
+
+    /// Whether any limit is set.
+    pub fn is_active(&self) -> bool {
+        SafetyLimit::ALL.iter().any(|l| self.max(*l).is_some())
+    }
+
+    /// The configured maximum for `limit`.
+    pub fn max(&self, limit: SafetyLimit) -> Option<u64> {
+        match limit {
+            SafetyLimit::Turns => self.max_turns,
+            SafetyLimit::IdenticalToolCalls => self.max_identical_tool_calls,
+            SafetyLimit::ToolCalls => self.max_tool_calls,
+        }
+    }
+}
+
+/// A per-turn safeguard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyLimit {
+    /// Provider round-trips.
+    Turns,
+    /// Consecutive calls to one tool with the same input.
+    IdenticalToolCalls,
+    /// Tool invocations.
+    ToolCalls,
+}
+
+impl SafetyLimit {
+    pub const ALL: [SafetyLimit; 3] = [Self::Turns, Self::IdenticalToolCalls, Self::ToolCalls];
+
+    /// The name used in events and errors, e.g. `"tool_calls"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Turns => "turns",
+            Self::IdenticalToolCalls => "identical_tool_calls",
+            Self::ToolCalls => "tool_calls",
+        }
+    }
+}
+
+/// The first limit a turn went over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafetyBreach {
+    pub limit: SafetyLimit,
+    pub max: u64,
+    pub observed: u64,
+    /// The repeated tool, for [`SafetyLimit::IdenticalToolCalls`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+}
+
+impl SafetyBreach {
+    pub fn to_error(&self) -> SessionError {
+        SessionError::SafetyLimitExceeded {
+            limit: self.limit.as_str().to_string(),
+            max: self.max,
+            observed: self.observed,
+        }
+    }
+
+    fn to_event(&self) -> Value {
+        let mut data = serde_json::to_value(self).unwrap_or_default();
+        data["limit"] = Value::from(self.limit.as_str());
+        data
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SafetyGuard
+// ---------------------------------------------------------------------------
+
+/// Per-turn counters.
+#[derive(Default)]
+struct TurnCounts {
+    turns: u64,
+    tool_calls: u64,
+    /// `(tool_name, input hash)` of the last tool call.
+    last_call: Option<(String, String)>,
+    /// Consecutive calls matching `last_call`, including it.
+    identical: u64,
+    /// Set once `provider:request` is seen; disables the `provider:pre` fallback.
+    orchestrator_events: bool,
+    breach: Option<SafetyBreach>,
+}
+
+/// Enforces a session's [`SafetyConfig`] on each turn.
+pub struct SafetyGuard {
+    config: SafetyConfig,
+    counts: Mutex<TurnCounts>,
+}
+
+impl SafetyGuard {
+    pub fn new(config: SafetyConfig) -> Self {
+        Self {
+            config,
+            counts: Mutex::new(TurnCounts::default()),
+        }
+    }
+
+    pub fn config(&self) -> &SafetyConfig {
+        &self.config
+    }
+
+    /// Register the counting hooks on `hooks` in [`HookPhase::PreValidation`]
+    /// as `"safety"`.
+    pub fn install(self: &Arc<Self>, hooks: &Arc<HookRegistry>) {
+        let handler = Arc::new(SafetyHook {
+            guard: Arc::clone(self),
+            hooks: Arc::downgrade(hooks),
+        });
+        for event in [
+            events::TOOL_PRE,
+            events::PROVIDER_REQUEST,
+            events::PROVIDER_PRE,
+        ] {
+            let _ = hooks.register_in_phase(
+                event,
+                handler.clone(),
+                HookPhase::PreValidation,
+                0,
+                Some("safety".into()),
+            );
+        }
+    }
+
+    /// The limit breached in the current (or last) turn, if any.
+    pub fn breach(&self) -> Option<SafetyBreach> {
+        self.counts.lock().unwrap().breach.clone()
+    }
+
+    /// Run one turn with fresh counters.
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::SafetyLimitExceeded` if a limit was hit during the
+    ///   turn, even if the orchestrator recovered from the denied call
+    /// - Any error from `fut`
+    pub async fn run<T>(
+        &self,
+        fut: impl Future<Output = Result<T, AmplifierError>>,
+    ) -> Result<T, AmplifierError> {
+        *self.counts.lock().unwrap() = TurnCounts::default();
+        let outcome = fut.await;
+        match self.breach() {
+            Some(breach) => Err(breach.to_error().into()),
+            None => outcome,
+        }
+    }
+
+    /// Count one event. Returns whether the call must be denied, and the
+    /// breach to announce if this call caused it.
+    fn observe(&self, event: &str, data: &Value) -> (bool, Option<SafetyBreach>) {
+        let mut counts = self.counts.lock().unwrap();
+        let counted = match event {
+            events::TOOL_PRE => true,
+            events::PROVIDER_REQUEST => {
+                counts.orchestrator_events = true;
+                true
+            }
+            events::PROVIDER_PRE => !counts.orchestrator_events,
+            _ => false,
+        };
+        if !counted || counts.breach.is_some() {
+            return (counts.breach.is_some(), None);
+        }
+
+        let over = |limit: SafetyLimit, observed: u64| {
+            self.config
+                .max(limit)
+                .filter(|max| observed > *max)
+                .map(|max| SafetyBreach {
+                    limit,
+                    max,
+                    observed,
+                    tool_name: None,
+                })
+        };
+        let breach = if event == events::TOOL_PRE {
+            counts.tool_calls += 1;
+            let tool_name = data
+                .get("tool_name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let call = (
+                tool_name.clone(),
+                canonical_hash(data.get("tool_input").unwrap_or(&Value::Null)),
+            );
+            if counts.last_call.as_ref() == Some(&call) {
+                counts.identical += 1;
+            } else {
+                counts.last_call = Some(call);
+                counts.identical = 1;
+            }
+            over(SafetyLimit::ToolCalls, counts.tool_calls).or_else(|| {
+                over(SafetyLimit::IdenticalToolCalls, counts.identical).map(|breach| SafetyBreach {
+                    tool_name: Some(tool_name),
+                    ..breach
+                })
+            })
+        } else {
+            counts.turns += 1;
+            over(SafetyLimit::Turns, counts.turns)
+        };
+        counts.breach.clone_from(&breach);
+        (breach.is_some(), breach)
+    }
+}
+
+/// The hook handler registered by [`SafetyGuard::install`].
+///
+/// Holds the registry weakly, since the registry owns the handler.
+struct SafetyHook {
+    guard: Arc<SafetyGuard>,
+    hooks: Weak<HookRegistry>,
+}
+
+impl HookHandler for SafetyHook {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        let (deny, breach) = self.guard.observe(event, &data);
+        Box::pin(async move {
+            if let (Some(breach), Some(hooks)) = (&breach, self.hooks.upgrade()) {
+                log::warn!(
+                    "Safety limit {} exceeded: {} of {}",
+                    breach.limit.as_str(),
+                    breach.observed,
+                    breach.max
+                );
+                hooks.emit(events::SAFETY_LIMIT, breach.to_event()).await;
+            }
+            if !deny {
+                return Ok(HookResult::default());
+            }
+            let reason = self
+                .guard
+                .breach()
+                .map(|b| {
+                    format!(
+                        "Safety limit exceeded: {} limit is {}",
+                        b.limit.as_str(),
+                        b.max
+                    )
+                })
+                .unwrap_or_default();
+            Ok(HookResult {
+                action: HookAction::Deny,
+                reason: Some(reason),
+                ..Default::default()
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::FakeHookHandler;
+
+    fn guard(config: Value) -> (Arc<SafetyGuard>, Arc<HookRegistry>) {
+        let config: SafetyConfig = serde_json::from_value(config).unwrap();
+        let guard = Arc::new(SafetyGuard::new(config));
+        let hooks = Arc::new(HookRegistry::new());
+        guard.install(&hooks);
+        (guard, hooks)
+    }
+
+    fn tool_call(name: &str, input: Value) -> Value {
+        serde_json::json!({"tool_name": name, "tool_input": input})
+    }
+
+    #[test]
+    fn config_section_is_optional() {
+        assert_eq!(SafetyConfig::from_session_config(&HashMap::new()), None);
+        let config = HashMap::from([(
+            "session".to_string(),
+            serde_json::json!({"safety": {"max_turns": 5}}),
+        )]);
+        let safety = SafetyConfig::from_session_config(&config).unwrap();
+        assert!(safety.is_active());
+        assert_eq!(safety.max(SafetyLimit::Turns), Some(5));
+        assert!(!SafetyConfig::default().is_active());
+    }
+
+    #[tokio::test]
+    async fn identical_consecutive_calls_are_denied() {
+        let (guard, hooks) = guard(serde_json::json!({"max_identical_tool_calls": 2}));
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::SAFETY_LIMIT, recorder.clone(), 0, None);
+
+        let run = guard.run(async {
+            for input in [1, 1, 2, 2] {
+                let result = hooks
+                    .emit(
+                        events::TOOL_PRE,
+                        tool_call("grep", serde_json::json!({"q": input})),
+                    )
+                    .await;
+                assert_eq!(result.action, HookAction::Continue);
+            }
+            let result = hooks
+                .emit(
+                    events::TOOL_PRE,
+                    tool_call("grep", serde_json::json!({"q": 2})),
+                )
+                .await;
+            assert_eq!(result.action, HookAction::Deny);
+            // Every later call in the turn is denied.
+            let result = hooks
+                .emit(events::PROVIDER_REQUEST, serde_json::json!({}))
+                .await;
+            assert_eq!(result.action, HookAction::Deny);
+            Ok(())
+        });
+        let err = run.await.unwrap_err();
+        assert!(matches!(
+            err,
+            AmplifierError::Session(SessionError::SafetyLimitExceeded { ref limit, max: 2, observed: 3 })
+                if limit == "identical_tool_calls"
+        ));
+
+        let events = recorder.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["limit"], "identical_tool_calls");
+        assert_eq!(events[0].1["tool_name"], "grep");
+
+        // The next turn starts with fresh counters.
+        guard
+            .run(async {
+                let result = hooks
+                    .emit(
+                        events::TOOL_PRE,
+                        tool_call("grep", serde_json::json!({"q": 2})),
+                    )
+                    .await;
+                assert_eq!(result.action, HookAction::Continue);
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn turns_fall_back_to_provider_pre() {
+        let (guard, hooks) = guard(serde_json::json!({"max_turns": 1, "max_tool_calls": 5}));
+        let err = guard
+            .run(async {
+                hooks
+                    .emit(events::PROVIDER_PRE, serde_json::json!({}))
+                    .await;
+                let result = hooks
+                    .emit(events::PROVIDER_PRE, serde_json::json!({}))
+                    .await;
+                assert_eq!(result.action, HookAction::Deny);
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "safety limit turns exceeded: 2 of 1");
+    }
+}
@@ -16,17 +16,84 @@
 //! - Owns a [`Coordinator`](crate::coordinator::Coordinator) for module access.
 //! - Emits lifecycle events via [`HookRegistry`](crate::hooks::HookRegistry).
 //! - Tracks status via [`SessionState`](crate::models::SessionState).
+//! - Records lifecycle milestones on a [`Timeline`](crate::timeline::Timeline).
+//! - Optionally persists history via a
+//!   [`ConversationStore`](crate::conversation_store::ConversationStore).
+//! - Records submitted prompts in a
+//!   [`PromptHistory`](crate::prompt_history::PromptHistory) and reports (or
+//!   replays) accidental resubmissions.
+//! - Emits periodic `session:heartbeat` events while executing when
+//!   `session.heartbeat` is set (see [`crate::heartbeat`]).
+//! - Tracks its last activity (`execute()`, tool and provider calls) so a
+//!   [`SessionManager`](crate::session_manager::SessionManager) can reap it
+//!   when idle.
+//!
+//! # Concurrency
+//!
+//! All per-session state uses interior mutability (atomics for flags, an
+//! `RwLock` for status), so every method after setup takes `&self`. Bindings
+//! hold the session as a plain `Arc<Session>`: status queries and
+//! cancellation never wait on an in-flight `execute()`.
+//! [`Session::execute_handle`] runs `execute()` on its own task and returns
+//! an [`ExecutionHandle`] that ties the task to the session's cancellation
+//! token.
+//!
+//! One prompt executes at a time. A concurrent `execute()` (e.g. two HTTP
+//! requests for the same session) is rejected with `SessionError::Busy` or,
+//! with `session.reentrancy: "queue"`, waits its turn in FIFO order (see
+//! [`ReentrancyPolicy`]). [`Session::current_execution`] reports what is
+//! running and how many prompts are waiting.
 
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-
+use tokio::task::JoinHandle;
+
+use crate::attachments::{AttachmentConfig, AttachmentStore, InMemoryAttachmentBackend};
+use crate::audit::{AuditConfig, AuditLog};
+use crate::cancellation::{CancellationState, CancellationToken, StateChangeCallback};
+use crate::catalog::{self, ModuleCatalog, MountPlanProblem};
+use crate::checkpoint::{CheckpointId, CheckpointStore};
+use crate::config_migration::ConfigMigrations;
+use crate::context_dedup::{DedupConfig, DedupContext};
+use crate::conversation_store::{ConversationStore, PersistentContext};
 use crate::coordinator::Coordinator;
+use crate::correlation::{self, CorrelationIds};
+use crate::credentials::CredentialResolver;
+use crate::deadline::{self, TurnDeadline};
 use crate::errors::{AmplifierError, SessionError};
+use crate::event_filter::EventFilterConfig;
 use crate::events;
-use crate::models::SessionState;
+use crate::heartbeat::{self, HeartbeatConfig, TurnActivity};
+use crate::hook_subscriptions::{HookHandlerSet, HookSubscription};
+use crate::hooks::builtin::{self, BuiltinHooksConfig};
+use crate::hooks::latency::{LatencyBudget, LatencyBudgetConfig};
+use crate::hooks::spill::HookDataLimit;
+use crate::hooks::HookRegistry;
+use crate::interpolation;
+use crate::models::{HookAction, SessionState};
+use crate::orchestrator_status;
+use crate::policy::{PermissionPolicy, PolicyConfig};
+use crate::pricing::{CostTracker, PricingCatalog};
+use crate::prompt_history::{PromptHistory, PromptHistoryConfig, PromptRecord};
+use crate::provenance::{ProvenanceConfig, ProvenanceContext};
+use crate::provider_invoker::ProviderInvoker;
+use crate::quota::{QuotaConfig, QuotaEnforcer};
+use crate::safety::{SafetyConfig, SafetyGuard};
+use crate::summarizer::{ProviderSummarizer, SummarizationConfig, SummarizingContext};
+#[cfg(feature = "otel")]
+use crate::telemetry::OtelTelemetry;
+use crate::telemetry::TelemetryConfig;
+use crate::timeline::{Milestone, Timeline};
+use crate::tool_discovery::{self, ToolDiscoveryConfig};
+use crate::traits::{ContextManager, HookHandler};
+use crate::turn::{self, TurnRecorder, TurnResult};
+use crate::workspace::Workspace;
 
 // ---------------------------------------------------------------------------
 // SessionConfig
@@ -44,8 +111,24 @@ pub struct SessionConfig {
 impl SessionConfig {
     /// Create a `SessionConfig` from a JSON value, validating required fields.
     ///
-    /// Requires `session.orchestrator` and `session.context` to be present.
+    /// Older plans are first migrated with [`ConfigMigrations::builtin`] (see
+    /// [`crate::config_migration`]). Requires `session.orchestrator` and
+    /// `session.context` to be present.
     pub fn from_value(value: Value) -> Result<Self, SessionError> {
+        Self::from_value_with_migrations(value, &ConfigMigrations::builtin())
+    }
+
+    /// [`from_value`](Self::from_value), migrating with `migrations`.
+    ///
+    /// # Errors
+    ///
+    /// `SessionError::ConfigMigration` if the plan cannot be migrated, or the
+    /// usual validation errors.
+    pub fn from_value_with_migrations(
+        mut value: Value,
+        migrations: &ConfigMigrations,
+    ) -> Result<Self, SessionError> {
+        migrations.migrate(&mut value)?;
         let obj = match value.as_object() {
             Some(o) => o,
             None => {
@@ -96,6 +179,180 @@ impl SessionConfig {
         Self::from_value(value)
     }
 
+    /// Replace `${ENV_VAR}` and `${secret:name}` references in the config
+    /// with values from `resolver` (see [`crate::interpolation`]).
+    ///
+    /// ```rust
+    /// use amplifier_core::credentials::StaticCredentialResolver;
+    /// use amplifier_core::interpolation::SECRET_SCOPE;
+    /// use amplifier_core::session::SessionConfig;
+    ///
+    /// let config = SessionConfig::from_value(serde_json::json!({
+    ///     "session": {"orchestrator": "loop-basic", "context": "context-simple"},
+    ///     "providers": [{"module": "provider-openai", "config": {"api_key": "${secret:openai}"}}]
+    /// }))
+    /// .unwrap()
+    /// .interpolate(&StaticCredentialResolver::new().with(SECRET_SCOPE, "openai", "sk-test"))
+    /// .unwrap();
+    /// assert_eq!(config.config["providers"][0]["config"]["api_key"], "sk-test");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// `SessionError::ConfigInterpolation` listing every reference that
+    /// could not be resolved.
+    pub fn interpolate(mut self, resolver: &dyn CredentialResolver) -> Result<Self, SessionError> {
+        interpolation::interpolate_config(&mut self.config, resolver)
+            .map_err(|unresolved| SessionError::ConfigInterpolation { unresolved })?;
+        Ok(self)
+    }
+
+    /// Telemetry export settings from `session.telemetry`
+    /// (see [`crate::telemetry`]).
+    pub fn telemetry(&self) -> TelemetryConfig {
+        TelemetryConfig::from_session_config(&self.config)
+    }
+
+    /// Tool permission policy from `session.policy`, if present
+    /// (see [`crate::policy`]).
+    pub fn policy(&self) -> Option<PolicyConfig> {
+        PolicyConfig::from_session_config(&self.config)
+    }
+
+    /// Attachment store settings from `session.attachments`, if present
+    /// (see [`crate::attachments`]).
+    pub fn attachments(&self) -> Option<AttachmentConfig> {
+        AttachmentConfig::from_session_config(&self.config)
+    }
+
+    /// Per-session resource quotas from `session.quota`, if present
+    /// (see [`crate::quota`]).
+    pub fn quota(&self) -> Option<QuotaConfig> {
+        QuotaConfig::from_session_config(&self.config)
+    }
+
+    /// Per-turn safeguards from `session.safety`, if present
+    /// (see [`crate::safety`]).
+    pub fn safety(&self) -> Option<SafetyConfig> {
+        SafetyConfig::from_session_config(&self.config)
+    }
+
+    /// Number of recent hook events kept for late subscribers, from
+    /// `session.hooks.replay` (see [`HookRegistry::enable_replay`](crate::hooks::HookRegistry::enable_replay)).
+    pub fn hook_replay(&self) -> Option<usize> {
+        self.config
+            .get("session")
+            .and_then(|s| s.get("hooks"))
+            .and_then(|h| h.get("replay"))
+            .and_then(Value::as_u64)
+            .map(|n| n as usize)
+            .filter(|n| *n > 0)
+    }
+
+    /// Maximum serialized size of hook result data, from
+    /// `session.hooks.max_result_bytes` (see [`crate::hooks::spill`]).
+    pub fn hook_max_result_bytes(&self) -> Option<usize> {
+        self.config
+            .get("session")
+            .and_then(|s| s.get("hooks"))
+            .and_then(|h| h.get("max_result_bytes"))
+            .and_then(Value::as_u64)
+            .map(|n| n as usize)
+            .filter(|n| *n > 0)
+    }
+
+    /// Hook handler latency budgets from `session.hooks.latency` (see
+    /// [`crate::hooks::latency`]).
+    pub fn hook_latency(&self) -> Option<LatencyBudgetConfig> {
+        LatencyBudgetConfig::from_session_config(&self.config)
+    }
+
+    /// Turn-start tool discovery settings from `session.tool_discovery`
+    /// (see [`crate::tool_discovery`]).
+    pub fn tool_discovery(&self) -> ToolDiscoveryConfig {
+        ToolDiscoveryConfig::from_session_config(&self.config)
+    }
+
+    /// Prompt history and duplicate detection settings from
+    /// `session.prompt_history` (see [`crate::prompt_history`]).
+    pub fn prompt_history(&self) -> PromptHistoryConfig {
+        PromptHistoryConfig::from_session_config(&self.config)
+    }
+
+    /// Heartbeat settings from `session.heartbeat`, if present
+    /// (see [`crate::heartbeat`]).
+    pub fn heartbeat(&self) -> Option<HeartbeatConfig> {
+        HeartbeatConfig::from_session_config(&self.config)
+    }
+
+    /// What a concurrent `execute()` does, from `session.reentrancy`
+    /// (`"reject"` or `"queue"`; rejects when absent or unrecognized).
+    pub fn reentrancy(&self) -> ReentrancyPolicy {
+        self.config
+            .get("session")
+            .and_then(|s| s.get("reentrancy"))
+            .and_then(|r| {
+                serde_json::from_value(r.clone())
+                    .map_err(|e| log::warn!("Ignoring malformed session.reentrancy: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Declared hook registrations from `session.hooks.subscriptions`
+    /// (see [`crate::hook_subscriptions`]).
+    pub fn hook_subscriptions(&self) -> Vec<HookSubscription> {
+        HookSubscription::from_session_config(&self.config)
+    }
+
+    /// Model pricing from `session.pricing`, if present
+    /// (see [`crate::pricing`]).
+    pub fn pricing(&self) -> Option<PricingCatalog> {
+        PricingCatalog::from_session_config(&self.config)
+    }
+
+    /// Tool audit log settings from `session.audit`, if present
+    /// (see [`crate::audit`]).
+    pub fn audit(&self) -> Option<AuditConfig> {
+        AuditConfig::from_session_config(&self.config)
+    }
+
+    /// Context deduplication settings from `session.context_dedup`, if
+    /// present (see [`crate::context_dedup`]).
+    pub fn context_dedup(&self) -> Option<DedupConfig> {
+        DedupConfig::from_session_config(&self.config)
+    }
+
+    /// Message provenance settings from `session.provenance`, if present
+    /// (see [`crate::provenance`]).
+    pub fn provenance(&self) -> Option<ProvenanceConfig> {
+        ProvenanceConfig::from_session_config(&self.config)
+    }
+
+    /// Context summarization settings from `session.summarization`, if
+    /// present (see [`crate::summarizer`]).
+    pub fn summarization(&self) -> Option<SummarizationConfig> {
+        SummarizationConfig::from_session_config(&self.config)
+    }
+
+    /// Options for the built-in hook handlers from `session.hooks.builtin`
+    /// (see [`crate::hooks::builtin`]).
+    pub fn builtin_hooks(&self) -> BuiltinHooksConfig {
+        BuiltinHooksConfig::from_session_config(&self.config)
+    }
+
+    /// Emit-time event filter from `session.hooks.filter`, if present
+    /// (see [`crate::event_filter`]).
+    pub fn event_filter(&self) -> Option<EventFilterConfig> {
+        EventFilterConfig::from_session_config(&self.config)
+    }
+
+    /// Check this mount plan against `catalog` without loading any module
+    /// (see [`crate::catalog`]). An empty list means the plan is valid.
+    pub fn validate_mount_plan(&self, catalog: &ModuleCatalog) -> Vec<MountPlanProblem> {
+        catalog::validate_mount_plan(&self.config, catalog)
+    }
+
     /// Create a minimal config for testing.
     ///
     /// Sets `session.orchestrator` and `session.context` to the given values.
@@ -123,7 +380,8 @@ impl SessionConfig {
 /// 1. **Create** — `Session::new(config, session_id, parent_id)`
 /// 2. **Mount modules** — caller mounts orchestrator, context, providers, tools
 ///    on `coordinator_mut()`
-/// 3. **Mark initialized** — `set_initialized()` (or auto-init on execute)
+/// 3. **Mark initialized** — `initialize()`, which also registers the hook
+///    subscriptions declared in the config, or `set_initialized()`
 /// 4. **Execute** — `execute(prompt)` runs the orchestrator loop
 /// 5. **Cleanup** — `cleanup()` runs cleanup functions
 ///
@@ -139,6 +397,9 @@ impl SessionConfig {
 pub struct Session {
     session_id: String,
     parent_id: Option<String>,
+    /// The session this one was cloned from by
+    /// [`clone_for_eval()`](Session::clone_for_eval).
+    eval_source: Option<String>,
     coordinator: Arc<Coordinator>,
     initialized: AtomicBool,
     /// Guards once-per-session emission of `session:start` / `session:resume`.
@@ -149,8 +410,44 @@ pub struct Session {
     /// ensures the emit fires at most once per session lifetime regardless of
     /// how many times `execute()` is called.
     lifecycle_event_emitted: AtomicBool,
-    status: SessionState,
+    /// Current status. Held only for the duration of a read or a single
+    /// transition — never across an `.await`.
+    status: RwLock<SessionState>,
     is_resumed: bool,
+    /// When set, the mounted context is wrapped in a [`PersistentContext`]
+    /// for every `execute()`, and resumed sessions reload history from it.
+    conversation_store: Option<Arc<dyn ConversationStore>>,
+    /// OpenTelemetry exporter, when `session.telemetry.enabled` is set.
+    #[cfg(feature = "otel")]
+    telemetry: Option<Arc<OtelTelemetry>>,
+    timeline: Arc<Timeline>,
+    /// Resource quota enforcement, when `session.quota` sets any limit.
+    quota: Option<Arc<QuotaEnforcer>>,
+    /// Per-turn safeguards, when `session.safety` sets any limit.
+    safety: Option<Arc<SafetyGuard>>,
+    /// Cost estimation, when `session.pricing` is configured.
+    costs: Option<Arc<CostTracker>>,
+    /// Tool audit log, when `session.audit` is configured.
+    audit: Option<Arc<AuditLog>>,
+    /// When set, the mounted context is wrapped in a [`DedupContext`] for
+    /// every `execute()`.
+    context_dedup: Option<DedupConfig>,
+    /// When set, the mounted context is wrapped in a [`ProvenanceContext`]
+    /// for every `execute()`.
+    provenance: bool,
+    /// When set, the mounted context is wrapped in a [`SummarizingContext`]
+    /// for every `execute()`.
+    summarization: Option<SummarizationConfig>,
+    tool_discovery: ToolDiscoveryConfig,
+    checkpoints: CheckpointStore,
+    prompt_history: PromptHistory,
+    /// Period of `session:heartbeat` events during `execute()`.
+    heartbeat: Option<Duration>,
+    /// Declared registrations, applied by [`initialize()`](Self::initialize).
+    hook_subscriptions: Vec<HookSubscription>,
+    hook_handlers: Mutex<HookHandlerSet>,
+    activity: Arc<ActivityTracker>,
+    execution: ExecutionGate,
 }
 
 impl Session {
@@ -167,22 +464,165 @@ impl Session {
         parent_id: Option<String>,
     ) -> Self {
         let id = session_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let telemetry_config = config.telemetry();
+        let policy_config = config.policy();
+        let attachment_config = config.attachments();
+        let quota_config = config.quota();
+        let safety_config = config.safety();
+        let pricing = config.pricing();
+        let audit_config = config.audit();
+        let context_dedup = config.context_dedup().filter(|c| c.enabled);
+        let provenance = config.provenance().is_some_and(|c| c.enabled);
+        let summarization = config.summarization().filter(|c| c.enabled);
+        let tool_discovery = config.tool_discovery();
+        let hook_replay = config.hook_replay();
+        let hook_max_result_bytes = config.hook_max_result_bytes();
+        let hook_latency = config.hook_latency();
+        let event_filter = config.event_filter();
+        let hook_subscriptions = config.hook_subscriptions();
+        let builtin_hooks = config.builtin_hooks();
+        let reentrancy = config.reentrancy();
+        let prompt_history = config.prompt_history();
+        let heartbeat = config.heartbeat().and_then(|c| c.interval());
         let coordinator = Arc::new(Coordinator::new(config.config));
 
+        if let Some(capacity) = hook_replay {
+            coordinator.hooks().enable_replay(capacity);
+        }
+        if let Some(filter) = event_filter {
+            coordinator.hooks().set_event_filter(filter);
+        }
+
+        match (policy_config, coordinator.workspace()) {
+            (policy, Some(workspace)) => {
+                Arc::new(
+                    PermissionPolicy::new(policy.unwrap_or_default())
+                        .with_workspace(Workspace::clone(&workspace)),
+                )
+                .install(coordinator.hooks());
+            }
+            (Some(policy), None) if policy.is_active() => {
+                Arc::new(PermissionPolicy::new(policy)).install(coordinator.hooks());
+            }
+            _ => {}
+        }
+
+        let quota = quota_config.filter(QuotaConfig::is_active).map(|quota| {
+            let quota = Arc::new(QuotaEnforcer::new(quota, coordinator.clock()));
+            quota.install(&coordinator.hooks_shared());
+            quota
+        });
+        let safety = safety_config.filter(SafetyConfig::is_active).map(|safety| {
+            let safety = Arc::new(SafetyGuard::new(safety));
+            safety.install(&coordinator.hooks_shared());
+            safety
+        });
+
+        let costs = pricing.map(|catalog| {
+            let costs = Arc::new(CostTracker::new(catalog));
+            costs.install(coordinator.hooks());
+            costs
+        });
+
+        let audit = audit_config.and_then(|audit| match audit.build(coordinator.clock()) {
+            Ok(log) => {
+                let log = Arc::new(log.with_memory(coordinator.memory()));
+                log.install(coordinator.hooks());
+                Some(log)
+            }
+            Err(e) => {
+                log::warn!("Audit log disabled: {e}");
+                None
+            }
+        });
+
+        if let Some(attachments) = attachment_config {
+            match attachments.build(coordinator.memory()) {
+                Ok(store) => coordinator.set_attachment_store(Some(Arc::new(store))),
+                Err(e) => log::warn!("Attachment store disabled: {e}"),
+            }
+        }
+
+        if let Some(max_bytes) = hook_max_result_bytes {
+            // Without session.attachments, spilled data goes to an in-memory
+            // store registered on the coordinator so its references resolve.
+            // Its threshold is unlimited: it does not offload tool output.
+            let store = coordinator.attachment_store().unwrap_or_else(|| {
+                let backend = InMemoryAttachmentBackend::with_memory(coordinator.memory());
+                let store =
+                    Arc::new(AttachmentStore::new(Box::new(backend)).with_threshold(usize::MAX));
+                coordinator.set_attachment_store(Some(Arc::clone(&store)));
+                store
+            });
+            coordinator
+                .hooks()
+                .set_data_limit(Arc::new(HookDataLimit::new(max_bytes, store)));
+        }
+        if let Some(latency) = hook_latency {
+            coordinator
+                .hooks()
+                .set_latency_budget(Arc::new(LatencyBudget::new(latency)));
+        }
+
+        #[cfg(feature = "otel")]
+        let telemetry = telemetry_config.enabled.then(|| {
+            let telemetry = Arc::new(
+                OtelTelemetry::global(telemetry_config).with_memory(&coordinator.memory(), &id),
+            );
+            telemetry.install(coordinator.hooks());
+            telemetry
+        });
+        #[cfg(not(feature = "otel"))]
+        if telemetry_config.enabled {
+            log::warn!(
+                "session.telemetry is enabled but amplifier-core was built without the `otel` feature — nothing will be exported"
+            );
+        }
+
         // Set default fields for all hook events
         coordinator.hooks().set_default_fields(serde_json::json!({
             "session_id": id,
             "parent_id": parent_id,
         }));
 
+        let hook_handlers = builtin::handlers(&builtin_hooks, quota.as_ref(), &coordinator);
+
+        let activity = ActivityTracker::install(&coordinator.hooks_shared());
+
+        let timeline = Arc::new(Timeline::new());
+        timeline.record(coordinator.clock().as_ref(), Milestone::Created, None);
+        coordinator
+            .cancellation()
+            .on_state_change(cancellation_recorder(&coordinator, &timeline));
+
         Self {
             session_id: id,
             parent_id,
+            eval_source: None,
             coordinator,
             initialized: AtomicBool::new(false),
             lifecycle_event_emitted: AtomicBool::new(false),
-            status: SessionState::Running,
+            status: RwLock::new(SessionState::Running),
             is_resumed: false,
+            conversation_store: None,
+            #[cfg(feature = "otel")]
+            telemetry,
+            timeline,
+            quota,
+            safety,
+            costs,
+            audit,
+            context_dedup,
+            provenance,
+            summarization,
+            tool_discovery,
+            checkpoints: CheckpointStore::new(),
+            prompt_history: PromptHistory::new(prompt_history),
+            heartbeat,
+            hook_subscriptions,
+            hook_handlers: Mutex::new(hook_handlers),
+            activity,
+            execution: ExecutionGate::new(reentrancy),
         }
     }
 
@@ -197,6 +637,36 @@ impl Session {
         session
     }
 
+    /// The session's quota enforcer, when `session.quota` sets any limit.
+    pub fn quota(&self) -> Option<Arc<QuotaEnforcer>> {
+        self.quota.clone()
+    }
+
+    /// The session's safety guard, when `session.safety` sets any limit.
+    pub fn safety(&self) -> Option<Arc<SafetyGuard>> {
+        self.safety.clone()
+    }
+
+    /// The session's cost tracker, when `session.pricing` is configured.
+    pub fn costs(&self) -> Option<Arc<CostTracker>> {
+        self.costs.clone()
+    }
+
+    /// The session's tool audit log, when `session.audit` is configured.
+    pub fn audit_log(&self) -> Option<Arc<AuditLog>> {
+        self.audit.clone()
+    }
+
+    /// Checkpoints taken with [`checkpoint()`](Self::checkpoint).
+    pub fn checkpoints(&self) -> &CheckpointStore {
+        &self.checkpoints
+    }
+
+    /// The session's lifecycle milestones (see [`crate::timeline`]).
+    pub fn timeline(&self) -> Arc<Timeline> {
+        Arc::clone(&self.timeline)
+    }
+
     /// The session ID.
     pub fn session_id(&self) -> &str {
         &self.session_id
@@ -207,9 +677,15 @@ impl Session {
         self.parent_id.as_deref()
     }
 
+    /// The ID of the session this one was cloned from by
+    /// [`clone_for_eval()`](Self::clone_for_eval).
+    pub fn eval_source(&self) -> Option<&str> {
+        self.eval_source.as_deref()
+    }
+
     /// Current session status as a string (matching Python's status field).
-    pub fn status(&self) -> &str {
-        match &self.status {
+    pub fn status(&self) -> &'static str {
+        match *self.status.read().unwrap() {
             SessionState::Running => "running",
             SessionState::Completed => "completed",
             SessionState::Failed => "failed",
@@ -217,9 +693,35 @@ impl Session {
         }
     }
 
-    /// Current session state enum.
-    pub fn state(&self) -> &SessionState {
-        &self.status
+    /// Current session state enum (a snapshot — the status may change
+    /// concurrently while `execute()` is running).
+    pub fn state(&self) -> SessionState {
+        self.status.read().unwrap().clone()
+    }
+
+    fn set_state(&self, state: SessionState) {
+        *self.status.write().unwrap() = state;
+    }
+
+    /// When the session last did work: the start or end of an `execute()`,
+    /// a tool call or a provider call (or [`touch()`](Self::touch)). Starts
+    /// at the session's creation.
+    pub fn last_activity(&self) -> DateTime<Utc> {
+        *self.activity.last.lock().unwrap()
+    }
+
+    /// Time since [`last_activity()`](Self::last_activity) on the session's
+    /// clock.
+    pub fn idle_time(&self) -> Duration {
+        (self.coordinator.clock().now_utc() - self.last_activity())
+            .to_std()
+            .unwrap_or_default()
+    }
+
+    /// Record activity now, for work the kernel does not see (e.g. a host
+    /// streaming the previous turn's output to a client).
+    pub fn touch(&self) {
+        self.activity.touch();
     }
 
     /// Whether the session has been initialized.
@@ -255,13 +757,78 @@ impl Session {
         Arc::clone(&self.coordinator)
     }
 
+    /// Attach host application data of type `T` to this session.
+    ///
+    /// Shorthand for [`Coordinator::set_host_data`]; tools and hooks that hold
+    /// the coordinator see the same value.
+    pub fn set_host_data<T: std::any::Any + Send + Sync>(&self, value: T) -> Option<Arc<T>> {
+        self.coordinator.set_host_data(value)
+    }
+
+    /// The host data of type `T` attached to this session, if any.
+    pub fn host_data<T: std::any::Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.coordinator.host_data()
+    }
+
+    /// Persist this session's conversation history in `store`.
+    ///
+    /// Every message the orchestrator adds to the context is appended to the
+    /// store under this session's ID. For sessions created with
+    /// [`new_resumed()`](Self::new_resumed), the first `execute()` loads the
+    /// stored history into the mounted context before the orchestrator runs.
+    /// Call during setup, alongside mounting modules.
+    pub fn set_conversation_store(&mut self, store: Arc<dyn ConversationStore>) {
+        self.conversation_store = Some(store);
+    }
+
+    /// The attached conversation store, if any.
+    pub fn conversation_store(&self) -> Option<Arc<dyn ConversationStore>> {
+        self.conversation_store.clone()
+    }
+
     /// Mark the session as initialized.
     ///
     /// In the Rust kernel, module loading is done externally (by the Python
     /// bridge or test harness). This method marks the session ready for
     /// execution after modules have been mounted.
     pub fn set_initialized(&self) {
-        self.initialized.store(true, Ordering::Relaxed);
+        if !self.initialized.swap(true, Ordering::Relaxed) {
+            self.timeline.record(
+                self.coordinator.clock().as_ref(),
+                Milestone::Initialized,
+                None,
+            );
+        }
+    }
+
+    /// Make `handler` available to `session.hooks.subscriptions` entries
+    /// whose `handler` is `name`. Call before [`initialize()`](Self::initialize).
+    ///
+    /// The [built-in handlers](crate::hooks::builtin) are provided already;
+    /// providing a handler under one of their names replaces it.
+    pub fn provide_hook_handler(&self, name: &str, handler: Arc<dyn HookHandler>) {
+        self.hook_handlers.lock().unwrap().insert(name, handler);
+    }
+
+    /// Register the declared hook subscriptions (see
+    /// [`crate::hook_subscriptions`]) and mark the session initialized.
+    ///
+    /// Does nothing if the session is already initialized, so subscriptions
+    /// are registered once.
+    ///
+    /// # Errors
+    ///
+    /// `SessionError::HookHandlerNotFound` if an enabled subscription names a
+    /// handler that was not provided; the session stays uninitialized and no
+    /// subscription is registered.
+    pub fn initialize(&self) -> Result<(), AmplifierError> {
+        let handlers = self.hook_handlers.lock().unwrap();
+        if self.is_initialized() {
+            return Ok(());
+        }
+        handlers.apply(&self.hook_subscriptions, self.coordinator.hooks())?;
+        self.set_initialized();
+        Ok(())
     }
 
     /// Clear the initialized flag (used during cleanup).
@@ -291,22 +858,141 @@ impl Session {
     /// first `execute()` call, then delegates to the orchestrator on every
     /// call.  Tracks status transitions on success, failure, or cancellation.
     ///
+    /// Before orchestration the prompt goes through `prompt:submit`
+    /// (payload `{"session_id", "prompt"}`):
+    ///
+    /// | Hook result      | Effect                                              |
+    /// |------------------|-----------------------------------------------------|
+    /// | `Deny`           | the turn is rejected with the hook's reason          |
+    /// | `Modify`         | `data.prompt` replaces the prompt                    |
+    /// | `InjectContext`  | the injection is added to the context as a message   |
+    ///
+    /// Injections are always stored: the orchestrator owns the LLM calls, so
+    /// there is no per-call channel for `ephemeral` ones at this point.
+    ///
+    /// A prompt identical to one submitted within
+    /// `session.prompt_history.duplicate_window_secs` emits `prompt:duplicate`
+    /// first and, with `replay_duplicates`, returns the earlier output without
+    /// executing (see [`crate::prompt_history`]).
+    ///
+    /// With `session.heartbeat` set, `session:heartbeat` is emitted
+    /// periodically while the orchestrator runs (see [`crate::heartbeat`]).
+    ///
+    /// Every event emitted during the call carries a fresh `turn_id` (see
+    /// [`crate::correlation`]).
+    ///
     /// # Errors
     ///
     /// - `SessionError::NotInitialized` if not initialized
+    /// - `CoordinatorError::MissingCapabilities` if a module's
+    ///   [required capability](Coordinator::require_capabilities) is not
+    ///   registered
     /// - `SessionError::Other("No orchestrator mounted")` if no orchestrator
     /// - `SessionError::Other("No context manager mounted")` if no context
     /// - `SessionError::Other("No providers mounted")` if providers map is empty
+    /// - `SessionError::PromptDenied` if a `prompt:submit` hook denies the prompt
+    /// - Any `ContextError` from adding a `prompt:submit` injection
+    /// - `ContextError::Storage` if resumed history cannot be loaded from the
+    ///   conversation store
+    /// - `SessionError::QuotaExceeded` if a `session.quota` limit is breached
+    ///   before or during the turn
+    /// - `SessionError::SafetyLimitExceeded` if a `session.safety` limit is
+    ///   hit during the turn
     /// - Any `AmplifierError` from the orchestrator
-    pub async fn execute(&mut self, prompt: &str) -> Result<String, AmplifierError> {
+    /// - `SessionError::Busy` if another `execute()` is in flight and
+    ///   `session.reentrancy` is not `"queue"`
+    pub async fn execute(&self, prompt: &str) -> Result<String, AmplifierError> {
+        self.submit(prompt, None)
+            .await
+            .map(|submitted| submitted.output)
+    }
+
+    /// [`execute()`](Self::execute), reporting where the output came from.
+    ///
+    /// `deadline` is stored on the coordinator only once the execution gate
+    /// admits the call, so a rejected or queued call cannot replace the
+    /// deadline of the turn in progress.
+    async fn submit(
+        &self,
+        prompt: &str,
+        deadline: Option<TurnDeadline>,
+    ) -> Result<Submitted, AmplifierError> {
+        let clock = self.coordinator.clock();
+        let turn_id = correlation::new_turn_id();
+        let _permit = self
+            .execution
+            .enter(prompt, &turn_id, clock.now_utc())
+            .await?;
+        let _deadline = deadline.map(|deadline| self.coordinator.scope_turn_deadline(deadline));
+        let _turn = CurrentTurn::set(&self.coordinator, turn_id.clone());
+        let ids = CorrelationIds {
+            turn_id: Some(turn_id),
+            ..Default::default()
+        };
+        // Boxed: the turn's future is too large to nest on the stack again.
+        correlation::scope(ids, Box::pin(self.submit_turn(prompt))).await
+    }
+
+    /// [`submit()`](Self::submit), once the turn ID is in place.
+    async fn submit_turn(&self, prompt: &str) -> Result<Submitted, AmplifierError> {
+        let now = self.coordinator.clock().now_utc();
+        if let Some(previous) = self.check_duplicate(prompt, now).await {
+            self.touch();
+            return Ok(Submitted {
+                id: previous.id,
+                output: previous.output.clone().unwrap_or_default(),
+                replayed: Some(previous),
+            });
+        }
+        let id = self.prompt_history.record(prompt, now);
+        let output = self.execute_exclusive(prompt).await?;
+        self.prompt_history.complete(id, &output);
+        Ok(Submitted {
+            id,
+            output,
+            replayed: None,
+        })
+    }
+
+    /// Emit `prompt:duplicate` if `prompt` repeats a recent one, returning
+    /// the earlier record when it is to be replayed.
+    async fn check_duplicate(&self, prompt: &str, now: DateTime<Utc>) -> Option<PromptRecord> {
+        let previous = self.prompt_history.duplicate_of(prompt, now)?;
+        let replayed = self.prompt_history.replays(&previous);
+        self.coordinator
+            .hooks()
+            .emit(
+                events::PROMPT_DUPLICATE,
+                serde_json::json!({
+                    "session_id": self.session_id,
+                    "prompt_hash": previous.prompt_hash,
+                    "previous_submitted_at": previous.submitted_at,
+                    "age_ms": (now - previous.submitted_at).num_milliseconds().max(0),
+                    "replayed": replayed,
+                }),
+            )
+            .await;
+        replayed.then_some(previous)
+    }
+
+    /// What is executing right now, if anything.
+    pub fn current_execution(&self) -> Option<CurrentExecution> {
+        self.execution.current()
+    }
+
+    /// [`execute()`](Self::execute), once the execution gate is held.
+    async fn execute_exclusive(&self, prompt: &str) -> Result<String, AmplifierError> {
         if !self.is_initialized() {
             return Err(AmplifierError::Session(SessionError::NotInitialized));
         }
+        self.coordinator.check_capabilities()?;
+        self.touch();
 
         // Emit lifecycle event once per session (not once per execute() call).
         // Pre-Rust Python kernel emitted in initialize(); we guard with an
         // atomic flag so the event fires on the first execute() only.
-        if self.claim_lifecycle_event() {
+        let first_execute = self.claim_lifecycle_event();
+        if first_execute {
             let event = if self.is_resumed {
                 events::SESSION_RESUME
             } else {
@@ -333,25 +1019,70 @@ impl Session {
         })?;
 
         // Get context
-        let context = self.coordinator.context().ok_or_else(|| {
-            AmplifierError::Session(SessionError::Other {
-                message: "No context manager mounted".into(),
-            })
-        })?;
+        let context = self.coordinator.context().ok_or_else(no_context)?;
+
+        // Route context writes through the conversation store, reloading the
+        // stored history once when resuming.
+        let context: Arc<dyn ContextManager> = match &self.conversation_store {
+            Some(store) => {
+                let persistent =
+                    PersistentContext::new(context, Arc::clone(store), self.session_id.clone());
+                if first_execute && self.is_resumed {
+                    persistent.restore().await?;
+                }
+                Arc::new(persistent)
+            }
+            None => context,
+        };
+        let context = self.summarizing_context(context)?;
+        let context: Arc<dyn ContextManager> = match &self.context_dedup {
+            Some(config) => Arc::new(DedupContext::new(
+                context,
+                config.clone(),
+                self.coordinator.hooks_shared(),
+            )),
+            None => context,
+        };
+        // Outermost, so stored and persisted messages carry the record.
+        let context: Arc<dyn ContextManager> = if self.provenance {
+            Arc::new(ProvenanceContext::new(
+                context,
+                Arc::clone(&self.coordinator),
+            ))
+        } else {
+            context
+        };
 
-        // Get providers
-        let providers = self.coordinator.providers();
+        // Get providers (the orchestrator takes its own copy of the maps)
+        let providers = HashMap::clone(&self.coordinator.providers());
         if providers.is_empty() {
             return Err(AmplifierError::Session(SessionError::Other {
                 message: "No providers mounted".into(),
             }));
         }
 
-        // Get tools
-        let tools = self.coordinator.tools();
+        let prompt = self.submit_prompt(prompt, context.as_ref()).await?;
+
+        // Get tools, including any proposed on `tools:discover` for this
+        // turn (unmounted when `_discovered` drops at the end of the turn).
+        let _discovered = tool_discovery::discover(
+            &self.coordinator,
+            &self.session_id,
+            &prompt,
+            &self.tool_discovery,
+        )
+        .await;
+        let tools = HashMap::clone(&self.coordinator.tools());
 
         // Execute orchestrator
-        self.status = SessionState::Running;
+        self.set_state(SessionState::Running);
+        if let Some(costs) = &self.costs {
+            costs.begin_turn();
+        }
+        if let Some(audit) = &self.audit {
+            audit.begin_turn();
+        }
+        let turn = self.timeline.start_turn(self.coordinator.clock());
 
         // Serialize hooks handler list and coordinator state for the orchestrator
         let hooks_value = serde_json::to_value(self.coordinator.hooks().list_handlers(None))
@@ -359,92 +1090,838 @@ impl Session {
         let coordinator_value =
             serde_json::to_value(self.coordinator.to_dict()).unwrap_or(serde_json::json!({}));
 
-        match orchestrator
-            .execute(
-                prompt.to_string(),
-                context,
-                providers,
-                tools,
-                hooks_value,
-                coordinator_value,
-            )
-            .await
-        {
-            Ok(result) => {
-                // Check cancellation
-                if self.coordinator.cancellation().is_cancelled() {
-                    self.status = SessionState::Cancelled;
-                } else {
-                    self.status = SessionState::Completed;
-                }
-                Ok(result)
+        #[cfg(feature = "otel")]
+        let turn_span = self
+            .telemetry
+            .as_ref()
+            .map(|t| t.start_turn(self.coordinator.turn_id().as_deref()));
+
+        let run = orchestrator.execute(
+            prompt,
+            context,
+            providers,
+            tools,
+            hooks_value,
+            coordinator_value,
+        );
+        let run = orchestrator_status::forward(self.coordinator.hooks(), run);
+        let run = async {
+            match &self.safety {
+                Some(safety) => safety.run(run).await,
+                None => run.await,
             }
-            Err(e) => {
-                if self.coordinator.cancellation().is_cancelled() {
-                    self.status = SessionState::Cancelled;
-                } else {
-                    self.status = SessionState::Failed;
-                }
-                Err(e)
+        };
+        let run = async {
+            match &self.quota {
+                Some(quota) => quota.run(run).await,
+                None => run.await,
             }
+        };
+        let outcome = match self.heartbeat {
+            Some(interval) => self.run_with_heartbeat(interval, run).await,
+            None => run.await,
+        };
+
+        // Turn boundary: report any memory pressure raised during the turn.
+        self.coordinator.emit_memory_pressure().await;
+        self.touch();
+
+        #[cfg(feature = "otel")]
+        if let Some(span) = turn_span {
+            span.finish(outcome.as_ref().err().map(ToString::to_string).as_deref());
         }
+
+        let state = match (&outcome, self.coordinator.cancellation().is_cancelled()) {
+            (_, true) => SessionState::Cancelled,
+            (Ok(_), false) => SessionState::Completed,
+            (Err(_), false) => SessionState::Failed,
+        };
+        self.set_state(state.clone());
+        turn.finish(state);
+        outcome
     }
 
-    /// Clean up session resources.
-    ///
-    /// Emits `session:end` event and runs all cleanup functions registered
-    /// on the coordinator.
-    pub async fn cleanup(&self) {
-        // Emit session:end event
-        self.coordinator
+    /// Drive `run`, emitting `session:heartbeat` every `interval`.
+    async fn run_with_heartbeat<F>(&self, interval: Duration, run: F) -> F::Output
+    where
+        F: std::future::Future,
+    {
+        let activity = Arc::new(TurnActivity::new());
+        let hooks = self.coordinator.hooks();
+        let _registrations = Registrations(
+            heartbeat::TRACKED_EVENTS
+                .iter()
+                .map(|event| {
+                    hooks.register(
+                        event,
+                        activity.clone(),
+                        i32::MAX,
+                        Some(format!("heartbeat:{}", self.session_id)),
+                    )
+                })
+                .collect(),
+        );
+        let clock = self.coordinator.clock();
+        let started = clock.now();
+        let beat = || {
+            let status = if self.coordinator.cancellation().is_cancelled() {
+                "cancelling"
+            } else {
+                self.status()
+            };
+            serde_json::json!({
+                "session_id": self.session_id,
+                "status": status,
+                "elapsed_ms": clock.now().saturating_duration_since(started).as_millis() as u64,
+                "usage": activity.usage(),
+                "in_flight_tools": activity.in_flight_tools(),
+            })
+        };
+        heartbeat::with_heartbeat(hooks, interval, beat, run).await
+    }
+
+    /// Wrap `context` in a [`SummarizingContext`] when `session.summarization`
+    /// is configured, summarizing with the named provider or else the first
+    /// mounted provider by name.
+    fn summarizing_context(
+        &self,
+        context: Arc<dyn ContextManager>,
+    ) -> Result<Arc<dyn ContextManager>, AmplifierError> {
+        let Some(config) = &self.summarization else {
+            return Ok(context);
+        };
+        let providers = self.coordinator.providers();
+        let provider = match &config.provider {
+            Some(name) => providers.get(name).cloned().ok_or_else(|| {
+                AmplifierError::Session(SessionError::Other {
+                    message: format!("Summarization provider '{name}' is not mounted"),
+                })
+            })?,
+            None => match providers.iter().min_by(|a, b| a.0.cmp(b.0)) {
+                Some((_, provider)) => Arc::clone(provider),
+                // `execute()` reports the missing providers.
+                None => return Ok(context),
+            },
+        };
+        let summarizer = ProviderSummarizer::from_config(provider, config)
+            .with_invoker(ProviderInvoker::from_coordinator(&self.coordinator));
+        Ok(Arc::new(SummarizingContext::new(
+            context,
+            Arc::new(summarizer),
+            config.clone(),
+            self.coordinator.hooks_shared(),
+        )))
+    }
+
+    /// Run `prompt` through `prompt:submit` and return the prompt to execute.
+    async fn submit_prompt(
+        &self,
+        prompt: &str,
+        context: &dyn ContextManager,
+    ) -> Result<String, AmplifierError> {
+        let result = self
+            .coordinator
             .hooks()
             .emit(
-                events::SESSION_END,
+                events::PROMPT_SUBMIT,
                 serde_json::json!({
                     "session_id": self.session_id,
-                    "status": self.status(),
+                    "prompt": prompt,
                 }),
             )
             .await;
 
-        // Run coordinator cleanup
-        self.coordinator.cleanup().await;
-
-        // Clear initialized flag so session cannot be re-executed
-        self.clear_initialized();
+        if result.action == HookAction::Deny {
+            return Err(AmplifierError::Session(SessionError::PromptDenied {
+                reason: result
+                    .reason
+                    .unwrap_or_else(|| "no reason given".to_string()),
+            }));
+        }
+        if result.action == HookAction::InjectContext {
+            if let Some(injection) = &result.context_injection {
+                context
+                    .add_message(serde_json::json!({
+                        "role": result.context_injection_role,
+                        "content": injection,
+                    }))
+                    .await?;
+            }
+        }
+        // Modified payloads come back as the final result's data.
+        Ok(result
+            .data
+            .as_ref()
+            .and_then(|data| data.get("prompt"))
+            .and_then(Value::as_str)
+            .unwrap_or(prompt)
+            .to_string())
     }
-}
 
-// ---------------------------------------------------------------------------
-// Tests
-// ---------------------------------------------------------------------------
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::testing::{
-        CapturingOrchestrator, FakeContextManager, FakeHookHandler, FakeOrchestrator, FakeProvider,
-        FakeTool,
-    };
-    use std::sync::Arc;
-
-    // ---------------------------------------------------------------
-    // SessionConfig validation
-    // ---------------------------------------------------------------
+    /// Execute a prompt and return the turn's structured result.
+    ///
+    /// Runs [`execute()`](Self::execute) with a [`TurnRecorder`] registered
+    /// for the duration of the turn (at the lowest priority, so it sees
+    /// payloads after other handlers have modified them), so the result carries the assistant
+    /// content blocks, tool call records, summed usage, degradations and stop
+    /// reason alongside the orchestrator's final string. With
+    /// `session.pricing` configured, a usage total without a provider-reported
+    /// cost gets the turn's estimated cost. A replayed duplicate prompt
+    /// returns the earlier turn's result.
+    ///
+    /// # Errors
+    ///
+    /// Everything [`execute()`](Self::execute) can return.
+    pub async fn run_turn(&self, prompt: &str) -> Result<TurnResult, AmplifierError> {
+        let recorder = Arc::new(TurnRecorder::new());
+        let hooks = self.coordinator.hooks();
+        let registrations = Registrations(
+            turn::RECORDED_EVENTS
+                .iter()
+                .map(|event| {
+                    hooks.register(
+                        event,
+                        recorder.clone(),
+                        i32::MAX,
+                        Some(format!("turn-recorder:{}", self.session_id)),
+                    )
+                })
+                .collect(),
+        );
 
-    #[test]
-    fn session_config_requires_orchestrator() {
-        let config = serde_json::json!({
-            "session": {
-                "context": "context-simple"
+        let outcome = self.submit(prompt, None).await;
+        drop(registrations);
+        outcome.map(|submitted| {
+            if let Some(previous) = submitted.replayed {
+                return previous
+                    .turn
+                    .unwrap_or_else(|| recorder.finish(submitted.output));
             }
-        });
-        let err = SessionConfig::from_value(config).unwrap_err();
-        assert!(err.to_string().contains("orchestrator"));
+            let id = submitted.id;
+            let mut result = recorder.finish(submitted.output);
+            let turn_cost = self
+                .costs
+                .as_ref()
+                .and_then(|costs| costs.current_turn())
+                .and_then(|turn| turn.cost_usd);
+            if let Some(usage) = result.usage.as_mut().filter(|u| u.cost_usd.is_none()) {
+                usage.cost_usd = turn_cost;
+            }
+            self.prompt_history.attach_turn(id, &result);
+            result
+        })
     }
 
-    #[test]
-    fn session_config_requires_context() {
+    /// Execute a prompt that must finish within `timeout`.
+    ///
+    /// The deadline is stored on the coordinator once the turn starts and
+    /// until it ends, so provider calls made through
+    /// [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) and
+    /// kernel-side tool calls are given at most the time remaining. If the
+    /// orchestrator is still running at the deadline, it is dropped and the
+    /// session is marked failed.
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::DeadlineExceeded` if the turn overruns `timeout`
+    /// - Everything [`execute()`](Self::execute) can return
+    pub async fn execute_with_deadline(
+        &self,
+        prompt: &str,
+        timeout: Duration,
+    ) -> Result<String, AmplifierError> {
+        let deadline = TurnDeadline::after_on(self.coordinator.clock(), timeout);
+        let outcome =
+            deadline::run_until(Some(deadline.clone()), self.submit(prompt, Some(deadline)))
+                .await
+                .map(|outcome| outcome.map(|submitted| submitted.output));
+
+        outcome.unwrap_or_else(|| {
+            self.set_state(SessionState::Failed);
+            Err(AmplifierError::Session(SessionError::DeadlineExceeded {
+                timeout_ms: timeout.as_millis() as u64,
+            }))
+        })
+    }
+
+    /// Start executing `prompt` on a new task and return a handle to it.
+    ///
+    /// The handle replaces a raw `JoinHandle` plus the coordinator's
+    /// [`CancellationToken`]: [`abort()`](ExecutionHandle::abort) escalates
+    /// from graceful to immediate cancellation, and dropping the handle
+    /// without joining aborts the execution.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn execute_handle(self: &Arc<Self>, prompt: &str) -> ExecutionHandle {
+        let status = Arc::new(Mutex::new(ExecutionStatus::Running));
+        let session = Arc::clone(self);
+        let prompt = prompt.to_string();
+        let finished = Arc::clone(&status);
+        let task = tokio::spawn(async move {
+            let outcome = session.execute(&prompt).await;
+            let cancelled = session.coordinator.cancellation().is_cancelled();
+            *finished.lock().unwrap() = match (&outcome, cancelled) {
+                (_, true) => ExecutionStatus::Cancelled,
+                (Ok(_), false) => ExecutionStatus::Completed,
+                (Err(_), false) => ExecutionStatus::Failed,
+            };
+            outcome
+        });
+        ExecutionHandle {
+            session: Arc::clone(self),
+            task: Some(task),
+            status,
+        }
+    }
+
+    /// Prompts submitted to this session (see [`crate::prompt_history`]).
+    pub fn prompt_history(&self) -> &PromptHistory {
+        &self.prompt_history
+    }
+
+    /// Snapshot the context's message history and the turn number
+    /// (see [`crate::checkpoint`]).
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::Other("No context manager mounted")` if no context
+    /// - Any `ContextError` from reading the messages
+    pub async fn checkpoint(&self) -> Result<CheckpointId, AmplifierError> {
+        let context = self.coordinator.context().ok_or_else(no_context)?;
+        let messages = context.get_messages().await?;
+        Ok(self.checkpoints.insert(
+            messages,
+            self.coordinator.turn_number(),
+            self.coordinator.clock().now_utc(),
+        ))
+    }
+
+    /// Restore the message history and turn number saved by
+    /// [`checkpoint()`](Self::checkpoint), and emit `session:rewind`.
+    ///
+    /// With a conversation store, the stored history is replaced too.
+    /// Checkpoints taken after `checkpoint` are kept; the prompt history is
+    /// cleared.
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::CheckpointNotFound` for an unknown checkpoint
+    /// - `SessionError::Other("No context manager mounted")` if no context
+    /// - Any `ContextError` from replacing the messages
+    pub async fn rewind(&self, checkpoint: CheckpointId) -> Result<(), AmplifierError> {
+        let saved = self.checkpoints.get(checkpoint).ok_or_else(|| {
+            AmplifierError::Session(SessionError::CheckpointNotFound {
+                checkpoint: checkpoint.to_string(),
+            })
+        })?;
+        let context = self.coordinator.context().ok_or_else(no_context)?;
+        let context: Arc<dyn ContextManager> = match &self.conversation_store {
+            Some(store) => Arc::new(PersistentContext::new(
+                context,
+                Arc::clone(store),
+                self.session_id.clone(),
+            )),
+            None => context,
+        };
+        let message_count = saved.messages.len();
+        context.set_messages(saved.messages).await?;
+        self.coordinator.rewind_turn(saved.turn_number);
+        self.prompt_history.clear();
+
+        let clock = self.coordinator.clock();
+        self.timeline.record(
+            clock.as_ref(),
+            Milestone::Rewound {
+                checkpoint,
+                turn_number: saved.turn_number,
+            },
+            None,
+        );
+        self.coordinator
+            .hooks()
+            .emit(
+                events::SESSION_REWIND,
+                serde_json::json!({
+                    "session_id": self.session_id,
+                    "checkpoint": checkpoint,
+                    "turn_number": saved.turn_number,
+                    "message_count": message_count,
+                }),
+            )
+            .await;
+        Ok(())
+    }
+
+    /// An independent copy of this session, for running the same state
+    /// against different models or configs side by side.
+    ///
+    /// The clone has a fresh session ID and its own coordinator built from
+    /// this session's config, so it shares no cancellation token, hooks or
+    /// turn tracking with it. `context` is seeded with a copy of this
+    /// session's messages, and the clone continues from the same turn
+    /// number on the same clock. The orchestrator, providers, tools,
+    /// approval provider, display service and moderator are shared; mount
+    /// others on the clone's coordinator to compare them. Hook handlers and
+    /// checkpoints are not copied. The clone records its tool calls in this
+    /// session's audit log rather than opening the log's file again.
+    ///
+    /// Every event the clone emits carries `eval_source`, this session's ID.
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::Other("No context manager mounted")` if no context
+    /// - Any `ContextError` from reading this session's messages or seeding
+    ///   `context`
+    pub async fn clone_for_eval(
+        &self,
+        context: Arc<dyn ContextManager>,
+    ) -> Result<Session, AmplifierError> {
+        let source = self.coordinator.context().ok_or_else(no_context)?;
+        context.set_messages(source.get_messages().await?).await?;
+
+        let mut config = SessionConfig {
+            config: self.coordinator.config().clone(),
+        };
+        if let Some(Value::Object(session)) = config.config.get_mut("session") {
+            session.remove("audit");
+        }
+        let mut clone = Session::new(config, None, self.parent_id.clone());
+        clone.eval_source = Some(self.session_id.clone());
+        if let Some(audit) = &self.audit {
+            audit.install(clone.coordinator.hooks());
+            clone.audit = Some(Arc::clone(audit));
+        }
+        let coordinator = &clone.coordinator;
+        coordinator.set_clock(self.coordinator.clock());
+        coordinator.rewind_turn(self.coordinator.turn_number());
+        coordinator.set_context(context);
+        if let Some(orchestrator) = self.coordinator.orchestrator() {
+            coordinator.set_orchestrator(orchestrator);
+        }
+        for (name, provider) in self.coordinator.providers().iter() {
+            coordinator.mount_provider(name, Arc::clone(provider));
+        }
+        for (name, tool) in self.coordinator.tools().iter() {
+            coordinator.mount_tool(name, Arc::clone(tool));
+        }
+        if let Some(approval) = self.coordinator.approval_provider() {
+            coordinator.set_approval_provider(approval);
+        }
+        if let Some(display) = self.coordinator.display_service() {
+            coordinator.set_display_service(display);
+        }
+        if self.coordinator.has_display_provider() {
+            if let Some(display) = self.coordinator.display_provider() {
+                coordinator.set_display_provider(display);
+            }
+        }
+        if let Some(moderator) = self.coordinator.moderator() {
+            coordinator.set_moderator(moderator);
+        }
+
+        let hooks = coordinator.hooks();
+        let mut fields = match hooks.default_fields() {
+            Some(Value::Object(fields)) => fields,
+            _ => serde_json::Map::new(),
+        };
+        fields.insert("eval_source".into(), Value::String(self.session_id.clone()));
+        hooks.set_default_fields(Value::Object(fields));
+
+        if self.is_initialized() {
+            clone.set_initialized();
+        }
+        Ok(clone)
+    }
+
+    /// Clean up session resources.
+    ///
+    /// Emits `session:end` event and runs all cleanup functions registered
+    /// on the coordinator.
+    pub async fn cleanup(&self) {
+        let clock = self.coordinator.clock();
+        let started = clock.now();
+        self.timeline
+            .record(clock.as_ref(), Milestone::CleanupStarted, None);
+
+        // Emit session:end event
+        self.coordinator
+            .hooks()
+            .emit(
+                events::SESSION_END,
+                serde_json::json!({
+                    "session_id": self.session_id,
+                    "status": self.status(),
+                }),
+            )
+            .await;
+
+        // Run coordinator cleanup
+        self.coordinator.cleanup().await;
+
+        // Clear initialized flag so session cannot be re-executed
+        self.clear_initialized();
+
+        self.timeline.record(
+            clock.as_ref(),
+            Milestone::CleanupFinished,
+            Some(clock.now().saturating_duration_since(started)),
+        );
+    }
+}
+
+fn no_context() -> AmplifierError {
+    AmplifierError::Session(SessionError::Other {
+        message: "No context manager mounted".into(),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Activity tracking
+// ---------------------------------------------------------------------------
+
+/// Events that count as session activity besides `execute()` itself.
+const ACTIVITY_EVENTS: [&str; 6] = [
+    events::TOOL_PRE,
+    events::TOOL_POST,
+    events::PROVIDER_PRE,
+    events::PROVIDER_POST,
+    events::PROVIDER_REQUEST,
+    events::PROVIDER_RESPONSE,
+];
+
+/// The session's last-activity time, kept current by an
+/// [emit observer](HookRegistry::on_emit) watching [`ACTIVITY_EVENTS`].
+struct ActivityTracker {
+    /// Weak: the registry owns this tracker through its observer.
+    hooks: Weak<HookRegistry>,
+    last: Mutex<DateTime<Utc>>,
+}
+
+impl ActivityTracker {
+    fn install(hooks: &Arc<HookRegistry>) -> Arc<Self> {
+        let tracker = Arc::new(Self {
+            hooks: Arc::downgrade(hooks),
+            last: Mutex::new(hooks.clock().now_utc()),
+        });
+        let observer = Arc::clone(&tracker);
+        hooks.on_emit(Arc::new(move |event| {
+            if ACTIVITY_EVENTS.contains(&event) {
+                observer.touch();
+            }
+        }));
+        tracker
+    }
+
+    fn touch(&self) {
+        if let Some(hooks) = self.hooks.upgrade() {
+            *self.last.lock().unwrap() = hooks.clock().now_utc();
+        }
+    }
+}
+
+/// Records cancellation requests and escalations on the session timeline.
+fn cancellation_recorder(
+    coordinator: &Arc<Coordinator>,
+    timeline: &Arc<Timeline>,
+) -> StateChangeCallback {
+    let hooks = coordinator.hooks_shared();
+    let timeline = Arc::clone(timeline);
+    Arc::new(move |from, to| {
+        if to != CancellationState::None && from != to {
+            timeline.record(
+                hooks.clock().as_ref(),
+                Milestone::CancellationRequested { state: to },
+                None,
+            );
+        }
+    })
+}
+
+/// The output of one `execute()` and where it came from.
+struct Submitted {
+    /// The prompt's id in the [`PromptHistory`].
+    id: u64,
+    output: String,
+    /// The earlier submission answered from the history, if any.
+    replayed: Option<PromptRecord>,
+}
+
+// ---------------------------------------------------------------------------
+// Re-entrancy
+// ---------------------------------------------------------------------------
+
+/// What [`Session::execute`] does when another execution is in flight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReentrancyPolicy {
+    /// Fail with `SessionError::Busy`.
+    #[default]
+    Reject,
+    /// Wait for the running execution, then run in FIFO order.
+    Queue,
+}
+
+/// The execution in flight, as reported by [`Session::current_execution`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrentExecution {
+    /// The prompt as passed to `execute()`, before `prompt:submit` hooks.
+    pub prompt: String,
+    /// The `turn_id` carried by this execution's events (see
+    /// [`crate::correlation`]).
+    pub turn_id: String,
+    pub started_at: DateTime<Utc>,
+    /// Executions waiting behind this one (always 0 under
+    /// [`ReentrancyPolicy::Reject`]).
+    pub queued: usize,
+}
+
+/// Serializes `execute()` calls on one session.
+struct ExecutionGate {
+    policy: ReentrancyPolicy,
+    /// Tokio's mutex grants the lock in the order it was requested.
+    lock: tokio::sync::Mutex<()>,
+    current: Mutex<Option<CurrentExecution>>,
+    queued: AtomicUsize,
+}
+
+impl ExecutionGate {
+    fn new(policy: ReentrancyPolicy) -> Self {
+        Self {
+            policy,
+            lock: tokio::sync::Mutex::new(()),
+            current: Mutex::new(None),
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    async fn enter(
+        &self,
+        prompt: &str,
+        turn_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ExecutionPermit<'_>, SessionError> {
+        let guard = match self.policy {
+            ReentrancyPolicy::Reject => self.lock.try_lock().map_err(|_| SessionError::Busy)?,
+            ReentrancyPolicy::Queue => {
+                let _waiting = QueuedSlot::new(&self.queued);
+                self.lock.lock().await
+            }
+        };
+        *self.current.lock().unwrap() = Some(CurrentExecution {
+            prompt: prompt.to_string(),
+            turn_id: turn_id.to_string(),
+            started_at: now,
+            queued: 0,
+        });
+        Ok(ExecutionPermit {
+            gate: self,
+            _guard: guard,
+        })
+    }
+
+    fn current(&self) -> Option<CurrentExecution> {
+        let mut current = self.current.lock().unwrap().clone()?;
+        current.queued = self.queued.load(Ordering::Acquire);
+        Some(current)
+    }
+}
+
+/// Held for the duration of one execution.
+struct ExecutionPermit<'a> {
+    gate: &'a ExecutionGate,
+    _guard: tokio::sync::MutexGuard<'a, ()>,
+}
+
+impl Drop for ExecutionPermit<'_> {
+    fn drop(&mut self) {
+        *self.gate.current.lock().unwrap() = None;
+    }
+}
+
+/// Counts a queued `execute()`, including one dropped while waiting.
+struct QueuedSlot<'a>(&'a AtomicUsize);
+
+impl<'a> QueuedSlot<'a> {
+    fn new(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::AcqRel);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// The coordinator's turn ID for one execution, cleared on drop (including
+/// when the execution is timed out or aborted).
+struct CurrentTurn<'a>(&'a Coordinator);
+
+impl<'a> CurrentTurn<'a> {
+    fn set(coordinator: &'a Coordinator, turn_id: String) -> Self {
+        coordinator.set_turn_id(Some(turn_id));
+        Self(coordinator)
+    }
+}
+
+impl Drop for CurrentTurn<'_> {
+    fn drop(&mut self) {
+        self.0.set_turn_id(None);
+    }
+}
+
+/// Hook registrations made for one execution, unregistered on drop so an
+/// execution that is timed out or aborted does not leave them behind.
+struct Registrations(Vec<Box<dyn Fn() + Send + Sync>>);
+
+impl Drop for Registrations {
+    fn drop(&mut self) {
+        for unregister in &self.0 {
+            unregister();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ExecutionHandle
+// ---------------------------------------------------------------------------
+
+/// Progress of an execution started with [`Session::execute_handle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Running,
+    /// Graceful cancellation was requested; the orchestrator is finishing
+    /// its current tools.
+    Cancelling,
+    Completed,
+    Failed,
+    /// Finished after cancellation was requested, or aborted outright.
+    Cancelled,
+}
+
+impl ExecutionStatus {
+    /// Whether the execution has stopped running.
+    pub fn is_finished(self) -> bool {
+        !matches!(self, Self::Running | Self::Cancelling)
+    }
+}
+
+/// A running [`Session::execute_handle`] execution.
+///
+/// Cancellation goes through the session's [`CancellationToken`], so
+/// orchestrators and tools see it exactly as they see a host-requested
+/// cancel. Dropping the handle without calling [`join()`](Self::join)
+/// aborts the execution immediately.
+pub struct ExecutionHandle {
+    session: Arc<Session>,
+    task: Option<JoinHandle<Result<String, AmplifierError>>>,
+    status: Arc<Mutex<ExecutionStatus>>,
+}
+
+impl ExecutionHandle {
+    /// Current status (a snapshot).
+    pub fn status(&self) -> ExecutionStatus {
+        let status = *self.status.lock().unwrap();
+        if status == ExecutionStatus::Running && self.cancellation().is_cancelled() {
+            ExecutionStatus::Cancelling
+        } else {
+            status
+        }
+    }
+
+    /// Ask the execution to stop.
+    ///
+    /// The first call requests graceful cancellation, letting running tools
+    /// finish. A second call (or a call after the token was already
+    /// cancelled) escalates to [`abort_now()`](Self::abort_now).
+    pub fn abort(&self) {
+        if !self.cancellation().request_graceful() {
+            self.abort_now();
+        }
+    }
+
+    /// Request immediate cancellation and stop the task at its next await
+    /// point. The session is marked cancelled.
+    pub fn abort_now(&self) {
+        self.cancellation().request_immediate();
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+        let mut status = self.status.lock().unwrap();
+        if !status.is_finished() {
+            *status = ExecutionStatus::Cancelled;
+            self.session.set_state(SessionState::Cancelled);
+        }
+    }
+
+    /// Wait for the execution to finish.
+    ///
+    /// # Errors
+    ///
+    /// - `SessionError::Aborted` if the task was stopped by
+    ///   [`abort_now()`](Self::abort_now)
+    /// - `SessionError::Other` if the task panicked
+    /// - Everything [`Session::execute`] can return
+    pub async fn join(mut self) -> Result<String, AmplifierError> {
+        let task = self.task.take().expect("task is only taken by join");
+        match task.await {
+            Ok(outcome) => outcome,
+            Err(e) if e.is_cancelled() => Err(AmplifierError::Session(SessionError::Aborted)),
+            Err(e) => {
+                *self.status.lock().unwrap() = ExecutionStatus::Failed;
+                self.session.set_state(SessionState::Failed);
+                Err(AmplifierError::Session(SessionError::Other {
+                    message: format!("execution task panicked: {e}"),
+                }))
+            }
+        }
+    }
+
+    fn cancellation(&self) -> &CancellationToken {
+        self.session.coordinator.cancellation()
+    }
+}
+
+impl Drop for ExecutionHandle {
+    fn drop(&mut self) {
+        if self.task.as_ref().is_some_and(|task| !task.is_finished()) {
+            self.abort_now();
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        CapturingOrchestrator, FakeContextManager, FakeHookHandler, FakeOrchestrator, FakeProvider,
+        FakeTool,
+    };
+    use std::sync::Arc;
+
+    // ---------------------------------------------------------------
+    // SessionConfig validation
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn session_config_requires_orchestrator() {
+        let config = serde_json::json!({
+            "session": {
+                "context": "context-simple"
+            }
+        });
+        let err = SessionConfig::from_value(config).unwrap_err();
+        assert!(err.to_string().contains("orchestrator"));
+    }
+
+    #[test]
+    fn session_config_requires_context() {
         let config = serde_json::json!({
             "session": {
                 "orchestrator": "loop-basic"
@@ -466,6 +1943,16 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn validate_mount_plan_checks_against_the_catalog() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let problems = config.validate_mount_plan(&ModuleCatalog::new());
+        assert_eq!(problems.len(), 2);
+        assert!(problems
+            .iter()
+            .all(|p| matches!(p, MountPlanProblem::UnknownModule { .. })));
+    }
+
     // ---------------------------------------------------------------
     // Session creation
     // ---------------------------------------------------------------
@@ -505,7 +1992,7 @@ mod tests {
         let config = SessionConfig::minimal("loop-basic", "context-simple");
         let session = Session::new(config, None, None);
         assert_eq!(session.status(), "running");
-        assert_eq!(*session.state(), SessionState::Running);
+        assert_eq!(session.state(), SessionState::Running);
     }
 
     #[test]
@@ -522,7 +2009,7 @@ mod tests {
     #[tokio::test]
     async fn execute_fails_when_not_initialized() {
         let config = SessionConfig::minimal("loop-basic", "context-simple");
-        let mut session = Session::new(config, None, None);
+        let session = Session::new(config, None, None);
 
         let result = session.execute("hello").await;
         assert!(result.is_err());
@@ -590,6 +2077,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn execute_fails_before_starting_when_required_capability_is_missing() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session
+            .coordinator()
+            .require_capabilities("tool-search", &["vector-store"]);
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = session.coordinator().hooks().register(
+            events::SESSION_START,
+            recorder.clone(),
+            0,
+            None,
+        );
+        session.set_initialized();
+
+        let err = session.execute("hello").await.unwrap_err();
+        assert_eq!(err.code(), "coordinator.missing_capabilities");
+        assert!(recorder.recorded_events().is_empty());
+    }
+
     // ---------------------------------------------------------------
     // Execute — success path
     // ---------------------------------------------------------------
@@ -634,7 +2151,7 @@ mod tests {
         session.set_initialized();
 
         let _ = session.execute("hello").await;
-        assert_eq!(*session.state(), SessionState::Completed);
+        assert_eq!(session.state(), SessionState::Completed);
     }
 
     #[tokio::test]
@@ -656,16 +2173,95 @@ mod tests {
         session.coordinator().cancellation().request_graceful();
 
         let _ = session.execute("hello").await;
-        assert_eq!(*session.state(), SessionState::Cancelled);
+        assert_eq!(session.state(), SessionState::Cancelled);
     }
 
     // ---------------------------------------------------------------
     // Hook events
     // ---------------------------------------------------------------
 
-    /// Verify session:start is emitted on first execute() — and only once
-    /// even when execute() is called multiple times on the same session.
-    #[tokio::test]
+    /// A ready session with a capturing orchestrator and a `prompt:submit`
+    /// hook returning `result`.
+    fn session_with_prompt_hook(
+        result: crate::models::HookResult,
+    ) -> (Session, Arc<CapturingOrchestrator>, Arc<FakeContextManager>) {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        let orchestrator = Arc::new(CapturingOrchestrator::new("ok"));
+        let context = Arc::new(FakeContextManager::new());
+        session
+            .coordinator_mut()
+            .set_orchestrator(orchestrator.clone());
+        session.coordinator_mut().set_context(context.clone());
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        let _ = session.coordinator().hooks().register(
+            events::PROMPT_SUBMIT,
+            Arc::new(FakeHookHandler::with_result(result)),
+            0,
+            Some("prompt-hook".into()),
+        );
+        session.set_initialized();
+        (session, orchestrator, context)
+    }
+
+    #[tokio::test]
+    async fn prompt_submit_modify_rewrites_the_prompt() {
+        let (session, orchestrator, _) = session_with_prompt_hook(crate::models::HookResult {
+            action: HookAction::Modify,
+            data: Some(HashMap::from([(
+                "prompt".to_string(),
+                serde_json::json!("hello, politely"),
+            )])),
+            ..Default::default()
+        });
+        session.execute("hello").await.unwrap();
+        assert_eq!(
+            orchestrator.last_prompt().as_deref(),
+            Some("hello, politely")
+        );
+    }
+
+    #[tokio::test]
+    async fn prompt_submit_deny_rejects_the_turn() {
+        let (session, orchestrator, _) = session_with_prompt_hook(crate::models::HookResult {
+            action: HookAction::Deny,
+            reason: Some("contains a secret".into()),
+            ..Default::default()
+        });
+        let err = session.execute("my key is sk-123").await.unwrap_err();
+        assert!(matches!(
+            err,
+            AmplifierError::Session(SessionError::PromptDenied { ref reason })
+                if reason == "contains a secret"
+        ));
+        assert!(orchestrator.last_prompt().is_none());
+    }
+
+    #[tokio::test]
+    async fn prompt_submit_injection_is_added_to_context() {
+        let (session, orchestrator, context) =
+            session_with_prompt_hook(crate::models::HookResult {
+                action: HookAction::InjectContext,
+                context_injection: Some("User prefers metric units.".into()),
+                ..Default::default()
+            });
+        session.execute("weather?").await.unwrap();
+        assert_eq!(orchestrator.last_prompt().as_deref(), Some("weather?"));
+        let messages = context.get_messages().await.unwrap();
+        assert_eq!(
+            messages,
+            vec![serde_json::json!({
+                "role": "system",
+                "content": "User prefers metric units.",
+            })]
+        );
+    }
+
+    /// Verify session:start is emitted on first execute() — and only once
+    /// even when execute() is called multiple times on the same session.
+    #[tokio::test]
     async fn execute_emits_session_start_event() {
         let config = SessionConfig::minimal("loop-basic", "context-simple");
         let mut session = Session::new(config, None, None);
@@ -709,6 +2305,268 @@ mod tests {
         );
     }
 
+    /// Orchestrator that records the prompt in context and returns the
+    /// number of messages it saw.
+    struct RecordingOrchestrator;
+
+    impl crate::traits::Orchestrator for RecordingOrchestrator {
+        fn execute(
+            &self,
+            prompt: String,
+            context: Arc<dyn ContextManager>,
+            _providers: HashMap<String, Arc<dyn crate::traits::Provider>>,
+            _tools: HashMap<String, Arc<dyn crate::traits::Tool>>,
+            _hooks: Value,
+            _coordinator: Value,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<String, AmplifierError>> + Send + '_>,
+        > {
+            Box::pin(async move {
+                context
+                    .add_message(serde_json::json!({"role": "user", "content": prompt}))
+                    .await?;
+                Ok(context.get_messages().await?.len().to_string())
+            })
+        }
+    }
+
+    fn session_with_store(resumed: bool, store: Arc<dyn ConversationStore>) -> Session {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = if resumed {
+            Session::new_resumed(config, "stored-id".into(), None)
+        } else {
+            Session::new(config, Some("stored-id".into()), None)
+        };
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(RecordingOrchestrator));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_conversation_store(store);
+        session.set_initialized();
+        session
+    }
+
+    #[tokio::test]
+    async fn resumed_session_reloads_history_from_conversation_store() {
+        use crate::conversation_store::InMemoryConversationStore;
+
+        let store: Arc<dyn ConversationStore> = Arc::new(InMemoryConversationStore::new());
+
+        let first = session_with_store(false, store.clone());
+        assert_eq!(first.execute("one").await.unwrap(), "1");
+        assert_eq!(first.execute("two").await.unwrap(), "2");
+        assert_eq!(
+            store.load_range("stored-id", 0, None).await.unwrap().len(),
+            2
+        );
+
+        // A fresh process: new context manager, same store.
+        let resumed = session_with_store(true, store.clone());
+        assert_eq!(resumed.execute("three").await.unwrap(), "3");
+        // History is reloaded only once, not on every turn.
+        assert_eq!(resumed.execute("four").await.unwrap(), "4");
+        assert_eq!(
+            store.load_range("stored-id", 0, None).await.unwrap().len(),
+            4
+        );
+    }
+
+    /// Orchestrator that reports interim states.
+    struct StatusOrchestrator;
+
+    impl crate::traits::Orchestrator for StatusOrchestrator {
+        fn execute(
+            &self,
+            _prompt: String,
+            _context: Arc<dyn ContextManager>,
+            _providers: HashMap<String, Arc<dyn crate::traits::Provider>>,
+            _tools: HashMap<String, Arc<dyn crate::traits::Tool>>,
+            _hooks: Value,
+            _coordinator: Value,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<String, AmplifierError>> + Send + '_>,
+        > {
+            use crate::orchestrator_status::OrchestratorStatus;
+            Box::pin(async move {
+                orchestrator_status::report(OrchestratorStatus::Thinking);
+                orchestrator_status::report(OrchestratorStatus::CallingTool {
+                    name: "echo".into(),
+                    tool_call_id: None,
+                });
+                Ok("done".into())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_forwards_orchestrator_status_events() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(StatusOrchestrator));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = session.coordinator().hooks().register(
+            events::ORCHESTRATOR_STATUS,
+            recorder.clone(),
+            0,
+            None,
+        );
+
+        assert_eq!(session.execute("go").await.unwrap(), "done");
+        let states: Vec<_> = recorder
+            .recorded_events()
+            .into_iter()
+            .map(|(_, data)| data["state"].clone())
+            .collect();
+        assert_eq!(states, vec!["thinking", "calling_tool"]);
+    }
+
+    /// Orchestrator that sleeps, recording whether it saw a turn deadline.
+    struct SlowOrchestrator {
+        delay: std::time::Duration,
+        coordinator: std::sync::OnceLock<Arc<Coordinator>>,
+        saw_deadline: AtomicBool,
+    }
+
+    impl crate::traits::Orchestrator for SlowOrchestrator {
+        fn execute(
+            &self,
+            _prompt: String,
+            _context: Arc<dyn ContextManager>,
+            _providers: HashMap<String, Arc<dyn crate::traits::Provider>>,
+            _tools: HashMap<String, Arc<dyn crate::traits::Tool>>,
+            _hooks: Value,
+            coordinator: Value,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<String, AmplifierError>> + Send + '_>,
+        > {
+            let has_deadline = coordinator.get("turn_deadline_remaining_ms").is_some()
+                && self
+                    .coordinator
+                    .get()
+                    .is_some_and(|c| c.turn_deadline().is_some());
+            self.saw_deadline.store(has_deadline, Ordering::SeqCst);
+            Box::pin(async move {
+                tokio::time::sleep(self.delay).await;
+                Ok("done".into())
+            })
+        }
+    }
+
+    fn session_with_slow_orchestrator(delay_ms: u64) -> (Session, Arc<SlowOrchestrator>) {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        let orch = Arc::new(SlowOrchestrator {
+            delay: std::time::Duration::from_millis(delay_ms),
+            coordinator: std::sync::OnceLock::new(),
+            saw_deadline: AtomicBool::new(false),
+        });
+        session.coordinator_mut().set_orchestrator(orch.clone());
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        let _ = orch.coordinator.set(session.coordinator_shared());
+        session.set_initialized();
+        (session, orch)
+    }
+
+    #[tokio::test]
+    async fn execute_with_deadline_exposes_deadline_during_turn() {
+        let (session, orch) = session_with_slow_orchestrator(1);
+        let result = session
+            .execute_with_deadline("hi", std::time::Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(result, "done");
+        assert!(orch.saw_deadline.load(Ordering::SeqCst));
+        // Cleared once the turn ends.
+        assert!(session.coordinator().turn_deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn events_during_a_turn_share_its_turn_id() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        let handler = Arc::new(FakeHookHandler::new());
+        for event in [events::SESSION_START, events::PROMPT_SUBMIT] {
+            let _ = session.coordinator().hooks().register(
+                event,
+                handler.clone(),
+                0,
+                Some(format!("rec-{event}")),
+            );
+        }
+        session.set_initialized();
+
+        session.execute("one").await.unwrap();
+        session.execute("two").await.unwrap();
+
+        let turn_ids: Vec<String> = handler
+            .recorded_events()
+            .iter()
+            .map(|(_, data)| data["turn_id"].as_str().unwrap().to_string())
+            .collect();
+        // session:start + prompt:submit in turn one, prompt:submit in turn two.
+        assert_eq!(turn_ids.len(), 3);
+        assert_eq!(turn_ids[0], turn_ids[1]);
+        assert_ne!(turn_ids[1], turn_ids[2]);
+        assert!(session.coordinator().turn_id().is_none());
+        assert!(correlation::current().is_empty());
+    }
+
+    #[tokio::test]
+    async fn execute_with_deadline_fails_when_turn_overruns() {
+        let (session, _orch) = session_with_slow_orchestrator(5_000);
+        let err = session
+            .execute_with_deadline("hi", std::time::Duration::from_millis(20))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AmplifierError::Session(SessionError::DeadlineExceeded { timeout_ms: 20 })
+        ));
+        assert_eq!(session.status(), "failed");
+        assert!(session.coordinator().turn_deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn dropped_execute_with_deadline_clears_the_deadline() {
+        let (session, orch) = session_with_slow_orchestrator(5_000);
+        let dropped = tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            session.execute_with_deadline("hi", std::time::Duration::from_secs(60)),
+        )
+        .await;
+        assert!(dropped.is_err());
+        assert!(orch.saw_deadline.load(Ordering::SeqCst));
+        assert!(session.coordinator().turn_deadline().is_none());
+    }
+
     /// Verify session:resume is emitted on first execute() for resumed sessions —
     /// and only once even when execute() is called multiple times.
     #[tokio::test]
@@ -849,117 +2707,791 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn cleanup_emits_session_end_event() {
-        let config = SessionConfig::minimal("loop-basic", "context-simple");
+    async fn session_policy_config_installs_tool_pre_policy() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "policy": {"rules": [{"tool": "bash", "action": "deny", "reason": "no shell"}]},
+            }
+        }))
+        .unwrap();
         let session = Session::new(config, None, None);
 
-        let handler = Arc::new(FakeHookHandler::new());
-        let _ = session.coordinator().hooks().register(
-            events::SESSION_END,
-            handler.clone(),
-            0,
-            Some("test-handler".into()),
-        );
+        let result = session
+            .coordinator()
+            .hooks()
+            .emit(
+                events::TOOL_PRE,
+                serde_json::json!({"tool_name": "bash", "tool_input": {}}),
+            )
+            .await;
+        assert_eq!(result.action, crate::models::HookAction::Deny);
+        assert!(result.reason.unwrap().contains("no shell"));
+    }
 
-        session.cleanup().await;
+    #[tokio::test]
+    async fn session_workspace_confines_tool_paths() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "workspace": {"root": "/work", "read_only_paths": ["docs"]},
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        assert_eq!(
+            session.coordinator().workspace().unwrap().root,
+            std::path::Path::new("/work")
+        );
 
-        let events = handler.recorded_events();
-        assert!(
-            events.iter().any(|(name, _)| name == events::SESSION_END),
-            "Expected session:end event, got: {:?}",
-            events.iter().map(|(n, _)| n).collect::<Vec<_>>()
+        let decide = |tool: &'static str, path: &'static str| {
+            session.coordinator().hooks().emit(
+                events::TOOL_PRE,
+                serde_json::json!({"tool_name": tool, "tool_input": {"path": path}}),
+            )
+        };
+        use crate::models::HookAction;
+        assert_eq!(
+            decide("read_file", "src/a.rs").await.action,
+            HookAction::Continue
+        );
+        assert_eq!(
+            decide("read_file", "docs/a.md").await.action,
+            HookAction::Continue
+        );
+        assert_eq!(
+            decide("write_file", "docs/a.md").await.action,
+            HookAction::Deny
+        );
+        assert_eq!(
+            decide("read_file", "/etc/hosts").await.action,
+            HookAction::Deny
         );
     }
 
-    // ---------------------------------------------------------------
-    // Coordinator access
-    // ---------------------------------------------------------------
-
-    #[test]
-    fn coordinator_is_accessible() {
+    #[tokio::test]
+    async fn discovered_tools_are_mounted_for_one_turn() {
         let config = SessionConfig::minimal("loop-basic", "context-simple");
         let mut session = Session::new(config, None, None);
-
-        // Mount tool via coordinator
+        let orchestrator = Arc::new(CapturingOrchestrator::new("done"));
         session
             .coordinator_mut()
-            .mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+            .set_orchestrator(orchestrator.clone());
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        let proposal = Arc::new(FakeHookHandler::with_result(crate::models::HookResult {
+            data: Some(HashMap::from([(
+                "candidates".to_string(),
+                serde_json::json!([{"id": "search_issues", "score": 1.0}]),
+            )])),
+            ..Default::default()
+        }));
+        let _ = session.coordinator().hooks().register(
+            events::TOOLS_DISCOVER,
+            proposal.clone(),
+            0,
+            Some("mcp".into()),
+        );
+        session.set_initialized();
 
-        // Verify via immutable access
-        let tools = session.coordinator().tools();
-        assert_eq!(tools.len(), 1);
-        assert!(tools.contains_key("echo"));
-    }
+        session.execute("find the bug").await.unwrap();
 
-    #[tokio::test]
-    async fn cleanup_clears_initialized_flag() {
-        let config = SessionConfig::minimal("test-orch", "test-ctx");
-        let session = Session::new(config, None, None);
-        session.set_initialized();
-        assert!(session.is_initialized());
-        session.cleanup().await;
-        assert!(
-            !session.is_initialized(),
-            "cleanup should clear initialized flag"
+        assert_eq!(
+            orchestrator.last_coordinator_value()["tools"],
+            serde_json::json!(["search_issues"])
         );
+        assert_eq!(proposal.recorded_events()[0].1["prompt"], "find the bug");
+        assert!(session.coordinator().get_tool("search_issues").is_none());
     }
 
-    // ---------------------------------------------------------------
-    // Task 4: real hooks + coordinator passed to orchestrator
-    // ---------------------------------------------------------------
-
     #[tokio::test]
-    async fn execute_passes_hooks_and_coordinator_to_orchestrator() {
-        let config = SessionConfig::minimal("loop-basic", "context-simple");
+    async fn summarization_with_unmounted_provider_fails_execute() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "summarization": {"provider": "summarizer"},
+            }
+        }))
+        .unwrap();
         let mut session = Session::new(config, None, None);
-
-        let orch = Arc::new(CapturingOrchestrator::new("test response"));
-        session.coordinator_mut().set_orchestrator(orch.clone());
         session
             .coordinator_mut()
-            .set_context(Arc::new(FakeContextManager::new()));
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
         session
             .coordinator_mut()
-            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+            .set_context(Arc::new(FakeContextManager::new()));
         session
             .coordinator_mut()
-            .mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
         session.set_initialized();
 
-        let result = session.execute("hello").await;
-        assert!(result.is_ok());
-
-        // Verify the orchestrator received non-empty coordinator data
-        let captured_coord = orch.last_coordinator_value();
-        assert_ne!(
-            captured_coord,
-            serde_json::json!({}),
-            "coordinator value should not be empty"
-        );
-        // Verify the coordinator value contains tools
-        assert!(
-            captured_coord.get("tools").is_some(),
-            "coordinator value should contain 'tools' key"
-        );
+        let err = session.execute("hello").await.unwrap_err().to_string();
+        assert!(err.contains("'summarizer' is not mounted"), "{err}");
     }
 
-    // ---------------------------------------------------------------
-    // Task 5: SessionConfig::from_json()
-    // ---------------------------------------------------------------
-
     #[test]
-    fn session_config_from_json_string() {
-        let json = r#"{
+    fn session_attachments_config_installs_store() {
+        let config = SessionConfig::from_value(serde_json::json!({
             "session": {
                 "orchestrator": "loop-basic",
-                "context": "context-simple"
+                "context": "context-simple",
+                "attachments": {"threshold_bytes": 4096},
             }
-        }"#;
-        let config = SessionConfig::from_json(json).unwrap();
-        // Verify the parsed config contains the expected values
-        let session_block = config.config.get("session").unwrap();
-        assert_eq!(session_block["orchestrator"], "loop-basic");
-        assert_eq!(session_block["context"], "context-simple");
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        let store = session.coordinator().attachment_store().unwrap();
+        assert_eq!(store.threshold_bytes(), 4096);
+    }
+
+    #[tokio::test]
+    async fn hook_max_result_bytes_spills_large_handler_data() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "hooks": {"max_result_bytes": 256},
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks();
+        let handler = Arc::new(FakeHookHandler::with_result(crate::models::HookResult {
+            action: crate::models::HookAction::Modify,
+            data: Some(HashMap::from([(
+                "content".to_string(),
+                serde_json::json!("x".repeat(4096)),
+            )])),
+            ..Default::default()
+        }));
+        let _ = hooks.register(events::TOOL_POST, handler, 0, None);
+
+        let result = hooks.emit(events::TOOL_POST, serde_json::json!({})).await;
+        let content = &result.data.unwrap()["content"];
+        assert_eq!(content["type"], "attachment");
+        assert_eq!(content["size"], 4096);
+        let stats = hooks.data_limit().unwrap().stats();
+        assert_eq!((stats.spilled, stats.bytes_spilled), (1, 4096));
+
+        // The reference resolves through the session's attachment store.
+        let store = session.coordinator().attachment_store().unwrap();
+        assert_eq!(store.threshold_bytes(), usize::MAX);
+        let uri = content["uri"].as_str().unwrap();
+        let attachment = store.get(uri).await.unwrap().unwrap();
+        assert_eq!(attachment.data, "x".repeat(4096).into_bytes());
+    }
+
+    #[tokio::test]
+    async fn rewind_restores_messages_and_turn_number() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        let context = Arc::new(FakeContextManager::new());
+        session.coordinator_mut().set_context(context.clone());
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = session.coordinator().hooks().register(
+            events::SESSION_REWIND,
+            handler.clone(),
+            0,
+            None,
+        );
+
+        context
+            .add_message(serde_json::json!({"role": "user", "content": "a"}))
+            .await
+            .unwrap();
+        session.coordinator().reset_turn();
+        let checkpoint = session.checkpoint().await.unwrap();
+
+        context
+            .add_message(serde_json::json!({"role": "user", "content": "b"}))
+            .await
+            .unwrap();
+        session.coordinator().reset_turn();
+        session.coordinator().reset_turn();
+        let later = session.checkpoint().await.unwrap();
+
+        session.rewind(checkpoint).await.unwrap();
+        assert_eq!(context.get_messages().await.unwrap().len(), 1);
+        assert_eq!(session.coordinator().turn_number(), 1);
+        let seen = handler.recorded_events();
+        assert_eq!(seen[0].1["checkpoint"], 1);
+        assert_eq!(seen[0].1["message_count"], 1);
+        assert_eq!(session.timeline().of_kind("rewound").len(), 1);
+
+        // Later branches survive the rewind.
+        session.rewind(later).await.unwrap();
+        assert_eq!(context.get_messages().await.unwrap().len(), 2);
+        assert_eq!(session.coordinator().turn_number(), 3);
+
+        let err = session.rewind(CheckpointId(99)).await.unwrap_err();
+        assert_eq!(err.code(), "session.checkpoint_not_found");
+    }
+
+    #[tokio::test]
+    async fn session_pricing_tracks_costs_per_turn() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "pricing": {"models": {"m": {"input_per_mtok": 1.0, "output_per_mtok": 2.0}}}
+            }
+        }))
+        .unwrap();
+        let mut session = Session::new(config, None, None);
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+
+        session.execute("hello").await.unwrap();
+        session
+            .coordinator()
+            .hooks()
+            .emit(
+                events::PROVIDER_POST,
+                serde_json::json!({
+                    "provider": "test",
+                    "model": "m",
+                    "response": {"usage": {"input_tokens": 1000, "output_tokens": 500, "total_tokens": 1500}}
+                }),
+            )
+            .await;
+
+        let report = session.costs().unwrap().report();
+        assert_eq!(report.turns.len(), 1);
+        assert_eq!(report.turns[0].cost_usd.as_deref(), Some("0.002000"));
+        assert_eq!(report.by_provider["test"].calls, 1);
+    }
+
+    #[tokio::test]
+    async fn session_quota_fails_execute_once_breached() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "quota": {"max_tool_calls": 1},
+            }
+        }))
+        .unwrap();
+        let mut session = Session::new(config, None, None);
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+        assert_eq!(session.execute("hi").await.unwrap(), "ok");
+
+        let hooks = session.coordinator().hooks();
+        hooks.emit(events::TOOL_PRE, serde_json::json!({})).await;
+        let denied = hooks.emit(events::TOOL_PRE, serde_json::json!({})).await;
+        assert_eq!(denied.action, crate::models::HookAction::Deny);
+
+        let err = session.execute("again").await.unwrap_err();
+        assert_eq!(err.code(), "session.quota_exceeded");
+        assert_eq!(session.status(), "failed");
+        assert_eq!(session.quota().unwrap().usage().tool_calls, 2);
+    }
+
+    /// Orchestrator that repeats one tool call until a hook denies it.
+    struct LoopingOrchestrator {
+        hooks: Arc<crate::hooks::HookRegistry>,
+    }
+
+    impl crate::traits::Orchestrator for LoopingOrchestrator {
+        fn execute(
+            &self,
+            _prompt: String,
+            _context: Arc<dyn ContextManager>,
+            _providers: HashMap<String, Arc<dyn crate::traits::Provider>>,
+            _tools: HashMap<String, Arc<dyn crate::traits::Tool>>,
+            _hooks: Value,
+            _coordinator: Value,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<String, AmplifierError>> + Send + '_>,
+        > {
+            Box::pin(async move {
+                let call = serde_json::json!({"tool_name": "ls", "tool_input": {"path": "."}});
+                while self.hooks.emit(events::TOOL_PRE, call.clone()).await.action
+                    != crate::models::HookAction::Deny
+                {}
+                Ok("gave up".into())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn session_safety_fails_looping_turn() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "safety": {"max_identical_tool_calls": 3},
+            }
+        }))
+        .unwrap();
+        let mut session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks_shared();
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(LoopingOrchestrator { hooks }));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+
+        let err = session.execute("list files").await.unwrap_err();
+        assert_eq!(err.code(), "session.safety_limit_exceeded");
+        assert_eq!(session.status(), "failed");
+        let breach = session.safety().unwrap().breach().unwrap();
+        assert_eq!(breach.observed, 4);
+        assert_eq!(breach.tool_name.as_deref(), Some("ls"));
+    }
+
+    #[tokio::test]
+    async fn clone_for_eval_copies_state_into_an_independent_session() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let session = Session::new(config, None, None);
+        let context = Arc::new(FakeContextManager::new());
+        context
+            .add_message(serde_json::json!({"role": "user", "content": "hi"}))
+            .await
+            .unwrap();
+        session.coordinator().set_context(context.clone());
+        session
+            .coordinator()
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
+        session
+            .coordinator()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.coordinator().reset_turn();
+        session.set_initialized();
+
+        let clone_context = Arc::new(FakeContextManager::new());
+        let clone = session.clone_for_eval(clone_context.clone()).await.unwrap();
+        assert_ne!(clone.session_id(), session.session_id());
+        assert_eq!(clone.eval_source(), Some(session.session_id()));
+        assert_eq!(clone.coordinator().turn_number(), 1);
+        assert_eq!(clone_context.get_messages().await.unwrap().len(), 1);
+
+        // Independent: new messages and cancellation stay on one side.
+        clone_context
+            .add_message(serde_json::json!({"role": "user", "content": "only clone"}))
+            .await
+            .unwrap();
+        assert_eq!(context.get_messages().await.unwrap().len(), 1);
+        session.coordinator().cancellation().request_graceful();
+        assert!(!clone.coordinator().cancellation().is_cancelled());
+
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ =
+            clone
+                .coordinator()
+                .hooks()
+                .register(events::SESSION_START, recorder.clone(), 0, None);
+        assert_eq!(clone.execute("go").await.unwrap(), "ok");
+        let events = recorder.recorded_events();
+        assert_eq!(events[0].1["eval_source"], session.session_id());
+        assert_eq!(events[0].1["session_id"], clone.session_id());
+    }
+
+    #[tokio::test]
+    async fn eval_clones_share_the_audit_log() {
+        use std::io::BufReader;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "audit": {"path": path},
+            }
+        }))
+        .unwrap();
+        let mut session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks_shared();
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(InvokingOrchestrator { hooks }));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+        session.execute("one").await.unwrap();
+
+        let clone = session
+            .clone_for_eval(Arc::new(FakeContextManager::new()))
+            .await
+            .unwrap();
+        let hooks = clone.coordinator().hooks_shared();
+        clone
+            .coordinator()
+            .set_orchestrator(Arc::new(InvokingOrchestrator { hooks }));
+        clone.execute("two").await.unwrap();
+        session.execute("three").await.unwrap();
+
+        let audit = session.audit_log().unwrap();
+        assert!(Arc::ptr_eq(&audit, &clone.audit_log().unwrap()));
+        let file = std::fs::File::open(&path).unwrap();
+        let (count, head) = crate::audit::verify_jsonl(BufReader::new(file)).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(head, audit.head());
+        let sessions: Vec<_> = audit
+            .records()
+            .into_iter()
+            .map(|record| record.session_id.unwrap())
+            .collect();
+        assert_eq!(
+            sessions,
+            [
+                session.session_id(),
+                clone.session_id(),
+                session.session_id()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn session_hook_filter_config_installs_event_filter() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "hooks": {"filter": {"disabled": [events::THINKING_DELTA]}},
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks();
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::THINKING_DELTA, handler.clone(), 0, None);
+
+        hooks
+            .emit(events::THINKING_DELTA, serde_json::json!({}))
+            .await;
+        assert!(handler.recorded_events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn session_hook_replay_config_enables_replay() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "hooks": {"replay": 16},
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks();
+        hooks
+            .emit(events::SESSION_START, serde_json::json!({}))
+            .await;
+
+        let late = Arc::new(FakeHookHandler::new());
+        let _ = hooks
+            .register_with_replay(events::SESSION_START, late.clone(), 0, None, 1)
+            .await;
+        let seen = late.recorded_events();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].1["session_id"], session.session_id());
+    }
+
+    #[tokio::test]
+    async fn initialize_registers_declared_hook_subscriptions() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "hooks": {"subscriptions": [
+                    {"event": events::TOOL_PRE, "handler": "audit", "priority": 5},
+                    {"event": events::TOOL_PRE, "handler": "audit", "name": "early", "priority": -5},
+                    {"event": events::TOOL_POST, "handler": "missing", "enabled": false}
+                ]},
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        let audit = Arc::new(FakeHookHandler::new());
+        session.provide_hook_handler("audit", audit.clone());
+
+        session.initialize().unwrap();
+        session.initialize().unwrap();
+        assert!(session.is_initialized());
+        let hooks = session.coordinator().hooks();
+        assert_eq!(
+            hooks.list_handlers(Some(events::TOOL_PRE))[events::TOOL_PRE],
+            vec!["early", "audit"]
+        );
+        hooks.emit(events::TOOL_PRE, serde_json::json!({})).await;
+        assert_eq!(audit.recorded_events().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn builtin_hook_handlers_mount_by_name() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "hooks": {
+                    "builtin": {"content_filter": {"terms": ["hunter2"]}},
+                    "subscriptions": [
+                        {"event": events::PROMPT_SUBMIT, "handler": builtin::CONTENT_FILTER},
+                        {"event": events::PROMPT_SUBMIT, "handler": builtin::LOGGING,
+                         "phase": "observation"}
+                    ]
+                },
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        session.initialize().unwrap();
+
+        let result = session
+            .coordinator()
+            .hooks()
+            .emit(
+                events::PROMPT_SUBMIT,
+                serde_json::json!({"prompt": "my password is hunter2"}),
+            )
+            .await;
+        assert_eq!(result.data.unwrap()["prompt"], "my password is [redacted]");
+    }
+
+    #[test]
+    fn initialize_fails_for_unprovided_hook_handler() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "hooks": {"subscriptions": [{"event": events::TOOL_PRE, "handler": "audit"}]},
+            }
+        }))
+        .unwrap();
+        let session = Session::new(config, None, None);
+        let err = session.initialize().unwrap_err();
+        assert!(matches!(
+            err,
+            AmplifierError::Session(SessionError::HookHandlerNotFound { .. })
+        ));
+        assert!(!session.is_initialized());
+    }
+
+    #[tokio::test]
+    async fn cleanup_emits_session_end_event() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let session = Session::new(config, None, None);
+
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = session.coordinator().hooks().register(
+            events::SESSION_END,
+            handler.clone(),
+            0,
+            Some("test-handler".into()),
+        );
+
+        session.cleanup().await;
+
+        let events = handler.recorded_events();
+        assert!(
+            events.iter().any(|(name, _)| name == events::SESSION_END),
+            "Expected session:end event, got: {:?}",
+            events.iter().map(|(n, _)| n).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn timeline_records_lifecycle_milestones() {
+        use crate::clock::Clock;
+
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        let clock = crate::testing::ManualClock::default();
+        session.coordinator().set_clock(Arc::new(clock.clone()));
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(FakeOrchestrator::new("ok")));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+        session.set_initialized();
+
+        session.execute("one").await.unwrap();
+        clock.advance(Duration::from_secs(2));
+        session.coordinator().cancellation().request_graceful();
+        session.execute("two").await.unwrap();
+        session.cleanup().await;
+
+        let timeline = session.timeline();
+        let kinds: Vec<_> = timeline
+            .entries()
+            .iter()
+            .map(|e| e.milestone.kind())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "created",
+                "initialized",
+                "turn_started",
+                "turn_ended",
+                "cancellation_requested",
+                "turn_started",
+                "turn_ended",
+                "cleanup_started",
+                "cleanup_finished",
+            ]
+        );
+        assert_eq!(timeline.turn_count(), 2);
+        let last_turn = timeline.last("turn_ended").unwrap();
+        assert_eq!(
+            last_turn.milestone,
+            Milestone::TurnEnded {
+                turn: 2,
+                state: SessionState::Cancelled
+            }
+        );
+        assert_eq!(last_turn.duration(), Some(Duration::ZERO));
+        assert_eq!(
+            last_turn.timestamp,
+            clock.now_utc(),
+            "timestamps come from the coordinator clock"
+        );
+    }
+
+    #[tokio::test]
+    async fn timeline_closes_turn_abandoned_at_deadline() {
+        let (session, _) = session_with_slow_orchestrator(5_000);
+        let _ = session
+            .execute_with_deadline("slow", Duration::from_millis(20))
+            .await;
+        let ended = session.timeline().last("turn_ended").unwrap();
+        assert!(matches!(
+            ended.milestone,
+            Milestone::TurnEnded {
+                state: SessionState::Failed,
+                ..
+            }
+        ));
+    }
+
+    // ---------------------------------------------------------------
+    // Coordinator access
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn coordinator_is_accessible() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+
+        // Mount tool via coordinator
+        session
+            .coordinator_mut()
+            .mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+
+        // Verify via immutable access
+        let tools = session.coordinator().tools();
+        assert_eq!(tools.len(), 1);
+        assert!(tools.contains_key("echo"));
+    }
+
+    #[tokio::test]
+    async fn cleanup_clears_initialized_flag() {
+        let config = SessionConfig::minimal("test-orch", "test-ctx");
+        let session = Session::new(config, None, None);
+        session.set_initialized();
+        assert!(session.is_initialized());
+        session.cleanup().await;
+        assert!(
+            !session.is_initialized(),
+            "cleanup should clear initialized flag"
+        );
+    }
+
+    // ---------------------------------------------------------------
+    // Task 4: real hooks + coordinator passed to orchestrator
+    // ---------------------------------------------------------------
+
+    #[tokio::test]
+    async fn execute_passes_hooks_and_coordinator_to_orchestrator() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+
+        let orch = Arc::new(CapturingOrchestrator::new("test response"));
+        session.coordinator_mut().set_orchestrator(orch.clone());
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session
+            .coordinator_mut()
+            .mount_tool("echo", Arc::new(FakeTool::new("echo", "echoes")));
+        session.set_initialized();
+
+        let result = session.execute("hello").await;
+        assert!(result.is_ok());
+
+        // Verify the orchestrator received non-empty coordinator data
+        let captured_coord = orch.last_coordinator_value();
+        assert_ne!(
+            captured_coord,
+            serde_json::json!({}),
+            "coordinator value should not be empty"
+        );
+        // Verify the coordinator value contains tools
+        assert!(
+            captured_coord.get("tools").is_some(),
+            "coordinator value should contain 'tools' key"
+        );
+    }
+
+    // ---------------------------------------------------------------
+    // Task 5: SessionConfig::from_json()
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn session_config_from_json_string() {
+        let json = r#"{
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple"
+            }
+        }"#;
+        let config = SessionConfig::from_json(json).unwrap();
+        // Verify the parsed config contains the expected values
+        let session_block = config.config.get("session").unwrap();
+        assert_eq!(session_block["orchestrator"], "loop-basic");
+        assert_eq!(session_block["context"], "context-simple");
     }
 
     #[test]
@@ -1021,4 +3553,465 @@ mod tests {
         assert_eq!(tools.len(), 1);
         assert!(tools.contains_key("search"));
     }
+
+    // ---------------------------------------------------------------
+    // Interior mutability — concurrent access during execute()
+    // ---------------------------------------------------------------
+
+    /// Orchestrator that blocks until released, so tests can observe the
+    /// session while `execute()` is in flight.
+    struct GatedOrchestrator {
+        started: Arc<tokio::sync::Notify>,
+        release: Arc<tokio::sync::Notify>,
+    }
+
+    impl crate::traits::Orchestrator for GatedOrchestrator {
+        fn execute(
+            &self,
+            _prompt: String,
+            _context: Arc<dyn crate::traits::ContextManager>,
+            _providers: HashMap<String, Arc<dyn crate::traits::Provider>>,
+            _tools: HashMap<String, Arc<dyn crate::traits::Tool>>,
+            _hooks: Value,
+            _coordinator: Value,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<String, AmplifierError>> + Send + '_>,
+        > {
+            Box::pin(async move {
+                self.started.notify_one();
+                self.release.notified().await;
+                Ok("released".into())
+            })
+        }
+    }
+
+    fn gated_session() -> (
+        Arc<Session>,
+        Arc<tokio::sync::Notify>,
+        Arc<tokio::sync::Notify>,
+    ) {
+        gated_session_with(SessionConfig::minimal("loop-basic", "context-simple"))
+    }
+
+    fn gated_session_with(
+        config: SessionConfig,
+    ) -> (
+        Arc<Session>,
+        Arc<tokio::sync::Notify>,
+        Arc<tokio::sync::Notify>,
+    ) {
+        let started = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::new(tokio::sync::Notify::new());
+        let mut session = Session::new(config, None, None);
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(GatedOrchestrator {
+                started: started.clone(),
+                release: release.clone(),
+            }));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi")));
+        session.set_initialized();
+        (Arc::new(session), started, release)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn status_is_queryable_while_execute_is_in_flight() {
+        let (session, started, release) = gated_session();
+        let runner = Arc::clone(&session);
+        let handle = tokio::spawn(async move { runner.execute("hello").await });
+
+        started.notified().await;
+        // Shared access while execute() holds no exclusive borrow
+        assert_eq!(session.status(), "running");
+        assert!(session.is_initialized());
+        session.coordinator().cancellation().request_graceful();
+
+        release.notify_one();
+        let result = handle.await.unwrap();
+        assert_eq!(result.unwrap(), "released");
+        assert_eq!(session.state(), SessionState::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn heartbeats_report_progress_while_executing() {
+        let mut config = SessionConfig::minimal("loop-basic", "context-simple");
+        config.config.get_mut("session").unwrap()["heartbeat"] =
+            serde_json::json!({"interval_secs": 10});
+        let (session, started, release) = gated_session_with(config);
+        let clock = crate::testing::ManualClock::default();
+        session.coordinator().set_clock(Arc::new(clock.clone()));
+        let observer = Arc::new(FakeHookHandler::new());
+        let hooks = session.coordinator().hooks();
+        let _ = hooks.register(events::SESSION_HEARTBEAT, observer.clone(), 0, None);
+        let runner = Arc::clone(&session);
+        let handle = tokio::spawn(async move { runner.execute("hello").await });
+
+        started.notified().await;
+        hooks
+            .emit(events::TOOL_PRE, serde_json::json!({"tool_name": "bash"}))
+            .await;
+        clock.advance(Duration::from_secs(10));
+        while observer.recorded_events().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        release.notify_one();
+        handle.await.unwrap().unwrap();
+
+        let (_, beat) = &observer.recorded_events()[0];
+        assert_eq!(beat["session_id"], session.session_id());
+        assert_eq!(beat["status"], "running");
+        assert_eq!(beat["elapsed_ms"], 10_000);
+        assert_eq!(beat["usage"], Value::Null);
+        assert_eq!(beat["in_flight_tools"], serde_json::json!(["bash"]));
+    }
+
+    #[tokio::test]
+    async fn aborted_turns_unregister_their_recorder() {
+        let (session, started, _release) = gated_session();
+        let recorders = || {
+            let name = format!("turn-recorder:{}", session.session_id());
+            session
+                .coordinator()
+                .hooks()
+                .list_handlers(None)
+                .into_values()
+                .flatten()
+                .filter(|handler| *handler == name)
+                .count()
+        };
+        let runner = Arc::clone(&session);
+        let handle = tokio::spawn(async move { runner.run_turn("hello").await });
+        started.notified().await;
+        assert_eq!(recorders(), turn::RECORDED_EVENTS.len());
+        assert!(session.coordinator().turn_id().is_some());
+
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert_eq!(recorders(), 0);
+        assert_eq!(session.coordinator().turn_id(), None);
+    }
+
+    #[tokio::test]
+    async fn dropped_executions_unregister_their_heartbeat_handlers() {
+        let mut config = SessionConfig::minimal("loop-basic", "context-simple");
+        config.config.get_mut("session").unwrap()["heartbeat"] =
+            serde_json::json!({"interval_secs": 10});
+        let (session, started, _release) = gated_session_with(config);
+        let heartbeat_handlers = || {
+            let name = format!("heartbeat:{}", session.session_id());
+            session
+                .coordinator()
+                .hooks()
+                .list_handlers(None)
+                .into_values()
+                .flatten()
+                .filter(|handler| *handler == name)
+                .count()
+        };
+        let runner = Arc::clone(&session);
+        let handle = tokio::spawn(async move { runner.execute("hello").await });
+        started.notified().await;
+        assert_eq!(heartbeat_handlers(), heartbeat::TRACKED_EVENTS.len());
+
+        handle.abort();
+        assert!(handle.await.unwrap_err().is_cancelled());
+        assert_eq!(heartbeat_handlers(), 0);
+    }
+
+    #[tokio::test]
+    async fn concurrent_execute_is_rejected_as_busy_by_default() {
+        let (session, started, release) = gated_session();
+        assert_eq!(session.current_execution(), None);
+        let runner = Arc::clone(&session);
+        let first = tokio::spawn(async move { runner.execute("first").await });
+        started.notified().await;
+
+        let current = session.current_execution().unwrap();
+        assert_eq!(current.prompt, "first");
+        assert_eq!(current.queued, 0);
+        let err = session.execute("second").await.unwrap_err();
+        assert!(matches!(err, AmplifierError::Session(SessionError::Busy)));
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap(), "released");
+        assert_eq!(session.current_execution(), None);
+    }
+
+    #[tokio::test]
+    async fn queued_executions_run_in_order() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "reentrancy": "queue",
+            }
+        }))
+        .unwrap();
+        let (session, started, release) = gated_session_with(config);
+        let runner = Arc::clone(&session);
+        let first = tokio::spawn(async move { runner.execute("first").await });
+        started.notified().await;
+        let runner = Arc::clone(&session);
+        let second = tokio::spawn(async move { runner.execute("second").await });
+        while session.current_execution().unwrap().queued == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap(), "released");
+        started.notified().await;
+        assert_eq!(session.current_execution().unwrap().prompt, "second");
+        release.notify_one();
+        assert_eq!(second.await.unwrap().unwrap(), "released");
+        assert_eq!(session.current_execution(), None);
+    }
+
+    #[tokio::test]
+    async fn rejected_execute_keeps_the_running_turns_deadline() {
+        let (session, started, release) = gated_session();
+        let remaining = || session.coordinator().turn_deadline().unwrap().remaining();
+        let runner = Arc::clone(&session);
+        let first = tokio::spawn(async move {
+            runner
+                .execute_with_deadline("first", Duration::from_secs(60))
+                .await
+        });
+        started.notified().await;
+        assert!(remaining() > Duration::from_secs(30));
+
+        let err = session
+            .execute_with_deadline("second", Duration::from_millis(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AmplifierError::Session(SessionError::Busy)));
+        assert!(remaining() > Duration::from_secs(30));
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap(), "released");
+        assert!(session.coordinator().turn_deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn queued_execute_sets_its_deadline_when_it_starts() {
+        let config = SessionConfig::from_value(serde_json::json!({
+            "session": {
+                "orchestrator": "loop-basic",
+                "context": "context-simple",
+                "reentrancy": "queue",
+            }
+        }))
+        .unwrap();
+        let (session, started, release) = gated_session_with(config);
+        let remaining = || session.coordinator().turn_deadline().unwrap().remaining();
+        let runner = Arc::clone(&session);
+        let first = tokio::spawn(async move {
+            runner
+                .execute_with_deadline("first", Duration::from_secs(60))
+                .await
+        });
+        started.notified().await;
+        let runner = Arc::clone(&session);
+        let second = tokio::spawn(async move {
+            runner
+                .execute_with_deadline("second", Duration::from_secs(20))
+                .await
+        });
+        while session.current_execution().unwrap().queued == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(remaining() > Duration::from_secs(30));
+
+        release.notify_one();
+        assert_eq!(first.await.unwrap().unwrap(), "released");
+        started.notified().await;
+        assert!(remaining() <= Duration::from_secs(20));
+        release.notify_one();
+        assert_eq!(second.await.unwrap().unwrap(), "released");
+        assert!(session.coordinator().turn_deadline().is_none());
+    }
+
+    #[tokio::test]
+    async fn execution_handle_joins_completed_execution() {
+        let (session, started, release) = gated_session();
+        let handle = session.execute_handle("hello");
+        started.notified().await;
+        assert_eq!(handle.status(), ExecutionStatus::Running);
+
+        release.notify_one();
+        // Yield until the task records its outcome.
+        while !handle.status().is_finished() {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(handle.status(), ExecutionStatus::Completed);
+        assert_eq!(handle.join().await.unwrap(), "released");
+    }
+
+    #[tokio::test]
+    async fn execution_handle_abort_escalates_from_graceful_to_immediate() {
+        let (session, started, _release) = gated_session();
+        let handle = session.execute_handle("hello");
+        started.notified().await;
+
+        handle.abort();
+        assert!(session.coordinator().cancellation().is_graceful());
+        assert_eq!(handle.status(), ExecutionStatus::Cancelling);
+
+        handle.abort();
+        assert!(session.coordinator().cancellation().is_immediate());
+        assert_eq!(handle.status(), ExecutionStatus::Cancelled);
+        assert_eq!(session.state(), SessionState::Cancelled);
+
+        let err = handle.join().await.unwrap_err();
+        assert!(matches!(
+            err,
+            AmplifierError::Session(SessionError::Aborted)
+        ));
+    }
+
+    #[tokio::test]
+    async fn dropping_execution_handle_aborts_execution() {
+        let (session, started, _release) = gated_session();
+        let handle = session.execute_handle("hello");
+        started.notified().await;
+        drop(handle);
+        assert!(session.coordinator().cancellation().is_immediate());
+        assert_eq!(session.state(), SessionState::Cancelled);
+    }
+
+    // ---------------------------------------------------------------
+    // run_turn — structured turn results
+    // ---------------------------------------------------------------
+
+    /// Orchestrator that calls its provider through the kernel invoker and
+    /// reports one tool call, like a minimal agent loop.
+    struct InvokingOrchestrator {
+        hooks: Arc<crate::hooks::HookRegistry>,
+    }
+
+    impl crate::traits::Orchestrator for InvokingOrchestrator {
+        fn execute(
+            &self,
+            prompt: String,
+            _context: Arc<dyn ContextManager>,
+            providers: HashMap<String, Arc<dyn crate::traits::Provider>>,
+            _tools: HashMap<String, Arc<dyn crate::traits::Tool>>,
+            _hooks: Value,
+            _coordinator: Value,
+        ) -> std::pin::Pin<
+            Box<dyn std::future::Future<Output = Result<String, AmplifierError>> + Send + '_>,
+        > {
+            Box::pin(async move {
+                self.hooks
+                    .emit(
+                        events::TOOL_POST,
+                        serde_json::json!({
+                            "tool_name": "echo",
+                            "tool_input": {"text": prompt},
+                            "tool_result": {"success": true, "output": prompt},
+                        }),
+                    )
+                    .await;
+                let request: crate::messages::ChatRequest = serde_json::from_value(
+                    serde_json::json!({"messages": [{"role": "user", "content": prompt}]}),
+                )
+                .unwrap();
+                let invoker = crate::provider_invoker::ProviderInvoker::new(self.hooks.clone());
+                let response = invoker
+                    .complete(providers["test"].as_ref(), request)
+                    .await?;
+                match response.content.first() {
+                    Some(crate::messages::ContentBlock::Text { text, .. }) => Ok(text.clone()),
+                    _ => Ok(String::new()),
+                }
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn run_turn_returns_structured_result() {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let mut session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks_shared();
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(InvokingOrchestrator { hooks }));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", Arc::new(FakeProvider::new("test", "hi there")));
+        session.set_initialized();
+
+        let turn = session.run_turn("hello").await.unwrap();
+        assert_eq!(turn.output, "hi there");
+        assert_eq!(turn.provider_calls, 1);
+        assert!(matches!(
+            &turn.content[..],
+            [crate::messages::ContentBlock::Text { text, .. }] if text == "hi there"
+        ));
+        assert_eq!(turn.tool_calls.len(), 1);
+        assert_eq!(turn.tool_calls[0].tool_name, "echo");
+        assert!(turn.tool_calls[0].success);
+
+        // The recorder is removed once the turn ends.
+        assert!(session
+            .coordinator()
+            .hooks()
+            .list_handlers(Some(events::TOOL_POST))
+            .values()
+            .all(|names| names.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn duplicate_prompt_replays_the_earlier_turn() {
+        let mut config = SessionConfig::minimal("loop-basic", "context-simple");
+        config.config.get_mut("session").unwrap()["prompt_history"] =
+            serde_json::json!({"replay_duplicates": true});
+        let mut session = Session::new(config, None, None);
+        let hooks = session.coordinator().hooks_shared();
+        let provider = Arc::new(FakeProvider::new("test", "hi there"));
+        session
+            .coordinator_mut()
+            .set_orchestrator(Arc::new(InvokingOrchestrator { hooks }));
+        session
+            .coordinator_mut()
+            .set_context(Arc::new(FakeContextManager::new()));
+        session
+            .coordinator_mut()
+            .mount_provider("test", provider.clone());
+        session.set_initialized();
+        let duplicates = Arc::new(FakeHookHandler::new());
+        let _ = session.coordinator().hooks().register(
+            events::PROMPT_DUPLICATE,
+            duplicates.clone(),
+            0,
+            None,
+        );
+
+        let first = session.run_turn("hello").await.unwrap();
+        let second = session.run_turn("hello").await.unwrap();
+        assert_eq!(second, first);
+        assert_eq!(session.execute("hello").await.unwrap(), "hi there");
+        assert_eq!(provider.recorded_calls().len(), 1);
+
+        let events = duplicates.recorded_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].1["replayed"], true);
+        assert_eq!(
+            events[0].1["prompt_hash"],
+            crate::prompt_history::prompt_hash("hello")
+        );
+        let history = session.prompt_history().entries();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].turn.as_ref(), Some(&first));
+
+        session.execute("goodbye").await.unwrap();
+        assert_eq!(provider.recorded_calls().len(), 2);
+    }
 }
@@ -0,0 +1,239 @@
+//! SessionManager — a host's table of live sessions, with idle reaping.
+//!
+//! Server hosts create a session per conversation and keep it between
+//! requests. Clients that disappear leave those sessions behind. A
+//! [`SessionManager`] holds the live sessions by ID, and
+//! [`reap_idle()`](SessionManager::reap_idle) removes every session whose
+//! [`idle_time()`](crate::session::Session::idle_time) exceeds a limit:
+//!
+//! 1. graceful cancellation is requested, so an execution still in flight
+//!    stops after its running tools;
+//! 2. [`SESSION_REAPED`](crate::events::SESSION_REAPED) is emitted with
+//!    `{session_id, idle_ms, max_idle_ms}`;
+//! 3. [`Session::cleanup`](crate::session::Session::cleanup) runs (emitting
+//!    `session:end`).
+//!
+//! [`spawn_reaper()`](SessionManager::spawn_reaper) runs `reap_idle` on an
+//! interval for hosts that do not schedule it themselves.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::events;
+use crate::session::Session;
+
+/// Live sessions by ID.
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: RwLock<HashMap<String, Arc<Session>>>,
+}
+
+impl SessionManager {
+    /// An empty manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `session` under its ID, returning the session it replaced.
+    pub fn insert(&self, session: Arc<Session>) -> Option<Arc<Session>> {
+        self.sessions
+            .write()
+            .unwrap()
+            .insert(session.session_id().to_string(), session)
+    }
+
+    /// The session with `session_id`, if managed.
+    pub fn get(&self, session_id: &str) -> Option<Arc<Session>> {
+        self.sessions.read().unwrap().get(session_id).cloned()
+    }
+
+    /// Stop managing `session_id`. The session is not cleaned up.
+    pub fn remove(&self, session_id: &str) -> Option<Arc<Session>> {
+        self.sessions.write().unwrap().remove(session_id)
+    }
+
+    /// IDs of the managed sessions, sorted.
+    pub fn session_ids(&self) -> Vec<String> {
+        let mut ids: Vec<_> = self.sessions.read().unwrap().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Number of managed sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.read().unwrap().len()
+    }
+
+    /// Whether no sessions are managed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cancel, clean up and remove every session idle for longer than
+    /// `max_idle`. Returns the reaped session IDs, sorted.
+    pub async fn reap_idle(&self, max_idle: Duration) -> Vec<String> {
+        let mut idle: Vec<(Arc<Session>, Duration)> = Vec::new();
+        self.sessions.write().unwrap().retain(|_, session| {
+            let idle_time = session.idle_time();
+            if idle_time <= max_idle {
+                return true;
+            }
+            idle.push((Arc::clone(session), idle_time));
+            false
+        });
+
+        let mut reaped = Vec::with_capacity(idle.len());
+        for (session, idle_time) in idle {
+            session.coordinator().cancellation().request_graceful();
+            session
+                .coordinator()
+                .hooks()
+                .emit(
+                    events::SESSION_REAPED,
+                    serde_json::json!({
+                        "session_id": session.session_id(),
+                        "idle_ms": idle_time.as_millis() as u64,
+                        "max_idle_ms": max_idle.as_millis() as u64,
+                    }),
+                )
+                .await;
+            session.cleanup().await;
+            log::info!(
+                "Reaped session '{}' after {}s idle",
+                session.session_id(),
+                idle_time.as_secs()
+            );
+            reaped.push(session.session_id().to_string());
+        }
+        reaped.sort();
+        reaped
+    }
+
+    /// Run [`reap_idle(max_idle)`](Self::reap_idle) every `interval` until
+    /// the manager is dropped or the returned task is aborted.
+    pub fn spawn_reaper(
+        self: &Arc<Self>,
+        interval: Duration,
+        max_idle: Duration,
+    ) -> JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    return;
+                };
+                manager.reap_idle(max_idle).await;
+            }
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionConfig;
+    use crate::testing::{FakeHookHandler, ManualClock};
+
+    fn session(id: &str, clock: &ManualClock) -> Arc<Session> {
+        let config = SessionConfig::minimal("loop-basic", "context-simple");
+        let session = Session::new(config, Some(id.into()), None);
+        session
+            .coordinator()
+            .hooks()
+            .set_clock(Arc::new(clock.clone()));
+        session.touch();
+        Arc::new(session)
+    }
+
+    #[tokio::test]
+    async fn reap_idle_cancels_cleans_up_and_removes_idle_sessions() {
+        let clock = ManualClock::default();
+        let manager = SessionManager::new();
+        let stale = session("stale", &clock);
+        let recorder = Arc::new(FakeHookHandler::new());
+        for event in [events::SESSION_REAPED, events::SESSION_END] {
+            let _ = stale
+                .coordinator()
+                .hooks()
+                .register(event, recorder.clone(), 0, None);
+        }
+        manager.insert(stale.clone());
+
+        clock.advance(Duration::from_secs(600));
+        let fresh = session("fresh", &clock);
+        manager.insert(fresh.clone());
+        clock.advance(Duration::from_secs(30));
+
+        let reaped = manager.reap_idle(Duration::from_secs(300)).await;
+
+        assert_eq!(reaped, vec!["stale".to_string()]);
+        assert_eq!(manager.session_ids(), vec!["fresh".to_string()]);
+        assert!(stale.coordinator().cancellation().is_cancelled());
+        assert!(!fresh.coordinator().cancellation().is_cancelled());
+        let events = recorder.recorded_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, events::SESSION_REAPED);
+        assert_eq!(events[0].1["session_id"], "stale");
+        assert_eq!(events[0].1["idle_ms"], 630_000);
+        assert_eq!(events[0].1["max_idle_ms"], 300_000);
+        assert_eq!(events[1].0, events::SESSION_END);
+    }
+
+    #[tokio::test]
+    async fn tool_and_provider_events_count_as_activity() {
+        let clock = ManualClock::default();
+        let session = session("busy", &clock);
+        let started = session.last_activity();
+
+        clock.advance(Duration::from_secs(120));
+        assert_eq!(session.idle_time(), Duration::from_secs(120));
+        session
+            .coordinator()
+            .hooks()
+            .emit(events::TOOL_POST, serde_json::json!({"tool_name": "grep"}))
+            .await;
+
+        assert_eq!(session.idle_time(), Duration::ZERO);
+        assert_eq!(
+            session.last_activity() - started,
+            chrono::Duration::seconds(120)
+        );
+        let manager = SessionManager::new();
+        manager.insert(session);
+        assert!(manager.reap_idle(Duration::from_secs(60)).await.is_empty());
+        assert_eq!(manager.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn provider_calls_through_the_invoker_keep_a_session_alive() {
+        let clock = ManualClock::default();
+        let session = session("streaming", &clock);
+        let manager = SessionManager::new();
+        manager.insert(session.clone());
+
+        // The provider invoker emits only provider:pre and provider:post.
+        let hooks = session.coordinator().hooks();
+        clock.advance(Duration::from_secs(90));
+        hooks
+            .emit(events::PROVIDER_PRE, serde_json::json!({"provider": "p"}))
+            .await;
+        assert!(manager.reap_idle(Duration::from_secs(60)).await.is_empty());
+        clock.advance(Duration::from_secs(90));
+        hooks
+            .emit(events::PROVIDER_POST, serde_json::json!({"provider": "p"}))
+            .await;
+        assert!(manager.reap_idle(Duration::from_secs(60)).await.is_empty());
+        assert_eq!(manager.len(), 1);
+    }
+}
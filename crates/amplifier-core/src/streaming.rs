@@ -0,0 +1,786 @@
+//! Reassembly of streamed provider output into a [`ChatResponse`].
+//!
+//! A streaming provider reports its output as a sequence of [`StreamChunk`]s.
+//! Content is addressed by block `index`, in the style of Anthropic's
+//! `content_block_*` events; OpenAI-style streams map each choice's text and
+//! each `tool_calls[i]` to a block index of their own.
+//!
+//! [`ResponseAccumulator`] ingests the chunks and produces the same
+//! [`ChatResponse`] a non-streaming call would have returned:
+//!
+//! - Blocks appear in index order, whatever order they were started in.
+//! - Text and thinking deltas are concatenated; empty text blocks are
+//!   dropped.
+//! - Tool call input arrives as fragments of a JSON document
+//!   (`partial_json`) and is parsed once the stream is finished. A call
+//!   with no fragments keeps the input its start chunk carried.
+//! - `tool_calls` mirrors the tool call blocks.
+//! - Usage chunks are running totals: each field keeps the largest value
+//!   reported, so a final usage report and incremental ones agree.
+//!
+//! ```json
+//! {"type": "block_start", "index": 0, "block": {"type": "text", "text": ""}}
+//! {"type": "text_delta", "index": 0, "text": "Hel"}
+//! {"type": "text_delta", "index": 0, "text": "lo"}
+//! {"type": "block_start", "index": 1, "block": {"type": "tool_call", "id": "c1", "name": "calc", "input": {}}}
+//! {"type": "tool_input_delta", "index": 1, "partial_json": "{\"expr\": "}
+//! {"type": "tool_input_delta", "index": 1, "partial_json": "\"2+2\"}"}
+//! {"type": "usage", "usage": {"input_tokens": 10, "output_tokens": 7, "total_tokens": 17}}
+//! {"type": "finish", "finish_reason": "tool_calls"}
+//! ```
+//!
+//! # Display events
+//!
+//! An accumulator built with [`ResponseAccumulator::with_events`] (or
+//! [`Coordinator::response_accumulator`](crate::coordinator::Coordinator::response_accumulator))
+//! also turns the chunks into the canonical streaming events, which
+//! [`ResponseAccumulator::emit_events`] dispatches:
+//!
+//! | Event                 | Payload                                                  |
+//! |-----------------------|----------------------------------------------------------|
+//! | `content:block_start` | `{index, block}`                                         |
+//! | `content:delta`       | `{index, kind: "text" \| "thinking" \| "tool_input", delta}` |
+//! | `content:block_stop`  | `{index}`                                                |
+//!
+//! Deltas are coalesced per block following `session.stream_events`:
+//!
+//! ```json
+//! {"session": {"stream_events": {"min_interval_ms": 50, "max_pending_chars": 256}}}
+//! ```
+//!
+//! A block's first delta is emitted at once; later ones are held until
+//! `min_interval_ms` has passed since the block's last `content:delta` or
+//! `max_pending_chars` have piled up. A block's pending text is always
+//! emitted before its `content:block_stop`, and all pending text once the
+//! `finish` chunk arrives. `"enabled": false` turns the events off.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::clock::Clock;
+use crate::dialect::tool_calls_of;
+use crate::errors::ProviderError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::messages::{ChatResponse, ContentBlock, Degradation, Usage};
+
+// ---------------------------------------------------------------------------
+// StreamChunk
+// ---------------------------------------------------------------------------
+
+/// One unit of streamed provider output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamChunk {
+    /// A content block opens at `index`. Text and thinking blocks usually
+    /// start empty and tool calls with empty input.
+    BlockStart { index: usize, block: ContentBlock },
+    /// Text appended to the text block at `index`, which is started if it
+    /// does not exist yet.
+    TextDelta { index: usize, text: String },
+    /// Reasoning appended to the thinking block at `index`.
+    ThinkingDelta { index: usize, thinking: String },
+    /// Signature appended to the thinking block at `index`.
+    SignatureDelta { index: usize, signature: String },
+    /// A fragment of the JSON input of the tool call at `index`.
+    ToolInputDelta { index: usize, partial_json: String },
+    /// The block at `index` is complete.
+    BlockStop { index: usize },
+    /// Token usage so far.
+    Usage { usage: Usage },
+    /// Why generation stopped.
+    Finish { finish_reason: String },
+    /// The provider served a different model than requested.
+    Degradation { degradation: Degradation },
+    /// Response metadata; keys are merged, later values win.
+    Metadata { metadata: HashMap<String, Value> },
+}
+
+impl StreamChunk {
+    /// The block index this chunk addresses, for block chunks.
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            Self::BlockStart { index, .. }
+            | Self::TextDelta { index, .. }
+            | Self::ThinkingDelta { index, .. }
+            | Self::SignatureDelta { index, .. }
+            | Self::ToolInputDelta { index, .. }
+            | Self::BlockStop { index } => Some(*index),
+            _ => None,
+        }
+    }
+}
+
+/// Streams that cannot be reassembled.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum StreamError {
+    /// A second `block_start` for an index already in use.
+    #[error("block {index} started twice")]
+    DuplicateBlock { index: usize },
+
+    /// A delta or stop for an index that was never started.
+    #[error("chunk for unknown block {index}")]
+    UnknownBlock { index: usize },
+
+    /// A delta after the block's `block_stop`.
+    #[error("chunk for block {index} after it stopped")]
+    BlockClosed { index: usize },
+
+    /// A delta that does not apply to the block's type.
+    #[error("{delta} does not apply to the {block} block at {index}")]
+    UnexpectedDelta {
+        index: usize,
+        delta: &'static str,
+        block: String,
+    },
+
+    /// The assembled tool call input is not a JSON object.
+    #[error("invalid input for tool call block {index}: {message}")]
+    InvalidToolInput { index: usize, message: String },
+}
+
+impl From<StreamError> for ProviderError {
+    fn from(error: StreamError) -> Self {
+        ProviderError::Other {
+            message: error.to_string(),
+            provider: None,
+            model: None,
+            retry_after: None,
+            status_code: None,
+            retryable: false,
+            delay_multiplier: None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Display events
+// ---------------------------------------------------------------------------
+
+/// The `session.stream_events` section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StreamEventConfig {
+    pub enabled: bool,
+    /// Minimum time between two `content:delta` events of one block.
+    pub min_interval_ms: u64,
+    /// Pending characters that force a `content:delta` before the interval.
+    pub max_pending_chars: usize,
+}
+
+impl Default for StreamEventConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_ms: 50,
+            max_pending_chars: 256,
+        }
+    }
+}
+
+impl StreamEventConfig {
+    /// Read `session.stream_events` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("stream_events")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.stream_events config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+/// A canonical streaming event ready to emit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamEvent {
+    pub name: &'static str,
+    pub data: Value,
+}
+
+/// Text a block has streamed since its last `content:delta`.
+struct PendingDelta {
+    kind: &'static str,
+    text: String,
+    last_emitted: Option<Instant>,
+}
+
+/// Turns chunks into coalesced [`StreamEvent`]s.
+struct DeltaEvents {
+    config: StreamEventConfig,
+    clock: Arc<dyn Clock>,
+    pending: BTreeMap<usize, PendingDelta>,
+    ready: Vec<StreamEvent>,
+}
+
+impl DeltaEvents {
+    fn observe(&mut self, chunk: StreamChunk, implicit_start: bool) {
+        match chunk {
+            StreamChunk::BlockStart { index, block } => self.start(index, &block),
+            StreamChunk::TextDelta { index, text } => {
+                if implicit_start {
+                    self.start(index, &serde_json::json!({"type": "text", "text": ""}));
+                }
+                self.buffer(index, "text", &text);
+            }
+            StreamChunk::ThinkingDelta { index, thinking } => {
+                self.buffer(index, "thinking", &thinking)
+            }
+            StreamChunk::ToolInputDelta {
+                index,
+                partial_json,
+            } => self.buffer(index, "tool_input", &partial_json),
+            StreamChunk::Finish { .. } => {
+                let indices: Vec<usize> = self.pending.keys().copied().collect();
+                for index in indices {
+                    self.flush(index);
+                }
+            }
+            StreamChunk::BlockStop { index } => {
+                self.flush(index);
+                self.ready.push(StreamEvent {
+                    name: events::CONTENT_STOP,
+                    data: serde_json::json!({"index": index}),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, index: usize, block: &impl Serialize) {
+        self.ready.push(StreamEvent {
+            name: events::CONTENT_START,
+            data: serde_json::json!({"index": index, "block": block}),
+        });
+    }
+
+    fn buffer(&mut self, index: usize, kind: &'static str, text: &str) {
+        let pending = self.pending.entry(index).or_insert(PendingDelta {
+            kind,
+            text: String::new(),
+            last_emitted: None,
+        });
+        pending.text.push_str(text);
+        if pending.text.chars().count() >= self.config.max_pending_chars
+            || self.interval_elapsed(index)
+        {
+            self.flush(index);
+        }
+    }
+
+    fn interval_elapsed(&self, index: usize) -> bool {
+        let interval = Duration::from_millis(self.config.min_interval_ms);
+        self.pending[&index]
+            .last_emitted
+            .is_none_or(|at| self.clock.now().saturating_duration_since(at) >= interval)
+    }
+
+    fn flush(&mut self, index: usize) {
+        let Some(pending) = self.pending.get_mut(&index) else {
+            return;
+        };
+        if pending.text.is_empty() {
+            return;
+        }
+        pending.last_emitted = Some(self.clock.now());
+        self.ready.push(StreamEvent {
+            name: events::CONTENT_DELTA,
+            data: serde_json::json!({
+                "index": index,
+                "kind": pending.kind,
+                "delta": std::mem::take(&mut pending.text),
+            }),
+        });
+    }
+
+    /// Ready events, plus pending deltas whose interval has passed.
+    fn take(&mut self) -> Vec<StreamEvent> {
+        let due: Vec<usize> = self
+            .pending
+            .keys()
+            .copied()
+            .filter(|&index| self.interval_elapsed(index))
+            .collect();
+        for index in due {
+            self.flush(index);
+        }
+        std::mem::take(&mut self.ready)
+    }
+}
+
+impl fmt::Debug for DeltaEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeltaEvents")
+            .field("config", &self.config)
+            .field("ready", &self.ready.len())
+            .finish_non_exhaustive()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ResponseAccumulator
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct PartialBlock {
+    block: ContentBlock,
+    /// Concatenated `partial_json` of a tool call.
+    input_json: String,
+    stopped: bool,
+}
+
+/// Builds a [`ChatResponse`] from [`StreamChunk`]s.
+#[derive(Debug, Default)]
+pub struct ResponseAccumulator {
+    blocks: BTreeMap<usize, PartialBlock>,
+    usage: Option<Usage>,
+    finish_reason: Option<String>,
+    degradation: Option<Degradation>,
+    metadata: HashMap<String, Value>,
+    /// Display events, when built with [`with_events`](Self::with_events).
+    events: Option<DeltaEvents>,
+}
+
+impl ResponseAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An accumulator that also produces the canonical streaming events
+    /// (see [Display events](self#display-events)), timing coalescing on
+    /// `clock`. With `config.enabled` off it produces none.
+    pub fn with_events(config: StreamEventConfig, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            events: config.enabled.then(|| DeltaEvents {
+                config,
+                clock,
+                pending: BTreeMap::new(),
+                ready: Vec::new(),
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Ingest the next chunk.
+    ///
+    /// # Errors
+    ///
+    /// A [`StreamError`] if the chunk does not fit the blocks seen so far;
+    /// the accumulator is left as it was.
+    pub fn push(&mut self, chunk: StreamChunk) -> Result<(), StreamError> {
+        let observed = self.events.as_ref().map(|_| {
+            let implicit_start = matches!(&chunk, StreamChunk::TextDelta { index, .. }
+                if !self.blocks.contains_key(index));
+            (chunk.clone(), implicit_start)
+        });
+        self.apply(chunk)?;
+        if let (Some(events), Some((chunk, implicit_start))) = (&mut self.events, observed) {
+            events.observe(chunk, implicit_start);
+        }
+        Ok(())
+    }
+
+    /// Streaming events produced since the last call, including held deltas
+    /// whose coalescing interval has passed. Always empty for an accumulator
+    /// built with [`new`](Self::new).
+    pub fn take_events(&mut self) -> Vec<StreamEvent> {
+        self.events
+            .as_mut()
+            .map(DeltaEvents::take)
+            .unwrap_or_default()
+    }
+
+    /// Emit [`take_events`](Self::take_events) on `hooks`, in order.
+    pub async fn emit_events(&mut self, hooks: &HookRegistry) {
+        for event in self.take_events() {
+            hooks.emit(event.name, event.data).await;
+        }
+    }
+
+    fn apply(&mut self, chunk: StreamChunk) -> Result<(), StreamError> {
+        match chunk {
+            StreamChunk::BlockStart { index, block } => {
+                if self.blocks.contains_key(&index) {
+                    return Err(StreamError::DuplicateBlock { index });
+                }
+                self.blocks.insert(
+                    index,
+                    PartialBlock {
+                        block,
+                        input_json: String::new(),
+                        stopped: false,
+                    },
+                );
+            }
+            StreamChunk::TextDelta { index, text } => {
+                let partial = self.blocks.entry(index).or_insert_with(|| PartialBlock {
+                    block: ContentBlock::Text {
+                        text: String::new(),
+                        visibility: None,
+                        cache: None,
+                        extensions: HashMap::new(),
+                    },
+                    input_json: String::new(),
+                    stopped: false,
+                });
+                match &mut ensure_open(partial, index)?.block {
+                    ContentBlock::Text { text: current, .. } => current.push_str(&text),
+                    other => return Err(unexpected(index, "text_delta", other)),
+                }
+            }
+            StreamChunk::ThinkingDelta { index, thinking } => {
+                match &mut self.open_block(index)?.block {
+                    ContentBlock::Thinking {
+                        thinking: current, ..
+                    } => current.push_str(&thinking),
+                    other => return Err(unexpected(index, "thinking_delta", other)),
+                }
+            }
+            StreamChunk::SignatureDelta { index, signature } => {
+                match &mut self.open_block(index)?.block {
+                    ContentBlock::Thinking {
+                        signature: current, ..
+                    } => current.get_or_insert_with(String::new).push_str(&signature),
+                    other => return Err(unexpected(index, "signature_delta", other)),
+                }
+            }
+            StreamChunk::ToolInputDelta {
+                index,
+                partial_json,
+            } => {
+                let partial = self.open_block(index)?;
+                if !matches!(partial.block, ContentBlock::ToolCall { .. }) {
+                    return Err(unexpected(index, "tool_input_delta", &partial.block));
+                }
+                partial.input_json.push_str(&partial_json);
+            }
+            StreamChunk::BlockStop { index } => {
+                self.blocks
+                    .get_mut(&index)
+                    .ok_or(StreamError::UnknownBlock { index })?
+                    .stopped = true;
+            }
+            StreamChunk::Usage { usage } => {
+                self.usage = Some(match self.usage.take() {
+                    Some(current) => merge_usage(current, usage),
+                    None => usage,
+                });
+            }
+            StreamChunk::Finish { finish_reason } => self.finish_reason = Some(finish_reason),
+            StreamChunk::Degradation { degradation } => self.degradation = Some(degradation),
+            StreamChunk::Metadata { metadata } => self.metadata.extend(metadata),
+        }
+        Ok(())
+    }
+
+    /// Text received so far, across all text blocks in index order.
+    pub fn text(&self) -> String {
+        self.blocks
+            .values()
+            .filter_map(|partial| match &partial.block {
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The response the stream describes. Blocks that were never stopped
+    /// are included as received.
+    ///
+    /// # Errors
+    ///
+    /// `StreamError::InvalidToolInput` if a tool call's assembled input is
+    /// not a JSON object.
+    pub fn finish(self) -> Result<ChatResponse, StreamError> {
+        let mut content = Vec::with_capacity(self.blocks.len());
+        for (index, partial) in self.blocks {
+            let mut block = partial.block;
+            if let ContentBlock::ToolCall { input, .. } = &mut block {
+                if !partial.input_json.trim().is_empty() {
+                    *input = parse_input(index, &partial.input_json)?;
+                }
+            }
+            if matches!(&block, ContentBlock::Text { text, .. } if text.is_empty()) {
+                continue;
+            }
+            content.push(block);
+        }
+
+        Ok(ChatResponse {
+            tool_calls: tool_calls_of(&content),
+            content,
+            usage: self.usage,
+            degradation: self.degradation,
+            finish_reason: self.finish_reason,
+            metadata: (!self.metadata.is_empty()).then_some(self.metadata),
+            extensions: HashMap::new(),
+        })
+    }
+
+    fn open_block(&mut self, index: usize) -> Result<&mut PartialBlock, StreamError> {
+        let partial = self
+            .blocks
+            .get_mut(&index)
+            .ok_or(StreamError::UnknownBlock { index })?;
+        ensure_open(partial, index)
+    }
+}
+
+fn ensure_open(partial: &mut PartialBlock, index: usize) -> Result<&mut PartialBlock, StreamError> {
+    if partial.stopped {
+        return Err(StreamError::BlockClosed { index });
+    }
+    Ok(partial)
+}
+
+fn unexpected(index: usize, delta: &'static str, block: &ContentBlock) -> StreamError {
+    let block = serde_json::to_value(block)
+        .ok()
+        .and_then(|v| v.get("type").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_default();
+    StreamError::UnexpectedDelta {
+        index,
+        delta,
+        block,
+    }
+}
+
+fn parse_input(index: usize, raw: &str) -> Result<HashMap<String, Value>, StreamError> {
+    let invalid = |message: String| StreamError::InvalidToolInput { index, message };
+    match serde_json::from_str::<Value>(raw).map_err(|e| invalid(e.to_string()))? {
+        Value::Object(map) => Ok(map.into_iter().collect()),
+        Value::Null => Ok(HashMap::new()),
+        other => Err(invalid(format!("expected an object, got {other}"))),
+    }
+}
+
+/// Combine two running usage reports field by field.
+fn merge_usage(current: Usage, later: Usage) -> Usage {
+    fn max_opt(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+    let input_tokens = current.input_tokens.max(later.input_tokens);
+    let output_tokens = current.output_tokens.max(later.output_tokens);
+    let mut extensions = current.extensions;
+    extensions.extend(later.extensions);
+    Usage {
+        input_tokens,
+        output_tokens,
+        total_tokens: current
+            .total_tokens
+            .max(later.total_tokens)
+            .max(input_tokens + output_tokens),
+        reasoning_tokens: max_opt(current.reasoning_tokens, later.reasoning_tokens),
+        cache_read_tokens: max_opt(current.cache_read_tokens, later.cache_read_tokens),
+        cache_write_tokens: max_opt(current.cache_write_tokens, later.cache_write_tokens),
+        cost_usd: later.cost_usd.or(current.cost_usd),
+        extensions,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::{OpenAiDialect, ProviderDialect};
+    use serde_json::json;
+
+    fn chunks(list: Value) -> Vec<StreamChunk> {
+        serde_json::from_value(list).unwrap()
+    }
+
+    fn accumulate(list: Value) -> Result<ChatResponse, StreamError> {
+        let mut acc = ResponseAccumulator::new();
+        for chunk in chunks(list) {
+            acc.push(chunk)?;
+        }
+        acc.finish()
+    }
+
+    #[test]
+    fn streamed_response_matches_non_streaming_decode() {
+        let streamed = accumulate(json!([
+            {"type": "text_delta", "index": 0, "text": "Let me "},
+            {"type": "block_start", "index": 1,
+             "block": {"type": "tool_call", "id": "c1", "name": "calc", "input": {}}},
+            {"type": "text_delta", "index": 0, "text": "check."},
+            {"type": "tool_input_delta", "index": 1, "partial_json": "{\"expr\": "},
+            {"type": "usage", "usage": {"input_tokens": 10, "output_tokens": 1, "total_tokens": 11}},
+            {"type": "tool_input_delta", "index": 1, "partial_json": "\"2+2\"}"},
+            {"type": "block_stop", "index": 1},
+            {"type": "usage", "usage": {"input_tokens": 0, "output_tokens": 7, "total_tokens": 0}},
+            {"type": "finish", "finish_reason": "tool_calls"}
+        ]))
+        .unwrap();
+
+        let decoded = OpenAiDialect
+            .decode_response(&json!({
+                "choices": [{
+                    "finish_reason": "tool_calls",
+                    "message": {
+                        "content": "Let me check.",
+                        "tool_calls": [{"id": "c1", "type": "function",
+                            "function": {"name": "calc", "arguments": "{\"expr\": \"2+2\"}"}}]
+                    }
+                }],
+                "usage": {"prompt_tokens": 10, "completion_tokens": 7, "total_tokens": 17}
+            }))
+            .unwrap();
+        assert_eq!(streamed, decoded);
+    }
+
+    #[test]
+    fn thinking_signatures_and_metadata_are_assembled() {
+        let response = accumulate(json!([
+            {"type": "block_start", "index": 0, "block": {"type": "thinking", "thinking": ""}},
+            {"type": "thinking_delta", "index": 0, "thinking": "hmm"},
+            {"type": "signature_delta", "index": 0, "signature": "sig"},
+            {"type": "block_start", "index": 1, "block": {"type": "text", "text": ""}},
+            {"type": "block_stop", "index": 1},
+            {"type": "metadata", "metadata": {"model": "m1"}}
+        ]))
+        .unwrap();
+        assert_eq!(response.content.len(), 1);
+        assert!(matches!(
+            &response.content[0],
+            ContentBlock::Thinking { thinking, signature: Some(sig), .. }
+                if thinking == "hmm" && sig == "sig"
+        ));
+        assert!(response.tool_calls.is_none());
+        assert_eq!(response.metadata.unwrap()["model"], "m1");
+    }
+
+    #[test]
+    fn inconsistent_streams_are_rejected() {
+        let err = accumulate(json!([{"type": "thinking_delta", "index": 0, "thinking": "x"}]));
+        assert_eq!(err.unwrap_err(), StreamError::UnknownBlock { index: 0 });
+
+        let err = accumulate(json!([
+            {"type": "text_delta", "index": 0, "text": "a"},
+            {"type": "block_stop", "index": 0},
+            {"type": "text_delta", "index": 0, "text": "b"}
+        ]));
+        assert_eq!(err.unwrap_err(), StreamError::BlockClosed { index: 0 });
+
+        let err = accumulate(json!([
+            {"type": "text_delta", "index": 0, "text": "a"},
+            {"type": "tool_input_delta", "index": 0, "partial_json": "{}"}
+        ]));
+        assert!(matches!(
+            err.unwrap_err(),
+            StreamError::UnexpectedDelta { block, .. } if block == "text"
+        ));
+
+        let err = accumulate(json!([
+            {"type": "block_start", "index": 0,
+             "block": {"type": "tool_call", "id": "c1", "name": "calc", "input": {}}},
+            {"type": "tool_input_delta", "index": 0, "partial_json": "{\"expr\": "}
+        ]));
+        assert!(matches!(
+            err.unwrap_err(),
+            StreamError::InvalidToolInput { index: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn deltas_are_coalesced_between_intervals() {
+        let clock = Arc::new(crate::testing::ManualClock::new(chrono::Utc::now()));
+        let config = StreamEventConfig {
+            max_pending_chars: 8,
+            ..Default::default()
+        };
+        let mut acc = ResponseAccumulator::with_events(config, clock.clone());
+        let push = |acc: &mut ResponseAccumulator, chunk: Value| {
+            acc.push(serde_json::from_value(chunk).unwrap()).unwrap();
+            acc.take_events()
+                .into_iter()
+                .map(|e| (e.name, e.data))
+                .collect::<Vec<_>>()
+        };
+
+        let first = push(
+            &mut acc,
+            json!({"type": "text_delta", "index": 0, "text": "He"}),
+        );
+        assert_eq!(
+            first,
+            vec![
+                (
+                    events::CONTENT_START,
+                    json!({"index": 0, "block": {"type": "text", "text": ""}})
+                ),
+                (
+                    events::CONTENT_DELTA,
+                    json!({"index": 0, "kind": "text", "delta": "He"})
+                ),
+            ]
+        );
+        // Held until the interval passes or enough text piles up.
+        assert!(push(
+            &mut acc,
+            json!({"type": "text_delta", "index": 0, "text": "ll"})
+        )
+        .is_empty());
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(
+            acc.take_events()[0].data,
+            json!({"index": 0, "kind": "text", "delta": "ll"})
+        );
+        let burst = push(
+            &mut acc,
+            json!({"type": "text_delta", "index": 0, "text": "o, world"}),
+        );
+        assert_eq!(burst[0].1["delta"], "o, world");
+
+        assert!(push(
+            &mut acc,
+            json!({"type": "text_delta", "index": 0, "text": "!"})
+        )
+        .is_empty());
+        let stop = push(&mut acc, json!({"type": "block_stop", "index": 0}));
+        assert_eq!(
+            stop,
+            vec![
+                (
+                    events::CONTENT_DELTA,
+                    json!({"index": 0, "kind": "text", "delta": "!"})
+                ),
+                (events::CONTENT_STOP, json!({"index": 0})),
+            ]
+        );
+        assert_eq!(acc.finish().unwrap().content.len(), 1);
+    }
+
+    #[test]
+    fn plain_and_disabled_accumulators_produce_no_events() {
+        let mut plain = ResponseAccumulator::new();
+        let disabled = StreamEventConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        let mut disabled = ResponseAccumulator::with_events(
+            disabled,
+            Arc::new(crate::testing::ManualClock::new(chrono::Utc::now())),
+        );
+        for acc in [&mut plain, &mut disabled] {
+            acc.push(StreamChunk::TextDelta {
+                index: 0,
+                text: "hi".into(),
+            })
+            .unwrap();
+            assert!(acc.take_events().is_empty());
+        }
+    }
+}
@@ -0,0 +1,539 @@
+//! Kernel-side validation of structured (JSON-schema) output.
+//!
+//! Providers honour [`ResponseFormat::JsonSchema`] to varying degrees: some
+//! enforce it, some treat it as a hint, and some wrap the JSON in a code
+//! fence. [`ProviderInvoker::complete_structured`] checks the final text
+//! itself and re-asks on failure:
+//!
+//! 1. The response's text blocks are joined, a surrounding code fence is
+//!    stripped, and the rest is parsed as JSON.
+//! 2. The value is validated against the request's schema with
+//!    [`validate`], then deserialized into the caller's type.
+//! 3. On any failure the invalid reply and a user message listing the
+//!    problems are appended to the request, and the provider is called
+//!    again, up to `max_retries` times.
+//!
+//! Every attempt goes through the invoker's `provider:pre` /
+//! `provider:post` hooks like any other call.
+//!
+//! # Supported Keywords
+//!
+//! `type`, `enum`, `const`, `properties`, `required`,
+//! `additionalProperties`, `items`, `minItems`, `maxItems`, `minLength`,
+//! `maxLength`, `minimum`, `maximum`, `exclusiveMinimum`,
+//! `exclusiveMaximum`, `allOf`, `anyOf`, `oneOf` and local `$ref`s
+//! (`#/$defs/...`). Other keywords are ignored, so a schema using them is
+//! checked less strictly than the provider may check it.
+//!
+//! ```json
+//! {"session": {"structured_output": {"max_retries": 2}}}
+//! ```
+//!
+//! [`ResponseFormat::JsonSchema`]: crate::messages::ResponseFormat::JsonSchema
+//! [`ProviderInvoker::complete_structured`]: crate::provider_invoker::ProviderInvoker::complete_structured
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::errors::ProviderError;
+use crate::messages::{ChatResponse, ContentBlock, Message, MessageContent, Role};
+
+// ---------------------------------------------------------------------------
+// StructuredOutputConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.structured_output` section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StructuredOutputConfig {
+    /// Re-asks after the first invalid response.
+    pub max_retries: u32,
+}
+
+impl Default for StructuredOutputConfig {
+    fn default() -> Self {
+        Self { max_retries: 2 }
+    }
+}
+
+impl StructuredOutputConfig {
+    /// Read `session.structured_output` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config
+            .get("session")
+            .and_then(|s| s.get("structured_output"))
+        else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.structured_output config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Results and errors
+// ---------------------------------------------------------------------------
+
+/// A response whose text matched the schema and deserialized into `T`.
+#[derive(Debug, Clone)]
+pub struct StructuredOutput<T> {
+    pub value: T,
+    /// The validated JSON, before deserialization.
+    pub json: Value,
+    /// The provider response it was read from.
+    pub response: ChatResponse,
+    /// Provider calls made, including re-asks.
+    pub attempts: u32,
+}
+
+/// One way a value fails its schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaViolation {
+    /// Where in the value, e.g. `$.items[0].name`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Why [`complete_structured`](crate::provider_invoker::ProviderInvoker::complete_structured)
+/// failed.
+#[derive(Debug, thiserror::Error)]
+pub enum StructuredOutputError {
+    /// The request has no `json_schema` response format.
+    #[error("request has no json_schema response format")]
+    NoSchema,
+
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    /// Every attempt produced output that did not match.
+    #[error("response did not match the schema after {attempts} attempt(s): {}", join(.violations))]
+    Invalid {
+        attempts: u32,
+        /// Problems with the last response.
+        violations: Vec<SchemaViolation>,
+        /// The last response's text.
+        text: String,
+    },
+}
+
+fn join(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// ---------------------------------------------------------------------------
+// Response handling
+// ---------------------------------------------------------------------------
+
+/// The text blocks of `response`, joined.
+pub fn response_text(response: &ChatResponse) -> String {
+    response
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            ContentBlock::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parse `text` as JSON, validate it against `schema` and deserialize it.
+///
+/// # Errors
+///
+/// The parse error, schema violations or deserialization error, as
+/// violations.
+pub fn parse<T: DeserializeOwned>(
+    schema: &Value,
+    text: &str,
+) -> Result<(T, Value), Vec<SchemaViolation>> {
+    let root = |message: String| vec![violation("$", message)];
+    let json: Value = serde_json::from_str(strip_code_fence(text))
+        .map_err(|e| root(format!("not valid JSON: {e}")))?;
+    validate(schema, &json)?;
+    let value = T::deserialize(&json).map_err(|e| root(e.to_string()))?;
+    Ok((value, json))
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let text = text.trim();
+    let Some(inner) = text.strip_prefix("```").and_then(|t| t.strip_suffix("```")) else {
+        return text;
+    };
+    // Drop the info string (`json`) on the opening line.
+    inner
+        .split_once('\n')
+        .map_or(inner, |(_, body)| body)
+        .trim()
+}
+
+/// The user message asking the model to correct an invalid response.
+pub fn reask_message(violations: &[SchemaViolation]) -> Message {
+    let problems: Vec<String> = violations.iter().map(|v| format!("- {v}")).collect();
+    Message {
+        role: Role::User,
+        content: MessageContent::Text(format!(
+            "Your previous response did not match the required JSON schema:\n{}\n\
+             Reply again with only a JSON value that matches the schema.",
+            problems.join("\n")
+        )),
+        name: None,
+        tool_call_id: None,
+        metadata: None,
+        cache: None,
+        extensions: HashMap::new(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Validate `value` against the JSON Schema `schema` (see the module docs
+/// for the keywords checked).
+///
+/// # Errors
+///
+/// Every violation found.
+pub fn validate(schema: &Value, value: &Value) -> Result<(), Vec<SchemaViolation>> {
+    let mut violations = Vec::new();
+    Validator { root: schema }.check(schema, value, "$", &mut violations);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+}
+
+impl Validator<'_> {
+    fn check(&self, schema: &Value, value: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+        let schema = match schema {
+            Value::Object(schema) => schema,
+            Value::Bool(false) => {
+                return out.push(violation(path, "no value is allowed here".into()))
+            }
+            _ => return,
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match reference
+                .strip_prefix('#')
+                .and_then(|p| self.root.pointer(p))
+            {
+                Some(target) => self.check(target, value, path, out),
+                None => out.push(violation(path, format!("unresolvable $ref {reference}"))),
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed: Vec<&str> = match types {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+                // Nothing else is meaningful for a value of the wrong type.
+                return out.push(violation(
+                    path,
+                    format!(
+                        "expected {}, got {}",
+                        allowed.join(" or "),
+                        type_name(value)
+                    ),
+                ));
+            }
+        }
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.contains(value) {
+                out.push(violation(
+                    path,
+                    format!("{value} is not one of the allowed values"),
+                ));
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if value != expected {
+                out.push(violation(path, format!("expected {expected}")));
+            }
+        }
+
+        match value {
+            Value::String(s) => {
+                let len = s.chars().count() as u64;
+                if bound(schema, "minLength").is_some_and(|min| len < min) {
+                    out.push(violation(
+                        path,
+                        format!("shorter than {} characters", schema["minLength"]),
+                    ));
+                }
+                if bound(schema, "maxLength").is_some_and(|max| len > max) {
+                    out.push(violation(
+                        path,
+                        format!("longer than {} characters", schema["maxLength"]),
+                    ));
+                }
+            }
+            Value::Number(n) => {
+                let n = n.as_f64().unwrap_or_default();
+                let limit = |key: &str| schema.get(key).and_then(Value::as_f64);
+                let checks = [
+                    ("minimum", limit("minimum").is_some_and(|m| n < m)),
+                    ("maximum", limit("maximum").is_some_and(|m| n > m)),
+                    (
+                        "exclusiveMinimum",
+                        limit("exclusiveMinimum").is_some_and(|m| n <= m),
+                    ),
+                    (
+                        "exclusiveMaximum",
+                        limit("exclusiveMaximum").is_some_and(|m| n >= m),
+                    ),
+                ];
+                for (key, broken) in checks {
+                    if broken {
+                        out.push(violation(path, format!("violates {key} {}", schema[key])));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let len = items.len() as u64;
+                if bound(schema, "minItems").is_some_and(|min| len < min) {
+                    out.push(violation(
+                        path,
+                        format!("fewer than {} items", schema["minItems"]),
+                    ));
+                }
+                if bound(schema, "maxItems").is_some_and(|max| len > max) {
+                    out.push(violation(
+                        path,
+                        format!("more than {} items", schema["maxItems"]),
+                    ));
+                }
+                if let Some(item_schema) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &format!("{path}[{i}]"), out);
+                    }
+                }
+            }
+            Value::Object(map) => self.check_object(schema, map, path, out),
+            _ => {}
+        }
+
+        if let Some(Value::Array(all)) = schema.get("allOf") {
+            for sub in all {
+                self.check(sub, value, path, out);
+            }
+        }
+        if let Some(Value::Array(any)) = schema.get("anyOf") {
+            if !any.iter().any(|sub| self.matches(sub, value)) {
+                out.push(violation(path, "does not match any anyOf schema".into()));
+            }
+        }
+        if let Some(Value::Array(one)) = schema.get("oneOf") {
+            let matched = one.iter().filter(|sub| self.matches(sub, value)).count();
+            if matched != 1 {
+                out.push(violation(
+                    path,
+                    format!("matches {matched} oneOf schemas, expected exactly 1"),
+                ));
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        map: &Map<String, Value>,
+        path: &str,
+        out: &mut Vec<SchemaViolation>,
+    ) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    out.push(violation(
+                        path,
+                        format!("missing required property `{key}`"),
+                    ));
+                }
+            }
+        }
+        for (key, item) in map {
+            let item_path = format!("{path}.{key}");
+            match properties.and_then(|p| p.get(key)) {
+                Some(property) => self.check(property, item, &item_path, out),
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        out.push(violation(&item_path, "unexpected property".into()))
+                    }
+                    Some(additional) => self.check(additional, item, &item_path, out),
+                    None => {}
+                },
+            }
+        }
+    }
+
+    fn matches(&self, schema: &Value, value: &Value) -> bool {
+        let mut violations = Vec::new();
+        self.check(schema, value, "$", &mut violations);
+        violations.is_empty()
+    }
+}
+
+fn violation(path: &str, message: String) -> SchemaViolation {
+    SchemaViolation {
+        path: path.to_string(),
+        message,
+    }
+}
+
+fn bound(schema: &Map<String, Value>, key: &str) -> Option<u64> {
+    schema.get(key).and_then(Value::as_u64)
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value
+            .as_f64()
+            .is_some_and(|n| value.is_i64() || value.is_u64() || n.fract() == 0.0),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "tags": {"type": "array", "items": {"$ref": "#/$defs/tag"}, "maxItems": 2},
+                "priority": {"type": "integer", "minimum": 1, "maximum": 5}
+            },
+            "required": ["name", "priority"],
+            "additionalProperties": false,
+            "$defs": {"tag": {"type": "string", "enum": ["bug", "feature"]}}
+        })
+    }
+
+    fn paths(result: Result<(), Vec<SchemaViolation>>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .into_iter()
+            .map(|v| v.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid_values_pass() {
+        let value = json!({"name": "crash on start", "tags": ["bug"], "priority": 2});
+        assert_eq!(validate(&schema(), &value), Ok(()));
+        assert_eq!(validate(&json!(true), &value), Ok(()));
+        assert_eq!(validate(&json!({"type": "integer"}), &json!(3.0)), Ok(()));
+    }
+
+    #[test]
+    fn every_violation_is_reported_with_its_path() {
+        let value = json!({"name": "", "tags": ["bug", "chore", "feature"], "extra": 1});
+        assert_eq!(
+            paths(validate(&schema(), &value)),
+            [
+                "$: missing required property `priority`",
+                "$.extra: unexpected property",
+                "$.name: shorter than 1 characters",
+                "$.tags: more than 2 items",
+                r#"$.tags[1]: "chore" is not one of the allowed values"#,
+            ]
+        );
+        assert_eq!(
+            paths(validate(
+                &schema(),
+                &json!({"name": "x", "priority": "high"})
+            )),
+            ["$.priority: expected integer, got string"]
+        );
+    }
+
+    #[test]
+    fn combinators() {
+        let schema =
+            json!({"oneOf": [{"type": "string"}, {"type": "integer"}, {"type": "number"}]});
+        assert_eq!(validate(&schema, &json!("a")), Ok(()));
+        assert_eq!(validate(&schema, &json!(1.5)), Ok(()));
+        assert_eq!(
+            paths(validate(&schema, &json!(1))),
+            ["$: matches 2 oneOf schemas, expected exactly 1"]
+        );
+        let schema = json!({"anyOf": [{"type": "null"}, {"type": "string"}]});
+        assert_eq!(
+            paths(validate(&schema, &json!(1))),
+            ["$: does not match any anyOf schema"]
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Ticket {
+        name: String,
+        priority: u8,
+    }
+
+    #[test]
+    fn parse_strips_code_fences_and_deserializes() {
+        let text = "```json\n{\"name\": \"x\", \"priority\": 1}\n```";
+        let (ticket, json) = parse::<Ticket>(&schema(), text).unwrap();
+        assert_eq!(
+            ticket,
+            Ticket {
+                name: "x".into(),
+                priority: 1
+            }
+        );
+        assert_eq!(json["priority"], 1);
+
+        let violations = parse::<Ticket>(&schema(), "Sure! Here it is").unwrap_err();
+        assert!(violations[0].message.starts_with("not valid JSON"));
+    }
+}
@@ -0,0 +1,693 @@
+//! Conversation summarization for context compaction.
+//!
+//! Long sessions eventually outgrow the model's context window. Dropping old
+//! messages loses what they established; summarizing them keeps the gist in
+//! a fraction of the tokens. A [`Summarizer`] turns a run of messages into a
+//! single summary [`Message`]; [`ProviderSummarizer`] does so by asking a
+//! mounted provider.
+//!
+//! [`compact`] applies a summarizer to a history: leading `system` /
+//! `developer` messages stay in place, the oldest `summarize_oldest`
+//! messages after them are replaced by one `system` summary, and everything
+//! newer is left untouched. Pinned messages inside the summarized range are
+//! kept verbatim after the summary, and the range is extended rather than
+//! cut between a tool call and its result.
+//!
+//! # Configuration
+//!
+//! Opt-in through `session.summarization`:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "summarization": {
+//!       "provider": "anthropic",
+//!       "model": "claude-haiku",
+//!       "trigger_messages": 100,
+//!       "summarize_oldest": 50
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! [`SummarizingContext`] compacts the stored history before a request once
+//! it holds more than `trigger_messages` messages, emitting
+//! [`CONTEXT_PRE_COMPACT`](crate::events::CONTEXT_PRE_COMPACT) and
+//! [`CONTEXT_POST_COMPACT`](crate::events::CONTEXT_POST_COMPACT) around the
+//! summarization. Without `provider`, the session uses the first mounted
+//! provider by name.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::dialect;
+use crate::errors::ContextError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::messages::{ChatRequest, Message, MessageContent, Role};
+use crate::models::MessagePriority;
+use crate::provider_invoker::ProviderInvoker;
+use crate::traits::{ContextManager, Provider};
+
+/// Instructions sent with the transcript when none are configured.
+pub const DEFAULT_INSTRUCTIONS: &str = "Summarize the conversation below so it can replace \
+the original messages. Keep decisions, facts, file names, open tasks and anything the \
+assistant committed to. Omit pleasantries. Write in the third person.";
+
+// ---------------------------------------------------------------------------
+// SummarizationConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.summarization` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SummarizationConfig {
+    /// Set to `false` to keep the section but turn summarization off.
+    pub enabled: bool,
+    /// Name of the mounted provider that writes summaries.
+    pub provider: Option<String>,
+    /// Model to request; the provider's default when unset.
+    pub model: Option<String>,
+    /// Compact once the history holds more than this many messages.
+    pub trigger_messages: usize,
+    /// How many of the oldest messages to fold into one summary.
+    pub summarize_oldest: usize,
+    pub max_output_tokens: Option<i64>,
+    /// Replaces [`DEFAULT_INSTRUCTIONS`].
+    pub instructions: Option<String>,
+}
+
+impl Default for SummarizationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            provider: None,
+            model: None,
+            trigger_messages: 100,
+            summarize_oldest: 50,
+            max_output_tokens: None,
+            instructions: None,
+        }
+    }
+}
+
+impl SummarizationConfig {
+    /// Read `session.summarization` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("summarization"))?;
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.summarization config: {e}"))
+            .ok()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Summarizer
+// ---------------------------------------------------------------------------
+
+/// Condenses a run of conversation messages into one summary message.
+pub trait Summarizer: Send + Sync {
+    /// Summarize `messages` (as stored by a context manager).
+    ///
+    /// # Errors
+    ///
+    /// `ContextError::CompactionFailed` when no summary could be produced.
+    fn summarize<'a>(
+        &'a self,
+        messages: &'a [Value],
+    ) -> Pin<Box<dyn Future<Output = Result<Message, ContextError>> + Send + 'a>>;
+}
+
+/// A [`Summarizer`] that asks a provider to write the summary.
+///
+/// The messages are rendered as a plain-text transcript and sent as a single
+/// user message, so the request is valid for any provider regardless of how
+/// the original tool calls were shaped.
+pub struct ProviderSummarizer {
+    provider: Arc<dyn Provider>,
+    invoker: Option<ProviderInvoker>,
+    model: Option<String>,
+    instructions: String,
+    max_output_tokens: Option<i64>,
+}
+
+impl ProviderSummarizer {
+    /// Summarize with `provider`, using its default model.
+    pub fn new(provider: Arc<dyn Provider>) -> Self {
+        Self {
+            provider,
+            invoker: None,
+            model: None,
+            instructions: DEFAULT_INSTRUCTIONS.into(),
+            max_output_tokens: None,
+        }
+    }
+
+    /// Build a summarizer as configured by `config`.
+    pub fn from_config(provider: Arc<dyn Provider>, config: &SummarizationConfig) -> Self {
+        let mut summarizer = Self::new(provider);
+        summarizer.model = config.model.clone();
+        summarizer.max_output_tokens = config.max_output_tokens;
+        if let Some(instructions) = &config.instructions {
+            summarizer.instructions = instructions.clone();
+        }
+        summarizer
+    }
+
+    /// Request `model` instead of the provider's default.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Replace [`DEFAULT_INSTRUCTIONS`].
+    pub fn with_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = instructions.into();
+        self
+    }
+
+    pub fn with_max_output_tokens(mut self, max_output_tokens: i64) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    /// Route calls through `invoker`, so hooks, deadlines and cost tracking
+    /// see them like any other provider call.
+    pub fn with_invoker(mut self, invoker: ProviderInvoker) -> Self {
+        self.invoker = Some(invoker);
+        self
+    }
+
+    fn request(&self, messages: &[Value]) -> ChatRequest {
+        ChatRequest {
+            messages: vec![
+                text_message(Role::System, self.instructions.clone()),
+                text_message(Role::User, transcript(messages)),
+            ],
+            tools: None,
+            response_format: None,
+            temperature: None,
+            top_p: None,
+            max_output_tokens: self.max_output_tokens,
+            conversation_id: None,
+            stream: None,
+            metadata: None,
+            model: self.model.clone(),
+            tool_choice: None,
+            stop: None,
+            reasoning_effort: None,
+            timeout: None,
+            extensions: HashMap::new(),
+        }
+    }
+}
+
+impl Summarizer for ProviderSummarizer {
+    fn summarize<'a>(
+        &'a self,
+        messages: &'a [Value],
+    ) -> Pin<Box<dyn Future<Output = Result<Message, ContextError>> + Send + 'a>> {
+        Box::pin(async move {
+            let request = self.request(messages);
+            let response = match &self.invoker {
+                Some(invoker) => invoker.complete(self.provider.as_ref(), request).await,
+                None => self.provider.complete(request).await,
+            }
+            .map_err(|e| ContextError::CompactionFailed {
+                message: format!("summarization by {} failed: {e}", self.provider.name()),
+            })?;
+
+            let summary = dialect::message_text(&MessageContent::Blocks(response.content))
+                .trim()
+                .to_string();
+            if summary.is_empty() {
+                return Err(ContextError::CompactionFailed {
+                    message: format!("{} returned an empty summary", self.provider.name()),
+                });
+            }
+
+            let mut message = text_message(
+                Role::System,
+                format!(
+                    "Summary of {} earlier messages:\n\n{summary}",
+                    messages.len()
+                ),
+            );
+            message.metadata = Some(HashMap::from([
+                ("summary".to_string(), Value::Bool(true)),
+                ("summarized_messages".to_string(), messages.len().into()),
+            ]));
+            Ok(message)
+        })
+    }
+}
+
+fn text_message(role: Role, text: String) -> Message {
+    Message {
+        role,
+        content: MessageContent::Text(text),
+        name: None,
+        tool_call_id: None,
+        metadata: None,
+        cache: None,
+        extensions: HashMap::new(),
+    }
+}
+
+/// `messages` as a plain-text transcript, one `role: text` entry per message.
+fn transcript(messages: &[Value]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            let role = message
+                .get("role")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            let mut parts = Vec::new();
+            match message.get("content") {
+                Some(Value::String(text)) => parts.push(text.clone()),
+                Some(Value::Array(blocks)) => parts.extend(blocks.iter().filter_map(block_text)),
+                Some(Value::Null) | None => {}
+                Some(other) => parts.push(other.to_string()),
+            }
+            // OpenAI-style tool calls live beside the content.
+            for call in message
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let function = call.get("function").unwrap_or(call);
+                parts.push(format!(
+                    "[called {} with {}]",
+                    function
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .unwrap_or("a tool"),
+                    function.get("arguments").unwrap_or(&Value::Null),
+                ));
+            }
+            format!("{role}: {}", parts.join("\n"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn block_text(block: &Value) -> Option<String> {
+    match block.get("type").and_then(Value::as_str)? {
+        "text" => block
+            .get("text")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        "tool_call" => Some(format!(
+            "[called {} with {}]",
+            block
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("a tool"),
+            block.get("input").unwrap_or(&Value::Null),
+        )),
+        "tool_result" => Some(format!(
+            "[tool result: {}]",
+            dialect::tool_output_text(block.get("output").unwrap_or(&Value::Null)),
+        )),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Compaction
+// ---------------------------------------------------------------------------
+
+/// What [`compact`] replaced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionReport {
+    /// Messages folded into the summary.
+    pub summarized: usize,
+    /// Pinned messages from the summarized range that were kept.
+    pub kept_pinned: usize,
+    pub messages_before: usize,
+    pub messages_after: usize,
+}
+
+/// Replace the oldest `count` messages of `messages` (after any leading
+/// `system` / `developer` messages) with a summary from `summarizer`.
+///
+/// Returns `Ok(None)` when there is nothing to summarize.
+///
+/// # Errors
+///
+/// Whatever `summarizer` returns; `messages` is unchanged on error.
+pub async fn compact(
+    messages: &mut Vec<Value>,
+    count: usize,
+    summarizer: &dyn Summarizer,
+) -> Result<Option<CompactionReport>, ContextError> {
+    let start = messages
+        .iter()
+        .position(|m| !matches!(role(m), "system" | "developer"))
+        .unwrap_or(messages.len());
+    let mut end = start.saturating_add(count).min(messages.len());
+    // Never separate a tool call from its results.
+    while end < messages.len() && is_tool_result(&messages[end]) {
+        end += 1;
+    }
+
+    let (pinned, summarized): (Vec<Value>, Vec<Value>) = messages[start..end]
+        .iter()
+        .cloned()
+        .partition(|m| MessagePriority::of(m) == MessagePriority::Pinned);
+    if summarized.is_empty() {
+        return Ok(None);
+    }
+
+    let summary = summarizer.summarize(&summarized).await?;
+    let summary = serde_json::to_value(summary).map_err(|e| ContextError::CompactionFailed {
+        message: format!("summary is not serializable: {e}"),
+    })?;
+
+    let before = messages.len();
+    let kept_pinned = pinned.len();
+    messages.splice(start..end, std::iter::once(summary).chain(pinned));
+    Ok(Some(CompactionReport {
+        summarized: summarized.len(),
+        kept_pinned,
+        messages_before: before,
+        messages_after: messages.len(),
+    }))
+}
+
+fn role(message: &Value) -> &str {
+    message.get("role").and_then(Value::as_str).unwrap_or("")
+}
+
+fn is_tool_result(message: &Value) -> bool {
+    role(message) == "tool"
+        || message
+            .get("content")
+            .and_then(Value::as_array)
+            .is_some_and(|blocks| {
+                blocks
+                    .iter()
+                    .any(|b| b.get("type").and_then(Value::as_str) == Some("tool_result"))
+            })
+}
+
+// ---------------------------------------------------------------------------
+// SummarizingContext
+// ---------------------------------------------------------------------------
+
+/// A [`ContextManager`] that summarizes the oldest part of its inner
+/// context's history once it grows past the configured trigger.
+///
+/// Compaction happens at the start of
+/// [`get_messages_for_request`](ContextManager::get_messages_for_request)
+/// and rewrites the stored history through
+/// [`set_messages`](ContextManager::set_messages). A failed summarization is
+/// logged and the request proceeds with the full history.
+pub struct SummarizingContext {
+    inner: Arc<dyn ContextManager>,
+    summarizer: Arc<dyn Summarizer>,
+    config: SummarizationConfig,
+    hooks: Arc<HookRegistry>,
+}
+
+impl SummarizingContext {
+    /// Wrap `inner`, summarizing with `summarizer` and reporting on `hooks`.
+    pub fn new(
+        inner: Arc<dyn ContextManager>,
+        summarizer: Arc<dyn Summarizer>,
+        config: SummarizationConfig,
+        hooks: Arc<HookRegistry>,
+    ) -> Self {
+        Self {
+            inner,
+            summarizer,
+            config,
+            hooks,
+        }
+    }
+
+    /// Summarize the oldest `summarize_oldest` messages now, regardless of
+    /// the trigger.
+    ///
+    /// Returns `Ok(None)` when there was nothing to summarize.
+    pub async fn compact(&self) -> Result<Option<CompactionReport>, ContextError> {
+        let mut messages = self.inner.get_messages().await?;
+        self.hooks
+            .emit(
+                events::CONTEXT_PRE_COMPACT,
+                serde_json::json!({
+                    "strategy": "summarize",
+                    "message_count": messages.len(),
+                    "summarize_oldest": self.config.summarize_oldest,
+                }),
+            )
+            .await;
+
+        let report = compact(
+            &mut messages,
+            self.config.summarize_oldest,
+            self.summarizer.as_ref(),
+        )
+        .await?;
+        if let Some(report) = &report {
+            self.inner.set_messages(messages).await?;
+            self.hooks
+                .emit(
+                    events::CONTEXT_POST_COMPACT,
+                    serde_json::json!({
+                        "strategy": "summarize",
+                        "summarized": report.summarized,
+                        "kept_pinned": report.kept_pinned,
+                        "messages_before": report.messages_before,
+                        "messages_after": report.messages_after,
+                    }),
+                )
+                .await;
+        }
+        Ok(report)
+    }
+}
+
+impl ContextManager for SummarizingContext {
+    fn add_message(
+        &self,
+        message: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.add_message(message)
+    }
+
+    fn get_messages_for_request(
+        &self,
+        token_budget: Option<i64>,
+        provider: Option<Arc<dyn Provider>>,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        Box::pin(async move {
+            let count = self.inner.get_messages().await?.len();
+            if count > self.config.trigger_messages {
+                if let Err(e) = self.compact().await {
+                    log::warn!("Context summarization failed, keeping full history: {e}");
+                }
+            }
+            self.inner
+                .get_messages_for_request(token_budget, provider)
+                .await
+        })
+    }
+
+    fn get_messages(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Value>, ContextError>> + Send + '_>> {
+        self.inner.get_messages()
+    }
+
+    fn set_messages(
+        &self,
+        messages: Vec<Value>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.set_messages(messages)
+    }
+
+    fn clear(&self) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.inner.clear()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeContextManager, FakeHookHandler, FakeProvider};
+    use serde_json::json;
+
+    fn history(turns: usize) -> Vec<Value> {
+        let mut messages = vec![json!({"role": "system", "content": "You are helpful."})];
+        for i in 0..turns {
+            messages.push(json!({"role": "user", "content": format!("question {i}")}));
+            messages.push(json!({"role": "assistant", "content": format!("answer {i}")}));
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn provider_summarizer_sends_transcript_and_returns_system_note() {
+        let provider = Arc::new(FakeProvider::new("fake", " They discussed Rust. "));
+        let summarizer = ProviderSummarizer::new(provider.clone())
+            .with_model("small-model")
+            .with_max_output_tokens(256);
+        let messages = vec![
+            json!({"role": "user", "content": "read main.rs"}),
+            json!({"role": "assistant", "content": [
+                {"type": "tool_call", "id": "c1", "name": "read_file", "input": {"path": "main.rs"}}
+            ]}),
+            json!({"role": "tool", "tool_call_id": "c1", "content": "fn main() {}"}),
+        ];
+
+        let summary = summarizer.summarize(&messages).await.unwrap();
+
+        assert_eq!(summary.role, Role::System);
+        assert_eq!(
+            summary.content,
+            MessageContent::Text("Summary of 3 earlier messages:\n\nThey discussed Rust.".into())
+        );
+        assert_eq!(summary.metadata.unwrap()["summarized_messages"], json!(3));
+        let request = &provider.recorded_calls()[0];
+        assert_eq!(request.model.as_deref(), Some("small-model"));
+        assert_eq!(request.max_output_tokens, Some(256));
+        assert_eq!(
+            request.messages[0].content,
+            MessageContent::Text(DEFAULT_INSTRUCTIONS.into())
+        );
+        let MessageContent::Text(transcript) = &request.messages[1].content else {
+            panic!("transcript should be text");
+        };
+        assert!(transcript.starts_with("user: read main.rs"), "{transcript}");
+        assert!(
+            transcript.contains(r#"[called read_file with {"path":"main.rs"}]"#),
+            "{transcript}"
+        );
+        assert!(transcript.ends_with("tool: fn main() {}"), "{transcript}");
+    }
+
+    #[tokio::test]
+    async fn empty_summary_is_a_compaction_failure() {
+        let summarizer = ProviderSummarizer::new(Arc::new(FakeProvider::new("fake", "  ")));
+        let err = summarizer
+            .summarize(&[json!({"role": "user", "content": "hi"})])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ContextError::CompactionFailed { .. }));
+    }
+
+    #[tokio::test]
+    async fn compact_keeps_leading_system_pinned_messages_and_tool_pairs() {
+        let summarizer = ProviderSummarizer::new(Arc::new(FakeProvider::new("fake", "gist")));
+        let mut messages = vec![
+            json!({"role": "system", "content": "You are helpful."}),
+            json!({"role": "user", "content": "one"}),
+            json!({"role": "user", "content": "remember", "metadata": {"priority": "pinned"}}),
+            json!({"role": "assistant", "content": "", "tool_calls": [
+                {"id": "c1", "function": {"name": "ls", "arguments": "{}"}}
+            ]}),
+            json!({"role": "tool", "tool_call_id": "c1", "content": "a.txt"}),
+            json!({"role": "user", "content": "latest"}),
+        ];
+
+        // Three messages would end between the call and its result.
+        let report = compact(&mut messages, 3, &summarizer)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            report,
+            CompactionReport {
+                summarized: 3,
+                kept_pinned: 1,
+                messages_before: 6,
+                messages_after: 4,
+            }
+        );
+        assert_eq!(messages[0]["content"], "You are helpful.");
+        assert_eq!(
+            messages[1]["content"],
+            "Summary of 3 earlier messages:\n\ngist"
+        );
+        assert_eq!(messages[2]["content"], "remember");
+        assert_eq!(messages[3]["content"], "latest");
+    }
+
+    #[tokio::test]
+    async fn compact_with_nothing_to_summarize_is_a_no_op() {
+        let summarizer = ProviderSummarizer::new(Arc::new(FakeProvider::new("fake", "gist")));
+        let mut messages = vec![json!({"role": "system", "content": "You are helpful."})];
+        assert_eq!(compact(&mut messages, 50, &summarizer).await.unwrap(), None);
+        assert_eq!(messages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn summarizing_context_compacts_past_the_trigger() {
+        let inner = Arc::new(FakeContextManager::new());
+        inner.set_messages(history(6)).await.unwrap();
+        let hooks = Arc::new(HookRegistry::new());
+        let pre = Arc::new(FakeHookHandler::new());
+        let post = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::CONTEXT_PRE_COMPACT, pre.clone(), 0, None);
+        let _ = hooks.register(events::CONTEXT_POST_COMPACT, post.clone(), 0, None);
+        let provider = Arc::new(FakeProvider::new("fake", "gist"));
+        let config = SummarizationConfig {
+            trigger_messages: 10,
+            summarize_oldest: 8,
+            ..SummarizationConfig::default()
+        };
+        let context = SummarizingContext::new(
+            inner.clone(),
+            Arc::new(ProviderSummarizer::new(provider.clone())),
+            config,
+            hooks,
+        );
+
+        let messages = context.get_messages_for_request(None, None).await.unwrap();
+
+        // system + summary + the two newest turns.
+        assert_eq!(messages.len(), 6);
+        assert_eq!(messages[1]["metadata"]["summary"], true);
+        assert_eq!(messages[2]["content"], "question 4");
+        assert_eq!(inner.get_messages().await.unwrap(), messages);
+        assert_eq!(pre.recorded_events()[0].1["message_count"], 13);
+        assert_eq!(post.recorded_events()[0].1["messages_after"], 6);
+
+        // Back under the trigger: no further calls.
+        context.get_messages_for_request(None, None).await.unwrap();
+        assert_eq!(provider.recorded_calls().len(), 1);
+    }
+
+    #[test]
+    fn config_from_session_section() {
+        let config = HashMap::from([(
+            "session".to_string(),
+            json!({"summarization": {"provider": "anthropic", "summarize_oldest": 20}}),
+        )]);
+        let parsed = SummarizationConfig::from_session_config(&config).unwrap();
+        assert_eq!(parsed.provider.as_deref(), Some("anthropic"));
+        assert_eq!(parsed.summarize_oldest, 20);
+        assert_eq!(parsed.trigger_messages, 100);
+
+        let malformed = HashMap::from([(
+            "session".to_string(),
+            json!({"summarization": {"oldest": 20}}),
+        )]);
+        assert!(SummarizationConfig::from_session_config(&malformed).is_none());
+    }
+}
@@ -0,0 +1,827 @@
+//! Telemetry export — OpenTelemetry spans and metrics.
+//!
+//! [`TelemetryConfig`] is read from the session config (`session.telemetry`)
+//! and is always available. The exporter itself, [`OtelTelemetry`], is built
+//! only with the `otel` feature; without it an enabled config is logged and
+//! ignored.
+//!
+//! # Configuration
+//!
+//! ```json
+//! {"session": {"telemetry": {"enabled": true, "attributes": {"deployment": "prod"}}}}
+//! ```
+//!
+//! The kernel only uses the OpenTelemetry API. Spans and metrics go to the
+//! global tracer and meter providers, so OTLP (or any other) export is set up
+//! by the host installing an SDK pipeline before creating sessions.
+//!
+//! # Spans
+//!
+//! | Span                 | Opened by                          | Closed by                                  |
+//! |----------------------|------------------------------------|--------------------------------------------|
+//! | `amplifier.session`  | `session:start` / `session:resume` | `session:end`                              |
+//! | `amplifier.turn`     | [`Session::execute`](crate::session::Session::execute) | the end of that call   |
+//! | `amplifier.provider` | `provider:request` / `provider:pre` | `provider:response` / `provider:post` / `provider:error` |
+//! | `amplifier.tool`     | `tool:pre`                         | `tool:post` / `tool:error`                 |
+//!
+//! Turns are children of the session span, provider and tool calls children
+//! of the current turn. An orchestrator that emits `provider:request` and
+//! also routes through the kernel's
+//! [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) produces a
+//! single provider span: events arriving while a provider call is open do
+//! not open another. Tool spans are matched by `tool_call_id`, falling back
+//! to `tool_name`.
+//!
+//! # Metrics
+//!
+//! | Instrument                | Kind            | Attributes                        |
+//! |---------------------------|-----------------|-----------------------------------|
+//! | `amplifier.tokens`        | counter         | `amplifier.token.type` (`input` / `output`), `amplifier.provider` |
+//! | `amplifier.tool.calls`    | counter         | `amplifier.tool.name`, `amplifier.tool.success` |
+//! | `amplifier.tool.errors`   | counter         | `amplifier.tool.name`             |
+//! | `amplifier.hook.duration` | histogram (s)   | `amplifier.event`, `amplifier.hook.handler` |
+//! | `amplifier.memory.used`   | gauge (bytes)   | `amplifier.session_id`, `amplifier.memory.category` |
+//!
+//! Tokens are counted from the `usage` of the response that closes a
+//! provider call, so the same response is never counted twice. Hook latency
+//! comes from [`HookRegistry::on_handler_timing`](crate::hooks::HookRegistry::on_handler_timing).
+//! Memory use is read from the session's
+//! [`MemoryAccountant`](crate::memory::MemoryAccountant) at each collection,
+//! one data point per category (see [`crate::memory`]).
+//! Every span and data point also carries the configured `attributes`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Telemetry export settings (`session.telemetry` in the config).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Export nothing unless set.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Record spans.
+    #[serde(default = "default_true")]
+    pub spans: bool,
+    /// Record metrics.
+    #[serde(default = "default_true")]
+    pub metrics: bool,
+    /// Extra attributes attached to every span and metric data point.
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spans: true,
+            metrics: true,
+            attributes: BTreeMap::new(),
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// Extract `session.telemetry` from a mount plan, falling back to the
+    /// default (disabled) when absent or malformed.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("telemetry")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.telemetry config: {e}");
+            Self::default()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OtelTelemetry (feature = "otel")
+// ---------------------------------------------------------------------------
+
+#[cfg(feature = "otel")]
+pub use otel::{OtelTelemetry, TurnSpan, TELEMETRY_EVENTS};
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use opentelemetry::global::{self, BoxedTracer};
+    use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge};
+    use opentelemetry::trace::{Status, TraceContextExt, Tracer};
+    use opentelemetry::{Context, InstrumentationScope, KeyValue};
+    use serde_json::Value;
+
+    use super::TelemetryConfig;
+    use crate::errors::HookError;
+    use crate::events;
+    use crate::hooks::{HookPhase, HookRegistry};
+    use crate::memory::MemoryAccountant;
+    use crate::messages::Usage;
+    use crate::models::HookResult;
+    use crate::traits::HookHandler;
+
+    /// Events the exporter observes.
+    pub const TELEMETRY_EVENTS: &[&str] = &[
+        events::SESSION_START,
+        events::SESSION_RESUME,
+        events::SESSION_END,
+        events::PROVIDER_REQUEST,
+        events::PROVIDER_PRE,
+        events::PROVIDER_RESPONSE,
+        events::PROVIDER_POST,
+        events::PROVIDER_ERROR,
+        events::TOOL_PRE,
+        events::TOOL_POST,
+        events::TOOL_ERROR,
+    ];
+
+    /// Name the exporter's hook handlers are registered under.
+    const HANDLER_NAME: &str = "telemetry";
+
+    struct Instruments {
+        tokens: Counter<u64>,
+        tool_calls: Counter<u64>,
+        tool_errors: Counter<u64>,
+        hook_duration: Histogram<f64>,
+    }
+
+    #[derive(Default)]
+    struct OpenSpans {
+        session: Option<Context>,
+        turn: Option<Context>,
+        provider: Option<(Context, String)>,
+        tools: HashMap<String, Context>,
+    }
+
+    /// Exports a session's activity as OpenTelemetry spans and metrics.
+    ///
+    /// Attach it with [`install()`](Self::install); the
+    /// [`Session`](crate::session::Session) does this itself when
+    /// `session.telemetry.enabled` is set.
+    pub struct OtelTelemetry {
+        config: TelemetryConfig,
+        tracer: BoxedTracer,
+        meter: Meter,
+        instruments: Option<Instruments>,
+        memory_gauge: Option<ObservableGauge<u64>>,
+        attributes: Vec<KeyValue>,
+        open: Mutex<OpenSpans>,
+    }
+
+    impl OtelTelemetry {
+        /// Create an exporter using the global tracer and meter providers.
+        pub fn global(config: TelemetryConfig) -> Self {
+            let scope = InstrumentationScope::builder("amplifier-core")
+                .with_version(env!("CARGO_PKG_VERSION"))
+                .build();
+            Self::new(
+                config,
+                global::tracer_with_scope(scope.clone()),
+                global::meter_with_scope(scope),
+            )
+        }
+
+        /// Create an exporter using an explicit tracer and meter.
+        pub fn new(config: TelemetryConfig, tracer: BoxedTracer, meter: Meter) -> Self {
+            let instruments = config.metrics.then(|| Instruments {
+                tokens: meter
+                    .u64_counter("amplifier.tokens")
+                    .with_description("Tokens reported by provider responses")
+                    .build(),
+                tool_calls: meter
+                    .u64_counter("amplifier.tool.calls")
+                    .with_description("Completed tool calls")
+                    .build(),
+                tool_errors: meter
+                    .u64_counter("amplifier.tool.errors")
+                    .with_description("Tool calls that failed")
+                    .build(),
+                hook_duration: meter
+                    .f64_histogram("amplifier.hook.duration")
+                    .with_description("Time spent in each hook handler call")
+                    .with_unit("s")
+                    .build(),
+            });
+            let attributes = config
+                .attributes
+                .iter()
+                .map(|(k, v)| KeyValue::new(k.clone(), v.clone()))
+                .collect();
+            Self {
+                config,
+                tracer,
+                meter,
+                instruments,
+                memory_gauge: None,
+                attributes,
+                open: Mutex::new(OpenSpans::default()),
+            }
+        }
+
+        /// Report `memory`'s usage as `amplifier.memory.used`, tagged with
+        /// `session_id`. No-op when metrics are disabled.
+        ///
+        /// The gauge holds `memory` weakly, so it stops reporting once the
+        /// session is gone.
+        pub fn with_memory(mut self, memory: &Arc<MemoryAccountant>, session_id: &str) -> Self {
+            if !self.config.metrics {
+                return self;
+            }
+            let memory = Arc::downgrade(memory);
+            let attributes = self.metric_attrs([KeyValue::new(
+                "amplifier.session_id",
+                session_id.to_string(),
+            )]);
+            let gauge = self
+                .meter
+                .u64_observable_gauge("amplifier.memory.used")
+                .with_description("Bytes held in kernel buffers, by memory category")
+                .with_unit("By")
+                .with_callback(move |observer| {
+                    let Some(memory) = memory.upgrade() else {
+                        return;
+                    };
+                    for (category, bytes) in memory.usage().by_category {
+                        let mut attrs = attributes.clone();
+                        attrs.push(KeyValue::new("amplifier.memory.category", category));
+                        observer.observe(bytes as u64, &attrs);
+                    }
+                })
+                .build();
+            self.memory_gauge = Some(gauge);
+            self
+        }
+
+        /// Register the exporter on `hooks`: an [`HookPhase::Observation`]
+        /// handler for [`TELEMETRY_EVENTS`] and a handler timing observer.
+        pub fn install(self: &Arc<Self>, hooks: &HookRegistry) {
+            for event in TELEMETRY_EVENTS {
+                let _ = hooks.register_in_phase(
+                    event,
+                    self.clone(),
+                    HookPhase::Observation,
+                    0,
+                    Some(HANDLER_NAME.into()),
+                );
+            }
+            if self.instruments.is_some() {
+                let this = Arc::clone(self);
+                hooks.on_handler_timing(Arc::new(move |event, handler, elapsed| {
+                    this.record_hook_duration(event, handler, elapsed)
+                }));
+            }
+        }
+
+        /// Open a turn span, tagged with the turn's correlation ID; it ends
+        /// when the returned guard is finished or dropped.
+        pub fn start_turn(&self, turn_id: Option<&str>) -> TurnSpan<'_> {
+            if self.config.spans {
+                let mut open = self.open.lock().unwrap();
+                let parent = open.session.clone().unwrap_or_else(Context::current);
+                let attrs = turn_id
+                    .map(|id| KeyValue::new("amplifier.turn_id", id.to_string()))
+                    .into_iter()
+                    .collect();
+                open.turn = Some(self.start_span("amplifier.turn", &parent, attrs));
+            }
+            TurnSpan {
+                telemetry: self,
+                finished: false,
+            }
+        }
+
+        fn end_turn(&self, error: Option<&str>) {
+            let Some(cx) = self.open.lock().unwrap().turn.take() else {
+                return;
+            };
+            end_span(&cx, error);
+        }
+
+        fn start_span(
+            &self,
+            name: &'static str,
+            parent: &Context,
+            attrs: Vec<KeyValue>,
+        ) -> Context {
+            let mut attributes = self.attributes.clone();
+            attributes.extend(attrs);
+            let span = self
+                .tracer
+                .span_builder(name)
+                .with_attributes(attributes)
+                .start_with_context(&self.tracer, parent);
+            parent.with_span(span)
+        }
+
+        fn metric_attrs(&self, attrs: impl IntoIterator<Item = KeyValue>) -> Vec<KeyValue> {
+            let mut all = self.attributes.clone();
+            all.extend(attrs);
+            all
+        }
+
+        fn record_hook_duration(&self, event: &str, handler: &str, elapsed: Duration) {
+            if handler == HANDLER_NAME {
+                return;
+            }
+            if let Some(instruments) = &self.instruments {
+                instruments.hook_duration.record(
+                    elapsed.as_secs_f64(),
+                    &self.metric_attrs([
+                        KeyValue::new("amplifier.event", event.to_string()),
+                        KeyValue::new("amplifier.hook.handler", handler.to_string()),
+                    ]),
+                );
+            }
+        }
+
+        fn record(&self, event: &str, data: &Value) {
+            let mut open = self.open.lock().unwrap();
+            match event {
+                events::SESSION_START | events::SESSION_RESUME => {
+                    if !self.config.spans || open.session.is_some() {
+                        return;
+                    }
+                    let mut attrs = vec![
+                        KeyValue::new("amplifier.session_id", str_field(data, "session_id")),
+                        KeyValue::new("amplifier.resumed", event == events::SESSION_RESUME),
+                    ];
+                    if let Some(parent_id) = data.get("parent_id").and_then(Value::as_str) {
+                        attrs.push(KeyValue::new("amplifier.parent_id", parent_id.to_string()));
+                    }
+                    open.session =
+                        Some(self.start_span("amplifier.session", &Context::current(), attrs));
+                }
+                events::SESSION_END => {
+                    let Some(cx) = open.session.take() else {
+                        return;
+                    };
+                    let status = str_field(data, "status");
+                    cx.span()
+                        .set_attribute(KeyValue::new("amplifier.status", status.clone()));
+                    end_span(&cx, (status == "failed").then_some("session failed"));
+                }
+                events::PROVIDER_REQUEST | events::PROVIDER_PRE => {
+                    if open.provider.is_some() {
+                        return;
+                    }
+                    let provider = str_field(data, "provider");
+                    // The call is tracked even without spans so its tokens
+                    // are counted once.
+                    let cx = if self.config.spans {
+                        let parent = open
+                            .turn
+                            .clone()
+                            .or_else(|| open.session.clone())
+                            .unwrap_or_else(Context::current);
+                        self.start_span(
+                            "amplifier.provider",
+                            &parent,
+                            vec![KeyValue::new("amplifier.provider", provider.clone())],
+                        )
+                    } else {
+                        Context::new()
+                    };
+                    open.provider = Some((cx, provider));
+                }
+                events::PROVIDER_RESPONSE | events::PROVIDER_POST | events::PROVIDER_ERROR => {
+                    let Some((cx, provider)) = open.provider.take() else {
+                        return;
+                    };
+                    if event == events::PROVIDER_ERROR {
+                        end_span(&cx, Some(&error_text(data)));
+                        return;
+                    }
+                    let usage = data
+                        .get("response")
+                        .and_then(|r| r.get("usage"))
+                        .and_then(|u| serde_json::from_value::<Usage>(u.clone()).ok());
+                    if let Some(usage) = &usage {
+                        let span = cx.span();
+                        span.set_attribute(KeyValue::new(
+                            "amplifier.tokens.input",
+                            usage.input_tokens,
+                        ));
+                        span.set_attribute(KeyValue::new(
+                            "amplifier.tokens.output",
+                            usage.output_tokens,
+                        ));
+                        self.count_tokens(&provider, usage);
+                    }
+                    end_span(&cx, None);
+                }
+                events::TOOL_PRE => {
+                    if !self.config.spans {
+                        return;
+                    }
+                    let parent = open
+                        .turn
+                        .clone()
+                        .or_else(|| open.session.clone())
+                        .unwrap_or_else(Context::current);
+                    let mut attrs = vec![KeyValue::new(
+                        "amplifier.tool.name",
+                        str_field(data, "tool_name"),
+                    )];
+                    if let Some(id) = data.get("tool_call_id").and_then(Value::as_str) {
+                        attrs.push(KeyValue::new("amplifier.tool.call_id", id.to_string()));
+                    }
+                    let cx = self.start_span("amplifier.tool", &parent, attrs);
+                    open.tools.insert(tool_key(data), cx);
+                }
+                events::TOOL_POST | events::TOOL_ERROR => {
+                    let success = event == events::TOOL_POST;
+                    let tool_name = str_field(data, "tool_name");
+                    if let Some(instruments) = &self.instruments {
+                        let name = KeyValue::new("amplifier.tool.name", tool_name.clone());
+                        instruments.tool_calls.add(
+                            1,
+                            &self.metric_attrs([
+                                name.clone(),
+                                KeyValue::new("amplifier.tool.success", success),
+                            ]),
+                        );
+                        if !success {
+                            instruments.tool_errors.add(1, &self.metric_attrs([name]));
+                        }
+                    }
+                    if let Some(cx) = open.tools.remove(&tool_key(data)) {
+                        let error = (!success).then(|| error_text(data));
+                        end_span(&cx, error.as_deref());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        fn count_tokens(&self, provider: &str, usage: &Usage) {
+            let Some(instruments) = &self.instruments else {
+                return;
+            };
+            for (kind, count) in [
+                ("input", usage.input_tokens),
+                ("output", usage.output_tokens),
+            ] {
+                if count > 0 {
+                    instruments.tokens.add(
+                        count as u64,
+                        &self.metric_attrs([
+                            KeyValue::new("amplifier.token.type", kind),
+                            KeyValue::new("amplifier.provider", provider.to_string()),
+                        ]),
+                    );
+                }
+            }
+        }
+    }
+
+    impl HookHandler for OtelTelemetry {
+        fn handle(
+            &self,
+            event: &str,
+            data: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+            self.record(event, &data);
+            Box::pin(async { Ok(HookResult::default()) })
+        }
+    }
+
+    /// Guard for an open `amplifier.turn` span.
+    ///
+    /// Dropping it without [`finish()`](Self::finish) ends the span as
+    /// successful.
+    pub struct TurnSpan<'a> {
+        telemetry: &'a OtelTelemetry,
+        finished: bool,
+    }
+
+    impl TurnSpan<'_> {
+        /// End the span, marking it failed when `error` is set.
+        pub fn finish(mut self, error: Option<&str>) {
+            self.finished = true;
+            self.telemetry.end_turn(error);
+        }
+    }
+
+    impl Drop for TurnSpan<'_> {
+        fn drop(&mut self) {
+            if !self.finished {
+                self.telemetry.end_turn(None);
+            }
+        }
+    }
+
+    fn end_span(cx: &Context, error: Option<&str>) {
+        let span = cx.span();
+        if let Some(message) = error {
+            span.set_status(Status::error(message.to_string()));
+        }
+        span.end();
+    }
+
+    fn str_field(data: &Value, key: &str) -> String {
+        data.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn error_text(data: &Value) -> String {
+        match data.get("error") {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => "error".to_string(),
+        }
+    }
+
+    fn tool_key(data: &Value) -> String {
+        data.get("tool_call_id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| str_field(data, "tool_name"))
+    }
+
+    // -----------------------------------------------------------------------
+    // Tests
+    // -----------------------------------------------------------------------
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use opentelemetry::metrics::MeterProvider as _;
+        use opentelemetry::trace::{SpanKind, TracerProvider as _};
+        use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+        use opentelemetry_sdk::metrics::{
+            InMemoryMetricExporter, PeriodicReader, SdkMeterProvider,
+        };
+        use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+        use serde_json::json;
+
+        struct Harness {
+            telemetry: Arc<OtelTelemetry>,
+            hooks: HookRegistry,
+            spans: InMemorySpanExporter,
+            metrics: InMemoryMetricExporter,
+            meter_provider: SdkMeterProvider,
+            _tracer_provider: SdkTracerProvider,
+        }
+
+        fn harness(config: TelemetryConfig) -> Harness {
+            let spans = InMemorySpanExporter::default();
+            let tracer_provider = SdkTracerProvider::builder()
+                .with_simple_exporter(spans.clone())
+                .build();
+            let metrics = InMemoryMetricExporter::default();
+            let meter_provider = SdkMeterProvider::builder()
+                .with_reader(PeriodicReader::builder(metrics.clone()).build())
+                .build();
+            let telemetry = Arc::new(OtelTelemetry::new(
+                config,
+                BoxedTracer::new(Box::new(tracer_provider.tracer("test"))),
+                meter_provider.meter("test"),
+            ));
+            let hooks = HookRegistry::new();
+            telemetry.install(&hooks);
+            Harness {
+                telemetry,
+                hooks,
+                spans,
+                metrics,
+                meter_provider,
+                _tracer_provider: tracer_provider,
+            }
+        }
+
+        fn enabled() -> TelemetryConfig {
+            TelemetryConfig {
+                enabled: true,
+                attributes: [("deployment".to_string(), "test".to_string())].into(),
+                ..Default::default()
+            }
+        }
+
+        /// Latest `(category, bytes)` points of the memory gauge.
+        fn memory_points(metrics: &InMemoryMetricExporter) -> Vec<(String, u64)> {
+            let finished = metrics.get_finished_metrics().unwrap();
+            let Some(resource) = finished.last() else {
+                return Vec::new();
+            };
+            let mut points = Vec::new();
+            for scope in resource.scope_metrics() {
+                for metric in scope
+                    .metrics()
+                    .filter(|m| m.name() == "amplifier.memory.used")
+                {
+                    if let AggregatedMetrics::U64(MetricData::Gauge(gauge)) = metric.data() {
+                        for point in gauge.data_points() {
+                            let category = point
+                                .attributes()
+                                .find(|kv| kv.key.as_str() == "amplifier.memory.category")
+                                .map(|kv| kv.value.to_string())
+                                .unwrap();
+                            points.push((category, point.value()));
+                        }
+                    }
+                }
+            }
+            points.sort();
+            points
+        }
+
+        fn counter_total(metrics: &InMemoryMetricExporter, name: &str) -> u64 {
+            let mut total = 0;
+            for resource in metrics.get_finished_metrics().unwrap() {
+                for scope in resource.scope_metrics() {
+                    for metric in scope.metrics().filter(|m| m.name() == name) {
+                        if let AggregatedMetrics::U64(MetricData::Sum(sum)) = metric.data() {
+                            total += sum.data_points().map(|p| p.value()).sum::<u64>();
+                        }
+                    }
+                }
+            }
+            total
+        }
+
+        async fn run_turn(h: &Harness) {
+            h.hooks
+                .emit(events::SESSION_START, json!({"session_id": "s1"}))
+                .await;
+            let turn = h.telemetry.start_turn(Some("t1"));
+            h.hooks
+                .emit(events::PROVIDER_REQUEST, json!({"provider": "mock"}))
+                .await;
+            h.hooks
+                .emit(
+                    events::PROVIDER_PRE,
+                    json!({"provider": "mock", "request": {}}),
+                )
+                .await;
+            let response =
+                json!({"usage": {"input_tokens": 10, "output_tokens": 4, "total_tokens": 14}});
+            h.hooks
+                .emit(
+                    events::PROVIDER_POST,
+                    json!({"provider": "mock", "response": response}),
+                )
+                .await;
+            h.hooks
+                .emit(
+                    events::PROVIDER_RESPONSE,
+                    json!({"provider": "mock", "response": response}),
+                )
+                .await;
+            h.hooks
+                .emit(
+                    events::TOOL_PRE,
+                    json!({"tool_name": "bash", "tool_call_id": "c1"}),
+                )
+                .await;
+            h.hooks
+                .emit(
+                    events::TOOL_ERROR,
+                    json!({"tool_name": "bash", "tool_call_id": "c1", "error": "exit 1"}),
+                )
+                .await;
+            turn.finish(None);
+            h.hooks
+                .emit(
+                    events::SESSION_END,
+                    json!({"session_id": "s1", "status": "completed"}),
+                )
+                .await;
+        }
+
+        #[tokio::test]
+        async fn turn_produces_nested_spans() {
+            let h = harness(enabled());
+            run_turn(&h).await;
+
+            let spans = h.spans.get_finished_spans().unwrap();
+            let names: Vec<_> = spans.iter().map(|s| s.name.as_ref()).collect();
+            assert_eq!(
+                names,
+                vec![
+                    "amplifier.provider",
+                    "amplifier.tool",
+                    "amplifier.turn",
+                    "amplifier.session"
+                ]
+            );
+            let by_name = |name: &str| spans.iter().find(|s| s.name == name).unwrap();
+            let session = by_name("amplifier.session");
+            let turn = by_name("amplifier.turn");
+            assert_eq!(turn.parent_span_id, session.span_context.span_id());
+            assert_eq!(
+                by_name("amplifier.tool").parent_span_id,
+                turn.span_context.span_id()
+            );
+            assert_eq!(by_name("amplifier.tool").status, Status::error("exit 1"));
+            assert_eq!(session.span_kind, SpanKind::Internal);
+            assert!(session
+                .attributes
+                .contains(&KeyValue::new("deployment", "test")));
+        }
+
+        #[tokio::test]
+        async fn metrics_count_tokens_once_and_tool_errors() {
+            let h = harness(enabled());
+            run_turn(&h).await;
+            h.meter_provider.force_flush().unwrap();
+
+            // provider:post closes the span; provider:response is not double counted.
+            assert_eq!(counter_total(&h.metrics, "amplifier.tokens"), 14);
+            assert_eq!(counter_total(&h.metrics, "amplifier.tool.calls"), 1);
+            assert_eq!(counter_total(&h.metrics, "amplifier.tool.errors"), 1);
+        }
+
+        #[test]
+        fn memory_use_is_exported_per_category() {
+            use crate::memory::{BoundedBuffer, MemoryConfig};
+
+            let metrics = InMemoryMetricExporter::default();
+            let meter_provider = SdkMeterProvider::builder()
+                .with_reader(PeriodicReader::builder(metrics.clone()).build())
+                .build();
+            let memory = Arc::new(MemoryAccountant::new(MemoryConfig::default()));
+            let _telemetry = OtelTelemetry::new(
+                enabled(),
+                BoxedTracer::new(Box::new(
+                    SdkTracerProvider::builder().build().tracer("test"),
+                )),
+                meter_provider.meter("test"),
+            )
+            .with_memory(&memory, "s1");
+
+            let mut buffer = BoundedBuffer::new("hook_replay", Arc::clone(&memory));
+            buffer.push((), 40);
+            assert!(memory.try_charge("attachments", 100));
+            meter_provider.force_flush().unwrap();
+            assert_eq!(
+                memory_points(&metrics),
+                vec![
+                    ("attachments".to_string(), 100),
+                    ("hook_replay".to_string(), 40)
+                ]
+            );
+
+            drop(buffer);
+            meter_provider.force_flush().unwrap();
+            assert!(memory_points(&metrics).contains(&("hook_replay".to_string(), 0)));
+        }
+
+        #[tokio::test]
+        async fn spans_can_be_disabled() {
+            let h = harness(TelemetryConfig {
+                spans: false,
+                ..enabled()
+            });
+            run_turn(&h).await;
+            h.meter_provider.force_flush().unwrap();
+
+            assert!(h.spans.get_finished_spans().unwrap().is_empty());
+            assert_eq!(counter_total(&h.metrics, "amplifier.tokens"), 14);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn config_parses_from_session_section() {
+        let plan: HashMap<String, Value> = [(
+            "session".to_string(),
+            json!({"telemetry": {"enabled": true, "metrics": false, "attributes": {"team": "a"}}}),
+        )]
+        .into();
+        let cfg = TelemetryConfig::from_session_config(&plan);
+        assert!(cfg.enabled);
+        assert!(cfg.spans);
+        assert!(!cfg.metrics);
+        assert_eq!(cfg.attributes["team"], "a");
+        assert_eq!(
+            TelemetryConfig::from_session_config(&Default::default()),
+            TelemetryConfig::default()
+        );
+    }
+}
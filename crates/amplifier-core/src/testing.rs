@@ -16,21 +16,37 @@
 //! # Connections
 //!
 //! All fakes implement the corresponding trait from [`crate::traits`].
+//! [`diff_transcripts`] compares recorded runs against golden transcripts.
+//! [`FlakyProvider`], [`SlowTool`] and [`TimeoutHookHandler`] inject
+//! failures and latency (optionally randomized by a seeded [`ChaosConfig`])
+//! for exercising retry, timeout and cancellation paths.
 //! They are used by kernel-internal tests (hooks, coordinator, session)
 //! and by downstream crate tests via the `testing` module re-export.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::sync::watch;
 
+use crate::cancellation::CancellationToken;
+use crate::clock::Clock;
 use crate::errors::{AmplifierError, ContextError, HookError, ProviderError, ToolError};
-use crate::messages::{ChatRequest, ChatResponse, ContentBlock, ToolCall, ToolSpec};
+use crate::messages::{ChatRequest, ChatResponse, ContentBlock, ToolCall, ToolSpec, Usage};
 use crate::models::{HookResult, ModelInfo, ProviderInfo, ToolResult};
 use crate::traits::{
-    ApprovalProvider, ContextManager, DisplayService, HookHandler, Orchestrator, Provider, Tool,
+    ApprovalProvider, ContextManager, DisplayService, HookHandler, Moderator, Orchestrator,
+    Provider, Tool,
 };
 
 // ---------------------------------------------------------------------------
@@ -82,6 +98,7 @@ impl Tool for EchoTool {
                 success: true,
                 output: Some(input),
                 error: None,
+                ..Default::default()
             })
         })
     }
@@ -169,6 +186,7 @@ impl Tool for FakeTool {
                     success: true,
                     output: Some(input),
                     error: None,
+                    ..Default::default()
                 }
             } else {
                 responses.remove(0)
@@ -187,8 +205,13 @@ pub struct FakeProvider {
     provider_name: String,
     /// Text content returned by `complete`.
     response_text: String,
+    /// Returned by the first calls, ahead of `response_text`.
+    queued_texts: Mutex<VecDeque<String>>,
     /// Records every request passed to `complete`.
     calls: Mutex<Vec<ChatRequest>>,
+    /// Returned by `list_models`.
+    models: Vec<ModelInfo>,
+    list_models_calls: AtomicUsize,
 }
 
 impl FakeProvider {
@@ -197,14 +220,42 @@ impl FakeProvider {
         Self {
             provider_name: name.into(),
             response_text: response_text.into(),
+            queued_texts: Mutex::new(VecDeque::new()),
             calls: Mutex::new(Vec::new()),
+            models: Vec::new(),
+            list_models_calls: AtomicUsize::new(0),
         }
     }
 
+    /// Return `models` from `list_models` (default: none).
+    pub fn with_models(mut self, models: Vec<ModelInfo>) -> Self {
+        self.models = models;
+        self
+    }
+
+    /// Return `texts` from the first calls, one per call, before falling
+    /// back to the configured response text.
+    pub fn with_responses<I, S>(self, texts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.queued_texts
+            .lock()
+            .unwrap()
+            .extend(texts.into_iter().map(Into::into));
+        self
+    }
+
     /// Return a clone of all recorded requests.
     pub fn recorded_calls(&self) -> Vec<ChatRequest> {
         self.calls.lock().unwrap().clone()
     }
+
+    /// How many times `list_models` was called.
+    pub fn list_models_calls(&self) -> usize {
+        self.list_models_calls.load(Ordering::SeqCst)
+    }
 }
 
 impl Provider for FakeProvider {
@@ -226,7 +277,9 @@ impl Provider for FakeProvider {
     fn list_models(
         &self,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<ModelInfo>, ProviderError>> + Send + '_>> {
-        Box::pin(async { Ok(Vec::new()) })
+        self.list_models_calls.fetch_add(1, Ordering::SeqCst);
+        let models = self.models.clone();
+        Box::pin(async move { Ok(models) })
     }
 
     fn complete(
@@ -234,12 +287,18 @@ impl Provider for FakeProvider {
         request: ChatRequest,
     ) -> Pin<Box<dyn Future<Output = Result<ChatResponse, ProviderError>> + Send + '_>> {
         self.calls.lock().unwrap().push(request);
-        let text = self.response_text.clone();
+        let text = self
+            .queued_texts
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| self.response_text.clone());
         Box::pin(async move {
             Ok(ChatResponse {
                 content: vec![ContentBlock::Text {
                     text,
                     visibility: None,
+                    cache: None,
                     extensions: HashMap::new(),
                 }],
                 tool_calls: None,
@@ -410,12 +469,15 @@ impl Orchestrator for FakeOrchestrator {
 // CapturingOrchestrator
 // ---------------------------------------------------------------------------
 
-/// An orchestrator that captures the hooks and coordinator values passed to it.
+/// An orchestrator that captures the prompt, hooks and coordinator values
+/// passed to it.
 ///
 /// Returns a pre-configured response (like `FakeOrchestrator`) but also
-/// stores the `hooks` and `coordinator` `Value` arguments for test assertions.
+/// stores the `prompt`, `hooks` and `coordinator` arguments for test
+/// assertions.
 pub struct CapturingOrchestrator {
     response: String,
+    last_prompt: Mutex<Option<String>>,
     last_hooks: Mutex<Value>,
     last_coordinator: Mutex<Value>,
 }
@@ -425,11 +487,17 @@ impl CapturingOrchestrator {
     pub fn new(response: &str) -> Self {
         Self {
             response: response.into(),
+            last_prompt: Mutex::new(None),
             last_hooks: Mutex::new(Value::Null),
             last_coordinator: Mutex::new(Value::Null),
         }
     }
 
+    /// The last prompt passed to `execute()`, if it has been called.
+    pub fn last_prompt(&self) -> Option<String> {
+        self.last_prompt.lock().unwrap().clone()
+    }
+
     /// The last `hooks` value passed to `execute()`.
     pub fn last_hooks_value(&self) -> Value {
         self.last_hooks.lock().unwrap().clone()
@@ -444,13 +512,14 @@ impl CapturingOrchestrator {
 impl Orchestrator for CapturingOrchestrator {
     fn execute(
         &self,
-        _prompt: String,
+        prompt: String,
         _context: Arc<dyn ContextManager>,
         _providers: HashMap<String, Arc<dyn Provider>>,
         _tools: HashMap<String, Arc<dyn Tool>>,
         hooks: Value,
         coordinator: Value,
     ) -> Pin<Box<dyn Future<Output = Result<String, AmplifierError>> + Send + '_>> {
+        *self.last_prompt.lock().unwrap() = Some(prompt);
         *self.last_hooks.lock().unwrap() = hooks;
         *self.last_coordinator.lock().unwrap() = coordinator;
         let resp = self.response.clone();
@@ -465,17 +534,37 @@ impl Orchestrator for CapturingOrchestrator {
 /// A fake approval provider that auto-approves or auto-denies.
 pub struct FakeApprovalProvider {
     approved: bool,
+    remember: bool,
+    calls: AtomicUsize,
 }
 
 impl FakeApprovalProvider {
     /// Create a provider that always approves.
     pub fn approving() -> Self {
-        Self { approved: true }
+        Self {
+            approved: true,
+            remember: false,
+            calls: AtomicUsize::new(0),
+        }
     }
 
     /// Create a provider that always denies.
     pub fn denying() -> Self {
-        Self { approved: false }
+        Self {
+            approved: false,
+            ..Self::approving()
+        }
+    }
+
+    /// Set `remember` on every response.
+    pub fn remembering(mut self) -> Self {
+        self.remember = true;
+        self
+    }
+
+    /// How many requests were answered.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
     }
 }
 
@@ -490,15 +579,68 @@ impl ApprovalProvider for FakeApprovalProvider {
                 + '_,
         >,
     > {
+        self.calls.fetch_add(1, Ordering::SeqCst);
         let response = crate::models::ApprovalResponse {
             approved: self.approved,
             reason: None,
-            remember: false,
+            remember: self.remember,
         };
         Box::pin(async move { Ok(response) })
     }
 }
 
+// ---------------------------------------------------------------------------
+// FakeModerator
+// ---------------------------------------------------------------------------
+
+/// A fake moderator that flags content containing any of its terms, under
+/// the category `"test"`, and records what it was asked to classify.
+pub struct FakeModerator {
+    terms: Vec<String>,
+    moderated: Mutex<Vec<String>>,
+}
+
+impl FakeModerator {
+    /// Create a moderator that flags content containing any of `terms`.
+    pub fn flagging(terms: &[&str]) -> Self {
+        Self {
+            terms: terms.iter().map(|t| t.to_string()).collect(),
+            moderated: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Every content string classified so far.
+    pub fn moderated(&self) -> Vec<String> {
+        self.moderated.lock().unwrap().clone()
+    }
+}
+
+impl Moderator for FakeModerator {
+    fn moderate(
+        &self,
+        content: &str,
+    ) -> Pin<
+        Box<
+            dyn Future<Output = Result<crate::models::ModerationResult, AmplifierError>>
+                + Send
+                + '_,
+        >,
+    > {
+        self.moderated.lock().unwrap().push(content.to_string());
+        let flagged = self.terms.iter().any(|t| content.contains(t.as_str()));
+        let result = crate::models::ModerationResult {
+            flagged,
+            categories: if flagged {
+                vec!["test".into()]
+            } else {
+                Vec::new()
+            },
+            reason: None,
+        };
+        Box::pin(async move { Ok(result) })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // FakeDisplayService
 // ---------------------------------------------------------------------------
@@ -542,6 +684,940 @@ impl DisplayService for FakeDisplayService {
     }
 }
 
+// ---------------------------------------------------------------------------
+// ManualClock
+// ---------------------------------------------------------------------------
+
+/// A [`Clock`] that only moves when told to.
+///
+/// Timestamps and timers are derived from a fixed start plus the elapsed time
+/// set by [`advance`](Self::advance); sleeps complete as soon as the clock has
+/// been advanced far enough. Cloning shares the same time.
+#[derive(Clone)]
+pub struct ManualClock {
+    start_utc: DateTime<Utc>,
+    start: Instant,
+    elapsed: Arc<watch::Sender<Duration>>,
+}
+
+impl ManualClock {
+    /// A clock reading `start_utc`, with nothing elapsed.
+    pub fn new(start_utc: DateTime<Utc>) -> Self {
+        Self {
+            start_utc,
+            start: Instant::now(),
+            elapsed: Arc::new(watch::channel(Duration::ZERO).0),
+        }
+    }
+
+    /// Move the clock forward by `by`, waking any sleeps that are now due.
+    pub fn advance(&self, by: Duration) {
+        self.elapsed.send_modify(|elapsed| *elapsed += by);
+    }
+
+    /// Total time advanced since creation.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.borrow()
+    }
+}
+
+impl Default for ManualClock {
+    /// Starts at `2025-01-01T00:00:00Z`.
+    fn default() -> Self {
+        Self::new(DateTime::from_timestamp(1_735_689_600, 0).expect("valid timestamp"))
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.start_utc + self.elapsed()
+    }
+
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> {
+        let due = self.elapsed() + duration;
+        let mut rx = self.elapsed.subscribe();
+        Box::pin(async move {
+            // If every clock handle is dropped, time never advances again.
+            if rx.wait_for(|elapsed| *elapsed >= due).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Failure injection
+// ---------------------------------------------------------------------------
+
+/// Seeded randomness for the failure-injecting fakes.
+///
+/// Every fake built from the same config makes the same sequence of
+/// decisions, so a failing chaos test reproduces by re-running with its seed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChaosConfig {
+    /// Seed for the random number generator.
+    pub seed: u64,
+    /// Probability (0.0–1.0) that a call fails.
+    pub failure_rate: f64,
+    /// Lower bound of the extra latency added to each call.
+    pub min_latency: Duration,
+    /// Upper bound of the extra latency added to each call.
+    pub max_latency: Duration,
+}
+
+impl ChaosConfig {
+    /// No failures and no latency, seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_failure_rate(mut self, rate: f64) -> Self {
+        self.failure_rate = rate;
+        self
+    }
+
+    /// Add a uniformly random latency in `min..=max` to each call.
+    pub fn with_latency(mut self, min: Duration, max: Duration) -> Self {
+        self.min_latency = min;
+        self.max_latency = max.max(min);
+        self
+    }
+}
+
+/// The random source behind a [`ChaosConfig`].
+#[derive(Debug)]
+pub struct Chaos {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Self {
+        Self {
+            rng: Mutex::new(StdRng::seed_from_u64(config.seed)),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    /// Decide whether the next call fails.
+    pub fn should_fail(&self) -> bool {
+        let rate = self.config.failure_rate.clamp(0.0, 1.0);
+        self.rng.lock().unwrap().gen_bool(rate)
+    }
+
+    /// Draw the extra latency for the next call.
+    pub fn latency(&self) -> Duration {
+        let ChaosConfig {
+            min_latency,
+            max_latency,
+            ..
+        } = self.config;
+        if max_latency <= min_latency {
+            return min_latency;
+        }
+        self.rng
+            .lock()
+            .unwrap()
+            .gen_range(min_latency..=max_latency)
+    }
+}
+
+impl From<ChaosConfig> for Chaos {
+    fn from(config: ChaosConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+/// A provider that fails its first `failures` calls, then behaves like
+/// [`FakeProvider`].
+///
+/// Each call waits the configured latency on the provider's [`Clock`] before
+/// answering. With [`with_chaos`](Self::with_chaos), calls after the scripted
+/// failures also fail at random and get extra random latency.
+///
+/// # Usage
+///
+/// ```rust
+/// use amplifier_core::testing::FlakyProvider;
+///
+/// let provider = FlakyProvider::new("flaky", "ok", 2);
+/// assert_eq!(provider.attempts(), 0);
+/// ```
+pub struct FlakyProvider {
+    inner: FakeProvider,
+    failures: usize,
+    latency: Duration,
+    error: Arc<dyn Fn(&str) -> ProviderError + Send + Sync>,
+    chaos: Option<Chaos>,
+    clock: Arc<dyn Clock>,
+    attempts: AtomicUsize,
+}
+
+impl FlakyProvider {
+    /// Fail the first `failures` calls with a retryable
+    /// [`ProviderError::Unavailable`], then return `response_text`.
+    pub fn new(name: &str, response_text: &str, failures: usize) -> Self {
+        Self {
+            inner: FakeProvider::new(name, response_text),
+            failures,
+            latency: Duration::ZERO,
+            error: Arc::new(|provider| ProviderError::Unavailable {
+                message: "injected provider failure".into(),
+                provider: Some(provider.to_string()),
+                model: None,
+                retry_after: None,
+                status_code: Some(503),
+                delay_multiplier: None,
+            }),
+            chaos: None,
+            clock: crate::clock::system(),
+            attempts: AtomicUsize::new(0),
+        }
+    }
+
+    /// Build the injected error with `error(provider_name)` instead.
+    pub fn with_error(
+        mut self,
+        error: impl Fn(&str) -> ProviderError + Send + Sync + 'static,
+    ) -> Self {
+        self.error = Arc::new(error);
+        self
+    }
+
+    /// Wait `latency` before every answer, failed or not.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_chaos(mut self, config: ChaosConfig) -> Self {
+        self.chaos = Some(Chaos::new(config));
+        self
+    }
+
+    /// Measure latency on `clock` (e.g. a [`ManualClock`]).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Number of `complete` calls so far, failed or not.
+    pub fn attempts(&self) -> usize {
+        self.attempts.load(Ordering::SeqCst)
+    }
+
+    /// Requests from the calls that succeeded.
+    pub fn recorded_calls(&self) -> Vec<ChatRequest> {
+        self.inner.recorded_calls()
+    }
+}
+
+impl Provider for FlakyProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn get_info(&self) -> ProviderInfo {
+        self.inner.get_info()
+    }
+
+    fn list_models(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<ModelInfo>, ProviderError>> + Send + '_>> {
+        self.inner.list_models()
+    }
+
+    fn complete(
+        &self,
+        request: ChatRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<ChatResponse, ProviderError>> + Send + '_>> {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst);
+        let mut fail = attempt < self.failures;
+        let mut latency = self.latency;
+        if let Some(chaos) = &self.chaos {
+            fail |= chaos.should_fail();
+            latency += chaos.latency();
+        }
+        let sleep = self.clock.sleep(latency);
+        Box::pin(async move {
+            sleep.await;
+            if fail {
+                return Err((self.error)(self.inner.name()));
+            }
+            self.inner.complete(request).await
+        })
+    }
+
+    fn parse_tool_calls(&self, response: &ChatResponse) -> Vec<ToolCall> {
+        self.inner.parse_tool_calls(response)
+    }
+}
+
+/// A tool that takes `delay` to finish and stops early when cancelled.
+///
+/// The delay runs on the tool's [`Clock`]. If a [`CancellationToken`] is
+/// attached, any cancellation request (graceful or immediate) ends the call
+/// with a failed [`ToolResult`]. Calls whose future is dropped mid-delay
+/// (deadlines, `select!`) count as started but neither completed nor
+/// cancelled.
+pub struct SlowTool {
+    tool_name: String,
+    delay: Duration,
+    clock: Arc<dyn Clock>,
+    cancellation: Option<CancellationToken>,
+    chaos: Option<Chaos>,
+    started: AtomicUsize,
+    completed: AtomicUsize,
+    cancelled: AtomicUsize,
+}
+
+impl SlowTool {
+    pub fn new(name: &str, delay: Duration) -> Self {
+        Self {
+            tool_name: name.into(),
+            delay,
+            clock: crate::clock::system(),
+            cancellation: None,
+            chaos: None,
+            started: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            cancelled: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Stop early when `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Add random latency and fail calls (with [`ToolError::ExecutionFailed`])
+    /// at random.
+    pub fn with_chaos(mut self, config: ChaosConfig) -> Self {
+        self.chaos = Some(Chaos::new(config));
+        self
+    }
+
+    pub fn started(&self) -> usize {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    /// Calls that ran their full delay.
+    pub fn completed(&self) -> usize {
+        self.completed.load(Ordering::SeqCst)
+    }
+
+    /// Calls that stopped early because of cancellation.
+    pub fn cancelled(&self) -> usize {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Tool for SlowTool {
+    fn name(&self) -> &str {
+        &self.tool_name
+    }
+
+    fn description(&self) -> &str {
+        "Waits before returning its input"
+    }
+
+    fn get_spec(&self) -> ToolSpec {
+        ToolSpec {
+            name: self.tool_name.clone(),
+            parameters: HashMap::new(),
+            description: Some(self.description().into()),
+            extensions: HashMap::new(),
+        }
+    }
+
+    fn execute(
+        &self,
+        input: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        self.started.fetch_add(1, Ordering::SeqCst);
+        let mut delay = self.delay;
+        let mut fail = false;
+        if let Some(chaos) = &self.chaos {
+            fail = chaos.should_fail();
+            delay += chaos.latency();
+        }
+        let sleep = self.clock.sleep(delay);
+        Box::pin(async move {
+            let finished = match &self.cancellation {
+                Some(token) => tokio::select! {
+                    _ = sleep => true,
+                    _ = token.cancelled() => false,
+                },
+                None => {
+                    sleep.await;
+                    true
+                }
+            };
+            if !finished {
+                self.cancelled.fetch_add(1, Ordering::SeqCst);
+                return Ok(ToolResult {
+                    success: false,
+                    output: None,
+                    error: Some(HashMap::from([(
+                        "message".to_string(),
+                        Value::from("cancelled"),
+                    )])),
+                    ..Default::default()
+                });
+            }
+            self.completed.fetch_add(1, Ordering::SeqCst);
+            if fail {
+                return Err(ToolError::ExecutionFailed {
+                    message: "injected tool failure".into(),
+                    stdout: None,
+                    stderr: None,
+                    exit_code: None,
+                });
+            }
+            Ok(ToolResult {
+                success: true,
+                output: Some(input),
+                error: None,
+                ..Default::default()
+            })
+        })
+    }
+}
+
+/// A hook handler that takes too long, for exercising handler timeouts.
+///
+/// [`new`](Self::new) answers after `delay` on the handler's [`Clock`];
+/// [`hanging`](Self::hanging) never answers.
+pub struct TimeoutHookHandler {
+    delay: Option<Duration>,
+    result: HookResult,
+    clock: Arc<dyn Clock>,
+    calls: AtomicUsize,
+    completed: AtomicUsize,
+}
+
+impl TimeoutHookHandler {
+    /// Return `Continue` after `delay`.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay: Some(delay),
+            result: HookResult::default(),
+            clock: crate::clock::system(),
+            calls: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+        }
+    }
+
+    /// Never return.
+    pub fn hanging() -> Self {
+        Self {
+            delay: None,
+            ..Self::new(Duration::ZERO)
+        }
+    }
+
+    /// Return `result` once the delay has passed.
+    pub fn with_result(mut self, result: HookResult) -> Self {
+        self.result = result;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// Calls that waited out their delay and returned.
+    pub fn completed(&self) -> usize {
+        self.completed.load(Ordering::SeqCst)
+    }
+}
+
+impl HookHandler for TimeoutHookHandler {
+    fn handle(
+        &self,
+        _event: &str,
+        _data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let sleep = self.delay.map(|delay| self.clock.sleep(delay));
+        Box::pin(async move {
+            match sleep {
+                Some(sleep) => sleep.await,
+                None => std::future::pending::<()>().await,
+            }
+            self.completed.fetch_add(1, Ordering::SeqCst);
+            Ok(self.result.clone())
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Transcript diffing
+// ---------------------------------------------------------------------------
+
+/// A canonical transcript of one run: the context messages, the hook events
+/// emitted (in order, as recorded by [`FakeHookHandler::recorded_events`]),
+/// and the aggregate token usage.
+///
+/// Serializable so golden transcripts can be checked in as JSON files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    #[serde(default)]
+    pub messages: Vec<Value>,
+    #[serde(default)]
+    pub events: Vec<(String, Value)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+}
+
+impl Transcript {
+    /// A transcript containing only messages.
+    pub fn new(messages: Vec<Value>) -> Self {
+        Self {
+            messages,
+            ..Default::default()
+        }
+    }
+
+    /// Attach the recorded hook events.
+    pub fn with_events(mut self, events: Vec<(String, Value)>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Attach the aggregate token usage.
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+}
+
+/// Ignore rules for [`diff_transcripts_with`].
+///
+/// Ignored keys are stripped from every JSON object (at any depth) in
+/// messages and event payloads before comparison.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptDiffOptions {
+    ignore_keys: BTreeSet<String>,
+}
+
+impl TranscriptDiffOptions {
+    /// Compare everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ignore the keys that differ between otherwise identical runs:
+    /// `timestamp`, `id`, `session_id`, `parent_id`.
+    pub fn ignoring_volatile() -> Self {
+        ["timestamp", "id", "session_id", "parent_id"]
+            .into_iter()
+            .fold(Self::new(), Self::ignore_key)
+    }
+
+    /// Ignore `key` wherever it appears.
+    pub fn ignore_key(mut self, key: impl Into<String>) -> Self {
+        self.ignore_keys.insert(key.into());
+        self
+    }
+
+    fn normalize(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter(|(k, _)| !self.ignore_keys.contains(k.as_str()))
+                    .map(|(k, v)| (k.clone(), self.normalize(v)))
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.normalize(v)).collect()),
+            other => other.clone(),
+        }
+    }
+}
+
+/// One differing leaf between two JSON values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Dotted path to the field, e.g. `content[0].text`. Empty for the root.
+    pub path: String,
+    /// Value in the first transcript (`None` if absent).
+    pub before: Option<Value>,
+    /// Value in the second transcript (`None` if absent).
+    pub after: Option<Value>,
+}
+
+/// A single difference between two transcripts.
+///
+/// Indices refer to positions in transcript `a` (removed), `b` (added), or
+/// both (changed).
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptChange {
+    MessageAdded {
+        index: usize,
+        message: Value,
+    },
+    MessageRemoved {
+        index: usize,
+        message: Value,
+    },
+    MessageChanged {
+        index_a: usize,
+        index_b: usize,
+        fields: Vec<FieldChange>,
+    },
+    EventAdded {
+        index: usize,
+        name: String,
+    },
+    EventRemoved {
+        index: usize,
+        name: String,
+    },
+    EventChanged {
+        index_a: usize,
+        index_b: usize,
+        name: String,
+        fields: Vec<FieldChange>,
+    },
+    UsageChanged {
+        field: &'static str,
+        before: i64,
+        after: i64,
+    },
+}
+
+/// The structured result of [`diff_transcripts`].
+///
+/// `Display` renders one line per difference (`-` removed, `+` added,
+/// `~` changed), suitable for test failure messages.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptDiff {
+    pub changes: Vec<TranscriptChange>,
+}
+
+impl TranscriptDiff {
+    /// `true` if the transcripts are equivalent under the ignore rules.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+fn fmt_field(f: &mut fmt::Formatter<'_>, prefix: &str, field: &FieldChange) -> fmt::Result {
+    let show = |v: &Option<Value>| match v {
+        Some(v) => v.to_string(),
+        None => "<absent>".to_string(),
+    };
+    let path = if field.path.is_empty() {
+        "<root>"
+    } else {
+        &field.path
+    };
+    writeln!(
+        f,
+        "~ {prefix}: {path}: {} -> {}",
+        show(&field.before),
+        show(&field.after)
+    )
+}
+
+impl fmt::Display for TranscriptDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.changes.is_empty() {
+            return writeln!(f, "transcripts are identical");
+        }
+        for change in &self.changes {
+            match change {
+                TranscriptChange::MessageAdded { index, message } => {
+                    writeln!(f, "+ message[{index}]: {message}")?
+                }
+                TranscriptChange::MessageRemoved { index, message } => {
+                    writeln!(f, "- message[{index}]: {message}")?
+                }
+                TranscriptChange::MessageChanged {
+                    index_a,
+                    index_b,
+                    fields,
+                } => {
+                    let prefix = if index_a == index_b {
+                        format!("message[{index_a}]")
+                    } else {
+                        format!("message[{index_a} -> {index_b}]")
+                    };
+                    for field in fields {
+                        fmt_field(f, &prefix, field)?;
+                    }
+                }
+                TranscriptChange::EventAdded { index, name } => {
+                    writeln!(f, "+ event[{index}]: {name}")?
+                }
+                TranscriptChange::EventRemoved { index, name } => {
+                    writeln!(f, "- event[{index}]: {name}")?
+                }
+                TranscriptChange::EventChanged {
+                    index_a,
+                    index_b,
+                    name,
+                    fields,
+                } => {
+                    let prefix = if index_a == index_b {
+                        format!("event[{index_a}] {name}")
+                    } else {
+                        format!("event[{index_a} -> {index_b}] {name}")
+                    };
+                    for field in fields {
+                        fmt_field(f, &prefix, field)?;
+                    }
+                }
+                TranscriptChange::UsageChanged {
+                    field,
+                    before,
+                    after,
+                } => writeln!(
+                    f,
+                    "~ usage.{field}: {before} -> {after} ({:+})",
+                    after - before
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Diff two transcripts, comparing everything. See [`diff_transcripts_with`].
+pub fn diff_transcripts(a: &Transcript, b: &Transcript) -> TranscriptDiff {
+    diff_transcripts_with(a, b, &TranscriptDiffOptions::new())
+}
+
+/// Diff two transcripts under the given ignore rules.
+///
+/// Messages and events are aligned by longest common subsequence, so an
+/// inserted message shows up as one addition rather than a cascade of
+/// changes. Within a run of unmatched entries, removals and additions are
+/// paired positionally as changes (events only when their names match).
+///
+/// # Example
+///
+/// ```rust
+/// use amplifier_core::testing::{diff_transcripts_with, Transcript, TranscriptDiffOptions};
+/// use serde_json::json;
+///
+/// let golden = Transcript::new(vec![json!({"role": "user", "content": "hi", "timestamp": 1})]);
+/// let actual = Transcript::new(vec![json!({"role": "user", "content": "hi", "timestamp": 2})]);
+/// let diff = diff_transcripts_with(&golden, &actual, &TranscriptDiffOptions::ignoring_volatile());
+/// assert!(diff.is_empty(), "{diff}");
+/// ```
+pub fn diff_transcripts_with(
+    a: &Transcript,
+    b: &Transcript,
+    options: &TranscriptDiffOptions,
+) -> TranscriptDiff {
+    let mut changes = Vec::new();
+
+    // -- messages --
+    let msgs_a: Vec<Value> = a.messages.iter().map(|m| options.normalize(m)).collect();
+    let msgs_b: Vec<Value> = b.messages.iter().map(|m| options.normalize(m)).collect();
+    for step in align(&msgs_a, &msgs_b, |_, _| true) {
+        match step {
+            Step::Removed(i) => changes.push(TranscriptChange::MessageRemoved {
+                index: i,
+                message: a.messages[i].clone(),
+            }),
+            Step::Added(j) => changes.push(TranscriptChange::MessageAdded {
+                index: j,
+                message: b.messages[j].clone(),
+            }),
+            Step::Changed(i, j) => changes.push(TranscriptChange::MessageChanged {
+                index_a: i,
+                index_b: j,
+                fields: diff_values(&msgs_a[i], &msgs_b[j]),
+            }),
+        }
+    }
+
+    // -- events --
+    let events_a: Vec<Value> = a
+        .events
+        .iter()
+        .map(|(n, d)| serde_json::json!([n, options.normalize(d)]))
+        .collect();
+    let events_b: Vec<Value> = b
+        .events
+        .iter()
+        .map(|(n, d)| serde_json::json!([n, options.normalize(d)]))
+        .collect();
+    for step in align(&events_a, &events_b, |x, y| x[0] == y[0]) {
+        match step {
+            Step::Removed(i) => changes.push(TranscriptChange::EventRemoved {
+                index: i,
+                name: a.events[i].0.clone(),
+            }),
+            Step::Added(j) => changes.push(TranscriptChange::EventAdded {
+                index: j,
+                name: b.events[j].0.clone(),
+            }),
+            Step::Changed(i, j) => changes.push(TranscriptChange::EventChanged {
+                index_a: i,
+                index_b: j,
+                name: a.events[i].0.clone(),
+                fields: diff_values(&events_a[i][1], &events_b[j][1]),
+            }),
+        }
+    }
+
+    // -- usage --
+    if a.usage.is_some() || b.usage.is_some() {
+        let ua = usage_fields(a.usage.as_ref());
+        let ub = usage_fields(b.usage.as_ref());
+        for ((field, before), (_, after)) in ua.into_iter().zip(ub) {
+            if before != after {
+                changes.push(TranscriptChange::UsageChanged {
+                    field,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
+    TranscriptDiff { changes }
+}
+
+/// Panic with a readable diff unless `actual` matches `golden`.
+pub fn assert_transcript_matches(
+    golden: &Transcript,
+    actual: &Transcript,
+    options: &TranscriptDiffOptions,
+) {
+    let diff = diff_transcripts_with(golden, actual, options);
+    assert!(diff.is_empty(), "transcript differs from golden:\n{diff}");
+}
+
+/// Usage counters by name; absent usage and absent optional counters read as 0.
+fn usage_fields(usage: Option<&Usage>) -> [(&'static str, i64); 6] {
+    let opt = |f: fn(&Usage) -> Option<i64>| usage.and_then(f).unwrap_or(0);
+    [
+        ("input_tokens", usage.map_or(0, |u| u.input_tokens)),
+        ("output_tokens", usage.map_or(0, |u| u.output_tokens)),
+        ("total_tokens", usage.map_or(0, |u| u.total_tokens)),
+        ("reasoning_tokens", opt(|u| u.reasoning_tokens)),
+        ("cache_read_tokens", opt(|u| u.cache_read_tokens)),
+        ("cache_write_tokens", opt(|u| u.cache_write_tokens)),
+    ]
+}
+
+/// An edit step from sequence `a` to sequence `b` (matches are omitted).
+enum Step {
+    Removed(usize),
+    Added(usize),
+    Changed(usize, usize),
+}
+
+/// LCS alignment of `a` and `b`. Unmatched runs are paired into
+/// [`Step::Changed`] where `pairable` allows it.
+fn align(a: &[Value], b: &[Value], pairable: impl Fn(&Value, &Value) -> bool) -> Vec<Step> {
+    let (n, m) = (a.len(), b.len());
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut steps = Vec::new();
+    let (mut removed, mut added) = (Vec::new(), Vec::new());
+    let flush = |steps: &mut Vec<Step>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        let mut added_iter = std::mem::take(added).into_iter().peekable();
+        for i in removed.drain(..) {
+            match added_iter.peek() {
+                Some(&j) if pairable(&a[i], &b[j]) => {
+                    added_iter.next();
+                    steps.push(Step::Changed(i, j));
+                }
+                _ => steps.push(Step::Removed(i)),
+            }
+        }
+        steps.extend(added_iter.map(Step::Added));
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            flush(&mut steps, &mut removed, &mut added);
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            added.push(j);
+            j += 1;
+        } else {
+            removed.push(i);
+            i += 1;
+        }
+    }
+    flush(&mut steps, &mut removed, &mut added);
+    steps
+}
+
+/// Leaf-level differences between two JSON values.
+fn diff_values(a: &Value, b: &Value) -> Vec<FieldChange> {
+    let mut out = Vec::new();
+    diff_into(String::new(), Some(a), Some(b), &mut out);
+    out
+}
+
+fn diff_into(path: String, a: Option<&Value>, b: Option<&Value>, out: &mut Vec<FieldChange>) {
+    match (a, b) {
+        (Some(Value::Object(ma)), Some(Value::Object(mb))) => {
+            let keys: BTreeSet<&String> = ma.keys().chain(mb.keys()).collect();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_into(child, ma.get(key), mb.get(key), out);
+            }
+        }
+        (Some(Value::Array(va)), Some(Value::Array(vb))) => {
+            for idx in 0..va.len().max(vb.len()) {
+                diff_into(format!("{path}[{idx}]"), va.get(idx), vb.get(idx), out);
+            }
+        }
+        (x, y) if x != y => out.push(FieldChange {
+            path,
+            before: x.cloned(),
+            after: y.cloned(),
+        }),
+        _ => {}
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -551,6 +1627,25 @@ mod tests {
     use super::*;
     use std::sync::Arc;
 
+    #[tokio::test]
+    async fn manual_clock_sleep_completes_on_advance() {
+        let clock = ManualClock::default();
+        let start = clock.now_utc();
+        let mut sleep = clock.sleep(Duration::from_secs(5));
+        assert!(tokio::time::timeout(Duration::from_millis(10), &mut sleep)
+            .await
+            .is_err());
+
+        clock.advance(Duration::from_secs(3));
+        assert!(tokio::time::timeout(Duration::from_millis(10), &mut sleep)
+            .await
+            .is_err());
+
+        clock.advance(Duration::from_secs(2));
+        sleep.await;
+        assert_eq!(clock.now_utc() - start, chrono::Duration::seconds(5));
+    }
+
     #[tokio::test]
     async fn fake_tool_returns_success() {
         let tool = FakeTool::new("echo", "echoes input");
@@ -571,11 +1666,13 @@ mod tests {
                     success: true,
                     output: Some(serde_json::json!("first")),
                     error: None,
+                    ..Default::default()
                 },
                 crate::models::ToolResult {
                     success: false,
                     output: None,
                     error: None,
+                    ..Default::default()
                 },
             ],
         );
@@ -614,6 +1711,7 @@ mod tests {
                 name: None,
                 tool_call_id: None,
                 metadata: None,
+                cache: None,
                 extensions: Default::default(),
             }],
             tools: None,
@@ -743,4 +1841,209 @@ mod tests {
     fn fake_orchestrator_is_arc_compatible() {
         let _orch: Arc<dyn Orchestrator> = Arc::new(FakeOrchestrator::new("ok"));
     }
+
+    // ---------------------------------------------------------------
+    // Transcript diffing
+    // ---------------------------------------------------------------
+
+    fn user(text: &str) -> Value {
+        serde_json::json!({"role": "user", "content": text})
+    }
+
+    #[test]
+    fn identical_transcripts_have_empty_diff() {
+        let t = Transcript::new(vec![user("a"), user("b")])
+            .with_events(vec![("tool:pre".into(), serde_json::json!({"x": 1}))]);
+        let diff = diff_transcripts(&t, &t.clone());
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "transcripts are identical\n");
+    }
+
+    #[test]
+    fn inserted_message_is_a_single_addition() {
+        let a = Transcript::new(vec![user("a"), user("c")]);
+        let b = Transcript::new(vec![user("a"), user("b"), user("c")]);
+        let diff = diff_transcripts(&a, &b);
+        assert_eq!(
+            diff.changes,
+            vec![TranscriptChange::MessageAdded {
+                index: 1,
+                message: user("b")
+            }]
+        );
+    }
+
+    #[test]
+    fn changed_message_reports_field_paths() {
+        let a = Transcript::new(vec![
+            serde_json::json!({"role": "assistant", "content": [{"text": "x"}]}),
+        ]);
+        let b = Transcript::new(vec![
+            serde_json::json!({"role": "assistant", "content": [{"text": "y"}]}),
+        ]);
+        let diff = diff_transcripts(&a, &b);
+        let TranscriptChange::MessageChanged { fields, .. } = &diff.changes[0] else {
+            panic!("expected MessageChanged, got {diff:?}");
+        };
+        assert_eq!(fields[0].path, "content[0].text");
+        assert!(diff
+            .to_string()
+            .contains(r#"~ message[0]: content[0].text: "x" -> "y""#));
+    }
+
+    #[test]
+    fn ignore_rules_strip_volatile_keys() {
+        let a = Transcript::new(vec![
+            serde_json::json!({"role": "user", "content": "a", "id": "1", "meta": {"timestamp": 10}}),
+        ]);
+        let b = Transcript::new(vec![
+            serde_json::json!({"role": "user", "content": "a", "id": "2", "meta": {"timestamp": 20}}),
+        ]);
+        assert!(!diff_transcripts(&a, &b).is_empty());
+        assert_transcript_matches(&a, &b, &TranscriptDiffOptions::ignoring_volatile());
+    }
+
+    #[test]
+    fn event_sequence_differences() {
+        let a = Transcript::default().with_events(vec![
+            ("provider:request".into(), serde_json::json!({})),
+            ("tool:pre".into(), serde_json::json!({"tool": "a"})),
+        ]);
+        let b = Transcript::default().with_events(vec![
+            ("provider:request".into(), serde_json::json!({})),
+            ("tool:pre".into(), serde_json::json!({"tool": "b"})),
+            ("tool:post".into(), serde_json::json!({})),
+        ]);
+        let diff = diff_transcripts(&a, &b);
+        assert!(matches!(
+            &diff.changes[0],
+            TranscriptChange::EventChanged { name, .. } if name == "tool:pre"
+        ));
+        assert!(matches!(
+            &diff.changes[1],
+            TranscriptChange::EventAdded { index: 2, name } if name == "tool:post"
+        ));
+    }
+
+    #[test]
+    fn usage_deltas() {
+        let usage = |total| Usage {
+            input_tokens: 5,
+            output_tokens: total - 5,
+            total_tokens: total,
+            reasoning_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            cost_usd: None,
+            extensions: HashMap::new(),
+        };
+        let a = Transcript::default().with_usage(usage(10));
+        let b = Transcript::default().with_usage(usage(12));
+        let diff = diff_transcripts(&a, &b);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff
+            .to_string()
+            .contains("~ usage.total_tokens: 10 -> 12 (+2)"));
+    }
+
+    fn empty_request() -> ChatRequest {
+        serde_json::from_value(serde_json::json!({"messages": []})).unwrap()
+    }
+
+    #[tokio::test]
+    async fn flaky_provider_fails_then_succeeds() {
+        let provider = FlakyProvider::new("flaky", "ok", 2);
+        for _ in 0..2 {
+            let err = provider.complete(empty_request()).await.unwrap_err();
+            assert!(err.retryable());
+        }
+        assert!(provider.complete(empty_request()).await.is_ok());
+        assert_eq!(provider.attempts(), 3);
+        assert_eq!(provider.recorded_calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn flaky_provider_latency_uses_its_clock() {
+        let clock = ManualClock::default();
+        let provider = FlakyProvider::new("flaky", "ok", 0)
+            .with_latency(Duration::from_secs(5))
+            .with_clock(Arc::new(clock.clone()));
+        let mut call = provider.complete(empty_request());
+        assert!(tokio::time::timeout(Duration::from_millis(10), &mut call)
+            .await
+            .is_err());
+        clock.advance(Duration::from_secs(5));
+        assert!(call.await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn chaos_is_reproducible_from_its_seed() {
+        let outcomes = |seed| async move {
+            let provider = FlakyProvider::new("chaos", "ok", 0)
+                .with_chaos(ChaosConfig::new(seed).with_failure_rate(0.5));
+            let mut outcomes = Vec::new();
+            for _ in 0..20 {
+                outcomes.push(provider.complete(empty_request()).await.is_ok());
+            }
+            outcomes
+        };
+        let first = outcomes(7).await;
+        assert_eq!(first, outcomes(7).await);
+        assert!(first.contains(&true) && first.contains(&false));
+
+        let chaos = Chaos::new(
+            ChaosConfig::new(1).with_latency(Duration::from_millis(10), Duration::from_millis(20)),
+        );
+        let latency = chaos.latency();
+        assert!(latency >= Duration::from_millis(10) && latency <= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn slow_tool_stops_when_cancelled() {
+        let clock = ManualClock::default();
+        let token = CancellationToken::new();
+        let tool = SlowTool::new("slow", Duration::from_secs(30))
+            .with_clock(Arc::new(clock.clone()))
+            .with_cancellation(token.clone());
+
+        let call = tool.execute(serde_json::json!("x"));
+        token.request_graceful();
+        let result = call.await.unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            (tool.started(), tool.cancelled(), tool.completed()),
+            (1, 1, 0)
+        );
+
+        token.reset();
+        let call = tool.execute(serde_json::json!("x"));
+        clock.advance(Duration::from_secs(30));
+        assert!(call.await.unwrap().success);
+        assert_eq!(tool.completed(), 1);
+    }
+
+    #[tokio::test]
+    async fn timeout_hook_handler_trips_handler_timeouts() {
+        let registry = crate::hooks::HookRegistry::new();
+        let handler = Arc::new(TimeoutHookHandler::hanging());
+        let _ = registry.register("test:event", handler.clone(), 0, None);
+        let results = registry
+            .emit_and_collect(
+                "test:event",
+                serde_json::json!({}),
+                Duration::from_millis(20),
+            )
+            .await;
+        assert!(results.is_empty());
+        assert_eq!((handler.calls(), handler.completed()), (1, 0));
+    }
+
+    #[test]
+    fn transcript_round_trips_through_json() {
+        let t = Transcript::new(vec![user("a")])
+            .with_events(vec![("session:start".into(), serde_json::json!({}))]);
+        let json = serde_json::to_string(&t).unwrap();
+        let back: Transcript = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, t);
+    }
 }
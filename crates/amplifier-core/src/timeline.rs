@@ -0,0 +1,303 @@
+//! Session timeline — ordered record of lifecycle milestones.
+//!
+//! The kernel records milestones on the session's [`Timeline`] as they
+//! happen, so hosts can show what a session did and how long it took without
+//! reassembling it from hook logs:
+//!
+//! | Milestone                | Recorded by                                            | Duration        |
+//! |--------------------------|--------------------------------------------------------|-----------------|
+//! | `created`                | [`Session::new`](crate::session::Session::new)         | —               |
+//! | `initialized`            | [`Session::set_initialized`](crate::session::Session::set_initialized) | — |
+//! | `turn_started`           | [`Session::execute`](crate::session::Session::execute) | —               |
+//! | `turn_ended`             | `execute` returning (or being dropped at a deadline)   | the turn        |
+//! | `cancellation_requested` | the coordinator's cancellation token                   | —               |
+//! | `cleanup_started`        | [`Session::cleanup`](crate::session::Session::cleanup) | —               |
+//! | `cleanup_finished`       | `cleanup` returning                                    | the cleanup     |
+//!
+//! Timestamps and durations come from the coordinator's
+//! [`Clock`](crate::clock::Clock), so a manual test clock makes the timeline
+//! deterministic.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cancellation::CancellationState;
+use crate::checkpoint::CheckpointId;
+use crate::clock::Clock;
+use crate::models::SessionState;
+
+/// A lifecycle milestone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Milestone {
+    Created,
+    Initialized,
+    /// Turns are numbered from 1 within the session.
+    TurnStarted {
+        turn: u64,
+    },
+    TurnEnded {
+        turn: u64,
+        state: SessionState,
+    },
+    /// Cancellation was requested (`graceful`) or escalated (`immediate`).
+    CancellationRequested {
+        state: CancellationState,
+    },
+    /// The conversation was rewound to a checkpoint taken at `turn_number`.
+    Rewound {
+        checkpoint: CheckpointId,
+        turn_number: u64,
+    },
+    CleanupStarted,
+    CleanupFinished,
+}
+
+impl Milestone {
+    /// The serialized `kind` tag (e.g. `"turn_ended"`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Initialized => "initialized",
+            Self::TurnStarted { .. } => "turn_started",
+            Self::TurnEnded { .. } => "turn_ended",
+            Self::CancellationRequested { .. } => "cancellation_requested",
+            Self::Rewound { .. } => "rewound",
+            Self::CleanupStarted => "cleanup_started",
+            Self::CleanupFinished => "cleanup_finished",
+        }
+    }
+}
+
+/// One recorded milestone.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    #[serde(flatten)]
+    pub milestone: Milestone,
+    pub timestamp: DateTime<Utc>,
+    /// How long the phase this milestone closes took (turns and cleanup).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+impl TimelineEntry {
+    /// [`duration_ms`](Self::duration_ms) as a `Duration`.
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::from_millis)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Timeline
+// ---------------------------------------------------------------------------
+
+/// Append-only, ordered list of a session's milestones.
+#[derive(Debug, Default)]
+pub struct Timeline {
+    entries: Mutex<Vec<TimelineEntry>>,
+}
+
+impl Timeline {
+    /// An empty timeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `milestone`, stamped with `clock`'s current time.
+    pub fn record(&self, clock: &dyn Clock, milestone: Milestone, duration: Option<Duration>) {
+        self.entries.lock().unwrap().push(TimelineEntry {
+            milestone,
+            timestamp: clock.now_utc(),
+            duration_ms: duration.map(|d| d.as_millis() as u64),
+        });
+    }
+
+    /// Record `turn_started` for the next turn. The returned span records
+    /// `turn_ended` when finished — or, if dropped unfinished (the turn was
+    /// abandoned), with [`SessionState::Failed`].
+    pub fn start_turn(self: &Arc<Self>, clock: Arc<dyn Clock>) -> TurnSpan {
+        let turn = {
+            let mut entries = self.entries.lock().unwrap();
+            let turn = 1 + entries
+                .iter()
+                .filter(|e| matches!(e.milestone, Milestone::TurnStarted { .. }))
+                .count() as u64;
+            entries.push(TimelineEntry {
+                milestone: Milestone::TurnStarted { turn },
+                timestamp: clock.now_utc(),
+                duration_ms: None,
+            });
+            turn
+        };
+        TurnSpan {
+            timeline: Arc::clone(self),
+            started: clock.now(),
+            clock,
+            turn,
+            finished: false,
+        }
+    }
+
+    /// Every entry, oldest first.
+    pub fn entries(&self) -> Vec<TimelineEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Entries whose milestone has the given [`kind`](Milestone::kind).
+    pub fn of_kind(&self, kind: &str) -> Vec<TimelineEntry> {
+        self.filter(|m| m.kind() == kind)
+    }
+
+    /// Entries whose milestone matches `predicate`.
+    pub fn filter(&self, predicate: impl Fn(&Milestone) -> bool) -> Vec<TimelineEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| predicate(&e.milestone))
+            .cloned()
+            .collect()
+    }
+
+    /// The most recent entry of the given kind.
+    pub fn last(&self, kind: &str) -> Option<TimelineEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|e| e.milestone.kind() == kind)
+            .cloned()
+    }
+
+    /// Number of turns started.
+    pub fn turn_count(&self) -> u64 {
+        self.of_kind("turn_started").len() as u64
+    }
+
+    /// Number of recorded entries.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An open turn, returned by [`Timeline::start_turn`].
+pub struct TurnSpan {
+    timeline: Arc<Timeline>,
+    clock: Arc<dyn Clock>,
+    started: Instant,
+    turn: u64,
+    finished: bool,
+}
+
+impl TurnSpan {
+    /// The turn number.
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Record `turn_ended` with the session's resulting state.
+    pub fn finish(mut self, state: SessionState) {
+        self.end(state);
+    }
+
+    fn end(&mut self, state: SessionState) {
+        self.finished = true;
+        let duration = self.clock.now().saturating_duration_since(self.started);
+        self.timeline.record(
+            self.clock.as_ref(),
+            Milestone::TurnEnded {
+                turn: self.turn,
+                state,
+            },
+            Some(duration),
+        );
+    }
+}
+
+impl Drop for TurnSpan {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.end(SessionState::Failed);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ManualClock;
+
+    #[test]
+    fn turn_span_records_duration() {
+        let timeline = Arc::new(Timeline::new());
+        let clock = ManualClock::default();
+        timeline.record(&clock, Milestone::Created, None);
+
+        let span = timeline.start_turn(Arc::new(clock.clone()));
+        clock.advance(Duration::from_millis(250));
+        span.finish(SessionState::Completed);
+
+        let ended = timeline.last("turn_ended").unwrap();
+        assert_eq!(
+            ended.milestone,
+            Milestone::TurnEnded {
+                turn: 1,
+                state: SessionState::Completed
+            }
+        );
+        assert_eq!(ended.duration(), Some(Duration::from_millis(250)));
+        assert_eq!(
+            ended.timestamp.to_rfc3339(),
+            "2025-01-01T00:00:00.250+00:00"
+        );
+        assert_eq!(timeline.turn_count(), 1);
+    }
+
+    #[test]
+    fn dropped_span_records_failed_turn() {
+        let timeline = Arc::new(Timeline::new());
+        drop(timeline.start_turn(Arc::new(ManualClock::default())));
+        let second = timeline.start_turn(Arc::new(ManualClock::default()));
+        assert_eq!(second.turn(), 2);
+        drop(second);
+
+        let ended = timeline.of_kind("turn_ended");
+        assert_eq!(ended.len(), 2);
+        assert!(matches!(
+            ended[0].milestone,
+            Milestone::TurnEnded {
+                state: SessionState::Failed,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn entries_serialize_with_kind_tag() {
+        let timeline = Timeline::new();
+        timeline.record(
+            &ManualClock::default(),
+            Milestone::CancellationRequested {
+                state: CancellationState::Graceful,
+            },
+            None,
+        );
+        let json = serde_json::to_value(timeline.entries()).unwrap();
+        assert_eq!(json[0]["kind"], "cancellation_requested");
+        assert_eq!(json[0]["state"], "graceful");
+        assert!(json[0].get("duration_ms").is_none());
+    }
+}
@@ -0,0 +1,525 @@
+//! Token counting for context-window math.
+//!
+//! Every place the kernel estimates token usage — the context budget
+//! ([`ContextBudget`]) and provider request conformance
+//! ([`crate::request_conformance`]) — goes through a [`TokenCounter`], so
+//! the numbers agree with each other. The coordinator holds the session's
+//! counter ([`Coordinator::token_counter`](crate::coordinator::Coordinator::token_counter)).
+//!
+//! | Counter                       | Accuracy                              | Availability          |
+//! |-------------------------------|---------------------------------------|-----------------------|
+//! | [`HeuristicTokenCounter`]     | ~4 characters per token, any model    | always (the default)  |
+//! | `TiktokenCounter`             | exact for OpenAI models, close for others | `tiktoken` feature |
+//!
+//! Counters only need to implement [`TokenCounter::count_text`]; blocks,
+//! messages and requests are counted from their text, plus a fixed
+//! per-message overhead and a flat estimate per image.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::messages::{ChatRequest, ContentBlock, Message, MessageContent, Role};
+use crate::models::{MessagePriority, ModelInfo};
+
+/// Tokens charged per message for role and framing.
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Flat estimate for one image block.
+pub const IMAGE_TOKENS: usize = 1_600;
+
+/// Flat estimate for an audio block without a transcript.
+pub const AUDIO_TOKENS: usize = 1_000;
+
+/// Flat estimate for a document block without a transcript.
+pub const DOCUMENT_TOKENS: usize = 3_000;
+
+/// Counts tokens for a model.
+///
+/// `model` is a [`ModelInfo::id`]; it may be empty when the model is unknown.
+pub trait TokenCounter: Send + Sync {
+    /// Tokens in `text`.
+    fn count_text(&self, model: &str, text: &str) -> usize;
+
+    /// Tokens in one content block.
+    fn count_block(&self, model: &str, block: &ContentBlock) -> usize {
+        match block {
+            ContentBlock::Text { text, .. } => self.count_text(model, text),
+            ContentBlock::Thinking { thinking, .. } => self.count_text(model, thinking),
+            ContentBlock::RedactedThinking { data, .. } => self.count_text(model, data),
+            ContentBlock::ToolCall { name, input, .. } => {
+                self.count_text(model, name) + self.count_text(model, &json_text(input))
+            }
+            ContentBlock::ToolResult { output, .. } => match output {
+                Value::String(text) => self.count_text(model, text),
+                other => self.count_text(model, &other.to_string()),
+            },
+            ContentBlock::Image { .. } => IMAGE_TOKENS,
+            ContentBlock::Audio { transcript, .. } => transcript
+                .as_deref()
+                .map_or(AUDIO_TOKENS, |text| self.count_text(model, text)),
+            ContentBlock::Document { transcript, .. } => transcript
+                .as_deref()
+                .map_or(DOCUMENT_TOKENS, |text| self.count_text(model, text)),
+            ContentBlock::Reasoning {
+                content, summary, ..
+            } => {
+                self.count_text(model, &json_text(content))
+                    + self.count_text(model, &json_text(summary))
+            }
+        }
+    }
+
+    /// Tokens in one message, including [`MESSAGE_OVERHEAD_TOKENS`].
+    fn count_message(&self, model: &str, message: &Message) -> usize {
+        let content = match &message.content {
+            MessageContent::Text(text) => self.count_text(model, text),
+            MessageContent::Blocks(blocks) => {
+                blocks.iter().map(|b| self.count_block(model, b)).sum()
+            }
+        };
+        let name = message
+            .name
+            .as_deref()
+            .map_or(0, |name| self.count_text(model, name));
+        MESSAGE_OVERHEAD_TOKENS + content + name
+    }
+
+    /// Tokens in `messages`.
+    fn count_messages(&self, model: &str, messages: &[Message]) -> usize {
+        messages.iter().map(|m| self.count_message(model, m)).sum()
+    }
+
+    /// Input tokens for a whole request: messages plus tool definitions.
+    fn count_request(&self, request: &ChatRequest) -> usize {
+        let model = request.model.as_deref().unwrap_or_default();
+        let tools: usize = request
+            .tools
+            .iter()
+            .flatten()
+            .map(|tool| self.count_text(model, &json_text(tool)))
+            .sum();
+        self.count_messages(model, &request.messages) + tools
+    }
+}
+
+fn json_text(value: &impl serde::Serialize) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// HeuristicTokenCounter
+// ---------------------------------------------------------------------------
+
+/// Character-ratio estimate that works for any model.
+///
+/// Rounds up, so non-empty text is never counted as zero tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeuristicTokenCounter {
+    chars_per_token: f64,
+}
+
+impl HeuristicTokenCounter {
+    /// Estimate one token per `chars_per_token` characters.
+    ///
+    /// Non-positive or non-finite ratios fall back to the default of 4.
+    pub fn new(chars_per_token: f64) -> Self {
+        let chars_per_token = if chars_per_token.is_finite() && chars_per_token > 0.0 {
+            chars_per_token
+        } else {
+            Self::default().chars_per_token
+        };
+        Self { chars_per_token }
+    }
+}
+
+impl Default for HeuristicTokenCounter {
+    fn default() -> Self {
+        Self {
+            chars_per_token: 4.0,
+        }
+    }
+}
+
+impl TokenCounter for HeuristicTokenCounter {
+    fn count_text(&self, _model: &str, text: &str) -> usize {
+        (text.chars().count() as f64 / self.chars_per_token).ceil() as usize
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TiktokenCounter
+// ---------------------------------------------------------------------------
+
+/// BPE counts using OpenAI's tokenizers.
+///
+/// The encoding is picked from the model id; ids tiktoken does not know
+/// (including other vendors' models) use the fallback encoding, `o200k_base`
+/// unless set with [`with_fallback`](Self::with_fallback).
+#[cfg(feature = "tiktoken")]
+#[derive(Debug, Clone, Copy)]
+pub struct TiktokenCounter {
+    fallback: tiktoken_rs::tokenizer::Tokenizer,
+}
+
+#[cfg(feature = "tiktoken")]
+impl TiktokenCounter {
+    /// Counter with the `o200k_base` fallback.
+    pub fn new() -> Self {
+        Self {
+            fallback: tiktoken_rs::tokenizer::Tokenizer::O200kBase,
+        }
+    }
+
+    /// Use `fallback` for models tiktoken does not recognise.
+    pub fn with_fallback(mut self, fallback: tiktoken_rs::tokenizer::Tokenizer) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    fn encoding(&self, model: &str) -> &'static tiktoken_rs::CoreBPE {
+        use tiktoken_rs::tokenizer::Tokenizer;
+        match tiktoken_rs::tokenizer::get_tokenizer(model).unwrap_or(self.fallback) {
+            Tokenizer::O200kBase => tiktoken_rs::o200k_base_singleton(),
+            Tokenizer::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+            Tokenizer::P50kBase => tiktoken_rs::p50k_base_singleton(),
+            Tokenizer::P50kEdit => tiktoken_rs::p50k_edit_singleton(),
+            Tokenizer::R50kBase | Tokenizer::Gpt2 => tiktoken_rs::r50k_base_singleton(),
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl Default for TiktokenCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+impl TokenCounter for TiktokenCounter {
+    fn count_text(&self, model: &str, text: &str) -> usize {
+        self.encoding(model).encode_ordinary(text).len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ContextBudget
+// ---------------------------------------------------------------------------
+
+/// Computes and enforces the input-token budget for a request.
+///
+/// The budget is `context_window − max_output_tokens − safety_margin`, the
+/// formula context managers apply in
+/// [`ContextManager::get_messages_for_request`](crate::traits::ContextManager::get_messages_for_request).
+#[derive(Clone)]
+pub struct ContextBudget {
+    counter: Arc<dyn TokenCounter>,
+    safety_margin: usize,
+}
+
+impl ContextBudget {
+    /// Budget using `counter`, with no safety margin.
+    pub fn new(counter: Arc<dyn TokenCounter>) -> Self {
+        Self {
+            counter,
+            safety_margin: 0,
+        }
+    }
+
+    /// Reserve `tokens` of headroom for counting error.
+    pub fn with_safety_margin(mut self, tokens: usize) -> Self {
+        self.safety_margin = tokens;
+        self
+    }
+
+    /// The counter used for estimates.
+    pub fn counter(&self) -> &Arc<dyn TokenCounter> {
+        &self.counter
+    }
+
+    /// Input tokens available for `model` when the response may use up to
+    /// `max_output_tokens` (the model's maximum if `None`).
+    pub fn available(&self, model: &ModelInfo, max_output_tokens: Option<i64>) -> usize {
+        let output = max_output_tokens
+            .unwrap_or(model.max_output_tokens)
+            .clamp(0, model.max_output_tokens.max(0));
+        let window = model.context_window.max(0) - output;
+        (window.max(0) as usize).saturating_sub(self.safety_margin)
+    }
+
+    /// Estimated tokens in a JSON message as stored by a context manager.
+    ///
+    /// Values that are not a valid [`Message`] are counted as JSON text.
+    pub fn count_value(&self, model: &str, message: &Value) -> usize {
+        match serde_json::from_value::<Message>(message.clone()) {
+            Ok(message) => self.counter.count_message(model, &message),
+            Err(_) => {
+                MESSAGE_OVERHEAD_TOKENS + self.counter.count_text(model, &message.to_string())
+            }
+        }
+    }
+
+    /// Trim `messages` to fit in `budget` tokens.
+    ///
+    /// Messages are evicted by [`MessagePriority`]: lowest priority first,
+    /// oldest first within a priority, stopping as soon as the rest fits.
+    /// [`MessagePriority::Pinned`] messages (including every system and
+    /// developer message) are always kept, even if they alone exceed the
+    /// budget. A tool result is evicted together with the message that made
+    /// the call, at the higher of their priorities, so kept messages never
+    /// contain an orphaned tool result. Kept messages stay in their original
+    /// order.
+    pub fn fit(&self, model: &str, messages: &[Value], budget: usize) -> Vec<Value> {
+        // Group each message with the tool results that follow it.
+        let mut units: Vec<(usize, usize)> = Vec::new();
+        for (index, message) in messages.iter().enumerate() {
+            match units.last_mut() {
+                Some((_, end)) if role(message) == Some(Role::Tool) => *end = index + 1,
+                _ => units.push((index, index + 1)),
+            }
+        }
+        let cost = |(start, end): (usize, usize)| -> usize {
+            messages[start..end]
+                .iter()
+                .map(|m| self.count_value(model, m))
+                .sum()
+        };
+        let priority = |(start, end): (usize, usize)| {
+            messages[start..end]
+                .iter()
+                .map(MessagePriority::of)
+                .max()
+                .unwrap_or_default()
+        };
+
+        let mut keep = vec![false; units.len()];
+        let mut used = 0;
+        // Highest priority first, newest first within a priority.
+        let mut order: Vec<usize> = (0..units.len()).collect();
+        order.sort_by_key(|&u| std::cmp::Reverse((priority(units[u]), u)));
+        let mut evicting = false;
+        for u in order {
+            let unit_cost = cost(units[u]);
+            let pinned = priority(units[u]) == MessagePriority::Pinned;
+            if pinned || (!evicting && used + unit_cost <= budget) {
+                keep[u] = true;
+                used += unit_cost;
+            } else {
+                evicting = true;
+            }
+        }
+
+        units
+            .iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .flat_map(|(&(start, end), _)| messages[start..end].iter().cloned())
+            .collect()
+    }
+}
+
+impl Default for ContextBudget {
+    fn default() -> Self {
+        Self::new(Arc::new(HeuristicTokenCounter::default()))
+    }
+}
+
+fn role(message: &Value) -> Option<Role> {
+    serde_json::from_value(message.get("role")?.clone()).ok()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn message(value: Value) -> Message {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn model(context_window: i64, max_output_tokens: i64) -> ModelInfo {
+        ModelInfo {
+            id: "m".into(),
+            display_name: "M".into(),
+            context_window,
+            max_output_tokens,
+            capabilities: Vec::new(),
+            defaults: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn heuristic_rounds_up() {
+        let counter = HeuristicTokenCounter::default();
+        assert_eq!(counter.count_text("", ""), 0);
+        assert_eq!(counter.count_text("", "abc"), 1);
+        assert_eq!(counter.count_text("", "abcdefghi"), 3);
+        assert_eq!(HeuristicTokenCounter::new(0.0), counter);
+    }
+
+    #[test]
+    fn media_blocks_count_transcripts_when_present() {
+        let counter = HeuristicTokenCounter::default();
+        let blocks = message(json!({"role": "user", "content": [
+            {"type": "audio", "source": {"media_type": "audio/wav"}, "transcript": "12345678"},
+            {"type": "audio", "source": {"media_type": "audio/wav"}},
+            {"type": "document", "source": {"media_type": "application/pdf"}},
+        ]}));
+        assert_eq!(
+            counter.count_message("", &blocks),
+            MESSAGE_OVERHEAD_TOKENS + 2 + AUDIO_TOKENS + DOCUMENT_TOKENS
+        );
+    }
+
+    #[test]
+    fn counts_messages_and_blocks() {
+        let counter = HeuristicTokenCounter::default();
+        let text = message(json!({"role": "user", "content": "12345678"}));
+        assert_eq!(
+            counter.count_message("", &text),
+            MESSAGE_OVERHEAD_TOKENS + 2
+        );
+
+        let blocks = message(json!({"role": "assistant", "content": [
+            {"type": "text", "text": "1234"},
+            {"type": "image", "source": {"type": "base64", "data": "..."}},
+        ]}));
+        assert_eq!(
+            counter.count_message("", &blocks),
+            MESSAGE_OVERHEAD_TOKENS + 1 + IMAGE_TOKENS
+        );
+        assert_eq!(
+            counter.count_messages("", &[text, blocks]),
+            2 * MESSAGE_OVERHEAD_TOKENS + 3 + IMAGE_TOKENS
+        );
+    }
+
+    #[test]
+    fn request_includes_tool_definitions() {
+        let counter = HeuristicTokenCounter::default();
+        let bare: ChatRequest = serde_json::from_value(json!({
+            "messages": [{"role": "user", "content": "hi"}],
+        }))
+        .unwrap();
+        let mut with_tools = bare.clone();
+        with_tools.tools = Some(vec![serde_json::from_value(json!({
+            "name": "bash", "parameters": {"type": "object"},
+        }))
+        .unwrap()]);
+        assert!(counter.count_request(&with_tools) > counter.count_request(&bare));
+    }
+
+    #[test]
+    fn available_subtracts_output_and_margin() {
+        let budget = ContextBudget::default().with_safety_margin(100);
+        let model = model(10_000, 2_000);
+        assert_eq!(budget.available(&model, None), 7_900);
+        assert_eq!(budget.available(&model, Some(500)), 9_400);
+        // Output is capped at the model's maximum.
+        assert_eq!(budget.available(&model, Some(50_000)), 7_900);
+    }
+
+    #[test]
+    fn fit_keeps_system_and_newest() {
+        let budget = ContextBudget::default();
+        let messages = vec![
+            json!({"role": "system", "content": "sys"}),
+            json!({"role": "user", "content": "old ".repeat(40)}),
+            json!({"role": "assistant", "content": "a"}),
+            json!({"role": "user", "content": "b"}),
+        ];
+        let each = budget.count_value("", &messages[2]);
+        let total = budget.count_value("", &messages[0]) + 2 * each;
+        let kept = budget.fit("", &messages, total);
+        assert_eq!(
+            kept,
+            vec![
+                messages[0].clone(),
+                messages[2].clone(),
+                messages[3].clone()
+            ]
+        );
+
+        let all: usize = messages.iter().map(|m| budget.count_value("", m)).sum();
+        assert_eq!(budget.fit("", &messages, all), messages);
+    }
+
+    #[test]
+    fn fit_does_not_orphan_tool_results() {
+        let budget = ContextBudget::default();
+        let messages = vec![
+            json!({"role": "assistant", "content": [
+                {"type": "tool_call", "id": "c1", "name": "read", "input": {"path": "x".repeat(200)}},
+            ]}),
+            json!({"role": "tool", "tool_call_id": "c1", "content": "ok"}),
+            json!({"role": "user", "content": "next"}),
+        ];
+        let tail = budget.count_value("", &messages[1]) + budget.count_value("", &messages[2]);
+        let kept = budget.fit("", &messages, tail);
+        assert_eq!(kept, vec![messages[2].clone()]);
+    }
+
+    #[test]
+    fn fit_evicts_by_priority_then_age() {
+        let budget = ContextBudget::default();
+        let with_priority = |content: &str, priority: &str| json!({"role": "user", "content": content, "metadata": {"priority": priority}});
+        let messages = vec![
+            with_priority("fact", "pinned"),
+            with_priority("keep", "high"),
+            with_priority("old", "normal"),
+            with_priority("noise", "low"),
+            with_priority("new", "normal"),
+            json!({"role": "system", "content": "late system"}),
+        ];
+        let cost = |indices: &[usize]| -> usize {
+            indices
+                .iter()
+                .map(|&i| budget.count_value("", &messages[i]))
+                .sum()
+        };
+
+        // Room for the pinned messages, the high one and the newest normal.
+        let kept = budget.fit("", &messages, cost(&[0, 1, 4, 5]));
+        let expected: Vec<Value> = [0, 1, 4, 5].iter().map(|&i| messages[i].clone()).collect();
+        assert_eq!(kept, expected);
+
+        // Low-priority messages go before any normal one.
+        let kept = budget.fit("", &messages, cost(&[0, 1, 2, 4, 5]));
+        assert!(!kept.contains(&messages[3]));
+        assert_eq!(kept.len(), 5);
+
+        // Pinned messages survive even an impossible budget.
+        assert_eq!(
+            budget.fit("", &messages, 0),
+            vec![messages[0].clone(), messages[5].clone()]
+        );
+    }
+
+    #[test]
+    fn fit_keeps_pinned_tool_calls_with_their_results() {
+        let budget = ContextBudget::default();
+        let messages = vec![
+            json!({"role": "assistant", "metadata": {"priority": "pinned"}, "content": [
+                {"type": "tool_call", "id": "c1", "name": "read", "input": {}},
+            ]}),
+            json!({"role": "tool", "tool_call_id": "c1", "content": "x".repeat(400)}),
+            json!({"role": "user", "content": "next"}),
+        ];
+        assert_eq!(budget.fit("", &messages, 0), messages[..2].to_vec());
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn tiktoken_counts_known_and_unknown_models() {
+        let counter = TiktokenCounter::new();
+        assert_eq!(counter.count_text("gpt-4o", "hello world"), 2);
+        assert_eq!(counter.count_text("gpt-4", "hello world"), 2);
+        assert!(counter.count_text("claude-sonnet-4-5", "hello world") > 0);
+    }
+}
@@ -0,0 +1,281 @@
+//! Session-wide result caching for pure tools.
+//!
+//! The per-turn [`ToolResultCache`](crate::tool_executor::ToolResultCache)
+//! only catches replays of the same call ID. Models also re-issue identical
+//! calls under new IDs — the same `read_file` several times in one session —
+//! and each costs a tool run and a provider round trip to digest the output.
+//!
+//! A tool whose spec sets the `pure` extension (see
+//! [`ToolSpec::is_pure`](crate::messages::ToolSpec::is_pure)) declares that
+//! its result depends only on its arguments. For such tools
+//! [`ToolExecutor`](crate::tool_executor::ToolExecutor) keys successful
+//! results by `(tool name, normalized arguments)` in the coordinator's
+//! [`PureToolCache`] and returns a hit without running the tool:
+//!
+//! | Rule                      | Effect                                              |
+//! |---------------------------|-----------------------------------------------------|
+//! | `ttl_secs`                | Entries older than this are misses                  |
+//! | `max_entries`             | The oldest entry is evicted to make room            |
+//! | `max_entry_bytes`         | Larger results (as JSON) are not cached             |
+//! | `invalidate_on_impure`    | Any call to a tool that is not pure clears the cache |
+//!
+//! Arguments are normalized by sorting object keys, so `{"a":1,"b":2}` and
+//! `{"b":2,"a":1}` share an entry. Only results with `success: true` are
+//! cached, and they are cached after output post-processing, so a hit is
+//! exactly what the first call returned.
+//!
+//! Settings come from `session.tool_cache`:
+//!
+//! ```json
+//! {"session": {"tool_cache": {"ttl_secs": 120, "max_entries": 64}}}
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::models::ToolResult;
+
+// ---------------------------------------------------------------------------
+// PureToolCacheConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.tool_cache` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PureToolCacheConfig {
+    /// Cache results of pure tools at all.
+    pub enabled: bool,
+    /// How long an entry stays valid, in seconds.
+    pub ttl_secs: u64,
+    /// Most entries kept at once.
+    pub max_entries: usize,
+    /// Largest result cached, measured as JSON text.
+    pub max_entry_bytes: usize,
+    /// Clear the cache whenever a tool that is not pure runs.
+    pub invalidate_on_impure: bool,
+}
+
+impl Default for PureToolCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: 300,
+            max_entries: 256,
+            max_entry_bytes: 64 * 1024,
+            invalidate_on_impure: true,
+        }
+    }
+}
+
+impl PureToolCacheConfig {
+    /// Read `session.tool_cache` from a mount plan, falling back to the
+    /// defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("tool_cache")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.tool_cache config: {e}");
+            Self::default()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// PureToolCache
+// ---------------------------------------------------------------------------
+
+/// Identity of a pure tool call: the tool and its normalized arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PureCallKey {
+    pub tool: String,
+    pub arguments: String,
+}
+
+impl PureCallKey {
+    pub fn new(tool: &str, arguments: &Value) -> Self {
+        Self {
+            tool: tool.to_string(),
+            arguments: normalize(arguments).to_string(),
+        }
+    }
+}
+
+struct Entry {
+    result: ToolResult,
+    stored_at: Instant,
+}
+
+/// Results of pure tool calls, bounded by age and count.
+pub struct PureToolCache {
+    config: PureToolCacheConfig,
+    entries: Mutex<HashMap<PureCallKey, Entry>>,
+}
+
+impl PureToolCache {
+    pub fn new(config: PureToolCacheConfig) -> Self {
+        Self {
+            config,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &PureToolCacheConfig {
+        &self.config
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.config.ttl_secs)
+    }
+
+    /// The result cached for `key` if it is younger than the TTL at `now`.
+    pub fn get(&self, key: &PureCallKey, now: Instant) -> Option<ToolResult> {
+        if !self.config.enabled {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+        if now.saturating_duration_since(entry.stored_at) >= self.ttl() {
+            entries.remove(key);
+            return None;
+        }
+        Some(entry.result.clone())
+    }
+
+    /// Cache `result` for `key` at `now`.
+    ///
+    /// Failed and oversized results are skipped. Returns whether the result
+    /// was stored.
+    pub fn insert(&self, key: PureCallKey, result: &ToolResult, now: Instant) -> bool {
+        if !self.config.enabled || self.config.max_entries == 0 || !result.success {
+            return false;
+        }
+        let size = serde_json::to_vec(result).map_or(usize::MAX, |bytes| bytes.len());
+        if size > self.config.max_entry_bytes {
+            return false;
+        }
+        let ttl = self.ttl();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, entry| now.saturating_duration_since(entry.stored_at) < ttl);
+        while entries.len() >= self.config.max_entries && !entries.contains_key(&key) {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+        entries.insert(
+            key,
+            Entry {
+                result: result.clone(),
+                stored_at: now,
+            },
+        );
+        true
+    }
+
+    /// Number of cached results, including expired ones not yet pruned.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Whether no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every cached result.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl Default for PureToolCache {
+    fn default() -> Self {
+        Self::new(PureToolCacheConfig::default())
+    }
+}
+
+impl fmt::Debug for PureToolCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PureToolCache")
+            .field("config", &self.config)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// `value` with every object's keys in sorted order.
+fn normalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let sorted: Map<String, Value> = keys
+                .into_iter()
+                .map(|key| (key.clone(), normalize(&map[key])))
+                .collect();
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(normalize).collect()),
+        other => other.clone(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ok(text: &str) -> ToolResult {
+        ToolResult::new(true, Some(json!(text)), None)
+    }
+
+    #[test]
+    fn entries_expire_and_respect_caps() {
+        let cache = PureToolCache::new(PureToolCacheConfig {
+            ttl_secs: 10,
+            max_entries: 2,
+            max_entry_bytes: 200,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        let a = PureCallKey::new("read_file", &json!({"path": "a", "opts": {"x": 1, "y": 2}}));
+        let same = PureCallKey::new("read_file", &json!({"opts": {"y": 2, "x": 1}, "path": "a"}));
+        assert_eq!(a, same);
+
+        assert!(cache.insert(a.clone(), &ok("A"), start));
+        assert_eq!(
+            cache.get(&same, start + Duration::from_secs(9)),
+            Some(ok("A"))
+        );
+        assert_eq!(cache.get(&a, start + Duration::from_secs(10)), None);
+
+        // Failures and oversized results are not cached.
+        let b = PureCallKey::new("read_file", &json!({"path": "b"}));
+        assert!(!cache.insert(b.clone(), &ToolResult::new(false, None, None), start));
+        assert!(!cache.insert(b.clone(), &ok(&"x".repeat(500)), start));
+
+        // The oldest entry makes room.
+        let c = PureCallKey::new("read_file", &json!({"path": "c"}));
+        let t = start + Duration::from_secs(20);
+        cache.insert(a.clone(), &ok("A"), t);
+        cache.insert(b.clone(), &ok("B"), t + Duration::from_secs(1));
+        cache.insert(c.clone(), &ok("C"), t + Duration::from_secs(2));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&a, t + Duration::from_secs(2)), None);
+        assert_eq!(cache.get(&c, t + Duration::from_secs(2)), Some(ok("C")));
+    }
+}
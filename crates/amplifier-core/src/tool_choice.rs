@@ -0,0 +1,251 @@
+//! Kernel-side enforcement of `ChatRequest::tool_choice`.
+//!
+//! Dialects translate `tool_choice` for the wire, but nothing stops a
+//! provider from ignoring it. [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker)
+//! enforces it on both sides of the call:
+//!
+//! | Mode                       | Before the call                                    | After the call                    |
+//! |----------------------------|----------------------------------------------------|-----------------------------------|
+//! | `auto` (or unset)          | —                                                  | —                                 |
+//! | `none`                     | —                                                  | any tool call is an error         |
+//! | `required` / `any`         | —                                                  | no tool call is an error          |
+//! | a named tool               | must be mounted and offered; `tools` is cut down to it | a call to another tool is an error |
+//!
+//! A tool is named by a string that is not one of the modes (the Anthropic
+//! dialect's convention) or by an object with a `name` or `function.name`
+//! (`{"type": "function", "function": {"name": "search"}}`). Narrowing
+//! `tools` is recorded with the other
+//! [request adjustments](crate::request_conformance::RequestAdjustment).
+
+use serde_json::Value;
+
+use crate::messages::{ChatRequest, ToolCall, ToolChoice};
+use crate::request_conformance::RequestAdjustment;
+
+/// What a `tool_choice` asks of the model.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ToolChoiceMode {
+    /// The model decides.
+    #[default]
+    Auto,
+    /// No tool may be called.
+    None,
+    /// Some tool must be called.
+    Required,
+    /// This tool must be called.
+    Tool(String),
+}
+
+impl ToolChoiceMode {
+    /// The mode `choice` asks for; no choice is [`Auto`](Self::Auto).
+    pub fn of(choice: Option<&ToolChoice>) -> Self {
+        match choice {
+            None => Self::Auto,
+            Some(ToolChoice::String(mode)) => Self::from_mode(mode),
+            Some(ToolChoice::Object(map)) => {
+                let name = map
+                    .get("name")
+                    .or_else(|| map.get("function").and_then(|f| f.get("name")))
+                    .and_then(Value::as_str);
+                match (name, map.get("type").and_then(Value::as_str)) {
+                    (Some(name), _) => Self::Tool(name.to_string()),
+                    (None, Some(mode)) => Self::from_mode(mode),
+                    (None, None) => Self::Auto,
+                }
+            }
+        }
+    }
+
+    fn from_mode(mode: &str) -> Self {
+        match mode {
+            "auto" => Self::Auto,
+            "none" => Self::None,
+            "required" | "any" => Self::Required,
+            name => Self::Tool(name.to_string()),
+        }
+    }
+}
+
+/// A `tool_choice` that cannot be, or was not, honoured.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ToolChoiceError {
+    /// The named tool is not mounted on the session.
+    #[error("tool_choice names tool '{tool}', which is not mounted")]
+    NotMounted { tool: String },
+
+    /// The named tool is not in the request's `tools`.
+    #[error("tool_choice names tool '{tool}', which is not among the offered tools")]
+    NotOffered { tool: String },
+
+    /// The model called a tool other than the named one.
+    #[error("tool_choice requires a call to '{expected}' but the model called '{actual}'")]
+    WrongTool { expected: String, actual: String },
+
+    /// The model called a tool although `tool_choice` is `none`.
+    #[error("tool_choice is 'none' but the model called '{actual}'")]
+    UnexpectedCall { actual: String },
+
+    /// The model called no tool although one was required.
+    #[error("tool_choice requires a tool call but the model made none")]
+    MissingCall,
+}
+
+/// Check a named tool choice against `mounted` (when known) and narrow
+/// `request.tools` to that tool.
+///
+/// # Errors
+///
+/// [`ToolChoiceError::NotMounted`] or [`ToolChoiceError::NotOffered`].
+pub fn restrict(
+    request: &mut ChatRequest,
+    mounted: Option<&[String]>,
+) -> Result<Option<RequestAdjustment>, ToolChoiceError> {
+    let ToolChoiceMode::Tool(tool) = ToolChoiceMode::of(request.tool_choice.as_ref()) else {
+        return Ok(None);
+    };
+    if mounted.is_some_and(|names| !names.contains(&tool)) {
+        return Err(ToolChoiceError::NotMounted { tool });
+    }
+    let Some(tools) = request
+        .tools
+        .as_mut()
+        .filter(|tools| tools.iter().any(|spec| spec.name == tool))
+    else {
+        return Err(ToolChoiceError::NotOffered { tool });
+    };
+    if tools.len() == 1 {
+        return Ok(None);
+    }
+    let offered: Vec<String> = tools.iter().map(|spec| spec.name.clone()).collect();
+    tools.retain(|spec| spec.name == tool);
+    Ok(Some(RequestAdjustment::new(
+        "tools",
+        offered,
+        [&tool],
+        "tool_choice names a single tool",
+    )))
+}
+
+/// Check the tool calls a response made against `choice`.
+///
+/// # Errors
+///
+/// [`ToolChoiceError::WrongTool`], [`ToolChoiceError::UnexpectedCall`] or
+/// [`ToolChoiceError::MissingCall`].
+pub fn check_calls(choice: Option<&ToolChoice>, calls: &[ToolCall]) -> Result<(), ToolChoiceError> {
+    match ToolChoiceMode::of(choice) {
+        ToolChoiceMode::Auto => Ok(()),
+        ToolChoiceMode::None => match calls.first() {
+            Some(call) => Err(ToolChoiceError::UnexpectedCall {
+                actual: call.name.clone(),
+            }),
+            None => Ok(()),
+        },
+        ToolChoiceMode::Required if calls.is_empty() => Err(ToolChoiceError::MissingCall),
+        ToolChoiceMode::Required => Ok(()),
+        ToolChoiceMode::Tool(expected) => {
+            if calls.is_empty() {
+                return Err(ToolChoiceError::MissingCall);
+            }
+            match calls.iter().find(|call| call.name != expected) {
+                Some(call) => Err(ToolChoiceError::WrongTool {
+                    expected,
+                    actual: call.name.clone(),
+                }),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use crate::messages::ToolSpec;
+
+    fn spec(name: &str) -> ToolSpec {
+        ToolSpec {
+            name: name.into(),
+            parameters: HashMap::new(),
+            description: None,
+            extensions: HashMap::new(),
+        }
+    }
+
+    fn call(name: &str) -> ToolCall {
+        ToolCall {
+            id: "c1".into(),
+            name: name.into(),
+            arguments: HashMap::new(),
+            extensions: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn modes_parse_from_strings_and_objects() {
+        let of =
+            |v: serde_json::Value| ToolChoiceMode::of(Some(&serde_json::from_value(v).unwrap()));
+        assert_eq!(of(json!("auto")), ToolChoiceMode::Auto);
+        assert_eq!(of(json!("any")), ToolChoiceMode::Required);
+        assert_eq!(of(json!("search")), ToolChoiceMode::Tool("search".into()));
+        assert_eq!(
+            of(json!({"type": "function", "function": {"name": "search"}})),
+            ToolChoiceMode::Tool("search".into())
+        );
+        assert_eq!(of(json!({"type": "none"})), ToolChoiceMode::None);
+        assert_eq!(ToolChoiceMode::of(None), ToolChoiceMode::Auto);
+    }
+
+    #[test]
+    fn named_tool_must_be_mounted_and_offered() {
+        let mut request: ChatRequest =
+            serde_json::from_value(json!({"messages": [], "tool_choice": "search"})).unwrap();
+        request.tools = Some(vec![spec("search"), spec("bash")]);
+        let mounted = vec!["search".to_string(), "bash".to_string()];
+
+        let adjustment = restrict(&mut request, Some(&mounted)).unwrap().unwrap();
+        assert_eq!(adjustment.applied, json!(["search"]));
+        assert_eq!(request.tools.as_ref().unwrap().len(), 1);
+
+        assert_eq!(
+            restrict(&mut request, Some(&mounted[1..])),
+            Err(ToolChoiceError::NotMounted {
+                tool: "search".into()
+            })
+        );
+        request.tools = Some(vec![spec("bash")]);
+        assert!(matches!(
+            restrict(&mut request, None),
+            Err(ToolChoiceError::NotOffered { .. })
+        ));
+    }
+
+    #[test]
+    fn calls_are_checked_against_the_choice() {
+        let named = ToolChoice::String("search".into());
+        assert!(check_calls(Some(&named), &[call("search")]).is_ok());
+        assert_eq!(
+            check_calls(Some(&named), &[call("search"), call("bash")]),
+            Err(ToolChoiceError::WrongTool {
+                expected: "search".into(),
+                actual: "bash".into()
+            })
+        );
+        let none = ToolChoice::String("none".into());
+        assert!(check_calls(Some(&none), &[call("bash")]).is_err());
+        let required = ToolChoice::String("required".into());
+        assert_eq!(
+            check_calls(Some(&required), &[]),
+            Err(ToolChoiceError::MissingCall)
+        );
+        assert!(check_calls(None, &[call("bash")]).is_ok());
+    }
+}
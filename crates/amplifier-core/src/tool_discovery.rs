@@ -0,0 +1,411 @@
+//! Hook-driven tool discovery.
+//!
+//! At the start of every turn the session emits
+//! [`TOOLS_DISCOVER`](crate::events::TOOLS_DISCOVER) as a decision event
+//! (see [`HookRegistry::emit_decision`]). Hooks backed by an MCP server, a
+//! plugin registry or anything else propose tools as candidates whose `id`
+//! is the tool name and whose `metadata.spec` is its [`ToolSpec`]:
+//!
+//! ```json
+//! {"candidates": [{"id": "search_issues", "score": 1.0,
+//!                  "metadata": {"spec": {"name": "search_issues", "parameters": {...}}}}]}
+//! ```
+//!
+//! Accepted proposals are mounted as [`ProxyTool`]s for that turn only and
+//! unmounted when it ends. A proposal is accepted when its score reaches
+//! `min_score`, its spec parses, and no tool of that name is already
+//! mounted; at most `max_tools` are accepted, best-ranked first.
+//!
+//! Calls to a proxy tool are emitted as
+//! [`TOOLS_INVOKE`](crate::events::TOOLS_INVOKE) with the proposing
+//! handler's name as `source`; the handler that owns the tool returns the
+//! result as `data.tool_result`.
+//!
+//! # Configuration
+//!
+//! Tuned (or turned off) through `session.tool_discovery`:
+//!
+//! ```json
+//! {"session": {"tool_discovery": {"max_tools": 10, "invoke_timeout_ms": 30000}}}
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::coordinator::Coordinator;
+use crate::errors::ToolError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::messages::ToolSpec;
+use crate::models::{Candidate, ToolResult};
+use crate::traits::Tool;
+
+// ---------------------------------------------------------------------------
+// ToolDiscoveryConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.tool_discovery` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolDiscoveryConfig {
+    /// Set to `false` to skip `tools:discover` entirely.
+    pub enabled: bool,
+    /// Most tools mounted per turn.
+    pub max_tools: usize,
+    /// Proposals scoring below this are ignored.
+    pub min_score: f64,
+    /// Per-handler timeout for `tools:discover`.
+    pub timeout_ms: u64,
+    /// Per-handler timeout for `tools:invoke`.
+    pub invoke_timeout_ms: u64,
+}
+
+impl Default for ToolDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_tools: 20,
+            min_score: 0.0,
+            timeout_ms: 5_000,
+            invoke_timeout_ms: 60_000,
+        }
+    }
+}
+
+impl ToolDiscoveryConfig {
+    /// Read `session.tool_discovery` from a mount plan.
+    ///
+    /// Returns the defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("tool_discovery")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone())
+            .map_err(|e| log::warn!("Ignoring malformed session.tool_discovery config: {e}"))
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ProxyTool
+// ---------------------------------------------------------------------------
+
+/// A tool proposed on `tools:discover`, executed by its proposing hook
+/// through `tools:invoke`.
+pub struct ProxyTool {
+    spec: ToolSpec,
+    /// Name of the handler that proposed the tool.
+    source: String,
+    hooks: Arc<HookRegistry>,
+    timeout: Duration,
+}
+
+impl ProxyTool {
+    pub fn new(
+        spec: ToolSpec,
+        source: impl Into<String>,
+        hooks: Arc<HookRegistry>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            spec,
+            source: source.into(),
+            hooks,
+            timeout,
+        }
+    }
+
+    /// The handler that proposed this tool.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Tool for ProxyTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        self.spec.description.as_deref().unwrap_or("")
+    }
+
+    fn get_spec(&self) -> ToolSpec {
+        self.spec.clone()
+    }
+
+    fn execute(
+        &self,
+        input: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        Box::pin(async move {
+            let responses = self
+                .hooks
+                .emit_and_collect(
+                    events::TOOLS_INVOKE,
+                    serde_json::json!({
+                        "tool_name": self.spec.name,
+                        "tool_input": input,
+                        "source": self.source,
+                    }),
+                    self.timeout,
+                )
+                .await;
+            let raw = responses
+                .into_iter()
+                .find_map(|mut data| data.remove("tool_result"))
+                .ok_or_else(|| ToolError::ExecutionFailed {
+                    message: format!(
+                        "no hook returned a result for discovered tool '{}' (source: {})",
+                        self.spec.name, self.source
+                    ),
+                    stdout: None,
+                    stderr: None,
+                    exit_code: None,
+                })?;
+            serde_json::from_value(raw).map_err(|e| ToolError::ExecutionFailed {
+                message: format!("malformed tool_result for '{}': {e}", self.spec.name),
+                stdout: None,
+                stderr: None,
+                exit_code: None,
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Discovery
+// ---------------------------------------------------------------------------
+
+/// Proxy tools mounted for one turn; unmounted when dropped.
+pub struct DiscoveredTools {
+    coordinator: Arc<Coordinator>,
+    mounted: Vec<(String, Arc<dyn Tool>)>,
+}
+
+impl DiscoveredTools {
+    /// Names of the tools mounted for this turn, best-ranked first.
+    pub fn names(&self) -> Vec<&str> {
+        self.mounted.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mounted.is_empty()
+    }
+}
+
+impl Drop for DiscoveredTools {
+    fn drop(&mut self) {
+        for (name, tool) in &self.mounted {
+            // Leave alone a tool that was remounted under the same name
+            // during the turn.
+            if self
+                .coordinator
+                .get_tool(name)
+                .is_some_and(|current| Arc::ptr_eq(&current, tool))
+            {
+                self.coordinator.unmount_tool(name);
+            }
+        }
+    }
+}
+
+/// Emit `tools:discover` for a turn about to run `prompt` and mount the
+/// accepted proposals as [`ProxyTool`]s until the returned value is dropped.
+pub async fn discover(
+    coordinator: &Arc<Coordinator>,
+    session_id: &str,
+    prompt: &str,
+    config: &ToolDiscoveryConfig,
+) -> DiscoveredTools {
+    let mut discovered = DiscoveredTools {
+        coordinator: Arc::clone(coordinator),
+        mounted: Vec::new(),
+    };
+    if !config.enabled || config.max_tools == 0 {
+        return discovered;
+    }
+
+    let mut mounted_tools: Vec<String> = coordinator.tools().keys().cloned().collect();
+    mounted_tools.sort();
+    let candidates = coordinator
+        .hooks()
+        .emit_decision(
+            events::TOOLS_DISCOVER,
+            serde_json::json!({
+                "session_id": session_id,
+                "prompt": prompt,
+                "mounted_tools": mounted_tools,
+            }),
+            Duration::from_millis(config.timeout_ms),
+        )
+        .await;
+
+    for candidate in candidates {
+        if discovered.mounted.len() >= config.max_tools {
+            break;
+        }
+        if candidate.score < config.min_score || coordinator.get_tool(&candidate.id).is_some() {
+            continue;
+        }
+        let Some(spec) = proposed_spec(&candidate) else {
+            continue;
+        };
+        let tool: Arc<dyn Tool> = Arc::new(ProxyTool::new(
+            spec,
+            candidate.source.clone(),
+            coordinator.hooks_shared(),
+            Duration::from_millis(config.invoke_timeout_ms),
+        ));
+        coordinator.mount_tool(&candidate.id, Arc::clone(&tool));
+        discovered.mounted.push((candidate.id, tool));
+    }
+    discovered
+}
+
+/// The [`ToolSpec`] in a candidate's `metadata.spec`, named after its `id`.
+fn proposed_spec(candidate: &Candidate) -> Option<ToolSpec> {
+    let raw = candidate.metadata.get("spec").cloned().unwrap_or_else(
+        || serde_json::json!({"name": candidate.id, "parameters": {"type": "object"}}),
+    );
+    match serde_json::from_value::<ToolSpec>(raw) {
+        Ok(mut spec) => {
+            spec.name = candidate.id.clone();
+            Some(spec)
+        }
+        Err(e) => {
+            log::warn!(
+                "Ignoring tool '{}' proposed by '{}': malformed spec: {e}",
+                candidate.id,
+                candidate.source
+            );
+            None
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HookResult;
+    use crate::testing::{FakeHookHandler, FakeTool};
+    use serde_json::json;
+
+    fn proposal(data: Value) -> Arc<FakeHookHandler> {
+        let data = serde_json::from_value(data).unwrap();
+        Arc::new(FakeHookHandler::with_result(HookResult {
+            data: Some(data),
+            ..Default::default()
+        }))
+    }
+
+    fn spec(name: &str) -> Value {
+        json!({"name": name, "parameters": {"type": "object"}, "description": "found"})
+    }
+
+    #[tokio::test]
+    async fn accepted_proposals_are_mounted_for_the_turn() {
+        let coordinator = Arc::new(Coordinator::new_for_test());
+        coordinator.mount_tool("bash", Arc::new(FakeTool::new("bash", "shell")));
+        let _ = coordinator.hooks().register(
+            events::TOOLS_DISCOVER,
+            proposal(json!({"candidates": [
+                {"id": "search_issues", "score": 2.0, "metadata": {"spec": spec("ignored")}},
+                {"id": "bash", "score": 3.0, "metadata": {"spec": spec("bash")}},
+                {"id": "low", "score": -1.0, "metadata": {"spec": spec("low")}},
+                {"id": "broken", "score": 1.0, "metadata": {"spec": "not a spec"}},
+                {"id": "list_repos", "score": 1.0},
+            ]})),
+            0,
+            Some("mcp".into()),
+        );
+
+        let discovered = discover(
+            &coordinator,
+            "s1",
+            "find the bug",
+            &ToolDiscoveryConfig::default(),
+        )
+        .await;
+
+        assert_eq!(discovered.names(), vec!["search_issues", "list_repos"]);
+        let tool = coordinator.get_tool("search_issues").unwrap();
+        assert_eq!(tool.get_spec().name, "search_issues");
+        assert_eq!(tool.description(), "found");
+        assert_eq!(coordinator.get_tool("bash").unwrap().description(), "shell");
+
+        drop(discovered);
+        assert!(coordinator.get_tool("search_issues").is_none());
+        assert!(coordinator.get_tool("list_repos").is_none());
+        assert!(coordinator.get_tool("bash").is_some());
+    }
+
+    #[tokio::test]
+    async fn max_tools_and_disabled_config_limit_mounting() {
+        let coordinator = Arc::new(Coordinator::new_for_test());
+        let _ = coordinator.hooks().register(
+            events::TOOLS_DISCOVER,
+            proposal(json!({"candidates": [
+                {"id": "a", "score": 1.0}, {"id": "b", "score": 2.0},
+            ]})),
+            0,
+            None,
+        );
+
+        let config = ToolDiscoveryConfig {
+            max_tools: 1,
+            ..ToolDiscoveryConfig::default()
+        };
+        let discovered = discover(&coordinator, "s1", "hi", &config).await;
+        assert_eq!(discovered.names(), vec!["b"]);
+        drop(discovered);
+
+        let config = ToolDiscoveryConfig {
+            enabled: false,
+            ..ToolDiscoveryConfig::default()
+        };
+        assert!(discover(&coordinator, "s1", "hi", &config).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn proxy_tool_calls_go_through_tools_invoke() {
+        let hooks = Arc::new(HookRegistry::new());
+        let owner = proposal(json!({"tool_result": {"success": true, "output": "3 issues"}}));
+        let _ = hooks.register(events::TOOLS_INVOKE, owner.clone(), 0, None);
+        let tool = ProxyTool::new(
+            serde_json::from_value(spec("search_issues")).unwrap(),
+            "mcp",
+            hooks.clone(),
+            Duration::from_secs(1),
+        );
+
+        let result = tool.execute(json!({"query": "crash"})).await.unwrap();
+
+        assert_eq!(result.output, Some(json!("3 issues")));
+        let (_, payload) = &owner.recorded_events()[0];
+        assert_eq!(payload["tool_name"], "search_issues");
+        assert_eq!(payload["tool_input"]["query"], "crash");
+        assert_eq!(payload["source"], "mcp");
+
+        let unhandled = ProxyTool::new(
+            serde_json::from_value(spec("other")).unwrap(),
+            "mcp",
+            Arc::new(HookRegistry::new()),
+            Duration::from_secs(1),
+        );
+        assert!(matches!(
+            unhandled.execute(json!({})).await,
+            Err(ToolError::ExecutionFailed { .. })
+        ));
+    }
+}
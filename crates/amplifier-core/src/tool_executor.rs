@@ -0,0 +1,1023 @@
+//! ToolExecutor — idempotent, deadline-bounded tool calls.
+//!
+//! Providers that retry a request can hand back the same `tool_calls` more
+//! than once, and an orchestrator that naively replays them would run
+//! side-effectful tools twice. The executor gives every call an
+//! [`IdempotencyKey`] — `(session id, turn number, call id)` — and memoizes
+//! the first [`ToolResult`] for that key in a [`ToolResultCache`]:
+//!
+//! | Call                                   | Behaviour                                   |
+//! |----------------------------------------|---------------------------------------------|
+//! | First execution of a key               | Runs the tool, caches the result            |
+//! | Same key again (sequential)            | Returns the cached result; tool not run     |
+//! | Same key while the first is in flight  | Waits for the first and shares its result   |
+//! | Empty call id                          | Never memoized                              |
+//!
+//! Only results are cached — a [`ToolError`] (including a turn-deadline
+//! timeout) leaves the key unset, so the call can be retried. Failed
+//! `ToolResult`s (`success: false`) are results and are cached.
+//!
+//! # Connections
+//!
+//! - [`ToolExecutor::from_coordinator`] shares the
+//!   [`Coordinator`]'s cache, turn number and turn deadline, and takes the
+//!   session id from the hook registry's default fields (the same
+//!   `session_id` every event carries).
+//! - [`Coordinator::reset_turn`] advances the turn number and clears the
+//!   cache, so memoization lasts for one turn.
+//! - Execution is bounded by [`deadline::execute_tool`].
+//! - A tool whose spec sets a `timeout` extension (seconds, see
+//!   [`ToolSpec::timeout`](crate::messages::ToolSpec::timeout)) is also
+//!   stopped after that long, and every call is stopped by immediate
+//!   cancellation of the coordinator's [`CancellationToken`]. Such calls run
+//!   through [`Tool::execute_streaming`], so the last
+//!   [`ToolProgress::partial_output`](crate::models::ToolProgress::partial_output)
+//!   the tool reported is kept on the resulting [`ToolError::Timeout`] or
+//!   [`ToolError::Cancelled`]. A tool timeout also emits `tool:timeout`.
+//! - Results pass through the coordinator's
+//!   [`ToolOutputProcessor`] (binary detection, `tool:result:transform`,
+//!   truncation) before they are cached or returned.
+//! - With an [`AttachmentStore`] (taken from the coordinator when one is
+//!   set), large outputs are then offloaded.
+//! - The coordinator's [`Workspace`], if any, is passed to tools on their
+//!   [`ToolContext`], together with [`ToolServices`]: the hooks (for
+//!   `tool:<name>:<event>` events), the cancellation token and the
+//!   coordinator's capabilities.
+//! - Results of tools whose spec is pure are also cached for the session
+//!   in the coordinator's [`PureToolCache`], keyed by tool name and
+//!   arguments (see [`crate::tool_cache`]).
+//! - Progress reported by tools is shown on the coordinator's
+//!   [`DisplayProvider`], if any.
+//! - Each call runs in a [`correlation::scope`] with its call ID and the
+//!   coordinator's turn ID, so events emitted while it runs carry both.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+use tokio_stream::StreamExt;
+
+use crate::attachments::AttachmentStore;
+use crate::cancellation::CancellationToken;
+use crate::clock::{self, Clock};
+use crate::coordinator::{CapabilityLookup, Coordinator};
+use crate::correlation::{self, CorrelationIds};
+use crate::deadline::{self, TurnDeadline};
+use crate::errors::ToolError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::messages::ToolCall;
+use crate::models::{ToolContext, ToolResult};
+use crate::orchestrator_status::{self, OrchestratorStatus};
+use crate::tool_cache::{PureCallKey, PureToolCache};
+use crate::tool_output::ToolOutputProcessor;
+use crate::tool_progress::ToolUpdate;
+use crate::tool_services::ToolServices;
+use crate::traits::{DisplayProvider, Tool};
+use crate::workspace::Workspace;
+
+/// Stable identity of one tool call within a session.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IdempotencyKey {
+    pub session_id: String,
+    pub turn: u64,
+    pub call_id: String,
+}
+
+impl fmt::Display for IdempotencyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.session_id, self.turn, self.call_id)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ToolResultCache
+// ---------------------------------------------------------------------------
+
+/// Memoized tool results, keyed by [`IdempotencyKey`].
+///
+/// Each key holds a once-cell, so concurrent executions of the same key run
+/// the tool once.
+#[derive(Default)]
+pub struct ToolResultCache {
+    entries: Mutex<HashMap<IdempotencyKey, Arc<OnceCell<ToolResult>>>>,
+}
+
+impl ToolResultCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached result for `key`, if its execution has completed.
+    pub fn get(&self, key: &IdempotencyKey) -> Option<ToolResult> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .and_then(|cell| cell.get().cloned())
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|cell| cell.initialized())
+            .count()
+    }
+
+    /// Whether no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop every cached result.
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Return the cached result for `key`, or run `execution` to produce it.
+    ///
+    /// # Errors
+    ///
+    /// The error from `execution`; nothing is cached in that case.
+    pub async fn get_or_execute<F>(
+        &self,
+        key: IdempotencyKey,
+        execution: F,
+    ) -> Result<ToolResult, ToolError>
+    where
+        F: std::future::Future<Output = Result<ToolResult, ToolError>>,
+    {
+        let cell = Arc::clone(self.entries.lock().unwrap().entry(key).or_default());
+        cell.get_or_try_init(|| execution).await.cloned()
+    }
+}
+
+impl fmt::Debug for ToolResultCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolResultCache")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ToolExecutor
+// ---------------------------------------------------------------------------
+
+/// Runs tool calls with per-turn memoization and the turn deadline.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # async fn example(tool: std::sync::Arc<dyn amplifier_core::Tool>, call: amplifier_core::ToolCall) {
+/// use amplifier_core::coordinator::Coordinator;
+/// use amplifier_core::tool_executor::ToolExecutor;
+///
+/// let coord = Coordinator::new_for_test();
+/// let executor = ToolExecutor::from_coordinator(&coord);
+/// let first = executor.execute_call(tool.as_ref(), &call).await;
+/// // A duplicate of the same call returns the cached result.
+/// let again = executor.execute_call(tool.as_ref(), &call).await;
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ToolExecutor {
+    cache: Option<Arc<ToolResultCache>>,
+    session_id: String,
+    turn: u64,
+    deadline: Option<TurnDeadline>,
+    attachments: Option<Arc<AttachmentStore>>,
+    post_processing: Option<(Arc<HookRegistry>, Arc<ToolOutputProcessor>)>,
+    cancellation: Option<CancellationToken>,
+    /// Receives `tool:timeout`; its clock times tool timeouts.
+    hooks: Option<Arc<HookRegistry>>,
+    workspace: Option<Arc<Workspace>>,
+    turn_id: Option<String>,
+    pure_results: Option<Arc<PureToolCache>>,
+    display: Option<Arc<dyn DisplayProvider>>,
+    capabilities: Option<CapabilityLookup>,
+}
+
+impl ToolExecutor {
+    /// An executor with no memoization and no deadline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an executor sharing the coordinator's result cache, turn
+    /// number, turn deadline, output post-processing, attachment store,
+    /// cancellation token, hooks, workspace, turn ID, pure tool cache,
+    /// display provider and capabilities.
+    pub fn from_coordinator(coordinator: &Coordinator) -> Self {
+        let session_id = coordinator
+            .hooks()
+            .default_fields()
+            .and_then(|fields| fields.get("session_id")?.as_str().map(str::to_owned))
+            .unwrap_or_default();
+        Self::new()
+            .with_cache(coordinator.tool_results())
+            .with_scope(session_id, coordinator.turn_number())
+            .with_deadline(coordinator.turn_deadline())
+            .with_post_processing(coordinator.hooks_shared(), coordinator.tool_output())
+            .with_attachments(coordinator.attachment_store())
+            .with_cancellation(coordinator.cancellation().clone())
+            .with_hooks(coordinator.hooks_shared())
+            .with_workspace(coordinator.workspace())
+            .with_turn_id(coordinator.turn_id())
+            .with_pure_cache(Some(coordinator.pure_tool_results()))
+            .with_display(coordinator.display_provider())
+            .with_capabilities(Some(coordinator.capability_lookup()))
+    }
+
+    /// Memoize results in `cache`.
+    pub fn with_cache(mut self, cache: Arc<ToolResultCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Session and turn used to build idempotency keys.
+    pub fn with_scope(mut self, session_id: impl Into<String>, turn: u64) -> Self {
+        self.session_id = session_id.into();
+        self.turn = turn;
+        self
+    }
+
+    /// Bound every call made through this executor by `deadline`.
+    pub fn with_deadline(mut self, deadline: Option<TurnDeadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Pass results through `processor`, emitting `tool:result:transform`
+    /// on `hooks`.
+    pub fn with_post_processing(
+        mut self,
+        hooks: Arc<HookRegistry>,
+        processor: Arc<ToolOutputProcessor>,
+    ) -> Self {
+        self.post_processing = Some((hooks, processor));
+        self
+    }
+
+    /// Offload large outputs to `store`.
+    pub fn with_attachments(mut self, store: Option<Arc<AttachmentStore>>) -> Self {
+        self.attachments = store;
+        self
+    }
+
+    /// Stop calls when `token` requests immediate cancellation.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Emit `tool:timeout` on `hooks`, and time tool timeouts on its clock.
+    pub fn with_hooks(mut self, hooks: Arc<HookRegistry>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    /// Hand `workspace` to tools on their [`ToolContext`].
+    pub fn with_workspace(mut self, workspace: Option<Arc<Workspace>>) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    /// Tag calls with `turn_id` (see [`crate::correlation`]).
+    pub fn with_turn_id(mut self, turn_id: Option<String>) -> Self {
+        self.turn_id = turn_id;
+        self
+    }
+
+    /// Cache results of pure tools in `cache` (see [`crate::tool_cache`]).
+    pub fn with_pure_cache(mut self, cache: Option<Arc<PureToolCache>>) -> Self {
+        self.pure_results = cache;
+        self
+    }
+
+    /// Show tool progress on `display`.
+    pub fn with_display(mut self, display: Option<Arc<dyn DisplayProvider>>) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Let tools look up `capabilities` through their [`ToolContext`].
+    pub fn with_capabilities(mut self, capabilities: Option<CapabilityLookup>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// The idempotency key for `call_id`, or `None` for an empty id.
+    pub fn key(&self, call_id: &str) -> Option<IdempotencyKey> {
+        (!call_id.is_empty()).then(|| IdempotencyKey {
+            session_id: self.session_id.clone(),
+            turn: self.turn,
+            call_id: call_id.to_string(),
+        })
+    }
+
+    /// Execute `call` with `tool`, returning the memoized result if the same
+    /// call already ran this turn.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`execute`](Self::execute).
+    pub async fn execute_call(
+        &self,
+        tool: &dyn Tool,
+        call: &ToolCall,
+    ) -> Result<ToolResult, ToolError> {
+        let input = Value::Object(call.arguments.clone().into_iter().collect());
+        self.execute(tool, &call.id, input).await
+    }
+
+    /// Execute `tool` with `input` on behalf of the call `call_id`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ToolError::Timeout`] if the tool's timeout or the turn deadline
+    ///   is reached
+    /// - [`ToolError::Cancelled`] on immediate cancellation
+    /// - Any `ToolError` from the tool itself
+    pub async fn execute(
+        &self,
+        tool: &dyn Tool,
+        call_id: &str,
+        input: Value,
+    ) -> Result<ToolResult, ToolError> {
+        let ids = CorrelationIds {
+            turn_id: self.turn_id.clone(),
+            tool_call_id: (!call_id.is_empty()).then(|| call_id.to_string()),
+        };
+        let run = correlation::scope(ids, self.run(tool, call_id, input));
+        match (&self.cache, self.key(call_id)) {
+            (Some(cache), Some(key)) => {
+                if let Some(cached) = cache.get(&key) {
+                    log::debug!(
+                        "Tool '{}' call {key} already ran; reusing result",
+                        tool.name()
+                    );
+                    return Ok(cached);
+                }
+                cache.get_or_execute(key, run).await
+            }
+            _ => run.await,
+        }
+    }
+
+    /// Run the call, answering pure tools from the pure cache.
+    async fn run(
+        &self,
+        tool: &dyn Tool,
+        call_id: &str,
+        input: Value,
+    ) -> Result<ToolResult, ToolError> {
+        let Some(cache) = &self.pure_results else {
+            return self.run_uncached(tool, call_id, input).await;
+        };
+        if !tool.get_spec().is_pure() {
+            let result = self.run_uncached(tool, call_id, input).await;
+            if cache.config().invalidate_on_impure {
+                cache.clear();
+            }
+            return result;
+        }
+        let key = PureCallKey::new(tool.name(), &input);
+        let clock = self.clock();
+        if let Some(cached) = cache.get(&key, clock.now()) {
+            log::debug!(
+                "Pure tool '{}' already ran with these arguments; reusing result",
+                tool.name()
+            );
+            return Ok(cached);
+        }
+        let result = self.run_uncached(tool, call_id, input).await?;
+        cache.insert(key, &result, clock.now());
+        Ok(result)
+    }
+
+    async fn run_uncached(
+        &self,
+        tool: &dyn Tool,
+        call_id: &str,
+        input: Value,
+    ) -> Result<ToolResult, ToolError> {
+        orchestrator_status::report(OrchestratorStatus::CallingTool {
+            name: tool.name().to_string(),
+            tool_call_id: Some(call_id.to_string()),
+        });
+        let result = match &self.post_processing {
+            Some((hooks, processor)) => {
+                let result = self.invoke(tool, call_id, input.clone()).await?;
+                processor.process(hooks, tool.name(), &input, result).await
+            }
+            None => self.invoke(tool, call_id, input).await?,
+        };
+        let Some(store) = &self.attachments else {
+            return Ok(result);
+        };
+        match store.offload(result.clone()).await {
+            Ok(offloaded) => Ok(offloaded),
+            Err(e) => {
+                log::warn!(
+                    "Failed to offload output of tool '{}': {e}; keeping it inline",
+                    tool.name()
+                );
+                Ok(result)
+            }
+        }
+    }
+
+    /// Run the tool itself, bounded by the turn deadline, its own timeout
+    /// and cancellation.
+    async fn invoke(
+        &self,
+        tool: &dyn Tool,
+        call_id: &str,
+        input: Value,
+    ) -> Result<ToolResult, ToolError> {
+        let timeout = tool.get_spec().timeout();
+        if timeout.is_none()
+            && self.cancellation.is_none()
+            && self.workspace.is_none()
+            && self.display.is_none()
+            && self.hooks.is_none()
+            && self.capabilities.is_none()
+        {
+            return deadline::execute_tool(self.deadline.clone(), tool, input).await;
+        }
+        let context = ToolContext {
+            tool_call_id: (!call_id.is_empty()).then(|| call_id.to_string()),
+            workspace: self.workspace.as_deref().cloned(),
+            turn_id: self.turn_id.clone(),
+            services: self.services(tool.name()),
+            ..Default::default()
+        };
+        let race = self.race(tool, call_id, input, context, timeout);
+        deadline::run_tool(self.deadline.clone(), tool, race).await
+    }
+
+    fn services(&self, tool: &str) -> ToolServices {
+        let mut services = ToolServices::new(tool);
+        if let Some(hooks) = &self.hooks {
+            services = services.with_hooks(Arc::clone(hooks));
+        }
+        if let Some(token) = &self.cancellation {
+            services = services.with_cancellation(token.clone());
+        }
+        if let Some(capabilities) = &self.capabilities {
+            services = services.with_capabilities(capabilities.clone());
+        }
+        services
+    }
+
+    /// The hooks' clock, or the system clock without hooks.
+    fn clock(&self) -> Arc<dyn Clock> {
+        self.hooks
+            .as_ref()
+            .map_or_else(clock::system, |hooks| hooks.clock())
+    }
+
+    async fn race(
+        &self,
+        tool: &dyn Tool,
+        call_id: &str,
+        input: Value,
+        context: ToolContext,
+        timeout: Option<Duration>,
+    ) -> Result<ToolResult, ToolError> {
+        enum Stop {
+            Finished(Option<Result<ToolResult, ToolError>>),
+            TimedOut(Duration),
+            Cancelled,
+        }
+
+        let mut partial_output = None;
+        let stop = {
+            let drive = async {
+                let mut updates = tool.execute_streaming(input, context);
+                while let Some(update) = updates.next().await {
+                    match update {
+                        ToolUpdate::Progress(progress) => {
+                            if let Some(display) = &self.display {
+                                if let Err(e) = display.show_progress(tool.name(), &progress).await
+                                {
+                                    log::warn!(
+                                        "Failed to display progress of tool '{}': {e}",
+                                        tool.name()
+                                    );
+                                }
+                            }
+                            if progress.partial_output.is_some() {
+                                partial_output = progress.partial_output;
+                            }
+                        }
+                        ToolUpdate::Finished(result) => return Some(result),
+                    }
+                }
+                None
+            };
+            let clock = self.clock();
+            let timer = async {
+                match timeout {
+                    Some(timeout) => clock.sleep(timeout).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let cancelled = async {
+                match &self.cancellation {
+                    Some(token) => token.cancelled_immediately().await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                biased;
+                _ = cancelled => Stop::Cancelled,
+                result = drive => Stop::Finished(result),
+                _ = timer => Stop::TimedOut(timeout.unwrap_or_default()),
+            }
+        };
+
+        match stop {
+            Stop::Finished(Some(result)) => result,
+            Stop::Finished(None) => Err(ToolError::Other {
+                message: format!("tool {} ended its stream without a result", tool.name()),
+            }),
+            Stop::TimedOut(timeout) => {
+                let timeout_ms = timeout.as_millis() as u64;
+                log::warn!("Tool '{}' timed out after {timeout_ms} ms", tool.name());
+                if let Some(hooks) = &self.hooks {
+                    hooks
+                        .emit(
+                            events::TOOL_TIMEOUT,
+                            serde_json::json!({
+                                "tool_name": tool.name(),
+                                "tool_call_id": call_id,
+                                "timeout_ms": timeout_ms,
+                                "partial_output": partial_output,
+                            }),
+                        )
+                        .await;
+                }
+                Err(ToolError::Timeout {
+                    name: tool.name().to_string(),
+                    timeout_ms,
+                    partial_output,
+                })
+            }
+            Stop::Cancelled => Err(ToolError::Cancelled {
+                name: tool.name().to_string(),
+                partial_output,
+            }),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use crate::messages::ToolSpec;
+    use crate::models::ToolProgress;
+    use crate::testing::{EchoTool, FakeHookHandler, ManualClock};
+    use crate::tool_progress::{with_progress, ToolUpdateStream};
+
+    /// A tool that counts its executions.
+    #[derive(Default)]
+    struct CountingTool {
+        runs: AtomicUsize,
+        fail: bool,
+        pure: bool,
+    }
+
+    impl Tool for CountingTool {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn description(&self) -> &str {
+            "counts executions"
+        }
+
+        fn get_spec(&self) -> ToolSpec {
+            let mut spec = EchoTool.get_spec();
+            if self.pure {
+                spec.extensions
+                    .insert("pure".to_string(), serde_json::json!(true));
+            }
+            spec
+        }
+
+        fn execute(
+            &self,
+            input: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst) + 1;
+            let fail = self.fail;
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                if fail {
+                    return Err(ToolError::Other {
+                        message: "boom".into(),
+                    });
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output: Some(serde_json::json!({"run": run, "input": input})),
+                    error: None,
+                    ..Default::default()
+                })
+            })
+        }
+    }
+
+    /// Reports partial output, then hangs; its spec sets a 10 s timeout.
+    struct HangingTool;
+
+    impl Tool for HangingTool {
+        fn name(&self) -> &str {
+            "hanging"
+        }
+
+        fn description(&self) -> &str {
+            "never finishes"
+        }
+
+        fn get_spec(&self) -> ToolSpec {
+            ToolSpec {
+                name: "hanging".into(),
+                parameters: HashMap::new(),
+                description: None,
+                extensions: HashMap::from([("timeout".to_string(), serde_json::json!(10))]),
+            }
+        }
+
+        fn execute(
+            &self,
+            _input: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            Box::pin(std::future::pending())
+        }
+
+        fn execute_streaming(&self, _input: Value, _context: ToolContext) -> ToolUpdateStream<'_> {
+            with_progress(|progress| async move {
+                progress.report(ToolProgress::partial("line 1"));
+                progress.report(ToolProgress::message("still going"));
+                std::future::pending().await
+            })
+        }
+    }
+
+    fn call(id: &str) -> ToolCall {
+        ToolCall {
+            id: id.into(),
+            name: "counting".into(),
+            arguments: HashMap::from([("x".to_string(), serde_json::json!(1))]),
+            extensions: HashMap::new(),
+        }
+    }
+
+    fn executor() -> ToolExecutor {
+        ToolExecutor::new()
+            .with_cache(Arc::new(ToolResultCache::new()))
+            .with_scope("s1", 0)
+    }
+
+    #[tokio::test]
+    async fn duplicate_call_returns_cached_result() {
+        let tool = CountingTool::default();
+        let executor = executor();
+        let first = executor.execute_call(&tool, &call("c1")).await.unwrap();
+        let again = executor.execute_call(&tool, &call("c1")).await.unwrap();
+        assert_eq!(first, again);
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 1);
+
+        executor.execute_call(&tool, &call("c2")).await.unwrap();
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_duplicates_run_once() {
+        let tool = CountingTool::default();
+        let executor = executor();
+        let call = call("c1");
+        let (a, b) = tokio::join!(
+            executor.execute_call(&tool, &call),
+            executor.execute_call(&tool, &call),
+        );
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn errors_and_empty_ids_are_not_memoized() {
+        let failing = CountingTool {
+            fail: true,
+            ..Default::default()
+        };
+        let executor = executor();
+        assert!(executor.execute_call(&failing, &call("c1")).await.is_err());
+        assert!(executor.execute_call(&failing, &call("c1")).await.is_err());
+        assert_eq!(failing.runs.load(Ordering::SeqCst), 2);
+
+        let tool = CountingTool::default();
+        executor.execute_call(&tool, &call("")).await.unwrap();
+        executor.execute_call(&tool, &call("")).await.unwrap();
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn calls_run_in_a_correlation_scope() {
+        /// Reports the correlation IDs it runs under.
+        struct IdsTool;
+
+        impl Tool for IdsTool {
+            fn name(&self) -> &str {
+                "ids"
+            }
+
+            fn description(&self) -> &str {
+                "reports correlation ids"
+            }
+
+            fn get_spec(&self) -> ToolSpec {
+                EchoTool.get_spec()
+            }
+
+            fn execute(
+                &self,
+                _input: Value,
+            ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>>
+            {
+                Box::pin(async move {
+                    Ok(ToolResult {
+                        success: true,
+                        output: Some(serde_json::to_value(correlation::current()).unwrap()),
+                        error: None,
+                        ..Default::default()
+                    })
+                })
+            }
+        }
+
+        let executor = ToolExecutor::new().with_turn_id(Some("t1".into()));
+        let result = executor.execute_call(&IdsTool, &call("c1")).await.unwrap();
+        assert_eq!(
+            result.output,
+            Some(serde_json::json!({"turn_id": "t1", "tool_call_id": "c1"}))
+        );
+        let result = executor.execute_call(&IdsTool, &call("")).await.unwrap();
+        assert_eq!(result.output, Some(serde_json::json!({"turn_id": "t1"})));
+    }
+
+    #[tokio::test]
+    async fn tools_emit_and_read_capabilities_through_their_context() {
+        /// Emits `tool:reporter:done` with the `root` capability.
+        struct ReporterTool;
+
+        impl Tool for ReporterTool {
+            fn name(&self) -> &str {
+                "reporter"
+            }
+
+            fn description(&self) -> &str {
+                "emits an event"
+            }
+
+            fn get_spec(&self) -> ToolSpec {
+                EchoTool.get_spec()
+            }
+
+            fn execute(
+                &self,
+                input: Value,
+            ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>>
+            {
+                self.execute_with_context(input, ToolContext::default())
+            }
+
+            fn execute_with_context(
+                &self,
+                _input: Value,
+                context: ToolContext,
+            ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>>
+            {
+                Box::pin(async move {
+                    let root = context.capability("root").unwrap_or_default();
+                    context
+                        .emit("done", serde_json::json!({"root": root}))
+                        .await;
+                    Ok(ToolResult::new(true, None, None))
+                })
+            }
+        }
+
+        let coord = Coordinator::new_for_test();
+        coord.register_capability("root", serde_json::json!("/srv"));
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = coord
+            .hooks()
+            .register("tool:reporter:done", handler.clone(), 0, None);
+
+        let executor = ToolExecutor::from_coordinator(&coord);
+        executor
+            .execute_call(&ReporterTool, &call("c1"))
+            .await
+            .unwrap();
+        let events = handler.recorded_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].1["root"], "/srv");
+        assert_eq!(events[0].1["tool_call_id"], "c1");
+    }
+
+    #[tokio::test]
+    async fn coordinator_turn_scopes_memoization() {
+        let coord = Coordinator::new_for_test();
+        coord
+            .hooks()
+            .set_default_fields(serde_json::json!({"session_id": "sess"}));
+        let tool = CountingTool::default();
+
+        let executor = ToolExecutor::from_coordinator(&coord);
+        assert_eq!(executor.key("c1").unwrap().to_string(), "sess/0/c1");
+        executor.execute_call(&tool, &call("c1")).await.unwrap();
+        ToolExecutor::from_coordinator(&coord)
+            .execute_call(&tool, &call("c1"))
+            .await
+            .unwrap();
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 1);
+        assert_eq!(coord.tool_results().len(), 1);
+
+        coord.reset_turn();
+        assert!(coord.tool_results().is_empty());
+        let executor = ToolExecutor::from_coordinator(&coord);
+        assert_eq!(executor.key("c1").unwrap().turn, 1);
+        executor.execute_call(&tool, &call("c1")).await.unwrap();
+        assert_eq!(tool.runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn coordinator_output_processing_is_applied() {
+        let coord = Coordinator::new(HashMap::from([(
+            "session".to_string(),
+            serde_json::json!({"tool_output": {"max_bytes": 16}}),
+        )]));
+        let observer = Arc::new(crate::testing::FakeHookHandler::new());
+        let _ = coord.hooks().register(
+            crate::events::TOOL_RESULT_TRANSFORM,
+            observer.clone(),
+            0,
+            None,
+        );
+        let tool = CountingTool::default();
+
+        let result = ToolExecutor::from_coordinator(&coord)
+            .execute_call(&tool, &call("c1"))
+            .await
+            .unwrap();
+        let output = result.output.unwrap();
+        assert!(output.as_str().unwrap().contains("bytes truncated"));
+        let seen = observer.recorded_events();
+        assert_eq!(seen[0].1["tool_name"], "counting");
+        assert_eq!(seen[0].1["tool_result"]["output"]["run"], 1);
+    }
+
+    #[tokio::test]
+    async fn large_outputs_are_offloaded_once() {
+        let coord = Coordinator::new_for_test();
+        let store = Arc::new(AttachmentStore::in_memory().with_threshold(8));
+        coord.set_attachment_store(Some(Arc::clone(&store)));
+        let tool = CountingTool::default();
+
+        let executor = ToolExecutor::from_coordinator(&coord);
+        let first = executor.execute_call(&tool, &call("c1")).await.unwrap();
+        let again = executor.execute_call(&tool, &call("c1")).await.unwrap();
+        assert_eq!(first, again);
+
+        let mut output = first.output.unwrap();
+        assert_eq!(output["type"], "attachment");
+        assert_eq!(store.rehydrate(&mut output).await.unwrap(), 1);
+        assert_eq!(output["run"], 1);
+    }
+
+    #[tokio::test]
+    async fn tool_timeout_keeps_partial_output() {
+        let clock = ManualClock::default();
+        let hooks = Arc::new(HookRegistry::new());
+        hooks.set_clock(Arc::new(clock.clone()));
+        let observer = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::TOOL_TIMEOUT, observer.clone(), 0, None);
+        let executor = ToolExecutor::new()
+            .with_cancellation(CancellationToken::new())
+            .with_hooks(Arc::clone(&hooks));
+
+        let advance = async {
+            tokio::task::yield_now().await;
+            clock.advance(Duration::from_secs(11));
+        };
+        let hanging = call("c1");
+        let (result, ()) = tokio::join!(executor.execute_call(&HangingTool, &hanging), advance);
+
+        match result.unwrap_err() {
+            ToolError::Timeout {
+                timeout_ms,
+                partial_output,
+                ..
+            } => {
+                assert_eq!(timeout_ms, 10_000);
+                assert_eq!(partial_output, Some(serde_json::json!("line 1")));
+            }
+            other => panic!("expected a timeout, got {other:?}"),
+        }
+        let seen = observer.recorded_events();
+        assert_eq!(seen[0].1["tool_call_id"], "c1");
+        assert_eq!(seen[0].1["partial_output"], "line 1");
+    }
+
+    #[tokio::test]
+    async fn immediate_cancellation_stops_the_tool() {
+        let token = CancellationToken::new();
+        let executor = ToolExecutor::new().with_cancellation(token.clone());
+
+        token.request_graceful();
+        let finished = executor
+            .execute_call(&CountingTool::default(), &call("c1"))
+            .await;
+        assert!(finished.is_ok(), "graceful cancellation lets tools finish");
+
+        let cancel = async {
+            tokio::task::yield_now().await;
+            token.request_immediate();
+        };
+        let hanging = call("c2");
+        let (result, ()) = tokio::join!(executor.execute_call(&HangingTool, &hanging), cancel);
+        let err = result.unwrap_err();
+        assert_eq!(err.code(), "tool.cancelled");
+        assert!(matches!(
+            err,
+            ToolError::Cancelled { partial_output: Some(ref o), .. } if o == "line 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn pure_tools_are_cached_across_call_ids() {
+        use crate::tool_cache::PureToolCacheConfig;
+
+        let clock = ManualClock::default();
+        let hooks = Arc::new(HookRegistry::new());
+        hooks.set_clock(Arc::new(clock.clone()));
+        let pure_cache = Arc::new(PureToolCache::new(PureToolCacheConfig {
+            ttl_secs: 60,
+            ..Default::default()
+        }));
+        let executor = executor()
+            .with_hooks(hooks)
+            .with_pure_cache(Some(pure_cache.clone()));
+        let pure = CountingTool {
+            pure: true,
+            ..Default::default()
+        };
+
+        let first = executor.execute_call(&pure, &call("c1")).await.unwrap();
+        let second = executor.execute_call(&pure, &call("c2")).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(pure.runs.load(Ordering::SeqCst), 1);
+
+        // Different arguments miss.
+        let mut other = call("c3");
+        other.arguments.insert("x".into(), serde_json::json!(2));
+        executor.execute_call(&pure, &other).await.unwrap();
+        assert_eq!(pure.runs.load(Ordering::SeqCst), 2);
+
+        // Entries expire.
+        clock.advance(Duration::from_secs(60));
+        executor.execute_call(&pure, &call("c4")).await.unwrap();
+        assert_eq!(pure.runs.load(Ordering::SeqCst), 3);
+
+        // A tool that is not pure may have changed what pure tools read.
+        executor
+            .execute_call(&CountingTool::default(), &call("c5"))
+            .await
+            .unwrap();
+        assert!(pure_cache.is_empty());
+        executor.execute_call(&pure, &call("c6")).await.unwrap();
+        assert_eq!(pure.runs.load(Ordering::SeqCst), 4);
+    }
+}
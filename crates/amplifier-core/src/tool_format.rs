@@ -0,0 +1,270 @@
+//! Tool output format negotiation.
+//!
+//! A tool advertises the formats it can produce under the `output_formats`
+//! key of its [`ToolSpec`] (a list of `"text"`, `"json"`, `"markdown"`). A
+//! caller states its preference in [`ToolContext::output_format`];
+//! [`execute_negotiated`] settles the format, hands it to the tool via
+//! [`Tool::execute_with_context`], and checks the returned [`ToolResult`]:
+//!
+//! | Negotiated | Accepted `output`                                    |
+//! |------------|------------------------------------------------------|
+//! | `text`     | a string                                             |
+//! | `markdown` | a string                                             |
+//! | `json`     | any non-string value; a string holding JSON is parsed |
+//!
+//! `null` output and failed results are never rejected. Tools that advertise
+//! nothing are not negotiated: the context's format is cleared and the
+//! result is passed through unchanged.
+
+use serde_json::Value;
+
+use crate::deadline::{self, TurnDeadline};
+use crate::errors::ToolError;
+use crate::messages::ToolSpec;
+use crate::models::{ToolContext, ToolOutputFormat, ToolResult};
+use crate::traits::Tool;
+
+/// `ToolSpec` extension key listing supported output formats.
+pub const OUTPUT_FORMATS_KEY: &str = "output_formats";
+
+/// Formats advertised by `spec`, in the tool's order of preference.
+///
+/// Unknown entries are skipped; an absent or malformed key yields an empty
+/// list.
+pub fn advertised_formats(spec: &ToolSpec) -> Vec<ToolOutputFormat> {
+    match spec.extensions.get(OUTPUT_FORMATS_KEY) {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| serde_json::from_value(item.clone()).ok())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Set the advertised formats on `spec`.
+pub fn set_advertised_formats(spec: &mut ToolSpec, formats: &[ToolOutputFormat]) {
+    spec.extensions.insert(
+        OUTPUT_FORMATS_KEY.to_string(),
+        serde_json::to_value(formats).unwrap_or_default(),
+    );
+}
+
+/// Pick the output format for a call.
+///
+/// The caller's `preferred` format wins if the tool supports it; otherwise
+/// the tool's first advertised format is used. Returns `None` when the tool
+/// advertises no formats.
+pub fn negotiate(spec: &ToolSpec, preferred: Option<ToolOutputFormat>) -> Option<ToolOutputFormat> {
+    let supported = advertised_formats(spec);
+    preferred
+        .filter(|format| supported.contains(format))
+        .or_else(|| supported.first().copied())
+}
+
+/// Check `result` against `format`, normalizing JSON carried as a string.
+///
+/// Returns the (possibly normalized) result, or a description of the
+/// mismatch.
+pub fn conform(format: ToolOutputFormat, mut result: ToolResult) -> Result<ToolResult, String> {
+    if !result.success {
+        return Ok(result);
+    }
+    match (format, result.output.take()) {
+        (_, None) | (_, Some(Value::Null)) => {}
+        (ToolOutputFormat::Text | ToolOutputFormat::Markdown, Some(Value::String(s))) => {
+            result.output = Some(Value::String(s));
+        }
+        (ToolOutputFormat::Text | ToolOutputFormat::Markdown, Some(other)) => {
+            return Err(format!("expected a string, got {}", json_kind(&other)));
+        }
+        (ToolOutputFormat::Json, Some(Value::String(s))) => {
+            let parsed = serde_json::from_str(&s)
+                .map_err(|e| format!("expected JSON, got a string that does not parse: {e}"))?;
+            result.output = Some(parsed);
+        }
+        (ToolOutputFormat::Json, Some(other)) => result.output = Some(other),
+    }
+    Ok(result)
+}
+
+/// Execute `tool` with a negotiated output format, bounded by `deadline`.
+///
+/// # Errors
+///
+/// - [`ToolError::InvalidOutput`] if the result does not match the
+///   negotiated format
+/// - Everything [`deadline::execute_tool`] can return
+pub async fn execute_negotiated(
+    deadline: Option<TurnDeadline>,
+    tool: &dyn Tool,
+    input: Value,
+    mut context: ToolContext,
+) -> Result<ToolResult, ToolError> {
+    context.output_format = negotiate(&tool.get_spec(), context.output_format);
+    let format = context.output_format;
+
+    let result =
+        deadline::run_tool(deadline, tool, tool.execute_with_context(input, context)).await?;
+    match format {
+        Some(format) => conform(format, result).map_err(|message| ToolError::InvalidOutput {
+            name: tool.name().to_string(),
+            format: format.as_str().to_string(),
+            message,
+        }),
+        None => Ok(result),
+    }
+}
+
+fn json_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    use serde_json::json;
+
+    use crate::testing::EchoTool;
+
+    fn spec_with(formats: &[ToolOutputFormat]) -> ToolSpec {
+        let mut spec = EchoTool.get_spec();
+        set_advertised_formats(&mut spec, formats);
+        spec
+    }
+
+    /// A tool that renders the same data in whichever format it is asked for.
+    struct ReportTool;
+
+    impl Tool for ReportTool {
+        fn name(&self) -> &str {
+            "report"
+        }
+
+        fn description(&self) -> &str {
+            "renders a report"
+        }
+
+        fn get_spec(&self) -> ToolSpec {
+            spec_with(&[ToolOutputFormat::Markdown, ToolOutputFormat::Json])
+        }
+
+        fn execute(
+            &self,
+            input: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            self.execute_with_context(input, ToolContext::default())
+        }
+
+        fn execute_with_context(
+            &self,
+            _input: Value,
+            context: ToolContext,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            let output = match context.output_format {
+                Some(ToolOutputFormat::Json) => json!({"passed": 3}),
+                _ => json!("**3 passed**"),
+            };
+            Box::pin(async move { Ok(ToolResult::new(true, Some(output), None)) })
+        }
+    }
+
+    #[test]
+    fn negotiation_prefers_caller_then_tool_order() {
+        let spec = spec_with(&[ToolOutputFormat::Markdown, ToolOutputFormat::Json]);
+        assert_eq!(advertised_formats(&spec).len(), 2);
+        assert_eq!(
+            negotiate(&spec, Some(ToolOutputFormat::Json)),
+            Some(ToolOutputFormat::Json)
+        );
+        assert_eq!(
+            negotiate(&spec, Some(ToolOutputFormat::Text)),
+            Some(ToolOutputFormat::Markdown)
+        );
+        assert_eq!(negotiate(&spec, None), Some(ToolOutputFormat::Markdown));
+        assert_eq!(
+            negotiate(&EchoTool.get_spec(), Some(ToolOutputFormat::Json)),
+            None
+        );
+    }
+
+    #[test]
+    fn conform_checks_and_normalizes_output() {
+        let ok = |output: Value| ToolResult::new(true, Some(output), None);
+
+        let parsed = conform(ToolOutputFormat::Json, ok(json!("{\"a\": 1}"))).unwrap();
+        assert_eq!(parsed.output, Some(json!({"a": 1})));
+        assert!(conform(ToolOutputFormat::Json, ok(json!("not json"))).is_err());
+        assert!(conform(ToolOutputFormat::Text, ok(json!("hi"))).is_ok());
+        let err = conform(ToolOutputFormat::Markdown, ok(json!({"a": 1}))).unwrap_err();
+        assert!(err.contains("an object"), "{err}");
+
+        // Failures and empty output are passed through.
+        let failed = ToolResult::new(false, Some(json!({"a": 1})), None);
+        assert!(conform(ToolOutputFormat::Text, failed).is_ok());
+        assert!(conform(ToolOutputFormat::Text, ToolResult::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn execute_negotiated_hands_format_to_tool() {
+        let context = ToolContext {
+            output_format: Some(ToolOutputFormat::Json),
+            ..Default::default()
+        };
+        let result = execute_negotiated(None, &ReportTool, json!({}), context)
+            .await
+            .unwrap();
+        assert_eq!(result.output, Some(json!({"passed": 3})));
+
+        let result = execute_negotiated(None, &ReportTool, json!({}), ToolContext::default())
+            .await
+            .unwrap();
+        assert_eq!(result.output, Some(json!("**3 passed**")));
+    }
+
+    #[tokio::test]
+    async fn execute_negotiated_rejects_mismatched_output() {
+        /// Advertises text but returns an object.
+        struct Liar;
+
+        impl Tool for Liar {
+            fn name(&self) -> &str {
+                "liar"
+            }
+
+            fn description(&self) -> &str {
+                "says text, returns JSON"
+            }
+
+            fn get_spec(&self) -> ToolSpec {
+                spec_with(&[ToolOutputFormat::Text])
+            }
+
+            fn execute(
+                &self,
+                _input: Value,
+            ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>>
+            {
+                Box::pin(async { Ok(ToolResult::new(true, Some(json!({"a": 1})), None)) })
+            }
+        }
+
+        let err = execute_negotiated(None, &Liar, json!({}), ToolContext::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidOutput { ref format, .. } if format == "text"));
+    }
+}
@@ -0,0 +1,374 @@
+//! Tool output post-processing.
+//!
+//! A single `cat largefile` can fill the context window. Kernel-side tool
+//! calls made through [`ToolExecutor`](crate::tool_executor::ToolExecutor)
+//! pass every [`ToolResult`] through a [`ToolOutputProcessor`] before it is
+//! returned to the orchestrator (and so before it reaches the context):
+//!
+//! 1. **Binary detection** — a string output that looks binary (NUL bytes,
+//!    or mostly control / replacement characters) is replaced by a short
+//!    `[binary output omitted: N bytes]` note.
+//! 2. **`tool:result:transform`** — emitted with
+//!    `{tool_name, tool_input, tool_result}`. A hook returning `Modify` with a
+//!    new `tool_result` replaces the result, e.g. to summarize it. A
+//!    replacement that does not deserialize into a `ToolResult` is logged and
+//!    ignored.
+//! 3. **Truncation** — an output larger than `max_bytes` keeps its head and
+//!    tail around a `[… N bytes truncated …]` marker. Non-string outputs are
+//!    measured (and, if needed, truncated) as JSON text.
+//!
+//! Settings come from `session.tool_output`:
+//!
+//! ```json
+//! {"session": {"tool_output": {"max_bytes": 65536, "head_ratio": 0.7, "detect_binary": true}}}
+//! ```
+//!
+//! `max_bytes: 0` disables truncation. Truncation runs before
+//! [attachment offloading](crate::attachments), so only the truncated output
+//! is stored.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::models::ToolResult;
+
+/// Default output size limit (256 KiB).
+pub const DEFAULT_MAX_BYTES: usize = 256 * 1024;
+
+/// Bytes inspected by binary detection.
+const BINARY_SAMPLE_BYTES: usize = 8 * 1024;
+
+/// Share of suspicious characters in the sample above which text is binary.
+const BINARY_RATIO: f64 = 0.1;
+
+// ---------------------------------------------------------------------------
+// ToolOutputConfig
+// ---------------------------------------------------------------------------
+
+/// The `session.tool_output` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolOutputConfig {
+    /// Largest output kept whole, in bytes; `0` disables truncation.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+    /// Fraction of the kept bytes taken from the start of the output; the
+    /// rest comes from the end.
+    #[serde(default = "default_head_ratio")]
+    pub head_ratio: f64,
+    /// Replace binary-looking string outputs with a note.
+    #[serde(default = "default_true")]
+    pub detect_binary: bool,
+}
+
+fn default_max_bytes() -> usize {
+    DEFAULT_MAX_BYTES
+}
+
+fn default_head_ratio() -> f64 {
+    0.5
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ToolOutputConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: default_max_bytes(),
+            head_ratio: default_head_ratio(),
+            detect_binary: true,
+        }
+    }
+}
+
+impl ToolOutputConfig {
+    /// Read `session.tool_output` from a mount plan, falling back to the
+    /// defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("tool_output")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.tool_output config: {e}");
+            Self::default()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ToolOutputProcessor
+// ---------------------------------------------------------------------------
+
+/// Applies the post-processing stages to tool results.
+#[derive(Debug, Clone, Default)]
+pub struct ToolOutputProcessor {
+    config: ToolOutputConfig,
+}
+
+impl ToolOutputProcessor {
+    pub fn new(config: ToolOutputConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &ToolOutputConfig {
+        &self.config
+    }
+
+    /// Run all stages on `result`, emitting `tool:result:transform` on
+    /// `hooks`.
+    pub async fn process(
+        &self,
+        hooks: &HookRegistry,
+        tool_name: &str,
+        input: &Value,
+        result: ToolResult,
+    ) -> ToolResult {
+        let result = self.replace_binary(result);
+        let result = transform(hooks, tool_name, input, result).await;
+        self.truncate(result)
+    }
+
+    /// Stage 1: replace a binary-looking string output with a note.
+    pub fn replace_binary(&self, mut result: ToolResult) -> ToolResult {
+        if !self.config.detect_binary {
+            return result;
+        }
+        if let Some(Value::String(text)) = &result.output {
+            if looks_binary(text) {
+                let note = format!("[binary output omitted: {} bytes]", text.len());
+                result.output = Some(Value::String(note));
+            }
+        }
+        result
+    }
+
+    /// Stage 3: cut an output larger than `max_bytes` down to its head and
+    /// tail.
+    pub fn truncate(&self, mut result: ToolResult) -> ToolResult {
+        let max = self.config.max_bytes;
+        if max == 0 {
+            return result;
+        }
+        let truncated = match &result.output {
+            None | Some(Value::Null) => None,
+            Some(Value::String(text)) => truncate_middle(text, max, self.config.head_ratio),
+            Some(other) => truncate_middle(&other.to_string(), max, self.config.head_ratio),
+        };
+        if let Some(text) = truncated {
+            result.output = Some(Value::String(text));
+        }
+        result
+    }
+}
+
+/// Stage 2: offer `result` to `tool:result:transform` hooks.
+async fn transform(
+    hooks: &HookRegistry,
+    tool_name: &str,
+    input: &Value,
+    result: ToolResult,
+) -> ToolResult {
+    let payload = serde_json::json!({
+        "tool_name": tool_name,
+        "tool_input": input,
+        "tool_result": result,
+    });
+    let outcome = hooks.emit(events::TOOL_RESULT_TRANSFORM, payload).await;
+    let Some(replacement) = outcome.data.as_ref().and_then(|d| d.get("tool_result")) else {
+        return result;
+    };
+    match serde_json::from_value(replacement.clone()) {
+        Ok(transformed) => transformed,
+        Err(e) => {
+            log::warn!(
+                "Ignoring malformed 'tool_result' from transform hook for '{tool_name}': {e}"
+            );
+            result
+        }
+    }
+}
+
+/// Whether `text` looks like decoded binary data: it contains a NUL, or
+/// more than 10% of its first 8 KiB are control characters (other than
+/// whitespace) or U+FFFD replacement characters.
+pub fn looks_binary(text: &str) -> bool {
+    let sample = &text[..floor_char_boundary(text, BINARY_SAMPLE_BYTES)];
+    let mut total = 0usize;
+    let mut suspicious = 0usize;
+    for c in sample.chars() {
+        if c == '\0' {
+            return true;
+        }
+        total += 1;
+        if c == '\u{FFFD}' || (c.is_control() && !matches!(c, '\n' | '\r' | '\t')) {
+            suspicious += 1;
+        }
+    }
+    total > 0 && suspicious as f64 / total as f64 > BINARY_RATIO
+}
+
+/// `text` cut to at most about `max_bytes`, keeping `head_ratio` of the
+/// budget from the start and the rest from the end around a marker naming
+/// the bytes removed. `None` when `text` already fits.
+pub fn truncate_middle(text: &str, max_bytes: usize, head_ratio: f64) -> Option<String> {
+    if text.len() <= max_bytes {
+        return None;
+    }
+    let head_bytes = (max_bytes as f64 * head_ratio.clamp(0.0, 1.0)) as usize;
+    let head_end = floor_char_boundary(text, head_bytes);
+    let tail_start = ceil_char_boundary(text, text.len() - (max_bytes - head_bytes));
+    let removed = tail_start - head_end;
+    Some(format!(
+        "{}\n[… {removed} bytes truncated …]\n{}",
+        &text[..head_end],
+        &text[tail_start..]
+    ))
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::models::{HookAction, HookResult};
+    use crate::testing::FakeHookHandler;
+
+    fn text_result(text: &str) -> ToolResult {
+        ToolResult {
+            success: true,
+            output: Some(Value::String(text.into())),
+            error: None,
+            ..Default::default()
+        }
+    }
+
+    fn processor(config: Value) -> ToolOutputProcessor {
+        ToolOutputProcessor::new(serde_json::from_value(config).unwrap())
+    }
+
+    #[test]
+    fn truncation_keeps_head_and_tail() {
+        let text = format!("{}{}", "a".repeat(600), "z".repeat(400));
+        let cut = truncate_middle(&text, 100, 0.7).unwrap();
+        assert!(cut.starts_with(&"a".repeat(70)));
+        assert!(cut.ends_with(&"z".repeat(30)));
+        assert!(cut.contains("[… 900 bytes truncated …]"));
+        assert!(truncate_middle("short", 100, 0.5).is_none());
+
+        // Cuts never split a multi-byte character.
+        let cut = truncate_middle(&"é".repeat(100), 11, 0.5).unwrap();
+        assert!(cut.starts_with("éé\n"));
+    }
+
+    #[test]
+    fn binary_detection() {
+        assert!(looks_binary("PNG\0\u{1}\u{2}"));
+        assert!(looks_binary(&"\u{FFFD}\u{7}x".repeat(50)));
+        assert!(!looks_binary("line one\n\tline two\r\n"));
+        assert!(!looks_binary(""));
+
+        let processed = processor(serde_json::json!({})).replace_binary(text_result("a\0b"));
+        assert_eq!(
+            processed.output,
+            Some(Value::String("[binary output omitted: 3 bytes]".into()))
+        );
+        let disabled = processor(serde_json::json!({"detect_binary": false}));
+        assert_eq!(
+            disabled.replace_binary(text_result("a\0b")),
+            text_result("a\0b")
+        );
+    }
+
+    #[test]
+    fn json_outputs_are_measured_as_text() {
+        let limited = processor(serde_json::json!({"max_bytes": 20}));
+        let result = ToolResult {
+            success: true,
+            output: Some(serde_json::json!({"lines": vec!["x"; 20]})),
+            error: None,
+            ..Default::default()
+        };
+        let output = limited.truncate(result).output.unwrap();
+        assert!(output.as_str().unwrap().contains("bytes truncated"));
+
+        let unlimited = processor(serde_json::json!({"max_bytes": 0}));
+        let long = "y".repeat(DEFAULT_MAX_BYTES * 2);
+        assert_eq!(unlimited.truncate(text_result(&long)), text_result(&long));
+    }
+
+    #[tokio::test]
+    async fn transform_hook_runs_before_truncation() {
+        let hooks = HookRegistry::new();
+        let summarizer = Arc::new(FakeHookHandler::with_result(HookResult {
+            action: HookAction::Modify,
+            data: Some(HashMap::from([(
+                "tool_result".to_string(),
+                serde_json::json!({"success": true, "output": "summary ".repeat(10)}),
+            )])),
+            ..Default::default()
+        }));
+        let _ = hooks.register(events::TOOL_RESULT_TRANSFORM, summarizer.clone(), 0, None);
+
+        let processor = processor(serde_json::json!({"max_bytes": 40}));
+        let input = serde_json::json!({"path": "big.log"});
+        let result = processor
+            .process(
+                &hooks,
+                "read_file",
+                &input,
+                text_result(&"x".repeat(10_000)),
+            )
+            .await;
+
+        let seen = summarizer.recorded_events();
+        assert_eq!(seen[0].1["tool_name"], "read_file");
+        assert_eq!(
+            seen[0].1["tool_result"]["output"].as_str().unwrap().len(),
+            10_000
+        );
+        let output = result.output.unwrap();
+        assert!(output.as_str().unwrap().starts_with("summary summary"));
+        assert!(output.as_str().unwrap().contains("bytes truncated"));
+    }
+
+    #[test]
+    fn config_defaults_apply_when_section_missing() {
+        assert_eq!(
+            ToolOutputConfig::from_session_config(&HashMap::new()),
+            ToolOutputConfig::default()
+        );
+        let config: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "session": {"tool_output": {"max_bytes": 1024}}
+        }))
+        .unwrap();
+        let parsed = ToolOutputConfig::from_session_config(&config);
+        assert_eq!(parsed.max_bytes, 1024);
+        assert!(parsed.detect_binary);
+    }
+}
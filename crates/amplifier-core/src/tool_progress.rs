@@ -0,0 +1,388 @@
+//! Tool progress streaming.
+//!
+//! [`Tool::execute_streaming`] yields a [`ToolUpdateStream`]: any number of
+//! [`ToolUpdate::Progress`] items followed by exactly one
+//! [`ToolUpdate::Finished`]. The default implementation wraps
+//! [`Tool::execute_with_context`] and reports no progress, so every tool can
+//! be driven this way.
+//!
+//! Tool authors rarely build a stream by hand; [`with_progress`] turns an
+//! async body that receives a [`ProgressReporter`] into one:
+//!
+//! ```rust
+//! use amplifier_core::models::{ToolProgress, ToolResult};
+//! use amplifier_core::tool_progress::{with_progress, ToolUpdateStream};
+//!
+//! fn scan<'a>(files: Vec<String>) -> ToolUpdateStream<'a> {
+//!     with_progress(move |progress| async move {
+//!         let total = files.len() as f64;
+//!         for (i, _file) in files.iter().enumerate() {
+//!             progress.report(ToolProgress::step(i as f64 + 1.0, total));
+//!         }
+//!         Ok(ToolResult::default())
+//!     })
+//! }
+//! ```
+//!
+//! [`execute_with_progress`] drives a tool's stream for the kernel, emitting
+//! each progress item as a `tool:progress` hook event:
+//!
+//! | Key            | Value                                   |
+//! |----------------|-----------------------------------------|
+//! | `tool_name`    | [`Tool::name`]                          |
+//! | `tool_call_id` | [`ToolContext::tool_call_id`] (if set)  |
+//! | `progress`     | the [`ToolProgress`]                    |
+//! | `fraction`     | [`ToolProgress::fraction`] (if known)   |
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::deadline::{self, TurnDeadline};
+use crate::errors::ToolError;
+use crate::events;
+use crate::hooks::HookRegistry;
+use crate::models::{ToolContext, ToolProgress, ToolResult};
+use crate::traits::Tool;
+
+/// One item of a tool's update stream.
+#[derive(Debug)]
+pub enum ToolUpdate {
+    /// The tool is still running.
+    Progress(ToolProgress),
+    /// The tool finished; the stream ends after this item.
+    Finished(Result<ToolResult, ToolError>),
+}
+
+/// The stream returned by [`Tool::execute_streaming`].
+pub type ToolUpdateStream<'a> = Pin<Box<dyn Stream<Item = ToolUpdate> + Send + 'a>>;
+
+// ---------------------------------------------------------------------------
+// Building streams
+// ---------------------------------------------------------------------------
+
+/// Handle a tool body uses to report progress. Cheap to clone.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    tx: mpsc::UnboundedSender<ToolProgress>,
+}
+
+impl ProgressReporter {
+    /// Report progress. Never blocks; reports after the stream is dropped
+    /// are discarded.
+    pub fn report(&self, progress: ToolProgress) {
+        let _ = self.tx.send(progress);
+    }
+}
+
+/// Build a [`ToolUpdateStream`] from an async body that reports progress.
+///
+/// Progress is yielded in report order, always before the final
+/// [`ToolUpdate::Finished`]. The body runs as the stream is polled; it is
+/// not spawned.
+pub fn with_progress<'a, F, Fut>(body: F) -> ToolUpdateStream<'a>
+where
+    F: FnOnce(ProgressReporter) -> Fut,
+    Fut: Future<Output = Result<ToolResult, ToolError>> + Send + 'a,
+{
+    let (tx, rx) = mpsc::unbounded_channel();
+    Box::pin(ReportingStream {
+        body: Some(Box::pin(body(ProgressReporter { tx }))),
+        rx,
+        result: None,
+    })
+}
+
+type BodyFuture<'a> = Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + 'a>>;
+
+struct ReportingStream<'a> {
+    /// `None` once the body has completed.
+    body: Option<BodyFuture<'a>>,
+    rx: mpsc::UnboundedReceiver<ToolProgress>,
+    /// The body's result, held until queued progress has been yielded.
+    result: Option<Result<ToolResult, ToolError>>,
+}
+
+impl Stream for ReportingStream<'_> {
+    type Item = ToolUpdate;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ToolUpdate>> {
+        if let Some(body) = self.body.as_mut() {
+            if let Poll::Ready(result) = body.as_mut().poll(cx) {
+                self.body = None;
+                self.result = Some(result);
+            }
+        }
+        if let Ok(progress) = self.rx.try_recv() {
+            return Poll::Ready(Some(ToolUpdate::Progress(progress)));
+        }
+        match self.result.take() {
+            Some(result) => Poll::Ready(Some(ToolUpdate::Finished(result))),
+            None if self.body.is_none() => Poll::Ready(None),
+            None => match self.rx.poll_recv(cx) {
+                Poll::Ready(Some(progress)) => Poll::Ready(Some(ToolUpdate::Progress(progress))),
+                // The body's waker is registered; wait for it or a report.
+                _ => Poll::Pending,
+            },
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Driving streams
+// ---------------------------------------------------------------------------
+
+/// Run `tool` via [`Tool::execute_streaming`], emitting `tool:progress` for
+/// each progress item, bounded by `deadline`.
+///
+/// Output formats are not negotiated here; see
+/// [`tool_format`](crate::tool_format) for that.
+///
+/// # Errors
+///
+/// - The tool's own error
+/// - [`ToolError::Timeout`] if `deadline` passes first
+/// - [`ToolError::Other`] if the stream ends without a result
+pub async fn execute_with_progress(
+    hooks: &HookRegistry,
+    deadline: Option<TurnDeadline>,
+    tool: &dyn Tool,
+    input: Value,
+    context: ToolContext,
+) -> Result<ToolResult, ToolError> {
+    let tool_call_id = context.tool_call_id.clone();
+    let drive = async {
+        let mut updates = tool.execute_streaming(input, context);
+        while let Some(update) = updates.next().await {
+            match update {
+                ToolUpdate::Progress(progress) => {
+                    let mut data = serde_json::json!({
+                        "tool_name": tool.name(),
+                        "fraction": progress.fraction(),
+                        "progress": progress,
+                    });
+                    if let Some(id) = &tool_call_id {
+                        data["tool_call_id"] = Value::String(id.clone());
+                    }
+                    hooks.emit(events::TOOL_PROGRESS, data).await;
+                }
+                ToolUpdate::Finished(result) => return result,
+            }
+        }
+        Err(ToolError::Other {
+            message: format!("tool {} ended its stream without a result", tool.name()),
+        })
+    };
+    deadline::run_tool(deadline, tool, drive).await
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::messages::ToolSpec;
+    use crate::testing::{FakeHookHandler, FakeTool};
+
+    /// Reports three steps, yielding between them, then succeeds.
+    struct ScanTool;
+
+    impl Tool for ScanTool {
+        fn name(&self) -> &str {
+            "scan"
+        }
+
+        fn description(&self) -> &str {
+            "Scans files"
+        }
+
+        fn get_spec(&self) -> ToolSpec {
+            FakeTool::new("scan", "Scans files").get_spec()
+        }
+
+        fn execute(
+            &self,
+            _input: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            Box::pin(async { unreachable!("scan is driven through execute_streaming") })
+        }
+
+        fn execute_streaming(&self, _input: Value, _context: ToolContext) -> ToolUpdateStream<'_> {
+            with_progress(|progress| async move {
+                for step in 1..=3 {
+                    progress.report(ToolProgress::step(step as f64, 3.0));
+                    tokio::task::yield_now().await;
+                }
+                progress.report(ToolProgress::message("done scanning"));
+                Ok(ToolResult {
+                    success: true,
+                    output: Some(Value::from(3)),
+                    error: None,
+                    ..Default::default()
+                })
+            })
+        }
+    }
+
+    /// A stream that never finishes.
+    struct EmptyStreamTool;
+
+    impl Tool for EmptyStreamTool {
+        fn name(&self) -> &str {
+            "empty"
+        }
+
+        fn description(&self) -> &str {
+            ""
+        }
+
+        fn get_spec(&self) -> ToolSpec {
+            FakeTool::new("empty", "").get_spec()
+        }
+
+        fn execute(
+            &self,
+            _input: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+            Box::pin(async { unreachable!() })
+        }
+
+        fn execute_streaming(&self, _input: Value, _context: ToolContext) -> ToolUpdateStream<'_> {
+            Box::pin(tokio_stream::empty())
+        }
+    }
+
+    fn recording_hooks() -> (HookRegistry, Arc<FakeHookHandler>) {
+        let hooks = HookRegistry::new();
+        let recorder = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register(events::TOOL_PROGRESS, recorder.clone(), 0, None);
+        (hooks, recorder)
+    }
+
+    #[tokio::test]
+    async fn progress_precedes_the_result_in_order() {
+        let updates: Vec<ToolUpdate> = ScanTool
+            .execute_streaming(Value::Null, ToolContext::default())
+            .collect()
+            .await;
+        assert_eq!(updates.len(), 5);
+        let completed: Vec<Option<f64>> = updates[..3]
+            .iter()
+            .map(|u| match u {
+                ToolUpdate::Progress(p) => p.completed,
+                other => panic!("expected progress, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(completed, vec![Some(1.0), Some(2.0), Some(3.0)]);
+        assert!(
+            matches!(&updates[3], ToolUpdate::Progress(p) if p.message.as_deref() == Some("done scanning"))
+        );
+        assert!(
+            matches!(&updates[4], ToolUpdate::Finished(Ok(r)) if r.output == Some(Value::from(3)))
+        );
+    }
+
+    #[tokio::test]
+    async fn default_stream_finishes_without_progress() {
+        let tool = FakeTool::new("echo", "Echoes");
+        let updates: Vec<ToolUpdate> = tool
+            .execute_streaming(serde_json::json!({"x": 1}), ToolContext::default())
+            .collect()
+            .await;
+        assert!(matches!(&updates[..], [ToolUpdate::Finished(Ok(_))]));
+    }
+
+    #[tokio::test]
+    async fn execute_with_progress_emits_tool_progress_events() {
+        let (hooks, recorder) = recording_hooks();
+        let context = ToolContext {
+            tool_call_id: Some("call_7".into()),
+            ..Default::default()
+        };
+        let result = execute_with_progress(&hooks, None, &ScanTool, Value::Null, context)
+            .await
+            .unwrap();
+        assert_eq!(result.output, Some(Value::from(3)));
+
+        let events = recorder.recorded_events();
+        assert_eq!(events.len(), 4);
+        let (name, first) = &events[0];
+        assert_eq!(name, "tool:progress");
+        assert_eq!(first["tool_name"], "scan");
+        assert_eq!(first["tool_call_id"], "call_7");
+        assert_eq!(first["progress"]["completed"], 1.0);
+        assert!((first["fraction"].as_f64().unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert!(events[3].1["fraction"].is_null());
+    }
+
+    #[tokio::test]
+    async fn stream_without_result_is_an_error() {
+        let (hooks, _) = recording_hooks();
+        let err = execute_with_progress(
+            &hooks,
+            None,
+            &EmptyStreamTool,
+            Value::Null,
+            ToolContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::Other { .. }));
+    }
+
+    #[tokio::test]
+    async fn deadline_bounds_a_streaming_tool() {
+        struct SlowTool;
+        impl Tool for SlowTool {
+            fn name(&self) -> &str {
+                "slow"
+            }
+            fn description(&self) -> &str {
+                ""
+            }
+            fn get_spec(&self) -> ToolSpec {
+                FakeTool::new("slow", "").get_spec()
+            }
+            fn execute(
+                &self,
+                _input: Value,
+            ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>>
+            {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    unreachable!()
+                })
+            }
+        }
+
+        let (hooks, _) = recording_hooks();
+        let deadline = TurnDeadline::after(Duration::from_millis(20));
+        let err = execute_with_progress(
+            &hooks,
+            Some(deadline),
+            &SlowTool,
+            Value::Null,
+            ToolContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::Timeout { .. }));
+    }
+
+    #[test]
+    fn fraction_requires_both_counts() {
+        assert_eq!(ToolProgress::step(5.0, 10.0).fraction(), Some(0.5));
+        assert_eq!(ToolProgress::step(12.0, 10.0).fraction(), Some(1.0));
+        assert_eq!(ToolProgress::step(1.0, 0.0).fraction(), None);
+        assert_eq!(ToolProgress::message("working").fraction(), None);
+    }
+}
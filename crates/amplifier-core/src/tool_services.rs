@@ -0,0 +1,194 @@
+//! Kernel services handed to a running tool.
+//!
+//! Tools get a [`ToolContext`] on every call but no coordinator. Its
+//! [`ToolServices`] carry what a tool may use while it runs:
+//!
+//! | Method                                   | Does                                                        |
+//! |------------------------------------------|-------------------------------------------------------------|
+//! | [`ToolContext::emit`]                    | emits `tool:<name>:<event>` on the session's hooks          |
+//! | [`ToolContext::cancellation`]            | the session's cancellation token                            |
+//! | [`ToolContext::capability`]              | a JSON capability (see [`Coordinator::get_capability`])     |
+//! | [`ToolContext::capability_object`]       | a capability object (see [`Coordinator::get_capability_object`]) |
+//!
+//! Events are namespaced by the tool's name, so a tool cannot emit kernel
+//! events (`tool:post`, `session:end`, ...) or another tool's events. They
+//! carry the call's correlation IDs like any event emitted during the call.
+//!
+//! [`ToolExecutor`](crate::tool_executor::ToolExecutor) fills the services
+//! in from the coordinator. A tool called directly gets the default, which
+//! has no hooks (emitting does nothing), no cancellation token and no
+//! capabilities.
+//!
+//! ```rust,no_run
+//! # async fn example(context: amplifier_core::models::ToolContext) {
+//! use serde_json::json;
+//!
+//! // From the `indexer` tool: emits `tool:indexer:batch_indexed`.
+//! context.emit("batch_indexed", json!({"files": 120})).await;
+//! if context.is_cancelled() {
+//!     return;
+//! }
+//! # }
+//! ```
+//!
+//! [`ToolContext`]: crate::models::ToolContext
+//! [`ToolContext::emit`]: crate::models::ToolContext::emit
+//! [`ToolContext::cancellation`]: crate::models::ToolContext::cancellation
+//! [`ToolContext::capability`]: crate::models::ToolContext::capability
+//! [`ToolContext::capability_object`]: crate::models::ToolContext::capability_object
+//! [`Coordinator::get_capability`]: crate::coordinator::Coordinator::get_capability
+//! [`Coordinator::get_capability_object`]: crate::coordinator::Coordinator::get_capability_object
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::cancellation::CancellationToken;
+use crate::coordinator::CapabilityLookup;
+use crate::hooks::HookRegistry;
+use crate::models::HookResult;
+
+/// What a tool may reach while it runs; see the [module docs](self).
+#[derive(Clone, Default)]
+pub struct ToolServices {
+    tool: String,
+    hooks: Option<Arc<HookRegistry>>,
+    cancellation: Option<CancellationToken>,
+    capabilities: CapabilityLookup,
+}
+
+impl ToolServices {
+    /// Services for calls to `tool`, with nothing attached.
+    pub fn new(tool: impl Into<String>) -> Self {
+        Self {
+            tool: tool.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Emit the tool's events on `hooks`.
+    pub fn with_hooks(mut self, hooks: Arc<HookRegistry>) -> Self {
+        self.hooks = Some(hooks);
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    pub fn with_capabilities(mut self, capabilities: CapabilityLookup) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// The tool these services were made for.
+    pub fn tool(&self) -> &str {
+        &self.tool
+    }
+
+    /// The full name of the tool's `event`: `tool:<name>:<event>`.
+    pub fn event_name(&self, event: &str) -> String {
+        let prefix = format!("tool:{}:", self.tool);
+        if event.starts_with(&prefix) {
+            event.to_string()
+        } else {
+            format!("{prefix}{event}")
+        }
+    }
+
+    pub(crate) async fn emit(&self, event: &str, data: Value) -> HookResult {
+        let event = self.event_name(event);
+        match &self.hooks {
+            Some(hooks) => hooks.emit(&event, data).await,
+            None => {
+                log::debug!(
+                    "No hooks attached to tool '{}'; dropping {event}",
+                    self.tool
+                );
+                HookResult::default()
+            }
+        }
+    }
+
+    pub(crate) fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
+
+    pub(crate) fn capabilities(&self) -> &CapabilityLookup {
+        &self.capabilities
+    }
+}
+
+impl fmt::Debug for ToolServices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolServices")
+            .field("tool", &self.tool)
+            .field("hooks", &self.hooks.is_some())
+            .field("cancellation", &self.cancellation.is_some())
+            .finish()
+    }
+}
+
+/// Equal when made for the same tool on the same hook registry.
+impl PartialEq for ToolServices {
+    fn eq(&self, other: &Self) -> bool {
+        self.tool == other.tool
+            && match (&self.hooks, &other.hooks) {
+                (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::models::ToolContext;
+    use crate::testing::FakeHookHandler;
+
+    #[tokio::test]
+    async fn tools_emit_namespaced_events_and_see_capabilities() {
+        let hooks = Arc::new(HookRegistry::new());
+        let handler = Arc::new(FakeHookHandler::new());
+        let _ = hooks.register("tool:indexer:batch_indexed", handler.clone(), 0, None);
+        let coordinator = crate::coordinator::Coordinator::new_for_test();
+        coordinator.register_capability("index-root", json!("/data"));
+        let token = CancellationToken::new();
+
+        let context = ToolContext {
+            services: ToolServices::new("indexer")
+                .with_hooks(hooks)
+                .with_cancellation(token.clone())
+                .with_capabilities(coordinator.capability_lookup()),
+            ..Default::default()
+        };
+        context.emit("batch_indexed", json!({"files": 3})).await;
+        context
+            .emit("tool:indexer:batch_indexed", json!({"files": 4}))
+            .await;
+        let events = handler.recorded_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].1["files"], 3);
+
+        assert_eq!(context.capability("index-root"), Some(json!("/data")));
+        coordinator.register_capability("late", json!(true));
+        assert_eq!(context.capability("late"), Some(json!(true)));
+        assert!(!context.is_cancelled());
+        token.request_immediate();
+        assert!(context.is_cancelled());
+
+        // Without services, emitting is a no-op.
+        let bare = ToolContext::default();
+        assert_eq!(bare.emit("x", json!({})).await, HookResult::default());
+        assert!(bare.cancellation().is_none());
+    }
+}
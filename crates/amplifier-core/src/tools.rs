@@ -0,0 +1,6 @@
+//! Tool implementations shipped with the kernel.
+//!
+//! - [`builtin`] — small reference tools for integration tests and quick
+//!   starts
+
+pub mod builtin;
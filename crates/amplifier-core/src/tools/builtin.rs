@@ -0,0 +1,522 @@
+//! Reference tools (feature `builtin-tools`).
+//!
+//! Pure-Rust integration tests and quick starts can mount these instead of
+//! writing a [`Tool`] from scratch:
+//!
+//! | Tool         | Type              | Does                                            |
+//! |--------------|-------------------|-------------------------------------------------|
+//! | `echo`       | [`EchoTool`]      | returns its `text` argument                     |
+//! | `http_fetch` | [`HttpFetchTool`] | `GET`s an `http://` URL on an allowed domain    |
+//! | `read_file`  | [`ReadFileTool`]  | reads a UTF-8 file inside the session's [`Workspace`] |
+//!
+//! ```rust,no_run
+//! use std::sync::Arc;
+//! use amplifier_core::coordinator::Coordinator;
+//! use amplifier_core::tools::builtin::{EchoTool, HttpFetchTool, ReadFileTool};
+//!
+//! let coordinator = Coordinator::new_for_test();
+//! coordinator.mount_tool("echo", Arc::new(EchoTool));
+//! coordinator.mount_tool("http_fetch", Arc::new(HttpFetchTool::new(["example.com"])));
+//! coordinator.mount_tool("read_file", Arc::new(ReadFileTool::new()));
+//! ```
+//!
+//! They are references, not hardened tools: `http_fetch` speaks plain
+//! HTTP/1.1 only (the kernel carries no TLS stack). `read_file` checks the
+//! path with symlinks resolved ([`Workspace::check_real`]), so a link inside
+//! the workspace cannot reach a file outside it. Failures are reported as
+//! unsuccessful [`ToolResult`]s with an `error.message`.
+//!
+//! `read_file` does not declare itself pure: what it returns depends on the
+//! file, not only on the path, so the session's
+//! [pure tool cache](crate::tool_cache) would serve stale contents after an
+//! edit made outside the session.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use http_body_util::{BodyExt, Empty, Limited};
+use hyper::body::Bytes;
+use hyper::{header, Request, Uri};
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Value};
+
+use crate::errors::ToolError;
+use crate::messages::ToolSpec;
+use crate::models::{ToolContext, ToolResult};
+use crate::traits::Tool;
+use crate::workspace::{PathAccess, Workspace};
+
+/// Response and file size limit of [`HttpFetchTool`] and [`ReadFileTool`]
+/// unless configured otherwise.
+pub const DEFAULT_MAX_BYTES: usize = 1024 * 1024;
+
+/// Default `http_fetch` timeout, in seconds (enforced by the tool executor).
+const HTTP_FETCH_TIMEOUT_SECS: f64 = 30.0;
+
+fn spec(name: &str, description: &str, properties: Value, required: &[&str]) -> ToolSpec {
+    ToolSpec {
+        name: name.into(),
+        parameters: HashMap::from([
+            ("type".to_string(), json!("object")),
+            ("properties".to_string(), properties),
+            ("required".to_string(), json!(required)),
+        ]),
+        description: Some(description.into()),
+        extensions: HashMap::new(),
+    }
+}
+
+fn failure(message: impl Into<String>) -> ToolResult {
+    ToolResult::new(
+        false,
+        None,
+        Some(HashMap::from([(
+            "message".to_string(),
+            Value::String(message.into()),
+        )])),
+    )
+}
+
+fn string_arg<'a>(input: &'a Value, name: &str) -> Result<&'a str, String> {
+    input
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing string argument '{name}'"))
+}
+
+// ---------------------------------------------------------------------------
+// EchoTool
+// ---------------------------------------------------------------------------
+
+/// `echo`: returns its `text` argument as the output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EchoTool;
+
+impl Tool for EchoTool {
+    fn name(&self) -> &str {
+        "echo"
+    }
+
+    fn description(&self) -> &str {
+        "Return the given text unchanged."
+    }
+
+    fn get_spec(&self) -> ToolSpec {
+        spec(
+            self.name(),
+            self.description(),
+            json!({"text": {"type": "string", "description": "Text to return"}}),
+            &["text"],
+        )
+    }
+
+    fn execute(
+        &self,
+        input: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        let result = match string_arg(&input, "text") {
+            Ok(text) => ToolResult::new(true, Some(Value::String(text.into())), None),
+            Err(message) => failure(message),
+        };
+        Box::pin(async move { Ok(result) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// HttpFetchTool
+// ---------------------------------------------------------------------------
+
+/// `http_fetch`: `GET`s a URL whose host is on the allowlist and returns
+/// `{"status", "content_type", "body"}`.
+///
+/// An allowlist entry matches the domain itself and its subdomains
+/// (`example.com` allows `api.example.com`); `*` allows every host. Bodies
+/// larger than the size limit are refused, and non-UTF-8 bytes are replaced.
+#[derive(Debug, Clone)]
+pub struct HttpFetchTool {
+    allowed_domains: Vec<String>,
+    max_bytes: usize,
+}
+
+impl HttpFetchTool {
+    /// A fetch tool limited to `allowed_domains`.
+    pub fn new<I, S>(allowed_domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allowed_domains: allowed_domains
+                .into_iter()
+                .map(|d| d.into().to_ascii_lowercase())
+                .collect(),
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    /// Refuse response bodies larger than `max_bytes`.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Whether `host` is on the allowlist.
+    pub fn allows(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.allowed_domains.iter().any(|domain| {
+            domain == "*"
+                || host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Value, String> {
+        let uri: Uri = url.parse().map_err(|e| format!("invalid URL: {e}"))?;
+        match uri.scheme_str() {
+            Some("http") => {}
+            Some("https") => return Err("https URLs are not supported (no TLS)".into()),
+            _ => return Err(format!("unsupported URL '{url}': expected http://")),
+        }
+        let host = uri.host().ok_or("URL has no host")?;
+        if !self.allows(host) {
+            return Err(format!("{host} is not an allowed domain"));
+        }
+        let authority = uri.authority().map(|a| a.as_str()).unwrap_or(host);
+        let path = uri.path_and_query().map_or("/", |p| p.as_str());
+
+        let stream = tokio::net::TcpStream::connect((host, uri.port_u16().unwrap_or(80)))
+            .await
+            .map_err(|e| format!("cannot connect to {authority}: {e}"))?;
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(|e| format!("HTTP handshake with {authority} failed: {e}"))?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                log::debug!("http_fetch connection closed with error: {e}");
+            }
+        });
+
+        let request = Request::get(path)
+            .header(header::HOST, authority)
+            .header(header::USER_AGENT, "amplifier-core")
+            .body(Empty::<Bytes>::new())
+            .map_err(|e| format!("invalid request: {e}"))?;
+        let response = sender
+            .send_request(request)
+            .await
+            .map_err(|e| format!("request to {authority} failed: {e}"))?;
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let body = Limited::new(response.into_body(), self.max_bytes)
+            .collect()
+            .await
+            .map_err(|e| format!("cannot read response (limit {} bytes): {e}", self.max_bytes))?
+            .to_bytes();
+        Ok(json!({
+            "status": status,
+            "content_type": content_type,
+            "body": String::from_utf8_lossy(&body),
+        }))
+    }
+}
+
+impl Tool for HttpFetchTool {
+    fn name(&self) -> &str {
+        "http_fetch"
+    }
+
+    fn description(&self) -> &str {
+        "Fetch a URL with HTTP GET and return the status, content type and body."
+    }
+
+    fn get_spec(&self) -> ToolSpec {
+        let mut spec = spec(
+            self.name(),
+            self.description(),
+            json!({"url": {"type": "string", "description": "http:// URL to fetch"}}),
+            &["url"],
+        );
+        spec.extensions
+            .insert("timeout".into(), json!(HTTP_FETCH_TIMEOUT_SECS));
+        spec
+    }
+
+    fn execute(
+        &self,
+        input: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        Box::pin(async move {
+            let fetched = match string_arg(&input, "url") {
+                Ok(url) => self.fetch(url).await,
+                Err(message) => Err(message),
+            };
+            Ok(match fetched {
+                Ok(output) => ToolResult::new(true, Some(output), None),
+                Err(message) => failure(message),
+            })
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ReadFileTool
+// ---------------------------------------------------------------------------
+
+/// `read_file`: returns the contents of a UTF-8 file inside the workspace.
+///
+/// Paths are resolved and checked against the session's workspace from the
+/// [`ToolContext`], or else the one given to
+/// [`with_workspace`](Self::with_workspace). Without either, every call
+/// fails.
+#[derive(Debug, Clone)]
+pub struct ReadFileTool {
+    workspace: Option<Workspace>,
+    max_bytes: usize,
+}
+
+impl Default for ReadFileTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReadFileTool {
+    /// A reader scoped to the calling session's workspace.
+    pub fn new() -> Self {
+        Self {
+            workspace: None,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    /// Use `workspace` when the call carries none.
+    pub fn with_workspace(mut self, workspace: Workspace) -> Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    /// Refuse files larger than `max_bytes`.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    async fn read(&self, input: &Value, workspace: Option<&Workspace>) -> ToolResult {
+        let path = match string_arg(input, "path") {
+            Ok(path) => path,
+            Err(message) => return failure(message),
+        };
+        let Some(workspace) = workspace.or(self.workspace.as_ref()) else {
+            return failure("read_file requires a workspace");
+        };
+        let (workspace, path) = (workspace.clone(), path.to_string());
+        let checked =
+            tokio::task::spawn_blocking(move || workspace.check_real(path, PathAccess::Read)).await;
+        let path = match checked {
+            Ok(Ok(path)) => path,
+            Ok(Err(violation)) => return failure(violation.to_string()),
+            Err(e) => return failure(format!("cannot check path: {e}")),
+        };
+        let display = path.display();
+        match tokio::fs::metadata(&path).await {
+            Ok(meta) if meta.len() > self.max_bytes as u64 => {
+                return failure(format!(
+                    "{display} is {} bytes (limit {})",
+                    meta.len(),
+                    self.max_bytes
+                ));
+            }
+            Ok(meta) if !meta.is_file() => return failure(format!("{display} is not a file")),
+            Ok(_) => {}
+            Err(e) => return failure(format!("cannot read {display}: {e}")),
+        }
+        match tokio::fs::read_to_string(&path).await {
+            Ok(text) => ToolResult::new(true, Some(Value::String(text)), None),
+            Err(e) => failure(format!("cannot read {display}: {e}")),
+        }
+    }
+}
+
+impl Tool for ReadFileTool {
+    fn name(&self) -> &str {
+        "read_file"
+    }
+
+    fn description(&self) -> &str {
+        "Read a UTF-8 text file from the workspace."
+    }
+
+    fn get_spec(&self) -> ToolSpec {
+        spec(
+            self.name(),
+            self.description(),
+            json!({"path": {"type": "string", "description": "File path, relative to the workspace root"}}),
+            &["path"],
+        )
+    }
+
+    fn execute(
+        &self,
+        input: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        Box::pin(async move { Ok(self.read(&input, None).await) })
+    }
+
+    fn execute_with_context(
+        &self,
+        input: Value,
+        context: ToolContext,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        Box::pin(async move { Ok(self.read(&input, context.workspace.as_ref()).await) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn message(result: &ToolResult) -> &str {
+        result.error.as_ref().unwrap()["message"].as_str().unwrap()
+    }
+
+    #[tokio::test]
+    async fn echo_returns_its_text() {
+        let result = EchoTool.execute(json!({"text": "hi"})).await.unwrap();
+        assert_eq!(result.output, Some(json!("hi")));
+        let result = EchoTool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn allowlist_matches_domains_and_subdomains() {
+        let tool = HttpFetchTool::new(["example.com"]);
+        assert!(tool.allows("example.com"));
+        assert!(tool.allows("API.example.com."));
+        assert!(!tool.allows("badexample.com"));
+        assert!(!tool.allows("example.com.evil.net"));
+        assert!(HttpFetchTool::new(["*"]).allows("anything.org"));
+    }
+
+    #[tokio::test]
+    async fn http_fetch_gets_allowed_urls_only() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 1024];
+            let n = socket.read(&mut request).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 5\r\n\r\nhello")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+
+        let tool = HttpFetchTool::new(["127.0.0.1"]);
+        let result = tool
+            .execute(json!({"url": format!("http://127.0.0.1:{port}/a?b=1")}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.output,
+            Some(json!({"status": 200, "content_type": "text/plain", "body": "hello"}))
+        );
+        assert!(server.await.unwrap().starts_with("GET /a?b=1 HTTP/1.1"));
+
+        let denied = tool
+            .execute(json!({"url": "http://example.com/"}))
+            .await
+            .unwrap();
+        assert_eq!(message(&denied), "example.com is not an allowed domain");
+    }
+
+    #[tokio::test]
+    async fn read_file_is_scoped_to_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "remember").unwrap();
+        let tool = ReadFileTool::new();
+
+        let unscoped = tool.execute(json!({"path": "notes.txt"})).await.unwrap();
+        assert_eq!(message(&unscoped), "read_file requires a workspace");
+
+        let context = ToolContext {
+            workspace: Some(Workspace::new(dir.path())),
+            ..Default::default()
+        };
+        let result = tool
+            .execute_with_context(json!({"path": "notes.txt"}), context.clone())
+            .await
+            .unwrap();
+        assert_eq!(result.output, Some(json!("remember")));
+
+        let outside = tool
+            .execute_with_context(json!({"path": "../secret"}), context)
+            .await
+            .unwrap();
+        assert!(message(&outside).ends_with("is outside the workspace"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_file_does_not_follow_symlinks_out_of_the_workspace() {
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::write(outside.path().join("secret.txt"), "hidden").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "remember").unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("escape")).unwrap();
+        std::os::unix::fs::symlink("notes.txt", dir.path().join("alias.txt")).unwrap();
+        let tool = ReadFileTool::new().with_workspace(Workspace::new(dir.path()));
+
+        let escaped = tool
+            .execute(json!({"path": "escape/secret.txt"}))
+            .await
+            .unwrap();
+        assert!(!escaped.success);
+        assert!(message(&escaped).ends_with("is outside the workspace"));
+
+        let inside = tool.execute(json!({"path": "alias.txt"})).await.unwrap();
+        assert_eq!(inside.output, Some(json!("remember")));
+    }
+
+    #[tokio::test]
+    async fn read_file_sees_edits_with_the_pure_cache_on() {
+        use crate::messages::ToolCall;
+        use crate::tool_cache::{PureToolCache, PureToolCacheConfig};
+        use crate::tool_executor::ToolExecutor;
+        use std::sync::Arc;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "before").unwrap();
+        let tool = ReadFileTool::new();
+        let executor = ToolExecutor::new()
+            .with_workspace(Some(Arc::new(Workspace::new(dir.path()))))
+            .with_pure_cache(Some(Arc::new(PureToolCache::new(
+                PureToolCacheConfig::default(),
+            ))));
+        let call = |id: &str| ToolCall {
+            id: id.into(),
+            name: "read_file".into(),
+            arguments: HashMap::from([("path".to_string(), json!("notes.txt"))]),
+            extensions: HashMap::new(),
+        };
+
+        let first = executor.execute_call(&tool, &call("c1")).await.unwrap();
+        assert_eq!(first.output, Some(json!("before")));
+        std::fs::write(&path, "after").unwrap();
+        let second = executor.execute_call(&tool, &call("c2")).await.unwrap();
+        assert_eq!(second.output, Some(json!("after")));
+    }
+}
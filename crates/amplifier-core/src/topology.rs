@@ -0,0 +1,458 @@
+//! Graph export of a coordinator's module topology.
+//!
+//! [`Coordinator::export_graph`] draws what [`Coordinator::describe`] lists,
+//! plus how the pieces connect:
+//!
+//! | Node         | Edge                                   |
+//! |--------------|----------------------------------------|
+//! | `module`     | —                                      |
+//! | `capability` | module `requires` capability           |
+//! | `event`      | module `subscribes` to event           |
+//! | `channel`    | module `contributes` to channel        |
+//!
+//! Mounted modules are nodes by mount name. Requirements, hook handlers and
+//! contributors are matched to them by mount name or module id (from
+//! [`Coordinator::set_module_info`]); names that match no mounted module —
+//! hook modules, for instance — get a module node of their own. Capabilities
+//! that are required but not registered are marked `missing` (dashed in the
+//! rendered graphs).
+//!
+//! The graph renders as Graphviz DOT, a Mermaid flowchart or JSON, with
+//! nodes and edges in a stable order so exports can be diffed:
+//!
+//! ```rust
+//! use amplifier_core::coordinator::Coordinator;
+//! use amplifier_core::topology::GraphFormat;
+//!
+//! let coordinator = Coordinator::new_for_test();
+//! coordinator.require_capabilities("tool-search", &["vector-store"]);
+//! let dot = coordinator.export_graph(GraphFormat::Dot);
+//! assert!(dot.contains(r#""module:tool-search" -> "capability:vector-store""#));
+//! ```
+//!
+//! [`Coordinator::export_graph`]: crate::coordinator::Coordinator::export_graph
+//! [`Coordinator::describe`]: crate::coordinator::Coordinator::describe
+//! [`Coordinator::set_module_info`]: crate::coordinator::Coordinator::set_module_info
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::coordinator::Coordinator;
+
+/// How [`ModuleGraph::render`] writes a graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    /// Graphviz DOT.
+    Dot,
+    /// A Mermaid flowchart.
+    Mermaid,
+    /// [`ModuleGraph`] as pretty-printed JSON.
+    Json,
+}
+
+impl GraphFormat {
+    pub const ALL: [GraphFormat; 3] = [GraphFormat::Dot, GraphFormat::Mermaid, GraphFormat::Json];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::Mermaid => "mermaid",
+            GraphFormat::Json => "json",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GraphFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| format!("unknown graph format '{s}'"))
+    }
+}
+
+/// What a [`GraphNode`] stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Module,
+    Capability,
+    Event,
+    Channel,
+}
+
+impl NodeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            NodeKind::Module => "module",
+            NodeKind::Capability => "capability",
+            NodeKind::Event => "event",
+            NodeKind::Channel => "channel",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// `<kind>:<name>`, or `<mount point>:<name>` for mounted modules.
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    /// Where a module is mounted; `None` for modules known only by name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_point: Option<String>,
+    /// A required capability that is not registered.
+    #[serde(default)]
+    pub missing: bool,
+}
+
+/// How the two ends of a [`GraphEdge`] relate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    Requires,
+    Subscribes,
+    Contributes,
+}
+
+impl EdgeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EdgeKind::Requires => "requires",
+            EdgeKind::Subscribes => "subscribes",
+            EdgeKind::Contributes => "contributes",
+        }
+    }
+}
+
+/// From a module to a capability, event or channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+/// A coordinator's modules and what connects them; see the
+/// [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleGraph {
+    /// Modules in mount order, then the other nodes by kind and name.
+    pub nodes: Vec<GraphNode>,
+    /// Grouped by kind, then sorted by the ids of their ends.
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ModuleGraph {
+    /// The current topology of `coordinator`.
+    pub fn from_coordinator(coordinator: &Coordinator) -> Self {
+        let report = coordinator.describe();
+        let mut builder = Builder::default();
+        for module in &report.modules {
+            let id = format!("{}:{}", module.mount_point, module.name);
+            builder.aliases.insert(module.name.clone(), id.clone());
+            if let Some(info) = &module.info {
+                builder
+                    .aliases
+                    .entry(info.id.clone())
+                    .or_insert_with(|| id.clone());
+            }
+            builder.graph.nodes.push(GraphNode {
+                id,
+                kind: NodeKind::Module,
+                label: module.name.clone(),
+                mount_point: Some(module.mount_point.clone()),
+                missing: false,
+            });
+        }
+
+        let registered: BTreeSet<&String> = report
+            .capabilities
+            .iter()
+            .chain(&report.capability_objects)
+            .collect();
+        for capability in &registered {
+            builder.node(NodeKind::Capability, capability, false);
+        }
+        for (module, required) in coordinator.capability_requirements() {
+            for capability in required {
+                let missing = !registered.contains(&capability);
+                let to = builder.node(NodeKind::Capability, &capability, missing);
+                builder.edge(&module, to, EdgeKind::Requires);
+            }
+        }
+
+        let handlers: BTreeMap<String, Vec<String>> = coordinator
+            .hooks()
+            .list_handlers(None)
+            .into_iter()
+            .collect();
+        for (event, names) in handlers {
+            if names.is_empty() {
+                continue;
+            }
+            let to = builder.node(NodeKind::Event, &event, false);
+            for name in names {
+                builder.edge(&name, to.clone(), EdgeKind::Subscribes);
+            }
+        }
+
+        for (channel, contributors) in &report.channels {
+            let to = builder.node(NodeKind::Channel, channel, false);
+            for name in contributors {
+                builder.edge(name, to.clone(), EdgeKind::Contributes);
+            }
+        }
+        builder.finish()
+    }
+
+    /// The graph in `format`.
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+            GraphFormat::Json => serde_json::to_string_pretty(self).unwrap_or_default(),
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph amplifier {\n  rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Module => "box",
+                NodeKind::Capability => "ellipse",
+                NodeKind::Event => "diamond",
+                NodeKind::Channel => "folder",
+            };
+            let label = match &node.mount_point {
+                Some(mount_point) => format!("{}\\n({mount_point})", dot_escape(&node.label)),
+                None => dot_escape(&node.label),
+            };
+            let style = if node.missing { ", style=dashed" } else { "" };
+            let _ = writeln!(
+                out,
+                "  \"{}\" [label=\"{label}\", shape={shape}{style}];",
+                dot_escape(&node.id)
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                out,
+                "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                dot_escape(&edge.from),
+                dot_escape(&edge.to),
+                edge.kind.as_str()
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid ids cannot hold most punctuation, so nodes are numbered.
+    fn to_mermaid(&self) -> String {
+        let ids: HashMap<&str, String> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id.as_str(), format!("n{i}")))
+            .collect();
+        let mut out = String::from("flowchart LR\n");
+        for node in &self.nodes {
+            let label = match &node.mount_point {
+                Some(mount_point) => {
+                    format!("{}<br/>({mount_point})", mermaid_escape(&node.label))
+                }
+                None => mermaid_escape(&node.label),
+            };
+            let (open, close) = match node.kind {
+                NodeKind::Module => ("[", "]"),
+                NodeKind::Capability => ("([", "])"),
+                NodeKind::Event => ("{{", "}}"),
+                NodeKind::Channel => ("[(", ")]"),
+            };
+            let _ = writeln!(out, "  {}{open}\"{label}\"{close}", ids[node.id.as_str()]);
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::Requires => "-.->",
+                EdgeKind::Subscribes | EdgeKind::Contributes => "-->",
+            };
+            let _ = writeln!(
+                out,
+                "  {} {arrow}|{}| {}",
+                ids[edge.from.as_str()],
+                edge.kind.as_str(),
+                ids[edge.to.as_str()]
+            );
+        }
+        let missing: Vec<&str> = self
+            .nodes
+            .iter()
+            .filter(|node| node.missing)
+            .map(|node| ids[node.id.as_str()].as_str())
+            .collect();
+        if !missing.is_empty() {
+            let _ = writeln!(out, "  classDef missing stroke-dasharray: 5 5");
+            let _ = writeln!(out, "  class {} missing", missing.join(","));
+        }
+        out
+    }
+}
+
+/// Collects nodes other than mounted modules, and edges, deduplicated.
+#[derive(Default)]
+struct Builder {
+    graph: ModuleGraph,
+    /// Mount names and module ids → module node ids.
+    aliases: HashMap<String, String>,
+    nodes: BTreeMap<(NodeKind, String), GraphNode>,
+    edges: BTreeSet<(EdgeKind, String, String)>,
+}
+
+impl Builder {
+    /// The id of the `kind` node named `name`, adding it if needed.
+    fn node(&mut self, kind: NodeKind, name: &str, missing: bool) -> String {
+        let node = self
+            .nodes
+            .entry((kind, name.to_string()))
+            .or_insert_with(|| GraphNode {
+                id: format!("{}:{name}", kind.as_str()),
+                kind,
+                label: name.to_string(),
+                mount_point: None,
+                missing,
+            });
+        node.id.clone()
+    }
+
+    fn edge(&mut self, module: &str, to: String, kind: EdgeKind) {
+        let from = match self.aliases.get(module) {
+            Some(id) => id.clone(),
+            None => self.node(NodeKind::Module, module, false),
+        };
+        self.edges.insert((kind, from, to));
+    }
+
+    fn finish(mut self) -> ModuleGraph {
+        self.graph.nodes.extend(self.nodes.into_values());
+        self.graph.edges = self
+            .edges
+            .into_iter()
+            .map(|(kind, from, to)| GraphEdge { from, to, kind })
+            .collect();
+        self.graph
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use serde_json::json;
+
+    use crate::testing::{EchoTool, FakeHookHandler, FakeProvider};
+
+    fn coordinator() -> Coordinator {
+        let coordinator = Coordinator::new_for_test();
+        coordinator.mount_provider("mock", Arc::new(FakeProvider::new("mock", "hi")));
+        coordinator.mount_tool("echo", Arc::new(EchoTool));
+        coordinator.register_capability("vector-store", json!({}));
+        coordinator.require_capabilities("echo", &["vector-store", "embeddings"]);
+        let _ = coordinator.hooks().register(
+            "tool:post",
+            Arc::new(FakeHookHandler::new()),
+            0,
+            Some("hooks-logging".into()),
+        );
+        coordinator.register_contributor(
+            "observability.events",
+            "hooks-logging",
+            Box::new(|| Box::pin(async { Ok(json!(["x"])) })),
+        );
+        coordinator
+    }
+
+    #[test]
+    fn the_graph_connects_modules_to_what_they_use() {
+        let graph = ModuleGraph::from_coordinator(&coordinator());
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "providers:mock",
+                "tools:echo",
+                "module:hooks-logging",
+                "capability:embeddings",
+                "capability:vector-store",
+                "event:tool:post",
+                "channel:observability.events",
+            ]
+        );
+        assert!(graph.nodes[3].missing);
+        let edges: Vec<(&str, &str, EdgeKind)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.kind))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("tools:echo", "capability:embeddings", EdgeKind::Requires),
+                ("tools:echo", "capability:vector-store", EdgeKind::Requires),
+                (
+                    "module:hooks-logging",
+                    "event:tool:post",
+                    EdgeKind::Subscribes
+                ),
+                (
+                    "module:hooks-logging",
+                    "channel:observability.events",
+                    EdgeKind::Contributes
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn graphs_render_in_every_format() {
+        let coordinator = coordinator();
+        let dot = coordinator.export_graph(GraphFormat::Dot);
+        assert!(dot.starts_with("digraph amplifier {"));
+        assert!(dot.contains(
+            r#""capability:embeddings" [label="embeddings", shape=ellipse, style=dashed];"#
+        ));
+        assert!(dot.contains(r#""tools:echo" -> "capability:vector-store" [label="requires"];"#));
+
+        let mermaid = coordinator.export_graph(GraphFormat::Mermaid);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("  n1[\"echo<br/>(tools)\"]"));
+        assert!(mermaid.contains("  n1 -.->|requires| n3"));
+        assert!(mermaid.contains("  class n3 missing"));
+
+        let json = coordinator.export_graph(GraphFormat::Json);
+        let parsed: ModuleGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, ModuleGraph::from_coordinator(&coordinator));
+
+        assert_eq!("mermaid".parse(), Ok(GraphFormat::Mermaid));
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+}
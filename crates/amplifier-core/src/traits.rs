@@ -19,9 +19,18 @@
 //! - [`HookHandler`] participates in the hook dispatch pipeline.
 //! - [`ApprovalProvider`] provides UI-driven approval gates.
 //! - [`DisplayService`] provides UI-driven message display.
+//! - [`DisplayProvider`] is the richer display contract for native hosts:
+//!   messages, tool progress and approval prompts.
+//! - [`Moderator`] classifies content for safety policies.
+//! - [`ModuleLifecycle`] is an optional init/health/shutdown contract any
+//!   mounted module can opt into.
 //!
 //! All data types referenced here are defined in [`crate::models`],
 //! [`crate::messages`], and [`crate::errors`].
+//!
+//! Implementors who prefer `async fn` can write the `Async*` traits in
+//! [`crate::native`] instead and mount them through
+//! [`Native`](crate::native::Native).
 
 use std::collections::HashMap;
 use std::future::Future;
@@ -33,8 +42,18 @@ use serde_json::Value;
 use crate::errors::{AmplifierError, ContextError, HookError, ProviderError, ToolError};
 use crate::messages::{ChatRequest, ChatResponse, ToolCall, ToolSpec};
 use crate::models::{
-    ApprovalRequest, ApprovalResponse, HookResult, ModelInfo, ProviderInfo, ToolResult,
+    ApprovalRequest, ApprovalResponse, HookResult, MessagePriority, ModelInfo, ModerationResult,
+    ModuleHealth, ProviderInfo, ToolContext, ToolProgress, ToolResult,
 };
+use crate::provenance::Provenance;
+use crate::tool_progress::ToolUpdateStream;
+
+/// The boxed future every async contract method returns.
+///
+/// `BoxFuture<'_, Result<ToolResult, ToolError>>` is the same type as the
+/// spelled-out `Pin<Box<dyn Future<Output = ...> + Send + '_>>` in the
+/// signatures below, so implementations may use either.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 // ---------------------------------------------------------------------------
 // Tool
@@ -91,7 +110,7 @@ use crate::models::{
 ///         input: Value,
 ///     ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
 ///         Box::pin(async move {
-///             Ok(ToolResult { success: true, output: Some(input), error: None })
+///             Ok(ToolResult::new(true, Some(input), None))
 ///         })
 ///     }
 /// }
@@ -123,6 +142,42 @@ pub trait Tool: Send + Sync {
         &self,
         input: Value,
     ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>>;
+
+    /// Execute with per-call context (e.g. the negotiated output format).
+    ///
+    /// The default ignores `context` and calls [`execute`](Tool::execute);
+    /// tools that advertise several output formats override this to honour
+    /// [`ToolContext::output_format`].
+    fn execute_with_context(
+        &self,
+        input: Value,
+        context: ToolContext,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolResult, ToolError>> + Send + '_>> {
+        let _ = context;
+        self.execute(input)
+    }
+
+    /// Execute while reporting progress.
+    ///
+    /// Returns a stream of [`ToolUpdate::Progress`](crate::tool_progress::ToolUpdate::Progress)
+    /// items ending with one
+    /// [`ToolUpdate::Finished`](crate::tool_progress::ToolUpdate::Finished). The default wraps
+    /// [`execute_with_context`](Tool::execute_with_context) and reports no
+    /// progress; long-running tools override this, usually via
+    /// [`with_progress`](crate::tool_progress::with_progress).
+    fn execute_streaming(&self, input: Value, context: ToolContext) -> ToolUpdateStream<'_> {
+        let execution = self.execute_with_context(input, context);
+        crate::tool_progress::with_progress(|_| execution)
+    }
+
+    /// This module's [`ModuleLifecycle`], if it has one.
+    ///
+    /// Modules that hold resources (HTTP pools, file handles) implement
+    /// [`ModuleLifecycle`] and return `Some(self)` here so the coordinator
+    /// can initialize, health-check and shut them down.
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -186,6 +241,20 @@ pub trait Provider: Send + Sync {
     /// Each provider may encode tool calls differently in the response.
     /// This method normalises them into [`ToolCall`] structs.
     fn parse_tool_calls(&self, response: &ChatResponse) -> Vec<ToolCall>;
+
+    /// Check that the provider is reachable with its current credentials.
+    ///
+    /// The default lists models and discards them. Providers with a cheaper
+    /// probe, or whose `list_models` never leaves the process, should
+    /// override it.
+    fn ping(&self) -> BoxFuture<'_, Result<(), ProviderError>> {
+        Box::pin(async move { self.list_models().await.map(|_| ()) })
+    }
+
+    /// This module's [`ModuleLifecycle`], if it has one (see [`Tool::lifecycle`]).
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -242,6 +311,11 @@ pub trait Orchestrator: Send + Sync {
         hooks: Value,
         coordinator: Value,
     ) -> Pin<Box<dyn Future<Output = Result<String, AmplifierError>> + Send + '_>>;
+
+    /// This module's [`ModuleLifecycle`], if it has one (see [`Tool::lifecycle`]).
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -313,6 +387,58 @@ pub trait ContextManager: Send + Sync {
 
     /// Clear all messages from context.
     fn clear(&self) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>>;
+
+    /// Set the eviction priority of the message at `index` (zero-based, in
+    /// [`get_messages`](ContextManager::get_messages) order).
+    ///
+    /// Compaction must evict lower priorities first and never evict
+    /// [`MessagePriority::Pinned`] messages;
+    /// [`ContextBudget::fit`](crate::token_counter::ContextBudget::fit)
+    /// implements that policy. The default records the priority in the
+    /// message's metadata by rewriting the history with
+    /// [`set_messages`](ContextManager::set_messages).
+    ///
+    /// Returns [`ContextError::MessageNotFound`] if `index` is out of range.
+    fn set_message_priority(
+        &self,
+        index: usize,
+        priority: MessagePriority,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        Box::pin(async move {
+            let mut messages = self.get_messages().await?;
+            let len = messages.len();
+            let message = messages
+                .get_mut(index)
+                .ok_or(ContextError::MessageNotFound { index, len })?;
+            priority.apply(message);
+            self.set_messages(messages).await
+        })
+    }
+
+    /// Pin the message at `index` so compaction never drops it.
+    fn pin_message(
+        &self,
+        index: usize,
+    ) -> Pin<Box<dyn Future<Output = Result<(), ContextError>> + Send + '_>> {
+        self.set_message_priority(index, MessagePriority::Pinned)
+    }
+
+    /// Where each message came from, in
+    /// [`get_messages`](ContextManager::get_messages) order (`None` for
+    /// messages without a record; see [`crate::provenance`]).
+    ///
+    /// The default reads the records from the messages' metadata.
+    fn get_provenance(&self) -> BoxFuture<'_, Result<Vec<Option<Provenance>>, ContextError>> {
+        Box::pin(async move {
+            let messages = self.get_messages().await?;
+            Ok(messages.iter().map(Provenance::of).collect())
+        })
+    }
+
+    /// This module's [`ModuleLifecycle`], if it has one (see [`Tool::lifecycle`]).
+    fn lifecycle(&self) -> Option<&dyn ModuleLifecycle> {
+        None
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -446,6 +572,123 @@ pub trait DisplayService: Send + Sync {
     ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>>;
 }
 
+// ---------------------------------------------------------------------------
+// DisplayProvider
+// ---------------------------------------------------------------------------
+
+/// Interface for host UIs that present kernel activity to the user.
+///
+/// The native counterpart of Python's `display_system`. Mounted with
+/// [`set_display_provider`](crate::coordinator::Coordinator::set_display_provider),
+/// it is shown approval prompts by [`ApprovalGate`](crate::approval::ApprovalGate)
+/// and tool progress by [`ToolExecutor`](crate::tool_executor::ToolExecutor).
+///
+/// Every method defaults to doing nothing, so a host implements only what
+/// its UI can show. Display is best effort: callers log errors and carry on.
+/// [`crate::display`] has a no-op and a channel-backed implementation.
+///
+/// # Object safety
+///
+/// This trait is object-safe: `Arc<dyn DisplayProvider>` is the standard storage type.
+pub trait DisplayProvider: Send + Sync {
+    /// Display a message, as [`DisplayService::show_message`].
+    fn show_message(
+        &self,
+        _message: &str,
+        _level: &str,
+        _source: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Display progress reported by `source` (a tool name).
+    fn show_progress(
+        &self,
+        _source: &str,
+        _progress: &ToolProgress,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Display a pending approval. The decision itself still comes from the
+    /// [`ApprovalProvider`].
+    fn show_approval_prompt(
+        &self,
+        _request: &ApprovalRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Moderator
+// ---------------------------------------------------------------------------
+
+/// Interface for moderation / safety classifiers.
+///
+/// Mounted on the coordinator with
+/// [`set_moderator`](crate::coordinator::Coordinator::set_moderator); the
+/// [`builtin:moderation`](crate::hooks::builtin::ModerationHook) hook runs it
+/// over prompts and tool results and applies the session's policy.
+///
+/// # Object safety
+///
+/// This trait is object-safe: `Arc<dyn Moderator>` is the standard storage type.
+pub trait Moderator: Send + Sync {
+    /// Classify `content`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(ModerationResult)` with the classification.
+    /// `Err(AmplifierError)` if the classifier could not be reached.
+    fn moderate(
+        &self,
+        content: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<ModerationResult, AmplifierError>> + Send + '_>>;
+}
+
+// ---------------------------------------------------------------------------
+// ModuleLifecycle
+// ---------------------------------------------------------------------------
+
+/// Optional setup, health and teardown for mounted modules.
+///
+/// A module opts in by implementing this trait and returning `Some(self)`
+/// from its `lifecycle()` method (e.g. [`Provider::lifecycle`]). The
+/// [`Coordinator`](crate::coordinator::Coordinator) then:
+///
+/// - calls [`init`](Self::init) before a managed mount
+///   ([`mount_provider_managed`](crate::coordinator::Coordinator::mount_provider_managed),
+///   [`mount_tool_managed`](crate::coordinator::Coordinator::mount_tool_managed)),
+///   and does not mount the module if it fails;
+/// - includes [`health_check`](Self::health_check) in
+///   [`Coordinator::health`](crate::coordinator::Coordinator::health);
+/// - calls [`shutdown`](Self::shutdown) on managed unmount, when a managed
+///   mount replaces the module, and from
+///   [`Coordinator::cleanup`](crate::coordinator::Coordinator::cleanup).
+///
+/// Every method has a no-op default.
+pub trait ModuleLifecycle: Send + Sync {
+    /// Prepare the module with its mount-plan `config`.
+    fn init(
+        &self,
+        config: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        let _ = config;
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Report whether the module can currently serve requests.
+    fn health_check(&self) -> Pin<Box<dyn Future<Output = ModuleHealth> + Send + '_>> {
+        Box::pin(async { ModuleHealth::healthy() })
+    }
+
+    /// Release the module's resources. Called at most once per mount.
+    fn shutdown(&self) -> Pin<Box<dyn Future<Output = Result<(), AmplifierError>> + Send + '_>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -466,5 +709,33 @@ mod tests {
         fn _assert_hook(_: Arc<dyn HookHandler>) {}
         fn _assert_approval(_: Arc<dyn ApprovalProvider>) {}
         fn _assert_display(_: Arc<dyn DisplayService>) {}
+        fn _assert_lifecycle(_: Arc<dyn ModuleLifecycle>) {}
+    }
+
+    #[tokio::test]
+    async fn pin_message_default_records_priority_in_metadata() {
+        let context = crate::testing::FakeContextManager::new();
+        context
+            .set_messages(vec![
+                serde_json::json!({"role": "user", "content": "a"}),
+                serde_json::json!({"role": "user", "content": "b", "metadata": {"source": "x"}}),
+            ])
+            .await
+            .unwrap();
+
+        context.pin_message(1).await.unwrap();
+        let messages = context.get_messages().await.unwrap();
+        assert_eq!(MessagePriority::of(&messages[0]), MessagePriority::Normal);
+        assert_eq!(MessagePriority::of(&messages[1]), MessagePriority::Pinned);
+        assert_eq!(messages[1]["metadata"]["source"], "x");
+
+        let err = context
+            .set_message_priority(2, MessagePriority::Low)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ContextError::MessageNotFound { index: 2, len: 2 }
+        ));
     }
 }
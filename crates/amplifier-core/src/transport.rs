@@ -38,6 +38,17 @@ pub async fn load_grpc_tool(
     Ok(Arc::new(bridge))
 }
 
+/// Load a tool module via gRPC transport, sending its input in `format`.
+pub async fn load_grpc_tool_with_format(
+    endpoint: &str,
+    format: crate::wire::WireFormat,
+) -> Result<Arc<dyn Tool>, Box<dyn std::error::Error + Send + Sync>> {
+    let bridge = crate::bridges::grpc_tool::GrpcToolBridge::connect(endpoint)
+        .await?
+        .with_wire_format(format);
+    Ok(Arc::new(bridge))
+}
+
 /// Load an orchestrator module via gRPC transport.
 ///
 /// # Arguments
@@ -216,6 +227,19 @@ pub fn load_wasm_provider(
     Ok(Arc::new(bridge))
 }
 
+/// Load a WASM provider, resolving its declared credentials through
+/// `resolver` (requires `wasm` feature).
+#[cfg(feature = "wasm")]
+pub fn load_wasm_provider_with_credentials(
+    wasm_bytes: &[u8],
+    engine: Arc<wasmtime::Engine>,
+    resolver: &dyn crate::credentials::CredentialResolver,
+) -> Result<Arc<dyn Provider>, Box<dyn std::error::Error + Send + Sync>> {
+    let bridge = crate::bridges::wasm_provider::WasmProviderBridge::from_bytes(wasm_bytes, engine)?
+        .with_credentials(resolver)?;
+    Ok(Arc::new(bridge))
+}
+
 /// Load a WASM orchestrator from raw bytes (requires `wasm` feature).
 ///
 /// The orchestrator bridge requires a [`Coordinator`](crate::coordinator::Coordinator)
@@ -312,6 +336,17 @@ mod tests {
         assert!(provider.is_ok());
     }
 
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn load_wasm_provider_with_credentials_resolves_through_resolver() {
+        let wasm_bytes = fixture("echo-provider.wasm");
+        let engine = crate::wasm_engine::WasmEngine::new().unwrap();
+        let resolver = crate::credentials::StaticCredentialResolver::new();
+        let provider =
+            super::load_wasm_provider_with_credentials(&wasm_bytes, engine.inner(), &resolver);
+        assert!(provider.is_ok());
+    }
+
     #[cfg(feature = "wasm")]
     #[test]
     fn load_wasm_orchestrator_returns_arc_dyn_orchestrator() {
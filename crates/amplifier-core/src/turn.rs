@@ -0,0 +1,405 @@
+//! TurnResult — structured output of a single conversation turn.
+//!
+//! [`Session::run_turn`](crate::session::Session::run_turn) returns a
+//! [`TurnResult`] instead of the orchestrator's flattened string, so UIs can
+//! render thinking blocks, tool activity and usage directly.
+//!
+//! Orchestrators only return a `String`, so the rest of the result is
+//! assembled by a [`TurnRecorder`] hook that observes the turn's events:
+//!
+//! | Event               | Payload key(s) read               | Contributes                     |
+//! |---------------------|-----------------------------------|---------------------------------|
+//! | `provider:response` | `response`                        | content, usage, degradations, stop reason |
+//! | `provider:post`     | `response`                        | same, when no `provider:response` was seen |
+//! | `tool:post`         | `tool_name`, `tool_input`, `tool_result` | a successful [`ToolCallRecord`] |
+//! | `tool:error`        | `tool_name`, `tool_input`, `error` | a failed [`ToolCallRecord`]    |
+//! | `orchestrator:recovery` | the whole payload             | a [`RecoveryAttempt`]           |
+//!
+//! `provider:post` is emitted by the kernel's
+//! [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker); it is only
+//! used as a fallback so an orchestrator that both routes through the invoker
+//! and emits `provider:response` is not counted twice.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::errors::HookError;
+use crate::events;
+use crate::messages::{ChatResponse, ContentBlock, Degradation, Usage};
+use crate::models::HookResult;
+use crate::pricing;
+use crate::recovery::RecoveryAttempt;
+use crate::traits::HookHandler;
+
+/// Events a [`TurnRecorder`] must be registered on.
+pub const RECORDED_EVENTS: &[&str] = &[
+    events::PROVIDER_RESPONSE,
+    events::PROVIDER_POST,
+    events::TOOL_POST,
+    events::TOOL_ERROR,
+    events::ORCHESTRATOR_RECOVERY,
+];
+
+/// One tool invocation made during a turn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub tool_name: String,
+    #[serde(default)]
+    pub input: Value,
+    /// The tool's result payload from `tool:post`, which may itself report
+    /// failure (`null` for `tool:error`).
+    #[serde(default)]
+    pub output: Value,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Everything a turn produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnResult {
+    /// The orchestrator's final response string.
+    pub output: String,
+    /// Assistant content blocks from the final provider response. Falls back
+    /// to a single text block holding `output` when no response was observed.
+    pub content: Vec<ContentBlock>,
+    /// Tool calls in completion order.
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Usage summed over every provider response in the turn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    /// Degradations reported by any provider response in the turn.
+    pub degradations: Vec<Degradation>,
+    /// `finish_reason` of the final provider response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+    /// Number of provider responses observed.
+    pub provider_calls: usize,
+    /// Recovery decisions applied to failures during the turn (see
+    /// [`crate::recovery`]).
+    #[serde(default)]
+    pub recovery_attempts: Vec<RecoveryAttempt>,
+}
+
+// ---------------------------------------------------------------------------
+// TurnRecorder
+// ---------------------------------------------------------------------------
+
+#[derive(Default)]
+struct Recorded {
+    responses: Vec<ChatResponse>,
+    post_responses: Vec<ChatResponse>,
+    tool_calls: Vec<ToolCallRecord>,
+    recovery_attempts: Vec<RecoveryAttempt>,
+}
+
+/// Hook handler that collects provider responses and tool calls for one turn.
+///
+/// Always returns [`HookResult::default()`] (continue); it never alters the
+/// pipeline.
+#[derive(Default)]
+pub struct TurnRecorder {
+    recorded: Mutex<Recorded>,
+}
+
+impl TurnRecorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, event: &str, data: &Value) {
+        let mut recorded = self.recorded.lock().unwrap();
+        match event {
+            events::PROVIDER_RESPONSE | events::PROVIDER_POST => {
+                let Some(response) = data
+                    .get("response")
+                    .and_then(|r| serde_json::from_value::<ChatResponse>(r.clone()).ok())
+                else {
+                    log::debug!("TurnRecorder: ignoring {event} without a parseable response");
+                    return;
+                };
+                if event == events::PROVIDER_RESPONSE {
+                    recorded.responses.push(response);
+                } else {
+                    recorded.post_responses.push(response);
+                }
+            }
+            events::TOOL_POST | events::TOOL_ERROR => {
+                let posted = event == events::TOOL_POST;
+                let result = data.get("tool_result").filter(|_| posted);
+                // A tool:post result can still report failure.
+                let success = posted
+                    && result
+                        .and_then(|r| r.get("success"))
+                        .and_then(Value::as_bool)
+                        .unwrap_or(true);
+                let error = (!success).then(|| {
+                    let error = match result {
+                        Some(result) => result.get("error"),
+                        None => data.get("error"),
+                    };
+                    match error {
+                        Some(Value::String(s)) => s.clone(),
+                        Some(Value::Object(map)) => match map.get("message") {
+                            Some(Value::String(message)) => message.clone(),
+                            _ => Value::Object(map.clone()).to_string(),
+                        },
+                        Some(Value::Null) | None => "tool error".to_string(),
+                        Some(other) => other.to_string(),
+                    }
+                });
+                recorded.tool_calls.push(ToolCallRecord {
+                    tool_name: data
+                        .get("tool_name")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                    input: data.get("tool_input").cloned().unwrap_or(Value::Null),
+                    output: result.cloned().unwrap_or(Value::Null),
+                    success,
+                    error,
+                });
+            }
+            events::ORCHESTRATOR_RECOVERY => {
+                match serde_json::from_value::<RecoveryAttempt>(data.clone()) {
+                    Ok(attempt) => recorded.recovery_attempts.push(attempt),
+                    Err(e) => log::debug!("TurnRecorder: ignoring malformed {event}: {e}"),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Build the turn result around the orchestrator's `output`.
+    pub fn finish(&self, output: String) -> TurnResult {
+        let recorded = std::mem::take(&mut *self.recorded.lock().unwrap());
+        let responses = if recorded.responses.is_empty() {
+            recorded.post_responses
+        } else {
+            recorded.responses
+        };
+
+        let usage = responses.iter().filter_map(|r| r.usage.as_ref()).fold(
+            None,
+            |acc: Option<Usage>, u| {
+                Some(match acc {
+                    None => u.clone(),
+                    Some(total) => add_usage(total, u),
+                })
+            },
+        );
+        let degradations = responses
+            .iter()
+            .filter_map(|r| r.degradation.clone())
+            .collect();
+        let provider_calls = responses.len();
+        let (content, stop_reason) = match responses.into_iter().last() {
+            Some(last) => (last.content, last.finish_reason),
+            None => (
+                vec![ContentBlock::Text {
+                    text: output.clone(),
+                    visibility: None,
+                    cache: None,
+                    extensions: Default::default(),
+                }],
+                None,
+            ),
+        };
+
+        TurnResult {
+            output,
+            content,
+            tool_calls: recorded.tool_calls,
+            usage,
+            degradations,
+            stop_reason,
+            provider_calls,
+            recovery_attempts: recorded.recovery_attempts,
+        }
+    }
+}
+
+impl HookHandler for TurnRecorder {
+    fn handle(
+        &self,
+        event: &str,
+        data: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<HookResult, HookError>> + Send + '_>> {
+        self.record(event, &data);
+        Box::pin(async { Ok(HookResult::default()) })
+    }
+}
+
+pub(crate) fn add_usage(mut total: Usage, next: &Usage) -> Usage {
+    fn add_opt(a: Option<i64>, b: Option<i64>) -> Option<i64> {
+        match (a, b) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+        }
+    }
+    total.input_tokens += next.input_tokens;
+    total.output_tokens += next.output_tokens;
+    total.total_tokens += next.total_tokens;
+    total.reasoning_tokens = add_opt(total.reasoning_tokens, next.reasoning_tokens);
+    total.cache_read_tokens = add_opt(total.cache_read_tokens, next.cache_read_tokens);
+    total.cache_write_tokens = add_opt(total.cache_write_tokens, next.cache_write_tokens);
+    // A turn's cost is only known if every call's cost is.
+    total.cost_usd = match (total.cost_usd.as_deref(), next.cost_usd.as_deref()) {
+        (Some(a), Some(b)) => pricing::parse_usd(a)
+            .zip(pricing::parse_usd(b))
+            .map(|(a, b)| pricing::format_usd(a + b)),
+        _ => None,
+    };
+    total
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(text: &str, input_tokens: i64, finish: &str) -> Value {
+        json!({
+            "content": [{"type": "text", "text": text}],
+            "usage": {"input_tokens": input_tokens, "output_tokens": 1, "total_tokens": input_tokens + 1},
+            "finish_reason": finish,
+        })
+    }
+
+    #[test]
+    fn empty_turn_falls_back_to_output_text() {
+        let result = TurnRecorder::new().finish("plain".into());
+        assert_eq!(result.provider_calls, 0);
+        assert!(result.usage.is_none());
+        assert!(
+            matches!(&result.content[..], [ContentBlock::Text { text, .. }] if text == "plain")
+        );
+    }
+
+    #[test]
+    fn usage_is_summed_and_last_response_wins() {
+        let recorder = TurnRecorder::new();
+        recorder.record(
+            events::PROVIDER_RESPONSE,
+            &json!({"response": response("thinking about it", 10, "tool_use")}),
+        );
+        recorder.record(
+            events::PROVIDER_RESPONSE,
+            &json!({"response": response("done", 20, "end_turn")}),
+        );
+
+        let result = recorder.finish("done".into());
+        assert_eq!(result.provider_calls, 2);
+        assert_eq!(result.usage.as_ref().unwrap().input_tokens, 30);
+        assert_eq!(result.usage.as_ref().unwrap().total_tokens, 32);
+        assert_eq!(result.stop_reason.as_deref(), Some("end_turn"));
+        assert!(matches!(&result.content[..], [ContentBlock::Text { text, .. }] if text == "done"));
+    }
+
+    #[test]
+    fn costs_are_summed_only_when_every_call_reports_one() {
+        let priced = |cost: &str| {
+            let mut r = response("a", 5, "end_turn");
+            r["usage"]["cost_usd"] = json!(cost);
+            json!({ "response": r })
+        };
+        let recorder = TurnRecorder::new();
+        recorder.record(events::PROVIDER_RESPONSE, &priced("0.01"));
+        recorder.record(events::PROVIDER_RESPONSE, &priced("0.002"));
+        let usage = recorder.finish("a".into()).usage.unwrap();
+        assert_eq!(usage.cost_usd.as_deref(), Some("0.012000"));
+
+        recorder.record(events::PROVIDER_RESPONSE, &priced("0.01"));
+        recorder.record(
+            events::PROVIDER_RESPONSE,
+            &json!({"response": response("a", 5, "end_turn")}),
+        );
+        assert!(recorder
+            .finish("a".into())
+            .usage
+            .unwrap()
+            .cost_usd
+            .is_none());
+    }
+
+    #[test]
+    fn provider_post_is_only_a_fallback() {
+        let recorder = TurnRecorder::new();
+        recorder.record(
+            events::PROVIDER_POST,
+            &json!({"response": response("a", 5, "end_turn")}),
+        );
+        assert_eq!(recorder.finish("a".into()).provider_calls, 1);
+
+        recorder.record(
+            events::PROVIDER_POST,
+            &json!({"response": response("a", 5, "end_turn")}),
+        );
+        recorder.record(
+            events::PROVIDER_RESPONSE,
+            &json!({"response": response("a", 5, "end_turn")}),
+        );
+        let result = recorder.finish("a".into());
+        assert_eq!(result.provider_calls, 1);
+        assert_eq!(result.usage.unwrap().input_tokens, 5);
+    }
+
+    #[test]
+    fn tool_calls_and_failures_are_recorded() {
+        let recorder = TurnRecorder::new();
+        recorder.record(
+            events::TOOL_POST,
+            &json!({"tool_name": "read", "tool_input": {"path": "a"}, "tool_result": {"output": "x"}}),
+        );
+        recorder.record(
+            events::TOOL_ERROR,
+            &json!({"tool_name": "bash", "tool_input": {}, "error": "exit 1"}),
+        );
+        recorder.record(
+            events::TOOL_POST,
+            &json!({
+                "tool_name": "grep",
+                "tool_input": {},
+                "tool_result": {"success": false, "error": {"message": "no such file"}},
+            }),
+        );
+
+        let calls = recorder.finish(String::new()).tool_calls;
+        assert_eq!(calls.len(), 3);
+        assert!(calls[0].success);
+        assert_eq!(calls[0].output["output"], "x");
+        assert!(!calls[1].success);
+        assert_eq!(calls[1].error.as_deref(), Some("exit 1"));
+        assert!(!calls[2].success);
+        assert_eq!(calls[2].error.as_deref(), Some("no such file"));
+        assert_eq!(calls[2].output["success"], false);
+    }
+
+    #[test]
+    fn recovery_decisions_are_recorded() {
+        let recorder = TurnRecorder::new();
+        recorder.record(
+            events::ORCHESTRATOR_RECOVERY,
+            &json!({"kind": "provider", "target": "openai", "error": "rate limited",
+                    "action": "retry_provider", "failures": 1, "timestamp": "t"}),
+        );
+        recorder.record(events::ORCHESTRATOR_RECOVERY, &json!({"kind": "nope"}));
+
+        let attempts = recorder.finish(String::new()).recovery_attempts;
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].target, "openai");
+        assert_eq!(
+            attempts[0].action,
+            crate::recovery::RecoveryAction::RetryProvider
+        );
+    }
+}
@@ -0,0 +1,293 @@
+//! Content visibility enforcement for provider requests.
+//!
+//! Content blocks carry a [`Visibility`]. Blocks marked
+//! [`Visibility::Internal`] and `redacted_thinking` blocks hold the session's
+//! own reasoning and must not reach a provider that was not explicitly
+//! trusted with it. [`enforce`] strips them from a [`ChatRequest`] according
+//! to a [`VisibilityPolicy`]:
+//!
+//! | Content                          | Sent when                           |
+//! |----------------------------------|-------------------------------------|
+//! | `visibility: "internal"` blocks  | `allow_internal`                    |
+//! | `redacted_thinking` blocks       | `allow_redacted_thinking`           |
+//! | everything else                  | always                              |
+//!
+//! Removing an internal `tool_call` also removes its results, so the
+//! provider never sees an orphaned `tool_result`. Messages left without any
+//! content blocks are dropped.
+//!
+//! Policies are per provider. [`VisibilityConfig`] is read from the
+//! `session.visibility` section of the mount plan:
+//!
+//! ```json
+//! {
+//!   "session": {
+//!     "visibility": {
+//!       "default": {"allow_internal": false},
+//!       "providers": {
+//!         "anthropic": {"allow_internal": true, "allow_redacted_thinking": true}
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Nothing is allowed by default.
+//! [`ProviderInvoker`](crate::provider_invoker::ProviderInvoker) applies the
+//! policy of the provider it calls to every request and records what was
+//! removed in the response's `metadata["withheld_content"]`.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::messages::{ChatRequest, ContentBlock, MessageContent, Role, Visibility};
+
+/// `ChatResponse.metadata` key under which a non-empty [`VisibilityReport`]
+/// is recorded.
+pub const WITHHELD_METADATA_KEY: &str = "withheld_content";
+
+// ---------------------------------------------------------------------------
+// VisibilityPolicy / VisibilityConfig
+// ---------------------------------------------------------------------------
+
+/// Which restricted content one provider may receive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisibilityPolicy {
+    /// Send blocks marked `visibility: "internal"`.
+    pub allow_internal: bool,
+    /// Send `redacted_thinking` blocks.
+    pub allow_redacted_thinking: bool,
+}
+
+impl VisibilityPolicy {
+    /// A policy that sends all content.
+    pub fn allow_all() -> Self {
+        Self {
+            allow_internal: true,
+            allow_redacted_thinking: true,
+        }
+    }
+}
+
+/// Per-provider visibility policies (`session.visibility`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisibilityConfig {
+    /// Policy for providers without an entry in `providers`.
+    pub default: VisibilityPolicy,
+    /// Policies by provider name.
+    pub providers: HashMap<String, VisibilityPolicy>,
+}
+
+impl VisibilityConfig {
+    /// Read `session.visibility` from a mount plan, falling back to the
+    /// defaults when the section is absent or malformed (logged).
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Self {
+        let Some(raw) = config.get("session").and_then(|s| s.get("visibility")) else {
+            return Self::default();
+        };
+        serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::warn!("Ignoring malformed session.visibility config: {e}");
+            Self::default()
+        })
+    }
+
+    /// Set the policy for `provider`.
+    pub fn with_provider(mut self, provider: impl Into<String>, policy: VisibilityPolicy) -> Self {
+        self.providers.insert(provider.into(), policy);
+        self
+    }
+
+    /// The policy that applies to `provider`.
+    pub fn policy_for(&self, provider: &str) -> VisibilityPolicy {
+        self.providers
+            .get(provider)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Enforcement
+// ---------------------------------------------------------------------------
+
+/// What [`enforce`] removed from a request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisibilityReport {
+    /// Blocks removed because they were marked internal, including the
+    /// results of removed tool calls.
+    pub internal_blocks: usize,
+    /// `redacted_thinking` blocks removed.
+    pub redacted_thinking_blocks: usize,
+    /// Messages dropped because nothing was left in them.
+    pub dropped_messages: usize,
+}
+
+impl VisibilityReport {
+    /// Whether the request was left unchanged.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Add the counts of a later pass over the same request.
+    pub fn merge(&mut self, later: VisibilityReport) {
+        self.internal_blocks += later.internal_blocks;
+        self.redacted_thinking_blocks += later.redacted_thinking_blocks;
+        self.dropped_messages += later.dropped_messages;
+    }
+}
+
+/// Strip the content `policy` does not allow from `request`.
+pub fn enforce(request: &mut ChatRequest, policy: VisibilityPolicy) -> VisibilityReport {
+    let mut report = VisibilityReport::default();
+    if policy == VisibilityPolicy::allow_all() {
+        return report;
+    }
+
+    let mut removed_calls = HashSet::new();
+    for message in &mut request.messages {
+        let MessageContent::Blocks(blocks) = &mut message.content else {
+            continue;
+        };
+        blocks.retain(|block| {
+            if !policy.allow_redacted_thinking
+                && matches!(block, ContentBlock::RedactedThinking { .. })
+            {
+                report.redacted_thinking_blocks += 1;
+                return false;
+            }
+            if !policy.allow_internal && block.visibility() == Some(&Visibility::Internal) {
+                if let ContentBlock::ToolCall { id, .. } = block {
+                    removed_calls.insert(id.clone());
+                }
+                report.internal_blocks += 1;
+                return false;
+            }
+            true
+        });
+    }
+
+    if !removed_calls.is_empty() {
+        for message in &mut request.messages {
+            if let MessageContent::Blocks(blocks) = &mut message.content {
+                blocks.retain(|block| match block {
+                    ContentBlock::ToolResult { tool_call_id, .. }
+                        if removed_calls.contains(tool_call_id) =>
+                    {
+                        report.internal_blocks += 1;
+                        false
+                    }
+                    _ => true,
+                });
+            }
+        }
+    }
+
+    let before = request.messages.len();
+    request.messages.retain(|message| {
+        let orphaned_result = message.role == Role::Tool
+            && message
+                .tool_call_id
+                .as_ref()
+                .is_some_and(|id| removed_calls.contains(id));
+        let emptied = matches!(&message.content, MessageContent::Blocks(b) if b.is_empty());
+        !(orphaned_result || emptied)
+    });
+    report.dropped_messages = before - request.messages.len();
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request() -> ChatRequest {
+        serde_json::from_value(json!({
+            "messages": [
+                {"role": "user", "content": "What is 2 + 2?"},
+                {"role": "assistant", "content": [
+                    {"type": "thinking", "thinking": "private plan", "visibility": "internal"},
+                    {"type": "redacted_thinking", "data": "opaque"},
+                    {"type": "tool_call", "id": "c1", "name": "scratch", "input": {},
+                     "visibility": "internal"},
+                    {"type": "tool_call", "id": "c2", "name": "calc", "input": {}}
+                ]},
+                {"role": "tool", "tool_call_id": "c1", "content": [
+                    {"type": "tool_result", "tool_call_id": "c1", "output": "notes"}
+                ]},
+                {"role": "tool", "tool_call_id": "c2", "content": [
+                    {"type": "tool_result", "tool_call_id": "c2", "output": 4}
+                ]},
+                {"role": "assistant", "content": [
+                    {"type": "redacted_thinking", "data": "opaque"}
+                ]}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn internal_reasoning_never_reaches_untrusted_providers() {
+        let mut req = request();
+        let report = enforce(&mut req, VisibilityPolicy::default());
+
+        let sent = serde_json::to_string(&req).unwrap();
+        for leaked in ["private plan", "opaque", "scratch", "notes", "\"c1\""] {
+            assert!(!sent.contains(leaked), "{leaked} leaked: {sent}");
+        }
+        assert!(sent.contains("calc"));
+        assert_eq!(req.messages.len(), 3);
+        assert_eq!(
+            report,
+            VisibilityReport {
+                internal_blocks: 3,
+                redacted_thinking_blocks: 2,
+                dropped_messages: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn allowed_content_is_sent() {
+        let mut req = request();
+        assert!(enforce(&mut req, VisibilityPolicy::allow_all()).is_empty());
+        assert_eq!(req, request());
+
+        let mut req = request();
+        let report = enforce(
+            &mut req,
+            VisibilityPolicy {
+                allow_internal: false,
+                allow_redacted_thinking: true,
+            },
+        );
+        assert_eq!(report.redacted_thinking_blocks, 0);
+        assert!(serde_json::to_string(&req).unwrap().contains("opaque"));
+    }
+
+    #[test]
+    fn config_reads_per_provider_policies() {
+        let config = HashMap::from([(
+            "session".to_string(),
+            json!({"visibility": {"providers": {"anthropic": {"allow_internal": true}}}}),
+        )]);
+        let config = VisibilityConfig::from_session_config(&config);
+        assert!(config.policy_for("anthropic").allow_internal);
+        assert!(!config.policy_for("anthropic").allow_redacted_thinking);
+        assert_eq!(config.policy_for("openai"), VisibilityPolicy::default());
+
+        let malformed = HashMap::from([("session".to_string(), json!({"visibility": 3}))]);
+        assert_eq!(
+            VisibilityConfig::from_session_config(&malformed),
+            VisibilityConfig::default()
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Serialization formats for data crossing a process boundary.
+//!
+//! JSON is always available. MessagePack (feature `msgpack`) and CBOR
+//! (feature `cbor`) encode the same serde models in less space, which
+//! matters most for large tool results and context snapshots.
+//!
+//! | Format                      | Feature   | Content type          |
+//! |-----------------------------|-----------|-----------------------|
+//! | [`WireFormat::Json`]        | always    | `application/json`    |
+//! | [`WireFormat::MessagePack`] | `msgpack` | `application/msgpack` |
+//! | [`WireFormat::Cbor`]        | `cbor`    | `application/cbor`    |
+//!
+//! Every format round-trips every core model. MessagePack is written with
+//! field names rather than as positional arrays, because the models rely on
+//! flattened and optional fields.
+//!
+//! The side that sends a payload chooses its format and labels it with
+//! [`WireFormat::content_type`]; the receiver decodes by that label (see
+//! [`WireFormat::from_content_type`]). A missing label means JSON.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// WireError
+// ---------------------------------------------------------------------------
+
+/// Errors from encoding, decoding or negotiating a wire format.
+#[derive(Debug, thiserror::Error)]
+pub enum WireError {
+    /// The format is known but this build was compiled without its feature.
+    #[error("wire format {format} is not enabled in this build (feature `{feature}`)")]
+    Unavailable {
+        format: WireFormat,
+        feature: &'static str,
+    },
+
+    /// A name or content type that does not identify any format.
+    #[error("unknown wire format: {0}")]
+    Unknown(String),
+
+    #[error("failed to encode {format}: {message}")]
+    Encode { format: WireFormat, message: String },
+
+    #[error("failed to decode {format}: {message}")]
+    Decode { format: WireFormat, message: String },
+}
+
+// ---------------------------------------------------------------------------
+// WireFormat
+// ---------------------------------------------------------------------------
+
+/// A serialization format for cross-boundary payloads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WireFormat {
+    #[default]
+    Json,
+    #[serde(rename = "msgpack")]
+    MessagePack,
+    Cbor,
+}
+
+impl WireFormat {
+    /// All formats, whether or not they are enabled.
+    pub const ALL: [WireFormat; 3] = [Self::Json, Self::MessagePack, Self::Cbor];
+
+    /// Short name used in configuration (`"json"`, `"msgpack"`, `"cbor"`).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+            Self::Cbor => "cbor",
+        }
+    }
+
+    /// MIME type that labels payloads in this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MessagePack => "application/msgpack",
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// The format a content type label names. An empty label is JSON;
+    /// parameters such as `; charset=utf-8` are ignored.
+    pub fn from_content_type(content_type: &str) -> Result<Self, WireError> {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match essence.as_str() {
+            "" | "application/json" => Ok(Self::Json),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Ok(Self::MessagePack)
+            }
+            "application/cbor" => Ok(Self::Cbor),
+            _ => Err(WireError::Unknown(content_type.to_string())),
+        }
+    }
+
+    /// Whether this build can encode and decode the format.
+    pub fn is_available(self) -> bool {
+        match self {
+            Self::Json => true,
+            Self::MessagePack => cfg!(feature = "msgpack"),
+            Self::Cbor => cfg!(feature = "cbor"),
+        }
+    }
+
+    /// The formats this build supports, JSON first.
+    pub fn available() -> Vec<WireFormat> {
+        Self::ALL.into_iter().filter(|f| f.is_available()).collect()
+    }
+
+    /// Pick the first of the peer's `preferred` formats this build supports,
+    /// falling back to JSON.
+    pub fn negotiate(preferred: &[WireFormat]) -> Self {
+        preferred
+            .iter()
+            .copied()
+            .find(|f| f.is_available())
+            .unwrap_or_default()
+    }
+
+    /// Serialize `value` in this format.
+    pub fn encode<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>, WireError> {
+        let encode_err = |e: &dyn fmt::Display| WireError::Encode {
+            format: self,
+            message: e.to_string(),
+        };
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| encode_err(&e)),
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| encode_err(&e)),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut out = Vec::new();
+                ciborium::into_writer(value, &mut out).map_err(|e| encode_err(&e))?;
+                Ok(out)
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unavailable()),
+        }
+    }
+
+    /// Deserialize a `T` from `bytes` in this format.
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> Result<T, WireError> {
+        let decode_err = |e: &dyn fmt::Display| WireError::Decode {
+            format: self,
+            message: e.to_string(),
+        };
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| decode_err(&e)),
+            #[cfg(feature = "msgpack")]
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| decode_err(&e)),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| decode_err(&e)),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unavailable()),
+        }
+    }
+
+    fn unavailable(self) -> WireError {
+        WireError::Unavailable {
+            format: self,
+            feature: self.name(),
+        }
+    }
+}
+
+impl fmt::Display for WireFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for WireFormat {
+    type Err = WireError;
+
+    /// Parse a short name (`"json"`, `"msgpack"`, `"cbor"`) or a content type.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "msgpack" | "messagepack" => Ok(Self::MessagePack),
+            "cbor" => Ok(Self::Cbor),
+            _ => Self::from_content_type(s),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::ChatResponse;
+    use crate::models::ToolResult;
+    use serde_json::json;
+
+    fn response() -> ChatResponse {
+        serde_json::from_value(json!({
+            "content": [
+                {"type": "thinking", "thinking": "plan", "signature": "sig"},
+                {"type": "text", "text": "Looking it up."},
+                {"type": "tool_call", "id": "c1", "name": "search", "input": {"q": "rust", "n": 3}}
+            ],
+            "tool_calls": [{"id": "c1", "name": "search", "arguments": {"q": "rust", "n": 3}}],
+            "usage": {"input_tokens": 12, "output_tokens": 30, "total_tokens": 42},
+            "finish_reason": "tool_use",
+            "metadata": {"provider": "fake", "latency_ms": 1.5}
+        }))
+        .unwrap()
+    }
+
+    fn tool_result() -> ToolResult {
+        ToolResult {
+            success: true,
+            output: Some(json!({"rows": [[1, "a"], [2, null]], "big": u64::MAX, "f": -0.25})),
+            error: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn names_and_content_types_identify_formats() {
+        for format in WireFormat::ALL {
+            assert_eq!(format.name().parse::<WireFormat>().unwrap(), format);
+            assert_eq!(
+                WireFormat::from_content_type(format.content_type()).unwrap(),
+                format
+            );
+            let json = serde_json::to_value(format).unwrap();
+            assert_eq!(json, json!(format.name()));
+        }
+        assert_eq!(WireFormat::from_content_type("").unwrap(), WireFormat::Json);
+        assert_eq!(
+            WireFormat::from_content_type("Application/JSON; charset=utf-8").unwrap(),
+            WireFormat::Json
+        );
+        assert!(matches!(
+            WireFormat::from_content_type("text/xml"),
+            Err(WireError::Unknown(_))
+        ));
+        assert_eq!(WireFormat::available()[0], WireFormat::Json);
+        assert_eq!(WireFormat::negotiate(&[]), WireFormat::Json);
+    }
+
+    #[test]
+    fn every_available_format_round_trips_core_models() {
+        for format in WireFormat::available() {
+            let bytes = format.encode(&response()).unwrap();
+            let decoded: ChatResponse = format.decode(&bytes).unwrap();
+            assert_eq!(decoded, response(), "{format}");
+
+            let bytes = format.encode(&tool_result()).unwrap();
+            let decoded: ToolResult = format.decode(&bytes).unwrap();
+            assert_eq!(decoded, tool_result(), "{format}");
+        }
+    }
+
+    #[test]
+    fn disabled_formats_are_rejected_not_misread() {
+        for format in WireFormat::ALL {
+            if format.is_available() {
+                assert!(format.decode::<ToolResult>(b"\xff\x00").is_err());
+            } else {
+                assert!(matches!(
+                    format.encode(&tool_result()),
+                    Err(WireError::Unavailable { .. })
+                ));
+                assert_eq!(WireFormat::negotiate(&[format]), WireFormat::Json);
+            }
+        }
+    }
+
+    #[cfg(all(feature = "msgpack", feature = "cbor"))]
+    #[test]
+    fn binary_formats_are_smaller_than_json() {
+        let large = ToolResult {
+            success: true,
+            output: Some(json!((0..500)
+                .map(|i| json!({"id": i, "ok": i % 2 == 0}))
+                .collect::<Vec<_>>())),
+            ..Default::default()
+        };
+        let json = WireFormat::Json.encode(&large).unwrap().len();
+        for format in [WireFormat::MessagePack, WireFormat::Cbor] {
+            assert!(format.encode(&large).unwrap().len() < json, "{format}");
+        }
+        assert_eq!(
+            WireFormat::negotiate(&[WireFormat::Cbor, WireFormat::MessagePack]),
+            WireFormat::Cbor
+        );
+    }
+}
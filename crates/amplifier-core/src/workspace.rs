@@ -0,0 +1,338 @@
+//! Per-session working directory and filesystem scope.
+//!
+//! `session.workspace` gives a session a root directory and the paths its
+//! tools may touch:
+//!
+//! ```json
+//! {"session": {"workspace": {
+//!     "root": "/home/me/project",
+//!     "allowed_paths": ["/tmp/scratch"],
+//!     "read_only_paths": ["/home/me/project/vendor"],
+//!     "write_tools": ["write_*", "edit_*", "bash"]
+//! }}}
+//! ```
+//!
+//! Tools see the workspace on their
+//! [`ToolContext`](crate::models::ToolContext) and should resolve relative
+//! paths with [`Workspace::resolve`]. The session's
+//! [`PermissionPolicy`](crate::policy::PermissionPolicy) enforces it on
+//! every `tool:pre`, for the arguments named in the policy's
+//! `path_arguments`:
+//!
+//! | Path argument                                        | Outcome |
+//! |------------------------------------------------------|---------|
+//! | outside `root` and every `allowed_paths` prefix      | `deny`  |
+//! | under a `read_only_paths` prefix (or `read_only` set), passed to a tool matching `write_tools` | `deny` |
+//! | anything else                                        | checked against the policy rules |
+//!
+//! Relative paths are taken relative to `root`; all paths are normalized
+//! lexically (`.` and `..` resolved, symlinks not followed) and prefixes
+//! match whole components. A malformed `session.workspace` fails closed:
+//! every path argument is denied.
+//!
+//! A lexical check cannot see a symlink inside the workspace that points
+//! outside it. Tools that open files should use [`Workspace::check_real`],
+//! which repeats the check with symlinks resolved.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::policy::{glob_match, normalize};
+
+/// How a tool uses a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathAccess {
+    Read,
+    Write,
+}
+
+/// Why a path is not usable.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WorkspaceViolation {
+    #[error("{} is outside the workspace", path.display())]
+    OutsideWorkspace { path: PathBuf },
+    #[error("{} is read-only", path.display())]
+    ReadOnly { path: PathBuf },
+}
+
+/// The `session.workspace` section.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workspace {
+    /// Working directory; relative paths resolve against it. Should be
+    /// absolute.
+    pub root: PathBuf,
+    /// Prefixes outside `root` that tools may also use.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_paths: Vec<PathBuf>,
+    /// Make every path read-only.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Prefixes that are read-only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub read_only_paths: Vec<PathBuf>,
+    /// Tool-name globs whose path arguments are writes.
+    #[serde(default = "default_write_tools")]
+    pub write_tools: Vec<String>,
+    /// Set for a malformed section: no path is inside.
+    #[serde(skip)]
+    sealed: bool,
+}
+
+fn default_write_tools() -> Vec<String> {
+    [
+        "write*",
+        "edit*",
+        "create*",
+        "delete*",
+        "move*",
+        "rename*",
+        "apply_patch",
+        "bash",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Workspace {
+    /// A workspace rooted at `root`, with no extra paths and nothing
+    /// read-only.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            allowed_paths: Vec::new(),
+            read_only: false,
+            read_only_paths: Vec::new(),
+            write_tools: default_write_tools(),
+            sealed: false,
+        }
+    }
+
+    /// Also allow paths under `prefix`.
+    pub fn with_allowed_path(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.allowed_paths.push(prefix.into());
+        self
+    }
+
+    /// Make paths under `prefix` read-only.
+    pub fn with_read_only_path(mut self, prefix: impl Into<PathBuf>) -> Self {
+        self.read_only_paths.push(prefix.into());
+        self
+    }
+
+    /// Read `session.workspace` from a mount plan.
+    ///
+    /// Returns `None` when the section is absent. A malformed section is
+    /// logged and replaced by a workspace that contains no path.
+    pub fn from_session_config(config: &HashMap<String, Value>) -> Option<Self> {
+        let raw = config.get("session").and_then(|s| s.get("workspace"))?;
+        Some(serde_json::from_value(raw.clone()).unwrap_or_else(|e| {
+            log::error!("Malformed session.workspace config ({e}); denying all paths");
+            Self {
+                sealed: true,
+                ..Self::new(PathBuf::new())
+            }
+        }))
+    }
+
+    /// `path` made absolute against `root` and normalized.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        normalize(&self.root.join(path))
+    }
+
+    /// Whether `path` lies under `root` or an allowed prefix.
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        if self.sealed {
+            return false;
+        }
+        let path = self.resolve(path);
+        std::iter::once(normalize(&self.root))
+            .chain(self.allowed_paths.iter().map(|prefix| self.resolve(prefix)))
+            .any(|prefix| path.starts_with(prefix))
+    }
+
+    /// Whether `path` may not be written.
+    pub fn is_read_only(&self, path: impl AsRef<Path>) -> bool {
+        let path = self.resolve(path);
+        self.read_only
+            || self
+                .read_only_paths
+                .iter()
+                .any(|prefix| path.starts_with(self.resolve(prefix)))
+    }
+
+    /// How `tool_name` uses its path arguments.
+    pub fn access_for(&self, tool_name: &str) -> PathAccess {
+        if self.write_tools.iter().any(|g| glob_match(g, tool_name)) {
+            PathAccess::Write
+        } else {
+            PathAccess::Read
+        }
+    }
+
+    /// Check that `path` may be used with `access`, returning it resolved.
+    ///
+    /// # Errors
+    ///
+    /// The [`WorkspaceViolation`] that rules it out.
+    pub fn check(
+        &self,
+        path: impl AsRef<Path>,
+        access: PathAccess,
+    ) -> Result<PathBuf, WorkspaceViolation> {
+        let resolved = self.resolve(path);
+        if !self.contains(&resolved) {
+            return Err(WorkspaceViolation::OutsideWorkspace { path: resolved });
+        }
+        if access == PathAccess::Write && self.is_read_only(&resolved) {
+            return Err(WorkspaceViolation::ReadOnly { path: resolved });
+        }
+        Ok(resolved)
+    }
+
+    /// [`check()`](Self::check), then the same check with symlinks resolved
+    /// in `path`, `root` and every prefix, returning the real path.
+    ///
+    /// Components that do not exist yet are kept as given, so a file about
+    /// to be created can be checked through its parent. Uses blocking
+    /// filesystem calls.
+    ///
+    /// # Errors
+    ///
+    /// The [`WorkspaceViolation`] that rules out `path` or its real path.
+    pub fn check_real(
+        &self,
+        path: impl AsRef<Path>,
+        access: PathAccess,
+    ) -> Result<PathBuf, WorkspaceViolation> {
+        let resolved = self.check(path, access)?;
+        let real = Self {
+            root: real_path(&normalize(&self.root)),
+            allowed_paths: self
+                .allowed_paths
+                .iter()
+                .map(|prefix| real_path(&self.resolve(prefix)))
+                .collect(),
+            read_only_paths: self
+                .read_only_paths
+                .iter()
+                .map(|prefix| real_path(&self.resolve(prefix)))
+                .collect(),
+            ..self.clone()
+        };
+        real.check(real_path(&resolved), access)
+    }
+}
+
+/// `path` with symlinks resolved in its longest existing prefix.
+fn real_path(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(real) = existing.canonicalize() {
+            return missing
+                .iter()
+                .rev()
+                .fold(real, |real, name| real.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn workspace() -> Workspace {
+        Workspace::new("/work/project")
+            .with_allowed_path("/tmp/scratch")
+            .with_read_only_path("vendor")
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_the_root() {
+        let ws = workspace();
+        assert_eq!(
+            ws.resolve("src/lib.rs"),
+            Path::new("/work/project/src/lib.rs")
+        );
+        assert_eq!(ws.resolve("../other"), Path::new("/work/other"));
+        assert_eq!(ws.resolve("/etc/hosts"), Path::new("/etc/hosts"));
+    }
+
+    #[test]
+    fn containment_covers_root_and_allowed_prefixes() {
+        let ws = workspace();
+        assert!(ws.contains("src/main.rs"));
+        assert!(ws.contains("/work/project"));
+        assert!(ws.contains("/tmp/scratch/out.txt"));
+        assert!(!ws.contains("../other/secret"));
+        assert!(!ws.contains("/work/project-old/x"));
+        assert!(!ws.contains("/tmp/scratch/../passwd"));
+        assert!(!ws.contains("../../../../etc/passwd"));
+
+        // A relative root keeps the `..` that climb out of it.
+        let relative = Workspace::new(PathBuf::from("ws"));
+        assert_eq!(relative.resolve("../../x"), Path::new("../x"));
+        assert!(!relative.contains("../../ws/x"));
+        assert!(relative.contains("a/../b"));
+    }
+
+    #[test]
+    fn writes_to_read_only_paths_are_violations() {
+        let ws = workspace();
+        assert_eq!(
+            ws.check("vendor/lib.rs", PathAccess::Read).unwrap(),
+            Path::new("/work/project/vendor/lib.rs")
+        );
+        assert_eq!(
+            ws.check("vendor/lib.rs", PathAccess::Write),
+            Err(WorkspaceViolation::ReadOnly {
+                path: "/work/project/vendor/lib.rs".into()
+            })
+        );
+        assert!(matches!(
+            ws.check("/etc/hosts", PathAccess::Read),
+            Err(WorkspaceViolation::OutsideWorkspace { .. })
+        ));
+
+        assert_eq!(ws.access_for("write_file"), PathAccess::Write);
+        assert_eq!(ws.access_for("read_file"), PathAccess::Read);
+    }
+
+    #[test]
+    fn config_section_is_optional_and_fails_closed() {
+        assert!(Workspace::from_session_config(&HashMap::new()).is_none());
+
+        let mut plan = HashMap::new();
+        plan.insert(
+            "session".to_string(),
+            json!({"workspace": {"root": "/work", "read_only": true}}),
+        );
+        let ws = Workspace::from_session_config(&plan).unwrap();
+        assert!(ws.contains("a.txt"));
+        assert!(ws.is_read_only("a.txt"));
+
+        plan.insert(
+            "session".to_string(),
+            json!({"workspace": {"roots": "/work"}}),
+        );
+        let ws = Workspace::from_session_config(&plan).unwrap();
+        assert!(!ws.contains("a.txt"));
+    }
+}
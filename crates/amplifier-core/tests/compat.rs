@@ -0,0 +1,301 @@
+//! Serialization compatibility: pydantic fixtures ⇄ serde types.
+//!
+//! `tests/fixtures/compat/<kind>/*.json` holds `model_dump(mode="json")`
+//! output of the Python models, generated by
+//! `scripts/generate_compat_fixtures.py`. For every fixture this test checks:
+//!
+//! 1. It deserializes into the Rust type.
+//! 2. No field lands in a `#[serde(flatten)] extensions` map — that means
+//!    the Rust type is missing a field Python has.
+//! 3. Re-serializing yields the fixture again (Python → Rust → Python).
+//! 4. Deserializing the Rust output yields the same value (Rust → Rust).
+//!
+//! A key that is `null` on one side and absent on the other is equal: both
+//! sides accept either. All drift is collected into one report so a single
+//! run shows every broken field.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+use amplifier_core::messages::{ChatRequest, ChatResponse, ContentBlock, Message, MessageContent};
+use amplifier_core::models::{HookResult, SessionStatus};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Fixture directories and how their files map to Rust types.
+const KINDS: &[&str] = &["messages", "hook_results", "session_status"];
+
+// ---------------------------------------------------------------------------
+// Harness
+// ---------------------------------------------------------------------------
+
+/// A type with pydantic fixtures.
+trait Fixture: Serialize + DeserializeOwned + PartialEq + Debug {
+    /// Paths of keys that were captured by a flattened `extensions` map.
+    fn unmodelled(&self) -> Vec<String>;
+}
+
+fn extension_keys(prefix: &str, extensions: &HashMap<String, Value>) -> Vec<String> {
+    let mut keys: Vec<String> = extensions.keys().map(|k| format!("{prefix}.{k}")).collect();
+    keys.sort();
+    keys
+}
+
+fn block_extensions(prefix: &str, block: &ContentBlock) -> Vec<String> {
+    let extensions = match block {
+        ContentBlock::Text { extensions, .. }
+        | ContentBlock::Thinking { extensions, .. }
+        | ContentBlock::RedactedThinking { extensions, .. }
+        | ContentBlock::ToolCall { extensions, .. }
+        | ContentBlock::ToolResult { extensions, .. }
+        | ContentBlock::Image { extensions, .. }
+        | ContentBlock::Reasoning { extensions, .. }
+        | ContentBlock::Audio { extensions, .. }
+        | ContentBlock::Document { extensions, .. } => extensions,
+    };
+    extension_keys(prefix, extensions)
+}
+
+fn message_extensions(prefix: &str, message: &Message) -> Vec<String> {
+    let mut keys = extension_keys(prefix, &message.extensions);
+    if let MessageContent::Blocks(blocks) = &message.content {
+        for (i, block) in blocks.iter().enumerate() {
+            keys.extend(block_extensions(&format!("{prefix}.content[{i}]"), block));
+        }
+    }
+    keys
+}
+
+impl Fixture for Message {
+    fn unmodelled(&self) -> Vec<String> {
+        message_extensions("$", self)
+    }
+}
+
+impl Fixture for ChatRequest {
+    fn unmodelled(&self) -> Vec<String> {
+        let mut keys = extension_keys("$", &self.extensions);
+        for (i, message) in self.messages.iter().enumerate() {
+            keys.extend(message_extensions(&format!("$.messages[{i}]"), message));
+        }
+        for (i, tool) in self.tools.iter().flatten().enumerate() {
+            keys.extend(extension_keys(&format!("$.tools[{i}]"), &tool.extensions));
+        }
+        keys
+    }
+}
+
+impl Fixture for ChatResponse {
+    fn unmodelled(&self) -> Vec<String> {
+        let mut keys = extension_keys("$", &self.extensions);
+        for (i, block) in self.content.iter().enumerate() {
+            keys.extend(block_extensions(&format!("$.content[{i}]"), block));
+        }
+        for (i, call) in self.tool_calls.iter().flatten().enumerate() {
+            keys.extend(extension_keys(
+                &format!("$.tool_calls[{i}]"),
+                &call.extensions,
+            ));
+        }
+        if let Some(usage) = &self.usage {
+            keys.extend(extension_keys("$.usage", &usage.extensions));
+        }
+        if let Some(degradation) = &self.degradation {
+            keys.extend(extension_keys("$.degradation", &degradation.extensions));
+        }
+        keys
+    }
+}
+
+impl Fixture for HookResult {
+    fn unmodelled(&self) -> Vec<String> {
+        extension_keys("$", &self.extensions)
+    }
+}
+
+impl Fixture for SessionStatus {
+    fn unmodelled(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Run every check for one fixture, returning its drift lines.
+fn check<T: Fixture>(fixture: &Value) -> Vec<String> {
+    let parsed: T = match serde_json::from_value(fixture.clone()) {
+        Ok(parsed) => parsed,
+        Err(e) => return vec![format!("does not deserialize: {e}")],
+    };
+
+    let mut drift: Vec<String> = parsed
+        .unmodelled()
+        .into_iter()
+        .map(|path| format!("{path}: not modelled in Rust (captured as extension)"))
+        .collect();
+
+    let emitted = serde_json::to_value(&parsed).expect("serialize");
+    diff("$", fixture, &emitted, &mut drift);
+
+    match serde_json::from_value::<T>(emitted) {
+        Ok(reparsed) if reparsed == parsed => {}
+        Ok(reparsed) => drift.push(format!(
+            "Rust round-trip is not stable:\n      first:  {parsed:?}\n      second: {reparsed:?}"
+        )),
+        Err(e) => drift.push(format!("Rust output does not deserialize: {e}")),
+    }
+    drift
+}
+
+/// Structural diff of `python` against `rust`, treating `null` as absent.
+fn diff(path: &str, python: &Value, rust: &Value, drift: &mut Vec<String>) {
+    match (python, rust) {
+        (Value::Object(p), Value::Object(r)) => {
+            let keys: BTreeSet<&String> = p.keys().chain(r.keys()).collect();
+            for key in keys {
+                let child = format!("{path}.{key}");
+                match (p.get(key), r.get(key)) {
+                    (Some(pv), Some(rv)) => diff(&child, pv, rv, drift),
+                    (Some(Value::Null), None) | (None, Some(Value::Null)) => {}
+                    (Some(pv), None) => drift.push(format!("{child}: python={pv} rust=<missing>")),
+                    (None, Some(rv)) => drift.push(format!("{child}: python=<missing> rust={rv}")),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+        (Value::Array(p), Value::Array(r)) => {
+            if p.len() != r.len() {
+                drift.push(format!(
+                    "{path}: python has {} items, rust has {}",
+                    p.len(),
+                    r.len()
+                ));
+            }
+            for (i, (pv, rv)) in p.iter().zip(r).enumerate() {
+                diff(&format!("{path}[{i}]"), pv, rv, drift);
+            }
+        }
+        // pydantic writes 60.0 for float fields; serde may write 60.
+        (Value::Number(p), Value::Number(r)) if p.as_f64() == r.as_f64() => {}
+        (p, r) if p == r => {}
+        (p, r) => drift.push(format!("{path}: python={p} rust={r}")),
+    }
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/compat")
+}
+
+/// Load `<kind>/*.json` sorted by name.
+fn load_kind(kind: &str) -> Vec<(String, Value)> {
+    let dir = fixtures_dir().join(kind);
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("reading {}: {e}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no fixtures in {}", dir.display());
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = format!("{kind}/{}", path.file_name().unwrap().to_string_lossy());
+            let content = std::fs::read_to_string(&path).unwrap();
+            let value = serde_json::from_str(&content)
+                .unwrap_or_else(|e| panic!("{name} is not valid JSON: {e}"));
+            (name, value)
+        })
+        .collect()
+}
+
+/// Check every fixture of `kind` with `check_one`, panicking with a
+/// combined report if any drifted.
+fn run_kind(kind: &str, check_one: impl Fn(&str, &Value) -> Vec<String>) {
+    let mut report = Vec::new();
+    let fixtures = load_kind(kind);
+    for (name, value) in &fixtures {
+        let drift = check_one(name, value);
+        if !drift.is_empty() {
+            report.push(format!("{name}\n    {}", drift.join("\n    ")));
+        }
+    }
+    assert!(
+        report.is_empty(),
+        "serialization drift in {} of {} {kind} fixture(s) \
+         (regenerate with scripts/generate_compat_fixtures.py if the Python models changed):\n\n{}\n",
+        report.len(),
+        fixtures.len(),
+        report.join("\n\n")
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[test]
+fn message_fixtures_round_trip() {
+    run_kind("messages", |name, value| {
+        let stem = name.trim_start_matches("messages/");
+        if stem.starts_with("chat_request") {
+            check::<ChatRequest>(value)
+        } else if stem.starts_with("chat_response") {
+            check::<ChatResponse>(value)
+        } else if stem.starts_with("message") {
+            check::<Message>(value)
+        } else {
+            vec![format!(
+                "no Rust type for this fixture (expected a message_/chat_request/chat_response prefix)"
+            )]
+        }
+    });
+}
+
+#[test]
+fn hook_result_fixtures_round_trip() {
+    run_kind("hook_results", |_, value| check::<HookResult>(value));
+}
+
+#[test]
+fn session_status_fixtures_round_trip() {
+    run_kind("session_status", |_, value| check::<SessionStatus>(value));
+}
+
+#[test]
+fn every_fixture_kind_is_checked() {
+    let mut unknown = Vec::new();
+    for entry in std::fs::read_dir(fixtures_dir()).unwrap() {
+        let entry = entry.unwrap();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.path().is_dir() && !KINDS.contains(&name.as_str()) {
+            unknown.push(name);
+        }
+    }
+    assert!(
+        unknown.is_empty(),
+        "fixture kinds without a test: {unknown:?}"
+    );
+}
+
+#[test]
+fn harness_reports_drift() {
+    let drifted = serde_json::json!({
+        "action": "deny",
+        "reason": "x",
+        "approval_timeout": 30.0,
+        "renamed_field": true,
+    });
+    let drift = check::<HookResult>(&drifted);
+    assert!(
+        drift
+            .iter()
+            .any(|d| d.starts_with("$.renamed_field: not modelled")),
+        "{drift:?}"
+    );
+
+    let python = serde_json::json!({"status": "running", "ended_at": null, "tokens": 5.0});
+    let rust = serde_json::json!({"status": "failed", "tokens": 5});
+    let mut drift = Vec::new();
+    diff("$", &python, &rust, &mut drift);
+    assert_eq!(drift, vec![r#"$.status: python="running" rust="failed""#]);
+}
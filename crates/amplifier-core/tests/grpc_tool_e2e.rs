@@ -38,6 +38,7 @@ impl ToolService for EchoToolService {
                 output: req.input,
                 content_type: req.content_type,
                 error: String::new(),
+                ..Default::default()
             },
         ))
     }
@@ -88,6 +89,7 @@ impl ToolService for InvalidParamsToolService {
                 output: b"{}".to_vec(),
                 content_type: "application/json".to_string(),
                 error: String::new(),
+                ..Default::default()
             },
         ))
     }
@@ -123,6 +125,7 @@ impl ToolService for BinaryOutputToolService {
                 output: vec![0xFF, 0xFE, 0x00, 0x01], // not valid JSON
                 content_type: "application/json".to_string(),
                 error: String::new(),
+                ..Default::default()
             },
         ))
     }
@@ -158,6 +161,7 @@ impl ToolService for NonJsonContentTypeToolService {
                 output: b"{\"result\": 42}".to_vec(),
                 content_type: "text/plain".to_string(),
                 error: String::new(),
+                ..Default::default()
             },
         ))
     }
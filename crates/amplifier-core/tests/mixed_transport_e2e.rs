@@ -192,6 +192,7 @@ async fn mixed_transport_session() {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         }],
         tools: None,
@@ -264,6 +264,7 @@ async fn provider_complete() {
             name: None,
             tool_call_id: None,
             metadata: None,
+            cache: None,
             extensions: HashMap::new(),
         }],
         tools: None,
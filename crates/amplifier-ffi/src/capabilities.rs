@@ -158,7 +158,7 @@ mod tests {
     /// Both capability functions return ERR_NULL_HANDLE for null arguments.
     #[test]
     fn capability_null_args_return_error() {
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let name_cstr = CString::new("my-capability").unwrap();
         let value_json_cstr = CString::new("{\"key\":\"value\"}").unwrap();
         let mut out_json: *mut c_char = ptr::null_mut();
@@ -217,7 +217,7 @@ mod tests {
     fn capability_valid_args_returns_internal() {
         use crate::handles::ERR_INTERNAL;
 
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let name_cstr = CString::new("my-capability").unwrap();
         let value_json_cstr = CString::new("{\"key\":\"value\"}").unwrap();
         let mut out_json: *mut c_char = ptr::null_mut();
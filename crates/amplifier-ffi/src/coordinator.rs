@@ -32,9 +32,8 @@ use crate::memory::set_last_error;
 /// Once `Session`/`Coordinator` expose a public `mount_provider` that accepts
 /// an FFI-wrapped `Arc<dyn Provider>`, replace the `ERR_INTERNAL` return with:
 ///   1. `handle_to_arc_ref::<FfiSession>(session)` to borrow the session.
-///   2. Lock `session_arc.session`.
-///   3. Access the `Coordinator` via `session.coordinator()`.
-///   4. Call `coordinator.mount_provider(name_str, provider_arc)`.
+///   2. Access the `Coordinator` via `session_arc.session.coordinator()`.
+///   3. Call `coordinator.mount_provider(name_str, provider_arc)`.
 // SAFETY: each pointer argument is verified non-null before any dereference.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -67,7 +66,7 @@ pub extern "C" fn amplifier_session_mount_provider(
     };
 
     // TODO: Obtain session Arc via handle_to_arc_ref::<FfiSession>(session),
-    //       lock session_arc.session, access the Coordinator, and call
+    //       access the Coordinator via session_arc.session, and call
     //       coordinator.mount_provider(_name_str, provider_arc).
     //       Requires a Provider trait object constructed from the FFI handle.
     set_last_error("amplifier_session_mount_provider: kernel integration not yet implemented");
@@ -89,9 +88,8 @@ pub extern "C" fn amplifier_session_mount_provider(
 /// Once `Session`/`Coordinator` expose a public `mount_tool` that accepts
 /// an FFI-wrapped `Arc<dyn Tool>`, replace the `ERR_INTERNAL` return with:
 ///   1. `handle_to_arc_ref::<FfiSession>(session)` to borrow the session.
-///   2. Lock `session_arc.session`.
-///   3. Access the `Coordinator` via `session.coordinator()`.
-///   4. Call `coordinator.mount_tool(name_str, tool_arc)`.
+///   2. Access the `Coordinator` via `session_arc.session.coordinator()`.
+///   3. Call `coordinator.mount_tool(name_str, tool_arc)`.
 // SAFETY: each pointer argument is verified non-null before any dereference.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -124,7 +122,7 @@ pub extern "C" fn amplifier_session_mount_tool(
     };
 
     // TODO: Obtain session Arc via handle_to_arc_ref::<FfiSession>(session),
-    //       lock session_arc.session, access the Coordinator, and call
+    //       access the Coordinator via session_arc.session, and call
     //       coordinator.mount_tool(_name_str, tool_arc).
     //       Requires a Tool trait object constructed from the FFI handle.
     set_last_error("amplifier_session_mount_tool: kernel integration not yet implemented");
@@ -145,9 +143,8 @@ pub extern "C" fn amplifier_session_mount_tool(
 /// Once `Session`/`Coordinator` expose a public `set_orchestrator` that accepts
 /// an FFI-wrapped `Arc<dyn Orchestrator>`, replace the `ERR_INTERNAL` return with:
 ///   1. `handle_to_arc_ref::<FfiSession>(session)` to borrow the session.
-///   2. Lock `session_arc.session`.
-///   3. Access the `Coordinator` via `session.coordinator()`.
-///   4. Call `coordinator.set_orchestrator(orchestrator_arc)`.
+///   2. Access the `Coordinator` via `session_arc.session.coordinator()`.
+///   3. Call `coordinator.set_orchestrator(orchestrator_arc)`.
 // SAFETY: each pointer argument is verified non-null before any dereference.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -165,7 +162,7 @@ pub extern "C" fn amplifier_session_set_orchestrator(
     }
 
     // TODO: Obtain session Arc via handle_to_arc_ref::<FfiSession>(session),
-    //       lock session_arc.session, access the Coordinator, and call
+    //       access the Coordinator via session_arc.session, and call
     //       coordinator.set_orchestrator(orchestrator_arc).
     //       Requires an Orchestrator trait object constructed from the FFI handle.
     set_last_error("amplifier_session_set_orchestrator: kernel integration not yet implemented");
@@ -186,9 +183,8 @@ pub extern "C" fn amplifier_session_set_orchestrator(
 /// Once `Session`/`Coordinator` expose a public `set_context` that accepts
 /// an FFI-wrapped `Arc<dyn ContextManager>`, replace the `ERR_INTERNAL` return with:
 ///   1. `handle_to_arc_ref::<FfiSession>(session)` to borrow the session.
-///   2. Lock `session_arc.session`.
-///   3. Access the `Coordinator` via `session.coordinator()`.
-///   4. Call `coordinator.set_context(context_arc)`.
+///   2. Access the `Coordinator` via `session_arc.session.coordinator()`.
+///   3. Call `coordinator.set_context(context_arc)`.
 // SAFETY: each pointer argument is verified non-null before any dereference.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -206,7 +202,7 @@ pub extern "C" fn amplifier_session_set_context(
     }
 
     // TODO: Obtain session Arc via handle_to_arc_ref::<FfiSession>(session),
-    //       lock session_arc.session, access the Coordinator, and call
+    //       access the Coordinator via session_arc.session, and call
     //       coordinator.set_context(context_arc).
     //       Requires a ContextManager trait object constructed from the FFI handle.
     set_last_error("amplifier_session_set_context: kernel integration not yet implemented");
@@ -229,7 +225,7 @@ mod tests {
     fn mount_null_args_return_error() {
         // Use a non-null fake pointer to pass the "first argument" check when
         // testing the second argument.  We never dereference it in the scaffold.
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let name_cstr = CString::new("test-module").unwrap();
 
         // ---- amplifier_session_mount_provider ----
@@ -319,7 +315,7 @@ mod tests {
     fn mount_valid_utf8_name_passes_validation() {
         use crate::handles::ERR_INTERNAL;
 
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let name_cstr = CString::new("my-provider").unwrap();
 
         // Both mount functions with valid UTF-8 name should return ERR_INTERNAL
@@ -345,7 +341,7 @@ mod tests {
     fn set_single_slot_valid_args_returns_internal() {
         use crate::handles::ERR_INTERNAL;
 
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
 
         let result = amplifier_session_set_orchestrator(fake_handle, fake_handle);
         assert_eq!(
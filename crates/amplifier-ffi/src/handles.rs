@@ -35,6 +35,9 @@ pub const ERR_RUNTIME: AmplifierResult = -3;
 /// A session-level error occurred.
 pub const ERR_SESSION: AmplifierResult = -4;
 
+/// The requested wire format is unknown or not enabled in this build.
+pub const ERR_UNSUPPORTED_FORMAT: AmplifierResult = -5;
+
 /// An unexpected internal error occurred.
 pub const ERR_INTERNAL: AmplifierResult = -99;
 
@@ -105,6 +108,7 @@ mod tests {
         assert_eq!(ERR_INVALID_JSON, -2);
         assert_eq!(ERR_RUNTIME, -3);
         assert_eq!(ERR_SESSION, -4);
+        assert_eq!(ERR_UNSUPPORTED_FORMAT, -5);
         assert_eq!(ERR_INTERNAL, -99);
     }
 
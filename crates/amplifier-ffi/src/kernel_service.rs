@@ -114,7 +114,7 @@ mod tests {
     /// Both kernel_service functions return ERR_NULL_HANDLE for null arguments.
     #[test]
     fn kernel_service_null_args_return_error() {
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let mut out_token: AmplifierHandle = ptr::null_mut();
 
         // amplifier_kernel_service_start: null session
@@ -145,7 +145,7 @@ mod tests {
     fn kernel_service_valid_args_returns_internal() {
         use crate::handles::ERR_INTERNAL;
 
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let mut out_token: AmplifierHandle = ptr::null_mut();
 
         let result = amplifier_kernel_service_start(fake_handle, 8080, &mut out_token);
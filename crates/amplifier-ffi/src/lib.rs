@@ -7,3 +7,4 @@ pub mod memory;
 pub mod runtime;
 pub mod session;
 pub mod transport;
+pub mod wire;
@@ -6,7 +6,7 @@
 //! boundary.
 
 use std::ffi::{c_char, CStr};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use amplifier_core::session::{Session, SessionConfig};
 
@@ -23,13 +23,14 @@ use crate::runtime::FfiRuntime;
 
 /// Wraps an `amplifier_core::Session` for FFI ownership transfer.
 ///
-/// Holds a reference to the runtime that drives async execution and a
-/// mutex-protected session for thread-safe FFI access.
+/// Holds a reference to the runtime that drives async execution and the
+/// session itself. `Session` uses interior mutability, so no lock is needed
+/// for thread-safe FFI access.
 pub struct FfiSession {
     /// The Tokio runtime used to drive async session operations.
     pub(crate) runtime: Arc<FfiRuntime>,
-    /// The underlying session, mutex-protected for thread-safe FFI access.
-    pub(crate) session: Mutex<Session>,
+    /// The underlying session (shared; all methods take `&self`).
+    pub(crate) session: Session,
 }
 
 // ---------------------------------------------------------------------------
@@ -97,7 +98,7 @@ pub extern "C" fn amplifier_session_create(
     let session = Session::new(session_config, None, None);
     let ffi_session = Arc::new(FfiSession {
         runtime: runtime_arc,
-        session: Mutex::new(session),
+        session,
     });
 
     let handle = arc_to_handle(ffi_session);
@@ -132,12 +133,11 @@ pub extern "C" fn amplifier_session_destroy(session: AmplifierHandle) -> Amplifi
 
 /// Mark the session as initialized and ready for execution.
 ///
-/// Acquires the session mutex and calls `set_initialized()` on the inner session.
+/// Calls `set_initialized()` on the inner session.
 ///
 /// Returns:
 /// - `AMPLIFIER_OK` on success.
 /// - `ERR_NULL_HANDLE` if `session` is null.
-/// - `ERR_SESSION` if the mutex is poisoned.
 // SAFETY: `session` is verified non-null before use.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -156,17 +156,7 @@ pub extern "C" fn amplifier_session_initialize(session: AmplifierHandle) -> Ampl
         }
     };
 
-    let guard = match session_arc.session.lock() {
-        Ok(g) => g,
-        Err(e) => {
-            set_last_error(&format!(
-                "amplifier_session_initialize: mutex poisoned: {e}"
-            ));
-            return ERR_SESSION;
-        }
-    };
-
-    guard.set_initialized();
+    session_arc.session.set_initialized();
 
     AMPLIFIER_OK
 }
@@ -181,7 +171,7 @@ pub extern "C" fn amplifier_session_initialize(session: AmplifierHandle) -> Ampl
 /// - `AMPLIFIER_OK` on success.
 /// - `ERR_NULL_HANDLE` if `session`, `prompt`, or `out_json` is null.
 /// - `ERR_INVALID_JSON` if `prompt` is not valid UTF-8.
-/// - `ERR_SESSION` if execution fails or the mutex is poisoned.
+/// - `ERR_SESSION` if execution fails.
 // SAFETY: each pointer argument is verified non-null before any dereference.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -221,18 +211,10 @@ pub extern "C" fn amplifier_session_execute(
         }
     };
 
-    let mut guard = match session_arc.session.lock() {
-        Ok(g) => g,
-        Err(e) => {
-            set_last_error(&format!("amplifier_session_execute: mutex poisoned: {e}"));
-            return ERR_SESSION;
-        }
-    };
-
     let result = session_arc
         .runtime
         .runtime
-        .block_on(guard.execute(&prompt_owned));
+        .block_on(session_arc.session.execute(&prompt_owned));
 
     match result {
         Ok(response) => {
@@ -257,7 +239,6 @@ pub extern "C" fn amplifier_session_execute(
 /// Returns:
 /// - `AMPLIFIER_OK` on success.
 /// - `ERR_NULL_HANDLE` if `session` is null.
-/// - `ERR_SESSION` if the mutex is poisoned.
 // SAFETY: `session` is verified non-null before use.
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[no_mangle]
@@ -276,15 +257,10 @@ pub extern "C" fn amplifier_session_cleanup(session: AmplifierHandle) -> Amplifi
         }
     };
 
-    let guard = match session_arc.session.lock() {
-        Ok(g) => g,
-        Err(e) => {
-            set_last_error(&format!("amplifier_session_cleanup: mutex poisoned: {e}"));
-            return ERR_SESSION;
-        }
-    };
-
-    session_arc.runtime.runtime.block_on(guard.cleanup());
+    session_arc
+        .runtime
+        .runtime
+        .block_on(session_arc.session.cleanup());
 
     AMPLIFIER_OK
 }
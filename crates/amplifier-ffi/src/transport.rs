@@ -2,6 +2,7 @@
 //!
 //! Provides scaffolded FFI functions for loading gRPC-backed modules:
 //! `amplifier_load_grpc_provider`, `amplifier_load_grpc_tool`,
+//! `amplifier_load_grpc_tool_with_format`,
 //! `amplifier_load_grpc_orchestrator`, `amplifier_load_grpc_hook`,
 //! `amplifier_load_grpc_context`, and `amplifier_load_grpc_approval`.
 //!
@@ -13,6 +14,7 @@ use std::ffi::{c_char, CStr};
 
 use crate::handles::{AmplifierHandle, AmplifierResult, ERR_INTERNAL, ERR_NULL_HANDLE};
 use crate::memory::set_last_error;
+use crate::wire::{wire_format_from_code, AmplifierWireFormat};
 
 // ---------------------------------------------------------------------------
 // FFI functions
@@ -128,6 +130,62 @@ pub extern "C" fn amplifier_load_grpc_tool(
     ERR_INTERNAL
 }
 
+/// Load a gRPC-backed tool module that takes its input in `wire_format`.
+///
+/// Same as [`amplifier_load_grpc_tool`], but tool input is encoded in the
+/// given `AMPLIFIER_WIRE_*` format instead of JSON.
+///
+/// # Returns
+///
+/// - `ERR_NULL_HANDLE` if any argument is null.
+/// - `ERR_UNSUPPORTED_FORMAT` if `wire_format` is unknown or not enabled.
+/// - `ERR_INTERNAL` after argument validation (TODO: gRPC transport integration).
+///
+/// # TODO
+///
+/// Connect as in [`amplifier_load_grpc_tool`], via
+/// `amplifier_core::transport::load_grpc_tool_with_format`.
+// SAFETY: each pointer argument is verified non-null before any dereference.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[no_mangle]
+pub extern "C" fn amplifier_load_grpc_tool_with_format(
+    runtime: AmplifierHandle,
+    endpoint: *const c_char,
+    wire_format: AmplifierWireFormat,
+    out: *mut AmplifierHandle,
+) -> AmplifierResult {
+    if runtime.is_null() {
+        set_last_error("amplifier_load_grpc_tool_with_format: runtime handle is null");
+        return ERR_NULL_HANDLE;
+    }
+    if endpoint.is_null() {
+        set_last_error("amplifier_load_grpc_tool_with_format: endpoint pointer is null");
+        return ERR_NULL_HANDLE;
+    }
+    if out.is_null() {
+        set_last_error("amplifier_load_grpc_tool_with_format: out pointer is null");
+        return ERR_NULL_HANDLE;
+    }
+
+    // SAFETY: endpoint is non-null (verified above); caller ensures valid C string.
+    let _endpoint_str = match unsafe { CStr::from_ptr(endpoint).to_str() } {
+        Ok(s) => s,
+        Err(_) => {
+            set_last_error("amplifier_load_grpc_tool_with_format: endpoint is not valid UTF-8");
+            return ERR_INTERNAL;
+        }
+    };
+    let _format = match wire_format_from_code("amplifier_load_grpc_tool_with_format", wire_format) {
+        Ok(format) => format,
+        Err(code) => return code,
+    };
+
+    // TODO: Connect to the gRPC endpoint with the chosen wire format and
+    //       write the resulting Arc handle into *out.
+    set_last_error("amplifier_load_grpc_tool_with_format: gRPC transport not yet implemented");
+    ERR_INTERNAL
+}
+
 /// Load a gRPC-backed orchestrator module from the given endpoint.
 ///
 /// Validates that `runtime`, `endpoint`, `session_id`, and `out` are all
@@ -377,7 +435,7 @@ mod tests {
     /// All 6 gRPC loader functions return ERR_NULL_HANDLE for null arguments.
     #[test]
     fn transport_null_args_return_error() {
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let endpoint_cstr = CString::new("http://localhost:50051").unwrap();
         let session_id_cstr = CString::new("test-session-id").unwrap();
         let mut out: AmplifierHandle = ptr::null_mut();
@@ -559,7 +617,7 @@ mod tests {
     fn transport_valid_args_returns_internal() {
         use crate::handles::ERR_INTERNAL;
 
-        let fake_handle: *mut std::ffi::c_void = 1usize as *mut std::ffi::c_void;
+        let fake_handle: *mut std::ffi::c_void = std::ptr::dangling_mut::<std::ffi::c_void>();
         let endpoint_cstr = CString::new("http://localhost:50051").unwrap();
         let session_id_cstr = CString::new("test-session-id").unwrap();
         let mut out: AmplifierHandle = ptr::null_mut();
@@ -576,6 +634,25 @@ mod tests {
             "load_grpc_tool: valid args → ERR_INTERNAL (scaffold TODO)"
         );
 
+        let result = amplifier_load_grpc_tool_with_format(
+            fake_handle,
+            endpoint_cstr.as_ptr(),
+            crate::wire::AMPLIFIER_WIRE_JSON,
+            &mut out,
+        );
+        assert_eq!(
+            result, ERR_INTERNAL,
+            "load_grpc_tool_with_format: valid args → ERR_INTERNAL (scaffold TODO)"
+        );
+
+        let result =
+            amplifier_load_grpc_tool_with_format(fake_handle, endpoint_cstr.as_ptr(), 99, &mut out);
+        assert_eq!(
+            result,
+            crate::handles::ERR_UNSUPPORTED_FORMAT,
+            "load_grpc_tool_with_format: unknown format → ERR_UNSUPPORTED_FORMAT"
+        );
+
         let result = amplifier_load_grpc_orchestrator(
             fake_handle,
             endpoint_cstr.as_ptr(),
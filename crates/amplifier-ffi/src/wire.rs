@@ -0,0 +1,109 @@
+//! Wire format selection for FFI.
+//!
+//! C callers pick the serialization format of cross-boundary payloads with
+//! an `AmplifierWireFormat` code. JSON is always available; MessagePack and
+//! CBOR require the `msgpack` and `cbor` features.
+
+use amplifier_core::wire::WireFormat;
+
+use crate::handles::{AmplifierResult, ERR_UNSUPPORTED_FORMAT};
+use crate::memory::set_last_error;
+
+/// FFI wire format code.
+pub type AmplifierWireFormat = i32;
+
+// ---------------------------------------------------------------------------
+// Format constants
+// ---------------------------------------------------------------------------
+
+/// JSON (`application/json`).
+pub const AMPLIFIER_WIRE_JSON: AmplifierWireFormat = 0;
+
+/// MessagePack (`application/msgpack`).
+pub const AMPLIFIER_WIRE_MSGPACK: AmplifierWireFormat = 1;
+
+/// CBOR (`application/cbor`).
+pub const AMPLIFIER_WIRE_CBOR: AmplifierWireFormat = 2;
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// Map a format code to a [`WireFormat`] this build supports.
+///
+/// On failure, records the reason as the last error (prefixed with
+/// `context`) and returns `ERR_UNSUPPORTED_FORMAT`.
+pub fn wire_format_from_code(
+    context: &str,
+    code: AmplifierWireFormat,
+) -> Result<WireFormat, AmplifierResult> {
+    let format = match code {
+        AMPLIFIER_WIRE_JSON => WireFormat::Json,
+        AMPLIFIER_WIRE_MSGPACK => WireFormat::MessagePack,
+        AMPLIFIER_WIRE_CBOR => WireFormat::Cbor,
+        _ => {
+            set_last_error(&format!("{context}: unknown wire format code {code}"));
+            return Err(ERR_UNSUPPORTED_FORMAT);
+        }
+    };
+    if !format.is_available() {
+        set_last_error(&format!(
+            "{context}: wire format {format} is not enabled in this build"
+        ));
+        return Err(ERR_UNSUPPORTED_FORMAT);
+    }
+    Ok(format)
+}
+
+// ---------------------------------------------------------------------------
+// FFI functions
+// ---------------------------------------------------------------------------
+
+/// Return `1` if this build supports the wire format `format`, else `0`.
+#[no_mangle]
+pub extern "C" fn amplifier_wire_format_supported(format: AmplifierWireFormat) -> i32 {
+    match format {
+        AMPLIFIER_WIRE_JSON => 1,
+        AMPLIFIER_WIRE_MSGPACK => WireFormat::MessagePack.is_available() as i32,
+        AMPLIFIER_WIRE_CBOR => WireFormat::Cbor.is_available() as i32,
+        _ => 0,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    #[test]
+    fn json_is_always_supported() {
+        assert_eq!(amplifier_wire_format_supported(AMPLIFIER_WIRE_JSON), 1);
+        assert_eq!(
+            wire_format_from_code("test", AMPLIFIER_WIRE_JSON),
+            Ok(WireFormat::Json)
+        );
+        assert_eq!(
+            amplifier_wire_format_supported(AMPLIFIER_WIRE_MSGPACK) == 1,
+            cfg!(feature = "msgpack")
+        );
+        assert_eq!(
+            amplifier_wire_format_supported(AMPLIFIER_WIRE_CBOR) == 1,
+            cfg!(feature = "cbor")
+        );
+    }
+
+    #[test]
+    fn unknown_code_sets_last_error() {
+        assert_eq!(amplifier_wire_format_supported(42), 0);
+        assert_eq!(
+            wire_format_from_code("test", 42),
+            Err(ERR_UNSUPPORTED_FORMAT)
+        );
+        let msg = unsafe { CStr::from_ptr(crate::memory::amplifier_last_error()) };
+        assert_eq!(msg.to_str().unwrap(), "test: unknown wire format code 42");
+    }
+}
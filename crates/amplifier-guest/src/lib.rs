@@ -1595,6 +1595,7 @@ mod kernel_tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_kernel_stub_feature_is_enabled_by_default() {
         // The kernel-stub feature must be enabled by default so that
         // non-wasm builds (including tests) can use the stub functions.
@@ -55,9 +55,11 @@ from .llm_errors import ProviderUnavailableError
 from .llm_errors import RateLimitError
 from .loader import ModuleLoader
 from .loader import ModuleValidationError
+from .message_models import AudioBlock
 from .message_models import ChatRequest
 from .message_models import ChatResponse
 from .message_models import Degradation
+from .message_models import DocumentBlock
 from .message_models import ImageBlock
 from .message_models import Message
 from .message_models import ReasoningBlock
@@ -138,6 +140,8 @@ __all__ = [
     "ToolResultBlock",
     "ImageBlock",
     "ReasoningBlock",
+    "AudioBlock",
+    "DocumentBlock",
     "ToolSpec",
     "Usage",
     "Degradation",
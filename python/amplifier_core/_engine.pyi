@@ -85,6 +85,8 @@ class RustHookRegistry:
         handler: Any,
         priority: int = 0,
         name: Optional[str] = None,
+        phase: Optional[str] = None,
+        condition: Optional[str] = None,
     ) -> Any: ...  # Returns a callable unregister function (RustUnregisterFn)
     def on(
         self,
@@ -92,6 +94,8 @@ class RustHookRegistry:
         handler: Any,
         priority: int = 0,
         name: Optional[str] = None,
+        phase: Optional[str] = None,
+        condition: Optional[str] = None,
     ) -> Any:
         """Alias for register()."""
         ...
@@ -99,9 +103,17 @@ class RustHookRegistry:
     async def emit_and_collect(
         self, event: str, data: dict[str, Any], timeout: Optional[float] = None
     ) -> list[Any]: ...
+    async def emit_decision(
+        self, event: str, data: dict[str, Any], timeout: Optional[float] = None
+    ) -> list[dict[str, Any]]: ...
     def unregister(self, name: str) -> None: ...
     def set_default_fields(self, **kwargs: Any) -> None: ...
+    def set_event_filter(self, config: Optional[dict[str, Any]] = None) -> None: ...
     def list_handlers(self, event: Optional[str] = None) -> dict[str, list[str]]: ...
+    def set_handler_enabled(
+        self, name: str, enabled: bool, event: Optional[str] = None
+    ) -> int: ...
+    def disabled_handlers(self) -> dict[str, list[str]]: ...
 
 # ---------------------------------------------------------------------------
 # RustCancellationToken — wraps amplifier_core::CancellationToken
@@ -324,6 +336,115 @@ class RetryConfig:
         """Deprecated: use ``backoff_factor`` instead."""
         ...
 
+# ---------------------------------------------------------------------------
+# Message / ChatRequest / ChatResponse — typed message wrappers (PyO3 bridge)
+# ---------------------------------------------------------------------------
+
+class Message:
+    """A conversation message backed by the Rust ``Message`` struct.
+
+    Properties read the Rust value directly; ``to_dict()`` and ``to_json()``
+    produce the same shape as the Pydantic ``Message.model_dump(mode="json")``.
+    """
+
+    @staticmethod
+    def from_dict(data: Any) -> "Message":
+        """Build from a dict or a Pydantic ``Message``. Raises ``ValueError`` if invalid."""
+        ...
+    @staticmethod
+    def from_json(json: str) -> "Message": ...
+    def to_dict(self) -> dict[str, Any]: ...
+    def to_json(self) -> str: ...
+    @property
+    def role(self) -> str: ...
+    @property
+    def content(self) -> "str | list[dict[str, Any]]": ...
+    @property
+    def text(self) -> str:
+        """The string content, or all text blocks joined."""
+        ...
+    @property
+    def name(self) -> Optional[str]: ...
+    @property
+    def tool_call_id(self) -> Optional[str]: ...
+    @property
+    def metadata(self) -> Optional[dict[str, Any]]: ...
+
+class ChatRequest:
+    """A provider request backed by the Rust ``ChatRequest`` struct.
+
+    Accepted wherever the engine takes a request (e.g. ``WasmProvider.complete``)
+    without being re-serialized.
+    """
+
+    @staticmethod
+    def from_dict(data: Any) -> "ChatRequest":
+        """Build from a dict or a Pydantic ``ChatRequest``. Raises ``ValueError`` if invalid."""
+        ...
+    @staticmethod
+    def from_json(json: str) -> "ChatRequest": ...
+    def to_dict(self) -> dict[str, Any]: ...
+    def to_json(self) -> str: ...
+    @property
+    def messages(self) -> list[Message]: ...
+    @property
+    def tools(self) -> Optional[list[dict[str, Any]]]: ...
+    @property
+    def model(self) -> Optional[str]: ...
+    @property
+    def temperature(self) -> Optional[float]: ...
+    @property
+    def top_p(self) -> Optional[float]: ...
+    @property
+    def max_output_tokens(self) -> Optional[int]: ...
+    @property
+    def stop(self) -> Optional[list[str]]: ...
+    @property
+    def stream(self) -> Optional[bool]: ...
+    @property
+    def timeout(self) -> Optional[float]: ...
+    @property
+    def reasoning_effort(self) -> Optional[str]: ...
+    @property
+    def tool_choice(self) -> "str | dict[str, Any] | None": ...
+    @property
+    def response_format(self) -> Optional[dict[str, Any]]: ...
+    @property
+    def metadata(self) -> Optional[dict[str, Any]]: ...
+    def __len__(self) -> int: ...
+
+class ChatResponse:
+    """A provider response backed by the Rust ``ChatResponse`` struct.
+
+    Accepted wherever the engine takes a response (e.g.
+    ``WasmProvider.parse_tool_calls``) without being re-serialized.
+    """
+
+    @staticmethod
+    def from_dict(data: Any) -> "ChatResponse":
+        """Build from a dict or a Pydantic ``ChatResponse``. Raises ``ValueError`` if invalid."""
+        ...
+    @staticmethod
+    def from_json(json: str) -> "ChatResponse": ...
+    def to_dict(self) -> dict[str, Any]: ...
+    def to_json(self) -> str: ...
+    @property
+    def content(self) -> list[dict[str, Any]]: ...
+    @property
+    def text(self) -> str:
+        """All text blocks joined."""
+        ...
+    @property
+    def tool_calls(self) -> Optional[list[dict[str, Any]]]: ...
+    @property
+    def usage(self) -> Optional[dict[str, Any]]: ...
+    @property
+    def degradation(self) -> Optional[dict[str, Any]]: ...
+    @property
+    def finish_reason(self) -> Optional[str]: ...
+    @property
+    def metadata(self) -> Optional[dict[str, Any]]: ...
+
 # ---------------------------------------------------------------------------
 # Retry utility functions (PyO3 bridge)
 # ---------------------------------------------------------------------------
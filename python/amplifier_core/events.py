@@ -10,9 +10,13 @@ from amplifier_core._engine import (
     SESSION_END,
     SESSION_FORK,
     SESSION_RESUME,
+    SESSION_REWIND,
+    SESSION_REAPED,
+    SESSION_HEARTBEAT,
     # Prompt lifecycle
     PROMPT_SUBMIT,
     PROMPT_COMPLETE,
+    PROMPT_DUPLICATE,
     # Planning
     PLAN_START,
     PLAN_END,
@@ -24,6 +28,8 @@ from amplifier_core._engine import (
     PROVIDER_THROTTLE,
     PROVIDER_TOOL_SEQUENCE_REPAIRED,
     PROVIDER_RESOLVE,
+    PROVIDER_PRE,
+    PROVIDER_POST,
     # LLM events
     LLM_REQUEST,
     LLM_RESPONSE,
@@ -31,6 +37,10 @@ from amplifier_core._engine import (
     CONTENT_BLOCK_START,
     CONTENT_BLOCK_DELTA,
     CONTENT_BLOCK_END,
+    # Canonical streaming events
+    CONTENT_START,
+    CONTENT_DELTA,
+    CONTENT_STOP,
     # Thinking events
     THINKING_DELTA,
     THINKING_FINAL,
@@ -38,13 +48,22 @@ from amplifier_core._engine import (
     TOOL_PRE,
     TOOL_POST,
     TOOL_ERROR,
+    TOOL_PROGRESS,
+    TOOL_TIMEOUT,
+    TOOL_RESULT_TRANSFORM,
+    TOOLS_DISCOVER,
+    TOOLS_INVOKE,
     # Context management
     CONTEXT_PRE_COMPACT,
     CONTEXT_POST_COMPACT,
     CONTEXT_COMPACTION,
     CONTEXT_INCLUDE,
+    CONTEXT_DEDUPLICATED,
+    CONTEXT_INJECTION_DROPPED,
     # Orchestrator lifecycle
     ORCHESTRATOR_COMPLETE,
+    ORCHESTRATOR_STATUS,
+    ORCHESTRATOR_RECOVERY,
     EXECUTION_START,
     EXECUTION_END,
     # User notifications
@@ -57,11 +76,19 @@ from amplifier_core._engine import (
     APPROVAL_REQUIRED,
     APPROVAL_GRANTED,
     APPROVAL_DENIED,
+    APPROVAL_CANCELLED,
     # Cancellation lifecycle
     CANCEL_REQUESTED,
+    CANCEL_ESCALATED,
     CANCEL_COMPLETED,
     # Module lifecycle events
     MODULE_ON_SESSION_READY_FAILED,
+    # Kernel resource events
+    KERNEL_MEMORY_PRESSURE,
+    KERNEL_MEMORY_EVICTED,
+    QUOTA_WARNING,
+    SAFETY_LIMIT,
+    HOOK_SLOW_HANDLER,
     ALL_EVENTS,
 )
 
@@ -70,8 +97,12 @@ __all__ = [
     "SESSION_END",
     "SESSION_FORK",
     "SESSION_RESUME",
+    "SESSION_REWIND",
+    "SESSION_REAPED",
+    "SESSION_HEARTBEAT",
     "PROMPT_SUBMIT",
     "PROMPT_COMPLETE",
+    "PROMPT_DUPLICATE",
     "PLAN_START",
     "PLAN_END",
     "PROVIDER_REQUEST",
@@ -81,21 +112,35 @@ __all__ = [
     "PROVIDER_THROTTLE",
     "PROVIDER_TOOL_SEQUENCE_REPAIRED",
     "PROVIDER_RESOLVE",
+    "PROVIDER_PRE",
+    "PROVIDER_POST",
     "LLM_REQUEST",
     "LLM_RESPONSE",
     "CONTENT_BLOCK_START",
     "CONTENT_BLOCK_DELTA",
     "CONTENT_BLOCK_END",
+    "CONTENT_START",
+    "CONTENT_DELTA",
+    "CONTENT_STOP",
     "THINKING_DELTA",
     "THINKING_FINAL",
     "TOOL_PRE",
     "TOOL_POST",
     "TOOL_ERROR",
+    "TOOL_PROGRESS",
+    "TOOL_TIMEOUT",
+    "TOOL_RESULT_TRANSFORM",
+    "TOOLS_DISCOVER",
+    "TOOLS_INVOKE",
     "CONTEXT_PRE_COMPACT",
     "CONTEXT_POST_COMPACT",
     "CONTEXT_COMPACTION",
     "CONTEXT_INCLUDE",
+    "CONTEXT_DEDUPLICATED",
+    "CONTEXT_INJECTION_DROPPED",
     "ORCHESTRATOR_COMPLETE",
+    "ORCHESTRATOR_STATUS",
+    "ORCHESTRATOR_RECOVERY",
     "EXECUTION_START",
     "EXECUTION_END",
     "USER_NOTIFICATION",
@@ -105,8 +150,15 @@ __all__ = [
     "APPROVAL_REQUIRED",
     "APPROVAL_GRANTED",
     "APPROVAL_DENIED",
+    "APPROVAL_CANCELLED",
     "CANCEL_REQUESTED",
+    "CANCEL_ESCALATED",
     "CANCEL_COMPLETED",
     "ALL_EVENTS",
     "MODULE_ON_SESSION_READY_FAILED",
+    "KERNEL_MEMORY_PRESSURE",
+    "KERNEL_MEMORY_EVICTED",
+    "QUOTA_WARNING",
+    "SAFETY_LIMIT",
+    "HOOK_SLOW_HANDLER",
 ]
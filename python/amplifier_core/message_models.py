@@ -36,6 +36,7 @@ class TextBlock(BaseModel):
     type: Literal["text"] = "text"
     text: str
     visibility: Literal["internal", "developer", "user"] | None = None
+    cache: Literal["ephemeral"] | None = None
 
 
 class ThinkingBlock(BaseModel):
@@ -72,6 +73,7 @@ class ToolCallBlock(BaseModel):
     name: str
     input: dict[str, Any]
     visibility: Literal["internal", "developer", "user"] | None = None
+    cache: Literal["ephemeral"] | None = None
 
 
 class ToolResultBlock(BaseModel):
@@ -83,6 +85,7 @@ class ToolResultBlock(BaseModel):
     tool_call_id: str
     output: Any
     visibility: Literal["internal", "developer", "user"] | None = None
+    cache: Literal["ephemeral"] | None = None
 
 
 class ImageBlock(BaseModel):
@@ -93,6 +96,7 @@ class ImageBlock(BaseModel):
     type: Literal["image"] = "image"
     source: dict[str, Any]
     visibility: Literal["internal", "developer", "user"] | None = None
+    cache: Literal["ephemeral"] | None = None
 
 
 class ReasoningBlock(BaseModel):
@@ -106,6 +110,31 @@ class ReasoningBlock(BaseModel):
     visibility: Literal["internal", "developer", "user"] | None = None
 
 
+class AudioBlock(BaseModel):
+    """Audio input (source carries media_type and data or url)."""
+
+    model_config = ConfigDict(extra="allow")
+
+    type: Literal["audio"] = "audio"
+    source: dict[str, Any]
+    transcript: str | None = None
+    visibility: Literal["internal", "developer", "user"] | None = None
+    cache: Literal["ephemeral"] | None = None
+
+
+class DocumentBlock(BaseModel):
+    """Document attachment such as a PDF (source carries media_type and data or url)."""
+
+    model_config = ConfigDict(extra="allow")
+
+    type: Literal["document"] = "document"
+    source: dict[str, Any]
+    title: str | None = None
+    transcript: str | None = None  # Text extracted from the document
+    visibility: Literal["internal", "developer", "user"] | None = None
+    cache: Literal["ephemeral"] | None = None
+
+
 ContentBlockUnion = Annotated[
     Union[
         TextBlock,
@@ -115,6 +144,8 @@ ContentBlockUnion = Annotated[
         ToolResultBlock,
         ImageBlock,
         ReasoningBlock,
+        AudioBlock,
+        DocumentBlock,
     ],
     Field(discriminator="type"),
 ]
@@ -136,6 +167,7 @@ class Message(BaseModel):
     metadata: dict[str, Any] | None = (
         None  # Provider-specific state (e.g., OpenAI reasoning items)
     )
+    cache: Literal["ephemeral"] | None = None  # Prompt-caching breakpoint
 
 
 class ToolSpec(BaseModel):
@@ -15,6 +15,8 @@ from pydantic import Field
 from pydantic import field_serializer
 from pydantic import field_validator
 
+from .message_models import ContentBlockUnion
+
 
 def _json_default(obj: Any) -> Any:
     """JSON encoder default for raw json.dumps() paths.
@@ -61,6 +63,18 @@ class ToolResult(BaseModel):
     error: dict[str, Any] | None = Field(
         default=None, description="Error details if failed"
     )
+    content: list[ContentBlockUnion] = Field(
+        default_factory=list,
+        description="Structured content blocks (images, formatted text), alongside or instead of output",
+    )
+    artifacts: list[dict[str, Any]] = Field(
+        default_factory=list,
+        description="Attachment references to files the tool produced",
+    )
+    display_hint: str | None = Field(
+        default=None,
+        description='How a host should render the result (e.g. "markdown", "table", "hidden")',
+    )
 
     def model_post_init(self, __context: Any) -> None:
         """Auto-populate output from error when tools forget to set it.
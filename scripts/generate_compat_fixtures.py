@@ -0,0 +1,213 @@
+#!/usr/bin/env python3
+"""
+Generate serialization-compatibility fixtures from the Python pydantic models.
+
+Each fixture is the `model_dump(mode="json")` output of a representative
+model instance. The Rust `compat` integration test
+(crates/amplifier-core/tests/compat.rs) deserializes every fixture into the
+matching serde type, re-serializes it, and reports any field that drifted.
+
+Fixtures are grouped by kind:
+
+    crates/amplifier-core/tests/fixtures/compat/
+        messages/        Message, ChatRequest, ChatResponse
+        hook_results/    HookResult
+        session_status/  SessionStatus
+
+The models are loaded straight from their source files, so the compiled
+extension does not need to be built.
+
+Usage:
+    python scripts/generate_compat_fixtures.py
+    python scripts/generate_compat_fixtures.py --check  # Exit 1 if fixtures are stale
+
+Exit codes:
+    0: Fixtures written (or up to date with --check)
+    1: Fixtures are stale (--check only)
+"""
+
+from __future__ import annotations
+
+import argparse
+import importlib.util
+import json
+import sys
+from datetime import datetime
+from decimal import Decimal
+from pathlib import Path
+from types import ModuleType
+from typing import Any
+
+try:
+    from pydantic import BaseModel
+except ImportError:
+    print("Error: pydantic required. Install with: pip install pydantic")
+    sys.exit(1)
+
+REPO_ROOT = Path(__file__).resolve().parent.parent
+MODELS_DIR = REPO_ROOT / "python" / "amplifier_core"
+FIXTURES_DIR = REPO_ROOT / "crates" / "amplifier-core" / "tests" / "fixtures" / "compat"
+
+
+def load_module(name: str) -> ModuleType:
+    """Load a model module by path, bypassing the package __init__."""
+    spec = importlib.util.spec_from_file_location(
+        f"_compat_{name}", MODELS_DIR / f"{name}.py"
+    )
+    assert spec and spec.loader
+    module = importlib.util.module_from_spec(spec)
+    spec.loader.exec_module(module)
+    return module
+
+
+def build_fixtures() -> dict[str, dict[str, BaseModel]]:
+    """Return {kind: {fixture_name: model_instance}}."""
+    mm = load_module("message_models")
+    models = load_module("models")
+
+    usage = mm.Usage(
+        input_tokens=120,
+        output_tokens=48,
+        total_tokens=168,
+        reasoning_tokens=16,
+        cache_read_tokens=64,
+        cost_usd=Decimal("0.0042"),
+    )
+
+    messages: dict[str, BaseModel] = {
+        "message_text": mm.Message(role="user", content="List the files in src/"),
+        "message_blocks": mm.Message(
+            role="assistant",
+            content=[
+                mm.ThinkingBlock(thinking="The user wants a listing.", signature="sig-1"),
+                mm.TextBlock(text="Listing now."),
+                mm.ToolCallBlock(id="call_1", name="bash", input={"command": "ls src/"}),
+            ],
+        ),
+        "message_tool_result": mm.Message(
+            role="tool",
+            content=[mm.ToolResultBlock(tool_call_id="call_1", output="lib.rs\nmain.rs")],
+            tool_call_id="call_1",
+        ),
+        "chat_request": mm.ChatRequest(
+            messages=[
+                mm.Message(role="system", content="You are terse."),
+                mm.Message(role="user", content="ls"),
+            ],
+            tools=[
+                mm.ToolSpec(
+                    name="bash",
+                    description="Run a shell command",
+                    parameters={
+                        "type": "object",
+                        "properties": {"command": {"type": "string"}},
+                        "required": ["command"],
+                    },
+                )
+            ],
+            max_output_tokens=1024,
+            temperature=0.2,
+        ),
+        "chat_response": mm.ChatResponse(
+            content=[mm.TextBlock(text="Done.")],
+            tool_calls=[mm.ToolCall(id="call_2", name="read", arguments={"path": "a"})],
+            usage=usage,
+            finish_reason="tool_use",
+            metadata={"model": "example-large"},
+        ),
+        "chat_response_degraded": mm.ChatResponse(
+            content=[mm.TextBlock(text="{}")],
+            degradation=mm.Degradation(
+                requested="json_schema", actual="json", reason="schema unsupported"
+            ),
+            finish_reason="end_turn",
+        ),
+    }
+
+    hook_results: dict[str, BaseModel] = {
+        "continue": models.HookResult(),
+        "deny": models.HookResult(action="deny", reason="Blocked by policy"),
+        "modify": models.HookResult(action="modify", data={"tool_input": {"path": "b"}}),
+        "inject_context": models.HookResult(
+            action="inject_context",
+            context_injection="Linter found 2 issues",
+            context_injection_role="user",
+            ephemeral=True,
+            append_to_last_tool_result=True,
+            user_message="2 lint issues",
+            user_message_level="warning",
+            user_message_source="python-check",
+            suppress_output=True,
+        ),
+        "ask_user": models.HookResult(
+            action="ask_user",
+            approval_prompt="Allow write to config.py?",
+            approval_options=["Allow once", "Allow always", "Deny"],
+            approval_timeout=60.0,
+            approval_default="allow",
+        ),
+    }
+
+    started = datetime(2025, 1, 2, 3, 4, 5)
+    session_status: dict[str, BaseModel] = {
+        "running": models.SessionStatus(session_id="sess-1", started_at=started),
+        "completed": models.SessionStatus(
+            session_id="sess-2",
+            started_at=started,
+            ended_at=datetime(2025, 1, 2, 3, 9, 0),
+            status="completed",
+            total_messages=12,
+            tool_invocations=4,
+            tool_successes=3,
+            tool_failures=1,
+            total_input_tokens=5000,
+            total_output_tokens=900,
+            cost_usd=Decimal("0.125"),
+        ),
+    }
+
+    return {
+        "messages": messages,
+        "hook_results": hook_results,
+        "session_status": session_status,
+    }
+
+
+def render(model: BaseModel) -> str:
+    data: Any = model.model_dump(mode="json")
+    return json.dumps(data, indent=2, sort_keys=True) + "\n"
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.split("\n\n")[0])
+    parser.add_argument(
+        "--check",
+        action="store_true",
+        help="Do not write; exit 1 if any fixture differs from the models",
+    )
+    args = parser.parse_args()
+
+    stale: list[Path] = []
+    for kind, fixtures in build_fixtures().items():
+        kind_dir = FIXTURES_DIR / kind
+        for name, model in fixtures.items():
+            path = kind_dir / f"{name}.json"
+            content = render(model)
+            if path.exists() and path.read_text(encoding="utf-8") == content:
+                continue
+            stale.append(path)
+            if not args.check:
+                kind_dir.mkdir(parents=True, exist_ok=True)
+                path.write_text(content, encoding="utf-8")
+
+    for path in stale:
+        verb = "stale" if args.check else "wrote"
+        print(f"  {verb}: {path.relative_to(REPO_ROOT)}")
+    if args.check and stale:
+        print("Fixtures are out of date. Run: python scripts/generate_compat_fixtures.py")
+        return 1
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,89 @@
+"""Tests for the typed Message / ChatRequest / ChatResponse PyO3 wrappers."""
+
+import json
+
+import pytest
+
+from amplifier_core.message_models import ChatRequest, ChatResponse, Message, TextBlock
+
+
+class TestChatRequest:
+    def test_from_dict_exposes_fields(self):
+        from amplifier_core._engine import ChatRequest as RustChatRequest
+
+        request = RustChatRequest.from_dict(
+            {
+                "messages": [{"role": "user", "content": "hi"}],
+                "model": "example-large",
+                "temperature": 0.3,
+                "stop": ["END"],
+            }
+        )
+
+        assert request.model == "example-large"
+        assert request.temperature == 0.3
+        assert request.stop == ["END"]
+        assert request.max_output_tokens is None
+        assert len(request) == 1
+        assert request.messages[0].role == "user"
+        assert request.messages[0].text == "hi"
+
+    def test_from_pydantic_model(self):
+        from amplifier_core._engine import ChatRequest as RustChatRequest
+
+        model = ChatRequest(
+            messages=[Message(role="user", content="hey")], max_output_tokens=5
+        )
+        request = RustChatRequest.from_dict(model)
+
+        assert request.max_output_tokens == 5
+        assert request.to_dict()["messages"] == [{"role": "user", "content": "hey"}]
+
+    def test_json_round_trip(self):
+        from amplifier_core._engine import ChatRequest as RustChatRequest
+
+        request = RustChatRequest.from_dict({"messages": [], "model": "m"})
+        again = RustChatRequest.from_json(request.to_json())
+
+        assert again == request
+        assert json.loads(request.to_json())["model"] == "m"
+
+    def test_invalid_input_raises_value_error(self):
+        from amplifier_core._engine import ChatRequest as RustChatRequest
+
+        with pytest.raises(ValueError, match="Invalid ChatRequest"):
+            RustChatRequest.from_dict({"messages": [{"role": "nobody"}]})
+
+
+class TestChatResponse:
+    def test_text_and_content(self):
+        from amplifier_core._engine import ChatResponse as RustChatResponse
+
+        response = RustChatResponse.from_dict(
+            ChatResponse(
+                content=[TextBlock(text="a"), TextBlock(text="b")],
+                finish_reason="end_turn",
+            )
+        )
+
+        assert response.text == "ab"
+        assert response.finish_reason == "end_turn"
+        assert response.content == [
+            {"type": "text", "text": "a"},
+            {"type": "text", "text": "b"},
+        ]
+        assert response.tool_calls is None
+
+
+class TestMessage:
+    def test_block_content(self):
+        from amplifier_core._engine import Message as RustMessage
+
+        message = RustMessage.from_dict(
+            {"role": "assistant", "content": [{"type": "text", "text": "t"}]}
+        )
+
+        assert message.role == "assistant"
+        assert message.content == [{"type": "text", "text": "t"}]
+        assert message.text == "t"
+        assert message == RustMessage.from_json(message.to_json())
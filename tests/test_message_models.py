@@ -1,9 +1,11 @@
 """Tests for REQUEST_ENVELOPE_V1 Pydantic models."""
 
 import pytest
+from amplifier_core.message_models import AudioBlock
 from amplifier_core.message_models import ChatRequest
 from amplifier_core.message_models import ChatResponse
 from amplifier_core.message_models import Degradation
+from amplifier_core.message_models import DocumentBlock
 from amplifier_core.message_models import ImageBlock
 from amplifier_core.message_models import Message
 from amplifier_core.message_models import ReasoningBlock
@@ -209,6 +211,44 @@ class TestContentBlocks:
         assert len(block.content) == 1
         assert len(block.summary) == 1
 
+    def test_audio_and_document_blocks(self) -> None:
+        """AudioBlock and DocumentBlock round-trip through the union."""
+        msg = Message(
+            role="user",
+            content=[
+                {  # type: ignore[list-item]
+                    "type": "audio",
+                    "source": {"type": "base64", "media_type": "audio/wav", "data": "..."},
+                    "transcript": "hello",
+                },
+                {  # type: ignore[list-item]
+                    "type": "document",
+                    "source": {"type": "url", "media_type": "application/pdf", "url": "https://example.com/a.pdf"},
+                    "title": "Spec",
+                },
+            ],
+        )
+
+        assert isinstance(msg.content, list)
+        assert isinstance(msg.content[0], AudioBlock)
+        assert msg.content[0].transcript == "hello"
+        assert isinstance(msg.content[1], DocumentBlock)
+        assert msg.content[1].source["media_type"] == "application/pdf"
+        restored = Message.model_validate(msg.model_dump())
+        assert restored == msg
+
+    def test_cache_hints(self) -> None:
+        """Message and block cache hints round-trip."""
+        msg = Message(
+            role="user",
+            content=[TextBlock(text="long context", cache="ephemeral")],
+            cache="ephemeral",
+        )
+
+        assert msg.cache == "ephemeral"
+        assert msg.content[0].cache == "ephemeral"  # type: ignore[union-attr]
+        assert Message.model_validate(msg.model_dump()) == msg
+
     def test_visibility_field(self) -> None:
         """ContentBlock visibility field."""
         block = TextBlock(text="Internal note", visibility="internal")